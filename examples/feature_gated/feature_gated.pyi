@@ -4,6 +4,13 @@
 import builtins
 import typing
 
+from typing_extensions import Self
+
+__all__ = [
+    "A",
+    "sum_as_string",
+]
+
 @typing.final
 class A:
     @property
@@ -20,7 +27,7 @@ class A:
         default = 0
         ```
         """
-    def __new__(cls) -> A: ...
+    def __new__(cls) -> Self: ...
     def get_y(self) -> builtins.int: ...
 
 def sum_as_string(a: builtins.int, b: builtins.int) -> builtins.str:
@@ -4,6 +4,13 @@
 import builtins
 import typing
 
+__all__ = [
+    "A",
+    "B",
+    "create_a",
+    "create_b",
+]
+
 @typing.final
 class A:
     def show_x(self) -> None: ...
@@ -3,9 +3,19 @@
 
 import builtins
 import typing
-from . import int
-from . import mod_a
-from . import mod_b
+
+from . import int, mod_a, mod_b
+
+__all__ = [
+    "A",
+    "B",
+    "create_a",
+    "create_b",
+    "greet_main",
+    "int",
+    "mod_a",
+    "mod_b",
+]
 
 @typing.final
 class A:
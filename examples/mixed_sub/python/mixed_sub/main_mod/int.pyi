@@ -3,5 +3,9 @@
 
 import builtins
 
+__all__ = [
+    "dummy_int_fun",
+]
+
 def dummy_int_fun(x: builtins.int) -> builtins.int: ...
 
@@ -2,8 +2,16 @@
 # ruff: noqa: E501, F401
 
 import typing
+
 from mixed_sub.main_mod import A, B
 
+__all__ = [
+    "C",
+    "create_c",
+    "greet_a",
+    "test_module_with_python",
+]
+
 @typing.final
 class C:
     def show_x(self) -> None: ...
@@ -6,28 +6,139 @@ Document for pure ...
 
 import builtins
 import collections.abc
+import dataclasses
 import datetime
 import decimal
 import enum
 import os
 import pathlib
+import types
 import typing
-import typing_extensions
 
+import typing_extensions
+from typing_extensions import Self
+
+__all__ = [
+    "A",
+    "B",
+    "Calculator",
+    "ColorMapping",
+    "ColorMappingVariant",
+    "ComparableStruct",
+    "DEFAULT_TIMEOUT",
+    "DataContainer",
+    "DataclassRecord",
+    "DecimalHolder",
+    "HashableStruct",
+    "Incrementer",
+    "Incrementer2",
+    "InstanceValue",
+    "Lock",
+    "MAX_ITER",
+    "MODE_AUTO",
+    "MODE_FAST",
+    "MODE_SLOW",
+    "MY_CONSTANT1",
+    "MY_CONSTANT2",
+    "Mode",
+    "MyDate",
+    "MyError",
+    "Node",
+    "NotIntError",
+    "Number",
+    "NumberComplex",
+    "NumberComplexVariant",
+    "NumberRenameAll",
+    "OldA",
+    "OverrideType",
+    "Placeholder",
+    "Problem",
+    "Ready",
+    "Segments",
+    "Shape1",
+    "Shape1Variant",
+    "Shape2",
+    "Shape2Variant",
+    "TracedDiagnostics",
+    "TrackIter",
+    "Tracks",
+    "TypeIgnoreTest",
+    "abort",
+    "add_decimals",
+    "ahash_dict",
+    "as_tuple",
+    "async_num",
+    "child",
+    "count_tags",
+    "create_a",
+    "create_containers",
+    "create_dict",
+    "default_value",
+    "deprecated_function",
+    "describe_item",
+    "diagnostics",
+    "echo_path",
+    "fn_override_type",
+    "fn_with_python_param",
+    "fn_with_python_stub",
+    "func_with_kwargs",
+    "func_with_star_arg",
+    "func_with_star_arg_typed",
+    "open_with_mode",
+    "overload_example_1",
+    "overload_example_2",
+    "print_c",
+    "process_container",
+    "read_dict",
+    "ready",
+    "root",
+    "run_with_mode",
+    "str_len",
+    "sum",
+    "sum_list",
+    "test_type_ignore_all",
+    "test_type_ignore_custom",
+    "test_type_ignore_no_comment_all",
+    "test_type_ignore_no_comment_specific",
+    "test_type_ignore_pyright",
+    "test_type_ignore_specific",
+]
+
+ColorMappingVariant: typing.TypeAlias = ColorMapping.Map
+Mode: typing.TypeAlias = typing.Literal['fast', 'slow', 'auto']
+NumberComplexVariant: typing.TypeAlias = builtins.float | NumberComplex.INTEGER
+Shape1Variant: typing.TypeAlias = Shape1.Circle | Shape1.Rectangle | Shape1.RegularPolygon | Shape1.Nothing
+Shape2Variant: typing.TypeAlias = Shape2.Circle | Shape2.Rectangle | Shape2.RegularPolygon | Shape2.Nothing
+
+DEFAULT_TIMEOUT: builtins.float
+r"""
+Timeout in seconds unless given otherwise
+"""
+MAX_ITER: typing.Final[builtins.int] = 100
+MODE_AUTO: typing.Final[builtins.str] = 'auto'
+MODE_FAST: typing.Final[builtins.str] = 'fast'
+MODE_SLOW: typing.Final[builtins.str] = 'slow'
 MY_CONSTANT1: builtins.int
-MY_CONSTANT2: builtins.int = 123
+MY_CONSTANT2: typing.Final[builtins.int] = 123
+__version__: typing.Final[builtins.str] = '0.16.2'
+class MyError(builtins.RuntimeError):
+    ...
+
 class A:
-    NUM: builtins.int = 2
+    NUM: typing.Final[builtins.int] = 2
     r"""
     class attribute NUM1
     """
-    NUM3: builtins.int = 3
+    NUM2: typing.Final[builtins.int] = 2
     r"""
-    deprecated class attribute NUM3 (will show warning)
+    class attribute NUM2
     """
-    NUM2: builtins.int
+    NUM3: typing.Final[builtins.int] = 3
     r"""
-    class attribute NUM2
+    deprecated class attribute NUM3 (will show warning)
+    
+    Deprecated:
+        Since 1.0.0: This constant is deprecated
     """
     @property
     def x(self) -> builtins.int:
@@ -46,30 +157,50 @@ class A:
     @property
     def y(self) -> builtins.int: ...
     @y.setter
-    @typing_extensions.deprecated("[Since 1.0.0] This setter is deprecated")
-    def y(self, value: builtins.int) -> None: ...
-    @typing_extensions.deprecated("[Since 1.0.0] This method is deprecated")
+    @typing_extensions.deprecated("deprecated since 1.0.0: This setter is deprecated")
+    def y(self, value: builtins.int) -> None:
+        r"""
+        Deprecated:
+            Since 1.0.0: This setter is deprecated
+        """
+    @typing_extensions.deprecated("deprecated since 1.0.0: This method is deprecated")
     @property
-    def deprecated_getter(self) -> builtins.int: ...
-    def __new__(cls, x: builtins.int) -> A:
+    def deprecated_getter(self) -> builtins.int:
+        r"""
+        Deprecated:
+            Since 1.0.0: This method is deprecated
+        """
+    def __new__(cls, x: builtins.int) -> Self:
         r"""
         This is a constructor of :class:`A`.
         """
     @classmethod
     def classmethod_test1(cls) -> None: ...
-    @typing_extensions.deprecated("[Since 1.0.0] This classmethod is deprecated")
+    @typing_extensions.deprecated("deprecated since 1.0.0: This classmethod is deprecated")
     @classmethod
-    def deprecated_classmethod(cls) -> None: ...
+    def deprecated_classmethod(cls) -> None:
+        r"""
+        Deprecated:
+            Since 1.0.0: This classmethod is deprecated
+        """
     @classmethod
     def classmethod_test2(cls) -> None: ...
     def show_x(self) -> None: ...
     def ref_test(self, x: dict) -> dict: ...
     async def async_get_x(self) -> builtins.int: ...
-    @typing_extensions.deprecated("[Since 1.0.0] This method is deprecated")
-    def deprecated_method(self) -> None: ...
-    @typing_extensions.deprecated("[Since 1.0.0] This staticmethod is deprecated")
+    @typing_extensions.deprecated("deprecated since 1.0.0: This method is deprecated")
+    def deprecated_method(self) -> None:
+        r"""
+        Deprecated:
+            Since 1.0.0: This method is deprecated
+        """
+    @typing_extensions.deprecated("deprecated since 1.0.0: This staticmethod is deprecated")
     @staticmethod
-    def deprecated_staticmethod() -> builtins.int: ...
+    def deprecated_staticmethod() -> builtins.int:
+        r"""
+        Deprecated:
+            Since 1.0.0: This staticmethod is deprecated
+        """
 
 @typing.final
 class B(A):
@@ -77,14 +208,28 @@ class B(A):
 
 @typing.final
 class Calculator:
+    def __new__(cls) -> Self: ...
+    def add(self, value: builtins.float) -> builtins.float: ...
     def multiply(self, other: Calculator) -> Calculator:
         r"""
         Multiply this calculator's result by another calculator's result.
         
         Using RustType marker for both input and output types.
         """
-    def __new__(cls) -> Calculator: ...
-    def add(self, value: builtins.float) -> builtins.float: ...
+
+class ColorMapping:
+    r"""
+    Example complex enum variant backed by a mapping using `#[pyo3(item)]`
+    """
+    class Map(typing.TypedDict):
+        r"""
+        Variant that behaves like a dict on the Python side
+        """
+        red: builtins.int
+        green: builtins.int
+        blue: builtins.int
+    
+    ...
 
 @typing.final
 class ComparableStruct:
@@ -98,7 +243,7 @@ class ComparableStruct:
     def __le__(self, other: builtins.object) -> builtins.bool: ...
     def __gt__(self, other: builtins.object) -> builtins.bool: ...
     def __ge__(self, other: builtins.object) -> builtins.bool: ...
-    def __new__(cls, value: builtins.int) -> ComparableStruct: ...
+    def __new__(cls, value: builtins.int) -> Self: ...
 
 @typing.final
 class DataContainer:
@@ -106,16 +251,25 @@ class DataContainer:
     def value(self) -> builtins.int: ...
     @value.setter
     def value(self, value: builtins.int) -> None: ...
-    def __new__(cls, value: builtins.int) -> DataContainer: ...
+    def __new__(cls, value: builtins.int) -> Self: ...
+
+@typing.final
+@dataclasses.dataclass
+class DataclassRecord:
+    r"""
+    Record rendered as `@dataclasses.dataclass`
+    """
+    name: builtins.str
+    count: builtins.int = 0
 
 @typing.final
 class DecimalHolder:
     @property
     def value(self) -> decimal.Decimal: ...
-    def __new__(cls, value: decimal.Decimal) -> DecimalHolder: ...
+    def __new__(cls, value: decimal.Decimal) -> Self: ...
 
 @typing.final
-class HashableStruct:
+class HashableStruct(collections.abc.Hashable):
     r"""
     Test struct for hash and str methods
     """
@@ -124,10 +278,11 @@ class HashableStruct:
     def __eq__(self, other: builtins.object) -> builtins.bool: ...
     def __hash__(self) -> builtins.int: ...
     def __str__(self) -> builtins.str: ...
-    def __new__(cls, name: builtins.str) -> HashableStruct: ...
+    def __new__(cls, name: builtins.str) -> Self: ...
 
 @typing.final
 class Incrementer:
+    def __new__(cls) -> Self: ...
     @typing.overload
     def increment_1(self, x: int) -> int:
         r"""
@@ -138,7 +293,6 @@ class Incrementer:
         r"""
         This is the original doc comment
         """
-    def __new__(cls) -> Incrementer: ...
 
 @typing.final
 class Incrementer2:
@@ -152,7 +306,7 @@ class Incrementer2:
         r"""
         increment_2 for floats, submitted by hands
         """
-    def __new__(cls) -> Incrementer2:
+    def __new__(cls) -> Self:
         r"""
         Constructor for Incrementer2
         """
@@ -163,14 +317,37 @@ class InstanceValue:
     def data(self) -> builtins.str: ...
     @data.setter
     def data(self, value: builtins.str) -> None: ...
-    def __new__(cls, data: builtins.str) -> InstanceValue: ...
+    def __new__(cls, data: builtins.str) -> Self: ...
+
+@typing.final
+class Lock:
+    r"""
+    Context manager whose `__enter__` returns itself
+    """
+    @property
+    def locked(self) -> builtins.bool: ...
+    def __new__(cls) -> Self: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(self, _exc_type: type[builtins.BaseException] | None, _exc_value: builtins.BaseException | None, _traceback: types.TracebackType | None) -> builtins.bool | None: ...
 
 @typing.final
 class MyDate(datetime.date):
     ...
 
-class MyError(builtins.RuntimeError):
-    ...
+@typing.final
+class Node:
+    r"""
+    Tree node referring to other nodes by `Py`, `PyRef`, `PyRefMut`, and `Bound`
+    """
+    @property
+    def parent(self) -> typing.Optional[Node]: ...
+    @property
+    def name(self) -> builtins.str: ...
+    @name.setter
+    def name(self, value: builtins.str) -> None: ...
+    def __new__(cls, name: builtins.str) -> Self: ...
+    def rename(self, name: builtins.str) -> Self: ...
+    def is_sibling(self, other: Node) -> builtins.bool: ...
 
 @typing.final
 class NotIntError(builtins.TypeError):
@@ -179,7 +356,7 @@ class NotIntError(builtins.TypeError):
     
     Based on the code reported in https://github.com/Jij-Inc/pyo3-stub-gen/issues/263
     """
-    def __new__(cls, item: typing.Any) -> NotIntError: ...
+    def __new__(cls, item: typing.Any) -> Self: ...
     def __str__(self) -> builtins.str: ...
     def trivial_number(self) -> builtins.int:
         r"""
@@ -196,10 +373,10 @@ class NumberComplex:
         r"""
         Float variant
         """
-        __match_args__ = ("_0",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("_0",)
         @property
         def _0(self) -> builtins.float: ...
-        def __new__(cls, _0: builtins.float) -> NumberComplex.FLOAT: ...
+        def __new__(cls, _0: builtins.float) -> Self: ...
         def __len__(self) -> builtins.int: ...
         def __getitem__(self, key: builtins.int) -> typing.Any: ...
     
@@ -208,14 +385,25 @@ class NumberComplex:
         r"""
         Integer variant
         """
-        __match_args__ = ("int",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("int",)
         @property
         def int(self) -> builtins.int:
             r"""
             The integer value
             """
-        def __new__(cls, int: builtins.int = 2) -> NumberComplex.INTEGER: ...
+        def __new__(cls, int: builtins.int = 2) -> Self: ...
+    
+    ...
+
+@typing_extensions.deprecated("deprecated since 1.0.0: Use `A` instead")
+@typing.final
+class OldA:
+    r"""
+    Class superseded by :class:`A`
     
+    Deprecated:
+        Since 1.0.0: Use `A` instead
+    """
     ...
 
 @typing.final
@@ -232,6 +420,7 @@ class Placeholder:
     def name(self) -> builtins.str: ...
     @name.setter
     def name(self, value: builtins.str) -> None: ...
+    def __new__(cls, name: builtins.str) -> Self: ...
     def configure(self, name: builtins.str, *, dtype: builtins.str, ndim: builtins.int, shape: typing.Optional[builtins.str], jagged: builtins.bool = False, latex: typing.Optional[builtins.str] = None) -> Placeholder:
         r"""
         Configure placeholder with keyword-only parameters.
@@ -239,10 +428,10 @@ class Placeholder:
         This demonstrates keyword-only parameters (after *) which should be
         preserved in the generated stub file.
         """
-    def __new__(cls, name: builtins.str) -> Placeholder: ...
 
 @typing.final
 class Problem:
+    def __new__(cls) -> Self: ...
     def evaluate(self, instance_data: builtins.dict[builtins.str, InstanceValue]) -> builtins.str:
         r"""
         Evaluate with instance data mapping string keys to InstanceValue objects.
@@ -250,7 +439,25 @@ class Problem:
         This example demonstrates RustType marker usage within nested generic types
         such as dict value types. The marker should expand to the correct Python type.
         """
-    def __new__(cls) -> Problem: ...
+
+@typing.final
+class Ready:
+    r"""
+    Awaitable object resolving to a number without suspending
+    """
+    def __await__(self) -> collections.abc.Generator[typing.Any, typing.Any, builtins.int]: ...
+    def __next__(self) -> None: ...
+
+@typing.final
+class Segments:
+    r"""
+    Segments indexed by an integer or a slice
+    """
+    def __new__(cls, values: typing.Sequence[builtins.int]) -> Self: ...
+    @typing.overload
+    def __getitem__(self, index: builtins.int) -> builtins.int: ...
+    @typing.overload
+    def __getitem__(self, index: builtins.slice) -> Self: ...
 
 class Shape1:
     r"""
@@ -259,35 +466,35 @@ class Shape1:
     """
     @typing.final
     class Circle(Shape1):
-        __match_args__ = ("radius",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("radius",)
         @property
         def radius(self) -> builtins.float: ...
-        def __new__(cls, radius: builtins.float) -> Shape1.Circle: ...
+        def __new__(cls, radius: builtins.float) -> Self: ...
     
     @typing.final
     class Rectangle(Shape1):
-        __match_args__ = ("width", "height",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("width", "height",)
         @property
         def width(self) -> builtins.float: ...
         @property
         def height(self) -> builtins.float: ...
-        def __new__(cls, width: builtins.float, height: builtins.float) -> Shape1.Rectangle: ...
+        def __new__(cls, width: builtins.float, height: builtins.float) -> Self: ...
     
     @typing.final
     class RegularPolygon(Shape1):
-        __match_args__ = ("_0", "_1",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("_0", "_1",)
         @property
         def _0(self) -> builtins.int: ...
         @property
         def _1(self) -> builtins.float: ...
-        def __new__(cls, _0: builtins.int, _1: builtins.float) -> Shape1.RegularPolygon: ...
+        def __new__(cls, _0: builtins.int, _1: builtins.float) -> Self: ...
         def __len__(self) -> builtins.int: ...
         def __getitem__(self, key: builtins.int) -> typing.Any: ...
     
     @typing.final
     class Nothing(Shape1):
-        __match_args__ = ()
-        def __new__(cls) -> Shape1.Nothing: ...
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ()
+        def __new__(cls) -> Self: ...
     
     ...
 
@@ -298,56 +505,68 @@ class Shape2:
     """
     @typing.final
     class Circle(Shape2):
-        __match_args__ = ("radius",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("radius",)
         @property
         def radius(self) -> builtins.float: ...
-        def __new__(cls, radius: builtins.float = 1.0) -> Shape2.Circle: ...
+        def __new__(cls, radius: builtins.float = 1.0) -> Self: ...
     
     @typing.final
     class Rectangle(Shape2):
-        __match_args__ = ("width", "height",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("width", "height",)
         @property
         def width(self) -> builtins.float: ...
         @property
         def height(self) -> builtins.float: ...
-        def __new__(cls, *, width: builtins.float, height: builtins.float) -> Shape2.Rectangle: ...
+        def __new__(cls, *, width: builtins.float, height: builtins.float) -> Self: ...
     
     @typing.final
     class RegularPolygon(Shape2):
-        __match_args__ = ("side_count", "radius",)
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ("side_count", "radius",)
         @property
         def side_count(self) -> builtins.int: ...
         @property
         def radius(self) -> builtins.float: ...
-        def __new__(cls, side_count: builtins.int, radius: builtins.float = 1.0) -> Shape2.RegularPolygon: ...
+        def __new__(cls, side_count: builtins.int, radius: builtins.float = 1.0) -> Self: ...
     
     @typing.final
     class Nothing(Shape2):
-        __match_args__ = ()
-        def __new__(cls) -> Shape2.Nothing: ...
-
+        __match_args__: typing.Final[tuple[builtins.str, ...]] = ()
+        def __new__(cls) -> Self: ...
+    
     ...
 
-class ColorMapping:
+@typing.final
+class TracedDiagnostics:
     r"""
-    Example complex enum variant backed by a mapping using `#[pyo3(item)]`
+    Extends a skipped class, and is rendered without a base
     """
-    class Map(typing.TypedDict):
-        r"""
-        Variant that behaves like a dict on the Python side
-        """
-        red: builtins.int
-        green: builtins.int
-        blue: builtins.int
+    def __new__(cls) -> Self: ...
 
-    ...
+@typing.final
+class TrackIter(collections.abc.Iterator[builtins.str]):
+    r"""
+    Iterator over tracks, rendered with the `collections.abc.Iterator[str]` base
+    """
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> builtins.str: ...
+
+@typing.final
+class Tracks(collections.abc.Iterable[builtins.str], collections.abc.Sized):
+    r"""
+    Sequence of tracks, rendered with the `collections.abc.Iterable[str]` and `collections.abc.Sized` bases,
+    since `__getitem__` does not take a slice as `collections.abc.Sequence` does
+    """
+    def __new__(cls, names: typing.Sequence[builtins.str]) -> Self: ...
+    def __len__(self) -> builtins.int: ...
+    def __getitem__(self, index: builtins.int) -> builtins.str: ...
+    def __iter__(self) -> TrackIter: ...
 
 @typing.final
 class TypeIgnoreTest:
     r"""
     Test class for method type: ignore functionality
     """
-    def __new__(cls) -> TypeIgnoreTest: ...
+    def __new__(cls) -> Self: ...
     def test_method_ignore(self, value: builtins.int) -> builtins.int:  # type: ignore[union-attr,return-value]
         r"""
         Test method with type: ignore for specific rules
@@ -381,6 +600,11 @@ class NumberRenameAll(enum.Enum):
     """
     INTEGER = ...
 
+def abort(msg: builtins.str) -> typing.NoReturn:
+    r"""
+    Raise `ValueError` with the message, so that the code after the call is unreachable
+    """
+
 def add_decimals(a: decimal.Decimal, b: decimal.Decimal) -> decimal.Decimal:
     r"""
     Add two decimal numbers with high precision
@@ -402,6 +626,16 @@ def as_tuple(xs: collections.abc.Sequence[int], /, *, tuple_out: typing.Literal[
 
 async def async_num() -> builtins.int: ...
 
+def child(parent: Node, name: builtins.str) -> Node: ...
+
+def count_tags(tags: typing.Sequence[builtins.str] = ..., options: typing.Optional[typing.Mapping[builtins.str, builtins.int]] = None) -> builtins.int:
+    r"""
+    Count the tags and options
+    
+    `tags` defaults to an empty list.
+    `options` defaults to an empty dict.
+    """
+
 def create_a(x: builtins.int = 2) -> A: ...
 
 def create_containers(count: builtins.int) -> builtins.list[DataContainer]:
@@ -415,10 +649,24 @@ def create_dict(n: builtins.int) -> builtins.dict[builtins.int, builtins.list[bu
 
 def default_value(num: Number = Number.FLOAT) -> Number: ...
 
-@typing_extensions.deprecated("[Since 1.0.0] This function is deprecated")
-def deprecated_function() -> None: ...
+@typing_extensions.deprecated("deprecated since 1.0.0: This function is deprecated")
+def deprecated_function() -> None:
+    r"""
+    Deprecated:
+        Since 1.0.0: This function is deprecated
+    """
 
-def echo_path(path: builtins.str | os.PathLike | pathlib.Path) -> pathlib.Path: ...
+def describe_item(type: builtins.str) -> builtins.str:
+    r"""
+    Parameters named by raw identifiers are exposed without `r#`
+    """
+
+def diagnostics() -> typing.Any:
+    r"""
+    Returns a skipped class, which is rendered as `typing.Any`
+    """
+
+def echo_path(path: builtins.str | os.PathLike[builtins.str]) -> pathlib.Path: ...
 
 def fn_override_type(cb: collections.abc.Callable[[str], typing.Any]) -> collections.abc.Callable[[str], typing.Any]: ...
 
@@ -449,6 +697,11 @@ def func_with_star_arg_typed(*args: str) -> builtins.str:
     Takes a variable number of arguments and returns their string representation.
     """
 
+def open_with_mode(path: builtins.str | os.PathLike[builtins.str], mode: typing.Literal['r', 'w', 'a'], encoding: typing.Literal['utf-8', 'latin-1'] | None = None) -> typing.Literal[0, 1]:
+    r"""
+    Open a file in one of the modes validated at runtime
+    """
+
 @typing.overload
 def overload_example_1(x: int) -> int: ...
 
@@ -483,6 +736,15 @@ def process_container(container: DataContainer) -> DataContainer:
 
 def read_dict(dict: typing.Mapping[builtins.int, typing.Mapping[builtins.int, builtins.int]]) -> None: ...
 
+def ready(value: builtins.int) -> collections.abc.Awaitable[builtins.int]: ...
+
+def root(node: Node) -> Node: ...
+
+def run_with_mode(mode: Mode) -> builtins.str:
+    r"""
+    Run in one of the `MODE_*` modes
+    """
+
 def str_len(x: builtins.str) -> builtins.int:
     r"""
     Returns the length of the string.
@@ -524,3 +786,4 @@ def test_type_ignore_specific() -> builtins.int:  # type: ignore[arg-type,return
     r"""
     Test function with type: ignore for specific rules
     """
+
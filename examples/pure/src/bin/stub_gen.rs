@@ -1,8 +1 @@
-use pyo3_stub_gen::Result;
-
-fn main() -> Result<()> {
-    env_logger::Builder::from_env(env_logger::Env::default().filter_or("RUST_LOG", "info")).init();
-    let stub = pure::stub_info()?;
-    stub.generate()?;
-    Ok(())
-}
+pyo3_stub_gen::stub_gen_main!(pure::stub_info);
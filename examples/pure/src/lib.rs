@@ -17,7 +17,10 @@ mod readme {}
 
 use ahash::RandomState;
 use pyo3::{prelude::*, types::*};
-use pyo3_stub_gen::{define_stub_info_gatherer, derive::*, module_doc, module_variable};
+use pyo3_stub_gen::{
+    concat_module, define_stub_info_gatherer, derive::*, gen_stub_module_attr, literal_group,
+    module_doc, module_variable, module_version,
+};
 use rust_decimal::Decimal;
 use std::{collections::HashMap, path::PathBuf};
 
@@ -160,11 +163,153 @@ fn create_a(x: usize) -> A {
     A { x, y: 10 }
 }
 
+/// Count the tags and options
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (tags = vec![], options = Some(HashMap::new())))]
+fn count_tags(
+    #[gen_stub(container_default = "ellipsis")] tags: Vec<String>,
+    #[gen_stub(container_default = "none-if-optional")] options: Option<HashMap<String, i64>>,
+) -> usize {
+    tags.len() + options.map_or(0, |options| options.len())
+}
+
 #[gen_stub_pyclass]
 #[pyclass(extends=A)]
 #[derive(Debug)]
 struct B;
 
+/// Diagnostics of the module, omitted from stubs by `#[gen_stub(skip)]`
+#[gen_stub_pyclass]
+#[pyclass(subclass)]
+#[gen_stub(skip)]
+#[derive(Debug, Default)]
+struct Diagnostics {
+    #[pyo3(get)]
+    calls: usize,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Diagnostics {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Extends a skipped class, and is rendered without a base
+#[gen_stub_pyclass]
+#[pyclass(extends=Diagnostics)]
+struct TracedDiagnostics;
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl TracedDiagnostics {
+    #[new]
+    fn new() -> (TracedDiagnostics, Diagnostics) {
+        (TracedDiagnostics, Diagnostics::default())
+    }
+}
+
+/// Sequence of tracks, rendered with the `collections.abc.Iterable[str]` and `collections.abc.Sized` bases,
+/// since `__getitem__` does not take a slice as `collections.abc.Sequence` does
+#[gen_stub_pyclass]
+#[pyclass]
+struct Tracks {
+    names: Vec<String>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Tracks {
+    #[new]
+    fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    fn __len__(&self) -> usize {
+        self.names.len()
+    }
+
+    fn __getitem__(&self, index: usize) -> PyResult<String> {
+        self.names
+            .get(index)
+            .cloned()
+            .ok_or_else(|| pyo3::exceptions::PyIndexError::new_err(index))
+    }
+
+    fn __iter__(&self) -> TrackIter {
+        TrackIter {
+            names: self.names.clone().into_iter(),
+        }
+    }
+}
+
+/// Segments indexed by an integer or a slice
+#[gen_stub_pyclass]
+#[pyclass]
+struct Segments {
+    values: Vec<i64>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Segments {
+    #[new]
+    fn new(values: Vec<i64>) -> Self {
+        Self { values }
+    }
+
+    #[gen_stub(getitem(int -> "i64", slice -> "Self"))]
+    fn __getitem__(&self, index: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let py = index.py();
+        if let Ok(slice) = index.cast::<PySlice>() {
+            let indices = slice.indices(self.values.len() as isize)?;
+            let values = (0..indices.slicelength)
+                .map(|i| self.values[(indices.start + i as isize * indices.step) as usize])
+                .collect();
+            return Ok(Py::new(py, Segments { values })?.into_any());
+        }
+        let len = self.values.len() as isize;
+        let i: isize = index.extract()?;
+        let i = if i < 0 { i + len } else { i };
+        let value = usize::try_from(i)
+            .ok()
+            .and_then(|i| self.values.get(i))
+            .ok_or_else(|| pyo3::exceptions::PyIndexError::new_err(i))?;
+        Ok(value.into_pyobject(py)?.into_any().unbind())
+    }
+}
+
+/// Iterator over tracks, rendered with the `collections.abc.Iterator[str]` base
+#[gen_stub_pyclass]
+#[pyclass]
+struct TrackIter {
+    names: std::vec::IntoIter<String>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl TrackIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<String> {
+        self.names
+            .next()
+            .ok_or_else(|| pyo3::exceptions::PyStopIteration::new_err(()))
+    }
+}
+
+/// Returns a skipped class, which is rendered as `typing.Any`
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn diagnostics() -> Diagnostics {
+    Diagnostics::default()
+}
+
 /// `C` only impl `FromPyObject`
 #[derive(Debug)]
 struct C {
@@ -197,6 +342,13 @@ fn str_len(x: &str) -> PyResult<usize> {
     Ok(x.len())
 }
 
+/// Parameters named by raw identifiers are exposed without `r#`
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn describe_item(r#type: String) -> String {
+    format!("item of {type}", type = r#type)
+}
+
 #[gen_stub_pyfunction]
 #[pyfunction]
 fn echo_path(path: PathBuf) -> PyResult<PathBuf> {
@@ -331,6 +483,49 @@ impl DecimalHolder {
 
 module_variable!("pure", "MY_CONSTANT1", usize);
 module_variable!("pure", "MY_CONSTANT2", usize, 123);
+module_variable!("pure", "MODE_FAST", &str, "fast");
+module_variable!("pure", "MODE_SLOW", &str, "slow");
+module_variable!("pure", "MODE_AUTO", &str, "auto");
+literal_group!("pure", "Mode", [MODE_FAST, MODE_SLOW, MODE_AUTO]);
+module_version!("pure");
+gen_stub_module_attr!(
+    m_path = concat_module!(),
+    name = "DEFAULT_TIMEOUT",
+    type = f64,
+    doc = "Timeout in seconds unless given otherwise",
+);
+
+#[gen_stub_pyconst(module = "pure")]
+pub const MAX_ITER: usize = 100;
+
+/// Raise `ValueError` with the message, so that the code after the call is unreachable
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[gen_stub(no_return)]
+fn abort(msg: &str) -> PyResult<()> {
+    Err(pyo3::exceptions::PyValueError::new_err(msg.to_string()))
+}
+
+/// Run in one of the `MODE_*` modes
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn run_with_mode(#[gen_stub(override_type(type_repr = "Mode"))] mode: String) -> String {
+    mode
+}
+
+/// Open a file in one of the modes validated at runtime
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (path, mode, encoding = None))]
+#[gen_stub(literal(0, 1))]
+fn open_with_mode(
+    path: PathBuf,
+    #[gen_stub(literal("r", "w", "a"))] mode: &str,
+    #[gen_stub(literal("utf-8", "latin-1"))] encoding: Option<&str>,
+) -> i32 {
+    let _ = (path, encoding);
+    (mode == "r") as i32
+}
 
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -338,6 +533,92 @@ async fn async_num() -> i32 {
     123
 }
 
+/// Awaitable object resolving to a number without suspending
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct Ready {
+    value: i32,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Ready {
+    #[gen_stub(override_return_type(type_repr = "builtins.int", imports = ("builtins")))]
+    fn __await__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self) -> PyResult<()> {
+        Err(pyo3::exceptions::PyStopIteration::new_err(self.value))
+    }
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[gen_stub(awaitable, override_return_type(type_repr = "builtins.int", imports = ("builtins")))]
+fn ready(value: i32) -> Ready {
+    Ready { value }
+}
+
+/// Tree node referring to other nodes by `Py`, `PyRef`, `PyRefMut`, and `Bound`
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct Node {
+    #[pyo3(get)]
+    parent: Option<Py<Node>>,
+    #[pyo3(get, set)]
+    name: String,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Node {
+    #[new]
+    fn new(name: String) -> Self {
+        Self { parent: None, name }
+    }
+
+    fn rename(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self> {
+        slf.name = name;
+        slf
+    }
+
+    fn is_sibling(&self, other: &Bound<'_, Node>) -> bool {
+        match (&self.parent, &other.borrow().parent) {
+            (Some(a), Some(b)) => a.is(b),
+            _ => false,
+        }
+    }
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn child(parent: PyRef<'_, Node>, name: String) -> PyResult<Py<Node>> {
+    let py = parent.py();
+    let node = Node {
+        parent: Some(parent.into()),
+        name,
+    };
+    Py::new(py, node)
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn root<'py>(node: Bound<'py, Node>) -> Bound<'py, Node> {
+    let mut current = node;
+    loop {
+        let parent = current
+            .borrow()
+            .parent
+            .as_ref()
+            .map(|p| p.bind(current.py()).clone());
+        match parent {
+            Some(parent) => current = parent,
+            None => return current,
+        }
+    }
+}
+
 #[gen_stub_pyfunction]
 #[pyfunction]
 #[deprecated(since = "1.0.0", note = "This function is deprecated")]
@@ -345,6 +626,12 @@ fn deprecated_function() {
     println!("This function is deprecated");
 }
 
+/// Class superseded by :class:`A`
+#[gen_stub_pyclass]
+#[pyclass]
+#[deprecated(since = "1.0.0", note = "Use `A` instead")]
+struct OldA {}
+
 // Test if non-any PyObject Target can be a default value
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -397,6 +684,60 @@ impl HashableStruct {
     }
 }
 
+/// Record rendered as `@dataclasses.dataclass`
+#[gen_stub_pyclass]
+#[gen_stub(dataclass)]
+#[pyclass(get_all, set_all)]
+#[derive(Debug, Clone)]
+pub struct DataclassRecord {
+    #[gen_stub(default = 0)]
+    pub count: i32,
+    pub name: String,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl DataclassRecord {
+    #[new]
+    #[pyo3(signature = (name, count = 0))]
+    fn new(name: String, count: i32) -> Self {
+        Self { count, name }
+    }
+}
+
+/// Context manager whose `__enter__` returns itself
+#[gen_stub_pyclass]
+#[pyclass]
+#[derive(Debug, Default)]
+pub struct Lock {
+    #[pyo3(get)]
+    locked: bool,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Lock {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.locked = true;
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&Bound<PyAny>>,
+        _exc_value: Option<&Bound<PyAny>>,
+        _traceback: Option<&Bound<PyAny>>,
+    ) -> bool {
+        self.locked = false;
+        false
+    }
+}
+
 /// Takes a variable number of arguments and returns their string representation.
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -427,11 +768,19 @@ module_doc!("pure", "Document for {} ...", env!("CARGO_PKG_NAME"));
 
 /// Initializes the Python module
 #[pymodule]
-fn pure(m: &Bound<PyModule>) -> PyResult<()> {
+pub fn pure(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add("MY_CONSTANT1", 19937)?;
     m.add("MY_CONSTANT2", 123)?;
+    m.add("DEFAULT_TIMEOUT", 30.0)?;
+    m.add("MAX_ITER", MAX_ITER)?;
+    m.add("MODE_FAST", "fast")?;
+    m.add("MODE_SLOW", "slow")?;
+    m.add("MODE_AUTO", "auto")?;
     m.add_class::<A>()?;
+    m.add_class::<Segments>()?;
     m.add_class::<B>()?;
+    m.add_class::<OldA>()?;
     m.add_class::<MyDate>()?;
     m.add_class::<Number>()?;
     m.add_class::<NumberRenameAll>()?;
@@ -443,6 +792,8 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<OverrideType>()?;
     m.add_class::<ComparableStruct>()?;
     m.add_class::<HashableStruct>()?;
+    m.add_class::<DataclassRecord>()?;
+    m.add_class::<Lock>()?;
     m.add_class::<DecimalHolder>()?;
     m.add_class::<DataContainer>()?;
     m.add_class::<Placeholder>()?;
@@ -454,10 +805,22 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(read_dict, m)?)?;
     m.add_function(wrap_pyfunction!(create_a, m)?)?;
     m.add_function(wrap_pyfunction!(print_c, m)?)?;
+    m.add_function(wrap_pyfunction!(count_tags, m)?)?;
+    m.add_class::<Diagnostics>()?;
+    m.add_class::<TracedDiagnostics>()?;
+    m.add_class::<Tracks>()?;
+    m.add_class::<TrackIter>()?;
+    m.add_function(wrap_pyfunction!(diagnostics, m)?)?;
     m.add_function(wrap_pyfunction!(str_len, m)?)?;
     m.add_function(wrap_pyfunction!(echo_path, m)?)?;
+    m.add_function(wrap_pyfunction!(describe_item, m)?)?;
     m.add_function(wrap_pyfunction!(ahash_dict, m)?)?;
     m.add_function(wrap_pyfunction!(async_num, m)?)?;
+    m.add_class::<Ready>()?;
+    m.add_class::<Node>()?;
+    m.add_function(wrap_pyfunction!(child, m)?)?;
+    m.add_function(wrap_pyfunction!(root, m)?)?;
+    m.add_function(wrap_pyfunction!(ready, m)?)?;
     m.add_function(wrap_pyfunction!(deprecated_function, m)?)?;
     m.add_function(wrap_pyfunction!(default_value, m)?)?;
     m.add_function(wrap_pyfunction!(fn_override_type, m)?)?;
@@ -482,6 +845,9 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(test_type_ignore_custom, m)?)?;
     m.add_function(wrap_pyfunction!(test_type_ignore_no_comment_all, m)?)?;
     m.add_function(wrap_pyfunction!(test_type_ignore_no_comment_specific, m)?)?;
+    m.add_function(wrap_pyfunction!(run_with_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(abort, m)?)?;
+    m.add_function(wrap_pyfunction!(open_with_mode, m)?)?;
 
     // Test case for custom exceptions
     m.add("MyError", m.py().get_type::<MyError>())?;
@@ -568,7 +934,7 @@ impl TypeIgnoreTest {
     }
 }
 
-define_stub_info_gatherer!(stub_info);
+define_stub_info_gatherer!(stub_info, export_entry);
 
 /// Test of unit test for testing link problem
 #[cfg(test)]
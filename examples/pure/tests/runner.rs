@@ -0,0 +1,28 @@
+//! Generate the stubs from `pure` built as an extension module, which is not linked to libpython
+
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    path::Path,
+    process::Command,
+};
+
+#[test]
+fn stubs_from_extension_module() -> pyo3_stub_gen::Result<()> {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("runner");
+    let status = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["build", "--quiet", "--package", "pure", "--lib"])
+        .args(["--features", "pyo3/extension-module"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()?;
+    assert!(status.success());
+
+    let stub = Path::new(env!("CARGO_MANIFEST_DIR")).join("pure.pyi");
+    let committed = std::fs::read_to_string(&stub)?;
+    let library = target_dir
+        .join("debug")
+        .join(format!("{DLL_PREFIX}pure{DLL_SUFFIX}"));
+    pyo3_stub_gen::runner::run_from_cdylib(&library)?;
+    assert_eq!(std::fs::read_to_string(&stub)?, committed);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! Compare the stubs with the module `pure` imported at runtime
+
+use pure::pure as pure_module;
+use pyo3_stub_gen::runtime_check::MismatchKind;
+
+/// Items omitted from the stubs on purpose by `#[gen_stub(skip)]`
+const SKIPPED: [&str; 2] = ["Diagnostics", "A.need_skip"];
+/// Classes in the stubs which the module does not register
+const UNREGISTERED: [&str; 1] = ["ColorMapping"];
+
+#[test]
+fn stubs_match_runtime() {
+    pyo3::append_to_inittab!(pure_module);
+    pyo3::Python::initialize();
+    let stub_info = pure::stub_info().unwrap();
+    let mismatches: Vec<_> = pyo3::Python::attach(|py| stub_info.verify_against_runtime(py))
+        .into_iter()
+        .filter(|mismatch| {
+            !(mismatch.kind == MismatchKind::MissingFromStub
+                && SKIPPED.contains(&mismatch.name.as_str()))
+        })
+        .filter(|mismatch| {
+            !(mismatch.kind == MismatchKind::MissingAtRuntime
+                && UNREGISTERED.contains(&mismatch.name.as_str()))
+        })
+        .map(|mismatch| mismatch.to_string())
+        .collect();
+    assert!(mismatches.is_empty(), "{mismatches:#?}");
+}
@@ -11,6 +11,8 @@ from pure import (
     Shape2,
     ComparableStruct,
     HashableStruct,
+    DataclassRecord,
+    Lock,
     add_decimals,
     DecimalHolder,
     fn_override_type,
@@ -218,6 +220,26 @@ def test_hashable_struct_hash_str_methods():
     assert len(s) == 2  # obj1 and obj2 are equal, so only 2 unique items
 
 
+def test_dataclass_record():
+    """Test that DataclassRecord is constructed like the generated dataclass stub"""
+    record = DataclassRecord("a")
+    assert record.name == "a"
+    assert record.count == 0
+
+    record = DataclassRecord("b", count=3)
+    record.count += 1
+    assert record.count == 4
+
+
+def test_lock_context_manager():
+    """Test that Lock.__enter__ returns the lock itself as typed by the stub"""
+    lock = Lock()
+    with lock as entered:
+        assert entered is lock
+        assert entered.locked
+    assert not lock.locked
+
+
 def test_add_decimals():
     """Test the add_decimals function works correctly"""
     from decimal import Decimal
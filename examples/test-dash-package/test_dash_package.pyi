@@ -3,5 +3,9 @@
 
 import builtins
 
+__all__ = [
+    "test_function",
+]
+
 def test_function() -> builtins.int: ...
 
@@ -9,8 +9,11 @@
 //! inventory::submit!{
 //!     PyClassInfo {
 //!         pyclass_name: "Placeholder",
+//!         name_given: true,
 //!         module: Some("my_module"),
 //!         struct_id: std::any::TypeId::of::<PyPlaceholder>,
+//!         rust_path: concat!(module_path!(), "::", stringify!(PyPlaceholder)),
+//!         source_location: None,
 //!         getters: &[
 //!             MemberInfo {
 //!                 name: "name",
@@ -20,6 +23,10 @@
 //!                 deprecated: None,
 //!                 item: false,
 //!                 is_abstract: false,
+//!                 also_as: &[],
+//!                 is_classattr: false,
+//!                 int_value: None,
+//!                 type_ignored: None,
 //!             },
 //!             MemberInfo {
 //!                 name: "ndim",
@@ -29,6 +36,10 @@
 //!                 deprecated: None,
 //!                 item: false,
 //!                 is_abstract: false,
+//!                 also_as: &[],
+//!                 is_classattr: false,
+//!                 int_value: None,
+//!                 type_ignored: None,
 //!             },
 //!             MemberInfo {
 //!                 name: "description",
@@ -38,6 +49,10 @@
 //!                 deprecated: None,
 //!                 item: false,
 //!                 is_abstract: false,
+//!                 also_as: &[],
+//!                 is_classattr: false,
+//!                 int_value: None,
+//!                 type_ignored: None,
 //!             },
 //!         ],
 //!         setters: &[],
@@ -49,6 +64,21 @@
 //!         has_str: false,
 //!         subclass: false,
 //!         is_abstract: false,
+//!         type_override: ClassTypeOverride {
+//!             input: None,
+//!             output: None,
+//!             custom_from_py_object: false,
+//!         },
+//!         dataclass: false,
+//!         hidden_base: false,
+//!         no_abc: false,
+//!         flag_enum: false,
+//!         total_ordering: false,
+//!         pickle_state: None,
+//!         slots: false,
+//!         weakref: false,
+//!         feature: None,
+//!         deprecated: None,
 //!     }
 //! }
 //! ```
@@ -88,9 +118,11 @@ mod member;
 mod method;
 mod parameter;
 mod parse_python;
+mod plain_type;
 mod pyclass;
 mod pyclass_complex_enum;
 mod pyclass_enum;
+mod pyconst;
 mod pyfunction;
 mod pymethods;
 mod renaming;
@@ -106,6 +138,7 @@ use method::*;
 use pyclass::*;
 use pyclass_complex_enum::*;
 use pyclass_enum::*;
+use pyconst::*;
 use pyfunction::*;
 use pymethods::*;
 use renaming::*;
@@ -115,7 +148,7 @@ use util::*;
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse2, ItemEnum, ItemFn, ItemImpl, ItemStruct, LitStr, Result};
+use syn::{parse2, DeriveInput, Item, ItemEnum, ItemFn, ItemImpl, ItemStruct, LitStr, Result};
 
 pub fn pyclass(item: TokenStream2) -> Result<TokenStream2> {
     let mut item_struct = parse2::<ItemStruct>(item)?;
@@ -132,10 +165,12 @@ pub fn pyclass(item: TokenStream2) -> Result<TokenStream2> {
 }
 
 pub fn pyclass_enum(item: TokenStream2) -> Result<TokenStream2> {
-    let inner = PyEnumInfo::try_from(parse2::<ItemEnum>(item.clone())?)?;
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = PyEnumInfo::try_from(item_enum.clone())?;
     let derive_stub_type = StubType::from(&inner);
+    attr::prune_attrs(&mut item_enum.attrs);
     Ok(quote! {
-        #item
+        #item_enum
         #derive_stub_type
         pyo3_stub_gen::inventory::submit! {
             #inner
@@ -144,10 +179,12 @@ pub fn pyclass_enum(item: TokenStream2) -> Result<TokenStream2> {
 }
 
 pub fn pyclass_complex_enum(item: TokenStream2) -> Result<TokenStream2> {
-    let inner = PyComplexEnumInfo::try_from(parse2::<ItemEnum>(item.clone())?)?;
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = PyComplexEnumInfo::try_from(item_enum.clone())?;
     let derive_stub_type = StubType::from(&inner);
+    attr::prune_attrs(&mut item_enum.attrs);
     Ok(quote! {
-        #item
+        #item_enum
         #derive_stub_type
         pyo3_stub_gen::inventory::submit! {
             #inner
@@ -155,9 +192,15 @@ pub fn pyclass_complex_enum(item: TokenStream2) -> Result<TokenStream2> {
     })
 }
 
-pub fn pymethods(item: TokenStream2) -> Result<TokenStream2> {
+pub fn plain_type(item: TokenStream2) -> Result<TokenStream2> {
+    let inner = plain_type::PlainType::try_from(parse2::<DeriveInput>(item)?)?;
+    Ok(quote! { #inner })
+}
+
+pub fn pymethods(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
     let mut item_impl = parse2::<ItemImpl>(item)?;
-    let inner = PyMethodsInfo::try_from(item_impl.clone())?;
+    let attr = parse2::<PyMethodsAttr>(attr)?;
+    let inner = PyMethodsInfo::try_from(attr.apply(item_impl.clone())?)?;
     pymethods::prune_attrs(&mut item_impl);
     Ok(quote! {
         #item_impl
@@ -171,30 +214,41 @@ pub fn pymethods(item: TokenStream2) -> Result<TokenStream2> {
 pub fn pyfunction(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
     let mut item_fn = parse2::<ItemFn>(item)?;
     let mut inner = PyFunctionInfo::try_from(item_fn.clone())?;
-    let python_stub = inner.parse_attr(attr)?;
+    let attr = parse2::<PyFunctionAttr>(attr)?;
     pyfunction::prune_attrs(&mut item_fn);
 
     // If python parameter is provided, use it instead of auto-generated metadata
-    if let Some(stub_str) = python_stub {
+    if let Some(stub_str) = attr.python {
         let mut python_inner = parse_python::parse_python_function_stub(stub_str)?;
-        // Preserve module information from attributes
-        python_inner.module = inner.module;
-        Ok(quote! {
-            #item_fn
-            #[automatically_derived]
-            pyo3_stub_gen::inventory::submit! {
-                #python_inner
-            }
-        })
-    } else {
-        Ok(quote! {
-            #item_fn
+        // Preserve feature information and the location of the Rust function
+        python_inner.feature = inner.feature;
+        python_inner.span = inner.span;
+        inner = python_inner;
+    }
+    // One submission per module, rendered identically in each of them
+    let submissions = inner.submissions(attr.modules);
+    Ok(quote! {
+        #item_fn
+        #(
             #[automatically_derived]
             pyo3_stub_gen::inventory::submit! {
-                #inner
+                #submissions
             }
-        })
-    }
+        )*
+    })
+}
+
+pub fn pyconst(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let mut item = parse2::<Item>(item)?;
+    let inner = PyConstInfo::new(parse2::<PyConstAttr>(attr)?, &item)?;
+    pyconst::prune_attrs(&mut item);
+    Ok(quote! {
+        #item
+        #[automatically_derived]
+        pyo3_stub_gen::inventory::submit! {
+            #inner
+        }
+    })
 }
 
 pub fn gen_function_from_python_impl(input: TokenStream2) -> Result<TokenStream2> {
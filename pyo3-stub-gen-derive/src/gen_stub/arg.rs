@@ -1,10 +1,18 @@
 use quote::ToTokens;
 use syn::{
-    spanned::Spanned, FnArg, GenericArgument, PatType, PathArguments, Result, Type, TypePath,
-    TypeReference,
+    ext::IdentExt, spanned::Spanned, FnArg, GenericArgument, LitStr, PatType, PathArguments,
+    Result, Type, TypePath, TypeReference,
 };
 
-use crate::gen_stub::{attr::parse_gen_stub_override_type, util::TypeOrOverride};
+use crate::gen_stub::{
+    attr::{
+        parse_gen_stub_accepts, parse_gen_stub_callable, parse_gen_stub_class_of,
+        parse_gen_stub_container_default, parse_gen_stub_default_repr, parse_gen_stub_literal,
+        parse_gen_stub_override_type,
+    },
+    parameter::ContainerDefault,
+    util::{accepts_type, callable_type, class_of_type, literal_type, TypeOrOverride},
+};
 
 pub fn parse_args(iter: impl IntoIterator<Item = FnArg>) -> Result<Vec<ArgInfo>> {
     let mut args = Vec::new();
@@ -64,6 +72,17 @@ pub fn parse_args(iter: impl IntoIterator<Item = FnArg>) -> Result<Vec<ArgInfo>>
 pub struct ArgInfo {
     pub(crate) name: String,
     pub(crate) r#type: TypeOrOverride,
+    /// Rendering of a list, dict, or set default, given by `#[gen_stub(container_default = "...")]`
+    pub(crate) container_default: Option<ContainerDefault>,
+    /// Python expression of the default, given by `#[gen_stub(default_repr = "...")]`
+    pub(crate) default_repr: Option<LitStr>,
+}
+
+impl ArgInfo {
+    /// Whether the type is given by `#[gen_stub(override_type(...))]`
+    pub(crate) fn is_overridden(&self) -> bool {
+        matches!(self.r#type, TypeOrOverride::OverrideType { .. })
+    }
 }
 
 impl TryFrom<FnArg> for ArgInfo {
@@ -71,10 +90,55 @@ impl TryFrom<FnArg> for ArgInfo {
     fn try_from(value: FnArg) -> Result<Self> {
         let span = value.span();
         if let FnArg::Typed(PatType { pat, ty, attrs, .. }) = value {
-            if let syn::Pat::Ident(mut ident) = *pat {
-                ident.mutability = None;
-                let name = ident.to_token_stream().to_string();
-                if let Some(attr) = parse_gen_stub_override_type(&attrs)? {
+            if let syn::Pat::Ident(ident) = *pat {
+                let name = ident.ident.unraw().to_string();
+                let class_of = parse_gen_stub_class_of(&attrs)?;
+                let literal = parse_gen_stub_literal(&attrs)?;
+                let callable = parse_gen_stub_callable(&attrs)?;
+                let container_default = parse_gen_stub_container_default(&attrs)?;
+                let default_repr = parse_gen_stub_default_repr(&attrs)?;
+                let override_type = parse_gen_stub_override_type(&attrs)?;
+                if let Some(accepts) = parse_gen_stub_accepts(&attrs)? {
+                    let conflict = [
+                        (override_type.is_some(), "override_type(...)"),
+                        (class_of.is_some(), "class_of"),
+                        (literal.is_some(), "literal(...)"),
+                        (callable.is_some(), "callable(...)"),
+                    ]
+                    .into_iter()
+                    .find_map(|(given, attr)| given.then_some(attr));
+                    if let Some(attr) = conflict {
+                        return Err(syn::Error::new(
+                            accepts.span(),
+                            format!("`{attr}` cannot be combined with `accepts = \"...\"`"),
+                        ));
+                    }
+                    return Ok(Self {
+                        name,
+                        r#type: accepts_type(&ty, &accepts),
+                        container_default,
+                        default_repr,
+                    });
+                }
+                if let Some(attr) = override_type {
+                    if let Some(class) = class_of {
+                        return Err(syn::Error::new(
+                            class.span(),
+                            "`class_of` cannot be combined with `override_type(...)`",
+                        ));
+                    }
+                    if literal.is_some() {
+                        return Err(syn::Error::new(
+                            span,
+                            "`literal(...)` cannot be combined with `override_type(...)`",
+                        ));
+                    }
+                    if callable.is_some() {
+                        return Err(syn::Error::new(
+                            span,
+                            "`callable(...)` cannot be combined with `override_type(...)`",
+                        ));
+                    }
                     return Ok(Self {
                         name,
                         r#type: TypeOrOverride::OverrideType {
@@ -82,6 +146,52 @@ impl TryFrom<FnArg> for ArgInfo {
                             type_repr: attr.type_repr,
                             imports: attr.imports,
                         },
+                        container_default,
+                        default_repr,
+                    });
+                }
+                if let Some(literal) = literal {
+                    if let Some(class) = class_of {
+                        return Err(syn::Error::new(
+                            class.span(),
+                            "`class_of` cannot be combined with `literal(...)`",
+                        ));
+                    }
+                    if callable.is_some() {
+                        return Err(syn::Error::new(
+                            span,
+                            "`callable(...)` cannot be combined with `literal(...)`",
+                        ));
+                    }
+                    return Ok(Self {
+                        name,
+                        r#type: literal_type(&ty, &literal),
+                        container_default,
+                        default_repr,
+                    });
+                }
+                if let Some(callable) = callable {
+                    if let Some(class) = class_of {
+                        return Err(syn::Error::new(
+                            class.span(),
+                            "`class_of` cannot be combined with `callable(...)`",
+                        ));
+                    }
+                    return Ok(Self {
+                        name,
+                        r#type: callable_type(&ty, &callable),
+                        container_default,
+                        default_repr,
+                    });
+                }
+                if let Some(class) = class_of {
+                    return Ok(Self {
+                        name,
+                        r#type: TypeOrOverride::RustType {
+                            r#type: class_of_type(&ty, &class)?,
+                        },
+                        container_default,
+                        default_repr,
                     });
                 }
                 return Ok(Self {
@@ -89,6 +199,8 @@ impl TryFrom<FnArg> for ArgInfo {
                     r#type: TypeOrOverride::RustType {
                         r#type: (*ty).clone(),
                     },
+                    container_default,
+                    default_repr,
                 });
             }
 
@@ -96,6 +208,8 @@ impl TryFrom<FnArg> for ArgInfo {
                 return Ok(Self {
                     name: "_".to_owned(),
                     r#type: TypeOrOverride::RustType { r#type: *ty },
+                    container_default: None,
+                    default_repr: None,
                 });
             }
         }
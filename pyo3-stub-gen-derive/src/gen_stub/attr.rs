@@ -1,6 +1,6 @@
 use indexmap::IndexSet;
 
-use super::{RenamingRule, Signature};
+use super::{parameter::ContainerDefault, RenamingRule, Signature};
 use proc_macro2::{TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
@@ -19,6 +19,24 @@ pub enum IgnoreTarget {
     SpecifiedLits(Vec<LitStr>),
 }
 
+impl ToTokens for IgnoreTarget {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            IgnoreTarget::All => tokens.append_all(quote! {
+                ::pyo3_stub_gen::type_info::IgnoreTarget::All
+            }),
+            IgnoreTarget::SpecifiedLits(rules) => {
+                let rules = rules.iter().map(LitStr::value);
+                tokens.append_all(quote! {
+                    ::pyo3_stub_gen::type_info::IgnoreTarget::Specified(
+                        &[#(#rules),*] as &[&str]
+                    )
+                })
+            }
+        }
+    }
+}
+
 pub fn extract_documents(attrs: &[Attribute]) -> Vec<String> {
     let mut docs = Vec::new();
     for attr in attrs {
@@ -50,33 +68,40 @@ pub fn extract_documents(attrs: &[Attribute]) -> Vec<String> {
     docs
 }
 
-/// Extract `#[deprecated(...)]` attribute
+/// Extract `#[deprecated]`, `#[deprecated = "note"]`, or `#[deprecated(since = "...", note = "...")]` attribute
 pub fn extract_deprecated(attrs: &[Attribute]) -> Option<DeprecatedInfo> {
-    for attr in attrs {
-        if attr.path().is_ident("deprecated") {
-            if let Ok(list) = attr.meta.require_list() {
-                let mut since = None;
-                let mut note = None;
-
-                list.parse_nested_meta(|meta| {
-                    if meta.path.is_ident("since") {
-                        let value = meta.value()?;
-                        let lit: LitStr = value.parse()?;
-                        since = Some(lit.value());
-                    } else if meta.path.is_ident("note") {
-                        let value = meta.value()?;
-                        let lit: LitStr = value.parse()?;
-                        note = Some(lit.value());
-                    }
-                    Ok(())
-                })
-                .ok()?;
-
-                return Some(DeprecatedInfo { since, note });
+    let attr = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("deprecated"))?;
+    let mut since = None;
+    let mut note = None;
+    match &attr.meta {
+        Meta::Path(_) => {}
+        Meta::NameValue(name_value) => {
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) = &name_value.value
+            {
+                note = Some(lit.value());
             }
         }
+        Meta::List(list) => {
+            list.parse_nested_meta(|meta| {
+                if meta.path.is_ident("since") {
+                    let value = meta.value()?;
+                    let lit: LitStr = value.parse()?;
+                    since = Some(lit.value());
+                } else if meta.path.is_ident("note") {
+                    let value = meta.value()?;
+                    let lit: LitStr = value.parse()?;
+                    note = Some(lit.value());
+                }
+                Ok(())
+            })
+            .ok()?;
+        }
     }
-    None
+    Some(DeprecatedInfo { since, note })
 }
 
 /// `#[pyo3(...)]` style attributes appear in `#[pyclass]` and `#[pymethods]` proc-macros
@@ -140,6 +165,8 @@ pub enum Attr {
     Hash,
     Str,
     Subclass,
+    Dict,
+    Weakref,
 
     // Attributes appears in components within `#[pymethods]`
     // <https://docs.rs/pyo3/latest/pyo3/attr.pymethods.html>
@@ -225,6 +252,12 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
                         if ident == "subclass" {
                             pyo3_attrs.push(Attr::Subclass);
                         }
+                        if ident == "dict" {
+                            pyo3_attrs.push(Attr::Dict);
+                        }
+                        if ident == "weakref" {
+                            pyo3_attrs.push(Attr::Weakref);
+                        }
                         // frozen is required by PyO3 when using hash, but doesn't affect stub generation
                     }
                     [Ident(ident), Punct(_), Literal(lit)] => {
@@ -251,10 +284,12 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
                                 .push(Attr::Constructor(syn::parse2(group.to_token_stream())?));
                         }
                     }
-                    [Ident(ident), Punct(_), Ident(ident2)] => {
-                        if ident == "extends" {
-                            pyo3_attrs.push(Attr::Extends(syn::parse2(ident2.to_token_stream())?));
-                        }
+                    // The base may be a path, e.g. `extends = other_crate::Base`
+                    [Ident(ident), Punct(_), base @ ..]
+                        if ident == "extends" && !base.is_empty() =>
+                    {
+                        let base: TokenStream2 = base.iter().cloned().collect();
+                        pyo3_attrs.push(Attr::Extends(syn::parse2(base)?));
                     }
                     _ => {}
                 }
@@ -301,6 +336,54 @@ pub enum StubGenAttr {
     AbstractClass,
     /// Mark a method as abstract
     AbstractMethod,
+    /// Render a function returning an awaitable object by `def` returning `collections.abc.Awaitable[...]`
+    Awaitable,
+    /// Render the return type of a function which always raises as `typing.Never`
+    NoReturn,
+    /// Parameter kept for compatibility, noted in the docstring
+    DeprecatedParam(DeprecatedParamAttribute),
+    /// Split the signature of a method into `@typing.overload`s, each taking the named parameters
+    OverloadGroups(Vec<Vec<LitStr>>),
+    /// Override the python type used when a class is received as an argument
+    TypeInputOverride(OverrideTypeAttribute),
+    /// Override the python type used when a class is returned
+    TypeOutputOverride(OverrideTypeAttribute),
+    /// Hint that a class has a hand-written `FromPyObject` implementation
+    CustomFromPyObject,
+    /// Render a class as `@dataclasses.dataclass`
+    Dataclass,
+    /// Omit a base class from stubs, and copy its members into its subclasses
+    HiddenBase,
+    /// Suppress `collections.abc` bases inferred from dunder methods
+    NoAbc,
+    /// Render a bitflags class as `enum.Flag`
+    FlagEnum,
+    /// Synthesize the comparisons `functools.total_ordering` supplies
+    TotalOrdering,
+    /// Render `__slots__` listing the attributes and properties of a class
+    Slots,
+    /// State of `__getstate__` and `__setstate__`, e.g. `"(int, str)"` for `tuple[int, str]`
+    PickleState(LitStr),
+    /// Additional names a method or property is exposed under
+    AlsoAs(Vec<LitStr>),
+    /// Class whose class object a `PyType` argument or return value is
+    ClassOf(LitStr),
+    /// Values an argument or return value is restricted to, rendered as `typing.Literal[...]`
+    Literal(LiteralAttribute),
+    /// Signature of a callback argument or a callable return value, rendered as `collections.abc.Callable[...]`
+    Callable(CallableAttribute),
+    /// Rendering of the list, dict, or set default of an argument
+    ContainerDefault(ContainerDefault),
+    /// Python expression of the default of an argument, e.g. `LogLevel.Info`
+    DefaultRepr(LitStr),
+    /// Python type an argument converted by `#[pyo3(from_py_with = ...)]` accepts
+    Accepts(LitStr),
+    /// Return types of `__getitem__` overloads taking an index or a slice
+    GetItem(GetItemOverloads),
+    /// Render a class-protocol hook such as `__init_subclass__` with its declared signature
+    KeepSignature,
+    /// Cargo feature the item belongs to, e.g. `cuda`
+    Feature(LitStr),
 }
 
 pub fn prune_attrs(attrs: &mut Vec<Attribute>) {
@@ -316,6 +399,87 @@ pub fn parse_gen_stub_override_type(attrs: &[Attribute]) -> Result<Option<Overri
     Ok(None)
 }
 
+pub fn parse_gen_stub_class_of(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        if let StubGenAttr::ClassOf(class) = attr {
+            return Ok(Some(class));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_literal(attrs: &[Attribute]) -> Result<Option<LiteralAttribute>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        if let StubGenAttr::Literal(literal) = attr {
+            return Ok(Some(literal));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_callable(attrs: &[Attribute]) -> Result<Option<CallableAttribute>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        if let StubGenAttr::Callable(callable) = attr {
+            return Ok(Some(callable));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_default_repr(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        if let StubGenAttr::DefaultRepr(repr) = attr {
+            return Ok(Some(repr));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_accepts(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        if let StubGenAttr::Accepts(accepts) = attr {
+            return Ok(Some(accepts));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_container_default(attrs: &[Attribute]) -> Result<Option<ContainerDefault>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        if let StubGenAttr::ContainerDefault(style) = attr {
+            return Ok(Some(style));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_return_class_of(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
+        if let StubGenAttr::ClassOf(class) = attr {
+            return Ok(Some(class));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_return_literal(attrs: &[Attribute]) -> Result<Option<LiteralAttribute>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
+        if let StubGenAttr::Literal(literal) = attr {
+            return Ok(Some(literal));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_return_callable(attrs: &[Attribute]) -> Result<Option<CallableAttribute>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
+        if let StubGenAttr::Callable(callable) = attr {
+            return Ok(Some(callable));
+        }
+    }
+    Ok(None)
+}
+
 pub fn parse_gen_stub_override_return_type(
     attrs: &[Attribute],
 ) -> Result<Option<OverrideTypeAttribute>> {
@@ -335,11 +499,31 @@ pub fn parse_gen_stub_default(attrs: &[Attribute]) -> Result<Option<Expr>> {
     }
     Ok(None)
 }
+/// Reject the keywords of `#[gen_stub(...)]` which are not valid on a field of a `#[pyclass]`
+pub fn validate_gen_stub_field_attrs(attrs: &[Attribute]) -> Result<()> {
+    parse_gen_stub_attrs(attrs, AttributeLocation::Field, None)?;
+    Ok(())
+}
+
 pub fn parse_gen_stub_skip(attrs: &[Attribute]) -> Result<bool> {
     let attrs = parse_gen_stub_attrs(
         attrs,
         AttributeLocation::Field,
-        Some(&["override_return_type", "default", "allow", "abstractmethod"]),
+        Some(&[
+            "override_return_type",
+            "default",
+            "allow",
+            "abstractmethod",
+            "no_return",
+            "deprecated_param",
+            "literal",
+            "class_of",
+            "overload_groups",
+            "awaitable",
+            "getitem",
+            "keep_signature",
+            "callable",
+        ]),
     )?;
     Ok(attrs.iter().any(|attr| matches!(attr, StubGenAttr::Skip)))
 }
@@ -348,7 +532,21 @@ pub fn parse_gen_stub_allow(attrs: &[Attribute]) -> Result<bool> {
     let field_attrs = parse_gen_stub_attrs(
         attrs,
         AttributeLocation::Field,
-        Some(&["override_return_type", "default", "skip", "abstractmethod"]),
+        Some(&[
+            "override_return_type",
+            "default",
+            "skip",
+            "abstractmethod",
+            "no_return",
+            "deprecated_param",
+            "literal",
+            "class_of",
+            "overload_groups",
+            "awaitable",
+            "getitem",
+            "keep_signature",
+            "callable",
+        ]),
     )?;
     if field_attrs
         .iter()
@@ -387,7 +585,20 @@ pub fn parse_gen_stub_type_ignore(attrs: &[Attribute]) -> Result<Option<IgnoreTa
     for attr in parse_gen_stub_attrs(
         attrs,
         AttributeLocation::Field,
-        Some(&["abstractmethod", "allow", "skip"]),
+        Some(&[
+            "abstractmethod",
+            "allow",
+            "skip",
+            "no_return",
+            "deprecated_param",
+            "literal",
+            "class_of",
+            "overload_groups",
+            "awaitable",
+            "getitem",
+            "keep_signature",
+            "callable",
+        ]),
     )? {
         if let StubGenAttr::TypeIgnore(target) = attr {
             return Ok(Some(target));
@@ -403,6 +614,155 @@ pub fn parse_gen_stub_is_abstract_class(attrs: &[Attribute]) -> Result<bool> {
         .any(|attr| matches!(attr, StubGenAttr::AbstractClass)))
 }
 
+pub fn parse_gen_stub_is_dataclass(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::Dataclass)))
+}
+
+pub fn parse_gen_stub_is_skipped_class(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs.iter().any(|attr| matches!(attr, StubGenAttr::Skip)))
+}
+
+pub fn parse_gen_stub_is_no_abc(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs.iter().any(|attr| matches!(attr, StubGenAttr::NoAbc)))
+}
+
+pub fn parse_gen_stub_is_flag_enum(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::FlagEnum)))
+}
+
+pub fn parse_gen_stub_is_total_ordering(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::TotalOrdering)))
+}
+
+pub fn parse_gen_stub_pickle_state(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)? {
+        if let StubGenAttr::PickleState(state) = attr {
+            return Ok(Some(state));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_is_slots(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs.iter().any(|attr| matches!(attr, StubGenAttr::Slots)))
+}
+
+pub fn parse_gen_stub_is_hidden_base(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::HiddenBase)))
+}
+
+/// Class level type overrides given by `type_input_override(...)`, `type_output_override(...)`,
+/// and whether the class is tagged by `custom_from_py_object`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassTypeOverride {
+    pub(crate) input: Option<OverrideTypeAttribute>,
+    pub(crate) output: Option<OverrideTypeAttribute>,
+    pub(crate) custom_from_py_object: bool,
+}
+
+pub fn parse_gen_stub_class_type_override(attrs: &[Attribute]) -> Result<ClassTypeOverride> {
+    let mut out = ClassTypeOverride::default();
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Class, None)? {
+        match attr {
+            StubGenAttr::TypeInputOverride(attr) => out.input = Some(attr),
+            StubGenAttr::TypeOutputOverride(attr) => out.output = Some(attr),
+            StubGenAttr::CustomFromPyObject => out.custom_from_py_object = true,
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+pub fn parse_gen_stub_overload_groups(attrs: &[Attribute]) -> Result<Vec<Vec<LitStr>>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
+        if let StubGenAttr::OverloadGroups(groups) = attr {
+            return Ok(groups);
+        }
+    }
+    Ok(Vec::new())
+}
+
+pub fn parse_gen_stub_getitem(attrs: &[Attribute]) -> Result<Option<GetItemOverloads>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
+        if let StubGenAttr::GetItem(overloads) = attr {
+            return Ok(Some(overloads));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse_gen_stub_also_as(attrs: &[Attribute]) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, Some(&["skip"]))? {
+        if let StubGenAttr::AlsoAs(aliases) = attr {
+            names.extend(aliases.iter().map(LitStr::value));
+        }
+    }
+    Ok(names)
+}
+
+pub fn parse_gen_stub_is_awaitable(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Function, Some(&["skip"]))?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::Awaitable)))
+}
+
+pub fn parse_gen_stub_is_no_return(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Function, Some(&["skip"]))?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::NoReturn)))
+}
+
+pub fn parse_gen_stub_deprecated_params(
+    attrs: &[Attribute],
+) -> Result<Vec<DeprecatedParamAttribute>> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Function, Some(&["skip"]))?;
+    Ok(attrs
+        .into_iter()
+        .filter_map(|attr| match attr {
+            StubGenAttr::DeprecatedParam(deprecated) => Some(deprecated),
+            _ => None,
+        })
+        .collect())
+}
+
+pub fn parse_gen_stub_keep_signature(attrs: &[Attribute]) -> Result<bool> {
+    let attrs = parse_gen_stub_attrs(attrs, AttributeLocation::Function, Some(&["skip"]))?;
+    Ok(attrs
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::KeepSignature)))
+}
+
+/// Cargo feature given by `#[gen_stub(feature = "...")]` on a class or a function
+pub fn parse_gen_stub_feature(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, location, Some(&["skip"]))? {
+        if let StubGenAttr::Feature(feature) = attr {
+            return Ok(Some(feature));
+        }
+    }
+    Ok(None)
+}
+
 pub fn parse_gen_stub_is_abstract_method(attrs: &[Attribute]) -> Result<bool> {
     let attrs = parse_gen_stub_attrs(
         attrs,
@@ -444,11 +804,19 @@ fn parse_gen_stub_attr(
                     || (ident == "override_return_type"
                         && (location == AttributeLocation::Function || location == AttributeLocation::Field || ignored_ident))
                 {
-                    let content;
-                    parenthesized!(content in input);
-                    let override_attr: OverrideTypeAttribute = content.parse()?;
+                    let override_attr = if input.peek(Token![=]) {
+                        // Shorthand form: `override_return_type = "...", imports = (...)`
+                        OverrideTypeAttribute::parse_shorthand(input)?
+                    } else {
+                        let content;
+                        parenthesized!(content in input);
+                        content.parse()?
+                    };
                     gen_stub_attrs.push(StubGenAttr::OverrideType(override_attr));
-                } else if ident == "skip" && (location == AttributeLocation::Field || ignored_ident)
+                } else if ident == "skip"
+                    && (location == AttributeLocation::Field
+                        || location == AttributeLocation::Class
+                        || ignored_ident)
                 {
                     gen_stub_attrs.push(StubGenAttr::Skip);
                 } else if ident == "allow" {
@@ -471,10 +839,15 @@ fn parse_gen_stub_attr(
                 } else if ident == "type_ignore"
                     && (location == AttributeLocation::Function || location == AttributeLocation::Field || ignored_ident)
                 {
-                    // Handle two cases:
+                    // Handle three cases:
                     // 1. type_ignore (without equals) -> IgnoreTarget::All
                     // 2. type_ignore = [...] -> IgnoreTarget::Specified(rules)
-                    if input.peek(Token![=]) {
+                    // 3. type_ignore = "rule" -> IgnoreTarget::Specified([rule])
+                    if input.peek(Token![=]) && input.peek2(LitStr) {
+                        input.parse::<Token![=]>()?;
+                        let rule: LitStr = input.parse()?;
+                        gen_stub_attrs.push(StubGenAttr::TypeIgnore(IgnoreTarget::SpecifiedLits(vec![rule])));
+                    } else if input.peek(Token![=]) {
                         input.parse::<Token![=]>()?;
                         // Parse array of rule names
                         let content;
@@ -504,6 +877,168 @@ fn parse_gen_stub_attr(
                     && (location == AttributeLocation::Function || ignored_ident)
                 {
                     gen_stub_attrs.push(StubGenAttr::AbstractMethod);
+                } else if ident == "awaitable"
+                    && (location == AttributeLocation::Function || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::Awaitable);
+                } else if ident == "no_return"
+                    && (location == AttributeLocation::Function || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::NoReturn);
+                } else if ident == "deprecated_param"
+                    && input.peek(syn::token::Paren)
+                    && (location == AttributeLocation::Function || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    gen_stub_attrs.push(StubGenAttr::DeprecatedParam(content.parse()?));
+                } else if ident == "overload_groups"
+                    && (location == AttributeLocation::Function || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    let groups = Punctuated::<_, Token![,]>::parse_terminated_with(
+                        &content,
+                        |input: ParseStream| {
+                            let group;
+                            parenthesized!(group in input);
+                            let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&group)?;
+                            Ok(names.into_iter().collect::<Vec<_>>())
+                        },
+                    )?;
+                    gen_stub_attrs.push(StubGenAttr::OverloadGroups(groups.into_iter().collect()));
+                } else if (ident == "type_input_override" || ident == "type_output_override")
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    let override_attr: OverrideTypeAttribute = content.parse()?;
+                    if ident == "type_input_override" {
+                        gen_stub_attrs.push(StubGenAttr::TypeInputOverride(override_attr));
+                    } else {
+                        gen_stub_attrs.push(StubGenAttr::TypeOutputOverride(override_attr));
+                    }
+                } else if ident == "custom_from_py_object"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::CustomFromPyObject);
+                } else if ident == "dataclass"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::Dataclass);
+                } else if ident == "hidden_base"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::HiddenBase);
+                } else if ident == "no_abc"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::NoAbc);
+                } else if ident == "flag_enum"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::FlagEnum);
+                } else if ident == "total_ordering"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::TotalOrdering);
+                } else if ident == "slots"
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::Slots);
+                } else if ident == "pickle_state"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Class || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::PickleState(input.parse()?));
+                } else if ident == "also_as"
+                    && (location == AttributeLocation::Function
+                        || location == AttributeLocation::Field
+                        || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    if names.is_empty() {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "`also_as(...)` requires at least one name",
+                        ));
+                    }
+                    gen_stub_attrs.push(StubGenAttr::AlsoAs(names.into_iter().collect()));
+                } else if ident == "class_of"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Argument
+                        || location == AttributeLocation::Function
+                        || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::ClassOf(input.parse()?));
+                } else if ident == "literal"
+                    && input.peek(syn::token::Paren)
+                    && (location == AttributeLocation::Argument
+                        || location == AttributeLocation::Function
+                        || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    let literal: LiteralAttribute = content.parse()?;
+                    if literal.members.is_empty() {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "`literal(...)` requires at least one value",
+                        ));
+                    }
+                    gen_stub_attrs.push(StubGenAttr::Literal(literal));
+                } else if ident == "callable"
+                    && input.peek(syn::token::Paren)
+                    && (location == AttributeLocation::Argument
+                        || location == AttributeLocation::Function
+                        || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    gen_stub_attrs.push(StubGenAttr::Callable(content.parse()?));
+                } else if ident == "getitem"
+                    && (location == AttributeLocation::Function || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    gen_stub_attrs.push(StubGenAttr::GetItem(content.parse()?));
+                } else if ident == "keep_signature"
+                    && (location == AttributeLocation::Function || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::KeepSignature);
+                } else if ident == "feature"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Class
+                        || location == AttributeLocation::Function
+                        || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::Feature(input.parse()?));
+                } else if ident == "default_repr"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Argument || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::DefaultRepr(input.parse()?));
+                } else if ident == "accepts"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Argument || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::Accepts(input.parse()?));
+                } else if ident == "container_default"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Argument || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    let value: LitStr = input.parse()?;
+                    gen_stub_attrs.push(StubGenAttr::ContainerDefault(ContainerDefault::parse(
+                        &value,
+                    )?));
                 } else if ident == "override_type" {
                     return Err(syn::Error::new(
                         ident.span(),
@@ -518,12 +1053,7 @@ fn parse_gen_stub_attr(
                 } else if ident == "skip" {
                     return Err(syn::Error::new(
                         ident.span(),
-                        "`skip` is only valid in field position".to_string(),
-                    ));
-                } else if ident == "allow" {
-                    return Err(syn::Error::new(
-                        ident.span(),
-                        "`allow` is only valid in field or function position".to_string(),
+                        "`skip` is only valid in field, method, or struct position".to_string(),
                     ));
                 } else if ident == "default" {
                     return Err(syn::Error::new(
@@ -546,28 +1076,107 @@ fn parse_gen_stub_attr(
                         ident.span(),
                         "`abstractmethod` is only valid in function or method position".to_string(),
                     ));
+                } else if ident == "awaitable" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`awaitable` is only valid in function or method position".to_string(),
+                    ));
+                } else if ident == "class_of" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`class_of = \"...\"` is only valid in argument or function position"
+                            .to_string(),
+                    ));
+                } else if ident == "literal" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`literal(...)` is only valid in argument or function position"
+                            .to_string(),
+                    ));
+                } else if ident == "callable" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`callable(...)` is only valid in argument or function position"
+                            .to_string(),
+                    ));
+                } else if ident == "default_repr" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`default_repr = \"...\"` is only valid in argument position".to_string(),
+                    ));
+                } else if ident == "accepts" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`accepts = \"...\"` is only valid in argument position".to_string(),
+                    ));
+                } else if ident == "container_default" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`container_default = \"...\"` is only valid in argument position"
+                            .to_string(),
+                    ));
+                } else if ident == "also_as" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`also_as(...)` is only valid in method or field position".to_string(),
+                    ));
+                } else if ident == "getitem" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`getitem(...)` is only valid in method position".to_string(),
+                    ));
+                } else if ident == "keep_signature" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`keep_signature` is only valid in method position".to_string(),
+                    ));
+                } else if ident == "feature" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`feature = \"...\"` is only valid for classes, enums and functions".to_string(),
+                    ));
+                } else if ident == "overload_groups" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`overload_groups(...)` is only valid in method position".to_string(),
+                    ));
+                } else if ident == "type_input_override"
+                    || ident == "type_output_override"
+                    || ident == "custom_from_py_object"
+                    || ident == "dataclass"
+                    || ident == "hidden_base"
+                    || ident == "no_abc"
+                    || ident == "flag_enum"
+                    || ident == "total_ordering"
+                    || ident == "slots"
+                    || ident == "pickle_state"
+                {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{ident}` is only valid in struct position"),
+                    ));
                 } else if location == AttributeLocation::Argument {
                     return Err(syn::Error::new(
                         ident.span(),
-                        format!("Unsupported keyword `{ident}`, valid is `override_type(...)`"),
+                        format!("Unsupported keyword `{ident}`, valid is `override_type(...)`, `class_of = \"...\"`, `literal(...)`, `callable(...)`, `accepts = \"...\"`, `default_repr = \"...\"`, or `container_default = \"...\"`"),
                     ));
                 } else if location == AttributeLocation::Field {
                     return Err(syn::Error::new(
                         ident.span(),
-                        format!("Unsupported keyword `{ident}`, valid is `default=xxx`, `skip`, `allow`, `override_return_type(...)`, `type_ignore`, or `type_ignore=[...]`"),
+                        format!("Unsupported keyword `{ident}`, valid is `default=xxx`, `skip`, `allow`, `override_return_type(...)`, `also_as(...)`, `type_ignore`, or `type_ignore=[...]`"),
                     ));
                 } else if location == AttributeLocation::Function {
                     return Err(syn::Error::new(
                         ident.span(),
                         format!(
-                            "Unsupported keyword `{ident}`, valid is `default=xxx`, `override_return_type(...)`, `type_ignore`, or `type_ignore=[...]`"
+                            "Unsupported keyword `{ident}`, valid is `default=xxx`, `override_return_type(...)`, `no_return`, `deprecated_param(...)`, `literal(...)`, `callable(...)`, `type_ignore`, or `type_ignore=[...]`"
                         ),
                     ));
                 } else if location == AttributeLocation::Class {
                     return Err(syn::Error::new(
                         ident.span(),
                         format!(
-                            "Unsupported keyword `{ident}`, valid is `abstract` or `abstract_class`"
+                            "Unsupported keyword `{ident}`, valid is `abstract`, `abstract_class`, `type_input_override(...)`, `type_output_override(...)`, `custom_from_py_object`, `dataclass`, `hidden_base`, `no_abc`, `flag_enum`, `total_ordering`, `slots`, `pickle_state = \"...\"`, or `skip`"
                         ),
                     ));
                 } else {
@@ -608,6 +1217,39 @@ mod kw {
     syn::custom_keyword!(override_type);
 }
 
+impl OverrideTypeAttribute {
+    /// Expression constructing the corresponding `TypeInfo`
+    pub(crate) fn to_type_info(&self) -> TokenStream2 {
+        let type_repr = &self.type_repr;
+        let imports = self.imports.iter();
+        quote! {
+            ::pyo3_stub_gen::TypeInfo {
+                name: #type_repr.to_string(),
+                import: ::std::collections::HashSet::from([#(#imports.into(),)*]),
+            }
+        }
+    }
+}
+
+impl OverrideTypeAttribute {
+    /// Parse `= "type_repr"` optionally followed by `, imports = (...)`
+    fn parse_shorthand(input: ParseStream) -> Result<Self> {
+        input.parse::<Token![=]>()?;
+        let type_repr = input.parse::<LitStr>()?.value();
+        let mut imports = IndexSet::new();
+        if input.peek(Token![,]) && input.peek2(kw::imports) {
+            input.parse::<Token![,]>()?;
+            input.parse::<kw::imports>()?;
+            input.parse::<Token![=]>()?;
+            let content;
+            parenthesized!(content in input);
+            let parsed_imports = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+            imports = parsed_imports.iter().map(|i| i.value()).collect();
+        }
+        Ok(Self { type_repr, imports })
+    }
+}
+
 impl Parse for OverrideTypeAttribute {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut type_repr = None;
@@ -646,6 +1288,232 @@ impl Parse for OverrideTypeAttribute {
     }
 }
 
+/// Values of `#[gen_stub(literal(...))]`, e.g. `literal("r", "w", "a")` or `literal(1, 2, -1)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiteralAttribute {
+    /// Python representations of the values
+    pub(crate) members: Vec<String>,
+}
+
+impl LiteralAttribute {
+    /// `typing.Literal[...]` of the values
+    pub(crate) fn type_repr(&self) -> String {
+        format!("typing.Literal[{}]", self.members.join(", "))
+    }
+}
+
+impl Parse for LiteralAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut members = Vec::new();
+        while !input.is_empty() {
+            let negative = input.parse::<Option<Token![-]>>()?.is_some();
+            let member = match input.parse::<Lit>()? {
+                Lit::Int(int) => {
+                    let sign = if negative { "-" } else { "" };
+                    format!("{sign}{}", int.base10_digits())
+                }
+                Lit::Str(s) if !negative => python_str_repr(&s.value()),
+                Lit::Bool(b) if !negative => if b.value { "True" } else { "False" }.to_string(),
+                lit => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "`literal(...)` accepts string, integer, or boolean literals",
+                    ))
+                }
+            };
+            members.push(member);
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self { members })
+    }
+}
+
+/// Signature of `#[gen_stub(callable(args = ("int", "str"), returns = "bool"))]`,
+/// where `args = ...` stands for any arguments, and `imports = (...)` lists the modules the types refer to
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallableAttribute {
+    /// Python types of the arguments, or `None` for any arguments
+    pub(crate) args: Option<Vec<String>>,
+    pub(crate) returns: String,
+    pub(crate) imports: IndexSet<String>,
+}
+
+impl CallableAttribute {
+    /// `collections.abc.Callable[...]` of the signature
+    pub(crate) fn type_repr(&self) -> String {
+        let args = match &self.args {
+            Some(args) => format!("[{}]", args.join(", ")),
+            None => "...".to_string(),
+        };
+        format!("collections.abc.Callable[{args}, {}]", self.returns)
+    }
+}
+
+impl Parse for CallableAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = None;
+        let mut returns = None;
+        let mut imports = IndexSet::new();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if key == "args" {
+                args = Some(if input.parse::<Option<Token![...]>>()?.is_some() {
+                    None
+                } else {
+                    let content;
+                    parenthesized!(content in input);
+                    let types = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    Some(types.iter().map(LitStr::value).collect())
+                });
+            } else if key == "returns" {
+                returns = Some(input.parse::<LitStr>()?.value());
+            } else if key == "imports" {
+                let content;
+                parenthesized!(content in input);
+                let modules = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                imports = modules.iter().map(LitStr::value).collect();
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("Unknown key `{key}` of `callable(...)`, valid is `args`, `returns`, or `imports`"),
+                ));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let Some(args) = args else {
+            return Err(syn::Error::new(
+                input.span(),
+                "`callable(...)` requires `args = (...)` or `args = ...`",
+            ));
+        };
+        Ok(Self {
+            args,
+            returns: returns.unwrap_or_else(|| "None".to_string()),
+            imports,
+        })
+    }
+}
+
+/// Parameter of `#[gen_stub(deprecated_param(name = "old_size", note = "use size"))]`, where `note` is optional
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeprecatedParamAttribute {
+    pub(crate) name: LitStr,
+    pub(crate) note: Option<String>,
+}
+
+impl Parse for DeprecatedParamAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut name = None;
+        let mut note = None;
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if key == "name" {
+                name = Some(input.parse::<LitStr>()?);
+            } else if key == "note" {
+                note = Some(input.parse::<LitStr>()?.value());
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "Unknown key `{key}` of `deprecated_param(...)`, valid is `name` or `note`"
+                    ),
+                ));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let Some(name) = name else {
+            return Err(syn::Error::new(
+                input.span(),
+                "`deprecated_param(...)` requires `name = \"...\"`",
+            ));
+        };
+        Ok(Self { name, note })
+    }
+}
+
+/// Return types of `#[gen_stub(getitem(int -> "Item", slice -> "Self"))]`, given as Rust types
+/// as `class_of = "..."`, where `"Self"` is rendered as `typing.Self`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetItemOverloads {
+    pub(crate) int: LitStr,
+    pub(crate) slice: LitStr,
+}
+
+impl Parse for GetItemOverloads {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (mut int, mut slice) = (None, None);
+        let pairs = Punctuated::<_, Token![,]>::parse_terminated_with(input, |input| {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![->]>()?;
+            let ty: LitStr = input.parse()?;
+            Ok((key, ty))
+        })?;
+        for (key, ty) in pairs {
+            let slot = if key == "int" {
+                &mut int
+            } else if key == "slice" {
+                &mut slice
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("Unknown key `{key}` of `getitem(...)`, valid is `int` or `slice`"),
+                ));
+            };
+            if slot.replace(ty).is_some() {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("`{key}` is given more than once in `getitem(...)`"),
+                ));
+            }
+        }
+        match (int, slice) {
+            (Some(int), Some(slice)) => Ok(Self { int, slice }),
+            _ => Err(syn::Error::new(
+                input.span(),
+                "`getitem(...)` requires both `int -> \"...\"` and `slice -> \"...\"`",
+            )),
+        }
+    }
+}
+
+/// `repr()` of a Python `str`, i.e. single-quoted unless the string contains only double quotes
+pub(crate) fn python_str_repr(value: &str) -> String {
+    let quote = if value.contains('\'') && !value.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    let mut repr = String::with_capacity(value.len() + 2);
+    repr.push(quote);
+    for c in value.chars() {
+        match c {
+            '\\' => repr.push_str("\\\\"),
+            '\n' => repr.push_str("\\n"),
+            '\r' => repr.push_str("\\r"),
+            '\t' => repr.push_str("\\t"),
+            c if c == quote => {
+                repr.push('\\');
+                repr.push(c);
+            }
+            c if c.is_control() => repr.push_str(&format!("\\x{:02x}", c as u32)),
+            c => repr.push(c),
+        }
+    }
+    repr.push(quote);
+    repr
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -754,6 +1622,94 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_gen_stub_literal_attr() -> Result<()> {
+        let literal: LiteralAttribute = parse_str(r#""a\\b\n", -3, true"#)?;
+        assert_eq!(literal.members, [r"'a\\b\n'", "-3", "True"]);
+        assert_eq!(literal.type_repr(), r"typing.Literal['a\\b\n', -3, True]");
+        for (input, repr) in [(r#""it's""#, r#""it's""#), (r#""\"'\"""#, r#"'"\'"'"#)] {
+            let literal: LiteralAttribute = parse_str(input)?;
+            assert_eq!(literal.members, [repr]);
+        }
+        let err = parse_str::<LiteralAttribute>("1.5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`literal(...)` accepts string, integer, or boolean literals"
+        );
+
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub(literal())]
+            fn open() {}
+            "#,
+        )?;
+        let err = parse_gen_stub_attrs(&item.attrs, AttributeLocation::Function, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`literal(...)` requires at least one value"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_callable_attr() -> Result<()> {
+        let callable: CallableAttribute = parse_str(
+            r#"args = ("builtins.int", "typing.Any"), returns = "builtins.bool", imports = ("builtins", "typing")"#,
+        )?;
+        assert_eq!(
+            callable.type_repr(),
+            "collections.abc.Callable[[builtins.int, typing.Any], builtins.bool]"
+        );
+        assert_eq!(
+            callable.imports,
+            IndexSet::from(["builtins".to_string(), "typing".to_string()])
+        );
+        let callable: CallableAttribute = parse_str("args = ...")?;
+        assert_eq!(callable.type_repr(), "collections.abc.Callable[..., None]");
+        let callable: CallableAttribute = parse_str("args = ()")?;
+        assert_eq!(callable.type_repr(), "collections.abc.Callable[[], None]");
+
+        for (input, message) in [
+            (
+                r#"returns = "int""#,
+                "`callable(...)` requires `args = (...)` or `args = ...`",
+            ),
+            (
+                "params = ()",
+                "Unknown key `params` of `callable(...)`, valid is `args`, `returns`, or `imports`",
+            ),
+        ] {
+            let err = parse_str::<CallableAttribute>(input).unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_getitem_attr() -> Result<()> {
+        let overloads: GetItemOverloads = parse_str(r#"slice -> "Self", int -> "Item""#)?;
+        assert_eq!(overloads.int.value(), "Item");
+        assert_eq!(overloads.slice.value(), "Self");
+        for (input, message) in [
+            (
+                r#"int -> "Item""#,
+                r#"`getitem(...)` requires both `int -> "..."` and `slice -> "..."`"#,
+            ),
+            (
+                r#"int -> "Item", int -> "Self""#,
+                "`int` is given more than once in `getitem(...)`",
+            ),
+            (
+                r#"index -> "Item""#,
+                "Unknown key `index` of `getitem(...)`, valid is `int` or `slice`",
+            ),
+        ] {
+            let err = parse_str::<GetItemOverloads>(input).unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+        Ok(())
+    }
+
     #[test]
     fn allow_attribute_on_function_is_recognized() -> Result<()> {
         let item_fn: ItemFn = parse_str(
@@ -813,4 +1769,69 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_parse_gen_stub_override_return_type_shorthand() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub_pyfunction]
+            #[pyfunction]
+            #[deprecated(note = "use other")]
+            #[gen_stub(override_return_type = "pandas.DataFrame", imports = ("pandas",), type_ignore)]
+            fn frame<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+                todo!()
+            }
+            "#,
+        )?;
+        let fn_attrs = parse_gen_stub_attrs(&item.attrs, AttributeLocation::Function, None)?;
+        assert_eq!(
+            fn_attrs,
+            vec![
+                StubGenAttr::OverrideType(OverrideTypeAttribute {
+                    type_repr: "pandas.DataFrame".into(),
+                    imports: IndexSet::from(["pandas".into()])
+                }),
+                StubGenAttr::TypeIgnore(IgnoreTarget::All),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_container_default() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub_pyfunction]
+            #[pyfunction]
+            #[pyo3(signature = (tags = vec![]))]
+            fn tag(#[gen_stub(container_default = "ellipsis")] tags: Vec<String>) {}
+            "#,
+        )?;
+        let syn::FnArg::Typed(PatType { attrs, .. }) = &item.sig.inputs[0] else {
+            panic!("argument should be typed");
+        };
+        assert_eq!(
+            parse_gen_stub_container_default(attrs)?,
+            Some(ContainerDefault::Ellipsis)
+        );
+
+        let unknown: ItemFn = parse_str(
+            r#"
+            fn tag(#[gen_stub(container_default = "none")] tags: Vec<String>) {}
+            "#,
+        )?;
+        let syn::FnArg::Typed(PatType { attrs, .. }) = &unknown.sig.inputs[0] else {
+            panic!("argument should be typed");
+        };
+        assert!(parse_gen_stub_container_default(attrs).is_err());
+
+        let function: ItemFn = parse_str(
+            r#"
+            #[gen_stub(container_default = "literal")]
+            fn tag(tags: Vec<String>) {}
+            "#,
+        )?;
+        assert!(parse_gen_stub_attrs(&function.attrs, AttributeLocation::Function, None).is_err());
+        Ok(())
+    }
 }
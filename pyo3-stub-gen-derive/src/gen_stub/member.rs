@@ -1,18 +1,23 @@
 use crate::gen_stub::{
     attr::{
-        parse_gen_stub_default, parse_gen_stub_is_abstract_method, parse_gen_stub_override_type,
+        parse_gen_stub_also_as, parse_gen_stub_callable, parse_gen_stub_default,
+        parse_gen_stub_is_abstract_method, parse_gen_stub_literal, parse_gen_stub_override_type,
+        parse_gen_stub_type_ignore, validate_gen_stub_field_attrs, IgnoreTarget,
         OverrideTypeAttribute,
     },
     extract_documents,
-    util::TypeOrOverride,
+    util::{callable_type, literal_type, quote_option, TypeOrOverride},
 };
 
-use super::{extract_return_type, parse_pyo3_attrs, Attr};
+use super::{extract_return_type, parse_pyo3_attrs, Attr, RenamingRule};
 
 use crate::gen_stub::arg::ArgInfo;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{Attribute, Error, Expr, Field, FnArg, ImplItemConst, ImplItemFn, Result};
+use syn::{
+    ext::IdentExt, Attribute, Block, Error, Expr, ExprLit, ExprUnary, Field, FnArg, ImplItemConst,
+    ImplItemFn, Lit, Result, Stmt, UnOp,
+};
 
 #[derive(Debug, Clone)]
 pub struct MemberInfo {
@@ -23,12 +28,26 @@ pub struct MemberInfo {
     deprecated: Option<crate::gen_stub::attr::DeprecatedInfo>,
     item: bool,
     is_abstract: bool,
+    also_as: Vec<String>,
+    is_classattr: bool,
+    /// The type is of the value assigned by the setter, i.e. the input type
+    is_setter: bool,
+    /// Integer literal wrapped by the value of a `#[classattr]`
+    int_value: Option<String>,
+    type_ignored: Option<IgnoreTarget>,
 }
 
 impl MemberInfo {
     pub fn is_item(&self) -> bool {
         self.item
     }
+    /// Use the member as the setter of a field, which takes the input type of the field
+    pub fn into_setter(self) -> Self {
+        Self {
+            is_setter: true,
+            ..self
+        }
+    }
     pub fn is_getter(attrs: &[Attribute]) -> Result<bool> {
         let attrs = parse_pyo3_attrs(attrs)?;
         Ok(attrs.iter().any(|attr| matches!(attr, Attr::Getter(_))))
@@ -63,23 +82,31 @@ impl MemberInfo {
         let default = parse_gen_stub_default(attrs)?;
         let doc = extract_documents(attrs).join("\n");
         let is_abstract = parse_gen_stub_is_abstract_method(attrs)?;
+        let also_as = parse_gen_stub_also_as(attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(attrs)?;
         let pyo3_attrs = parse_pyo3_attrs(attrs)?;
+        let pyo3_name = explicit_name(&pyo3_attrs);
         for attr in pyo3_attrs {
             if let Attr::Getter(name) = attr {
-                let fn_name = sig.ident.to_string();
+                let fn_name = sig.ident.unraw().to_string();
                 let fn_getter_name = match fn_name.strip_prefix("get_") {
                     Some(s) => s.to_owned(),
                     None => fn_name,
                 };
                 return Ok(MemberInfo {
                     doc,
-                    name: name.unwrap_or(fn_getter_name),
+                    name: name.or(pyo3_name).unwrap_or(fn_getter_name),
                     r#type: extract_return_type(&sig.output, attrs)?
                         .expect("Getter must return a type"),
                     default,
                     deprecated: crate::gen_stub::attr::extract_deprecated(attrs),
                     item: false,
                     is_abstract,
+                    also_as,
+                    is_classattr: false,
+                    is_setter: false,
+                    int_value: None,
+                    type_ignored,
                 });
             }
         }
@@ -91,10 +118,13 @@ impl MemberInfo {
         let default = parse_gen_stub_default(attrs)?;
         let doc = extract_documents(attrs).join("\n");
         let is_abstract = parse_gen_stub_is_abstract_method(attrs)?;
+        let also_as = parse_gen_stub_also_as(attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(attrs)?;
         let pyo3_attrs = parse_pyo3_attrs(attrs)?;
+        let pyo3_name = explicit_name(&pyo3_attrs);
         for attr in pyo3_attrs {
             if let Attr::Setter(name) = attr {
-                let fn_name = sig.ident.to_string();
+                let fn_name = sig.ident.unraw().to_string();
                 let fn_setter_name = match fn_name.strip_prefix("set_") {
                     Some(s) => s.to_owned(),
                     None => fn_name,
@@ -105,6 +135,12 @@ impl MemberInfo {
                     .ok_or(syn::Error::new_spanned(&item, "Setter must input a type"))
                     .and_then(|arg| {
                         if let FnArg::Typed(t) = arg {
+                            if let Some(literal) = parse_gen_stub_literal(&t.attrs)? {
+                                return Ok(literal_type(&t.ty, &literal));
+                            }
+                            if let Some(callable) = parse_gen_stub_callable(&t.attrs)? {
+                                return Ok(callable_type(&t.ty, &callable));
+                            }
                             Ok(match parse_gen_stub_override_type(&t.attrs)? {
                                 Some(OverrideTypeAttribute { type_repr, imports }) => {
                                     TypeOrOverride::OverrideType {
@@ -123,12 +159,17 @@ impl MemberInfo {
                     })?;
                 return Ok(MemberInfo {
                     doc,
-                    name: name.unwrap_or(fn_setter_name),
+                    name: name.or(pyo3_name).unwrap_or(fn_setter_name),
                     r#type,
                     default,
                     deprecated: crate::gen_stub::attr::extract_deprecated(attrs),
                     item: false,
                     is_abstract,
+                    also_as,
+                    is_classattr: false,
+                    is_setter: true,
+                    int_value: None,
+                    type_ignored,
                 });
             }
         }
@@ -136,10 +177,19 @@ impl MemberInfo {
     }
     pub fn new_classattr_fn(item: ImplItemFn) -> Result<Self> {
         assert!(Self::is_classattr(&item.attrs)?);
-        let ImplItemFn { attrs, sig, .. } = &item;
-        let default = parse_gen_stub_default(attrs)?;
+        let ImplItemFn {
+            attrs, sig, block, ..
+        } = &item;
+        let default = match parse_gen_stub_default(attrs)? {
+            Some(default) => Some(default),
+            None => literal_body(block),
+        };
         let doc = extract_documents(attrs).join("\n");
-        let mut name = sig.ident.to_string();
+        let int_value = match block.stmts.as_slice() {
+            [Stmt::Expr(expr, None)] => wrapped_int(expr),
+            _ => None,
+        };
+        let mut name = sig.ident.unraw().to_string();
         for attr in parse_pyo3_attrs(attrs)? {
             if let Attr::Name(_name) = attr {
                 name = _name;
@@ -153,6 +203,11 @@ impl MemberInfo {
             deprecated: crate::gen_stub::attr::extract_deprecated(attrs),
             item: false,
             is_abstract: false,
+            also_as: Vec::new(),
+            is_classattr: true,
+            is_setter: false,
+            int_value,
+            type_ignored: None,
         })
     }
     pub fn new_classattr_const(item: ImplItemConst) -> Result<Self> {
@@ -165,7 +220,7 @@ impl MemberInfo {
             ..
         } = item;
         let doc = extract_documents(&attrs).join("\n");
-        let mut name = ident.to_string();
+        let mut name = ident.unraw().to_string();
         for attr in parse_pyo3_attrs(&attrs)? {
             if let Attr::Name(_name) = attr {
                 name = _name;
@@ -175,17 +230,81 @@ impl MemberInfo {
             doc,
             name,
             r#type: TypeOrOverride::RustType { r#type: ty },
+            int_value: wrapped_int(&expr),
             default: Some(expr),
             deprecated: crate::gen_stub::attr::extract_deprecated(&attrs),
             item: false,
             is_abstract: false,
+            also_as: Vec::new(),
+            is_classattr: true,
+            is_setter: false,
+            type_ignored: None,
         })
     }
 }
 
+/// Integer literal of `expr` possibly wrapped by a constructor,
+/// e.g. `1` of `1`, `Flags(1)`, `Self::from_bits_retain(1)`, or `Self { bits: 1 }`
+fn wrapped_int(expr: &Expr) -> Option<String> {
+    let inner = match expr {
+        Expr::Call(call) if call.args.len() == 1 => &call.args[0],
+        Expr::Struct(init) if init.fields.len() == 1 && init.rest.is_none() => &init.fields[0].expr,
+        expr => expr,
+    };
+    match inner {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => Some(int.base10_digits().to_string()),
+        _ => None,
+    }
+}
+
+/// Name given by `#[pyo3(name = "...")]`
+fn explicit_name(attrs: &[Attr]) -> Option<String> {
+    attrs.iter().find_map(|attr| match attr {
+        Attr::Name(name) => Some(name.clone()),
+        _ => None,
+    })
+}
+
+/// The body of a `#[classattr]` function if it is a literal, e.g. `"1.2.3"` or `-1`,
+/// whose value is rendered in the stub file.
+fn literal_body(block: &Block) -> Option<Expr> {
+    let [Stmt::Expr(expr, None)] = block.stmts.as_slice() else {
+        return None;
+    };
+    let lit = match expr {
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr: operand,
+            ..
+        }) => match operand.as_ref() {
+            Expr::Lit(ExprLit {
+                lit: lit @ (Lit::Int(_) | Lit::Float(_)),
+                ..
+            }) => lit,
+            _ => return None,
+        },
+        Expr::Lit(ExprLit { lit, .. }) => lit,
+        _ => return None,
+    };
+    matches!(
+        lit,
+        Lit::Str(_) | Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) | Lit::Char(_)
+    )
+    .then(|| expr.clone())
+}
+
 impl TryFrom<Field> for MemberInfo {
     type Error = Error;
     fn try_from(field: Field) -> Result<Self> {
+        Self::from_field(field, &None)
+    }
+}
+
+impl MemberInfo {
+    /// Property of a field, named by `#[pyo3(name = "...")]`, or by the `rename_all` rule of the class
+    pub fn from_field(field: Field, renaming_rule: &Option<RenamingRule>) -> Result<Self> {
         let Field {
             ident, ty, attrs, ..
         } = field;
@@ -198,17 +317,31 @@ impl TryFrom<Field> for MemberInfo {
                 _ => {}
             }
         }
+        validate_gen_stub_field_attrs(&attrs)?;
         let doc = extract_documents(&attrs).join("\n");
         let default = parse_gen_stub_default(&attrs)?;
         let deprecated = crate::gen_stub::attr::extract_deprecated(&attrs);
+        let also_as = parse_gen_stub_also_as(&attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(&attrs)?;
         Ok(Self {
-            name: field_name.unwrap_or(ident.unwrap().to_string()),
+            name: field_name.unwrap_or_else(|| {
+                let name = ident.unwrap().unraw().to_string();
+                match renaming_rule {
+                    Some(rule) => rule.apply(&name),
+                    None => name,
+                }
+            }),
             r#type: TypeOrOverride::RustType { r#type: ty },
             doc,
             default,
             deprecated,
             item: is_item,
             is_abstract: false,
+            also_as,
+            is_classattr: false,
+            is_setter: false,
+            int_value: None,
+            type_ignored,
         })
     }
 }
@@ -223,6 +356,11 @@ impl ToTokens for MemberInfo {
             deprecated,
             item,
             is_abstract,
+            also_as,
+            is_classattr,
+            is_setter,
+            int_value,
+            type_ignored,
         } = self;
         let default = default
             .as_ref()
@@ -232,11 +370,15 @@ impl ToTokens for MemberInfo {
                         "None".to_string()
                     }
                 } else {
-                    let (TypeOrOverride::RustType { r#type: ty }
-                    | TypeOrOverride::OverrideType { r#type: ty, .. }) = r#type;
-                    quote! {
-                    let v: #ty = #value;
-                    ::pyo3_stub_gen::util::fmt_py_obj(v)
+                    match r#type {
+                        TypeOrOverride::RustType { r#type: ty } => quote! {
+                            let v: #ty = #value;
+                            ::pyo3_stub_gen::util::fmt_default(v)
+                        },
+                        TypeOrOverride::OverrideType { r#type: ty, .. } => quote! {
+                            let v: #ty = #value;
+                            ::pyo3_stub_gen::util::fmt_py_obj(v)
+                        },
                     }
                 }
             })
@@ -248,6 +390,8 @@ impl ToTokens for MemberInfo {
                     _fmt
                 })}
             });
+        let int_value = quote_option(int_value);
+        let type_ignored = quote_option(type_ignored);
         let deprecated_info = deprecated
             .as_ref()
             .map(|deprecated| {
@@ -260,17 +404,28 @@ impl ToTokens for MemberInfo {
             })
             .unwrap_or_else(|| quote! { None });
         match r#type {
-            TypeOrOverride::RustType { r#type: ty } => tokens.append_all(quote! {
+            TypeOrOverride::RustType { r#type: ty } => {
+                let type_fn = if *is_setter {
+                    quote! { type_input }
+                } else {
+                    quote! { type_output }
+                };
+                tokens.append_all(quote! {
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: #name,
-                    r#type: <#ty as ::pyo3_stub_gen::PyStubType>::type_output,
+                    r#type: <#ty as ::pyo3_stub_gen::PyStubType>::#type_fn,
                     doc: #doc,
                     default: #default,
                     deprecated: #deprecated_info,
                     item: #item,
                     is_abstract: #is_abstract,
+                    also_as: &[#(#also_as),*],
+                    is_classattr: #is_classattr,
+                    int_value: #int_value,
+                    type_ignored: #type_ignored,
                 }
-            }),
+                })
+            }
             TypeOrOverride::OverrideType {
                 type_repr, imports, ..
             } => {
@@ -284,6 +439,10 @@ impl ToTokens for MemberInfo {
                         deprecated: #deprecated_info,
                         item: #item,
                         is_abstract: #is_abstract,
+                        also_as: &[#(#also_as),*],
+                        is_classattr: #is_classattr,
+                        int_value: #int_value,
+                        type_ignored: #type_ignored,
                     }
                 })
             }
@@ -295,7 +454,12 @@ impl From<MemberInfo> for ArgInfo {
     fn from(value: MemberInfo) -> Self {
         let MemberInfo { name, r#type, .. } = value;
 
-        Self { name, r#type }
+        Self {
+            name,
+            r#type,
+            container_default: None,
+            default_repr: None,
+        }
     }
 }
 
@@ -331,4 +495,238 @@ mod tests {
         assert!(info.is_abstract);
         Ok(())
     }
+
+    #[test]
+    fn deprecated_getter_with_type_ignore() -> Result<()> {
+        let item: ImplItemFn = parse_quote! {
+            #[deprecated(since = "1.0", note = "use fd")]
+            #[gen_stub(type_ignore = "override")]
+            #[getter]
+            fn handle(&self) -> i32 {
+                self.fd
+            }
+        };
+        let out = MemberInfo::new_getter(item)?.to_token_stream().to_string();
+        assert!(
+            out.contains(r#"type_ignored : Some (:: pyo3_stub_gen :: type_info :: IgnoreTarget :: Specified (& ["override"] as & [& str]))"#),
+            "{out}"
+        );
+        assert!(out.contains(r#"since : Some ("1.0")"#), "{out}");
+
+        let item: ImplItemFn = parse_quote! {
+            #[gen_stub(type_ignore)]
+            #[setter]
+            fn set_handle(&mut self, fd: i32) {
+                self.fd = fd;
+            }
+        };
+        let info = MemberInfo::new_setter(item)?;
+        assert_eq!(info.type_ignored, Some(IgnoreTarget::All));
+        Ok(())
+    }
+
+    #[test]
+    fn field_rejects_method_keywords() {
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(literal("a", "b"))]
+            mode: String
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "`literal(...)` is only valid in argument or function position"
+        );
+
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(class_of = "Node")]
+            kind: Py<PyType>
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "`class_of = \"...\"` is only valid in argument or function position"
+        );
+
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(overload_groups(("a")))]
+            mode: String
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "`overload_groups(...)` is only valid in method position"
+        );
+
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(awaitable)]
+            mode: String
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "`awaitable` is only valid in function or method position"
+        );
+
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(getitem(int -> "u8", slice -> "Vec<u8>"))]
+            mode: String
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(error, "`getitem(...)` is only valid in method position");
+
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(keep_signature)]
+            mode: String
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(error, "`keep_signature` is only valid in method position");
+
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            #[gen_stub(callable(args = ()))]
+            mode: String
+        };
+        let error = MemberInfo::try_from(field).err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "`callable(...)` is only valid in argument or function position"
+        );
+    }
+
+    #[test]
+    fn method_keywords_pass_member_checks() -> Result<()> {
+        let item: ImplItemFn = parse_quote! {
+            #[gen_stub(literal("a", "b"))]
+            #[gen_stub(class_of = "Node")]
+            #[gen_stub(overload_groups(("a")))]
+            #[gen_stub(awaitable)]
+            #[gen_stub(getitem(int -> "u8", slice -> "Vec<u8>"))]
+            #[gen_stub(keep_signature)]
+            #[gen_stub(callable(args = ()))]
+            fn mode(&self) -> String {
+                self.mode.clone()
+            }
+        };
+        assert!(!crate::gen_stub::attr::parse_gen_stub_skip(&item.attrs)?);
+        assert!(!crate::gen_stub::attr::parse_gen_stub_allow(&item.attrs)?);
+        assert_eq!(parse_gen_stub_type_ignore(&item.attrs)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn classattr_int_values() -> Result<()> {
+        let int_value = |item: syn::ImplItem| -> Result<Option<String>> {
+            let info = match item {
+                syn::ImplItem::Const(item) => MemberInfo::new_classattr_const(item)?,
+                syn::ImplItem::Fn(item) => MemberInfo::new_classattr_fn(item)?,
+                _ => unreachable!(),
+            };
+            Ok(info.int_value)
+        };
+        let value = |s: &str| Some(s.to_string());
+        assert_eq!(
+            int_value(parse_quote! { #[classattr] const READ: Flags = Flags(0b01); })?,
+            value("1")
+        );
+        assert_eq!(
+            int_value(parse_quote! { #[classattr] const WRITE: Flags = Self { bits: 2u32 }; })?,
+            value("2")
+        );
+        assert_eq!(
+            int_value(
+                parse_quote! { #[classattr] fn EXEC() -> Flags { Flags::from_bits_retain(0x4) } }
+            )?,
+            value("4")
+        );
+        assert_eq!(
+            int_value(parse_quote! { #[classattr] const ALL: Flags = Flags(Inner::all()); })?,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn raw_identifiers() -> Result<()> {
+        let getter: ImplItemFn = parse_quote! {
+            #[getter]
+            fn r#type(&self) -> String {
+                unimplemented!()
+            }
+        };
+        assert_eq!(MemberInfo::new_getter(getter)?.name, "type");
+        let setter: ImplItemFn = parse_quote! {
+            #[setter]
+            fn r#type(&mut self, value: String) {
+                let _ = value;
+            }
+        };
+        assert_eq!(MemberInfo::new_setter(setter)?.name, "type");
+        let field: Field = parse_quote! {
+            #[pyo3(get)]
+            pub r#type: String
+        };
+        assert_eq!(MemberInfo::try_from(field)?.name, "type");
+        Ok(())
+    }
+
+    #[test]
+    fn getter_with_gen_stub_also_as() -> Result<()> {
+        let item: ImplItemFn = parse_quote! {
+            #[gen_stub(also_as("legacy_value"))]
+            #[getter]
+            fn value(&self) -> i32 {
+                0
+            }
+        };
+        let info = MemberInfo::new_getter(item)?;
+        assert_eq!(info.also_as, ["legacy_value"]);
+        Ok(())
+    }
+
+    #[test]
+    fn classattr_fn_with_literal_body_has_value() -> Result<()> {
+        for (item, value) in [
+            (
+                parse_quote! {
+                    #[classattr]
+                    fn VERSION() -> &'static str {
+                        "1.2.3"
+                    }
+                },
+                Some(r#""1.2.3""#),
+            ),
+            (
+                parse_quote! {
+                    #[classattr]
+                    fn OFFSET() -> i32 {
+                        -1
+                    }
+                },
+                Some("- 1"),
+            ),
+            (
+                parse_quote! {
+                    #[classattr]
+                    fn NAMES() -> Vec<&'static str> {
+                        vec!["a", "b"]
+                    }
+                },
+                None,
+            ),
+        ] {
+            let info = MemberInfo::new_classattr_fn(item)?;
+            assert!(info.is_classattr);
+            assert_eq!(
+                info.default.map(|expr| expr.to_token_stream().to_string()),
+                value.map(ToString::to_string)
+            );
+        }
+        Ok(())
+    }
 }
@@ -1,15 +1,22 @@
-use crate::gen_stub::util::TypeOrOverride;
+use crate::gen_stub::util::{quote_option, source_location, TypeOrOverride};
 
 use super::{
-    arg::parse_args, attr::IgnoreTarget, extract_deprecated, extract_documents,
-    extract_return_type, parameter::Parameters, parse_gen_stub_is_abstract_method,
-    parse_gen_stub_type_ignore, parse_pyo3_attrs, ArgInfo, Attr, DeprecatedInfo, Signature,
+    arg::parse_args,
+    attr::{GetItemOverloads, IgnoreTarget},
+    extract_deprecated, extract_documents, extract_return_type,
+    parameter::{is_none_expr, DefaultExpr, ParameterKind, ParameterWithKind, Parameters},
+    parse_gen_stub_also_as, parse_gen_stub_deprecated_params, parse_gen_stub_feature,
+    parse_gen_stub_is_abstract_method, parse_gen_stub_is_awaitable, parse_gen_stub_keep_signature,
+    parse_gen_stub_type_ignore, parse_pyo3_attrs, ArgInfo, Attr, AttributeLocation, DeprecatedInfo,
+    Signature,
 };
 
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens, TokenStreamExt};
+use std::collections::BTreeSet;
 use syn::{
-    Error, GenericArgument, ImplItemFn, PathArguments, Result, Type, TypePath, TypeReference,
+    ext::IdentExt, Error, GenericArgument, ImplItemFn, LitStr, PathArguments, Result, Type,
+    TypePath, TypeReference,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,7 +27,7 @@ pub enum MethodType {
     New,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MethodInfo {
     pub(super) name: String,
     pub(super) parameters: Parameters,
@@ -28,9 +35,16 @@ pub struct MethodInfo {
     pub(super) doc: String,
     pub(super) r#type: MethodType,
     pub(super) is_async: bool,
+    /// Rendered by `def` returning `collections.abc.Awaitable[...]`, by `#[gen_stub(awaitable)]`
+    pub(super) is_awaitable: bool,
     pub(super) deprecated: Option<DeprecatedInfo>,
     pub(super) type_ignored: Option<IgnoreTarget>,
     pub(super) is_abstract: bool,
+    pub(super) also_as: Vec<String>,
+    /// Set by `#[gen_stub(keep_signature)]`
+    pub(super) keep_signature: bool,
+    /// Span of the Rust method, whose file and line are reported in diagnostics
+    pub(super) span: Span,
 }
 
 fn replace_inner(ty: &mut Type, self_: &Type) {
@@ -56,29 +70,60 @@ fn replace_inner(ty: &mut Type, self_: &Type) {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use syn::{parse_str, ImplItemFn};
+/// Whether `ty` is `Self` or a reference of Python to it, e.g. `PyRefMut<'_, Self>`
+fn is_self(ty: &Type) -> bool {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return false;
+    };
+    if path.is_ident("Self") {
+        return true;
+    }
+    let Some(last) = path.segments.last() else {
+        return false;
+    };
+    if !["PyRef", "PyRefMut", "Py", "Bound"].contains(&last.ident.to_string().as_str()) {
+        return false;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return false;
+    };
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    matches!((types.next(), types.next()), (Some(ty), None) if is_self(ty))
+}
 
-    #[test]
-    fn parse_gen_stub_abstractmethod_sets_flag() -> Result<()> {
-        let item_fn: ImplItemFn = parse_str(
-            r#"
-            #[gen_stub(abstractmethod)]
-            fn abstract_method(&self, value: i32) -> i32 {
-                value
-            }
-            "#,
-        )?;
-        let method = MethodInfo::try_from(item_fn)?;
-        assert!(method.is_abstract);
-        Ok(())
+/// Replace `Self` returned by a method, possibly in `PyResult` or `Option`, by `typing.Self`
+/// so that builder-style methods keep the type of Python subclasses
+fn replace_self_return(ty: &mut Type) {
+    if is_self(ty) {
+        *ty = syn::parse_quote!(::pyo3_stub_gen::type_info::SelfType);
+        return;
+    }
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return;
+    };
+    let Some(last) = path.segments.last_mut() else {
+        return;
+    };
+    if !["PyResult", "Result", "Option"].contains(&last.ident.to_string().as_str()) {
+        return;
+    }
+    if let PathArguments::AngleBracketed(args) = &mut last.arguments {
+        if let Some(GenericArgument::Type(ty)) = args.args.first_mut() {
+            replace_self_return(ty);
+        }
     }
 }
 
 impl MethodInfo {
     pub fn replace_self(&mut self, self_: &Type) {
+        if matches!(self.r#type, MethodType::Instance | MethodType::Class) {
+            if let Some(TypeOrOverride::RustType { r#type: ret }) = self.r#return.as_mut() {
+                replace_self_return(ret);
+            }
+        }
         for param in self.parameters.iter_mut() {
             let arg_info = &mut param.arg_info;
             let (ArgInfo {
@@ -107,14 +152,218 @@ impl MethodInfo {
     }
 }
 
+/// Whether an overridden return type of `__await__` is already the generator
+fn is_generator(type_repr: &str) -> bool {
+    ["collections.abc.Generator[", "typing.Generator["]
+        .iter()
+        .any(|prefix| type_repr.starts_with(prefix))
+}
+
+/// Whether a default value is `None`, i.e. the parameter is optional only to be omitted
+fn is_none_default(default: &Option<DefaultExpr>) -> bool {
+    match default {
+        Some(DefaultExpr::Rust(expr)) => is_none_expr(expr),
+        Some(DefaultExpr::Python(expr)) => expr == "None",
+        None => false,
+    }
+}
+
+/// `T` for `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let last = path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// Make a parameter defaulting to `None` required, e.g. `config: Option<Config> = None` becomes `config: Config`
+fn make_required(param: &mut ParameterWithKind) {
+    if !is_none_default(&param.default_expr) {
+        return;
+    }
+    param.default_expr = None;
+    if let TypeOrOverride::RustType { r#type } = &mut param.arg_info.r#type {
+        if let Some(inner) = option_inner(r#type) {
+            *r#type = inner.clone();
+        }
+    }
+}
+
+impl MethodInfo {
+    /// Split into one method per `#[gen_stub(overload_groups(...))]` group.
+    ///
+    /// Each overload takes only the parameters named in its group,
+    /// and those defaulting to `None` become required.
+    pub fn split_overload_groups(self, groups: &[Vec<LitStr>]) -> Result<Vec<Self>> {
+        if groups.is_empty() {
+            return Ok(vec![self]);
+        }
+        let mut overloads: Vec<Self> = Vec::new();
+        // Parameters required by each overload, and those it accepts, or `None` if it takes `**kwargs`
+        let mut signatures: Vec<(BTreeSet<String>, Option<BTreeSet<String>>)> = Vec::new();
+        for group in groups {
+            for name in group {
+                if !self
+                    .parameters
+                    .iter()
+                    .any(|param| param.arg_info.name == name.value())
+                {
+                    return Err(Error::new(
+                        name.span(),
+                        format!(
+                            "overload group refers to unknown parameter `{}` of `{}`",
+                            name.value(),
+                            self.name
+                        ),
+                    ));
+                }
+            }
+            let mut overload = self.clone();
+            overload
+                .parameters
+                .retain(|param| group.iter().any(|name| param.arg_info.name == name.value()));
+            for param in overload.parameters.iter_mut() {
+                make_required(param);
+            }
+
+            let named = |param: &&ParameterWithKind| {
+                !matches!(
+                    param.kind,
+                    ParameterKind::VarPositional | ParameterKind::VarKeyword
+                )
+            };
+            let required: BTreeSet<String> = overload
+                .parameters
+                .iter()
+                .filter(named)
+                .filter(|param| param.default_expr.is_none())
+                .map(|param| param.arg_info.name.clone())
+                .collect();
+            let accepted: Option<BTreeSet<String>> = (!overload
+                .parameters
+                .iter()
+                .any(|param| param.kind == ParameterKind::VarKeyword))
+            .then(|| {
+                overload
+                    .parameters
+                    .iter()
+                    .filter(named)
+                    .map(|param| param.arg_info.name.clone())
+                    .collect()
+            });
+            let accepts = |accepted: &Option<BTreeSet<String>>, names: &BTreeSet<String>| {
+                accepted
+                    .as_ref()
+                    .is_none_or(|accepted| names.is_subset(accepted))
+            };
+            // A call passing the parameters required by both overloads matches both, if both accept them
+            if let Some((other_required, _)) =
+                signatures.iter().find(|(other_required, other_accepted)| {
+                    accepts(other_accepted, &required) && accepts(&accepted, other_required)
+                })
+            {
+                let span = group
+                    .first()
+                    .map(LitStr::span)
+                    .unwrap_or_else(Span::call_site);
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "overload groups of `{}` overlap: a call passing only ({}) matches more than one group",
+                        self.name,
+                        required
+                            .union(other_required)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ));
+            }
+            signatures.push((required, accepted));
+            overloads.push(overload);
+        }
+        Ok(overloads)
+    }
+}
+
+impl MethodInfo {
+    /// Split `__getitem__` into the `@typing.overload`s taking an `int` and a `slice`
+    /// by `#[gen_stub(getitem(...))]`.
+    pub fn split_getitem(self, overloads: Option<&GetItemOverloads>) -> Result<Vec<Self>> {
+        let Some(GetItemOverloads { int, slice }) = overloads else {
+            return Ok(vec![self]);
+        };
+        if self.name != "__getitem__" || self.r#type != MethodType::Instance {
+            return Err(Error::new(
+                int.span(),
+                format!(
+                    "`getitem(...)` is only valid for `__getitem__`, not `{}`",
+                    self.name
+                ),
+            ));
+        }
+        if self.parameters.iter().count() != 1 {
+            return Err(Error::new(
+                int.span(),
+                "`getitem(...)` requires `__getitem__` to take exactly one parameter",
+            ));
+        }
+        [(int, "int"), (slice, "slice")]
+            .into_iter()
+            .map(|(r#return, key)| {
+                let mut overload = self.clone();
+                for param in overload.parameters.iter_mut() {
+                    let r#type = match &param.arg_info.r#type {
+                        TypeOrOverride::RustType { r#type }
+                        | TypeOrOverride::OverrideType { r#type, .. } => r#type.clone(),
+                    };
+                    param.arg_info.r#type = TypeOrOverride::OverrideType {
+                        r#type,
+                        type_repr: format!("builtins.{key}"),
+                        imports: ["builtins".to_string()].into_iter().collect(),
+                    };
+                    param.type_overridden = true;
+                }
+                let r#type = if r#return.value() == "Self" {
+                    syn::parse_quote!(::pyo3_stub_gen::type_info::SelfType)
+                } else {
+                    r#return.parse()?
+                };
+                overload.r#return = Some(TypeOrOverride::RustType { r#type });
+                Ok(overload)
+            })
+            .collect()
+    }
+}
+
 impl TryFrom<ImplItemFn> for MethodInfo {
     type Error = Error;
     fn try_from(item: ImplItemFn) -> Result<Self> {
         let ImplItemFn { attrs, sig, .. } = item;
+        let span = sig.ident.span();
         let doc = extract_documents(&attrs).join("\n");
         let deprecated = extract_deprecated(&attrs);
         let type_ignored = parse_gen_stub_type_ignore(&attrs)?;
         let is_abstract = parse_gen_stub_is_abstract_method(&attrs)?;
+        let also_as = parse_gen_stub_also_as(&attrs)?;
+        let keep_signature = parse_gen_stub_keep_signature(&attrs)?;
+        let is_awaitable = parse_gen_stub_is_awaitable(&attrs)?;
+        if parse_gen_stub_feature(&attrs, AttributeLocation::Function)?.is_some() {
+            return Err(Error::new_spanned(
+                &sig.ident,
+                "`feature = \"...\"` is only valid for classes, enums and functions",
+            ));
+        }
         let pyo3_attrs = parse_pyo3_attrs(&attrs)?;
         let mut method_name = None;
         let mut text_sig = Signature::overriding_operator(&sig);
@@ -132,17 +381,26 @@ impl TryFrom<ImplItemFn> for MethodInfo {
         let name = if method_type == MethodType::New {
             "__new__".to_string()
         } else {
-            method_name.unwrap_or(sig.ident.to_string())
+            method_name.unwrap_or(sig.ident.unraw().to_string())
+        };
+        let (name, args, r#return) = if let Some(slot) = buffer_slot(&name) {
+            text_sig = Some(syn::parse_str(slot.signature)?);
+            (slot.name.to_string(), vec![slot.arg()], slot.r#return())
+        } else {
+            (
+                name,
+                parse_args(sig.inputs)?,
+                extract_return_type(&sig.output, &attrs)?,
+            )
         };
-        let r#return = extract_return_type(&sig.output, &attrs)?;
 
         // Build parameters from args and signature
-        let args = parse_args(sig.inputs)?;
-        let parameters = if let Some(text_sig) = text_sig {
+        let mut parameters = if let Some(text_sig) = text_sig {
             Parameters::new_with_sig(&args, &text_sig)?
         } else {
             Parameters::new(&args)
         };
+        parameters.deprecate(parse_gen_stub_deprecated_params(&attrs)?, &name)?;
 
         Ok(MethodInfo {
             name,
@@ -151,13 +409,69 @@ impl TryFrom<ImplItemFn> for MethodInfo {
             doc,
             r#type: method_type,
             is_async: sig.asyncness.is_some(),
+            is_awaitable,
             deprecated,
             type_ignored,
             is_abstract,
+            also_as,
+            keep_signature,
+            span,
         })
     }
 }
 
+/// PEP 688 method standing for a buffer-protocol slot, whose FFI arguments have no Python types
+struct BufferSlot {
+    name: &'static str,
+    signature: &'static str,
+    parameter: &'static str,
+    parameter_type: &'static str,
+    r#return: Option<&'static str>,
+}
+
+fn buffer_slot(name: &str) -> Option<BufferSlot> {
+    match name {
+        "__getbuffer__" => Some(BufferSlot {
+            name: "__buffer__",
+            signature: "(flags, /)",
+            parameter: "flags",
+            parameter_type: "builtins.int",
+            r#return: Some("builtins.memoryview"),
+        }),
+        "__releasebuffer__" => Some(BufferSlot {
+            name: "__release_buffer__",
+            signature: "(buffer, /)",
+            parameter: "buffer",
+            parameter_type: "builtins.memoryview",
+            r#return: None,
+        }),
+        _ => None,
+    }
+}
+
+impl BufferSlot {
+    fn override_type(type_repr: &str) -> TypeOrOverride {
+        TypeOrOverride::OverrideType {
+            r#type: syn::parse_quote!(()),
+            type_repr: type_repr.to_string(),
+            imports: ["builtins".to_string()].into_iter().collect(),
+        }
+    }
+
+    fn arg(&self) -> ArgInfo {
+        ArgInfo {
+            name: self.parameter.to_string(),
+            r#type: Self::override_type(self.parameter_type),
+            container_default: None,
+            default_repr: None,
+        }
+    }
+
+    fn r#return(&self) -> Option<TypeOrOverride> {
+        self.r#return.map(Self::override_type)
+    }
+}
+
 impl ToTokens for MethodInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
@@ -167,9 +481,13 @@ impl ToTokens for MethodInfo {
             doc,
             r#type,
             is_async,
+            is_awaitable,
             deprecated,
             type_ignored,
             is_abstract,
+            also_as,
+            keep_signature,
+            span,
         } = self;
 
         let ret_tt = if let Some(ret) = ret {
@@ -190,6 +508,26 @@ impl ToTokens for MethodInfo {
         } else {
             quote! { ::pyo3_stub_gen::type_info::no_return_type_output }
         };
+        let ret_tt = if *is_awaitable {
+            quote! { || ::pyo3_stub_gen::TypeInfo::awaitable((#ret_tt)()) }
+        } else if name == "__await__" && *r#type == MethodType::Instance {
+            // The declared return, e.g. `PyRef<Self>`, is the iterator driving the await, rather than its result
+            match ret {
+                Some(TypeOrOverride::OverrideType { type_repr, .. }) if is_generator(type_repr) => {
+                    ret_tt
+                }
+                Some(TypeOrOverride::OverrideType { .. }) => {
+                    quote! { || ::pyo3_stub_gen::TypeInfo::await_generator((#ret_tt)()) }
+                }
+                _ => quote! {
+                    || ::pyo3_stub_gen::TypeInfo::await_generator(::pyo3_stub_gen::TypeInfo::any())
+                },
+            }
+        } else {
+            ret_tt
+        };
+        let is_async = *is_async && !is_awaitable;
+        let source_location = source_location(*span);
         let type_tt = match r#type {
             MethodType::Instance => quote! { ::pyo3_stub_gen::type_info::MethodType::Instance },
             MethodType::Static => quote! { ::pyo3_stub_gen::type_info::MethodType::Static },
@@ -200,23 +538,7 @@ impl ToTokens for MethodInfo {
             .as_ref()
             .map(|d| quote! { Some(#d) })
             .unwrap_or_else(|| quote! { None });
-        let type_ignored_tt = if let Some(target) = type_ignored {
-            match target {
-                IgnoreTarget::All => {
-                    quote! { Some(::pyo3_stub_gen::type_info::IgnoreTarget::All) }
-                }
-                IgnoreTarget::SpecifiedLits(rules) => {
-                    let rule_strs: Vec<String> = rules.iter().map(|lit| lit.value()).collect();
-                    quote! {
-                        Some(::pyo3_stub_gen::type_info::IgnoreTarget::Specified(
-                            &[#(#rule_strs),*] as &[&str]
-                        ))
-                    }
-                }
-            }
-        } else {
-            quote! { None }
-        };
+        let type_ignored_tt = quote_option(type_ignored);
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::MethodInfo {
                 name: #name,
@@ -228,7 +550,177 @@ impl ToTokens for MethodInfo {
                 deprecated: #deprecated_tt,
                 type_ignored: #type_ignored_tt,
                 is_abstract: #is_abstract,
+                also_as: &[#(#also_as),*],
+                keep_signature: #keep_signature,
+                source_location: #source_location,
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_str, ImplItemFn};
+
+    #[test]
+    fn parse_gen_stub_abstractmethod_sets_flag() -> Result<()> {
+        let item_fn: ImplItemFn = parse_str(
+            r#"
+            #[gen_stub(abstractmethod)]
+            fn abstract_method(&self, value: i32) -> i32 {
+                value
+            }
+            "#,
+        )?;
+        let method = MethodInfo::try_from(item_fn)?;
+        assert!(method.is_abstract);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_gen_stub_also_as() -> Result<()> {
+        let item_fn: ImplItemFn = parse_str(
+            r#"
+            #[gen_stub(also_as("count", "size"))]
+            fn __len__(&self) -> usize {
+                0
+            }
+            "#,
+        )?;
+        let method = MethodInfo::try_from(item_fn)?;
+        assert_eq!(method.also_as, ["count", "size"]);
+
+        let item_fn: ImplItemFn = parse_str(
+            r#"
+            #[gen_stub(also_as())]
+            fn __len__(&self) -> usize {
+                0
+            }
+            "#,
+        )?;
+        let err = MethodInfo::try_from(item_fn).unwrap_err();
+        assert_eq!(err.to_string(), "`also_as(...)` requires at least one name");
+        Ok(())
+    }
+
+    #[test]
+    fn raw_identifiers() -> Result<()> {
+        let item_fn: ImplItemFn = parse_str("fn r#match(&self, r#in: i32) {}")?;
+        let method = MethodInfo::try_from(item_fn)?;
+        assert_eq!(method.name, "match");
+        let out = method.to_token_stream().to_string();
+        assert!(out.contains(r#"name : "in""#), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn buffer_protocol() -> Result<()> {
+        let item_fn: ImplItemFn = parse_str(
+            r#"
+            unsafe fn __getbuffer__(
+                slf: PyRefMut<'_, Self>,
+                view: *mut ffi::Py_buffer,
+                flags: std::os::raw::c_int,
+            ) -> PyResult<()> {
+                Ok(())
+            }
+            "#,
+        )?;
+        let method = MethodInfo::try_from(item_fn)?;
+        assert_eq!(method.name, "__buffer__");
+        let out = method.to_token_stream().to_string();
+        assert!(!out.contains("Py_buffer"), "{out}");
+        assert!(out.contains(r#"name : "flags""#), "{out}");
+        assert!(out.contains("PositionalOnly"), "{out}");
+        assert!(out.contains(r#""builtins.memoryview""#), "{out}");
+
+        let item_fn: ImplItemFn =
+            parse_str("unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {}")?;
+        let method = MethodInfo::try_from(item_fn)?;
+        assert_eq!(method.name, "__release_buffer__");
+        assert!(method.r#return.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_gen_stub_keep_signature() -> Result<()> {
+        let item_fn: ImplItemFn = parse_str(
+            r#"
+            #[classmethod]
+            #[gen_stub(keep_signature)]
+            fn __init_subclass__(cls: &Bound<'_, PyType>) {}
+            "#,
+        )?;
+        let method = MethodInfo::try_from(item_fn)?;
+        assert!(method.keep_signature);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_gen_stub_type_ignore_rule() -> Result<()> {
+        let item_fn: ImplItemFn = parse_str(
+            r#"
+            #[gen_stub(type_ignore = "override")]
+            fn update(&self, value: i64) {}
+            "#,
+        )?;
+        let method = MethodInfo::try_from(item_fn)?;
+        let out = method.to_token_stream().to_string();
+        assert!(
+            out.contains(r#"IgnoreTarget :: Specified (& ["override"] as & [& str])"#),
+            "{out}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn self_returning_methods() -> Result<()> {
+        let return_of = |item_fn: &str| -> Result<String> {
+            let mut method = MethodInfo::try_from(parse_str::<ImplItemFn>(item_fn)?)?;
+            method.replace_self(&parse_str("Builder")?);
+            Ok(match method.r#return {
+                Some(TypeOrOverride::RustType { r#type }) => r#type.to_token_stream().to_string(),
+                _ => unreachable!(),
+            })
+        };
+        let self_type = ":: pyo3_stub_gen :: type_info :: SelfType";
+        assert_eq!(
+            return_of(
+                "fn with_name(mut slf: PyRefMut<Self>, name: String) -> PyRefMut<Self> { slf }"
+            )?,
+            self_type
+        );
+        assert_eq!(
+            return_of("fn copy(slf: PyRef<'_, Self>) -> PyRef<'_, Self> { slf }")?,
+            self_type
+        );
+        assert_eq!(
+            return_of("fn handle(slf: Bound<'_, Self>) -> Py<Self> { slf.unbind() }")?,
+            self_type
+        );
+        assert_eq!(
+            return_of("fn scaled(&self) -> PyResult<Self> { todo!() }")?,
+            self_type
+        );
+        assert_eq!(
+            return_of("fn parent(&self) -> Option<Py<Self>> { None }")?,
+            format!("Option < {self_type} >")
+        );
+        assert_eq!(
+            return_of("#[classmethod] fn default(_cls: &Bound<'_, PyType>) -> Self { todo!() }")?,
+            self_type
+        );
+        // `typing.Self` is not allowed in static methods
+        assert_eq!(
+            return_of("#[staticmethod] fn default() -> Self { todo!() }")?,
+            "Builder"
+        );
+        // Only the returned value itself is replaced
+        assert_eq!(
+            return_of("fn children(&self) -> Vec<Self> { vec![] }")?,
+            "Vec < Builder >"
+        );
+        Ok(())
+    }
+}
@@ -8,9 +8,12 @@ use std::collections::HashMap;
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{Expr, Result};
+use syn::{ext::IdentExt, Expr, Result};
 
-use super::{remove_lifetime, signature::SignatureArg, util::TypeOrOverride, ArgInfo, Signature};
+use super::{
+    attr::DeprecatedParamAttribute, quote_option, remove_lifetime, signature::SignatureArg,
+    util::TypeOrOverride, ArgInfo, Signature,
+};
 
 /// Represents a default value expression from either Rust or Python source
 #[derive(Debug, Clone)]
@@ -23,30 +26,64 @@ pub(crate) enum DefaultExpr {
     Python(String),
 }
 
+/// Whether `expr` is `None`, e.g. `None`, `Option::None`, `std::option::Option::None` or `None::<i64>`,
+/// which is rendered as `None` without evaluating it at runtime.
+/// Other paths ending with `None`, e.g. `Mode::None`, are evaluated.
+pub(crate) fn is_none_expr(expr: &Expr) -> bool {
+    let Expr::Path(path) = expr else {
+        return false;
+    };
+    if path.qself.is_some() {
+        return false;
+    }
+    let segments: Vec<String> = path
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    matches!(
+        segments[..],
+        ["None"] | ["Option", "None"] | ["std" | "core", "option", "Option", "None"]
+    )
+}
+
 /// Intermediate representation for a parameter with its kind determined
 #[derive(Debug, Clone)]
 pub(crate) struct ParameterWithKind {
     pub(crate) arg_info: ArgInfo,
     pub(crate) kind: ParameterKind,
     pub(crate) default_expr: Option<DefaultExpr>,
+    /// Whether the type is given by the user, e.g. `#[gen_stub(override_type(...))]`,
+    /// rather than derived from the Rust type or filled in as a fallback
+    pub(crate) type_overridden: bool,
+    /// Note of `#[gen_stub(deprecated_param(...))]`, which is empty without `note = "..."`
+    pub(crate) deprecated: Option<String>,
 }
 
 impl ToTokens for ParameterWithKind {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let name = &self.arg_info.name;
         let kind = &self.kind;
+        let type_overridden = self.type_overridden;
+        let deprecated = quote_option(&self.deprecated);
+        let container_default = match &self.arg_info.container_default {
+            Some(style) => quote! { Some(#style) },
+            None => quote! { None },
+        };
 
         let default_tokens = match &self.default_expr {
             Some(DefaultExpr::Rust(expr)) => {
                 // Rust expression: needs runtime conversion via fmt_py_obj
                 match &self.arg_info.r#type {
                     TypeOrOverride::RustType { r#type } => {
-                        let default = if expr.to_token_stream().to_string() == "None" {
+                        let default = if is_none_expr(expr) {
                             quote! { "None".to_string() }
                         } else {
                             quote! {
                                 let v: #r#type = #expr;
-                                ::pyo3_stub_gen::util::fmt_py_obj(v)
+                                ::pyo3_stub_gen::util::fmt_default(v)
                             }
                         };
                         quote! {
@@ -101,6 +138,9 @@ impl ToTokens for ParameterWithKind {
                         kind: #kind,
                         type_info: <#r#type as ::pyo3_stub_gen::PyStubType>::type_input,
                         default: #default_tokens,
+                        type_overridden: #type_overridden,
+                        container_default: #container_default,
+                        deprecated: #deprecated,
                     }
                 }
             }
@@ -117,6 +157,9 @@ impl ToTokens for ParameterWithKind {
                             import: ::std::collections::HashSet::from([#(#imports.into(),)*])
                         },
                         default: #default_tokens,
+                        type_overridden: #type_overridden,
+                        container_default: #container_default,
+                        deprecated: #deprecated,
                     }
                 }
             }
@@ -126,6 +169,41 @@ impl ToTokens for ParameterWithKind {
     }
 }
 
+/// Rendering of a list, dict, or set default given by `#[gen_stub(container_default = "...")]`
+///
+/// This enum mirrors `::pyo3_stub_gen::generate::ContainerDefaultStyle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ContainerDefault {
+    Literal,
+    Ellipsis,
+    NoneIfOptional,
+}
+
+impl ContainerDefault {
+    pub(crate) fn parse(value: &syn::LitStr) -> Result<Self> {
+        match value.value().as_str() {
+            "literal" => Ok(Self::Literal),
+            "ellipsis" => Ok(Self::Ellipsis),
+            "none-if-optional" => Ok(Self::NoneIfOptional),
+            other => Err(syn::Error::new(
+                value.span(),
+                format!("Unknown container default `{other}`, valid is `literal`, `ellipsis`, or `none-if-optional`"),
+            )),
+        }
+    }
+}
+
+impl ToTokens for ContainerDefault {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let style = match self {
+            Self::Literal => quote! { Literal },
+            Self::Ellipsis => quote! { Ellipsis },
+            Self::NoneIfOptional => quote! { NoneIfOptional },
+        };
+        tokens.append_all(quote! { ::pyo3_stub_gen::generate::ContainerDefaultStyle::#style });
+    }
+}
+
 /// Parameter kind for intermediate representation in derive macro
 ///
 /// This enum mirrors `::pyo3_stub_gen::type_info::ParameterKind` but exists
@@ -182,6 +260,38 @@ impl Parameters {
         self.0.iter_mut()
     }
 
+    /// Iterate over the parameters
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &ParameterWithKind> {
+        self.0.iter()
+    }
+
+    /// Mark the parameters named by `#[gen_stub(deprecated_param(...))]` of `function` as deprecated
+    pub(crate) fn deprecate(
+        &mut self,
+        deprecated: Vec<DeprecatedParamAttribute>,
+        function: &str,
+    ) -> Result<()> {
+        for DeprecatedParamAttribute { name, note } in deprecated {
+            let Some(parameter) = self
+                .0
+                .iter_mut()
+                .find(|parameter| parameter.arg_info.name == name.value())
+            else {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{}` is not a parameter of `{function}`", name.value()),
+                ));
+            };
+            parameter.deprecated = Some(note.unwrap_or_default());
+        }
+        Ok(())
+    }
+
+    /// Keep only the parameters for which `f` returns `true`
+    pub(crate) fn retain(&mut self, f: impl FnMut(&ParameterWithKind) -> bool) {
+        self.0.retain(f);
+    }
+
     /// Create parameters without signature attribute
     ///
     /// All parameters will be classified as `PositionalOrKeyword`.
@@ -198,9 +308,11 @@ impl Parameters {
                     remove_lifetime(r#type);
                 }
                 ParameterWithKind {
+                    type_overridden: arg_with_clean_type.is_overridden(),
                     arg_info: arg_with_clean_type,
                     kind: ParameterKind::PositionalOrKeyword,
                     default_expr: None,
+                    deprecated: None,
                 }
             })
             .collect();
@@ -249,7 +361,7 @@ impl Parameters {
                     after_star = true;
                 }
                 SignatureArg::Ident(ident) => {
-                    let name = ident.to_string();
+                    let name = ident.unraw().to_string();
                     let kind = if positional_only {
                         ParameterKind::PositionalOnly
                     } else if after_star {
@@ -265,14 +377,22 @@ impl Parameters {
                         })?
                         .clone();
 
+                    if let Some(repr) = &arg_info.default_repr {
+                        return Err(syn::Error::new(
+                            repr.span(),
+                            "`default_repr` requires a default in `#[pyo3(signature = ...)]`",
+                        ));
+                    }
                     parameters.push(ParameterWithKind {
+                        type_overridden: arg_info.is_overridden(),
                         arg_info,
                         kind,
                         default_expr: None,
+                        deprecated: None,
                     });
                 }
                 SignatureArg::Assign(ident, _eq, value) => {
-                    let name = ident.to_string();
+                    let name = ident.unraw().to_string();
                     let kind = if positional_only {
                         ParameterKind::PositionalOnly
                     } else if after_star {
@@ -288,16 +408,22 @@ impl Parameters {
                         })?
                         .clone();
 
+                    let default_expr = match &arg_info.default_repr {
+                        Some(repr) => DefaultExpr::Python(repr.value()),
+                        None => DefaultExpr::Rust(value.clone()),
+                    };
                     parameters.push(ParameterWithKind {
+                        type_overridden: arg_info.is_overridden(),
                         arg_info,
                         kind,
-                        default_expr: Some(DefaultExpr::Rust(value.clone())),
+                        default_expr: Some(default_expr),
+                        deprecated: None,
                     });
                 }
                 SignatureArg::Args(_, ident) => {
                     positional_only = false;
                     after_star = true; // After *args, everything is keyword-only
-                    let name = ident.to_string();
+                    let name = ident.unraw().to_string();
 
                     let mut arg_info = args_map
                         .get(&name)
@@ -308,7 +434,8 @@ impl Parameters {
 
                     // For VarPositional, if the type is auto-inferred from Rust (RustType),
                     // replace it with typing.Any. If it's OverrideType, keep the user's specification.
-                    if matches!(arg_info.r#type, TypeOrOverride::RustType { .. }) {
+                    let type_overridden = arg_info.is_overridden();
+                    if !type_overridden {
                         arg_info.r#type = TypeOrOverride::OverrideType {
                             r#type: syn::parse_quote!(()), // Dummy type, won't be used
                             type_repr: "typing.Any".to_string(),
@@ -320,11 +447,13 @@ impl Parameters {
                         arg_info,
                         kind: ParameterKind::VarPositional,
                         default_expr: None,
+                        type_overridden,
+                        deprecated: None,
                     });
                 }
                 SignatureArg::Keywords(_, _, ident) => {
                     positional_only = false;
-                    let name = ident.to_string();
+                    let name = ident.unraw().to_string();
 
                     let mut arg_info = args_map
                         .get(&name)
@@ -335,7 +464,8 @@ impl Parameters {
 
                     // For VarKeyword, if the type is auto-inferred from Rust (RustType),
                     // replace it with typing.Any. If it's OverrideType, keep the user's specification.
-                    if matches!(arg_info.r#type, TypeOrOverride::RustType { .. }) {
+                    let type_overridden = arg_info.is_overridden();
+                    if !type_overridden {
                         arg_info.r#type = TypeOrOverride::OverrideType {
                             r#type: syn::parse_quote!(()), // Dummy type, won't be used
                             type_repr: "typing.Any".to_string(),
@@ -347,6 +477,8 @@ impl Parameters {
                         arg_info,
                         kind: ParameterKind::VarKeyword,
                         default_expr: None,
+                        type_overridden,
+                        deprecated: None,
                     });
                 }
             }
@@ -362,3 +494,27 @@ impl ToTokens for Parameters {
         tokens.append_all(quote! { &[ #(#params),* ] })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::{parse_str, Result};
+
+    #[test]
+    fn test_is_none_expr() -> Result<()> {
+        for expr in [
+            "None",
+            "None::<i64>",
+            "Option::None",
+            "Option::<i64>::None",
+            "std::option::Option::None",
+            "core::option::Option::None",
+        ] {
+            assert!(is_none_expr(&parse_str(expr)?), "{expr}");
+        }
+        for expr in ["Mode::None", "crate::Mode::None", "<Mode>::None", "Some(1)"] {
+            assert!(!is_none_expr(&parse_str(expr)?), "{expr}");
+        }
+        Ok(())
+    }
+}
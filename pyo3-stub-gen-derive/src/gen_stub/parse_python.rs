@@ -128,7 +128,12 @@ pub(super) fn build_parameters_from_ast(
             let arg_info = ArgInfo {
                 name: arg_name,
                 r#type: type_override,
+                container_default: None,
+                default_repr: None,
             };
+            // Annotations written by the user are overrides, but `typing.Any` fallbacks and
+            // `pyo3_stub_gen.RustType[...]` markers are not
+            let type_overridden = arg.def.annotation.is_some() && arg_info.is_overridden();
 
             // Convert default value from Python AST to Python string
             let default_expr = if let Some(default) = &arg.default {
@@ -141,6 +146,8 @@ pub(super) fn build_parameters_from_ast(
                 arg_info,
                 kind,
                 default_expr,
+                type_overridden,
+                deprecated: None,
             }))
         };
 
@@ -162,12 +169,17 @@ pub(super) fn build_parameters_from_ast(
         let arg_info = ArgInfo {
             name: arg_name,
             r#type: type_override,
+            container_default: None,
+            default_repr: None,
         };
+        let type_overridden = arg.annotation.is_some() && arg_info.is_overridden();
 
         Ok(ParameterWithKind {
             arg_info,
             kind,
             default_expr: None,
+            type_overridden,
+            deprecated: None,
         })
     };
 
@@ -1,5 +1,6 @@
 //! Parse Python function stub syntax and generate PyFunctionInfo
 
+use proc_macro2::Span;
 use rustpython_parser::{ast, Parse};
 use syn::{parse::Parse as SynParse, parse::ParseStream, Error, LitStr, Result};
 
@@ -79,14 +80,18 @@ impl TryFrom<PythonFunctionStub> for PyFunctionInfo {
 
         // Construct PyFunctionInfo
         Ok(PyFunctionInfo {
+            rust_name: func_name.clone(),
             name: func_name,
             parameters, // Use pre-built Parameters from Python AST
             r#return: return_type,
             doc,
             module: None,
             is_async: stub.is_async,
+            is_awaitable: false,
             deprecated,
             type_ignored: None,
+            feature: None,
+            span: Span::call_site(),
         })
     }
 }
@@ -199,6 +204,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "foo",
+            rust_path: concat!(module_path!(), "::", "foo"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
@@ -208,6 +215,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -219,6 +229,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -240,6 +251,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "process",
+            rust_path: concat!(module_path!(), "::", "process"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "func",
@@ -252,6 +265,9 @@ mod test {
                         ]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -266,6 +282,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -287,6 +304,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "fn_override_type",
+            rust_path: concat!(module_path!(), "::", "fn_override_type"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "cb",
@@ -299,6 +318,9 @@ mod test {
                         ]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -313,6 +335,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -332,6 +355,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "add",
+            rust_path: concat!(module_path!(), "::", "add"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "a",
@@ -341,6 +366,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["typing".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "b",
@@ -350,6 +378,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["typing".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "c",
@@ -359,6 +390,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["typing".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -370,6 +404,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -388,6 +423,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "print_hello",
+            rust_path: concat!(module_path!(), "::", "print_hello"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "name",
@@ -397,6 +434,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: ::pyo3_stub_gen::type_info::no_return_type_output,
@@ -405,6 +445,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -423,6 +464,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "fetch_data",
+            rust_path: concat!(module_path!(), "::", "fetch_data"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "url",
@@ -432,6 +475,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -443,6 +489,7 @@ mod test {
             is_async: true,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -462,6 +509,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "old_function",
+            rust_path: concat!(module_path!(), "::", "old_function"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
@@ -471,6 +520,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -485,6 +537,7 @@ mod test {
                 note: None,
             }),
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -504,6 +557,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "old_function",
+            rust_path: concat!(module_path!(), "::", "old_function"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
@@ -513,6 +568,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -527,6 +585,7 @@ mod test {
                 note: Some("Use new_function instead"),
             }),
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -545,12 +604,17 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "process_data",
+            rust_path: concat!(module_path!(), "::", "process_data"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
                     kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
                     type_info: <MyRustType as ::pyo3_stub_gen::PyStubType>::type_input,
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: false,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: <MyRustType as pyo3_stub_gen::PyStubType>::type_output,
@@ -559,6 +623,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -577,12 +642,17 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "process",
+            rust_path: concat!(module_path!(), "::", "process"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
                     kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
                     type_info: <crate::MyType as ::pyo3_stub_gen::PyStubType>::type_input,
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: false,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: <Vec<String> as pyo3_stub_gen::PyStubType>::type_output,
@@ -591,6 +661,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -611,6 +682,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "configure",
+            rust_path: concat!(module_path!(), "::", "configure"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "name",
@@ -620,6 +693,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["typing".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "dtype",
@@ -629,6 +705,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["typing".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "ndim",
@@ -638,6 +717,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["typing".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "jagged",
@@ -652,6 +734,9 @@ mod test {
                         }
                         _fmt
                     }),
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -663,6 +748,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
@@ -681,6 +767,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyFunctionInfo {
             name: "func",
+            rust_path: concat!(module_path!(), "::", "func"),
+            source_location: Some(concat!(file!(), ":", line!())),
             parameters: &[
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
@@ -690,6 +778,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "y",
@@ -699,6 +790,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "z",
@@ -708,6 +802,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -719,6 +816,7 @@ mod test {
             is_async: false,
             deprecated: None,
             type_ignored: None,
+            feature: None,
         }
         "###);
         Ok(())
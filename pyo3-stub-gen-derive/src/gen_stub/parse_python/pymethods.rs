@@ -1,5 +1,6 @@
 //! Parse Python class method stub syntax and generate MethodInfo
 
+use proc_macro2::Span;
 use rustpython_parser::{ast, Parse};
 use syn::{Error, LitStr, Result, Type};
 
@@ -51,9 +52,13 @@ impl TryFrom<PythonMethodStub> for MethodInfo {
             doc,
             r#type: stub.method_type,
             is_async: stub.func_stub.is_async,
+            is_awaitable: false,
             deprecated,
             type_ignored: None,
             is_abstract,
+            also_as: Vec::new(),
+            keep_signature: false,
+            span: Span::call_site(),
         })
     }
 }
@@ -289,6 +294,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -301,6 +309,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -352,6 +363,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -364,6 +378,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -395,6 +412,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -407,6 +427,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -472,6 +495,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -508,6 +534,9 @@ mod test {
                         ]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -523,6 +552,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -553,6 +585,9 @@ mod test {
                         import: ::std::collections::HashSet::from([]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -565,6 +600,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -598,6 +636,9 @@ mod test {
                         import: ::std::collections::HashSet::from(["abc".into()]),
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: || ::pyo3_stub_gen::TypeInfo {
@@ -610,6 +651,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: true,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -637,6 +681,9 @@ mod test {
                     kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
                     type_info: <SomeRustType as ::pyo3_stub_gen::PyStubType>::type_input,
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: false,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             r#return: <PyProblem as pyo3_stub_gen::PyStubType>::type_output,
@@ -646,6 +693,9 @@ mod test {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: Some(concat!(file!(), ":", line!())),
         }
         "###);
         Ok(())
@@ -685,6 +735,8 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyMethodsInfo {
             struct_id: std::any::TypeId::of::<Placeholder>,
+            source_location: Some(concat!(file!(), ":", line!())),
+            struct_name: std::any::type_name::<Placeholder>,
             attrs: &[],
             getters: &[],
             setters: &[],
@@ -703,6 +755,9 @@ mod test {
                                 ]),
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "dtype",
@@ -715,6 +770,9 @@ mod test {
                                 ]),
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "ndim",
@@ -727,6 +785,9 @@ mod test {
                                 ]),
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "shape",
@@ -739,6 +800,9 @@ mod test {
                                 ]),
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "jagged",
@@ -756,6 +820,9 @@ mod test {
                                 }
                                 _fmt
                             }),
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "latex",
@@ -773,6 +840,9 @@ mod test {
                                 }
                                 _fmt
                             }),
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "description",
@@ -790,6 +860,9 @@ mod test {
                                 }
                                 _fmt
                             }),
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
                         },
                     ],
                     r#return: <Placeholder as pyo3_stub_gen::PyStubType>::type_output,
@@ -799,6 +872,9 @@ mod test {
                     deprecated: None,
                     type_ignored: None,
                     is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
                 },
             ],
         }
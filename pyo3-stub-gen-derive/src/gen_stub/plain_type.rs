@@ -0,0 +1,397 @@
+//! `PyStubType` derived for plain Rust types converted by `IntoPyObject`, which are not pyclasses.
+//!
+//! A struct is rendered as a stub-only `typing.TypedDict` class whose keys are the fields,
+//! and a fieldless enum as `typing.Literal` of the variant names.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    ext::IdentExt, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields,
+    LitStr, Result, Type,
+};
+
+use super::{
+    attr::python_str_repr,
+    extract_documents,
+    util::{option_inner, quote_option, source_location},
+    RenamingRule, StubType,
+};
+
+/// Options given by `#[gen_stub(...)]` on a type deriving `PyStubType`, or on its fields and variants
+#[derive(Default)]
+struct DeriveOptions {
+    module: Option<String>,
+    rename_all: Option<RenamingRule>,
+    rename: Option<String>,
+}
+
+impl DeriveOptions {
+    /// Parse the options, allowing only the keywords in `valid`
+    fn parse(attrs: &[Attribute], valid: &[&str]) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("gen_stub")) {
+            attr.parse_nested_meta(|meta| {
+                let key = meta.path.to_token_stream().to_string();
+                if !valid.contains(&key.as_str()) {
+                    let valid = valid
+                        .iter()
+                        .map(|key| format!("`{key} = \"...\"`"))
+                        .collect::<Vec<_>>()
+                        .join(" or ");
+                    return Err(
+                        meta.error(format!("Unsupported keyword `{key}`, valid is {valid}"))
+                    );
+                }
+                let value: LitStr = meta.value()?.parse()?;
+                match key.as_str() {
+                    "module" => options.module = Some(value.value()),
+                    "rename" => options.rename = Some(value.value()),
+                    _ => {
+                        let rule = RenamingRule::try_new(&value.value()).ok_or_else(|| {
+                            Error::new(value.span(), "Unknown renaming rule of `rename_all`")
+                        })?;
+                        options.rename_all = Some(rule);
+                    }
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+
+    /// Python name of a field or a variant `ident`, renamed by `rename`, or else by `rename_all` of the type
+    fn name(&self, ident: &syn::Ident, rename_all: Option<RenamingRule>) -> String {
+        let name = ident.unraw().to_string();
+        match (&self.rename, rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => rule.apply(&name),
+            (None, None) => name,
+        }
+    }
+}
+
+/// Keywords of Python, which cannot be the names of `typing.TypedDict` keys
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Whether `name` can be declared as a key in the class syntax of `typing.TypedDict`
+fn is_python_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+        && !KEYWORDS.contains(&name)
+}
+
+/// Struct rendered as a stub-only `typing.TypedDict`
+pub struct TypedDictInfo {
+    name: String,
+    struct_type: Type,
+    module: Option<String>,
+    doc: String,
+    fields: Vec<TypedDictField>,
+}
+
+struct TypedDictField {
+    name: String,
+    r#type: Type,
+    doc: String,
+}
+
+/// Fieldless enum rendered as `typing.Literal` of the variant names
+pub struct LiteralEnumInfo {
+    enum_type: Type,
+    /// Python representations of the variant names
+    members: Vec<String>,
+}
+
+/// Type deriving `PyStubType`
+pub enum PlainType {
+    TypedDict(TypedDictInfo),
+    LiteralEnum(LiteralEnumInfo),
+}
+
+impl TryFrom<DeriveInput> for PlainType {
+    type Error = Error;
+    fn try_from(input: DeriveInput) -> Result<Self> {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &input.generics,
+                "`PyStubType` cannot be derived for a generic type",
+            ));
+        }
+        let ident = &input.ident;
+        match &input.data {
+            Data::Struct(data) => {
+                let options = DeriveOptions::parse(&input.attrs, &["module", "rename_all"])?;
+                let Fields::Named(named) = &data.fields else {
+                    return Err(Error::new_spanned(
+                        ident,
+                        "`PyStubType` can be derived only for a struct with named fields, which is rendered as `typing.TypedDict`",
+                    ));
+                };
+                let fields = named
+                    .named
+                    .iter()
+                    .map(|field| {
+                        let field_options = DeriveOptions::parse(&field.attrs, &["rename"])?;
+                        let ident = field.ident.as_ref().expect("named field");
+                        let name = field_options.name(ident, options.rename_all);
+                        if !is_python_identifier(&name) {
+                            return Err(Error::new_spanned(
+                                ident,
+                                format!("Key `{name}` of `typing.TypedDict` is not a Python identifier, which the class syntax requires"),
+                            ));
+                        }
+                        Ok(TypedDictField {
+                            name,
+                            r#type: field.ty.clone(),
+                            doc: extract_documents(&field.attrs).join("\n"),
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Self::TypedDict(TypedDictInfo {
+                    name: ident.unraw().to_string(),
+                    struct_type: parse_quote!(#ident),
+                    module: options.module,
+                    doc: extract_documents(&input.attrs).join("\n"),
+                    fields,
+                }))
+            }
+            Data::Enum(data) => {
+                let options = DeriveOptions::parse(&input.attrs, &["rename_all"])?;
+                let members = data
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        if !matches!(variant.fields, Fields::Unit) {
+                            return Err(Error::new_spanned(
+                                &variant.ident,
+                                "`PyStubType` can be derived only for a fieldless enum, which is rendered as `typing.Literal` of the variant names",
+                            ));
+                        }
+                        let variant_options = DeriveOptions::parse(&variant.attrs, &["rename"])?;
+                        let name = variant_options.name(&variant.ident, options.rename_all);
+                        Ok(python_str_repr(&name))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Self::LiteralEnum(LiteralEnumInfo {
+                    enum_type: parse_quote!(#ident),
+                    members,
+                }))
+            }
+            Data::Union(_) => Err(Error::new_spanned(
+                ident,
+                "`PyStubType` cannot be derived for a union",
+            )),
+        }
+    }
+}
+
+impl From<&TypedDictInfo> for StubType {
+    fn from(info: &TypedDictInfo) -> Self {
+        Self {
+            ty: info.struct_type.clone(),
+            name: info.name.clone(),
+            module: info.module.clone(),
+            type_input_override: None,
+            type_output_override: None,
+            default_repr: None,
+        }
+    }
+}
+
+impl From<&LiteralEnumInfo> for StubType {
+    fn from(info: &LiteralEnumInfo) -> Self {
+        let type_repr = format!("typing.Literal[{}]", info.members.join(", "));
+        Self {
+            ty: info.enum_type.clone(),
+            name: String::new(),
+            module: None,
+            type_input_override: None,
+            type_output_override: Some(quote! {
+                ::pyo3_stub_gen::TypeInfo::with_module(#type_repr, "typing".into())
+            }),
+            default_repr: None,
+        }
+    }
+}
+
+impl ToTokens for TypedDictInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            name,
+            struct_type,
+            module,
+            doc,
+            fields,
+        } = self;
+        let module = quote_option(module);
+        let source_location = source_location(struct_type.span());
+        let fields = fields.iter().map(|field| {
+            let TypedDictField {
+                name,
+                r#type: ty,
+                doc,
+            } = field;
+            let required = option_inner(ty).is_none();
+            quote! {
+                ::pyo3_stub_gen::type_info::TypedDictFieldInfo {
+                    name: #name,
+                    r#type: <#ty as ::pyo3_stub_gen::PyStubType>::type_output,
+                    doc: #doc,
+                    required: #required,
+                }
+            }
+        });
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::TypedDictInfo {
+                struct_id: ::std::any::TypeId::of::<#struct_type>,
+                name: #name,
+                module: #module,
+                rust_path: concat!(module_path!(), "::", #name),
+                source_location: #source_location,
+                doc: #doc,
+                fields: &[#(#fields),*],
+            }
+        })
+    }
+}
+
+impl ToTokens for PlainType {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            Self::TypedDict(info) => {
+                let stub_type = StubType::from(info);
+                tokens.append_all(quote! {
+                    #stub_type
+                    ::pyo3_stub_gen::inventory::submit! {
+                        #info
+                    }
+                });
+            }
+            Self::LiteralEnum(info) => StubType::from(info).to_tokens(tokens),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn expand(input: &str) -> Result<String> {
+        let out = PlainType::try_from(parse_str::<DeriveInput>(input)?)?.to_token_stream();
+        Ok(prettyplease::unparse(&syn::parse_file(&out.to_string())?))
+    }
+
+    #[test]
+    fn test_typed_dict() -> Result<()> {
+        let out = expand(
+            r#"
+            /// Summary of the samples
+            #[gen_stub(module = "my_module", rename_all = "camelCase")]
+            pub struct Summary {
+                /// Number of the samples
+                sample_count: usize,
+                mean: Option<f64>,
+                #[gen_stub(rename = "kind")]
+                r#type: String,
+            }
+            "#,
+        )?;
+        insta::assert_snapshot!(out, @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Summary {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::locally_defined("Summary", "my_module".into())
+            }
+            fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::locally_defined("Summary", "my_module".into())
+            }
+        }
+        ::pyo3_stub_gen::inventory::submit! {
+            ::pyo3_stub_gen::type_info::TypedDictInfo { struct_id : ::std::any::TypeId::of:: <
+            Summary >, name : "Summary", module : Some("my_module"), rust_path :
+            concat!(module_path!(), "::", "Summary"), source_location : Some(concat!(file!(),
+            ":", line!())), doc : "Summary of the samples", fields : &
+            [::pyo3_stub_gen::type_info::TypedDictFieldInfo { name : "sampleCount", r#type : <
+            usize as ::pyo3_stub_gen::PyStubType > ::type_output, doc : "Number of the samples",
+            required : true, }, ::pyo3_stub_gen::type_info::TypedDictFieldInfo { name : "mean",
+            r#type : < Option < f64 > as ::pyo3_stub_gen::PyStubType > ::type_output, doc : "",
+            required : false, }, ::pyo3_stub_gen::type_info::TypedDictFieldInfo { name : "kind",
+            r#type : < String as ::pyo3_stub_gen::PyStubType > ::type_output, doc : "", required
+            : true, }], }
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_enum() -> Result<()> {
+        let out = expand(
+            r#"
+            #[gen_stub(rename_all = "kebab-case")]
+            pub enum Mode {
+                FastPath,
+                #[gen_stub(rename = "it's slow")]
+                Slow,
+            }
+            "#,
+        )?;
+        insta::assert_snapshot!(out, @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Mode {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::with_module(
+                    "typing.Literal['fast-path', \"it's slow\"]",
+                    "typing".into(),
+                )
+            }
+            fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::with_module(
+                    "typing.Literal['fast-path', \"it's slow\"]",
+                    "typing".into(),
+                )
+            }
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_types() {
+        let error = |input: &str| expand(input).err().unwrap().to_string();
+        assert_eq!(
+            error("struct Pair(i32, i32);"),
+            "`PyStubType` can be derived only for a struct with named fields, which is rendered as `typing.TypedDict`"
+        );
+        assert_eq!(
+            error("enum Shape { Circle { radius: f64 }, Empty }"),
+            "`PyStubType` can be derived only for a fieldless enum, which is rendered as `typing.Literal` of the variant names"
+        );
+        assert_eq!(
+            error("struct Wrapper<T> { value: T }"),
+            "`PyStubType` cannot be derived for a generic type"
+        );
+        assert_eq!(
+            error(r#"#[gen_stub(module = "my_module")] enum Mode { Fast }"#),
+            "Unsupported keyword `module`, valid is `rename_all = \"...\"`"
+        );
+        assert_eq!(
+            error(r#"#[gen_stub(rename_all = "Title Case")] enum Mode { Fast }"#),
+            "Unknown renaming rule of `rename_all`"
+        );
+        assert_eq!(
+            error(r#"#[gen_stub(rename_all = "kebab-case")] struct Range { low_end: f64 }"#),
+            "Key `low-end` of `typing.TypedDict` is not a Python identifier, which the class syntax requires"
+        );
+        assert_eq!(
+            error(r#"struct Range { #[gen_stub(rename = "from")] start: f64 }"#),
+            "Key `from` of `typing.TypedDict` is not a Python identifier, which the class syntax requires"
+        );
+    }
+}
@@ -1,13 +1,21 @@
 use super::{
-    extract_documents, parse_gen_stub_is_abstract_class, parse_pyo3_attrs, util::quote_option,
-    Attr, MemberInfo, StubType,
+    extract_deprecated, extract_documents, parse_gen_stub_class_type_override,
+    parse_gen_stub_feature, parse_gen_stub_is_abstract_class, parse_gen_stub_is_dataclass,
+    parse_gen_stub_is_flag_enum, parse_gen_stub_is_hidden_base, parse_gen_stub_is_no_abc,
+    parse_gen_stub_is_skipped_class, parse_gen_stub_is_slots, parse_gen_stub_is_total_ordering,
+    parse_gen_stub_pickle_state, parse_gen_stub_skip, parse_pyo3_attrs,
+    util::{pickle_state_type, quote_option, source_location},
+    Attr, AttributeLocation, ClassTypeOverride, DeprecatedInfo, MemberInfo, OverrideTypeAttribute,
+    StubType,
 };
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{parse_quote, Error, ItemStruct, Result, Type};
+use syn::{parse_quote, spanned::Spanned, Error, ItemStruct, Result, Type};
 
 pub struct PyClassInfo {
     pyclass_name: String,
+    /// Whether the name is given by `#[pyclass(name = "...")]`
+    name_given: bool,
     struct_type: Type,
     module: Option<String>,
     getters: Vec<MemberInfo>,
@@ -20,6 +28,25 @@ pub struct PyClassInfo {
     has_str: bool,
     subclass: bool,
     is_abstract: bool,
+    type_override: ClassTypeOverride,
+    dataclass: bool,
+    hidden_base: bool,
+    no_abc: bool,
+    /// `#[gen_stub(flag_enum)]`
+    flag_enum: bool,
+    /// `#[gen_stub(total_ordering)]`
+    total_ordering: bool,
+    /// `#[gen_stub(pickle_state = "...")]`
+    pickle_state: Option<OverrideTypeAttribute>,
+    /// `#[gen_stub(slots)]`
+    slots: bool,
+    /// `#[pyclass(weakref)]`, listing `__weakref__` in the slots
+    weakref: bool,
+    /// Cargo feature given by `#[gen_stub(feature = "...")]`
+    feature: Option<String>,
+    /// `#[deprecated]` on the struct
+    deprecated: Option<DeprecatedInfo>,
+    skip: bool,
 }
 
 impl From<&PyClassInfo> for StubType {
@@ -28,14 +55,27 @@ impl From<&PyClassInfo> for StubType {
             pyclass_name,
             module,
             struct_type,
+            type_override,
+            skip,
             ..
         } = info;
+        // A skipped class is rendered as `typing.Any` unless its types are overridden
+        let skipped = skip.then(|| quote! { ::pyo3_stub_gen::TypeInfo::any() });
         Self {
             ty: struct_type.clone(),
             name: pyclass_name.clone(),
             module: module.clone(),
-            type_input_override: None,
-            type_output_override: None,
+            type_input_override: type_override
+                .input
+                .as_ref()
+                .map(|attr| attr.to_type_info())
+                .or_else(|| skipped.clone()),
+            type_output_override: type_override
+                .output
+                .as_ref()
+                .map(|attr| attr.to_type_info())
+                .or(skipped),
+            default_repr: None,
         }
     }
 }
@@ -60,7 +100,24 @@ impl TryFrom<ItemStruct> for PyClassInfo {
         let mut has_hash = false;
         let mut has_str = false;
         let mut subclass = false;
+        let mut dict = false;
+        let mut weakref = false;
+        let mut renaming_rule = None;
         let is_abstract = parse_gen_stub_is_abstract_class(&attrs)?;
+        let type_override = parse_gen_stub_class_type_override(&attrs)?;
+        let dataclass = parse_gen_stub_is_dataclass(&attrs)?;
+        let hidden_base = parse_gen_stub_is_hidden_base(&attrs)?;
+        let no_abc = parse_gen_stub_is_no_abc(&attrs)?;
+        let flag_enum = parse_gen_stub_is_flag_enum(&attrs)?;
+        let total_ordering = parse_gen_stub_is_total_ordering(&attrs)?;
+        let pickle_state = parse_gen_stub_pickle_state(&attrs)?
+            .map(|state| pickle_state_type(&state))
+            .transpose()?;
+        let slots = parse_gen_stub_is_slots(&attrs)?;
+        let feature = parse_gen_stub_feature(&attrs, AttributeLocation::Class)?
+            .map(|feature| feature.value());
+        let skip = parse_gen_stub_is_skipped_class(&attrs)?;
+        let deprecated = extract_deprecated(&attrs);
         for attr in parse_pyo3_attrs(&attrs)? {
             match attr {
                 Attr::Name(name) => pyclass_name = Some(name),
@@ -75,24 +132,38 @@ impl TryFrom<ItemStruct> for PyClassInfo {
                 Attr::Hash => has_hash = true,
                 Attr::Str => has_str = true,
                 Attr::Subclass => subclass = true,
+                Attr::Dict => dict = true,
+                Attr::Weakref => weakref = true,
+                Attr::RenameAll(rule) => renaming_rule = Some(rule),
                 _ => {}
             }
         }
+        if dict && slots {
+            return Err(Error::new(
+                ident.span(),
+                "`#[gen_stub(slots)]` cannot be used with `#[pyclass(dict)]`, whose instances accept any attribute",
+            ));
+        }
+        let name_given = pyclass_name.is_some();
         let pyclass_name = pyclass_name.unwrap_or_else(|| ident.to_string());
         let mut getters = Vec::new();
         let mut setters = Vec::new();
         for field in fields {
+            if parse_gen_stub_skip(&field.attrs)? {
+                continue;
+            }
             if is_get_all || MemberInfo::is_get(&field)? {
-                getters.push(MemberInfo::try_from(field.clone())?)
+                getters.push(MemberInfo::from_field(field.clone(), &renaming_rule)?)
             }
             if is_set_all || MemberInfo::is_set(&field)? {
-                setters.push(MemberInfo::try_from(field)?)
+                setters.push(MemberInfo::from_field(field, &renaming_rule)?.into_setter())
             }
         }
         let doc = extract_documents(&attrs).join("\n");
         Ok(Self {
             struct_type,
             pyclass_name,
+            name_given,
             getters,
             setters,
             module,
@@ -104,6 +175,18 @@ impl TryFrom<ItemStruct> for PyClassInfo {
             has_str,
             subclass,
             is_abstract,
+            type_override,
+            dataclass,
+            hidden_base,
+            no_abc,
+            flag_enum,
+            total_ordering,
+            pickle_state,
+            slots,
+            weakref,
+            feature,
+            deprecated,
+            skip,
         })
     }
 }
@@ -112,6 +195,7 @@ impl ToTokens for PyClassInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
             pyclass_name,
+            name_given,
             struct_type,
             getters,
             setters,
@@ -124,12 +208,55 @@ impl ToTokens for PyClassInfo {
             has_str,
             subclass,
             is_abstract,
+            type_override,
+            dataclass,
+            hidden_base,
+            no_abc,
+            flag_enum,
+            total_ordering,
+            pickle_state,
+            slots,
+            weakref,
+            feature,
+            deprecated,
+            skip,
         } = self;
+        if *skip {
+            // Referred to as `typing.Any` unless both of its types are overridden
+            let as_any = type_override.input.is_none() || type_override.output.is_none();
+            let source_location = source_location(struct_type.span());
+            tokens.append_all(quote! {
+                ::pyo3_stub_gen::type_info::SkippedClassInfo {
+                    struct_id: std::any::TypeId::of::<#struct_type>,
+                    pyclass_name: #pyclass_name,
+                    as_any: #as_any,
+                    source_location: #source_location,
+                }
+            });
+            return;
+        }
         let module = quote_option(module);
+        let feature = quote_option(feature);
+        let deprecated = quote_option(deprecated);
+        let override_tt = |attr: &Option<OverrideTypeAttribute>| match attr {
+            Some(attr) => {
+                let type_info = attr.to_type_info();
+                quote! { Some(|| #type_info) }
+            }
+            None => quote! { None },
+        };
+        let input_override = override_tt(&type_override.input);
+        let output_override = override_tt(&type_override.output);
+        let pickle_state = override_tt(pickle_state);
+        let custom_from_py_object = type_override.custom_from_py_object;
+        let source_location = source_location(struct_type.span());
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyClassInfo {
                 pyclass_name: #pyclass_name,
+                name_given: #name_given,
                 struct_id: std::any::TypeId::of::<#struct_type>,
+                rust_path: concat!(module_path!(), "::", stringify!(#struct_type)),
+                source_location: #source_location,
                 getters: &[ #( #getters),* ],
                 setters: &[ #( #setters),* ],
                 module: #module,
@@ -141,6 +268,21 @@ impl ToTokens for PyClassInfo {
                 has_str: #has_str,
                 subclass: #subclass,
                 is_abstract: #is_abstract,
+                type_override: ::pyo3_stub_gen::type_info::ClassTypeOverride {
+                    input: #input_override,
+                    output: #output_override,
+                    custom_from_py_object: #custom_from_py_object,
+                },
+                dataclass: #dataclass,
+                hidden_base: #hidden_base,
+                no_abc: #no_abc,
+                flag_enum: #flag_enum,
+                total_ordering: #total_ordering,
+                pickle_state: #pickle_state,
+                slots: #slots,
+                weakref: #weakref,
+                feature: #feature,
+                deprecated: #deprecated,
             }
         })
     }
@@ -184,7 +326,10 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyClassInfo {
             pyclass_name: "Placeholder",
+            name_given: true,
             struct_id: std::any::TypeId::of::<PyPlaceholder>,
+            rust_path: concat!(module_path!(), "::", stringify!(PyPlaceholder)),
+            source_location: Some(concat!(file!(), ":", line!())),
             getters: &[
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: "name",
@@ -194,6 +339,10 @@ mod test {
                     deprecated: None,
                     item: false,
                     is_abstract: false,
+                    also_as: &[],
+                    is_classattr: false,
+                    int_value: None,
+                    type_ignored: None,
                 },
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: "ndim",
@@ -203,6 +352,10 @@ mod test {
                     deprecated: None,
                     item: false,
                     is_abstract: false,
+                    also_as: &[],
+                    is_classattr: false,
+                    int_value: None,
+                    type_ignored: None,
                 },
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: "description",
@@ -212,6 +365,10 @@ mod test {
                     deprecated: None,
                     item: false,
                     is_abstract: false,
+                    also_as: &[],
+                    is_classattr: false,
+                    int_value: None,
+                    type_ignored: None,
                 },
             ],
             setters: &[],
@@ -224,6 +381,99 @@ mod test {
             has_str: false,
             subclass: false,
             is_abstract: false,
+            type_override: ::pyo3_stub_gen::type_info::ClassTypeOverride {
+                input: None,
+                output: None,
+                custom_from_py_object: false,
+            },
+            dataclass: false,
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_setter_takes_input_type() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            pub struct Config {
+                #[pyo3(get, set)]
+                path: PathBuf,
+            }
+            "#,
+        )?;
+        let out = PyClassInfo::try_from(input)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyClassInfo {
+            pyclass_name: "Config",
+            name_given: false,
+            struct_id: std::any::TypeId::of::<Config>,
+            rust_path: concat!(module_path!(), "::", stringify!(Config)),
+            source_location: Some(concat!(file!(), ":", line!())),
+            getters: &[
+                ::pyo3_stub_gen::type_info::MemberInfo {
+                    name: "path",
+                    r#type: <PathBuf as ::pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    default: None,
+                    deprecated: None,
+                    item: false,
+                    is_abstract: false,
+                    also_as: &[],
+                    is_classattr: false,
+                    int_value: None,
+                    type_ignored: None,
+                },
+            ],
+            setters: &[
+                ::pyo3_stub_gen::type_info::MemberInfo {
+                    name: "path",
+                    r#type: <PathBuf as ::pyo3_stub_gen::PyStubType>::type_input,
+                    doc: "",
+                    default: None,
+                    deprecated: None,
+                    item: false,
+                    is_abstract: false,
+                    also_as: &[],
+                    is_classattr: false,
+                    int_value: None,
+                    type_ignored: None,
+                },
+            ],
+            module: None,
+            doc: "",
+            bases: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: ::pyo3_stub_gen::type_info::ClassTypeOverride {
+                input: None,
+                output: None,
+                custom_from_py_object: false,
+            },
+            dataclass: false,
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
         }
         "###);
         Ok(())
@@ -242,7 +492,10 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyClassInfo {
             pyclass_name: "MyAbstract",
+            name_given: true,
             struct_id: std::any::TypeId::of::<AbstractThing>,
+            rust_path: concat!(module_path!(), "::", stringify!(AbstractThing)),
+            source_location: Some(concat!(file!(), ":", line!())),
             getters: &[],
             setters: &[],
             module: None,
@@ -254,11 +507,389 @@ mod test {
             has_str: false,
             subclass: false,
             is_abstract: true,
+            type_override: ::pyo3_stub_gen::type_info::ClassTypeOverride {
+                input: None,
+                output: None,
+                custom_from_py_object: false,
+            },
+            dataclass: false,
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
         }
         "###);
         Ok(())
     }
 
+    #[test]
+    fn test_pyclass_type_override() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[gen_stub(
+                type_output_override(type_repr = "str"),
+                type_input_override(type_repr = "str | os.PathLike", imports = ("os")),
+                custom_from_py_object,
+            )]
+            #[pyclass]
+            pub struct Path;
+            "#,
+        )?;
+        let out = PyClassInfo::try_from(input)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyClassInfo {
+            pyclass_name: "Path",
+            name_given: false,
+            struct_id: std::any::TypeId::of::<Path>,
+            rust_path: concat!(module_path!(), "::", stringify!(Path)),
+            source_location: Some(concat!(file!(), ":", line!())),
+            getters: &[],
+            setters: &[],
+            module: None,
+            doc: "",
+            bases: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: ::pyo3_stub_gen::type_info::ClassTypeOverride {
+                input: Some(|| ::pyo3_stub_gen::TypeInfo {
+                    name: "str | os.PathLike".to_string(),
+                    import: ::std::collections::HashSet::from(["os".into()]),
+                }),
+                output: Some(|| ::pyo3_stub_gen::TypeInfo {
+                    name: "str".to_string(),
+                    import: ::std::collections::HashSet::from([]),
+                }),
+                custom_from_py_object: true,
+            },
+            dataclass: false,
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_skipped_pyclass() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(subclass)]
+            #[gen_stub(skip)]
+            pub struct Diagnostics {
+                #[pyo3(get)]
+                pub count: usize,
+            }
+            "#,
+        )?;
+        let info = PyClassInfo::try_from(input)?;
+        insta::assert_snapshot!(format_as_value(info.to_token_stream()), @r###"
+        ::pyo3_stub_gen::type_info::SkippedClassInfo {
+            struct_id: std::any::TypeId::of::<Diagnostics>,
+            pyclass_name: "Diagnostics",
+            as_any: true,
+            source_location: Some(concat!(file!(), ":", line!())),
+        }
+        "###);
+        let stub_type = syn::parse2(StubType::from(&info).to_token_stream())?;
+        insta::assert_snapshot!(prettyplease::unparse(&stub_type), @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Diagnostics {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::any()
+            }
+            fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::any()
+            }
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_abc() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(no_abc)]
+            pub struct Buffer {}
+            "#,
+        )?;
+        assert!(PyClassInfo::try_from(input)?.no_abc);
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            pub struct Buffer {
+                #[gen_stub(no_abc)]
+                data: Vec<u8>,
+            }
+            "#,
+        )?;
+        assert!(PyClassInfo::try_from(input).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_flag_enum() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(frozen)]
+            #[gen_stub(flag_enum)]
+            pub struct Permissions(u32);
+            "#,
+        )?;
+        let out = PyClassInfo::try_from(input)?.to_token_stream().to_string();
+        assert!(out.contains("flag_enum : true"), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_total_ordering() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(total_ordering)]
+            pub struct Version {}
+            "#,
+        )?;
+        let out = PyClassInfo::try_from(input)?.to_token_stream().to_string();
+        assert!(out.contains("total_ordering : true"), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_slots() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(frozen, weakref)]
+            #[gen_stub(slots)]
+            pub struct Point {}
+            "#,
+        )?;
+        let info = PyClassInfo::try_from(input)?;
+        assert!(info.slots && info.weakref);
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(dict)]
+            #[gen_stub(slots)]
+            pub struct Point {}
+            "#,
+        )?;
+        let err = PyClassInfo::try_from(input).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`#[gen_stub(slots)]` cannot be used with `#[pyclass(dict)]`, whose instances accept any attribute"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pickle_state() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(pickle_state = "(int, str, typing.Optional[decimal.Decimal])")]
+            pub struct Record {}
+            "#,
+        )?;
+        let state = PyClassInfo::try_from(input)?.pickle_state.unwrap();
+        assert_eq!(
+            state.type_repr,
+            "tuple[int, str, typing.Optional[decimal.Decimal]]"
+        );
+        assert_eq!(
+            state.imports.into_iter().collect::<Vec<_>>(),
+            ["typing", "decimal"]
+        );
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(pickle_state = " ")]
+            pub struct Record {}
+            "#,
+        )?;
+        let err = PyClassInfo::try_from(input).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`pickle_state` requires a Python type, e.g. `pickle_state = \"(int, str)\"`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_feature() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(feature = "cuda")]
+            pub struct Device {}
+            "#,
+        )?;
+        let info = PyClassInfo::try_from(input)?;
+        assert_eq!(info.feature.as_deref(), Some("cuda"));
+        let out = info.to_token_stream().to_string();
+        assert!(out.contains(r#"feature : Some ("cuda")"#), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_deprecated() -> Result<()> {
+        let mut deprecated = Vec::new();
+        for input in [
+            r#"#[pyclass] #[deprecated(since = "1.4", note = "Use `Device2`")] pub struct Device;"#,
+            r#"#[pyclass] #[deprecated = "Use `Device2`"] pub struct Device;"#,
+            "#[pyclass] #[deprecated] pub struct Device;",
+            "#[pyclass] pub struct Device;",
+        ] {
+            let input: ItemStruct = parse_str(input)?;
+            let out = format_as_value(PyClassInfo::try_from(input)?.to_token_stream());
+            // The field comes last in `PyClassInfo`
+            let field = out
+                .lines()
+                .skip_while(|line| !line.contains("deprecated:"))
+                .take_while(|line| *line != "}");
+            deprecated.push(field.map(str::trim).collect::<Vec<_>>().join(" "));
+        }
+        insta::assert_snapshot!(deprecated.join("\n"), @r###"
+        deprecated: Some(::pyo3_stub_gen::type_info::DeprecatedInfo { since: Some("1.4"), note: Some("Use `Device2`"), }),
+        deprecated: Some(::pyo3_stub_gen::type_info::DeprecatedInfo { since: None, note: Some("Use `Device2`"), }),
+        deprecated: Some(::pyo3_stub_gen::type_info::DeprecatedInfo { since: None, note: None, }),
+        deprecated: None,
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bases() -> Result<()> {
+        let mut bases = Vec::new();
+        for input in [
+            "#[pyclass(extends = other_crate::shapes::Base)] pub struct Child;",
+            "#[pyclass(extends = PyDict)] pub struct Mapping;",
+            "#[pyclass(extends = PyValueError)] pub struct Invalid;",
+        ] {
+            let input: ItemStruct = parse_str(input)?;
+            let out = format_as_value(PyClassInfo::try_from(input)?.to_token_stream());
+            bases.extend(
+                out.lines()
+                    .filter(|line| line.contains("bases:"))
+                    .map(str::trim)
+                    .map(String::from),
+            );
+        }
+        insta::assert_snapshot!(bases.join("\n"), @r###"
+        bases: &[<other_crate::shapes::Base as ::pyo3_stub_gen::PyStubType>::type_output],
+        bases: &[<PyDict as ::pyo3_stub_gen::PyStubType>::type_output],
+        bases: &[<PyValueError as ::pyo3_stub_gen::PyStubType>::type_output],
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_all_fields() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(rename_all = "camelCase")]
+            pub struct Limits {
+                #[pyo3(get, set)]
+                max_value: usize,
+                #[pyo3(get, name = "raw")]
+                raw_data: Vec<u8>,
+            }
+            "#,
+        )?;
+        let out = PyClassInfo::try_from(input)?.to_token_stream().to_string();
+        assert_eq!(out.matches("name : \"maxValue\"").count(), 2, "{out}");
+        assert!(out.contains("name : \"raw\""), "{out}");
+        assert!(
+            !out.contains("max_value") && !out.contains("rawData"),
+            "{out}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_skipped_field() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(get_all)]
+            pub struct Counter {
+                pub count: usize,
+                #[gen_stub(skip)]
+                pub cache: usize,
+            }
+            "#,
+        )?;
+        let info = PyClassInfo::try_from(input)?;
+        assert_eq!(info.getters.len(), 1);
+        assert!(!info.to_token_stream().to_string().contains("cache"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_all_fields() -> Result<()> {
+        // Renamed fields, skipped fields, and pyclass references through `Py<T>`
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(get_all)]
+            pub struct Node {
+                /// Parent of this node
+                #[pyo3(name = "parent_node")]
+                pub parent: Option<Py<Node>>,
+                pub root: Py<Tree>,
+                #[gen_stub(skip)]
+                pub cache: Py<PyAny>,
+            }
+            "#,
+        )?;
+        let getters = PyClassInfo::try_from(input)?.getters;
+        insta::assert_snapshot!(format_as_value(quote! { [#(#getters),*] }), @r###"
+        [
+            ::pyo3_stub_gen::type_info::MemberInfo {
+                name: "parent_node",
+                r#type: <Option<Py<Node>> as ::pyo3_stub_gen::PyStubType>::type_output,
+                doc: "Parent of this node",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+            ::pyo3_stub_gen::type_info::MemberInfo {
+                name: "root",
+                r#type: <Py<Tree> as ::pyo3_stub_gen::PyStubType>::type_output,
+                doc: "",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+        ]
+        "###);
+        Ok(())
+    }
+
     fn format_as_value(tt: TokenStream2) -> String {
         let ttt = quote! { const _: () = #tt; };
         let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
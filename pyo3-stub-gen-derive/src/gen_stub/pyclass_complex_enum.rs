@@ -1,34 +1,50 @@
-use super::{extract_documents, parse_pyo3_attrs, util::quote_option, Attr, StubType};
+use super::{
+    extract_documents, parse_gen_stub_feature, parse_pyo3_attrs,
+    util::{quote_option, source_location},
+    Attr, AttributeLocation, StubType,
+};
 use crate::gen_stub::variant::VariantInfo;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{parse_quote, Error, ItemEnum, Result, Type};
+use syn::{parse_quote, spanned::Spanned, Error, ItemEnum, Result, Type};
 
 pub struct PyComplexEnumInfo {
     pyclass_name: String,
+    /// Whether the name is given by `#[pyclass(name = "...")]`
+    name_given: bool,
     enum_type: Type,
     module: Option<String>,
+    /// Cargo feature given by `#[gen_stub(feature = "...")]`
+    feature: Option<String>,
     variants: Vec<VariantInfo>,
     doc: String,
 }
 
+impl PyComplexEnumInfo {
+    /// Union of the types of the variants, or `None` without any variant
+    fn variant_union(&self) -> Option<TokenStream2> {
+        self.variants
+            .iter()
+            .map(|variant| union_type_for_variant(&self.pyclass_name, variant))
+            .reduce(|acc, expr| quote! { (#acc) | (#expr) })
+    }
+}
+
 impl From<&PyComplexEnumInfo> for StubType {
     fn from(info: &PyComplexEnumInfo) -> Self {
         let PyComplexEnumInfo {
             pyclass_name,
             module,
             enum_type,
-            variants,
             ..
         } = info;
-        let union_terms: Vec<_> = variants
-            .iter()
-            .map(|variant| union_type_for_variant(pyclass_name, variant))
-            .collect();
-        let type_union = (!union_terms.is_empty()).then(|| {
-            let mut iter = union_terms.into_iter();
-            let first = iter.next().unwrap();
-            iter.fold(first, |acc, expr| quote! { (#acc) | (#expr) })
+        let module_tt = match module {
+            Some(module) => quote! { #module.into() },
+            None => quote! { Default::default() },
+        };
+        // Referred to by the alias rendered with the enum, or the union itself if configured so
+        let type_union = info.variant_union().map(|union| {
+            quote! { ::pyo3_stub_gen::TypeInfo::complex_enum(#pyclass_name, #module_tt, || #union) }
         });
         Self {
             ty: enum_type.clone(),
@@ -36,6 +52,7 @@ impl From<&PyComplexEnumInfo> for StubType {
             module: module.clone(),
             type_input_override: type_union.clone(),
             type_output_override: type_union,
+            default_repr: None,
         }
     }
 }
@@ -81,6 +98,8 @@ impl TryFrom<ItemEnum> for PyComplexEnumInfo {
         let mut pyclass_name = None;
         let mut module = None;
         let mut renaming_rule = None;
+        let feature = parse_gen_stub_feature(&attrs, AttributeLocation::Class)?
+            .map(|feature| feature.value());
         let mut bases = Vec::new();
         for attr in parse_pyo3_attrs(&attrs)? {
             match attr {
@@ -93,6 +112,7 @@ impl TryFrom<ItemEnum> for PyComplexEnumInfo {
         }
 
         let enum_type = parse_quote!(#ident);
+        let name_given = pyclass_name.is_some();
         let pyclass_name = pyclass_name.unwrap_or_else(|| ident.clone().to_string());
 
         let mut items = Vec::new();
@@ -104,7 +124,9 @@ impl TryFrom<ItemEnum> for PyComplexEnumInfo {
             doc,
             enum_type,
             pyclass_name,
+            name_given,
             module,
+            feature,
             variants: items,
         })
     }
@@ -114,21 +136,31 @@ impl ToTokens for PyComplexEnumInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
             pyclass_name,
+            name_given,
             enum_type,
             variants,
             doc,
             module,
+            feature,
             ..
         } = self;
         let module = quote_option(module);
+        let feature = quote_option(feature);
+        let variant_union = quote_option(&self.variant_union().map(|union| quote! { || #union }));
 
+        let source_location = source_location(enum_type.span());
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyComplexEnumInfo {
                 pyclass_name: #pyclass_name,
+                name_given: #name_given,
                 enum_id: std::any::TypeId::of::<#enum_type>,
+                rust_path: concat!(module_path!(), "::", stringify!(#enum_type)),
+                source_location: #source_location,
                 variants: &[ #( #variants ),* ],
                 module: #module,
                 doc: #doc,
+                variant_union: #variant_union,
+                feature: #feature,
             }
         })
     }
@@ -157,11 +189,22 @@ mod test {
             }
             "#,
         )?;
-        let out = PyComplexEnumInfo::try_from(input)?.to_token_stream();
+        let info = PyComplexEnumInfo::try_from(input)?;
+        let stub_type = StubType::from(&info).to_token_stream().to_string();
+        assert!(
+            stub_type.contains(
+                r#"fn type_output () -> :: pyo3_stub_gen :: TypeInfo { :: pyo3_stub_gen :: TypeInfo :: complex_enum ("Placeholder" , "my_module" . into () , ||"#
+            ),
+            "{stub_type}"
+        );
+        let out = info.to_token_stream();
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyComplexEnumInfo {
             pyclass_name: "Placeholder",
+            name_given: true,
             enum_id: std::any::TypeId::of::<PyPlaceholder>,
+            rust_path: concat!(module_path!(), "::", stringify!(PyPlaceholder)),
+            source_location: Some(concat!(file!(), ":", line!())),
             variants: &[
                 ::pyo3_stub_gen::type_info::VariantInfo {
                     pyclass_name: "Name",
@@ -174,6 +217,10 @@ mod test {
                             deprecated: None,
                             item: false,
                             is_abstract: false,
+                            also_as: &[],
+                            is_classattr: false,
+                            int_value: None,
+                            type_ignored: None,
                         },
                     ],
                     module: None,
@@ -185,6 +232,9 @@ mod test {
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
                             type_info: <String as ::pyo3_stub_gen::PyStubType>::type_input,
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
                         },
                     ],
                     is_mapping: false,
@@ -200,6 +250,10 @@ mod test {
                             deprecated: None,
                             item: false,
                             is_abstract: false,
+                            also_as: &[],
+                            is_classattr: false,
+                            int_value: None,
+                            type_ignored: None,
                         },
                         ::pyo3_stub_gen::type_info::MemberInfo {
                             name: "_1",
@@ -209,6 +263,10 @@ mod test {
                             deprecated: None,
                             item: false,
                             is_abstract: false,
+                            also_as: &[],
+                            is_classattr: false,
+                            int_value: None,
+                            type_ignored: None,
                         },
                     ],
                     module: None,
@@ -220,6 +278,9 @@ mod test {
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
                             type_info: <i32 as ::pyo3_stub_gen::PyStubType>::type_input,
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "_1",
@@ -228,10 +289,13 @@ mod test {
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr({
                                 fn _fmt() -> String {
                                     let v: f64 = 1.0;
-                                    ::pyo3_stub_gen::util::fmt_py_obj(v)
+                                    ::pyo3_stub_gen::util::fmt_default(v)
                                 }
                                 _fmt
                             }),
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
                         },
                     ],
                     is_mapping: false,
@@ -247,6 +311,10 @@ mod test {
                             deprecated: None,
                             item: false,
                             is_abstract: false,
+                            also_as: &[],
+                            is_classattr: false,
+                            int_value: None,
+                            type_ignored: None,
                         },
                     ],
                     module: None,
@@ -258,6 +326,9 @@ mod test {
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
                             type_info: <usize as ::pyo3_stub_gen::PyStubType>::type_input,
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
                         },
                     ],
                     is_mapping: false,
@@ -274,6 +345,13 @@ mod test {
             ],
             module: Some("my_module"),
             doc: "",
+            variant_union: Some(|| {
+                (((<String as ::pyo3_stub_gen::PyStubType>::type_input())
+                    | (::pyo3_stub_gen::TypeInfo::unqualified("Placeholder.twonum")))
+                    | (::pyo3_stub_gen::TypeInfo::unqualified("Placeholder.ndim")))
+                    | (::pyo3_stub_gen::TypeInfo::unqualified("Placeholder.description"))
+            }),
+            feature: None,
         }
         "###);
         Ok(())
@@ -1,14 +1,24 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{parse_quote, Error, ItemEnum, Result, Type};
+use syn::{parse_quote, spanned::Spanned, Error, Ident, ItemEnum, Result, Type};
 
-use super::{extract_documents, parse_pyo3_attrs, util::quote_option, Attr, StubType};
+use super::{
+    extract_documents, parse_gen_stub_feature, parse_pyo3_attrs,
+    util::{quote_option, source_location},
+    Attr, AttributeLocation, StubType,
+};
 
 pub struct PyEnumInfo {
     pyclass_name: String,
+    /// Whether the name is given by `#[pyclass(name = "...")]`
+    name_given: bool,
     enum_type: Type,
     module: Option<String>,
+    /// Cargo feature given by `#[gen_stub(feature = "...")]`
+    feature: Option<String>,
     variants: Vec<(String, String)>,
+    /// Rust names of the variants, in the same order as `variants`
+    variant_idents: Vec<Ident>,
     doc: String,
 }
 
@@ -18,14 +28,26 @@ impl From<&PyEnumInfo> for StubType {
             pyclass_name,
             module,
             enum_type,
+            variants,
+            variant_idents,
             ..
         } = info;
+        // A unit variant is rendered as `LogLevel.Info` by the name of the enum in the stub
+        let names = variants.iter().map(|(name, _)| name);
+        let default_repr = quote! {
+            let variant = match value {
+                #(Self::#variant_idents => #names,)*
+            };
+            let name = <Self as ::pyo3_stub_gen::PyStubType>::type_input().name;
+            Some(format!("{name}.{variant}"))
+        };
         Self {
             ty: enum_type.clone(),
             name: pyclass_name.clone(),
             module: module.clone(),
             type_input_override: None,
             type_output_override: None,
+            default_repr: Some(default_repr),
         }
     }
 }
@@ -44,6 +66,8 @@ impl TryFrom<ItemEnum> for PyEnumInfo {
         let mut pyclass_name = None;
         let mut module = None;
         let mut renaming_rule = None;
+        let feature = parse_gen_stub_feature(&attrs, AttributeLocation::Class)?
+            .map(|feature| feature.value());
         for attr in parse_pyo3_attrs(&attrs)? {
             match attr {
                 Attr::Name(name) => pyclass_name = Some(name),
@@ -53,7 +77,9 @@ impl TryFrom<ItemEnum> for PyEnumInfo {
             }
         }
         let struct_type = parse_quote!(#ident);
+        let name_given = pyclass_name.is_some();
         let pyclass_name = pyclass_name.unwrap_or_else(|| ident.to_string());
+        let variant_idents = variants.iter().map(|var| var.ident.clone()).collect();
         let variants = variants
             .into_iter()
             .map(|var| -> Result<(String, String)> {
@@ -75,8 +101,11 @@ impl TryFrom<ItemEnum> for PyEnumInfo {
             doc,
             enum_type: struct_type,
             pyclass_name,
+            name_given,
             module,
+            feature,
             variants,
+            variant_idents,
         })
     }
 }
@@ -85,24 +114,137 @@ impl ToTokens for PyEnumInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
             pyclass_name,
+            name_given,
             enum_type,
             variants,
             doc,
             module,
+            feature,
+            ..
         } = self;
         let module = quote_option(module);
+        let feature = quote_option(feature);
         let variants: Vec<_> = variants
             .iter()
             .map(|(name, doc)| quote! {(#name,#doc)})
             .collect();
+        let source_location = source_location(enum_type.span());
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyEnumInfo {
                 pyclass_name: #pyclass_name,
+                name_given: #name_given,
                 enum_id: std::any::TypeId::of::<#enum_type>,
+                rust_path: concat!(module_path!(), "::", stringify!(#enum_type)),
+                source_location: #source_location,
                 variants: &[ #(#variants),* ],
                 module: #module,
                 doc: #doc,
+                feature: #feature,
             }
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    #[test]
+    fn test_default_repr_of_variants() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass(eq, eq_int, module = "my_module")]
+            #[pyo3(rename_all = "UPPERCASE")]
+            pub enum LogLevel {
+                Info,
+                #[pyo3(name = "WARN")]
+                Warning,
+            }
+            "#,
+        )?;
+        let info = PyEnumInfo::try_from(input)?;
+        let out = StubType::from(&info).to_token_stream();
+        insta::assert_snapshot!(prettyplease::unparse(&syn::parse_file(&out.to_string())?), @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for LogLevel {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::locally_defined("LogLevel", "my_module".into())
+            }
+            fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::locally_defined("LogLevel", "my_module".into())
+            }
+            fn default_repr(value: &Self) -> Option<String> {
+                let variant = match value {
+                    Self::Info => "INFO",
+                    Self::Warning => "WARN",
+                };
+                let name = <Self as ::pyo3_stub_gen::PyStubType>::type_input().name;
+                Some(format!("{name}.{variant}"))
+            }
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_documented_variants() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            /// Severity of a message
+            #[pyclass(eq, eq_int)]
+            pub enum LogLevel {
+                /// Informational message,
+                /// shown by default
+                Info,
+                Warning,
+            }
+            "#,
+        )?;
+        let out = PyEnumInfo::try_from(input)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyEnumInfo {
+            pyclass_name: "LogLevel",
+            name_given: false,
+            enum_id: std::any::TypeId::of::<LogLevel>,
+            rust_path: concat!(module_path!(), "::", stringify!(LogLevel)),
+            source_location: Some(concat!(file!(), ":", line!())),
+            variants: &[("Info", "Informational message,\nshown by default"), ("Warning", "")],
+            module: None,
+            doc: "Severity of a message",
+            feature: None,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_feature() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass(eq, eq_int)]
+            #[gen_stub(feature = "cuda")]
+            pub enum Stream {
+                Default,
+            }
+            "#,
+        )?;
+        let info = PyEnumInfo::try_from(input)?;
+        assert_eq!(info.feature.as_deref(), Some("cuda"));
+        let out = info.to_token_stream().to_string();
+        assert!(out.contains(r#"feature : Some ("cuda")"#), "{out}");
+        Ok(())
+    }
+
+    fn format_as_value(tt: TokenStream2) -> String {
+        let ttt = quote! { const _: () = #tt; };
+        let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
+        formatted
+            .trim()
+            .strip_prefix("const _: () = ")
+            .unwrap()
+            .strip_suffix(';')
+            .unwrap()
+            .to_string()
+    }
+}
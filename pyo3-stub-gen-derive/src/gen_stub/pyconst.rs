@@ -0,0 +1,311 @@
+use super::{
+    attr::IgnoreTarget, extract_deprecated, extract_documents, parse_gen_stub_type_ignore,
+    quote_option, util::source_location, DeprecatedInfo,
+};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    Error, Expr, Item, ItemConst, ItemStatic, LitStr, Result, Type, UnOp,
+};
+
+/// Arguments of `#[gen_stub_pyconst(module = "...", name = "...")]`
+pub struct PyConstAttr {
+    module: LitStr,
+    name: Option<LitStr>,
+}
+
+impl Parse for PyConstAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut module = None;
+        let mut name = None;
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let _: syn::token::Eq = input.parse()?;
+            match key.to_string().as_str() {
+                "module" => module = Some(input.parse()?),
+                "name" => name = Some(input.parse()?),
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("Unknown parameter: {}", key),
+                    ));
+                }
+            }
+            if input.peek(syn::token::Comma) {
+                let _: syn::token::Comma = input.parse()?;
+            } else {
+                break;
+            }
+        }
+        let module = module.ok_or_else(|| {
+            Error::new(
+                input.span(),
+                "`module = \"...\"` is required for `#[gen_stub_pyconst]`",
+            )
+        })?;
+        Ok(Self { module, name })
+    }
+}
+
+/// Module variable declared by a `const` or a `static` item
+pub struct PyConstInfo {
+    name: String,
+    module: String,
+    r#type: Type,
+    /// Initializer, if it is a literal which can be rendered as the value
+    value: Option<Expr>,
+    doc: String,
+    deprecated: Option<DeprecatedInfo>,
+    type_ignored: Option<IgnoreTarget>,
+    span: Span,
+}
+
+impl PyConstInfo {
+    pub fn new(attr: PyConstAttr, item: &Item) -> Result<Self> {
+        let (attrs, ident, ty, expr) = match item {
+            Item::Const(ItemConst {
+                attrs,
+                ident,
+                ty,
+                expr,
+                ..
+            })
+            | Item::Static(ItemStatic {
+                attrs,
+                ident,
+                ty,
+                expr,
+                ..
+            }) => (attrs, ident, ty, expr),
+            _ => {
+                return Err(Error::new_spanned(
+                    item,
+                    "`#[gen_stub_pyconst]` is only valid for `const` and `static` items",
+                ))
+            }
+        };
+        let is_literal = match expr.as_ref() {
+            Expr::Lit(_) => true,
+            Expr::Unary(unary) => {
+                matches!(unary.op, UnOp::Neg(_)) && matches!(unary.expr.as_ref(), Expr::Lit(_))
+            }
+            _ => false,
+        };
+        Ok(Self {
+            name: attr
+                .name
+                .map_or_else(|| ident.unraw().to_string(), |name| name.value()),
+            module: attr.module.value(),
+            r#type: ty.as_ref().clone(),
+            value: is_literal.then(|| expr.as_ref().clone()),
+            doc: extract_documents(attrs).join("\n"),
+            deprecated: extract_deprecated(attrs),
+            type_ignored: parse_gen_stub_type_ignore(attrs)?,
+            span: ident.span(),
+        })
+    }
+}
+
+impl ToTokens for PyConstInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            name,
+            module,
+            r#type,
+            value,
+            doc,
+            deprecated,
+            type_ignored,
+            span,
+        } = self;
+        let source_location = source_location(*span);
+        let deprecated = quote_option(deprecated);
+        let type_ignored = quote_option(type_ignored);
+        let default = match value {
+            Some(value) => quote! {
+                Some({
+                    fn _fmt() -> String {
+                        let v: #r#type = #value;
+                        ::pyo3_stub_gen::util::fmt_py_obj(v)
+                    }
+                    _fmt
+                })
+            },
+            None => quote! { None },
+        };
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::PyVariableInfo {
+                name: #name,
+                module: #module,
+                rust_path: module_path!(),
+                source_location: #source_location,
+                r#type: <#r#type as ::pyo3_stub_gen::PyStubType>::type_output,
+                default: #default,
+                doc: #doc,
+                deprecated: #deprecated,
+                type_ignored: #type_ignored,
+            }
+        })
+    }
+}
+
+// `#[gen_stub(xxx)]` is not a valid proc_macro_attribute, so remove it before printing the item back
+pub fn prune_attrs(item: &mut Item) {
+    if let Item::Const(ItemConst { attrs, .. }) | Item::Static(ItemStatic { attrs, .. }) = item {
+        super::attr::prune_attrs(attrs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::{parse2, parse_str};
+
+    fn expand(attr: TokenStream2, item: &str) -> Result<String> {
+        let info = PyConstInfo::new(parse2(attr)?, &parse_str(item)?)?;
+        let tt = info.to_token_stream();
+        let ttt = quote! { const _: () = #tt; };
+        let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
+        Ok(formatted
+            .trim()
+            .strip_prefix("const _: () = ")
+            .unwrap()
+            .strip_suffix(';')
+            .unwrap()
+            .to_string())
+    }
+
+    #[test]
+    fn test_literal_const() -> Result<()> {
+        let out = expand(
+            quote! { module = "mypkg._core" },
+            "pub const MAX_ITER: usize = 100;",
+        )?;
+        insta::assert_snapshot!(out, @r###"
+        ::pyo3_stub_gen::type_info::PyVariableInfo {
+            name: "MAX_ITER",
+            module: "mypkg._core",
+            rust_path: module_path!(),
+            source_location: Some(concat!(file!(), ":", line!())),
+            r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_output,
+            default: Some({
+                fn _fmt() -> String {
+                    let v: usize = 100;
+                    ::pyo3_stub_gen::util::fmt_py_obj(v)
+                }
+                _fmt
+            }),
+            doc: "",
+            deprecated: None,
+            type_ignored: None,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_renamed_negative_const() -> Result<()> {
+        let out = expand(
+            quote! { module = "mypkg._core", name = "OFFSET" },
+            "const DEFAULT_OFFSET: i32 = -1;",
+        )?;
+        insta::assert_snapshot!(out, @r###"
+        ::pyo3_stub_gen::type_info::PyVariableInfo {
+            name: "OFFSET",
+            module: "mypkg._core",
+            rust_path: module_path!(),
+            source_location: Some(concat!(file!(), ":", line!())),
+            r#type: <i32 as ::pyo3_stub_gen::PyStubType>::type_output,
+            default: Some({
+                fn _fmt() -> String {
+                    let v: i32 = -1;
+                    ::pyo3_stub_gen::util::fmt_py_obj(v)
+                }
+                _fmt
+            }),
+            doc: "",
+            deprecated: None,
+            type_ignored: None,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_static_without_literal() -> Result<()> {
+        let out = expand(
+            quote! { module = "mypkg._core" },
+            r#"
+            /// Names of the items
+            static NAMES: &[&str] = &["a", "b"];
+            "#,
+        )?;
+        insta::assert_snapshot!(out, @r###"
+        ::pyo3_stub_gen::type_info::PyVariableInfo {
+            name: "NAMES",
+            module: "mypkg._core",
+            rust_path: module_path!(),
+            source_location: Some(concat!(file!(), ":", line!())),
+            r#type: <&[&str] as ::pyo3_stub_gen::PyStubType>::type_output,
+            default: None,
+            doc: "Names of the items",
+            deprecated: None,
+            type_ignored: None,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deprecated_with_type_ignore() -> Result<()> {
+        let out = expand(
+            quote! { module = "mypkg._core" },
+            r#"
+            #[deprecated(note = "use MAX_ITER")]
+            #[gen_stub(type_ignore = ["misc"])]
+            pub const MAX_ITERATIONS: usize = 100;
+            "#,
+        )?;
+        insta::assert_snapshot!(out, @r###"
+        ::pyo3_stub_gen::type_info::PyVariableInfo {
+            name: "MAX_ITERATIONS",
+            module: "mypkg._core",
+            rust_path: module_path!(),
+            source_location: Some(concat!(file!(), ":", line!())),
+            r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_output,
+            default: Some({
+                fn _fmt() -> String {
+                    let v: usize = 100;
+                    ::pyo3_stub_gen::util::fmt_py_obj(v)
+                }
+                _fmt
+            }),
+            doc: "",
+            deprecated: Some(::pyo3_stub_gen::type_info::DeprecatedInfo {
+                since: None,
+                note: Some("use MAX_ITER"),
+            }),
+            type_ignored: Some(
+                ::pyo3_stub_gen::type_info::IgnoreTarget::Specified(&["misc"] as &[&str]),
+            ),
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = expand(quote! {}, "const A: i32 = 1;").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`module = \"...\"` is required for `#[gen_stub_pyconst]`"
+        );
+        let err = expand(quote! { module = "m" }, "fn f() {}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`#[gen_stub_pyconst]` is only valid for `const` and `static` items"
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream},
     Error, FnArg, ItemFn, Result,
 };
@@ -9,29 +10,41 @@ use crate::gen_stub::util::TypeOrOverride;
 
 use super::{
     attr::IgnoreTarget, extract_deprecated, extract_documents, extract_return_type,
-    parameter::Parameters, parse_args, parse_gen_stub_type_ignore, parse_pyo3_attrs, quote_option,
-    Attr, DeprecatedInfo,
+    parameter::Parameters, parse_args, parse_gen_stub_also_as, parse_gen_stub_deprecated_params,
+    parse_gen_stub_feature, parse_gen_stub_getitem, parse_gen_stub_is_awaitable,
+    parse_gen_stub_keep_signature, parse_gen_stub_overload_groups, parse_gen_stub_type_ignore,
+    parse_pyo3_attrs, quote_option, util::source_location, Attr, AttributeLocation, DeprecatedInfo,
 };
 
 pub struct PyFunctionInfo {
     pub(crate) name: String,
+    /// Name of the Rust function, or the Python name for functions declared by a Python stub
+    pub(crate) rust_name: String,
     pub(crate) parameters: Parameters,
     pub(crate) r#return: Option<TypeOrOverride>,
     pub(crate) doc: String,
     pub(crate) module: Option<String>,
     pub(crate) is_async: bool,
+    /// Rendered by `def` returning `collections.abc.Awaitable[...]`, by `#[gen_stub(awaitable)]`
+    pub(crate) is_awaitable: bool,
     pub(crate) deprecated: Option<DeprecatedInfo>,
     pub(crate) type_ignored: Option<IgnoreTarget>,
+    /// Cargo feature given by `#[gen_stub(feature = "...")]`
+    pub(crate) feature: Option<String>,
+    /// Span of the Rust function, whose file and line are reported in diagnostics
+    pub(crate) span: Span,
 }
 
-struct PyFunctionAttr {
-    module: Option<String>,
-    python: Option<syn::LitStr>,
+/// Arguments of `#[gen_stub_pyfunction(...)]`
+pub struct PyFunctionAttr {
+    /// Modules given by `module = "..."`, which is repeated for a function registered under several modules
+    pub(crate) modules: Vec<String>,
+    pub(crate) python: Option<syn::LitStr>,
 }
 
 impl Parse for PyFunctionAttr {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut module = None;
+        let mut modules: Vec<String> = Vec::new();
         let mut python = None;
 
         // Parse comma-separated key-value pairs
@@ -42,7 +55,13 @@ impl Parse for PyFunctionAttr {
             match key.to_string().as_str() {
                 "module" => {
                     let value: syn::LitStr = input.parse()?;
-                    module = Some(value.value());
+                    if modules.contains(&value.value()) {
+                        return Err(Error::new(
+                            value.span(),
+                            format!("Module `{}` is given twice", value.value()),
+                        ));
+                    }
+                    modules.push(value.value());
                 }
                 "python" => {
                     let value: syn::LitStr = input.parse()?;
@@ -64,25 +83,23 @@ impl Parse for PyFunctionAttr {
             }
         }
 
-        Ok(Self { module, python })
+        Ok(Self { modules, python })
     }
 }
 
 impl PyFunctionInfo {
-    /// Parse attribute and return python stub string if present
-    pub fn parse_attr(&mut self, attr: TokenStream2) -> Result<Option<syn::LitStr>> {
-        if attr.is_empty() {
-            return Ok(None);
-        }
-        let parsed_attr: PyFunctionAttr = syn::parse2(attr)?;
-
-        // Set module if provided
-        if let Some(module) = parsed_attr.module {
-            self.module = Some(module);
+    /// Metadata of this function registered under each of `modules`, or under the default module if empty
+    pub fn submissions(mut self, modules: Vec<String>) -> Vec<TokenStream2> {
+        if modules.is_empty() {
+            return vec![self.to_token_stream()];
         }
-
-        // Return python stub string if provided
-        Ok(parsed_attr.python)
+        modules
+            .into_iter()
+            .map(|module| {
+                self.module = Some(module);
+                self.to_token_stream()
+            })
+            .collect()
     }
 }
 
@@ -92,6 +109,33 @@ impl TryFrom<ItemFn> for PyFunctionInfo {
         let doc = extract_documents(&item.attrs).join("\n");
         let deprecated = extract_deprecated(&item.attrs);
         let type_ignored = parse_gen_stub_type_ignore(&item.attrs)?;
+        let is_awaitable = parse_gen_stub_is_awaitable(&item.attrs)?;
+        if !parse_gen_stub_also_as(&item.attrs)?.is_empty() {
+            return Err(Error::new_spanned(
+                &item.sig.ident,
+                "`also_as(...)` is only valid for methods and properties",
+            ));
+        }
+        if !parse_gen_stub_overload_groups(&item.attrs)?.is_empty() {
+            return Err(Error::new_spanned(
+                &item.sig.ident,
+                "`overload_groups(...)` is only valid for methods",
+            ));
+        }
+        if parse_gen_stub_getitem(&item.attrs)?.is_some() {
+            return Err(Error::new_spanned(
+                &item.sig.ident,
+                "`getitem(...)` is only valid for methods",
+            ));
+        }
+        if parse_gen_stub_keep_signature(&item.attrs)? {
+            return Err(Error::new_spanned(
+                &item.sig.ident,
+                "`keep_signature` is only valid for methods",
+            ));
+        }
+        let feature = parse_gen_stub_feature(&item.attrs, AttributeLocation::Function)?
+            .map(|feature| feature.value());
         let args = parse_args(item.sig.inputs)?;
         let r#return = extract_return_type(&item.sig.output, &item.attrs)?;
         let mut name = None;
@@ -103,24 +147,29 @@ impl TryFrom<ItemFn> for PyFunctionInfo {
                 _ => {}
             }
         }
-        let name = name.unwrap_or_else(|| item.sig.ident.to_string());
+        let name = name.unwrap_or_else(|| item.sig.ident.unraw().to_string());
 
         // Build parameters from args and signature
-        let parameters = if let Some(sig) = sig {
+        let mut parameters = if let Some(sig) = sig {
             Parameters::new_with_sig(&args, &sig)?
         } else {
             Parameters::new(&args)
         };
+        parameters.deprecate(parse_gen_stub_deprecated_params(&item.attrs)?, &name)?;
 
         Ok(Self {
             name,
+            rust_name: item.sig.ident.to_string(),
             parameters,
             r#return,
             doc,
             module: None,
             is_async: item.sig.asyncness.is_some(),
+            is_awaitable,
             deprecated,
             type_ignored,
+            feature,
+            span: item.sig.ident.span(),
         })
     }
 }
@@ -130,12 +179,16 @@ impl ToTokens for PyFunctionInfo {
         let Self {
             r#return: ret,
             name,
+            rust_name,
             doc,
             parameters,
             module,
             is_async,
+            is_awaitable,
             deprecated,
             type_ignored,
+            feature,
+            span,
         } = self;
         let ret_tt = if let Some(ret) = ret {
             match ret {
@@ -155,33 +208,27 @@ impl ToTokens for PyFunctionInfo {
         } else {
             quote! { ::pyo3_stub_gen::type_info::no_return_type_output }
         };
+        let ret_tt = if *is_awaitable {
+            quote! { || ::pyo3_stub_gen::TypeInfo::awaitable((#ret_tt)()) }
+        } else {
+            ret_tt
+        };
+        let is_async = *is_async && !is_awaitable;
         // let sig_tt = quote_option(sig);
         let module_tt = quote_option(module);
+        let feature_tt = quote_option(feature);
+        let source_location = source_location(*span);
         let deprecated_tt = deprecated
             .as_ref()
             .map(|d| quote! { Some(#d) })
             .unwrap_or_else(|| quote! { None });
-        let type_ignored_tt = if let Some(target) = type_ignored {
-            match target {
-                IgnoreTarget::All => {
-                    quote! { Some(::pyo3_stub_gen::type_info::IgnoreTarget::All) }
-                }
-                IgnoreTarget::SpecifiedLits(rules) => {
-                    let rule_strs: Vec<String> = rules.iter().map(|lit| lit.value()).collect();
-                    quote! {
-                        Some(::pyo3_stub_gen::type_info::IgnoreTarget::Specified(
-                            &[#(#rule_strs),*] as &[&str]
-                        ))
-                    }
-                }
-            }
-        } else {
-            quote! { None }
-        };
+        let type_ignored_tt = quote_option(type_ignored);
 
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyFunctionInfo {
                 name: #name,
+                rust_path: concat!(module_path!(), "::", #rust_name),
+                source_location: #source_location,
                 parameters: #parameters,
                 r#return: #ret_tt,
                 doc: #doc,
@@ -189,6 +236,7 @@ impl ToTokens for PyFunctionInfo {
                 is_async: #is_async,
                 deprecated: #deprecated_tt,
                 type_ignored: #type_ignored_tt,
+                feature: #feature_tt,
             }
         })
     }
@@ -205,3 +253,217 @@ pub fn prune_attrs(item_fn: &mut ItemFn) {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    #[test]
+    fn raw_identifiers() -> Result<()> {
+        for item_fn in [
+            "fn configure(r#type: String, r#async: bool) {}",
+            "#[pyo3(signature = (r#type, r#async = false))] fn configure(r#type: String, r#async: bool) {}",
+        ] {
+            let info = PyFunctionInfo::try_from(parse_str::<ItemFn>(item_fn)?)?;
+            let out = info.to_token_stream().to_string();
+            assert!(out.contains(r#"name : "type""#), "{out}");
+            assert!(out.contains(r#"name : "async""#), "{out}");
+            assert!(!out.contains("\"r#"), "{out}");
+        }
+        let info = PyFunctionInfo::try_from(parse_str::<ItemFn>("fn r#match() {}")?)?;
+        assert_eq!(info.name, "match");
+        Ok(())
+    }
+
+    #[test]
+    fn method_only_keywords() {
+        let error = |item_fn: &str| {
+            PyFunctionInfo::try_from(parse_str::<ItemFn>(item_fn).unwrap())
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            error(
+                r#"#[gen_stub(overload_groups(("a"), ("b")))] fn f(a: Option<i32>, b: Option<i32>) {}"#
+            ),
+            "`overload_groups(...)` is only valid for methods"
+        );
+        assert_eq!(
+            error(r#"#[gen_stub(getitem(int -> "u8", slice -> "Vec<u8>"))] fn f(key: i32) {}"#),
+            "`getitem(...)` is only valid for methods"
+        );
+    }
+
+    #[test]
+    fn from_py_with_parameter() -> Result<()> {
+        let item_fn: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            fn lookup(
+                #[pyo3(from_py_with = parse_id)]
+                #[gen_stub(accepts = "str | int")]
+                id: u64,
+                limit: u64,
+            ) -> u64 {
+                id
+            }
+            "#,
+        )?;
+        let tt = PyFunctionInfo::try_from(item_fn)?.to_token_stream();
+        let ttt = quote::quote! { const _: () = #tt; };
+        let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
+        insta::assert_snapshot!(formatted, @r###"
+        const _: () = ::pyo3_stub_gen::type_info::PyFunctionInfo {
+            name: "lookup",
+            rust_path: concat!(module_path!(), "::", "lookup"),
+            source_location: Some(concat!(file!(), ":", line!())),
+            parameters: &[
+                ::pyo3_stub_gen::type_info::ParameterInfo {
+                    name: "id",
+                    kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                    type_info: || ::pyo3_stub_gen::TypeInfo {
+                        name: "str | int".to_string(),
+                        import: ::std::collections::HashSet::from([]),
+                    },
+                    default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: true,
+                    container_default: None,
+                    deprecated: None,
+                },
+                ::pyo3_stub_gen::type_info::ParameterInfo {
+                    name: "limit",
+                    kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                    type_info: <u64 as ::pyo3_stub_gen::PyStubType>::type_input,
+                    default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                    type_overridden: false,
+                    container_default: None,
+                    deprecated: None,
+                },
+            ],
+            r#return: <u64 as pyo3_stub_gen::PyStubType>::type_output,
+            doc: "",
+            module: None,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            feature: None,
+        };
+        "###);
+
+        let item_fn: ItemFn = parse_str(
+            r#"fn load(#[gen_stub(accepts = "decimal.Decimal | str", literal("a"))] value: u64) {}"#,
+        )?;
+        let err = PyFunctionInfo::try_from(item_fn).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`literal(...)` cannot be combined with `accepts = \"...\"`"
+        );
+
+        let accepts = crate::gen_stub::util::accepts_type(
+            &parse_str("u64")?,
+            &parse_str(r#""decimal.Decimal | tuple[int, ...]""#)?,
+        );
+        let TypeOrOverride::OverrideType { imports, .. } = accepts else {
+            panic!("accepts should override the type");
+        };
+        assert_eq!(imports.into_iter().collect::<Vec<_>>(), ["decimal"]);
+        Ok(())
+    }
+
+    #[test]
+    fn no_return() -> Result<()> {
+        for item_fn in [
+            "#[gen_stub(no_return)] fn abort(msg: &str) -> PyResult<()> { todo!() }",
+            "#[deprecated] #[gen_stub(no_return)] fn exit() { todo!() }",
+        ] {
+            let info = PyFunctionInfo::try_from(parse_str::<ItemFn>(item_fn)?)?;
+            let out = info.to_token_stream().to_string();
+            assert!(out.contains(r#"name : "typing.Never""#), "{out}");
+        }
+        let item_fn: ItemFn = parse_str(
+            r#"
+            #[gen_stub(no_return, override_return_type(type_repr = "typing.NoReturn", imports = ("typing")))]
+            fn abort() {}
+            "#,
+        )?;
+        let err = PyFunctionInfo::try_from(item_fn).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "`no_return` cannot be combined with `override_return_type(...)`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deprecated_param() -> Result<()> {
+        let item_fn: ItemFn = parse_str(
+            r#"
+            #[gen_stub(deprecated_param(name = "old_size", note = "use size"))]
+            #[gen_stub(deprecated_param(name = "legacy"))]
+            fn resize(size: usize, old_size: Option<usize>, legacy: bool) {}
+            "#,
+        )?;
+        let out = PyFunctionInfo::try_from(item_fn)?
+            .to_token_stream()
+            .to_string();
+        assert_eq!(out.matches("deprecated : None").count(), 2, "{out}");
+        assert!(out.contains(r#"deprecated : Some ("use size")"#), "{out}");
+        assert!(out.contains(r#"deprecated : Some ("")"#), "{out}");
+
+        let item_fn: ItemFn = parse_str(
+            r#"
+            #[gen_stub(deprecated_param(name = "old_size"))]
+            fn resize(size: usize) {}
+            "#,
+        )?;
+        let err = PyFunctionInfo::try_from(item_fn).err().unwrap();
+        assert_eq!(err.to_string(), "`old_size` is not a parameter of `resize`");
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_modules() -> Result<()> {
+        let out = crate::gen_stub::pyfunction(
+            quote::quote! { module = "mypkg._core", module = "mypkg.compat" },
+            quote::quote! {
+                #[pyfunction]
+                fn ping() -> bool {
+                    true
+                }
+            },
+        )?;
+        let formatted = prettyplease::unparse(&syn::parse_file(&out.to_string())?);
+        insta::assert_snapshot!(formatted, @r###"
+        #[pyfunction]
+        fn ping() -> bool {
+            true
+        }
+        #[automatically_derived]
+        pyo3_stub_gen::inventory::submit! {
+            ::pyo3_stub_gen::type_info::PyFunctionInfo { name : "ping", rust_path :
+            concat!(module_path!(), "::", "ping"), source_location : Some(concat!(file!(), ":",
+            line!())), parameters : & [], r#return : < bool as pyo3_stub_gen::PyStubType >
+            ::type_output, doc : "", module : Some("mypkg._core"), is_async : false, deprecated :
+            None, type_ignored : None, feature : None, }
+        }
+        #[automatically_derived]
+        pyo3_stub_gen::inventory::submit! {
+            ::pyo3_stub_gen::type_info::PyFunctionInfo { name : "ping", rust_path :
+            concat!(module_path!(), "::", "ping"), source_location : Some(concat!(file!(), ":",
+            line!())), parameters : & [], r#return : < bool as pyo3_stub_gen::PyStubType >
+            ::type_output, doc : "", module : Some("mypkg.compat"), is_async : false, deprecated
+            : None, type_ignored : None, feature : None, }
+        }
+        "###);
+
+        let err = crate::gen_stub::pyfunction(
+            quote::quote! { module = "mypkg._core", module = "mypkg._core" },
+            quote::quote! { fn ping() {} },
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Module `mypkg._core` is given twice");
+        Ok(())
+    }
+}
@@ -1,9 +1,17 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{Error, FnArg, ImplItem, ItemImpl, Result, Type};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Error, FnArg, ImplItem, ItemImpl, Result, Type,
+};
 
 use super::{
-    attr::{parse_gen_stub_allow, parse_gen_stub_skip},
+    attr::{
+        parse_gen_stub_allow, parse_gen_stub_getitem, parse_gen_stub_overload_groups,
+        parse_gen_stub_skip,
+    },
+    util::source_location,
     MemberInfo, MethodInfo,
 };
 
@@ -16,6 +24,59 @@ pub struct PyMethodsInfo {
     pub(crate) methods: Vec<MethodInfo>,
 }
 
+/// Arguments of `#[gen_stub_pymethods(...)]`
+#[derive(Debug, Default)]
+pub struct PyMethodsAttr {
+    /// Concrete type the metadata is registered for, e.g. `self_ty = PyWrapper<f64>`
+    self_ty: Option<Type>,
+}
+
+impl Parse for PyMethodsAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut self_ty = None;
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let _: syn::token::Eq = input.parse()?;
+            match key.to_string().as_str() {
+                "self_ty" => self_ty = Some(input.parse()?),
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("Unknown parameter: {}", key),
+                    ));
+                }
+            }
+            if input.peek(syn::token::Comma) {
+                let _: syn::token::Comma = input.parse()?;
+            } else {
+                break;
+            }
+        }
+        Ok(Self { self_ty })
+    }
+}
+
+impl PyMethodsAttr {
+    /// Replace the self type of `item` by `self_ty`.
+    ///
+    /// The metadata is keyed by the `TypeId` of the self type, which a generic impl cannot provide,
+    /// so that `self_ty` is required for them.
+    pub fn apply(self, mut item: ItemImpl) -> Result<ItemImpl> {
+        match self.self_ty {
+            Some(self_ty) => item.self_ty = Box::new(self_ty),
+            None if !item.generics.params.is_empty() => {
+                return Err(Error::new_spanned(
+                    &item.generics,
+                    "`#[gen_stub_pymethods]` cannot register a generic impl; \
+                     specify the concrete class by `#[gen_stub_pymethods(self_ty = ...)]`",
+                ));
+            }
+            None => {}
+        }
+        Ok(item)
+    }
+}
+
 impl TryFrom<ItemImpl> for PyMethodsInfo {
     type Error = Error;
     fn try_from(item: ItemImpl) -> Result<Self> {
@@ -70,9 +131,13 @@ impl TryFrom<ItemImpl> for PyMethodsInfo {
                         attrs.push(MemberInfo::new_classattr_fn(item_fn)?);
                         continue;
                     }
-                    let mut method = MethodInfo::try_from(item_fn)?;
-                    method.replace_self(&item.self_ty);
-                    methods.push(method);
+                    let overload_groups = parse_gen_stub_overload_groups(&item_fn.attrs)?;
+                    let getitem = parse_gen_stub_getitem(&item_fn.attrs)?;
+                    let method = MethodInfo::try_from(item_fn)?;
+                    for mut method in method.split_getitem(getitem.as_ref())? {
+                        method.replace_self(&item.self_ty);
+                        methods.extend(method.split_overload_groups(&overload_groups)?);
+                    }
                 }
                 _ => continue,
             }
@@ -96,9 +161,12 @@ impl ToTokens for PyMethodsInfo {
             setters,
             methods,
         } = self;
+        let source_location = source_location(struct_id.span());
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyMethodsInfo {
                 struct_id: std::any::TypeId::of::<#struct_id>,
+                source_location: #source_location,
+                struct_name: std::any::type_name::<#struct_id>,
                 attrs: &[ #(#attrs),* ],
                 getters: &[ #(#getters),* ],
                 setters: &[ #(#setters),* ],
@@ -128,6 +196,7 @@ pub fn prune_attrs(item_impl: &mut ItemImpl) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gen_stub::{parameter::DefaultExpr, util::TypeOrOverride};
     use syn::parse_quote;
 
     #[test]
@@ -178,6 +247,299 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn class_of_parameter_and_return() -> Result<()> {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl Registry {
+                #[gen_stub(class_of = "Plugin")]
+                fn lookup(
+                    &self,
+                    #[gen_stub(class_of = "Plugin")] default: Option<&Bound<'_, PyType>>,
+                ) -> Py<PyType> {
+                    unimplemented!()
+                }
+            }
+        };
+        let info = PyMethodsInfo::try_from(item_impl)?;
+        let method = &info.methods[0];
+        let TypeOrOverride::RustType { r#type } =
+            &method.parameters.iter().next().unwrap().arg_info.r#type
+        else {
+            panic!("class_of should keep a Rust type");
+        };
+        assert_eq!(
+            r#type,
+            &parse_quote!(::pyo3_stub_gen::type_info::OptionalClassOf<Plugin>)
+        );
+        let Some(TypeOrOverride::RustType { r#type }) = &method.r#return else {
+            panic!("class_of should keep a Rust type");
+        };
+        assert_eq!(
+            r#type,
+            &parse_quote!(::pyo3_stub_gen::type_info::ClassOf<Plugin>)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn literal_parameter_and_return() -> Result<()> {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl File {
+                #[gen_stub(literal(0, 1, -1))]
+                fn open(
+                    &self,
+                    #[gen_stub(literal("r", "w", "a"))] mode: &str,
+                    #[gen_stub(literal("utf-8", "it's"))] encoding: Option<&str>,
+                ) -> i32 {
+                    unimplemented!()
+                }
+            }
+        };
+        let info = PyMethodsInfo::try_from(item_impl)?;
+        let method = &info.methods[0];
+        let reprs: Vec<_> = method
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.arg_info.r#type {
+                TypeOrOverride::OverrideType {
+                    type_repr, imports, ..
+                } => {
+                    assert!(imports.contains("typing"));
+                    type_repr.as_str()
+                }
+                TypeOrOverride::RustType { .. } => panic!("literal should override the type"),
+            })
+            .collect();
+        assert_eq!(
+            reprs,
+            [
+                "typing.Literal['r', 'w', 'a']",
+                "typing.Literal['utf-8', \"it's\"] | None"
+            ]
+        );
+        let Some(TypeOrOverride::OverrideType { type_repr, .. }) = &method.r#return else {
+            panic!("literal should override the type");
+        };
+        assert_eq!(type_repr, "typing.Literal[0, 1, -1]");
+        Ok(())
+    }
+
+    #[test]
+    fn callable_parameter_and_return() -> Result<()> {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl Button {
+                #[gen_stub(callable(args = ..., returns = "builtins.bool", imports = ("builtins")))]
+                fn on_click(
+                    &self,
+                    #[gen_stub(callable(args = ("builtins.int", "builtins.str"), returns = "builtins.bool", imports = ("builtins")))]
+                    handler: Py<PyFunction>,
+                    #[gen_stub(callable(args = ()))] fallback: Option<PyObject>,
+                ) -> PyObject {
+                    unimplemented!()
+                }
+            }
+        };
+        let info = PyMethodsInfo::try_from(item_impl)?;
+        let method = &info.methods[0];
+        let reprs: Vec<_> = method
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.arg_info.r#type {
+                TypeOrOverride::OverrideType {
+                    type_repr, imports, ..
+                } => {
+                    assert!(imports.contains("collections.abc"));
+                    type_repr.as_str()
+                }
+                TypeOrOverride::RustType { .. } => panic!("callable should override the type"),
+            })
+            .collect();
+        assert_eq!(
+            reprs,
+            [
+                "collections.abc.Callable[[builtins.int, builtins.str], builtins.bool]",
+                "typing.Optional[collections.abc.Callable[[], None]]"
+            ]
+        );
+        let Some(TypeOrOverride::OverrideType {
+            type_repr, imports, ..
+        }) = &method.r#return
+        else {
+            panic!("callable should override the type");
+        };
+        assert_eq!(type_repr, "collections.abc.Callable[..., builtins.bool]");
+        assert!(imports.contains("builtins"));
+        Ok(())
+    }
+
+    #[test]
+    fn explicit_names_of_properties() -> Result<()> {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl Meter {
+                #[getter]
+                #[pyo3(name = "pythonName")]
+                fn get_value(&self) -> i32 {
+                    self.value
+                }
+                #[setter]
+                #[pyo3(name = "pythonName")]
+                fn set_value(&mut self, value: i32) {
+                    self.value = value;
+                }
+                #[getter(shown)]
+                #[pyo3(name = "hidden")]
+                fn get_other(&self) -> i32 {
+                    self.value
+                }
+            }
+        };
+        let out = PyMethodsInfo::try_from(item_impl)?
+            .to_token_stream()
+            .to_string();
+        assert_eq!(out.matches("name : \"pythonName\"").count(), 2, "{out}");
+        // The name given to `#[getter(...)]` wins
+        assert!(out.contains("name : \"shown\""), "{out}");
+        assert!(
+            !out.contains("\"value\"") && !out.contains("\"hidden\""),
+            "{out}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn getitem_overloads() -> Result<()> {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl FooList {
+                #[gen_stub(getitem(int -> "Foo", slice -> "Self"))]
+                fn __getitem__(&self, index: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                    unimplemented!()
+                }
+            }
+        };
+        let out = PyMethodsInfo::try_from(item_impl)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyMethodsInfo {
+            struct_id: std::any::TypeId::of::<FooList>,
+            source_location: Some(concat!(file!(), ":", line!())),
+            struct_name: std::any::type_name::<FooList>,
+            attrs: &[],
+            getters: &[],
+            setters: &[],
+            methods: &[
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__getitem__",
+                    parameters: &[
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "index",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: || ::pyo3_stub_gen::TypeInfo {
+                                name: "builtins.int".to_string(),
+                                import: ::std::collections::HashSet::from(["builtins".into()]),
+                            },
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                    ],
+                    r#return: <Foo as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__getitem__",
+                    parameters: &[
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "index",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: || ::pyo3_stub_gen::TypeInfo {
+                                name: "builtins.slice".to_string(),
+                                import: ::std::collections::HashSet::from(["builtins".into()]),
+                            },
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: true,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                    ],
+                    r#return: <::pyo3_stub_gen::type_info::SelfType as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+            ],
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn getitem_overloads_of_other_method() {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl FooList {
+                #[gen_stub(getitem(int -> "Foo", slice -> "Self"))]
+                fn get(&self, index: isize) -> Foo {
+                    unimplemented!()
+                }
+            }
+        };
+        let err = PyMethodsInfo::try_from(item_impl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`getitem(...)` is only valid for `__getitem__`, not `get`"
+        );
+    }
+
+    #[test]
+    fn default_repr_of_parameter() -> Result<()> {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl Logger {
+                #[pyo3(signature = (level = levels::DEFAULT))]
+                fn log(&self, #[gen_stub(default_repr = "LogLevel.Info")] level: LogLevel) {}
+            }
+        };
+        let info = PyMethodsInfo::try_from(item_impl)?;
+        let parameter = info.methods[0].parameters.iter().next().unwrap();
+        assert!(matches!(
+            &parameter.default_expr,
+            Some(DefaultExpr::Python(repr)) if repr == "LogLevel.Info"
+        ));
+
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl Logger {
+                #[pyo3(signature = (level))]
+                fn log(&self, #[gen_stub(default_repr = "LogLevel.Info")] level: LogLevel) {}
+            }
+        };
+        let err = PyMethodsInfo::try_from(item_impl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`default_repr` requires a default in `#[pyo3(signature = ...)]`"
+        );
+        Ok(())
+    }
+
     #[test]
     fn allow_on_getter_enables_whitelist_mode() -> Result<()> {
         let item_impl: ItemImpl = parse_quote! {
@@ -234,4 +596,309 @@ mod tests {
         );
         Ok(())
     }
+
+    fn connection_impl(groups: TokenStream2) -> ItemImpl {
+        parse_quote! {
+            #[pymethods]
+            impl Connection {
+                #[new]
+                #[pyo3(signature = (config = None, host = None, port = None, timeout = 30.0))]
+                #[gen_stub(overload_groups(#groups))]
+                fn new(
+                    config: Option<Config>,
+                    host: Option<String>,
+                    port: Option<u16>,
+                    timeout: f64,
+                ) -> PyResult<Self> {
+                    unimplemented!()
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn overload_groups_split_constructor() -> Result<()> {
+        let item_impl = connection_impl(quote! { ("config"), ("host", "port", "timeout") });
+        let out = PyMethodsInfo::try_from(item_impl)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyMethodsInfo {
+            struct_id: std::any::TypeId::of::<Connection>,
+            source_location: Some(concat!(file!(), ":", line!())),
+            struct_name: std::any::type_name::<Connection>,
+            attrs: &[],
+            getters: &[],
+            setters: &[],
+            methods: &[
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__new__",
+                    parameters: &[
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "config",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: <Config as ::pyo3_stub_gen::PyStubType>::type_input,
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                    ],
+                    r#return: <Connection as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::New,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__new__",
+                    parameters: &[
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "host",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: <String as ::pyo3_stub_gen::PyStubType>::type_input,
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "port",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: <u16 as ::pyo3_stub_gen::PyStubType>::type_input,
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "timeout",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: <f64 as ::pyo3_stub_gen::PyStubType>::type_input,
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr({
+                                fn _fmt() -> String {
+                                    let v: f64 = 30.0;
+                                    ::pyo3_stub_gen::util::fmt_default(v)
+                                }
+                                _fmt
+                            }),
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                    ],
+                    r#return: <Connection as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::New,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+            ],
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn overload_groups_reject_unknown_parameter() {
+        let item_impl = connection_impl(quote! { ("config"), ("hostname", "port") });
+        let err = PyMethodsInfo::try_from(item_impl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "overload group refers to unknown parameter `hostname` of `__new__`"
+        );
+    }
+
+    #[test]
+    fn overload_groups_reject_overlapping_required() {
+        let item_impl = connection_impl(quote! { ("config"), ("config", "timeout") });
+        let err = PyMethodsInfo::try_from(item_impl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "overload groups of `__new__` overlap: a call passing only (config) matches more than one group"
+        );
+
+        // Both accept a call passing only `host`, as `timeout` has a default
+        let item_impl = connection_impl(quote! { ("host"), ("host", "timeout") });
+        let err = PyMethodsInfo::try_from(item_impl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "overload groups of `__new__` overlap: a call passing only (host) matches more than one group"
+        );
+
+        // Disjoint groups, of which neither accepts the parameters required by the other
+        let item_impl = connection_impl(quote! { ("config", "timeout"), ("host", "timeout") });
+        assert!(PyMethodsInfo::try_from(item_impl).is_ok());
+    }
+
+    #[test]
+    fn awaitable_methods() -> Result<()> {
+        let item_impl: ItemImpl = syn::parse2(quote! {
+            #[pymethods]
+            impl Job {
+                #[gen_stub(awaitable)]
+                async fn wait(&self) -> PyResult<i32> {
+                    Ok(0)
+                }
+
+                fn __await__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+                    slf
+                }
+
+                #[gen_stub(override_return_type(type_repr = "builtins.int", imports = ("builtins")))]
+                fn __await__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+                    slf
+                }
+            }
+        })?;
+        let out = PyMethodsInfo::try_from(item_impl)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyMethodsInfo {
+            struct_id: std::any::TypeId::of::<Job>,
+            source_location: Some(concat!(file!(), ":", line!())),
+            struct_name: std::any::type_name::<Job>,
+            attrs: &[],
+            getters: &[],
+            setters: &[],
+            methods: &[
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "wait",
+                    parameters: &[],
+                    r#return: || ::pyo3_stub_gen::TypeInfo::awaitable(
+                        (<i32 as pyo3_stub_gen::PyStubType>::type_output)(),
+                    ),
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__await__",
+                    parameters: &[],
+                    r#return: || ::pyo3_stub_gen::TypeInfo::await_generator(
+                        ::pyo3_stub_gen::TypeInfo::any(),
+                    ),
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__await__",
+                    parameters: &[],
+                    r#return: || ::pyo3_stub_gen::TypeInfo::await_generator(
+                        (|| ::pyo3_stub_gen::TypeInfo {
+                            name: "builtins.int".to_string(),
+                            import: ::std::collections::HashSet::from(["builtins".into()]),
+                        })(),
+                    ),
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+            ],
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn generic_impl_with_self_ty() -> Result<()> {
+        let attr: PyMethodsAttr = parse_quote! { self_ty = PyWrapper<f64> };
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl<T: Float> PyWrapper<T> {
+                fn scaled(&self, factor: f64) -> Self {
+                    unimplemented!()
+                }
+            }
+        };
+        let out = PyMethodsInfo::try_from(attr.apply(item_impl)?)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyMethodsInfo {
+            struct_id: std::any::TypeId::of::<PyWrapper<f64>>,
+            source_location: Some(concat!(file!(), ":", line!())),
+            struct_name: std::any::type_name::<PyWrapper<f64>>,
+            attrs: &[],
+            getters: &[],
+            setters: &[],
+            methods: &[
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "scaled",
+                    parameters: &[
+                        ::pyo3_stub_gen::type_info::ParameterInfo {
+                            name: "factor",
+                            kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
+                            type_info: <f64 as ::pyo3_stub_gen::PyStubType>::type_input,
+                            default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
+                            type_overridden: false,
+                            container_default: None,
+                            deprecated: None,
+                        },
+                    ],
+                    r#return: <::pyo3_stub_gen::type_info::SelfType as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    also_as: &[],
+                    keep_signature: false,
+                    source_location: Some(concat!(file!(), ":", line!())),
+                },
+            ],
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn generic_impl_without_self_ty() {
+        let item_impl: ItemImpl = parse_quote! {
+            #[pymethods]
+            impl<T: Float> PyWrapper<T> {}
+        };
+        let err = PyMethodsAttr::default().apply(item_impl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`#[gen_stub_pymethods]` cannot register a generic impl; specify the concrete class by `#[gen_stub_pymethods(self_ty = ...)]`"
+        );
+    }
+
+    fn format_as_value(tt: TokenStream2) -> String {
+        let ttt = quote! { const _: () = #tt; };
+        let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
+        formatted
+            .trim()
+            .strip_prefix("const _: () = ")
+            .unwrap()
+            .strip_suffix(';')
+            .unwrap()
+            .to_string()
+    }
 }
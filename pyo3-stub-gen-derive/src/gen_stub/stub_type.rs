@@ -8,6 +8,8 @@ pub struct StubType {
     pub(crate) module: Option<String>,
     pub(crate) type_input_override: Option<TokenStream2>,
     pub(crate) type_output_override: Option<TokenStream2>,
+    /// Body of `PyStubType::default_repr`, if the type has its own rendering of default values
+    pub(crate) default_repr: Option<TokenStream2>,
 }
 
 impl ToTokens for StubType {
@@ -18,6 +20,7 @@ impl ToTokens for StubType {
             module,
             type_input_override,
             type_output_override,
+            default_repr,
         } = self;
         let module_tt = if let Some(module) = module {
             quote! { #module.into() }
@@ -32,6 +35,13 @@ impl ToTokens for StubType {
         let type_input_tokens = type_input_override
             .clone()
             .unwrap_or(type_output_tokens.clone());
+        let default_repr_tokens = default_repr.as_ref().map(|body| {
+            quote! {
+                fn default_repr(value: &Self) -> Option<String> {
+                    #body
+                }
+            }
+        });
 
         tokens.append_all(quote! {
             #[automatically_derived]
@@ -42,6 +52,7 @@ impl ToTokens for StubType {
                 fn type_input() -> ::pyo3_stub_gen::TypeInfo {
                     #type_input_tokens
                 }
+                #default_repr_tokens
             }
         })
     }
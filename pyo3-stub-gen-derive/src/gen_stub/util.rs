@@ -1,11 +1,16 @@
 use indexmap::IndexSet;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, ToTokens};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    Attribute, GenericArgument, PathArguments, PathSegment, Result, ReturnType, Type, TypePath,
+    Attribute, Error, GenericArgument, LitStr, PathArguments, PathSegment, Result, ReturnType,
+    Type, TypePath,
 };
 
-use crate::gen_stub::attr::parse_gen_stub_override_return_type;
+use crate::gen_stub::attr::{
+    parse_gen_stub_is_no_return, parse_gen_stub_override_return_type,
+    parse_gen_stub_return_callable, parse_gen_stub_return_class_of, parse_gen_stub_return_literal,
+    CallableAttribute, LiteralAttribute, OverrideTypeAttribute,
+};
 
 pub fn quote_option<T: ToTokens>(a: &Option<T>) -> TokenStream2 {
     if let Some(a) = a {
@@ -15,6 +20,12 @@ pub fn quote_option<T: ToTokens>(a: &Option<T>) -> TokenStream2 {
     }
 }
 
+/// `source_location` of the info at `span`, e.g. `Some("src/lib.rs:12")`,
+/// whose `file!()` and `line!()` resolve to the item in the user's crate
+pub fn source_location(span: Span) -> TokenStream2 {
+    quote_spanned! { span => Some(concat!(file!(), ":", line!())) }
+}
+
 pub fn remove_lifetime(ty: &mut Type) {
     match ty {
         Type::Path(TypePath { path, .. }) => {
@@ -56,10 +67,29 @@ pub fn remove_lifetime(ty: &mut Type) {
 /// Extract `T` from `PyResult<T>` and apply `override_type` attribute if present.
 ///
 /// For `PyResult<&'a T>` case, `'a` will be removed, i.e. returns `&T` for this case.
+/// `#[gen_stub(no_return)]` renders `typing.Never` instead, whatever `T` is.
 pub fn extract_return_type(
     ret: &ReturnType,
     attrs: &[Attribute],
 ) -> Result<Option<TypeOrOverride>> {
+    if parse_gen_stub_is_no_return(attrs)? {
+        if parse_gen_stub_override_return_type(attrs)?.is_some() {
+            let attr = attrs.iter().find(|attr| attr.path().is_ident("gen_stub"));
+            return Err(Error::new_spanned(
+                attr,
+                "`no_return` cannot be combined with `override_return_type(...)`",
+            ));
+        }
+        let r#type = match ret {
+            ReturnType::Type(_, ty) => unwrap_pyresult(ty).clone(),
+            ReturnType::Default => syn::parse_quote!(()),
+        };
+        return Ok(Some(TypeOrOverride::OverrideType {
+            r#type,
+            type_repr: "typing.Never".to_string(),
+            imports: IndexSet::from(["typing".to_string()]),
+        }));
+    }
     let ret = if let ReturnType::Type(_, ty) = ret {
         unwrap_pyresult(ty)
     } else {
@@ -74,9 +104,152 @@ pub fn extract_return_type(
             imports: attr.imports,
         }));
     }
+    if let Some(class) = parse_gen_stub_return_class_of(attrs)? {
+        return Ok(Some(TypeOrOverride::RustType {
+            r#type: class_of_type(&ret, &class)?,
+        }));
+    }
+    if let Some(literal) = parse_gen_stub_return_literal(attrs)? {
+        return Ok(Some(literal_type(&ret, &literal)));
+    }
+    if let Some(callable) = parse_gen_stub_return_callable(attrs)? {
+        return Ok(Some(callable_type(&ret, &callable)));
+    }
     Ok(Some(TypeOrOverride::RustType { r#type: ret }))
 }
 
+/// `typing.Literal[...]` for `#[gen_stub(literal(...))]`, with `| None` for `Option<T>`
+pub fn literal_type(ty: &Type, literal: &LiteralAttribute) -> TypeOrOverride {
+    let mut type_repr = literal.type_repr();
+    if option_inner(ty).is_some() {
+        type_repr.push_str(" | None");
+    }
+    TypeOrOverride::OverrideType {
+        r#type: ty.clone(),
+        type_repr,
+        imports: IndexSet::from(["typing".to_string()]),
+    }
+}
+
+/// `collections.abc.Callable[...]` for `#[gen_stub(callable(...))]`,
+/// wrapped in `typing.Optional[...]` for `Option<T>` as `TypeInfo::optional` renders it
+pub fn callable_type(ty: &Type, callable: &CallableAttribute) -> TypeOrOverride {
+    let mut type_repr = callable.type_repr();
+    let mut imports = IndexSet::from(["collections.abc".to_string()]);
+    if option_inner(ty).is_some() {
+        type_repr = format!("typing.Optional[{type_repr}]");
+        imports.insert("typing".to_string());
+    }
+    imports.extend(callable.imports.iter().cloned());
+    TypeOrOverride::OverrideType {
+        r#type: ty.clone(),
+        type_repr,
+        imports,
+    }
+}
+
+/// Python type given by `#[gen_stub(accepts = "...")]`, importing the modules of its dotted names,
+/// e.g. `decimal` for `decimal.Decimal | int`
+pub fn accepts_type(ty: &Type, accepts: &LitStr) -> TypeOrOverride {
+    let type_repr = accepts.value();
+    let imports = dotted_modules(&type_repr);
+    TypeOrOverride::OverrideType {
+        r#type: ty.clone(),
+        type_repr,
+        imports,
+    }
+}
+
+/// Modules of the dotted names in the Python type `type_repr`, e.g. `typing` of `typing.Any | int`
+fn dotted_modules(type_repr: &str) -> IndexSet<String> {
+    type_repr
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter_map(|name| name.rsplit_once('.'))
+        .map(|(module, _)| module)
+        .filter(|module| module.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Python type given by `#[gen_stub(pickle_state = "...")]`, where a parenthesized list is a tuple,
+/// e.g. `tuple[int, typing.Any]` of `(int, typing.Any)`
+pub fn pickle_state_type(state: &LitStr) -> Result<OverrideTypeAttribute> {
+    let value = state.value();
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(Error::new(
+            state.span(),
+            "`pickle_state` requires a Python type, e.g. `pickle_state = \"(int, str)\"`",
+        ));
+    }
+    let type_repr = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(items) => format!("tuple[{}]", items.trim()),
+        None => value.to_string(),
+    };
+    Ok(OverrideTypeAttribute {
+        imports: dotted_modules(&type_repr),
+        type_repr,
+    })
+}
+
+/// Replace a class object type, i.e. `&Bound<PyType>`, `Bound<PyType>`, `Py<PyType>`, or `Option` of them,
+/// by the marker type rendered as `type[class]` for `#[gen_stub(class_of = "class")]`.
+pub fn class_of_type(ty: &Type, class: &LitStr) -> Result<Type> {
+    let class: Type = class.parse()?;
+    match option_inner(ty) {
+        Some(inner) if is_py_type(inner) => {
+            Ok(syn::parse_quote!(::pyo3_stub_gen::type_info::OptionalClassOf<#class>))
+        }
+        None if is_py_type(ty) => Ok(syn::parse_quote!(::pyo3_stub_gen::type_info::ClassOf<#class>)),
+        _ => Err(Error::new_spanned(
+            ty,
+            "`class_of` is only valid for `&Bound<PyType>`, `Bound<PyType>`, `Py<PyType>`, or `Option` of them",
+        )),
+    }
+}
+
+/// `T` of `Option<T>`
+pub(crate) fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let last = path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(inner) = &last.arguments else {
+        return None;
+    };
+    inner.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Whether `ty` is a smart pointer of `PyType`, optionally behind a reference
+fn is_py_type(ty: &Type) -> bool {
+    let ty = match ty {
+        Type::Reference(reference) => reference.elem.as_ref(),
+        ty => ty,
+    };
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return false;
+    };
+    let Some(last) = path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(inner) = &last.arguments else {
+        return false;
+    };
+    ["Bound", "Borrowed", "Py"]
+        .iter()
+        .any(|name| last.ident == name)
+        && inner.args.iter().any(|arg| {
+            matches!(arg, GenericArgument::Type(Type::Path(TypePath { path, .. }))
+                if path.segments.last().is_some_and(|segment| segment.ident == "PyType"))
+        })
+}
+
 fn unwrap_pyresult(ty: &Type) -> &Type {
     if let Type::Path(TypePath { path, .. }) = ty {
         if let Some(last) = path.segments.last() {
@@ -139,4 +312,30 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_class_of_type() -> Result<()> {
+        let class: LitStr = parse_str(r#""Plugin""#)?;
+        for (ty, expected) in [
+            (
+                "&Bound<'_, PyType>",
+                "::pyo3_stub_gen::type_info::ClassOf<Plugin>",
+            ),
+            ("Py<PyType>", "::pyo3_stub_gen::type_info::ClassOf<Plugin>"),
+            (
+                "Option<&Bound<'_, PyType>>",
+                "::pyo3_stub_gen::type_info::OptionalClassOf<Plugin>",
+            ),
+        ] {
+            let out = class_of_type(&parse_str(ty)?, &class)?;
+            assert_eq!(out, parse_str::<Type>(expected)?, "{ty}");
+        }
+
+        let err = class_of_type(&parse_str("&Bound<'_, PyAny>")?, &class).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`class_of` is only valid for `&Bound<PyType>`, `Bound<PyType>`, `Py<PyType>`, or `Option` of them"
+        );
+        Ok(())
+    }
 }
@@ -3,10 +3,11 @@ use crate::gen_stub::attr::{extract_documents, parse_pyo3_attrs, Attr};
 use crate::gen_stub::member::MemberInfo;
 use crate::gen_stub::parameter::Parameters;
 use crate::gen_stub::renaming::RenamingRule;
-use crate::gen_stub::signature::Signature;
+use crate::gen_stub::signature::{Signature, SignatureArg};
 use crate::gen_stub::util::quote_option;
-use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
 use syn::{Fields, Result, Variant};
 
@@ -90,7 +91,10 @@ impl VariantInfo {
             }
         };
 
-        let constr_args = members.iter().map(|f| f.clone().into()).collect();
+        let constr_args: Vec<ArgInfo> = members.iter().map(|f| f.clone().into()).collect();
+        if let Some(sig) = &constr_sig {
+            check_constructor_defaults(sig, &constr_args)?;
+        }
         let is_mapping = matches!(form, VariantForm::Struct)
             && !members.is_empty()
             && members.iter().all(MemberInfo::is_item);
@@ -109,6 +113,31 @@ impl VariantInfo {
     }
 }
 
+/// Fail if a default of the constructor refers to a field, which is not in scope when PyO3 evaluates it
+fn check_constructor_defaults(sig: &Signature, fields: &[ArgInfo]) -> Result<()> {
+    fn find_field(tokens: TokenStream2, fields: &[ArgInfo]) -> Option<Ident> {
+        tokens.into_iter().find_map(|token| match token {
+            TokenTree::Ident(ident) => fields
+                .iter()
+                .any(|field| ident.unraw() == field.name)
+                .then_some(ident),
+            TokenTree::Group(group) => find_field(group.stream(), fields),
+            _ => None,
+        })
+    }
+    for arg in sig.args() {
+        if let SignatureArg::Assign(ident, _, value) = arg {
+            if let Some(field) = find_field(value.to_token_stream(), fields) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!("default of `{ident}` cannot refer to the field `{field}` in `#[pyo3(constructor = ...)]`"),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 impl ToTokens for VariantInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
@@ -195,4 +224,48 @@ mod tests {
             "tuple variants stay regular classes"
         );
     }
+
+    #[test]
+    fn constructor_kinds_and_omitted_fields() {
+        let variants = parse_variants(
+            r#"
+            enum Shape {
+                #[pyo3(constructor = (*, width, height=1))]
+                Rectangle { width: u32, height: u32 },
+                #[pyo3(constructor = (radius, /))]
+                Circle { radius: f64, cache: Option<f64> },
+            }
+            "#,
+        );
+        let rectangle = variants[0].to_token_stream().to_string();
+        assert_eq!(rectangle.matches("ParameterKind :: KeywordOnly").count(), 2);
+
+        // `cache` is left out of `__new__`, but stays a field
+        assert_eq!(variants[1].fields.len(), 2);
+        let circle = variants[1].to_token_stream().to_string();
+        assert!(
+            circle.contains("ParameterKind :: PositionalOnly"),
+            "{circle}"
+        );
+        assert!(!circle.contains(r#"name : "cache" , kind"#), "{circle}");
+    }
+
+    #[test]
+    fn constructor_default_referring_to_field() {
+        let item: syn::ItemEnum = parse_str(
+            r#"
+            enum Pair {
+                #[pyo3(constructor = (_0, _1 = _0 + 1))]
+                Ints(i32, i32),
+            }
+            "#,
+        )
+        .unwrap();
+        let variant = item.variants.into_iter().next().unwrap();
+        let err = VariantInfo::from_variant(variant, &None).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "default of `_1` cannot refer to the field `_0` in `#[pyo3(constructor = ...)]`"
+        );
+    }
 }
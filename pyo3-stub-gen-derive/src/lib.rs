@@ -65,6 +65,39 @@ pub fn gen_stub_pyclass_complex_enum(_attr: TokenStream, item: TokenStream) -> T
         .into()
 }
 
+/// Derive `PyStubType` for a plain Rust type converted by `IntoPyObject`, which is not a `#[pyclass]`
+///
+/// A struct with named fields is rendered as a stub-only `typing.TypedDict` class, whose keys are the fields.
+/// An `Option` field is a `typing.NotRequired` key. The class is in the default module unless `module` is given.
+///
+/// ```
+/// #[derive(pyo3_stub_gen_derive::PyStubType)]
+/// #[gen_stub(module = "my_module", rename_all = "camelCase")]
+/// pub struct Summary {
+///     /// Number of the samples
+///     pub sample_count: usize,
+///     pub mean: Option<f64>,
+/// }
+/// ```
+///
+/// A fieldless enum is rendered as `typing.Literal` of the variant names, e.g. `typing.Literal['fast', 'slow']` below.
+/// Both names of fields and variants are renamed by `rename_all`, or by `#[gen_stub(rename = "...")]` on each of them.
+///
+/// ```
+/// #[derive(pyo3_stub_gen_derive::PyStubType)]
+/// #[gen_stub(rename_all = "lowercase")]
+/// pub enum Mode {
+///     Fast,
+///     Slow,
+/// }
+/// ```
+#[proc_macro_derive(PyStubType, attributes(gen_stub))]
+pub fn derive_py_stub_type(item: TokenStream) -> TokenStream {
+    gen_stub::plain_type(item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Embed metadata for Python stub file generation for `#[pymethods]` macro
 ///
 /// ```
@@ -81,9 +114,14 @@ pub fn gen_stub_pyclass_complex_enum(_attr: TokenStream, item: TokenStream) -> T
 ///     }
 /// }
 /// ```
+///
+/// The metadata is registered for the self type of the impl block. A generic impl, e.g. `impl<T: Float> PyWrapper<T>`
+/// expanded by another macro, cannot provide it, and needs the concrete class as
+/// `#[gen_stub_pymethods(self_ty = PyWrapper<f64>)]`. `Self` in the methods is also replaced by `self_ty`,
+/// while the other types must not use the generic parameters.
 #[proc_macro_attribute]
-pub fn gen_stub_pymethods(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    gen_stub::pymethods(item.into())
+pub fn gen_stub_pymethods(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::pymethods(attr.into(), item.into())
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -110,6 +148,16 @@ pub fn gen_stub_pymethods(_attr: TokenStream, item: TokenStream) -> TokenStream
 ///     todo!()
 /// }
 /// ```
+///
+/// A function added to several modules repeats `module`, and is rendered in each of them.
+///
+/// ```
+/// #[pyo3_stub_gen_derive::gen_stub_pyfunction(module = "my_module", module = "my_module.compat")]
+/// #[pyo3::pyfunction]
+/// pub fn is_even(x: u32) -> bool {
+///     todo!()
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn gen_stub_pyfunction(attr: TokenStream, item: TokenStream) -> TokenStream {
     gen_stub::pyfunction(attr.into(), item.into())
@@ -117,6 +165,23 @@ pub fn gen_stub_pyfunction(attr: TokenStream, item: TokenStream) -> TokenStream
         .into()
 }
 
+/// Embed metadata for Python stub file generation for a module variable declared by a `const` or a `static`
+///
+/// ```
+/// #[pyo3_stub_gen_derive::gen_stub_pyconst(module = "my_module")]
+/// pub const MAX_ITER: usize = 100;
+/// ```
+///
+/// This renders `MAX_ITER: typing.Final[builtins.int] = 100` in `my_module`, where the type is derived from
+/// the declared Rust type, and the value is rendered only if the initializer is a literal.
+/// Rename the variable by `name = "..."`. The module still has to define the attribute, e.g. by `m.add("MAX_ITER", MAX_ITER)?`.
+#[proc_macro_attribute]
+pub fn gen_stub_pyconst(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::pyconst(attr.into(), item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Do nothing but remove all `#[gen_stub(xxx)]` for `pyclass`, `pymethods`, and `pyfunction`.
 ///
 /// It is useful to use `#[gen_stub(xxx)]` under feature-gating stub-gen.
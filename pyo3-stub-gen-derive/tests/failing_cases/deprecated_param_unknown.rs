@@ -0,0 +1,17 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen_derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct Canvas {}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Canvas {
+    #[gen_stub(deprecated_param(name = "old_size", note = "use size"))]
+    fn resize(&mut self, size: usize) {
+        let _ = size;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen_derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct Device {}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Device {
+    #[gen_stub(feature = "cuda")]
+    fn synchronize(&self) {}
+}
+
+fn main() {}
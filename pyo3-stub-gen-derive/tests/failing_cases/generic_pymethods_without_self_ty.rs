@@ -0,0 +1,12 @@
+use pyo3_stub_gen_derive::gen_stub_pymethods;
+
+struct PyWrapper<T>(T);
+
+#[gen_stub_pymethods]
+impl<T: Copy> PyWrapper<T> {
+    fn get(&self) -> f64 {
+        0.0
+    }
+}
+
+fn main() {}
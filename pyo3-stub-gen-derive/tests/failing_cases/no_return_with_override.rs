@@ -0,0 +1,18 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen_derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct Process {}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Process {
+    #[gen_stub(no_return)]
+    #[gen_stub(override_return_type(type_repr = "typing.NoReturn", imports = ("typing")))]
+    fn kill(&self) -> PyResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use pyo3_stub_gen_derive::gen_stub_pyfunction;
+
+trait Shape {}
+
+#[gen_stub_pyfunction]
+fn area(shape: Box<dyn Shape>) -> f64 {
+    let _ = shape;
+    0.0
+}
+
+fn main() {}
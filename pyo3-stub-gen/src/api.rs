@@ -0,0 +1,127 @@
+//! Stable API for tools built on top of the stub generation, e.g. visitors, manifests, and custom renderers.
+//!
+//! Items re-exported here follow semantic versioning, while other public items such as [crate::type_info]
+//! exist for the proc-macros and may change in any release.
+//! Structs which may gain fields in a minor release are `#[non_exhaustive]`:
+//! their fields can be read and modified, but values are created by their `new` constructors
+//! or by [StubInfo] instead of struct literals. Likewise, enums of options are `#[non_exhaustive]`.
+//!
+//! ```rust
+//! use pyo3_stub_gen::api::*;
+//! use std::{any::TypeId, collections::BTreeMap, path::{Path, PathBuf}};
+//!
+//! // Definitions of the `pkg` module
+//! let mut point = ClassDef::new("Point");
+//! point.doc = "A point in the plane";
+//! let mut x = MemberDef::new("x", TypeInfo::builtin("float"));
+//! x.default = Some("0.0".to_string());
+//! point.attrs.push(x);
+//! let mut parameters = Parameters::new();
+//! parameters.positional_or_keyword.push(Parameter::new(
+//!     "factor",
+//!     ParameterKind::PositionalOrKeyword,
+//!     TypeInfo::builtin("float"),
+//! ));
+//! point.methods.insert(
+//!     "scale".to_string(),
+//!     vec![MethodDef::new("scale", MethodType::Instance, parameters, TypeInfo::self_type())],
+//! );
+//!
+//! let mut module = Module::new("pkg", "pkg");
+//! module.class.insert(TypeId::of::<()>(), point);
+//! module.enum_.insert(
+//!     TypeId::of::<u8>(),
+//!     EnumDef::new("Color", &[("RED", ""), ("GREEN", "")]),
+//! );
+//! module.function.insert(
+//!     "origin".into(),
+//!     vec![FunctionDef::new(
+//!         "origin",
+//!         Parameters::new(),
+//!         TypeInfo::locally_defined("Point", ModuleRef::Default),
+//!     )],
+//! );
+//! module.variables.insert(
+//!     "VERSION",
+//!     VariableDef::new("VERSION", TypeInfo::builtin("str")),
+//! );
+//! module.assign_qualnames();
+//!
+//! // Visit the definitions
+//! let point = module.class.values().next().unwrap();
+//! assert_eq!(point.qualname.dotted(), "pkg.Point");
+//! assert_eq!(module.public_names(), ["Color", "Point", "VERSION", "origin"]);
+//! let imports = module.import();
+//! assert!(imports.contains(&ImportRef::Module(ModuleRef::from("builtins"))));
+//! let origin = &module.function["origin"][0];
+//! assert!(origin.r#return.import.iter().any(|import| matches!(
+//!     import,
+//!     ImportRef::Type(TypeRef { name, .. }) if name == "Point"
+//! )));
+//!
+//! // Render into a custom sink
+//! #[derive(Default)]
+//! struct MemorySink(BTreeMap<PathBuf, String>);
+//!
+//! impl StubSink for MemorySink {
+//!     fn write(&mut self, rel_path: &Path, content: &str) -> Result<()> {
+//!         self.0.insert(rel_path.to_path_buf(), content.to_string());
+//!         Ok(())
+//!     }
+//!     fn exists(&self, rel_path: &Path) -> bool {
+//!         self.0.contains_key(rel_path)
+//!     }
+//!     fn read(&self, rel_path: &Path) -> Result<String> {
+//!         Ok(self.0[rel_path].clone())
+//!     }
+//! }
+//!
+//! let mut info = StubInfo::from_project_root("pkg".to_string(), std::env::temp_dir())?;
+//! info.modules.insert("pkg".to_string(), module);
+//! let mut sink = MemorySink::default();
+//! info.generate_into(&mut sink)?;
+//! let stub = &sink.0[Path::new("pkg.pyi")];
+//! assert!(stub.contains("class Point:"), "{stub}");
+//! assert!(stub.contains("def scale(self, factor: builtins.float) -> Self: ..."), "{stub}");
+//! assert!(info.check_against(&sink)?.is_empty());
+//!
+//! // Options
+//! assert!(glob_match("pkg.**", "pkg.sub.Point"));
+//! assert_eq!(Config::current().docstring_style, DocstringStyle::Verbatim);
+//! assert_eq!(
+//!     deprecated_style(DeprecatedTarget::Function),
+//!     DeprecatedStyle::Both
+//! );
+//! let stub_gen: StubGen = toml::from_str("generate-all = false")?;
+//! assert_eq!(stub_gen.generate_all, Some(false));
+//! assert_eq!(Filter::default().exclude_modules, Vec::<String>::new());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub use crate::{
+    config::{Config, ConfigScope},
+    generate::{
+        annotate_cls, annotate_deprecated_parameters, constructor_style, container_default_style,
+        deprecated_style, docstring_style, generate_dunder_all, glob_match, name_conflict_resolver,
+        set_annotate_cls, set_annotate_deprecated_parameters, set_constructor_style,
+        set_container_default_style, set_deprecated_style, set_docstring_style,
+        set_generate_dunder_all, set_name_conflict_resolver, set_synthesize_attributes_section,
+        synthesize_attributes_section, ClassDef, ConstructorStyle, ContainerDefaultStyle,
+        DataclassDef, DeprecatedStyle, DeprecatedTarget, DocstringStyle, EnumDef, ExceptionDef,
+        FsSink, FunctionDef, GenerateReport, Import, ItemKind, MemberDef, MethodDef, Module,
+        NameConflict, NameConflictResolver, NamedItem, Parameter, ParameterDefault, Parameters,
+        QualName, StubInfo, StubLayout, StubSink, SynthesizedDef, SynthesizedKind, TypeIgnore,
+        Validator, VariableDef,
+    },
+    pyproject::{Filter, PyProject, StubGen},
+    stub_type::{
+        abstract_set_input, inline_complex_enum_unions, self_import_strategy,
+        set_abstract_set_input, set_inline_complex_enum_unions, set_self_import_strategy,
+        ImportRef, ModuleRef, PyStubType, SelfImportStrategy, TypeInfo, TypeRef,
+    },
+    type_info::{DeprecatedInfo, IgnoreTarget, MethodType, ParameterKind},
+    Result,
+};
+
+#[cfg(feature = "zip")]
+pub use crate::generate::ZipSink;
@@ -0,0 +1,160 @@
+//! Helpers for `build.rs` of a PyO3 crate whose stubs are generated by [run_from_cdylib](crate::runner::run_from_cdylib).
+//!
+//! ```rust:ignore
+//! // build.rs
+//! fn main() -> pyo3_stub_gen::Result<()> {
+//!     pyo3_stub_gen::build_helper::emit_rerun_and_marker()
+//! }
+//! ```
+//!
+//! The marker is a fingerprint of the Rust sources, `Cargo.toml`, `Cargo.lock`, `pyproject.toml`
+//! and the enabled features embedded into the library,
+//! so that the runner skips the stub generation while the library is built from the same inputs.
+//! Changes of path dependencies other than their versions are not reflected in the marker.
+
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable the marker is passed to the compilation of the crate by
+pub const MARKER_ENV: &str = "PYO3_STUB_GEN_MARKER";
+
+/// Emit `cargo:rerun-if-changed` for the inputs of the stubs in `CARGO_MANIFEST_DIR`,
+/// and pass their fingerprint with the enabled features to the crate as [MARKER_ENV].
+pub fn emit_rerun_and_marker() -> Result<()> {
+    let manifest_dir =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").context(
+            "`CARGO_MANIFEST_DIR` is not set; call `emit_rerun_and_marker` from build.rs",
+        )?);
+    for input in inputs(&manifest_dir) {
+        println!("cargo:rerun-if-changed={}", input.display());
+    }
+    // Cargo runs the build script again when the features change
+    let features: Vec<String> = std::env::vars()
+        .filter_map(|(name, _)| Some(name.strip_prefix("CARGO_FEATURE_")?.to_lowercase()))
+        .collect();
+    println!(
+        "cargo:rustc-env={MARKER_ENV}={:016x}",
+        source_marker(&manifest_dir, &features)?
+    );
+    Ok(())
+}
+
+/// Fingerprint of the files under `src`, `Cargo.toml`, `pyproject.toml` in `manifest_dir`,
+/// the `Cargo.lock` of its workspace, and the enabled `features`, which is never `0`.
+pub fn source_marker(manifest_dir: &Path, features: &[String]) -> Result<u64> {
+    let mut files = Vec::new();
+    for input in inputs(manifest_dir) {
+        collect_files(&input, &mut files)?;
+    }
+    files.sort();
+    let mut hash = Fnv1a::default();
+    for file in files {
+        let content =
+            fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let relative = file.strip_prefix(manifest_dir).unwrap_or(&file);
+        hash.write(relative.to_string_lossy().as_bytes());
+        hash.write(&[0]);
+        hash.write(&(content.len() as u64).to_le_bytes());
+        hash.write(&content);
+    }
+    let mut features = features.to_vec();
+    features.sort();
+    for feature in features {
+        hash.write(b"feature ");
+        hash.write(feature.as_bytes());
+        hash.write(&[0]);
+    }
+    // `0` is reserved for a library built without the marker
+    Ok(hash.0.max(1))
+}
+
+/// Existing inputs of the stubs in `manifest_dir`
+fn inputs(manifest_dir: &Path) -> Vec<PathBuf> {
+    let lock = manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.exists());
+    ["src", "Cargo.toml", "pyproject.toml"]
+        .into_iter()
+        .map(|name| manifest_dir.join(name))
+        .filter(|path| path.exists())
+        .chain(lock)
+        .collect()
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_dir() {
+        let entries =
+            fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        for entry in entries {
+            collect_files(&entry?.path(), files)?;
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// 64-bit FNV-1a, which is stable across Rust versions unlike [std::hash::DefaultHasher]
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_follows_inputs() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("pyo3-stub-gen-marker-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/sub"))?;
+        fs::write(dir.join("src/lib.rs"), "mod sub;")?;
+        fs::write(dir.join("src/sub/mod.rs"), "")?;
+        fs::write(dir.join("README.md"), "")?;
+
+        let marker = source_marker(&dir, &[])?;
+        assert_ne!(marker, 0);
+        // Files other than the inputs are ignored
+        fs::write(dir.join("README.md"), "# Example")?;
+        assert_eq!(source_marker(&dir, &[])?, marker);
+
+        let mut last = marker;
+        for (file, content) in [
+            ("pyproject.toml", "[project]\nname = \"example\""),
+            ("Cargo.toml", "[package]\nname = \"example\""),
+            ("Cargo.lock", "version = 4"),
+            ("src/sub/mod.rs", "fn f() {}"),
+        ] {
+            fs::write(dir.join(file), content)?;
+            let current = source_marker(&dir, &[])?;
+            assert_ne!(current, last, "{file}");
+            last = current;
+        }
+
+        // The order of the features does not matter
+        let features = ["default".to_string(), "extension_module".to_string()];
+        let with_features = source_marker(&dir, &features)?;
+        assert_ne!(with_features, last);
+        let reversed = [features[1].clone(), features[0].clone()];
+        assert_eq!(source_marker(&dir, &reversed)?, with_features);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+//! Command line interface of stub generation executables.
+//!
+//! The `main` function of a `stub_gen` executable is defined by [stub_gen_main](crate::stub_gen_main):
+//!
+//! ```rust:ignore
+//! pyo3_stub_gen::stub_gen_main!(my_crate::stub_info);
+//! ```
+//!
+//! The executable accepts the following flags:
+//!
+//! - `--out <DIR>`: Write stub files under `DIR` instead of the python root of the project
+//! - `--only-module <MODULE>`: Generate only the modules matching the glob pattern, e.g. `mypkg._core` or `mypkg.**`.
+//!   Can be given more than once.
+//! - `--check`: Do not write anything, and fail if any stub file is missing or out of date
+//! - `--manifest <PATH>`: Also write the public API as JSON to `PATH` unless `--check` is given, see [ApiManifest](crate::generate::ApiManifest)
+//! - `--coverage <DIR>`: Warn about PyO3 items in the source files under `DIR` without stub information,
+//!   see [coverage](crate::coverage). Requires the `coverage` feature.
+//! - `--strict`: Fail if `--coverage` finds such items, except those possibly unreachable behind `#[cfg(...)]`
+//! - `--quiet`: Report errors only
+//!
+//! The exit code is [EXIT_SUCCESS] on success, [EXIT_CHECK_FAILED] if `--check` finds outdated stub files
+//! or `--strict` finds items without stub information, and [EXIT_ERROR] if the stub generation itself fails.
+
+use crate::{generate::glob_match, StubInfo};
+use anyhow::{bail, Context, Result};
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// Exit code on success
+pub const EXIT_SUCCESS: u8 = 0;
+/// Exit code when `--check` finds missing or outdated stub files, or `--strict` finds items without stub information
+pub const EXIT_CHECK_FAILED: u8 = 1;
+/// Exit code when the arguments are invalid or the stub generation fails
+pub const EXIT_ERROR: u8 = 2;
+
+const USAGE: &str = "\
+Usage: stub_gen [--out <DIR>] [--only-module <MODULE>]... [--check] [--manifest <PATH>] [--coverage <DIR> [--strict]] [--quiet]
+
+Options:
+  --out <DIR>              Write stub files under DIR instead of the python root
+  --only-module <MODULE>   Generate only the modules matching the glob pattern
+  --check                  Fail if any stub file is missing or out of date
+  --manifest <PATH>        Also write the public API as JSON to PATH
+  --coverage <DIR>         Warn about PyO3 items under DIR without stub information
+  --strict                 Fail if --coverage finds such items
+  --quiet                  Report errors only
+  -h, --help               Print this help
+";
+
+/// Parsed command line arguments
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    pub out: Option<PathBuf>,
+    pub only_modules: Vec<String>,
+    pub check: bool,
+    pub manifest: Option<PathBuf>,
+    pub coverage: Option<PathBuf>,
+    pub strict: bool,
+    pub quiet: bool,
+    pub help: bool,
+}
+
+impl Args {
+    /// Parse the arguments, excluding the executable name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .with_context(|| format!("`{flag}` requires a value"))
+            };
+            match flag {
+                "--out" => parsed.out = Some(value()?.into()),
+                "--only-module" => parsed.only_modules.push(value()?),
+                "--check" if inline_value.is_none() => parsed.check = true,
+                "--manifest" => parsed.manifest = Some(value()?.into()),
+                "--coverage" => parsed.coverage = Some(value()?.into()),
+                "--strict" if inline_value.is_none() => parsed.strict = true,
+                "--quiet" | "-q" if inline_value.is_none() => parsed.quiet = true,
+                "--help" | "-h" if inline_value.is_none() => parsed.help = true,
+                _ => bail!("Unknown argument `{arg}`\n\n{USAGE}"),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Whether the module `name` is selected by `--only-module`
+    pub fn selects(&self, name: &str) -> bool {
+        self.only_modules.is_empty()
+            || self
+                .only_modules
+                .iter()
+                .any(|pattern| glob_match(pattern, name))
+    }
+}
+
+/// Run the stub generation with the arguments of the process, and return the exit code.
+pub fn run(stub_info: fn() -> Result<StubInfo>) -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    if args.help {
+        print!("{USAGE}");
+        return ExitCode::from(EXIT_SUCCESS);
+    }
+    let default_filter = if args.quiet { "error" } else { "info" };
+    // Ignore the error when the executable has already initialized a logger
+    let _ = env_logger::Builder::from_env(
+        env_logger::Env::default().filter_or("RUST_LOG", default_filter),
+    )
+    .try_init();
+    match run_with(&args, stub_info) {
+        Ok(code) => ExitCode::from(code),
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+fn run_with(args: &Args, stub_info: fn() -> Result<StubInfo>) -> Result<u8> {
+    let info = stub_info()?.filtered(|name| args.selects(name));
+    if info.modules.is_empty() {
+        bail!(
+            "No module matches `--only-module {}`",
+            args.only_modules.join("`, `--only-module ")
+        );
+    }
+    let root = args.out.clone().unwrap_or_else(|| info.python_root.clone());
+    let mut code = if args.check {
+        check(&info, &root)?
+    } else {
+        info.generate_to(&root)?;
+        if let Some(manifest) = &args.manifest {
+            info.write_manifest(manifest)?;
+        }
+        EXIT_SUCCESS
+    };
+    if let Some(src_dir) = &args.coverage {
+        if !report_coverage(src_dir, args.strict)? && args.strict {
+            code = EXIT_CHECK_FAILED;
+        }
+    }
+    Ok(code)
+}
+
+fn check(info: &StubInfo, root: &Path) -> Result<u8> {
+    let outdated = info.check_against(&crate::generate::FsSink::new(root))?;
+    if outdated.is_empty() {
+        log::info!("Stub files under {} are up to date", root.display());
+        return Ok(EXIT_SUCCESS);
+    }
+    eprintln!("Stub files are missing or out of date:");
+    for path in outdated {
+        eprintln!("  {}", root.join(path).display());
+    }
+    Ok(EXIT_CHECK_FAILED)
+}
+
+/// Report PyO3 items without stub information, and return whether every reachable item has it.
+///
+/// The items are errors in the strict mode, and warnings otherwise.
+#[cfg(feature = "coverage")]
+fn report_coverage(src_dir: &Path, strict: bool) -> Result<bool> {
+    let items = crate::coverage::scan(src_dir)?;
+    for item in &items {
+        if strict && !item.possibly_unreachable {
+            log::error!("{item}");
+        } else {
+            log::warn!("{item}");
+        }
+    }
+    let covered = items.iter().all(|item| item.possibly_unreachable);
+    if covered {
+        log::info!(
+            "PyO3 items under {} have stub information",
+            src_dir.display()
+        );
+    }
+    Ok(covered)
+}
+
+#[cfg(not(feature = "coverage"))]
+fn report_coverage(_src_dir: &Path, _strict: bool) -> Result<bool> {
+    bail!("`--coverage` requires the `coverage` feature of pyo3-stub-gen")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn parse_flags() -> Result<()> {
+        assert_eq!(
+            parse(&[
+                "--out",
+                "stubs",
+                "--only-module=mypkg._core",
+                "--only-module",
+                "mypkg.sub.**",
+                "--check",
+                "--manifest=api.json",
+                "--coverage=src",
+                "--strict",
+                "--quiet",
+            ])?,
+            Args {
+                out: Some("stubs".into()),
+                only_modules: vec!["mypkg._core".into(), "mypkg.sub.**".into()],
+                check: true,
+                manifest: Some("api.json".into()),
+                coverage: Some("src".into()),
+                strict: true,
+                quiet: true,
+                help: false,
+            }
+        );
+        assert_eq!(parse(&[])?, Args::default());
+        Ok(())
+    }
+
+    #[test_case(&["--out"], "`--out` requires a value" ; "missing value")]
+    #[test_case(&["--verbose"], "Unknown argument `--verbose`" ; "unknown flag")]
+    #[test_case(&["--check=yes"], "Unknown argument `--check=yes`" ; "value for switch")]
+    #[test_case(&["--coverage"], "`--coverage` requires a value" ; "missing coverage directory")]
+    fn parse_errors(args: &[&str], message: &str) {
+        let err = parse(args).unwrap_err();
+        assert!(err.to_string().starts_with(message), "{err}");
+    }
+
+    #[test_case(&[], "mypkg", true ; "no filter")]
+    #[test_case(&["mypkg._core"], "mypkg._core", true ; "exact")]
+    #[test_case(&["mypkg._core"], "mypkg", false ; "parent")]
+    #[test_case(&["mypkg", "mypkg.sub.*"], "mypkg.sub.a", true ; "glob")]
+    fn selects(only_modules: &[&str], name: &str, expected: bool) {
+        let args = Args {
+            only_modules: only_modules.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+        assert_eq!(args.selects(name), expected);
+    }
+}
@@ -0,0 +1,167 @@
+//! Process-wide configuration of the stub generation.
+//!
+//! The setters such as [set_self_import_strategy](crate::set_self_import_strategy) change the configuration
+//! of the process. [StubInfo](crate::StubInfo) takes a snapshot of it, overridden by `pyproject.toml`, when it is built,
+//! and renders its stubs with the snapshot, so that stubs of several crates can be generated in the same process.
+
+use crate::{
+    generate::{
+        ConstructorStyle, ContainerDefaultStyle, DeprecatedStyle, DocstringStyle,
+        NameConflictResolver, TargetVersion,
+    },
+    SelfImportStrategy,
+};
+use std::{cell::Cell, marker::PhantomData, sync::RwLock};
+
+/// Snapshot of the configuration
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Config {
+    /// Module `Self` is imported from
+    pub self_import_strategy: SelfImportStrategy,
+    /// Whether `__all__` is emitted in generated modules
+    pub generate_dunder_all: bool,
+    /// How deprecated items are rendered, indexed by [DeprecatedTarget](crate::generate::DeprecatedTarget)
+    pub deprecated_styles: [DeprecatedStyle; 4],
+    /// Resolver for module-level items sharing the same name
+    pub name_conflict_resolver: Option<NameConflictResolver>,
+    /// How the `# Arguments` section of doc comments is rendered
+    pub docstring_style: DocstringStyle,
+    /// Whether class docstrings get an `Attributes:` section listing the attributes and properties
+    pub synthesize_attributes_section: bool,
+    /// How list, dict, and set defaults of parameters are rendered
+    pub container_default_style: ContainerDefaultStyle,
+    /// How constructors of `#[new]` are rendered
+    pub constructor_style: ConstructorStyle,
+    /// Whether `cls` of classmethods and `__new__` is annotated as `type[Self]`
+    pub annotate_cls: bool,
+    /// Oldest Python version the stubs are written for
+    pub target_version: TargetVersion,
+    /// Whether sets taken as arguments are annotated as `typing.AbstractSet` instead of `set`
+    pub abstract_set_input: bool,
+    /// Whether parameters of `#[gen_stub(deprecated_param(...))]` are annotated with `typing_extensions.deprecated`
+    pub annotate_deprecated_parameters: bool,
+    /// Whether complex enums are annotated by the union of their variants instead of its alias
+    pub inline_complex_enum_unions: bool,
+}
+
+impl Config {
+    const DEFAULT: Config = Config {
+        self_import_strategy: SelfImportStrategy::Typing,
+        generate_dunder_all: true,
+        deprecated_styles: [DeprecatedStyle::Both; 4],
+        name_conflict_resolver: None,
+        docstring_style: DocstringStyle::Verbatim,
+        synthesize_attributes_section: false,
+        container_default_style: ContainerDefaultStyle::Literal,
+        constructor_style: ConstructorStyle::New,
+        annotate_cls: false,
+        target_version: TargetVersion::Py310,
+        abstract_set_input: false,
+        annotate_deprecated_parameters: false,
+        inline_complex_enum_unions: false,
+    };
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static CONFIG: RwLock<Config> = RwLock::new(Config::DEFAULT);
+
+thread_local! {
+    static SCOPED: Cell<Option<Config>> = const { Cell::new(None) };
+}
+
+impl Config {
+    /// The configuration in effect, which is the one of the innermost [Config::scope] on this thread if any,
+    /// or the one of the setters otherwise.
+    pub fn current() -> Config {
+        SCOPED.get().unwrap_or_else(|| *CONFIG.read().unwrap())
+    }
+
+    /// Use this configuration on the current thread until the returned guard is dropped,
+    /// which restores the configuration in effect before.
+    ///
+    /// [StubInfo](crate::StubInfo) renders its stubs within the scope of the configuration it was built with,
+    /// so that the setters called afterwards do not affect it.
+    pub fn scope(self) -> ConfigScope {
+        ConfigScope {
+            previous: SCOPED.replace(Some(self)),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Update the configuration of the setters by `apply`.
+    pub(crate) fn update(apply: impl FnOnce(&mut Config)) {
+        apply(&mut CONFIG.write().unwrap());
+    }
+}
+
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        let same_resolver = match (self.name_conflict_resolver, other.name_conflict_resolver) {
+            (Some(left), Some(right)) => std::ptr::fn_addr_eq(left, right),
+            (left, right) => left.is_none() && right.is_none(),
+        };
+        same_resolver
+            && self.self_import_strategy == other.self_import_strategy
+            && self.generate_dunder_all == other.generate_dunder_all
+            && self.deprecated_styles == other.deprecated_styles
+            && self.docstring_style == other.docstring_style
+            && self.synthesize_attributes_section == other.synthesize_attributes_section
+            && self.container_default_style == other.container_default_style
+            && self.constructor_style == other.constructor_style
+            && self.annotate_cls == other.annotate_cls
+            && self.target_version == other.target_version
+            && self.abstract_set_input == other.abstract_set_input
+            && self.annotate_deprecated_parameters == other.annotate_deprecated_parameters
+            && self.inline_complex_enum_unions == other.inline_complex_enum_unions
+    }
+}
+
+/// Guard of [Config::scope] restoring the previous configuration when dropped
+#[must_use = "the configuration is restored as soon as the guard is dropped"]
+pub struct ConfigScope {
+    previous: Option<Config>,
+    /// The scope belongs to the thread it is created on
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ConfigScope {
+    fn drop(&mut self) {
+        SCOPED.set(self.previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_is_restored() {
+        let outer = Config {
+            annotate_cls: true,
+            ..Config::current()
+        };
+        let inner = Config {
+            generate_dunder_all: false,
+            ..outer
+        };
+        {
+            let _outer = outer.scope();
+            assert_eq!(Config::current(), outer);
+            {
+                let _inner = inner.scope();
+                assert_eq!(Config::current(), inner);
+            }
+            assert_eq!(Config::current(), outer);
+        }
+        assert_ne!(Config::current(), inner);
+        // Scopes are local to the thread
+        let _inner = inner.scope();
+        assert_ne!(std::thread::spawn(Config::current).join().unwrap(), inner);
+    }
+}
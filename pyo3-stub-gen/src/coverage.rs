@@ -0,0 +1,380 @@
+//! Report of PyO3 items in the source files which produce no stub information.
+//!
+//! The derive macros cannot see items without `#[gen_stub_*]` attributes,
+//! so [scan] parses the source files, e.g. under `src/`, and lists every `#[pyclass]`, `#[pyfunction]`,
+//! and `#[pymethods]` which is neither annotated nor found in the collected [inventory].
+//!
+//! This runs in the `stub_gen` executable, which links the items of the library:
+//!
+//! ```text
+//! cargo run --bin stub_gen -- --coverage src --strict
+//! ```
+//!
+//! An item under `#[cfg(...)]` may not be compiled at all, and is reported as possibly unreachable
+//! instead of missing.
+
+use crate::type_info::{PyClassInfo, PyComplexEnumInfo, PyEnumInfo, PyFunctionInfo, PyMethodsInfo};
+use anyhow::{Context, Result};
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use syn::{punctuated::Punctuated, visit::Visit, Attribute, Meta, Token};
+
+/// Kind of a PyO3 item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyItemKind {
+    /// `#[pyclass]` on a struct or an enum
+    Class,
+    /// `#[pyfunction]`
+    Function,
+    /// `#[pymethods]` on an `impl` block
+    Methods,
+}
+
+impl PyItemKind {
+    fn pyo3_attr(self) -> &'static str {
+        match self {
+            Self::Class => "pyclass",
+            Self::Function => "pyfunction",
+            Self::Methods => "pymethods",
+        }
+    }
+
+    fn gen_stub_attrs(self) -> &'static [&'static str] {
+        match self {
+            Self::Class => &[
+                "gen_stub_pyclass",
+                "gen_stub_pyclass_enum",
+                "gen_stub_pyclass_complex_enum",
+            ],
+            Self::Function => &["gen_stub_pyfunction"],
+            Self::Methods => &["gen_stub_pymethods"],
+        }
+    }
+}
+
+/// PyO3 item without stub information
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UncoveredItem {
+    pub kind: PyItemKind,
+    /// Name of the struct, enum, or function, or the type of the `impl` block
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+    /// Whether the item is under `#[cfg(...)]`, and may not be compiled
+    pub possibly_unreachable: bool,
+}
+
+impl fmt::Display for UncoveredItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let attr = self.kind.gen_stub_attrs()[0];
+        write!(
+            f,
+            "{}:{}: `#[{}]` `{}` has no `#[{attr}]`",
+            self.file.display(),
+            self.line,
+            self.kind.pyo3_attr(),
+            self.name,
+        )?;
+        if self.possibly_unreachable {
+            write!(f, " (possibly unreachable behind `#[cfg(...)]`)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Scan the Rust source files under `src_dir` for PyO3 items without stub information.
+///
+/// Items are sorted by file and line.
+pub fn scan(src_dir: &Path) -> Result<Vec<UncoveredItem>> {
+    let mut files = Vec::new();
+    collect_rust_files(src_dir, &mut files)
+        .with_context(|| format!("Failed to read {}", src_dir.display()))?;
+    files.sort();
+    let mut scanner = Scanner::default();
+    for file in files {
+        let source = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        scanner.scan(&file, &source)?;
+    }
+    Ok(scanner.finish())
+}
+
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_rust_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Scanner {
+    file: PathBuf,
+    /// Number of enclosing items under `#[cfg(...)]`
+    cfg_depth: usize,
+    items: Vec<UncoveredItem>,
+    /// Types with an `impl` block under `#[gen_stub_pymethods]`
+    annotated_methods: HashSet<String>,
+}
+
+/// Attributes of an item relevant to stub coverage
+#[derive(Default)]
+struct Attrs {
+    names: Vec<String>,
+    /// Whether the item is under `#[cfg(...)]`, or its PyO3 attribute is given by `#[cfg_attr(...)]`
+    conditional: bool,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> Self {
+        let mut parsed = Attrs::default();
+        for attr in attrs {
+            let path = attr.path();
+            if path.is_ident("cfg") {
+                parsed.conditional = true;
+            } else if path.is_ident("cfg_attr") {
+                let Ok(metas) =
+                    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                else {
+                    continue;
+                };
+                for meta in metas.iter().skip(1) {
+                    if let Some(name) = last_segment(meta.path()) {
+                        parsed.conditional |= is_pyo3_attr(&name);
+                        parsed.names.push(name);
+                    }
+                }
+            } else if let Some(name) = last_segment(path) {
+                parsed.names.push(name);
+            }
+        }
+        parsed
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.names.iter().any(|other| other == name)
+    }
+}
+
+fn last_segment(path: &syn::Path) -> Option<String> {
+    path.segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+fn is_pyo3_attr(name: &str) -> bool {
+    matches!(name, "pyclass" | "pyfunction" | "pymethods")
+}
+
+impl Scanner {
+    /// Scan a single source file
+    fn scan(&mut self, file: &Path, source: &str) -> Result<()> {
+        let syntax = syn::parse_file(source)
+            .with_context(|| format!("Failed to parse {}", file.display()))?;
+        self.file = file.to_path_buf();
+        self.visit_file(&syntax);
+        Ok(())
+    }
+
+    /// Uncovered items, except `#[pymethods]` submitted by hand for a type without
+    /// any `#[gen_stub_pymethods]` block
+    fn finish(self) -> Vec<UncoveredItem> {
+        let Self {
+            items,
+            annotated_methods,
+            ..
+        } = self;
+        items
+            .into_iter()
+            .filter(|item| {
+                item.kind != PyItemKind::Methods
+                    || annotated_methods.contains(&item.name)
+                    || !methods_in_inventory(&item.name)
+            })
+            .collect()
+    }
+
+    fn check(&mut self, kind: PyItemKind, attrs: &[Attribute], name: String, line: usize) {
+        let attrs = Attrs::parse(attrs);
+        if kind == PyItemKind::Methods && attrs.has("gen_stub_pymethods") {
+            self.annotated_methods.insert(name.clone());
+        }
+        if !attrs.has(kind.pyo3_attr())
+            || kind.gen_stub_attrs().iter().any(|attr| attrs.has(attr))
+            || in_inventory(kind, &name)
+        {
+            return;
+        }
+        self.items.push(UncoveredItem {
+            kind,
+            name,
+            file: self.file.clone(),
+            line,
+            possibly_unreachable: self.cfg_depth > 0 || attrs.conditional,
+        });
+    }
+
+    fn nested(&mut self, attrs: &[Attribute], visit: impl FnOnce(&mut Self)) {
+        let conditional = attrs.iter().any(|attr| attr.path().is_ident("cfg"));
+        self.cfg_depth += usize::from(conditional);
+        visit(self);
+        self.cfg_depth -= usize::from(conditional);
+    }
+}
+
+/// Whether stub information of the item is submitted by other means, e.g. [inventory::submit] by hand
+fn in_inventory(kind: PyItemKind, name: &str) -> bool {
+    let matches = |rust_path: &str| rust_path.rsplit("::").next() == Some(name);
+    match kind {
+        PyItemKind::Class => {
+            inventory::iter::<PyClassInfo>
+                .into_iter()
+                .any(|info| matches(info.rust_path))
+                || inventory::iter::<PyEnumInfo>
+                    .into_iter()
+                    .any(|info| matches(info.rust_path))
+                || inventory::iter::<PyComplexEnumInfo>
+                    .into_iter()
+                    .any(|info| matches(info.rust_path))
+        }
+        PyItemKind::Function => inventory::iter::<PyFunctionInfo>
+            .into_iter()
+            .any(|info| matches(info.rust_path)),
+        // Methods are submitted without the name of the class, see [methods_in_inventory]
+        PyItemKind::Methods => false,
+    }
+}
+
+/// Whether [PyMethodsInfo] is submitted for the class `name`
+fn methods_in_inventory(name: &str) -> bool {
+    inventory::iter::<PyClassInfo>
+        .into_iter()
+        .filter(|class| class.rust_path.rsplit("::").next() == Some(name))
+        .any(|class| {
+            inventory::iter::<PyMethodsInfo>
+                .into_iter()
+                .any(|methods| (methods.struct_id)() == (class.struct_id)())
+        })
+}
+
+impl<'ast> Visit<'ast> for Scanner {
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        let line = item.ident.span().start().line;
+        self.check(PyItemKind::Class, &item.attrs, item.ident.to_string(), line);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        let line = item.ident.span().start().line;
+        self.check(PyItemKind::Class, &item.attrs, item.ident.to_string(), line);
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        let line = item.sig.ident.span().start().line;
+        self.check(
+            PyItemKind::Function,
+            &item.attrs,
+            item.sig.ident.to_string(),
+            line,
+        );
+        self.nested(&item.attrs, |scanner| {
+            syn::visit::visit_item_fn(scanner, item)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        let line = item.impl_token.span.start().line;
+        let name = match item.self_ty.as_ref() {
+            syn::Type::Path(path) => last_segment(&path.path).unwrap_or_default(),
+            _ => String::new(),
+        };
+        self.check(PyItemKind::Methods, &item.attrs, name, line);
+        self.nested(&item.attrs, |scanner| {
+            syn::visit::visit_item_impl(scanner, item)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        self.nested(&item.attrs, |scanner| {
+            syn::visit::visit_item_mod(scanner, item)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct Annotated;
+
+#[pyclass]
+struct Plain;
+
+#[pymethods]
+impl Annotated {
+    fn method(&self) {}
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn annotated() {}
+
+#[pyo3::pyfunction]
+fn plain() {}
+
+#[cfg(feature = "extra")]
+mod extra {
+    #[pyclass]
+    enum Extra { A }
+}
+
+#[cfg_attr(feature = "python", pyclass)]
+struct MaybePython;
+
+#[cfg_attr(feature = "python", gen_stub_pyclass, pyclass)]
+struct MaybeAnnotated;
+
+struct NotPython;
+"#;
+
+    fn scan_source(file: &str, source: &str) -> Result<Vec<UncoveredItem>> {
+        let mut scanner = Scanner::default();
+        scanner.scan(Path::new(file), source)?;
+        Ok(scanner.finish())
+    }
+
+    #[test]
+    fn scan_items() -> Result<()> {
+        let items = scan_source("src/lib.rs", SOURCE)?;
+        let reports: Vec<String> = items.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            reports,
+            [
+                "src/lib.rs:10: `#[pyclass]` `Plain` has no `#[gen_stub_pyclass]`",
+                "src/lib.rs:13: `#[pymethods]` `Annotated` has no `#[gen_stub_pymethods]`",
+                "src/lib.rs:22: `#[pyfunction]` `plain` has no `#[gen_stub_pyfunction]`",
+                "src/lib.rs:27: `#[pyclass]` `Extra` has no `#[gen_stub_pyclass]` (possibly unreachable behind `#[cfg(...)]`)",
+                "src/lib.rs:31: `#[pyclass]` `MaybePython` has no `#[gen_stub_pyclass]` (possibly unreachable behind `#[cfg(...)]`)",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_error() {
+        let err = scan_source("src/broken.rs", "fn broken(").unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse src/broken.rs");
+    }
+}
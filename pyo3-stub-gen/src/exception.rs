@@ -2,44 +2,96 @@ use pyo3::exceptions::*;
 
 /// Wrapper of [pyo3::create_exception] macro to create a custom exception with [crate::PyStubType] support.
 ///
-/// Note
-/// -----
-/// [pyo3::create_exception!] macro creates a new exception type as [pyo3::PyErr],
-/// which does not implement [pyo3::PyClass] trait. So it is not a "class" in PyO3 sense,
-/// but we create a [crate::type_info::PyClassInfo] since it will be treated as a class eventually in Python side.
+/// This is [pyo3::create_exception!] followed by [crate::gen_stub_create_exception!],
+/// so that the exception is rendered in the stub file of `$module`.
 #[macro_export]
 macro_rules! create_exception {
     ($module: expr, $name: ident, $base: ty) => {
         $crate::create_exception!($module, $name, $base, "");
     };
+    ($module: expr, $name: ident, $base: ty, feature = $feature: literal) => {
+        $crate::create_exception!($module, $name, $base, "", feature = $feature);
+    };
+    ($module: expr, $name: ident, $base: ty, $doc: expr, feature = $feature: literal) => {
+        ::pyo3::create_exception!($module, $name, $base, $doc);
+        $crate::gen_stub_create_exception!($module, $name, $base, $doc, feature = $feature);
+    };
     ($module: expr, $name: ident, $base: ty, $doc: expr) => {
         ::pyo3::create_exception!($module, $name, $base, $doc);
+        $crate::gen_stub_create_exception!($module, $name, $base, $doc);
+    };
+}
 
-        // Add PyStubType implementation for the created exception
+/// Stub of an exception created by [pyo3::create_exception!], taking the same arguments.
+///
+/// ```rust
+/// use pyo3::exceptions::PyValueError;
+///
+/// pyo3::create_exception!(mypkg, ParseError, PyValueError, "Raised when the input is malformed.");
+/// pyo3_stub_gen::gen_stub_create_exception!(mypkg, ParseError, PyValueError, "Raised when the input is malformed.");
+///
+/// // An exception created by this crate can be the base of another one
+/// pyo3::create_exception!(mypkg.io, ReadError, ParseError);
+/// pyo3_stub_gen::gen_stub_create_exception!(mypkg.io, ReadError, ParseError);
+/// ```
+///
+/// The exception is rendered before the other classes of the module:
+///
+/// ```python
+/// class ParseError(builtins.ValueError):
+///     r"""
+///     Raised when the input is malformed.
+///     """
+///     ...
+/// ```
+///
+/// An exception behind an optional cargo feature can be tagged by the feature name
+/// as `#[gen_stub(feature = "...")]` of classes and functions:
+///
+/// ```rust
+/// # use pyo3::exceptions::PyRuntimeError;
+/// pyo3::create_exception!(mypkg, DeviceError, PyRuntimeError);
+/// pyo3_stub_gen::gen_stub_create_exception!(mypkg, DeviceError, PyRuntimeError, "", feature = "cuda");
+/// ```
+///
+/// Note
+/// -----
+/// [pyo3::create_exception!] macro creates a new exception type as [pyo3::PyErr],
+/// which does not implement [pyo3::PyClass] trait. So it is not a "class" in PyO3 sense,
+/// and is submitted as [crate::type_info::PyExceptionInfo] instead of [crate::type_info::PyClassInfo].
+#[macro_export]
+macro_rules! gen_stub_create_exception {
+    (@submit $module: expr, $name: ident, $base: ty, $doc: expr, $feature: expr) => {
         impl $crate::PyStubType for $name {
             fn type_output() -> $crate::TypeInfo {
-                $crate::TypeInfo::builtin(stringify!($name))
+                $crate::TypeInfo::locally_defined(stringify!($name), stringify!($module).into())
             }
         }
 
         $crate::inventory::submit! {
-            $crate::type_info::PyClassInfo {
-                pyclass_name: stringify!($name),
-                struct_id: std::any::TypeId::of::<$name>,
-                getters: &[],
-                setters: &[],
-                module: Some(stringify!($module)),
+            $crate::type_info::PyExceptionInfo {
+                name: stringify!($name),
+                module: stringify!($module),
+                rust_path: concat!(module_path!(), "::", stringify!($name)),
+                source_location: Some(concat!(file!(), ":", line!())),
+                base: <$base as $crate::PyStubType>::type_output,
                 doc: $doc,
-                bases: &[|| <$base as $crate::PyStubType>::type_output()],
-                has_eq: false,
-                has_ord: false,
-                has_hash: false,
-                has_str: false,
-                subclass: true,
-                is_abstract: false,
+                feature: $feature,
             }
         }
     };
+    ($module: expr, $name: ident, $base: ty) => {
+        $crate::gen_stub_create_exception!($module, $name, $base, "");
+    };
+    ($module: expr, $name: ident, $base: ty, feature = $feature: literal) => {
+        $crate::gen_stub_create_exception!($module, $name, $base, "", feature = $feature);
+    };
+    ($module: expr, $name: ident, $base: ty, $doc: expr, feature = $feature: literal) => {
+        $crate::gen_stub_create_exception!(@submit $module, $name, $base, $doc, Some($feature));
+    };
+    ($module: expr, $name: ident, $base: ty, $doc: expr) => {
+        $crate::gen_stub_create_exception!(@submit $module, $name, $base, $doc, None);
+    };
 }
 
 // Direct PyStubType implementations for PyO3 exception types
@@ -1,30 +1,105 @@
 //! Generate Python typing stub file a.k.a. `*.pyi` file.
 
+mod abc_bases;
+mod alias;
 mod class;
+mod combine;
+mod compat;
+mod conflict;
+mod constructor;
+mod container_default;
 mod deprecated;
 mod docstring;
+mod dunder;
 mod enum_;
+mod exception;
+mod filter;
+mod flag_enum;
+mod forward_ref;
 mod function;
+mod hidden_base;
+mod import_block;
+mod keyword;
+mod layout;
+mod lint;
+mod literal_group;
+mod local_ref;
+mod manifest;
+mod manual;
 mod member;
 mod method;
 mod module;
+mod module_rename;
+mod ordering;
+mod override_conflict;
+mod override_decorator;
 mod parameters;
+mod pickle;
+mod qualname;
+mod receiver;
+mod reference;
+mod sink;
 mod stub_info;
+mod synthesized;
+mod target_version;
+mod total_ordering;
+mod transform;
+mod type_ignore;
+mod validation;
 mod variable;
 mod variant_methods;
 
 pub use class::*;
+#[allow(deprecated)]
+pub use compat::{GetterDisplay, SetterDisplay};
+pub use conflict::{
+    name_conflict_resolver, set_name_conflict_resolver, ItemKind, NameConflict,
+    NameConflictResolver, NamedItem,
+};
+pub use constructor::{constructor_style, set_constructor_style, ConstructorStyle};
+pub use container_default::{
+    container_default_style, set_container_default_style, ContainerDefaultStyle,
+};
+pub use deprecated::{
+    annotate_deprecated_parameters, deprecated_style, set_annotate_deprecated_parameters,
+    set_deprecated_style, DeprecatedStyle, DeprecatedTarget,
+};
+pub use docstring::{
+    docstring_style, set_docstring_style, set_synthesize_attributes_section,
+    synthesize_attributes_section, DocstringStyle,
+};
 pub use enum_::*;
+pub use exception::*;
+pub use filter::glob_match;
+pub use forward_ref::ForwardReferences;
 pub use function::*;
+pub use import_block::ImportBlock;
+pub use keyword::KeywordParameters;
+pub use layout::StubLayout;
+pub use manifest::*;
 pub use member::*;
 pub use method::*;
 pub use module::*;
 pub use parameters::*;
+pub use qualname::*;
+pub use receiver::{annotate_cls, set_annotate_cls};
+pub use reference::{UnresolvedReference, UnresolvedReferences};
+pub use sink::*;
 pub use stub_info::*;
+pub use synthesized::*;
+pub use target_version::{set_target_version, target_version, TargetVersion};
+pub use type_ignore::TypeIgnore;
+pub use validation::Validator;
 pub use variable::*;
 
 use crate::stub_type::ImportRef;
+use container_default::docstring_with_container_defaults;
+use deprecated::{
+    append_deprecated_section, deprecated_decorator, docstring_with_deprecated_parameters,
+    docstring_with_deprecation,
+};
 use std::collections::HashSet;
+use type_ignore::type_ignore_comment;
 
 fn indent() -> &'static str {
     "    "
@@ -0,0 +1,418 @@
+//! Inference of `collections.abc` bases from the dunder methods of classes.
+//!
+//! Type checkers regard a class as e.g. a `Sequence` only if it is a subclass of it,
+//! even when it defines all the methods the ABC requires.
+
+use crate::{generate::*, stub_type::ImportRef, type_info::MethodType, TypeInfo};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+};
+
+/// Append the `collections.abc` bases implemented by the classes in `modules`,
+/// except the classes in `opted_out` by `#[gen_stub(no_abc)]`.
+pub(crate) fn add_abc_bases(modules: &mut BTreeMap<String, Module>, opted_out: &BTreeSet<TypeId>) {
+    // Items of iterator classes, for `__iter__` returning one of them
+    let items: HashMap<String, TypeInfo> = modules
+        .values()
+        .flat_map(|module| module.class.values())
+        .filter_map(|class| Some((class.name.to_string(), iterator_item(class)?)))
+        .collect();
+    for module in modules.values_mut() {
+        for (id, class) in module.class.iter_mut() {
+            if opted_out.contains(id) {
+                continue;
+            }
+            for base in abc_bases(class, &items) {
+                if !class
+                    .bases
+                    .iter()
+                    .any(|existing| existing.name == base.name)
+                {
+                    class.bases.push(base);
+                }
+            }
+        }
+    }
+}
+
+/// `collections.abc` bases implemented by the dunder methods of `class`,
+/// whose signatures are compatible with those of the ABCs
+fn abc_bases(class: &ClassDef, items: &HashMap<String, TypeInfo>) -> Vec<TypeInfo> {
+    let mut bases = Vec::new();
+    let iter = method(class, "__iter__").map(|iter| iter_item(class, &iter.r#return, items));
+    if let Some((key, value)) = mapping(class, iter.as_ref()) {
+        bases.push(abc("Mapping", &[key, value]));
+    } else if let Some(item) = sequence(class, iter.as_ref()) {
+        bases.push(abc("Sequence", &[item]));
+    } else {
+        if let Some(item) = iterator_item(class) {
+            bases.push(abc("Iterator", &[&item]));
+        } else if let Some(Some(item)) = &iter {
+            bases.push(abc("Iterable", &[item]));
+        }
+        if returns(class, "__len__", is_int) {
+            bases.push(abc("Sized", &[]));
+        }
+        if method(class, "__contains__").is_some() && contains_compatible(class) {
+            let item = iter.flatten().unwrap_or_else(TypeInfo::any);
+            bases.push(abc("Container", &[&item]));
+        }
+    }
+    if returns(class, "__hash__", is_int) {
+        bases.push(abc("Hashable", &[]));
+    }
+    bases
+}
+
+/// Key and value of `collections.abc.Mapping` implemented by `class`
+fn mapping<'a>(
+    class: &'a ClassDef,
+    iter: Option<&Option<TypeInfo>>,
+) -> Option<(&'a TypeInfo, &'a TypeInfo)> {
+    let [getitem] = overloads(class, "__getitem__")[..] else {
+        return None;
+    };
+    let (key, value) = (first_parameter(getitem)?, &getitem.r#return);
+    // A mapping with `int` keys is told from a sequence by `keys()`
+    let keys_defined = method(class, "keys").is_some();
+    let compatible = (!is_int(key) || keys_defined)
+        && returns(class, "__len__", is_int)
+        && iterates(iter, key)
+        && contains_compatible(class)
+        && method(class, "get").is_none()
+        && [
+            ("keys", format!("KeysView[{}]", key.name)),
+            ("values", format!("ValuesView[{}]", value.name)),
+            ("items", format!("ItemsView[{}, {}]", key.name, value.name)),
+        ]
+        .iter()
+        .all(|(name, view)| optional_returns(class, name, |r#return| is_abc(r#return, view)));
+    compatible.then_some((key, value))
+}
+
+/// Item of `collections.abc.Sequence` implemented by `class`,
+/// which requires `__getitem__` overloaded for `int` and `slice`
+fn sequence<'a>(class: &'a ClassDef, iter: Option<&Option<TypeInfo>>) -> Option<&'a TypeInfo> {
+    let getitem = overloads(class, "__getitem__");
+    let item = getitem
+        .iter()
+        .find(|getitem| first_parameter(getitem).is_some_and(is_int))
+        .map(|getitem| &getitem.r#return)?;
+    let slices = |getitem: &MethodDef| {
+        first_parameter(getitem).is_some_and(|index| index.name == "builtins.slice")
+            && is_sequence_of(class, &getitem.r#return, item)
+    };
+    let compatible = getitem.iter().any(|getitem| slices(getitem))
+        && getitem
+            .iter()
+            .all(|getitem| first_parameter(getitem).is_some_and(is_int) || slices(getitem))
+        && returns(class, "__len__", is_int)
+        && iterates(iter, item)
+        && contains_compatible(class)
+        && ["index", "count", "__reversed__"]
+            .iter()
+            .all(|name| method(class, name).is_none());
+    compatible.then_some(item)
+}
+
+/// Item of `collections.abc.Iterator` implemented by `class`,
+/// where `__next__` returning `None` is not compatible with it
+fn iterator_item(class: &ClassDef) -> Option<TypeInfo> {
+    let iter = &method(class, "__iter__")?.r#return;
+    let next = &method(class, "__next__")?.r#return;
+    (is_self(class, iter) && !is_optional(next)).then(|| next.clone())
+}
+
+/// Whether the item of the iterator returned by `__iter__` is `item`
+fn iterates(iter: Option<&Option<TypeInfo>>, item: &TypeInfo) -> bool {
+    match iter {
+        None => true,
+        Some(Some(iterated)) => is_any(iterated) || iterated.name == item.name,
+        Some(None) => false,
+    }
+}
+
+/// Whether `__contains__` takes any object as the ABCs do, if it is defined
+fn contains_compatible(class: &ClassDef) -> bool {
+    overloads(class, "__contains__").iter().all(|contains| {
+        first_parameter(contains).is_some_and(|item| is_any(item) || item.name == "builtins.object")
+            && (is_any(&contains.r#return) || contains.r#return.name == "builtins.bool")
+    })
+}
+
+/// Instance method `name` of `class`, or its first overload
+fn method<'a>(class: &'a ClassDef, name: &str) -> Option<&'a MethodDef> {
+    overloads(class, name).into_iter().next()
+}
+
+/// Overloads of instance method `name` of `class`
+fn overloads<'a>(class: &'a ClassDef, name: &str) -> Vec<&'a MethodDef> {
+    class
+        .methods
+        .get(name)
+        .into_iter()
+        .flatten()
+        .filter(|method| method.r#type == MethodType::Instance)
+        .collect()
+}
+
+/// Whether `class` defines instance method `name` whose every overload returns a type satisfying `expected`
+fn returns(class: &ClassDef, name: &str, expected: impl Fn(&TypeInfo) -> bool) -> bool {
+    method(class, name).is_some() && optional_returns(class, name, expected)
+}
+
+/// Same as [returns], but also holds if `class` does not define `name`
+fn optional_returns(class: &ClassDef, name: &str, expected: impl Fn(&TypeInfo) -> bool) -> bool {
+    overloads(class, name)
+        .iter()
+        .all(|method| is_any(&method.r#return) || expected(&method.r#return))
+}
+
+/// Type of the first parameter after `self`
+fn first_parameter(method: &MethodDef) -> Option<&TypeInfo> {
+    method
+        .parameters
+        .iter_entries()
+        .next()
+        .map(|parameter| &parameter.type_info)
+}
+
+fn is_int(type_info: &TypeInfo) -> bool {
+    type_info.name == "builtins.int"
+}
+
+fn is_any(type_info: &TypeInfo) -> bool {
+    type_info.name == "typing.Any"
+}
+
+fn is_optional(type_info: &TypeInfo) -> bool {
+    let name = type_info.name.as_str();
+    name == "None" || name.starts_with("typing.Optional[") || name.ends_with(" | None")
+}
+
+/// Whether `type_info` refers to `class` itself
+fn is_self(class: &ClassDef, type_info: &TypeInfo) -> bool {
+    let name = type_info.name.as_str();
+    name == "Self" || name.rsplit('.').next() == Some(class.name.as_ref())
+}
+
+/// Whether `type_info` is `collections.abc.{view}` or `typing.{view}`
+fn is_abc(type_info: &TypeInfo, view: &str) -> bool {
+    ["collections.abc.", "typing."]
+        .iter()
+        .any(|prefix| type_info.name.strip_prefix(prefix) == Some(view))
+}
+
+/// Whether `type_info`, returned for a slice, is a sequence of `item`
+fn is_sequence_of(class: &ClassDef, type_info: &TypeInfo, item: &TypeInfo) -> bool {
+    is_self(class, type_info)
+        || is_abc(type_info, &format!("Sequence[{}]", item.name))
+        || type_info.name == format!("builtins.list[{}]", item.name)
+        || type_info.name == format!("builtins.tuple[{}, ...]", item.name)
+}
+
+/// Item of the iterator returned by `__iter__`, `typing.Any` if it is `typing.Any`,
+/// or `None` if it is not known to be an iterator
+fn iter_item(
+    class: &ClassDef,
+    iter: &TypeInfo,
+    items: &HashMap<String, TypeInfo>,
+) -> Option<TypeInfo> {
+    if is_any(iter) {
+        return Some(TypeInfo::any());
+    }
+    let name = iter.name.as_str();
+    let class_name = if name == "Self" { &class.name } else { name };
+    let class_name = class_name.rsplit('.').next().unwrap_or(class_name);
+    if let Some(item) = items.get(class_name) {
+        return Some(item.clone());
+    }
+    ["collections.abc.Iterator[", "typing.Iterator["]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix)?.strip_suffix(']'))
+        .map(|item| TypeInfo {
+            name: item.to_string(),
+            import: iter.import.clone(),
+        })
+}
+
+/// `collections.abc.{name}[{args}]`
+fn abc(name: &str, args: &[&TypeInfo]) -> TypeInfo {
+    let mut import: HashSet<ImportRef> = args
+        .iter()
+        .flat_map(|arg| arg.import.iter().cloned())
+        .collect();
+    import.insert("collections.abc".into());
+    let name = if args.is_empty() {
+        format!("collections.abc.{name}")
+    } else {
+        let args: Vec<&str> = args.iter().map(|arg| arg.name.as_str()).collect();
+        format!("collections.abc.{name}[{}]", args.join(", "))
+    };
+    TypeInfo { name, import }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::ParameterKind;
+    use test_case::test_case;
+
+    fn method_def(name: &'static str, parameters: &[TypeInfo], r#return: TypeInfo) -> MethodDef {
+        let mut params = Parameters::new();
+        params.positional_or_keyword = parameters
+            .iter()
+            .map(|type_info| {
+                Parameter::new("arg", ParameterKind::PositionalOrKeyword, type_info.clone())
+            })
+            .collect();
+        MethodDef::new(name, MethodType::Instance, params, r#return)
+    }
+
+    fn class(methods: Vec<MethodDef>) -> ClassDef {
+        let mut class = ClassDef::new("Items");
+        for method in methods {
+            class.add_method(method);
+        }
+        class
+    }
+
+    fn int() -> TypeInfo {
+        TypeInfo::builtin("int")
+    }
+
+    fn str() -> TypeInfo {
+        TypeInfo::builtin("str")
+    }
+
+    fn object() -> TypeInfo {
+        TypeInfo::builtin("object")
+    }
+
+    fn bool() -> TypeInfo {
+        TypeInfo::builtin("bool")
+    }
+
+    #[test_case(
+        vec![
+            method_def("__getitem__", &[str()], int()),
+            method_def("__len__", &[], int()),
+            method_def("__iter__", &[], TypeInfo::any()),
+        ],
+        &["collections.abc.Mapping[builtins.str, builtins.int]"] ; "mapping"
+    )]
+    #[test_case(
+        vec![
+            method_def("__getitem__", &[int()], str()),
+            method_def("__len__", &[], int()),
+            method_def("__iter__", &[], TypeInfo::any()),
+            method_def("keys", &[], TypeInfo::any()),
+        ],
+        &["collections.abc.Mapping[builtins.int, builtins.str]"] ; "mapping with int keys"
+    )]
+    #[test_case(
+        vec![
+            method_def("__getitem__", &[str()], int()),
+            method_def("__len__", &[], int()),
+            method_def("__iter__", &[], TypeInfo::any()),
+            method_def("keys", &[], TypeInfo::list(str())),
+        ],
+        &["collections.abc.Iterable[typing.Any]", "collections.abc.Sized"] ; "mapping with incompatible keys"
+    )]
+    #[test_case(
+        vec![
+            method_def("__getitem__", &[int()], str()),
+            method_def("__getitem__", &[TypeInfo::builtin("slice")], TypeInfo::self_type()),
+            method_def("__len__", &[], int()),
+            method_def("__hash__", &[], int()),
+        ],
+        &["collections.abc.Sequence[builtins.str]", "collections.abc.Hashable"] ; "hashable sequence"
+    )]
+    #[test_case(
+        vec![
+            method_def("__getitem__", &[int()], str()),
+            method_def("__len__", &[], int()),
+        ],
+        &["collections.abc.Sized"] ; "getitem without slices"
+    )]
+    #[test_case(
+        vec![
+            method_def("__getitem__", &[int()], str()),
+            method_def("__getitem__", &[TypeInfo::builtin("slice")], TypeInfo::self_type()),
+            method_def("__len__", &[], int()),
+            method_def("__contains__", &[str()], bool()),
+        ],
+        &["collections.abc.Sized"] ; "sequence with narrow contains"
+    )]
+    #[test_case(
+        vec![
+            method_def("__iter__", &[], TypeInfo::self_type()),
+            method_def("__next__", &[], int()),
+        ],
+        &["collections.abc.Iterator[builtins.int]"] ; "iterator"
+    )]
+    #[test_case(
+        vec![
+            method_def("__iter__", &[], TypeInfo::self_type()),
+            method_def("__next__", &[], TypeInfo::with_module("typing.Optional[builtins.int]", "typing".into())),
+        ],
+        &[] ; "iterator returning none"
+    )]
+    #[test_case(
+        vec![
+            method_def("__iter__", &[], TypeInfo::unqualified("Cursor")),
+            method_def("__len__", &[], int()),
+            method_def("__contains__", &[object()], bool()),
+        ],
+        &[
+            "collections.abc.Iterable[builtins.float]",
+            "collections.abc.Sized",
+            "collections.abc.Container[builtins.float]",
+        ] ; "sized iterable container"
+    )]
+    #[test_case(
+        vec![method_def("__contains__", &[str()], bool())],
+        &[] ; "narrow container"
+    )]
+    #[test_case(
+        vec![method_def("__iter__", &[], TypeInfo::unqualified("Unknown"))],
+        &[] ; "unknown iterator"
+    )]
+    #[test_case(
+        vec![method_def("__iter__", &[], TypeInfo::any())],
+        &["collections.abc.Iterable[typing.Any]"] ; "any iterator"
+    )]
+    #[test_case(vec![method_def("__len__", &[], int())], &["collections.abc.Sized"] ; "sized")]
+    #[test_case(vec![method_def("__len__", &[], str())], &[] ; "len returning str")]
+    #[test_case(vec![method_def("__getitem__", &[int()], str())], &[] ; "getitem only")]
+    fn infer(methods: Vec<MethodDef>, expected: &[&str]) {
+        let items = HashMap::from([("Cursor".to_string(), TypeInfo::builtin("float"))]);
+        let bases = abc_bases(&class(methods), &items);
+        let names: Vec<&str> = bases.iter().map(|base| base.name.as_str()).collect();
+        assert_eq!(names, expected);
+        assert!(bases
+            .iter()
+            .all(|base| base.import.contains(&"collections.abc".into())));
+    }
+
+    #[test]
+    fn opted_out() {
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(
+            TypeId::of::<u8>(),
+            class(vec![method_def("__len__", &[], int())]),
+        );
+        module.class.insert(
+            TypeId::of::<u16>(),
+            class(vec![method_def("__len__", &[], int())]),
+        );
+        let mut modules = BTreeMap::from([("pkg".to_string(), module)]);
+        add_abc_bases(&mut modules, &BTreeSet::from([TypeId::of::<u16>()]));
+        let class = &modules["pkg"].class;
+        assert_eq!(class[&TypeId::of::<u8>()].bases.len(), 1);
+        assert!(class[&TypeId::of::<u16>()].bases.is_empty());
+        let rendered = modules["pkg"].to_string();
+        assert!(rendered.contains("import collections.abc"), "{rendered}");
+    }
+}
@@ -0,0 +1,357 @@
+//! Additional names of methods and properties given by `#[gen_stub(also_as(...))]`.
+
+use crate::{generate::*, type_info::*};
+use anyhow::{bail, Result};
+use std::{any::TypeId, collections::BTreeMap};
+
+/// Method or property of a class exposed under additional names
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Aliased {
+    Method(&'static MethodInfo),
+    Getter(&'static MemberInfo),
+    Setter(&'static MemberInfo),
+}
+
+impl Aliased {
+    fn name(&self) -> &'static str {
+        match self {
+            Aliased::Method(info) => info.name,
+            Aliased::Getter(info) | Aliased::Setter(info) => info.name,
+        }
+    }
+
+    fn also_as(&self) -> &'static [&'static str] {
+        match self {
+            Aliased::Method(info) => info.also_as,
+            Aliased::Getter(info) | Aliased::Setter(info) => info.also_as,
+        }
+    }
+}
+
+/// Collect the methods and properties submitted to [inventory] which have additional names
+pub(crate) fn aliased_members() -> Vec<(TypeId, Aliased)> {
+    let mut members = Vec::new();
+    let mut collect =
+        |struct_id: TypeId, getters: &'static [MemberInfo], setters: &'static [MemberInfo]| {
+            members.extend(
+                getters
+                    .iter()
+                    .map(|info| (struct_id, Aliased::Getter(info))),
+            );
+            members.extend(
+                setters
+                    .iter()
+                    .map(|info| (struct_id, Aliased::Setter(info))),
+            );
+        };
+    for info in inventory::iter::<PyClassInfo> {
+        collect((info.struct_id)(), info.getters, info.setters);
+    }
+    for info in inventory::iter::<PyMethodsInfo> {
+        collect((info.struct_id)(), info.getters, info.setters);
+    }
+    for info in inventory::iter::<PyMethodsInfo> {
+        let struct_id = (info.struct_id)();
+        members.extend(
+            info.methods
+                .iter()
+                .map(|info| (struct_id, Aliased::Method(info))),
+        );
+    }
+    members.retain(|(_, member)| !member.also_as().is_empty());
+    members
+}
+
+/// Add the members in `aliased` to their classes under their additional names.
+///
+/// Fails if an additional name is already defined in the class, or given to another member.
+pub(crate) fn add_aliases(
+    modules: &mut BTreeMap<String, Module>,
+    aliased: &[(TypeId, Aliased)],
+) -> Result<()> {
+    // Member each additional name is given to
+    let mut given: BTreeMap<(TypeId, &str), &str> = BTreeMap::new();
+    for (struct_id, member) in aliased {
+        for &alias in member.also_as() {
+            for module in modules.values_mut() {
+                if let Some(class) = module.class.get_mut(struct_id) {
+                    check_alias(&mut given, *struct_id, &class.name, *member, alias, || {
+                        class.defined_as(alias)
+                    })?;
+                    class.add_alias(*member, alias, &module.name, &module.default_module_name);
+                } else if let Some(enum_) = module.enum_.get_mut(struct_id) {
+                    check_alias(&mut given, *struct_id, enum_.name, *member, alias, || {
+                        enum_.defined_as(alias)
+                    })?;
+                    enum_.add_alias(*member, alias, &module.name, &module.default_module_name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_alias<'a>(
+    given: &mut BTreeMap<(TypeId, &'a str), &'a str>,
+    struct_id: TypeId,
+    class_name: &str,
+    member: Aliased,
+    alias: &'a str,
+    defined_as: impl FnOnce() -> Option<&'static str>,
+) -> Result<()> {
+    let source = member.name();
+    match given.get(&(struct_id, alias)) {
+        // Overloads of a method, or the getter and setter of a property
+        Some(&other) if other == source => return Ok(()),
+        Some(&other) => bail!(
+            "`{class_name}.{alias}` given by `#[gen_stub(also_as(\"{alias}\"))]` on `{source}` \
+             is also given to `{other}`"
+        ),
+        None => {}
+    }
+    if let Some(kind) = defined_as() {
+        bail!(
+            "`{class_name}.{alias}` given by `#[gen_stub(also_as(\"{alias}\"))]` on `{source}` \
+             is already defined as a {kind}"
+        );
+    }
+    given.insert((struct_id, alias), source);
+    Ok(())
+}
+
+impl ClassDef {
+    /// Kind of the member named `name`, if defined
+    fn defined_as(&self, name: &str) -> Option<&'static str> {
+        if self.methods.contains_key(name) {
+            Some("method")
+        } else if self.getter_setters.contains_key(name) {
+            Some("property")
+        } else if self.attrs.iter().any(|attr| attr.name == name) {
+            Some("class attribute")
+        } else {
+            None
+        }
+    }
+
+    fn add_alias(
+        &mut self,
+        member: Aliased,
+        alias: &'static str,
+        module: &str,
+        default_module_name: &str,
+    ) {
+        match member {
+            Aliased::Method(info) => {
+                let mut method = MethodDef::named(info, alias);
+                method.resolve_enter_return(&self.name, module, default_module_name);
+                if method.is_abstract {
+                    self.mark_abstract();
+                }
+                self.methods
+                    .entry(alias.to_string())
+                    .or_default()
+                    .push(method);
+            }
+            Aliased::Getter(info) => {
+                self.getter_setters.entry(alias.to_string()).or_default().0 = Some(MemberDef {
+                    name: alias,
+                    ..MemberDef::from(info)
+                });
+            }
+            Aliased::Setter(info) => {
+                self.getter_setters.entry(alias.to_string()).or_default().1 = Some(MemberDef {
+                    name: alias,
+                    ..MemberDef::from(info)
+                });
+            }
+        }
+    }
+}
+
+impl EnumDef {
+    /// Kind of the member named `name`, if defined
+    fn defined_as(&self, name: &str) -> Option<&'static str> {
+        if self.methods.iter().any(|method| method.name == name) {
+            Some("method")
+        } else if self
+            .getters
+            .iter()
+            .chain(&self.setters)
+            .any(|member| member.name == name)
+        {
+            Some("property")
+        } else if self.attrs.iter().any(|attr| attr.name == name) {
+            Some("class attribute")
+        } else if self.variants.iter().any(|(variant, _)| *variant == name) {
+            Some("variant")
+        } else {
+            None
+        }
+    }
+
+    fn add_alias(
+        &mut self,
+        member: Aliased,
+        alias: &'static str,
+        module: &str,
+        default_module_name: &str,
+    ) {
+        match member {
+            Aliased::Method(info) => {
+                let mut method = MethodDef::named(info, alias);
+                method.resolve_enter_return(self.name, module, default_module_name);
+                self.methods.push(method);
+            }
+            Aliased::Getter(info) => self.getters.push(MemberDef {
+                name: alias,
+                ..MemberDef::from(info)
+            }),
+            Aliased::Setter(info) => self.setters.push(MemberDef {
+                name: alias,
+                ..MemberDef::from(info)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static LEN: MethodInfo = MethodInfo {
+        name: "__len__",
+        parameters: &[],
+        r#return: <usize as crate::PyStubType>::type_output,
+        doc: "Number of items",
+        r#type: MethodType::Instance,
+        is_async: false,
+        deprecated: None,
+        type_ignored: None,
+        is_abstract: false,
+        also_as: &["count"],
+        keep_signature: false,
+        source_location: None,
+    };
+
+    static VALUE: MemberInfo = MemberInfo {
+        name: "value",
+        r#type: <f64 as crate::PyStubType>::type_output,
+        doc: "Current value",
+        default: None,
+        deprecated: None,
+        item: false,
+        is_abstract: false,
+        also_as: &["legacy_value"],
+        is_classattr: false,
+        int_value: None,
+        type_ignored: None,
+    };
+
+    fn modules() -> BTreeMap<String, Module> {
+        let mut class = ClassDef {
+            subclass: true,
+            ..ClassDef::new("Counter")
+        };
+        class
+            .methods
+            .insert("__len__".into(), vec![MethodDef::from(&LEN)]);
+        class.getter_setters.insert(
+            "value".into(),
+            (Some(MemberDef::from(&VALUE)), Some(MemberDef::from(&VALUE))),
+        );
+        let mut module = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        module.class.insert(TypeId::of::<u8>(), class);
+        BTreeMap::from([("pkg".to_string(), module)])
+    }
+
+    #[test]
+    fn method_and_property_aliases() -> Result<()> {
+        let mut modules = modules();
+        let id = TypeId::of::<u8>();
+        add_aliases(
+            &mut modules,
+            &[
+                (id, Aliased::Method(&LEN)),
+                (id, Aliased::Getter(&VALUE)),
+                (id, Aliased::Setter(&VALUE)),
+            ],
+        )?;
+        assert_eq!(
+            modules["pkg"].class[&id].to_string(),
+            r#"class Counter:
+    @property
+    def value(self) -> builtins.float:
+        r"""
+        Current value
+        """
+    @value.setter
+    def value(self, value: builtins.float) -> None:
+        r"""
+        Current value
+        """
+    @property
+    def legacy_value(self) -> builtins.float:
+        r"""
+        Current value
+        """
+    @legacy_value.setter
+    def legacy_value(self, value: builtins.float) -> None:
+        r"""
+        Current value
+        """
+    def __len__(self) -> builtins.int:
+        r"""
+        Number of items
+        """
+    def count(self) -> builtins.int:
+        r"""
+        Number of items
+        """
+
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn alias_conflicts_with_existing_member() {
+        let mut modules = modules();
+        let id = TypeId::of::<u8>();
+        let class = modules.get_mut("pkg").unwrap().class.get_mut(&id).unwrap();
+        class
+            .methods
+            .insert("count".into(), vec![MethodDef::from(&LEN)]);
+        let err = add_aliases(&mut modules, &[(id, Aliased::Method(&LEN))]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Counter.count` given by `#[gen_stub(also_as(\"count\"))]` on `__len__` is already defined as a method"
+        );
+
+        static TOTAL: MemberInfo = MemberInfo {
+            name: "total",
+            r#type: <f64 as crate::PyStubType>::type_output,
+            doc: "",
+            default: None,
+            deprecated: None,
+            item: false,
+            is_abstract: false,
+            also_as: &["legacy_value"],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
+        };
+        let err = add_aliases(
+            &mut super::tests::modules(),
+            &[(id, Aliased::Getter(&VALUE)), (id, Aliased::Getter(&TOTAL))],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Counter.legacy_value` given by `#[gen_stub(also_as(\"legacy_value\"))]` on `total` is also given to `value`"
+        );
+    }
+}
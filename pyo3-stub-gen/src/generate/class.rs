@@ -3,20 +3,24 @@ use indexmap::IndexMap;
 use crate::generate::variant_methods::get_variant_methods;
 use crate::{
     generate::{
-        docstring, indent, GetterDisplay, Import, MemberDef, MethodDef, Parameter,
-        ParameterDefault, Parameters, SetterDisplay,
+        constructor_style, deprecated_decorator, docstring, docstring_with_deprecation, indent,
+        member::{GetterDisplay, SetterDisplay},
+        method::StyledMethod,
+        target_version, ConstructorStyle, DeprecatedTarget, Import, MemberDef, MethodDef,
+        Parameter, ParameterDefault, Parameters, QualName,
     },
     stub_type::{ImportRef, ModuleRef},
     type_info::*,
     TypeInfo,
 };
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 use std::{fmt, vec};
 
 /// Definition of a Python class.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct ClassDef {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     pub doc: &'static str,
     pub attrs: Vec<MemberDef>,
     pub getter_setters: IndexMap<String, (Option<MemberDef>, Option<MemberDef>)>,
@@ -25,7 +29,85 @@ pub struct ClassDef {
     pub classes: Vec<ClassDef>,
     pub match_args: Option<Vec<String>>,
     pub subclass: bool,
+    /// Render `metaclass=abc.ABCMeta` after the bases, so that checkers reject instantiating the class
     pub is_abstract: bool,
+    /// Render as `@dataclasses.dataclass` instead of properties and constructor
+    pub dataclass: Option<DataclassDef>,
+    /// Render `__slots__` listing the attributes and properties
+    pub slots: Option<SlotsDef>,
+    /// Render as `enum.Flag` with these members instead of class attributes
+    pub flag: Option<FlagDef>,
+    /// Render `@functools.total_ordering`, with the comparisons it supplies synthesized
+    pub total_ordering: bool,
+    /// Deprecation by `#[deprecated]` on the struct
+    pub deprecated: Option<DeprecatedInfo>,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+}
+
+/// Fields of a class rendered as `@dataclasses.dataclass`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct DataclassDef {
+    /// Fields in the order of the Rust struct
+    pub fields: Vec<MemberDef>,
+    /// `frozen=True` is set when neither a field nor `#[pymethods]` has a setter
+    pub frozen: bool,
+}
+
+impl DataclassDef {
+    /// Fields without defaults come first since dataclasses reject a required field after a defaulted one.
+    fn ordered_fields(&self) -> impl Iterator<Item = &MemberDef> {
+        let (required, defaulted): (Vec<_>, Vec<_>) = self
+            .fields
+            .iter()
+            .partition(|field| field.default.is_none());
+        required.into_iter().chain(defaulted)
+    }
+
+    /// Check that `#[new]` takes the fields in the order of the synthesized `__init__`
+    pub(crate) fn check_constructor(&self, new: &MethodDef) -> anyhow::Result<()> {
+        let params = &new.parameters;
+        let params: Vec<_> = params
+            .positional_only
+            .iter()
+            .chain(&params.positional_or_keyword)
+            .chain(&params.keyword_only)
+            .map(|param| param.name)
+            .collect();
+        let fields: Vec<_> = self.ordered_fields().map(|field| field.name).collect();
+        anyhow::ensure!(
+            params == fields,
+            "it takes ({}), but the dataclass is constructed by ({})",
+            params.join(", "),
+            fields.join(", ")
+        );
+        Ok(())
+    }
+}
+
+/// `__slots__` of a class by `#[gen_stub(slots)]`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SlotsDef {
+    /// `__weakref__` is listed for `#[pyclass(weakref)]`
+    pub weakref: bool,
+}
+
+impl SlotsDef {
+    fn from_info(info: &PyClassInfo) -> Option<Self> {
+        info.slots.then_some(Self {
+            weakref: info.weakref,
+        })
+    }
+}
+
+/// Members of a class rendered as `enum.Flag` by `#[gen_stub(flag_enum)]`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct FlagDef {
+    /// Members rendered as `NAME = value`, or `NAME = ...` without a default
+    pub members: Vec<MemberDef>,
 }
 
 impl Import for ClassDef {
@@ -38,6 +120,32 @@ impl Import for ClassDef {
         if self.is_abstract {
             import.insert("abc".into());
         }
+        if self.total_ordering {
+            import.insert("functools".into());
+        }
+        if deprecated_decorator(&self.deprecated, DeprecatedTarget::Class).is_some() {
+            import.insert("typing_extensions".into());
+        }
+        if let Some(dataclass) = &self.dataclass {
+            import.insert("dataclasses".into());
+            if !self.attrs.is_empty() {
+                // for `typing.ClassVar`
+                import.insert("typing".into());
+            }
+            for field in &dataclass.fields {
+                import.extend(field.import());
+            }
+        }
+        if self.match_args.is_some() {
+            // for `__match_args__: typing.Final[tuple[builtins.str, ...]]`
+            import.insert("typing".into());
+            import.insert("builtins".into());
+        }
+        if self.slots.is_some() {
+            // for `__slots__: tuple[builtins.str, ...]`, which is `typing.Tuple` for Python 3.8
+            import.insert("typing".into());
+            import.insert("builtins".into());
+        }
         for base in &self.bases {
             import.extend(base.import.clone());
         }
@@ -74,7 +182,7 @@ impl From<&PyComplexEnumInfo> for ClassDef {
         // This is only an initializer. See `StubInfo::gather` for the actual merging.
 
         let enum_info = Self {
-            name: info.pyclass_name,
+            name: info.pyclass_name.into(),
             doc: info.doc,
             getter_setters: IndexMap::new(),
             methods: IndexMap::new(),
@@ -88,6 +196,12 @@ impl From<&PyComplexEnumInfo> for ClassDef {
             attrs: Vec::new(),
             subclass: true, // Complex enums can be subclassed by their variants
             is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
         };
 
         enum_info
@@ -95,10 +209,47 @@ impl From<&PyComplexEnumInfo> for ClassDef {
 }
 
 impl ClassDef {
+    /// Class without any members or bases, which is not allowed to be subclassed
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            doc: "",
+            attrs: Vec::new(),
+            getter_setters: IndexMap::new(),
+            methods: IndexMap::new(),
+            bases: Vec::new(),
+            classes: Vec::new(),
+            match_args: None,
+            subclass: false,
+            is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
+        }
+    }
+
+    /// Add a method after the others, or an overload of the method of the same name
+    pub fn add_method(&mut self, method: MethodDef) {
+        self.methods
+            .entry(method.name.to_string())
+            .or_default()
+            .push(method);
+    }
+
+    /// Add a property read by `getter`, which is writable if `settable`
+    pub fn add_property(&mut self, getter: MemberDef, settable: bool) {
+        let setter = settable.then(|| getter.clone());
+        self.getter_setters
+            .insert(getter.name.to_string(), (Some(getter), setter));
+    }
+
     fn from_variant(enum_info: &PyComplexEnumInfo, info: &VariantInfo) -> Self {
         if info.is_mapping {
             return Self {
-                name: info.pyclass_name,
+                name: info.pyclass_name.into(),
                 doc: info.doc,
                 attrs: info.fields.iter().map(MemberDef::from).collect(),
                 getter_setters: IndexMap::new(),
@@ -111,12 +262,18 @@ impl ClassDef {
                 match_args: None,
                 subclass: true,
                 is_abstract: false,
+                dataclass: None,
+                slots: None,
+                flag: None,
+                total_ordering: false,
+                deprecated: None,
+                qualname: QualName::default(),
             };
         }
         let methods = get_variant_methods(enum_info, info);
 
         Self {
-            name: info.pyclass_name,
+            name: info.pyclass_name.into(),
             doc: info.doc,
             getter_setters: info
                 .fields
@@ -126,10 +283,22 @@ impl ClassDef {
             methods,
             classes: Vec::new(),
             bases: vec![TypeInfo::unqualified(enum_info.pyclass_name)],
-            match_args: Some(info.fields.iter().map(|f| f.name.to_string()).collect()),
+            // `__match_args__` follows the field order, not the order of constructor arguments
+            match_args: match info.form {
+                VariantForm::Unit => None,
+                VariantForm::Tuple | VariantForm::Struct => {
+                    Some(info.fields.iter().map(|f| f.name.to_string()).collect())
+                }
+            },
             attrs: Vec::new(),
             subclass: false,
             is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
         }
     }
 }
@@ -138,6 +307,9 @@ impl From<&PyClassInfo> for ClassDef {
     fn from(info: &PyClassInfo) -> Self {
         // Since there are multiple `#[pymethods]` for a single class, we need to merge them.
         // This is only an initializer. See `StubInfo::gather` for the actual merging.
+        if info.dataclass {
+            return Self::dataclass_from(info);
+        }
         let mut getter_setters: IndexMap<String, (Option<MemberDef>, Option<MemberDef>)> = info
             .getters
             .iter()
@@ -150,21 +322,168 @@ impl From<&PyClassInfo> for ClassDef {
                 doc: setter.doc,
                 default: setter.default.map(|f| f()),
                 deprecated: setter.deprecated.clone(),
+                type_ignored: setter.type_ignored.map(Into::into),
                 is_abstract: setter.is_abstract,
+                is_classattr: false,
+                qualname: QualName::default(),
             });
         }
         let mut new = Self {
-            name: info.pyclass_name,
+            name: info.pyclass_name.into(),
             doc: info.doc,
             attrs: Vec::new(),
             getter_setters,
             methods: Default::default(),
             classes: Vec::new(),
-            bases: info.bases.iter().map(|f| f()).collect(),
+            bases: bases(info),
+            match_args: None,
+            subclass: info.subclass || info.is_abstract,
+            is_abstract: info.is_abstract,
+            dataclass: None,
+            slots: SlotsDef::from_info(info),
+            flag: None,
+            total_ordering: false,
+            deprecated: info.deprecated.clone(),
+            qualname: QualName::default(),
+        };
+        new.add_pyclass_methods(info);
+        new
+    }
+}
+
+impl From<&TypedDictInfo> for ClassDef {
+    fn from(info: &TypedDictInfo) -> Self {
+        let attrs = info
+            .fields
+            .iter()
+            .map(|field| {
+                let r#type = (field.r#type)();
+                MemberDef {
+                    name: field.name,
+                    r#type: if field.required {
+                        r#type
+                    } else {
+                        TypeInfo::not_required(r#type)
+                    },
+                    doc: field.doc,
+                    default: None,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    is_classattr: false,
+                    qualname: QualName::default(),
+                }
+            })
+            .collect();
+        Self {
+            name: info.name.into(),
+            doc: info.doc,
+            attrs,
+            getter_setters: IndexMap::new(),
+            methods: Default::default(),
+            classes: Vec::new(),
+            bases: vec![TypeInfo::with_module("typing.TypedDict", "typing".into())],
+            match_args: None,
+            // TypedDicts cannot be marked final, as for mapping variants
+            subclass: true,
+            is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
+        }
+    }
+}
+
+impl From<&NamedTupleInfo> for ClassDef {
+    fn from(info: &NamedTupleInfo) -> Self {
+        let attrs = info
+            .fields
+            .iter()
+            .map(|field| MemberDef {
+                name: field.name,
+                r#type: (field.r#type)(),
+                doc: "",
+                default: None,
+                deprecated: None,
+                type_ignored: None,
+                is_abstract: false,
+                is_classattr: false,
+                qualname: QualName::default(),
+            })
+            .collect();
+        Self {
+            name: info.name.into(),
+            doc: info.doc,
+            attrs,
+            getter_setters: IndexMap::new(),
+            methods: Default::default(),
+            classes: Vec::new(),
+            bases: vec![TypeInfo::with_module("typing.NamedTuple", "typing".into())],
+            match_args: None,
+            // `@typing.final` is not rendered for a named tuple, as for an exception
+            subclass: true,
+            is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
+        }
+    }
+}
+
+/// Bases given by `#[pyclass(extends = ...)]`
+///
+/// A class skipped by `#[gen_stub(skip)]` is rendered as `typing.Any`, which is dropped
+/// since it cannot be extended in stubs.
+fn bases(info: &PyClassInfo) -> Vec<TypeInfo> {
+    info.bases
+        .iter()
+        .map(|f| f())
+        .filter(|base| *base != TypeInfo::any())
+        .collect()
+}
+
+impl ClassDef {
+    fn dataclass_from(info: &PyClassInfo) -> Self {
+        let mut fields: IndexMap<&str, MemberDef> = IndexMap::new();
+        for member in info.getters.iter().chain(info.setters) {
+            fields
+                .entry(member.name)
+                .or_insert_with(|| MemberDef::from(member));
+        }
+        let mut new = Self {
+            name: info.pyclass_name.into(),
+            doc: info.doc,
+            attrs: Vec::new(),
+            getter_setters: IndexMap::new(),
+            methods: Default::default(),
+            classes: Vec::new(),
+            bases: bases(info),
             match_args: None,
             subclass: info.subclass || info.is_abstract,
             is_abstract: info.is_abstract,
+            dataclass: Some(DataclassDef {
+                fields: fields.into_values().collect(),
+                frozen: info.setters.is_empty(),
+            }),
+            slots: SlotsDef::from_info(info),
+            flag: None,
+            total_ordering: false,
+            deprecated: info.deprecated.clone(),
+            qualname: QualName::default(),
         };
+        new.add_pyclass_methods(info);
+        new
+    }
+
+    /// Add methods implied by `#[pyclass(...)]` options
+    fn add_pyclass_methods(&mut self, info: &PyClassInfo) {
+        let new = self;
         if new.getter_setters.values().any(|(getter, setter)| {
             getter.as_ref().is_some_and(|m| m.is_abstract)
                 || setter.as_ref().is_some_and(|m| m.is_abstract)
@@ -186,28 +505,27 @@ impl From<&PyClassInfo> for ClassDef {
         if info.is_abstract {
             new.mark_abstract();
         }
-        new
     }
 }
+
 impl ClassDef {
+    /// Render this class with `metaclass=abc.ABCMeta`, which is not inherited by the stubs of its subclasses
     pub(crate) fn mark_abstract(&mut self) {
         self.is_abstract = true;
         self.subclass = true;
-        if !self.bases.iter().any(|base| base.name == "abc.ABC") {
-            self.bases
-                .push(TypeInfo::with_module("abc.ABC", ModuleRef::from("abc")));
-        }
     }
 
     fn add_eq_method(&mut self) {
         let method = MethodDef {
-            name: "__eq__",
+            name: "__eq__".into(),
             parameters: Parameters {
                 positional_or_keyword: vec![Parameter {
                     name: "other",
                     kind: ParameterKind::PositionalOrKeyword,
                     type_info: TypeInfo::builtin("object"),
                     default: ParameterDefault::None,
+                    container_default: None,
+                    deprecated: None,
                 }],
                 ..Parameters::new()
             },
@@ -218,6 +536,9 @@ impl ClassDef {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: None,
         };
         self.methods
             .entry("__eq__".to_string())
@@ -230,13 +551,15 @@ impl ClassDef {
 
         for name in &ord_methods {
             let method = MethodDef {
-                name,
+                name: (*name).into(),
                 parameters: Parameters {
                     positional_or_keyword: vec![Parameter {
                         name: "other",
                         kind: ParameterKind::PositionalOrKeyword,
                         type_info: TypeInfo::builtin("object"),
                         default: ParameterDefault::None,
+                        container_default: None,
+                        deprecated: None,
                     }],
                     ..Parameters::new()
                 },
@@ -247,6 +570,9 @@ impl ClassDef {
                 deprecated: None,
                 type_ignored: None,
                 is_abstract: false,
+                is_override: false,
+                qualname: QualName::default(),
+                source_location: None,
             };
             self.methods
                 .entry(name.to_string())
@@ -257,7 +583,7 @@ impl ClassDef {
 
     fn add_hash_method(&mut self) {
         let method = MethodDef {
-            name: "__hash__",
+            name: "__hash__".into(),
             parameters: Parameters::new(),
             r#return: TypeInfo::builtin("int"),
             doc: "",
@@ -266,6 +592,9 @@ impl ClassDef {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: None,
         };
         self.methods
             .entry("__hash__".to_string())
@@ -275,7 +604,7 @@ impl ClassDef {
 
     fn add_str_method(&mut self) {
         let method = MethodDef {
-            name: "__str__",
+            name: "__str__".into(),
             parameters: Parameters::new(),
             r#return: TypeInfo::builtin("str"),
             doc: "",
@@ -284,6 +613,9 @@ impl ClassDef {
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: None,
         };
         self.methods
             .entry("__str__".to_string())
@@ -292,37 +624,137 @@ impl ClassDef {
     }
 }
 
+impl ClassDef {
+    /// Attributes and properties in the order they are rendered
+    fn attributes(&self) -> Vec<&MemberDef> {
+        let fields = self
+            .dataclass
+            .iter()
+            .flat_map(|dataclass| dataclass.ordered_fields());
+        let properties = self
+            .getter_setters
+            .values()
+            .filter_map(|(getter, setter)| getter.as_ref().or(setter.as_ref()));
+        fields.chain(&self.attrs).chain(properties).collect()
+    }
+
+    /// Names in `__slots__`, i.e. the instance attributes and properties
+    fn slot_names(&self, slots: &SlotsDef) -> Vec<String> {
+        // Class attributes conflict with slots of the same name, and all attributes of a dataclass are class variables
+        let attrs: Vec<_> = match &self.dataclass {
+            Some(dataclass) => dataclass.fields.iter().collect(),
+            None => self
+                .attrs
+                .iter()
+                .filter(|attr| !attr.is_classattr)
+                .collect(),
+        };
+        attrs
+            .into_iter()
+            .map(|attr| attr.name.to_string())
+            .chain(self.getter_setters.keys().cloned())
+            .chain(slots.weakref.then(|| "__weakref__".to_string()))
+            .collect()
+    }
+}
+
+/// Tuple literal of `names` as strings, e.g. `("a", "b",)`
+fn str_tuple(names: &[String]) -> String {
+    if names.is_empty() {
+        return "()".to_string();
+    }
+    let names = names
+        .iter()
+        .map(|name| format!(r##""{name}""##))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("({names},)")
+}
+
 impl fmt::Display for ClassDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let metaclass = self
+            .is_abstract
+            .then(|| "metaclass=abc.ABCMeta".to_string());
         let bases = self
             .bases
             .iter()
             .map(|i| i.name.clone())
+            .chain(metaclass)
             .reduce(|acc, path| format!("{acc}, {path}"))
             .map(|bases| format!("({bases})"))
             .unwrap_or_default();
+        if let Some(deprecated) = deprecated_decorator(&self.deprecated, DeprecatedTarget::Class) {
+            writeln!(f, "{deprecated}")?;
+        }
         if !self.subclass {
             writeln!(f, "@typing.final")?;
         }
+        match &self.dataclass {
+            Some(DataclassDef { frozen: true, .. }) => {
+                writeln!(f, "@dataclasses.dataclass(frozen=True)")?
+            }
+            Some(_) => writeln!(f, "@dataclasses.dataclass")?,
+            None => {}
+        }
+        if self.total_ordering {
+            writeln!(f, "@functools.total_ordering")?;
+        }
         writeln!(f, "class {}{}:", self.name, bases)?;
         let indent = indent();
-        let doc = self.doc.trim();
-        docstring::write_docstring(f, doc, indent)?;
+        let doc = docstring_with_deprecation(self.doc, &self.deprecated, DeprecatedTarget::Class);
+        let mut doc = docstring::docstring_lines(&doc);
+        if docstring::synthesize_attributes_section() {
+            docstring::append_attributes_section(
+                &mut doc,
+                &self.attributes(),
+                docstring::docstring_style(),
+            );
+        }
+        docstring::write_docstring_lines(f, &doc, indent)?;
 
         if let Some(match_args) = &self.match_args {
-            if match_args.is_empty() {
-                writeln!(f, "{indent}__match_args__ = ()")?;
-            } else {
-                let match_args_txt = match_args
-                    .iter()
-                    .map(|a| format!(r##""{a}""##))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                writeln!(f, "{indent}__match_args__ = ({match_args_txt},)")?;
-            }
+            writeln!(
+                f,
+                "{indent}__match_args__: {} = {}",
+                target_version::annotation("typing.Final[tuple[builtins.str, ...]]"),
+                str_tuple(match_args)
+            )?;
         }
-        for attr in &self.attrs {
-            attr.fmt(f)?;
+        if let Some(slots) = &self.slots {
+            writeln!(
+                f,
+                "{indent}__slots__: {} = {}",
+                target_version::annotation("tuple[builtins.str, ...]"),
+                str_tuple(&self.slot_names(slots))
+            )?;
+        }
+        if let Some(dataclass) = &self.dataclass {
+            for field in dataclass.ordered_fields() {
+                field.fmt(f)?;
+            }
+            // Class attributes must not become fields
+            for attr in &self.attrs {
+                MemberDef {
+                    r#type: TypeInfo {
+                        name: format!("typing.ClassVar[{}]", attr.r#type.name),
+                        import: attr.r#type.import.clone(),
+                    },
+                    // `typing.Final` cannot be nested in `typing.ClassVar`
+                    is_classattr: false,
+                    ..attr.clone()
+                }
+                .fmt(f)?;
+            }
+        } else {
+            for member in self.flag.iter().flat_map(|flag| &flag.members) {
+                let value = member.default.as_deref().unwrap_or("...");
+                writeln!(f, "{indent}{} = {value}", member.name)?;
+                docstring::write_docstring(f, member.doc, indent)?;
+            }
+            for attr in &self.attrs {
+                attr.fmt(f)?;
+            }
         }
         for (getter, setter) in self.getter_setters.values() {
             if let Some(getter) = getter {
@@ -332,13 +764,37 @@ impl fmt::Display for ClassDef {
                 SetterDisplay(setter).fmt(f)?;
             }
         }
-        for methods in self.methods.values() {
+        for (name, methods) in &self.methods {
+            // `__init__` is synthesized from the fields of a dataclass
+            if self.dataclass.is_some() && (name == "__new__" || name == "__init__") {
+                continue;
+            }
             let overloaded = methods.len() > 1;
-            for method in methods {
-                if overloaded {
-                    writeln!(f, "{indent}@typing.overload")?;
+            // Overloads of a constructor rendered as both are grouped into those of `__new__` and `__init__`
+            let styles = match constructor_style() {
+                ConstructorStyle::Both
+                    if overloaded && methods.iter().any(|m| m.r#type == MethodType::New) =>
+                {
+                    vec![ConstructorStyle::New, ConstructorStyle::Init]
+                }
+                style => vec![style],
+            };
+            for style in styles {
+                for (index, method) in methods.iter().enumerate() {
+                    if overloaded {
+                        writeln!(f, "{indent}@typing.overload")?;
+                    }
+                    // `@typing.override` of overloads is placed on the first one
+                    if index > 0 && method.is_override {
+                        let method = MethodDef {
+                            is_override: false,
+                            ..method.clone()
+                        };
+                        StyledMethod(&method, style).fmt(f)?;
+                    } else {
+                        StyledMethod(method, style).fmt(f)?;
+                    }
                 }
-                method.fmt(f)?;
             }
         }
         for class in &self.classes {
@@ -347,7 +803,24 @@ impl fmt::Display for ClassDef {
                 writeln!(f, "{indent}{line}")?;
             }
         }
-        if self.attrs.is_empty() && self.getter_setters.is_empty() && self.methods.is_empty() {
+        let has_fields = self
+            .dataclass
+            .as_ref()
+            .is_some_and(|dataclass| !dataclass.fields.is_empty());
+        let has_methods = self
+            .methods
+            .keys()
+            .any(|name| self.dataclass.is_none() || (name != "__new__" && name != "__init__"));
+        let has_members = self
+            .flag
+            .as_ref()
+            .is_some_and(|flag| !flag.members.is_empty());
+        if self.attrs.is_empty()
+            && self.getter_setters.is_empty()
+            && !has_methods
+            && !has_fields
+            && !has_members
+        {
             writeln!(f, "{indent}...")?;
         }
         writeln!(f)?;
@@ -358,6 +831,11 @@ impl fmt::Display for ClassDef {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        config::Config,
+        generate::{DocstringStyle, Module},
+        stub_type::SelfImportStrategy,
+    };
     use std::any::TypeId;
 
     fn int_type() -> TypeInfo {
@@ -383,6 +861,10 @@ mod tests {
                 deprecated: None,
                 item: true,
                 is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
             },
             MemberInfo {
                 name: "green",
@@ -392,6 +874,10 @@ mod tests {
                 deprecated: None,
                 item: true,
                 is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
             },
         ]);
         let variant = Box::leak(Box::new(VariantInfo {
@@ -406,9 +892,14 @@ mod tests {
         let enum_info = Box::leak(Box::new(PyComplexEnumInfo {
             enum_id: dummy_enum_id,
             pyclass_name: "ColorInput",
+            name_given: false,
             module: None,
             doc: "",
             variants: std::slice::from_ref(variant),
+            rust_path: "test::Dummy",
+            source_location: None,
+            variant_union: None,
+            feature: None,
         }));
         (enum_info, variant)
     }
@@ -428,6 +919,10 @@ mod tests {
                 deprecated: None,
                 item: false,
                 is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
             },
             MemberInfo {
                 name: "green",
@@ -437,6 +932,10 @@ mod tests {
                 deprecated: None,
                 item: false,
                 is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
             },
         ]);
         let variant = Box::leak(Box::new(VariantInfo {
@@ -451,13 +950,95 @@ mod tests {
         let enum_info = Box::leak(Box::new(PyComplexEnumInfo {
             enum_id: dummy_enum_id,
             pyclass_name: "ColorInput",
+            name_given: false,
             module: None,
             doc: "",
             variants: std::slice::from_ref(variant),
+            rust_path: "test::Dummy",
+            source_location: None,
+            variant_union: None,
+            feature: None,
         }));
         (enum_info, variant)
     }
 
+    #[test]
+    fn named_tuple() {
+        let info = NamedTupleInfo {
+            name: "ScoreEntry",
+            module: "pkg",
+            rust_path: "my_crate::ScoreEntry",
+            doc: " Score of a player\n",
+            fields: &[
+                NamedTupleFieldInfo {
+                    name: "name",
+                    r#type: <String as crate::PyStubType>::type_output,
+                },
+                NamedTupleFieldInfo {
+                    name: "score",
+                    r#type: <f64 as crate::PyStubType>::type_output,
+                },
+            ],
+            ..NamedTupleInfo::DEFAULT
+        };
+        let class_def = ClassDef::from(&info);
+        assert_eq!(
+            class_def.to_string(),
+            r#"class ScoreEntry(typing.NamedTuple):
+    r"""
+    Score of a player
+    """
+    name: builtins.str
+    score: builtins.float
+
+"#
+        );
+        assert!(class_def.import().contains(&"typing".into()));
+    }
+
+    #[test]
+    fn typed_dict() {
+        let info = TypedDictInfo {
+            name: "Summary",
+            module: Some("pkg"),
+            rust_path: "my_crate::Summary",
+            doc: "Summary of the samples",
+            fields: &[
+                TypedDictFieldInfo {
+                    name: "count",
+                    r#type: <usize as crate::PyStubType>::type_output,
+                    doc: "Number of the samples",
+                    required: true,
+                },
+                TypedDictFieldInfo {
+                    name: "label",
+                    r#type: <String as crate::PyStubType>::type_output,
+                    doc: "",
+                    required: false,
+                },
+            ],
+            ..TypedDictInfo::DEFAULT
+        };
+        let _config = Config {
+            self_import_strategy: SelfImportStrategy::TypingExtensions,
+            ..Config::current()
+        }
+        .scope();
+        let class_def = ClassDef::from(&info);
+        let rendered = class_def.to_string();
+        assert!(
+            rendered.starts_with(
+                "class Summary(typing.TypedDict):\n    r\"\"\"\n    Summary of the samples\n    \"\"\"\n    count: builtins.int\n    r\"\"\"\n    Number of the samples\n    \"\"\"\n"
+            ),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("    label: typing_extensions.NotRequired[builtins.str]\n"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("@typing.final"), "{rendered}");
+    }
+
     #[test]
     fn mapping_variants_render_as_typed_dicts() {
         let (enum_info, variant) = mapping_variant();
@@ -482,7 +1063,239 @@ mod tests {
         let rendered = class_def.to_string();
         assert!(rendered.contains("@typing.final"));
         assert!(rendered.contains("class Struct(ColorInput):"));
-        assert!(rendered.contains("__match_args__ = (\"red\", \"green\",)"));
+        assert!(rendered.contains(
+            "__match_args__: typing.Final[tuple[builtins.str, ...]] = (\"red\", \"green\",)"
+        ));
+    }
+
+    #[test]
+    fn nested_variant_members_get_qualnames() {
+        let (enum_info, _) = struct_variant();
+        let mut module = Module {
+            name: "pkg.shapes".into(),
+            ..Default::default()
+        };
+        module
+            .class
+            .insert(TypeId::of::<(u8,)>(), ClassDef::from(enum_info));
+        module.assign_qualnames();
+
+        let class_def = &module.class[&TypeId::of::<(u8,)>()];
+        assert_eq!(class_def.qualname.dotted(), "pkg.shapes.ColorInput");
+        let variant = &class_def.classes[0];
+        assert_eq!(variant.qualname.path(), ["ColorInput", "Struct"]);
+        let new = &variant.methods["__new__"][0];
+        assert_eq!(
+            new.qualname.dotted(),
+            "pkg.shapes.ColorInput.Struct.__new__"
+        );
+        assert_eq!(new.qualname.module(), "pkg.shapes");
+        let (getter, _) = &variant.getter_setters["red"];
+        assert_eq!(
+            getter.as_ref().unwrap().qualname.dotted(),
+            "pkg.shapes.ColorInput.Struct.red"
+        );
+    }
+
+    fn str_type() -> TypeInfo {
+        TypeInfo::builtin("str")
+    }
+
+    #[test]
+    fn tuple_variant_match_args_follow_field_order() {
+        fn dummy_enum_id() -> TypeId {
+            TypeId::of::<(u16,)>()
+        }
+        static TUPLE_FORM: VariantForm = VariantForm::Tuple;
+        // `#[pyo3(constructor = (_1, _0))]` swaps the argument order
+        static CONSTR_ARGS: [ParameterInfo; 2] = [
+            ParameterInfo {
+                name: "_1",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: str_type,
+                default: crate::type_info::ParameterDefault::None,
+                type_overridden: false,
+                container_default: None,
+                deprecated: None,
+            },
+            ParameterInfo {
+                name: "_0",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: int_type,
+                default: crate::type_info::ParameterDefault::None,
+                type_overridden: false,
+                container_default: None,
+                deprecated: None,
+            },
+        ];
+        let members = leak_members(vec![
+            MemberInfo {
+                name: "_0",
+                r#type: int_type,
+                doc: "",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+            MemberInfo {
+                name: "_1",
+                r#type: str_type,
+                doc: "",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+        ]);
+        let variant = Box::leak(Box::new(VariantInfo {
+            pyclass_name: "Pair",
+            module: None,
+            doc: "",
+            fields: members,
+            form: &TUPLE_FORM,
+            constr_args: &CONSTR_ARGS,
+            is_mapping: false,
+        }));
+        let enum_info = Box::leak(Box::new(PyComplexEnumInfo {
+            enum_id: dummy_enum_id,
+            pyclass_name: "Shape",
+            name_given: false,
+            module: None,
+            doc: "",
+            variants: std::slice::from_ref(variant),
+            rust_path: "test::Dummy",
+            source_location: None,
+            variant_union: None,
+            feature: None,
+        }));
+        let class_def = ClassDef::from_variant(enum_info, variant);
+        assert!(class_def.import().contains(&"typing".into()));
+        let rendered = class_def.to_string();
+        assert!(rendered.contains(
+            "__match_args__: typing.Final[tuple[builtins.str, ...]] = (\"_0\", \"_1\",)"
+        ));
+        assert!(rendered.contains("def __new__(cls, _1: builtins.str, _0: builtins.int)"));
+    }
+
+    #[test]
+    fn struct_variant_constructor_with_keyword_only_and_omitted_fields() {
+        fn dummy_enum_id() -> TypeId {
+            TypeId::of::<(u8,)>()
+        }
+        static STRUCT_FORM: VariantForm = VariantForm::Struct;
+        // `#[pyo3(constructor = (*, width, height=1))]`, leaving `label` out
+        static CONSTR_ARGS: [ParameterInfo; 2] = [
+            ParameterInfo {
+                name: "width",
+                kind: ParameterKind::KeywordOnly,
+                type_info: int_type,
+                default: crate::type_info::ParameterDefault::None,
+                type_overridden: false,
+                container_default: None,
+                deprecated: None,
+            },
+            ParameterInfo {
+                name: "height",
+                kind: ParameterKind::KeywordOnly,
+                type_info: int_type,
+                default: crate::type_info::ParameterDefault::Expr(|| "1".to_string()),
+                type_overridden: false,
+                container_default: None,
+                deprecated: None,
+            },
+        ];
+        let member = |name, r#type| MemberInfo {
+            name,
+            r#type,
+            doc: "",
+            default: None,
+            deprecated: None,
+            item: false,
+            is_abstract: false,
+            also_as: &[],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
+        };
+        let members = leak_members(vec![
+            member("width", int_type),
+            member("height", int_type),
+            member("label", str_type),
+        ]);
+        let variant = Box::leak(Box::new(VariantInfo {
+            pyclass_name: "Rectangle",
+            module: None,
+            doc: "",
+            fields: members,
+            form: &STRUCT_FORM,
+            constr_args: &CONSTR_ARGS,
+            is_mapping: false,
+        }));
+        let enum_info = Box::leak(Box::new(PyComplexEnumInfo {
+            enum_id: dummy_enum_id,
+            pyclass_name: "Shape",
+            name_given: false,
+            module: None,
+            doc: "",
+            variants: std::slice::from_ref(variant),
+            rust_path: "test::Dummy",
+            source_location: None,
+            variant_union: None,
+            feature: None,
+        }));
+        let rendered = ClassDef::from_variant(enum_info, variant).to_string();
+        assert!(
+            rendered.contains(
+                "def __new__(cls, *, width: builtins.int, height: builtins.int = 1) -> Self: ..."
+            ),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("def label(self) -> builtins.str: ..."),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn unit_variants_have_no_match_args() {
+        fn dummy_enum_id() -> TypeId {
+            TypeId::of::<(u32,)>()
+        }
+        static UNIT_FORM: VariantForm = VariantForm::Unit;
+        static EMPTY_PARAMS: [ParameterInfo; 0] = [];
+        let variant = Box::leak(Box::new(VariantInfo {
+            pyclass_name: "Empty",
+            module: None,
+            doc: "",
+            fields: &[],
+            form: &UNIT_FORM,
+            constr_args: &EMPTY_PARAMS,
+            is_mapping: false,
+        }));
+        let enum_info = Box::leak(Box::new(PyComplexEnumInfo {
+            enum_id: dummy_enum_id,
+            pyclass_name: "Shape",
+            name_given: false,
+            module: None,
+            doc: "",
+            variants: std::slice::from_ref(variant),
+            rust_path: "test::Dummy",
+            source_location: None,
+            variant_union: None,
+            feature: None,
+        }));
+        let class_def = ClassDef::from_variant(enum_info, variant);
+        assert_eq!(class_def.match_args, None);
+        assert!(!class_def.to_string().contains("__match_args__"));
     }
 
     #[test]
@@ -498,11 +1311,16 @@ mod tests {
             deprecated: None,
             item: false,
             is_abstract: true,
+            also_as: &[],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
         }];
         static SETTERS: [MemberInfo; 0] = [];
         let info = PyClassInfo {
             struct_id: dummy_struct_id,
             pyclass_name: "WithProperty",
+            name_given: false,
             module: None,
             doc: "",
             getters: &GETTERS,
@@ -514,19 +1332,256 @@ mod tests {
             has_str: false,
             subclass: false,
             is_abstract: false,
+            type_override: Default::default(),
+            dataclass: false,
+            rust_path: "test::Dummy",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
         };
         let class_def = ClassDef::from(&info);
         assert!(class_def.is_abstract);
-        assert!(class_def.bases.iter().any(|base| base.name == "abc.ABC"));
         let rendered = class_def.to_string();
+        assert!(rendered.contains("class WithProperty(metaclass=abc.ABCMeta):"));
         assert!(rendered.contains("@abc.abstractmethod"));
         assert!(!rendered.contains("@typing.final"));
     }
 
     #[test]
-    fn mark_abstract_adds_abc_base_and_skips_final() {
+    fn concrete_subclass_of_abstract_class() {
+        fn base() -> TypeInfo {
+            TypeInfo::unqualified("Base")
+        }
+        static GETTERS: [MemberInfo; 1] = [MemberInfo {
+            name: "value",
+            r#type: int_type,
+            doc: "",
+            default: None,
+            deprecated: None,
+            item: false,
+            is_abstract: false,
+            also_as: &[],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
+        }];
+        let info = PyClassInfo {
+            struct_id: TypeId::of::<u8>,
+            pyclass_name: "Derived",
+            name_given: false,
+            module: None,
+            doc: "",
+            getters: &GETTERS,
+            setters: &[],
+            bases: &[base],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: Default::default(),
+            dataclass: false,
+            rust_path: "test::Derived",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
+        };
+        let rendered = ClassDef::from(&info).to_string();
+        assert!(rendered.contains("class Derived(Base):"), "{rendered}");
+        assert!(!rendered.contains("abc."), "{rendered}");
+    }
+
+    #[test]
+    fn setter_type_differs_from_getter() {
+        fn path_output() -> TypeInfo {
+            TypeInfo::builtin("str")
+        }
+        fn path_input() -> TypeInfo {
+            TypeInfo::builtin("str") | TypeInfo::with_module("pathlib.Path", "pathlib".into())
+        }
+        let member = |r#type| MemberInfo {
+            name: "path",
+            r#type,
+            doc: "",
+            default: None,
+            deprecated: None,
+            item: false,
+            is_abstract: false,
+            also_as: &[],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
+        };
+        let info = PyClassInfo {
+            struct_id: TypeId::of::<u64>,
+            pyclass_name: "Config",
+            name_given: false,
+            module: None,
+            doc: "",
+            getters: leak_members(vec![member(path_output)]),
+            setters: leak_members(vec![member(path_input)]),
+            bases: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: Default::default(),
+            dataclass: false,
+            rust_path: "test::Config",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
+        };
+        let class_def = ClassDef::from(&info);
+        let rendered = class_def.to_string();
+        assert!(
+            rendered.contains(
+                "    @property\n    def path(self) -> builtins.str: ...\n    @path.setter\n    def path(self, value: builtins.str | pathlib.Path) -> None: ...\n"
+            ),
+            "{rendered}"
+        );
+        let import = class_def.import();
+        assert!(import.contains(&"builtins".into()));
+        assert!(import.contains(&"pathlib".into()));
+    }
+
+    #[test]
+    fn skipped_base_is_dropped() {
+        fn dummy_struct_id() -> TypeId {
+            TypeId::of::<i8>()
+        }
+        fn skipped_base() -> TypeInfo {
+            TypeInfo::any()
+        }
+        fn base() -> TypeInfo {
+            TypeInfo::unqualified("Base")
+        }
+        let info = PyClassInfo {
+            struct_id: dummy_struct_id,
+            pyclass_name: "Traced",
+            name_given: false,
+            module: None,
+            doc: "",
+            getters: &[],
+            setters: &[],
+            bases: &[skipped_base, base],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: Default::default(),
+            dataclass: false,
+            rust_path: "test::Traced",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
+        };
+        assert_eq!(ClassDef::from(&info).bases, vec![base()]);
+    }
+
+    #[test]
+    fn bases_from_other_modules() {
+        use crate::PyStubType;
+        use pyo3::{exceptions::PyValueError, types::PyDict};
+
+        fn info(pyclass_name: &'static str, bases: &'static [fn() -> TypeInfo]) -> PyClassInfo {
+            PyClassInfo {
+                struct_id: TypeId::of::<i8>,
+                pyclass_name,
+                name_given: false,
+                module: Some("pkg.child"),
+                doc: "",
+                getters: &[],
+                setters: &[],
+                bases,
+                has_eq: false,
+                has_ord: false,
+                has_hash: false,
+                has_str: false,
+                subclass: true,
+                is_abstract: false,
+                type_override: Default::default(),
+                dataclass: false,
+                rust_path: "test::Child",
+                hidden_base: false,
+                no_abc: false,
+                flag_enum: false,
+                total_ordering: false,
+                pickle_state: None,
+                slots: false,
+                weakref: false,
+                feature: None,
+                deprecated: None,
+                source_location: None,
+            }
+        }
+        fn base() -> TypeInfo {
+            // `PyStubType::type_output` of a `#[pyclass(module = "pkg.base")]`
+            TypeInfo::locally_defined("Base", "pkg.base".into())
+        }
+
+        let mut module = Module::new("pkg.child", "pkg");
+        for (id, info) in [
+            (TypeId::of::<i8>(), info("Child", &[base])),
+            (TypeId::of::<i16>(), info("Mapping", &[PyDict::type_output])),
+            (
+                TypeId::of::<i32>(),
+                info("Invalid", &[PyValueError::type_output]),
+            ),
+        ] {
+            module.class.insert(id, ClassDef::from(&info));
+        }
+        let rendered = module.to_string();
+        assert!(
+            rendered.contains("from pkg.base import Base\n"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("class Child(Base):"), "{rendered}");
+        assert!(rendered.contains("class Mapping(dict):"), "{rendered}");
+        assert!(
+            rendered.contains("import builtins\n")
+                && rendered.contains("class Invalid(builtins.ValueError):"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn mark_abstract_adds_metaclass_and_skips_final() {
         let mut class_def = ClassDef {
-            name: "Base",
+            name: "Base".into(),
             doc: "",
             attrs: Vec::new(),
             getter_setters: IndexMap::new(),
@@ -536,13 +1591,262 @@ mod tests {
             match_args: None,
             subclass: false,
             is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
         };
         class_def.mark_abstract();
         assert!(class_def.is_abstract);
         assert!(class_def.subclass);
-        assert!(class_def.bases.iter().any(|base| base.name == "abc.ABC"));
+        assert!(class_def.bases.is_empty());
         let rendered = class_def.to_string();
-        assert!(rendered.contains("class Base(abc.ABC):"));
+        assert!(rendered.contains("class Base(metaclass=abc.ABCMeta):"));
         assert!(!rendered.contains("@typing.final"));
+
+        // Merged with the bases
+        class_def.bases.push(TypeInfo::unqualified("Other"));
+        let rendered = class_def.to_string();
+        assert!(rendered.contains("class Base(Other, metaclass=abc.ABCMeta):"));
+    }
+
+    #[test]
+    fn dataclass_orders_fields_and_skips_constructor() {
+        fn dummy_struct_id() -> TypeId {
+            TypeId::of::<u8>()
+        }
+        fn zero() -> String {
+            "0".to_string()
+        }
+        let fields = leak_members(vec![
+            MemberInfo {
+                name: "size",
+                r#type: int_type,
+                doc: "",
+                default: Some(zero),
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+            MemberInfo {
+                name: "name",
+                r#type: str_type,
+                doc: "",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+        ]);
+        let info = PyClassInfo {
+            struct_id: dummy_struct_id,
+            pyclass_name: "Record",
+            name_given: false,
+            module: None,
+            doc: "",
+            getters: fields,
+            setters: fields,
+            bases: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: Default::default(),
+            dataclass: true,
+            rust_path: "test::Dummy",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
+        };
+        let mut class_def = ClassDef::from(&info);
+        class_def.add_method(MethodDef::new(
+            "__new__",
+            MethodType::New,
+            Parameters::new(),
+            TypeInfo::self_type(),
+        ));
+        class_def.attrs.push(MemberDef {
+            name: "VERSION",
+            r#type: int_type(),
+            doc: "",
+            default: None,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            is_classattr: false,
+            qualname: QualName::default(),
+        });
+        assert!(class_def.import().contains(&"dataclasses".into()));
+        assert_eq!(
+            class_def.to_string(),
+            r#"@typing.final
+@dataclasses.dataclass
+class Record:
+    name: builtins.str
+    size: builtins.int = 0
+    VERSION: typing.ClassVar[builtins.int]
+
+"#
+        );
+
+        let frozen = ClassDef::from(&PyClassInfo {
+            setters: &[],
+            ..info
+        });
+        assert!(frozen
+            .to_string()
+            .contains("@dataclasses.dataclass(frozen=True)\n"));
+    }
+
+    #[test]
+    fn slots_list_attributes_and_properties() {
+        let mut class_def = ClassDef::new("Point");
+        class_def.slots = Some(SlotsDef { weakref: false });
+        let mut origin = MemberDef::new("ORIGIN", TypeInfo::builtin("int"));
+        origin.is_classattr = true;
+        class_def.attrs = vec![MemberDef::new("x", TypeInfo::builtin("int")), origin];
+        class_def.getter_setters.insert(
+            "y".to_string(),
+            (Some(MemberDef::new("y", TypeInfo::builtin("int"))), None),
+        );
+        assert!(class_def.import().contains(&"builtins".into()));
+        assert!(class_def
+            .to_string()
+            .contains("    __slots__: tuple[builtins.str, ...] = (\"x\", \"y\",)\n"));
+
+        class_def.slots = Some(SlotsDef { weakref: true });
+        assert!(class_def.to_string().contains(
+            "    __slots__: tuple[builtins.str, ...] = (\"x\", \"y\", \"__weakref__\",)\n"
+        ));
+
+        class_def.slots = None;
+        assert!(!class_def.to_string().contains("__slots__"));
+    }
+
+    #[test]
+    fn attributes_section_from_properties() {
+        let mut class_def = ClassDef::new("Point");
+        let mut x = MemberDef::new("x", TypeInfo::builtin("int"));
+        x.doc = " X coordinate.\n\n Grows to the right.";
+        class_def
+            .getter_setters
+            .insert("x".to_string(), (Some(x), None));
+        let label = MemberDef::new("label", TypeInfo::list(TypeInfo::builtin("str")));
+        class_def
+            .getter_setters
+            .insert("label".to_string(), (None, Some(label)));
+        let section = |doc: &str, style| {
+            let mut lines = docstring::docstring_lines(doc);
+            docstring::append_attributes_section(&mut lines, &class_def.attributes(), style);
+            lines.join("\n")
+        };
+
+        assert_eq!(
+            section("", DocstringStyle::Google),
+            "Attributes:\n    x (builtins.int): X coordinate.\n    label (builtins.list[builtins.str])"
+        );
+        // Appended after a hand-written docstring
+        assert_eq!(
+            section(" A point.\n\n Immutable.", DocstringStyle::Google),
+            "A point.\n\nImmutable.\n\nAttributes:\n    x (builtins.int): X coordinate.\n    label (builtins.list[builtins.str])"
+        );
+        assert_eq!(
+            section(" A point.", DocstringStyle::Numpy),
+            "A point.\n\nAttributes\n----------\nx : builtins.int\n    X coordinate.\nlabel : builtins.list[builtins.str]"
+        );
+        // A hand-written section is kept as is
+        let doc = " A point.\n\n Attributes:\n     x: Horizontal";
+        assert_eq!(
+            section(doc, DocstringStyle::Google),
+            "A point.\n\nAttributes:\n    x: Horizontal"
+        );
+    }
+
+    #[test]
+    fn deprecated_class_has_decorator_and_docstring_section() {
+        let mut class_def = ClassDef::new("OldDevice");
+        class_def.doc = " Device of the legacy driver";
+        class_def.deprecated = Some(DeprecatedInfo {
+            since: Some("1.4"),
+            note: Some("Use `Device` instead"),
+        });
+        assert!(class_def.import().contains(&"typing_extensions".into()));
+        assert_eq!(
+            class_def.to_string(),
+            r#"@typing_extensions.deprecated("deprecated since 1.4: Use `Device` instead")
+@typing.final
+class OldDevice:
+    r"""
+    Device of the legacy driver
+    
+    Deprecated:
+        Since 1.4: Use `Device` instead
+    """
+    ...
+
+"#
+        );
+    }
+
+    #[test]
+    fn overloaded_constructor_rendered_as_both() {
+        let constructor = |ty: &str| {
+            let mut parameters = Parameters::new();
+            parameters.push(Parameter::new(
+                "x",
+                ParameterKind::PositionalOrKeyword,
+                TypeInfo::builtin(ty),
+            ));
+            MethodDef::new(
+                "__new__",
+                MethodType::New,
+                parameters,
+                ConstructorStyle::Both.return_type(),
+            )
+        };
+        let mut class_def = ClassDef::new("Point");
+        class_def.methods.insert(
+            "__new__".to_string(),
+            vec![constructor("int"), constructor("float")],
+        );
+        let _config = Config {
+            constructor_style: ConstructorStyle::Both,
+            ..Config::current()
+        }
+        .scope();
+        assert_eq!(
+            class_def.to_string(),
+            r#"@typing.final
+class Point:
+    @typing.overload
+    def __new__(cls, x: builtins.int) -> Self: ...
+    @typing.overload
+    def __new__(cls, x: builtins.float) -> Self: ...
+    @typing.overload
+    def __init__(self, x: builtins.int) -> None: ...
+    @typing.overload
+    def __init__(self, x: builtins.float) -> None: ...
+
+"#
+        );
     }
 }
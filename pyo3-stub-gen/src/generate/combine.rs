@@ -0,0 +1,425 @@
+//! Combination of [StubInfo]s gathered for several crates, see [StubInfo::merge].
+
+use crate::{
+    generate::*,
+    stub_type::{ImportRef, ModuleRef, TypeRef},
+    TypeInfo,
+};
+use anyhow::{bail, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+impl StubInfo {
+    /// Combine the stubs of another crate into this one.
+    ///
+    /// Modules are united, and items defined by both sides must render identically,
+    /// which is the case for the items of a crate linked into the `gen_stub` executable of both.
+    /// A module is partial or has the `__getattr__` fallback if it does on either side.
+    /// [StubInfo::python_root], [StubInfo::config] and the other settings of `self` are kept.
+    pub fn merge(mut self, other: StubInfo) -> Result<StubInfo> {
+        for (name, mut module) in other.modules {
+            match self.modules.get_mut(&name) {
+                Some(existing) => existing.merge(module)?,
+                None => {
+                    module.config = Some(self.config);
+                    self.modules.insert(name, module);
+                }
+            }
+        }
+        let names: BTreeSet<String> = self.modules.keys().cloned().collect();
+        for name in &names {
+            if let Some((parent, child)) = name.rsplit_once('.') {
+                if let Some(parent) = self.modules.get_mut(parent) {
+                    parent.submodules.insert(child.to_string());
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl Module {
+    fn merge(&mut self, mut other: Module) -> Result<()> {
+        if other.default_module_name != self.default_module_name {
+            other.resolve_default_module();
+        }
+        // Destructured exhaustively so that a new field of [Module] cannot be forgotten here
+        let Module {
+            doc,
+            class: other_class,
+            enum_: other_enum,
+            class_order,
+            exceptions: other_exceptions,
+            function: other_function,
+            variables: other_variables,
+            synthesized: other_synthesized,
+            name: _,
+            default_module_name: _,
+            submodules,
+            attr_fallback,
+            partial,
+            reexport_all,
+            header,
+            // Rendered with the configuration of `self`
+            config: _,
+        } = other;
+        let mut defined = self.rendered_items();
+        let mut define = |name: &str, rendered: String| -> Result<bool> {
+            match defined.get(name) {
+                Some(existing) if *existing == rendered => Ok(false),
+                Some(_) => bail!("Conflicting definitions of `{}.{name}`", self.name),
+                None => {
+                    defined.insert(name.to_string(), rendered);
+                    Ok(true)
+                }
+            }
+        };
+        let mut class = BTreeMap::new();
+        for (id, def) in other_class {
+            if define(&def.name, def.to_string())? {
+                class.insert(id, def);
+            }
+        }
+        let mut enum_ = BTreeMap::new();
+        for (id, def) in other_enum {
+            if define(def.name, def.to_string())? {
+                enum_.insert(id, def);
+            }
+        }
+        let mut exceptions = BTreeMap::new();
+        for (name, def) in other_exceptions {
+            if define(name, def.to_string())? {
+                exceptions.insert(name, def);
+            }
+        }
+        let mut function = BTreeMap::new();
+        for (name, defs) in other_function {
+            if define(&name, render_all(&defs))? {
+                function.insert(name, defs);
+            }
+        }
+        let mut variables = BTreeMap::new();
+        for (name, def) in other_variables {
+            if define(name, def.to_string())? {
+                variables.insert(name, def);
+            }
+        }
+        let mut synthesized = BTreeMap::new();
+        for (name, def) in other_synthesized {
+            if define(&name, def.to_string())? {
+                synthesized.insert(name, def);
+            }
+        }
+        self.class.extend(class);
+        self.enum_.extend(enum_);
+        self.exceptions.extend(exceptions);
+        self.function.extend(function);
+        self.variables.extend(variables);
+        self.synthesized.extend(synthesized);
+
+        for id in class_order {
+            if !self.class_order.contains(&id) {
+                self.class_order.push(id);
+            }
+        }
+
+        if self.doc.is_empty() {
+            self.doc = doc;
+        } else if !doc.is_empty() && doc != self.doc {
+            bail!("Conflicting docstrings of module `{}`", self.name);
+        }
+        match (&self.header, header) {
+            (_, None) => {}
+            (None, header) => self.header = header,
+            (Some(existing), Some(header)) if *existing == header => {}
+            (Some(_), Some(_)) => bail!("Conflicting headers of module `{}`", self.name),
+        }
+        self.submodules.extend(submodules);
+        self.attr_fallback |= attr_fallback;
+        self.partial |= partial;
+        self.reexport_all.extend(reexport_all);
+        Ok(())
+    }
+
+    /// Rendered top-level items by their Python names
+    fn rendered_items(&self) -> BTreeMap<String, String> {
+        let mut items = BTreeMap::new();
+        for def in self.class.values() {
+            items.insert(def.name.to_string(), def.to_string());
+        }
+        for def in self.enum_.values() {
+            items.insert(def.name.to_string(), def.to_string());
+        }
+        for (name, def) in &self.exceptions {
+            items.insert(name.to_string(), def.to_string());
+        }
+        for (name, defs) in &self.function {
+            items.insert(name.to_string(), render_all(defs));
+        }
+        for (name, def) in &self.variables {
+            items.insert(name.to_string(), def.to_string());
+        }
+        for (name, def) in &self.synthesized {
+            items.insert(name.clone(), def.to_string());
+        }
+        items
+    }
+
+    /// Replace [ModuleRef::Default] by the default module name of this module,
+    /// so that references keep their target when rendered with another default module name.
+    fn resolve_default_module(&mut self) {
+        let default = ModuleRef::Named(self.default_module_name.clone());
+        let mut resolve = |type_info: &mut TypeInfo| {
+            type_info.import = type_info
+                .import
+                .drain()
+                .map(|import| match import {
+                    ImportRef::Module(ModuleRef::Default) => ImportRef::Module(default.clone()),
+                    ImportRef::Type(TypeRef {
+                        module: ModuleRef::Default,
+                        name,
+                    }) => ImportRef::Type(TypeRef::new(default.clone(), name)),
+                    import => import,
+                })
+                .collect();
+        };
+        self.for_each_type_mut(&mut resolve);
+        for def in self.synthesized.values_mut() {
+            match &mut def.kind {
+                SynthesizedKind::TypeAlias(type_info) => resolve(type_info),
+            }
+        }
+    }
+}
+
+fn render_all(defs: &[impl Display]) -> String {
+    defs.iter().map(|def| def.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{any::TypeId, path::PathBuf};
+
+    struct A;
+    struct B;
+
+    fn stub_info(modules: Vec<Module>) -> StubInfo {
+        StubInfo {
+            modules: modules
+                .into_iter()
+                .map(|module| (module.name.clone(), module))
+                .collect(),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn merge_crates() -> Result<()> {
+        let mut core = Module::new("pkg._core", "pkg._core");
+        core.class.insert(TypeId::of::<A>(), ClassDef::new("A"));
+        core.function.insert(
+            "version".into(),
+            vec![FunctionDef::new(
+                "version",
+                Parameters::new(),
+                TypeInfo::builtin("str"),
+            )],
+        );
+
+        let mut io = Module::new("pkg._io", "pkg._io");
+        io.class.insert(TypeId::of::<B>(), ClassDef::new("B"));
+        // Defined in the default module of the other crate
+        let mut shared = Module::new("pkg._core", "pkg._io");
+        shared.function.insert(
+            "open".into(),
+            vec![FunctionDef::new(
+                "open",
+                Parameters::new(),
+                TypeInfo::locally_defined("B", "pkg._io".into()),
+            )],
+        );
+        shared.variables.insert(
+            "DEFAULT",
+            VariableDef::new(
+                "DEFAULT",
+                TypeInfo::locally_defined("B", ModuleRef::Default),
+            ),
+        );
+
+        let merged = stub_info(vec![core]).merge(stub_info(vec![io, shared]))?;
+        assert_eq!(
+            merged.modules.keys().collect::<Vec<_>>(),
+            ["pkg._core", "pkg._io"]
+        );
+        assert_eq!(
+            merged.modules["pkg._core"].to_string(),
+            r#"# This file is automatically generated by pyo3_stub_gen
+# ruff: noqa: E501, F401
+
+import builtins
+import typing
+
+from pkg._io import B
+
+__all__ = [
+    "A",
+    "DEFAULT",
+    "open",
+    "version",
+]
+
+DEFAULT: B
+@typing.final
+class A:
+    ...
+
+def open() -> B: ...
+
+def version() -> builtins.str: ...
+
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn same_definitions() -> Result<()> {
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(TypeId::of::<A>(), ClassDef::new("A"));
+        module
+            .variables
+            .insert("X", VariableDef::new("X", TypeInfo::builtin("int")));
+        let mut sub = Module::new("pkg.sub", "pkg");
+        sub.doc = "Submodule".to_string();
+
+        let merged = stub_info(vec![module.clone()]).merge(stub_info(vec![module.clone(), sub]))?;
+        assert_eq!(merged.modules["pkg"].class.len(), 1);
+        assert_eq!(merged.modules["pkg"].variables.len(), 1);
+        assert_eq!(
+            merged.modules["pkg"].submodules,
+            BTreeSet::from(["sub".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merge_every_field() -> Result<()> {
+        let mut core = Module::new("pkg", "pkg");
+        core.class.insert(TypeId::of::<A>(), ClassDef::new("A"));
+        core.class_order.push(TypeId::of::<A>());
+        core.header = Some("# SPDX-License-Identifier: MIT".to_string());
+
+        let mut io = Module::new("pkg", "pkg");
+        io.doc = "Package".to_string();
+        io.class.insert(TypeId::of::<B>(), ClassDef::new("B"));
+        io.enum_.insert(TypeId::of::<u8>(), EnumDef::new("C", &[]));
+        io.class_order
+            .extend([TypeId::of::<B>(), TypeId::of::<A>()]);
+        io.exceptions.insert(
+            "Error",
+            ExceptionDef::new("Error", TypeInfo::builtin("Exception")),
+        );
+        io.function.insert(
+            "f".into(),
+            vec![FunctionDef::new("f", Parameters::new(), TypeInfo::none())],
+        );
+        io.variables
+            .insert("X", VariableDef::new("X", TypeInfo::builtin("int")));
+        io.synthesized.insert(
+            "Alias".to_string(),
+            SynthesizedDef {
+                name: "Alias".to_string(),
+                kind: SynthesizedKind::TypeAlias(TypeInfo::builtin("int")),
+                origins: Vec::new(),
+                qualname: QualName::default(),
+                type_alias_module: "typing",
+            },
+        );
+        io.submodules.insert("sub".to_string());
+        io.attr_fallback = true;
+        io.partial = true;
+        io.reexport_all.insert("pkg._io".to_string());
+        io.header = core.header.clone();
+
+        let mut merged = stub_info(vec![core]).merge(stub_info(vec![io]))?;
+        // Fails to compile when a field is added, which then needs a check here and in `Module::merge`
+        let Module {
+            doc,
+            class,
+            enum_,
+            class_order,
+            exceptions,
+            function,
+            variables,
+            synthesized,
+            name,
+            default_module_name,
+            submodules,
+            attr_fallback,
+            partial,
+            reexport_all,
+            header,
+            config,
+        } = merged.modules.remove("pkg").unwrap();
+        assert_eq!(doc, "Package");
+        assert_eq!(class.len(), 2);
+        assert_eq!(enum_.len(), 1);
+        assert_eq!(class_order, [TypeId::of::<A>(), TypeId::of::<B>()]);
+        assert!(exceptions.contains_key("Error"));
+        assert!(function.contains_key("f"));
+        assert!(variables.contains_key("X"));
+        assert!(synthesized.contains_key("Alias"));
+        assert_eq!(
+            (name.as_str(), default_module_name.as_str()),
+            ("pkg", "pkg")
+        );
+        assert_eq!(submodules, BTreeSet::from(["sub".to_string()]));
+        assert!(attr_fallback && partial);
+        assert_eq!(reexport_all, BTreeSet::from(["pkg._io".to_string()]));
+        assert_eq!(header.as_deref(), Some("# SPDX-License-Identifier: MIT"));
+        assert_eq!(config, None);
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_headers() {
+        let module = |header: &str| {
+            let mut module = Module::new("pkg", "pkg");
+            module.header = Some(header.to_string());
+            stub_info(vec![module])
+        };
+        let err = module("# SPDX-License-Identifier: MIT")
+            .merge(module("# SPDX-License-Identifier: Apache-2.0"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting headers of module `pkg`");
+    }
+
+    #[test]
+    fn conflicting_definitions() {
+        let module = |type_info: TypeInfo| {
+            let mut module = Module::new("pkg", "pkg");
+            module
+                .variables
+                .insert("X", VariableDef::new("X", type_info));
+            stub_info(vec![module])
+        };
+        let err = module(TypeInfo::builtin("int"))
+            .merge(module(TypeInfo::builtin("str")))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting definitions of `pkg.X`");
+
+        // A class and a function of the same name are also a conflict
+        let mut class = Module::new("pkg", "pkg");
+        class.class.insert(TypeId::of::<A>(), ClassDef::new("X"));
+        let err = stub_info(vec![class])
+            .merge(module(TypeInfo::builtin("int")))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting definitions of `pkg.X`");
+    }
+}
@@ -0,0 +1,31 @@
+//! Deprecated public paths of items internal to the stub generation, kept for one release.
+#![allow(deprecated)]
+
+use crate::generate::*;
+use std::fmt;
+
+#[deprecated(
+    since = "0.17.0",
+    note = "internal to the stub generation, and will be private in the next release"
+)]
+#[doc(hidden)]
+pub struct GetterDisplay<'a>(pub &'a MemberDef);
+
+impl fmt::Display for GetterDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        member::GetterDisplay(self.0).fmt(f)
+    }
+}
+
+#[deprecated(
+    since = "0.17.0",
+    note = "internal to the stub generation, and will be private in the next release"
+)]
+#[doc(hidden)]
+pub struct SetterDisplay<'a>(pub &'a MemberDef);
+
+impl fmt::Display for SetterDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        member::SetterDisplay(self.0).fmt(f)
+    }
+}
@@ -0,0 +1,309 @@
+//! Detection of module-level items sharing the same Python name.
+
+use crate::config::Config;
+use crate::type_info::{
+    PyClassInfo, PyComplexEnumInfo, PyEnumInfo, PyExceptionInfo, PyFunctionInfo, PyVariableInfo,
+};
+use anyhow::{bail, Result};
+use std::{collections::BTreeMap, fmt};
+
+/// Resolver called for each item involved in a [NameConflict].
+/// Returning `Some(name)` renames the item in the stub, and `None` keeps its name.
+///
+/// Note that references to a renamed class from other items are not rewritten.
+pub type NameConflictResolver = fn(&NameConflict, &NamedItem) -> Option<String>;
+
+/// Set the resolver used when module-level items share the same Python name.
+/// Without a resolver, such conflicts fail the stub generation.
+pub fn set_name_conflict_resolver(resolver: Option<NameConflictResolver>) {
+    Config::update(|config| config.name_conflict_resolver = resolver);
+}
+
+/// Retrieve the currently configured [NameConflictResolver].
+pub fn name_conflict_resolver() -> Option<NameConflictResolver> {
+    Config::current().name_conflict_resolver
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ItemKind {
+    Class,
+    Enum,
+    Exception,
+    Function,
+    Variable,
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ItemKind::Class => "class",
+            ItemKind::Enum => "enum",
+            ItemKind::Exception => "exception",
+            ItemKind::Function => "function",
+            ItemKind::Variable => "variable",
+        })
+    }
+}
+
+/// Module-level item of a stub file
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct NamedItem {
+    pub module: String,
+    pub name: &'static str,
+    pub kind: ItemKind,
+    /// Paths of the Rust items. Overloads of a function share one [NamedItem].
+    pub rust_paths: Vec<&'static str>,
+}
+
+/// Items defined with the same name in a module
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct NameConflict {
+    pub module: String,
+    pub name: String,
+    pub items: Vec<NamedItem>,
+}
+
+impl fmt::Display for NameConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "`{}` is defined more than once in module `{}`:",
+            self.name, self.module
+        )?;
+        for item in &self.items {
+            writeln!(
+                f,
+                "  - {} from `{}`",
+                item.kind,
+                item.rust_paths.join("`, `")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Python names of module-level items, after renaming by the [NameConflictResolver]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Renames(BTreeMap<(ItemKind, &'static str), &'static str>);
+
+impl Renames {
+    /// Name of the item defined by `rust_path`, which is `name` unless renamed
+    pub(crate) fn name(
+        &self,
+        kind: ItemKind,
+        rust_path: &'static str,
+        name: &'static str,
+    ) -> &'static str {
+        self.0.get(&(kind, rust_path)).copied().unwrap_or(name)
+    }
+}
+
+/// Collect the module-level items submitted to [inventory]
+pub(crate) fn named_items(default_module_name: &str) -> Vec<NamedItem> {
+    let module = |m: Option<&str>| m.unwrap_or(default_module_name).to_string();
+    let mut items = Vec::new();
+    for info in inventory::iter::<PyClassInfo> {
+        items.push(item(
+            module(info.module),
+            info.pyclass_name,
+            ItemKind::Class,
+            info.rust_path,
+        ));
+    }
+    for info in inventory::iter::<PyComplexEnumInfo> {
+        items.push(item(
+            module(info.module),
+            info.pyclass_name,
+            ItemKind::Enum,
+            info.rust_path,
+        ));
+    }
+    for info in inventory::iter::<PyEnumInfo> {
+        items.push(item(
+            module(info.module),
+            info.pyclass_name,
+            ItemKind::Enum,
+            info.rust_path,
+        ));
+    }
+    for info in inventory::iter::<PyExceptionInfo> {
+        items.push(item(
+            info.module.to_string(),
+            info.name,
+            ItemKind::Exception,
+            info.rust_path,
+        ));
+    }
+    for info in inventory::iter::<PyFunctionInfo> {
+        let module = module(info.module);
+        // Functions with the same name are overloads of one function
+        match items.iter_mut().find(|item| {
+            item.kind == ItemKind::Function && item.module == module && item.name == info.name
+        }) {
+            Some(item) => item.rust_paths.push(info.rust_path),
+            None => items.push(item(module, info.name, ItemKind::Function, info.rust_path)),
+        }
+    }
+    for info in inventory::iter::<PyVariableInfo> {
+        items.push(item(
+            info.module.to_string(),
+            info.name,
+            ItemKind::Variable,
+            info.rust_path,
+        ));
+    }
+    items
+}
+
+fn item(module: String, name: &'static str, kind: ItemKind, rust_path: &'static str) -> NamedItem {
+    NamedItem {
+        module,
+        name,
+        kind,
+        rust_paths: vec![rust_path],
+    }
+}
+
+fn find_conflicts(items: &[NamedItem]) -> Vec<NameConflict> {
+    let mut names: BTreeMap<(&str, &str), Vec<&NamedItem>> = BTreeMap::new();
+    for item in items {
+        names
+            .entry((&item.module, item.name))
+            .or_default()
+            .push(item);
+    }
+    names
+        .into_iter()
+        .filter(|(_, items)| items.len() > 1)
+        .map(|((module, name), items)| NameConflict {
+            module: module.to_string(),
+            name: name.to_string(),
+            items: items.into_iter().cloned().collect(),
+        })
+        .collect()
+}
+
+/// Detect name conflicts among `items` and rename them by `resolver`.
+/// Fails if any conflict remains after renaming.
+pub(crate) fn resolve_name_conflicts(
+    mut items: Vec<NamedItem>,
+    resolver: Option<NameConflictResolver>,
+) -> Result<Renames> {
+    let mut renames = Renames::default();
+    let conflicts = find_conflicts(&items);
+    if let Some(resolver) = resolver {
+        for conflict in &conflicts {
+            for conflicting in &conflict.items {
+                let Some(new_name) = resolver(conflict, conflicting) else {
+                    continue;
+                };
+                let new_name: &'static str = Box::leak(new_name.into_boxed_str());
+                for rust_path in &conflicting.rust_paths {
+                    renames.0.insert((conflicting.kind, rust_path), new_name);
+                }
+                if let Some(item) = items.iter_mut().find(|item| item == &conflicting) {
+                    item.name = new_name;
+                }
+            }
+        }
+    }
+    let conflicts = if resolver.is_some() {
+        find_conflicts(&items)
+    } else {
+        conflicts
+    };
+    if !conflicts.is_empty() {
+        bail!(
+            "Found {} name conflict(s) in the generated stubs. \
+             Rename the items with `#[pyclass(name = \"...\")]` or `#[pyo3(name = \"...\")]`, \
+             or set a resolver by `set_name_conflict_resolver`.\n{}",
+            conflicts.len(),
+            conflicts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<String>()
+                .trim_end()
+        );
+    }
+    Ok(renames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<NamedItem> {
+        vec![
+            item("pkg".into(), "Config", ItemKind::Class, "pkg::a::Config"),
+            item("pkg".into(), "Config", ItemKind::Class, "pkg::b::Config"),
+            item("pkg".into(), "load", ItemKind::Function, "pkg::load"),
+            item(
+                "pkg.sub".into(),
+                "Config",
+                ItemKind::Class,
+                "pkg::sub::Config",
+            ),
+        ]
+    }
+
+    #[test]
+    fn duplicate_class_names() {
+        let err = resolve_name_conflicts(items(), None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Found 1 name conflict(s) in the generated stubs. \
+             Rename the items with `#[pyclass(name = \"...\")]` or `#[pyo3(name = \"...\")]`, \
+             or set a resolver by `set_name_conflict_resolver`.\n\
+             `Config` is defined more than once in module `pkg`:\n  \
+             - class from `pkg::a::Config`\n  \
+             - class from `pkg::b::Config`"
+        );
+    }
+
+    #[test]
+    fn class_and_function_names() {
+        let items = vec![
+            item("pkg".into(), "Config", ItemKind::Class, "pkg::Config"),
+            NamedItem {
+                module: "pkg".into(),
+                name: "Config",
+                kind: ItemKind::Function,
+                rust_paths: vec!["pkg::config", "pkg::config_from_path"],
+            },
+        ];
+        let err = resolve_name_conflicts(items, None).unwrap_err();
+        assert!(err.to_string().ends_with(
+            "`Config` is defined more than once in module `pkg`:\n  \
+             - class from `pkg::Config`\n  \
+             - function from `pkg::config`, `pkg::config_from_path`"
+        ));
+    }
+
+    #[test]
+    fn resolver_renames_conflicting_items() {
+        fn resolver(_: &NameConflict, item: &NamedItem) -> Option<String> {
+            let rust_name = item.rust_paths[0].rsplit("::").nth(1)?;
+            (rust_name == "b").then(|| format!("{}B", item.name))
+        }
+        let renames = resolve_name_conflicts(items(), Some(resolver)).unwrap();
+        assert_eq!(
+            renames.name(ItemKind::Class, "pkg::b::Config", "Config"),
+            "ConfigB"
+        );
+        assert_eq!(
+            renames.name(ItemKind::Class, "pkg::a::Config", "Config"),
+            "Config"
+        );
+
+        // Renaming into another existing name still fails
+        fn bad_resolver(_: &NameConflict, item: &NamedItem) -> Option<String> {
+            item.rust_paths[0]
+                .ends_with("b::Config")
+                .then(|| "load".to_string())
+        }
+        assert!(resolve_name_conflicts(items(), Some(bad_resolver)).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Rendering of constructors, i.e. methods of `#[new]`.
+//!
+//! PyO3 exposes the constructor as `__new__`, while some tools such as `mypy.stubtest` compare
+//! the stub against the `__init__` which the runtime also reports for the class.
+
+use crate::{config::Config, TypeInfo};
+use serde::{Deserialize, Serialize};
+
+/// How a constructor of `#[new]` is rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ConstructorStyle {
+    /// `def __new__(cls, ...) -> Self`
+    #[default]
+    New,
+    /// `def __init__(self, ...) -> None`
+    Init,
+    /// Both of `__new__` and `__init__` with the same parameters
+    Both,
+}
+
+impl ConstructorStyle {
+    pub(crate) fn renders_new(self) -> bool {
+        self != ConstructorStyle::Init
+    }
+
+    pub(crate) fn renders_init(self) -> bool {
+        self != ConstructorStyle::New
+    }
+
+    /// Return type of the constructor kept in [MethodDef](crate::generate::MethodDef),
+    /// which is the one of `__new__` unless only `__init__` is rendered
+    pub(crate) fn return_type(self) -> TypeInfo {
+        if self.renders_new() {
+            TypeInfo::self_type()
+        } else {
+            TypeInfo::none()
+        }
+    }
+}
+
+/// Set how constructors of `#[new]` are rendered in generated stubs.
+pub fn set_constructor_style(style: ConstructorStyle) {
+    Config::update(|config| config.constructor_style = style);
+}
+
+/// Retrieve how constructors of `#[new]` are rendered.
+pub fn constructor_style() -> ConstructorStyle {
+    Config::current().constructor_style
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_in_pyproject() {
+        use crate::pyproject::StubGen;
+        for table in [
+            r#"constructor-style = "both""#,
+            r#"constructor_style = "both""#,
+        ] {
+            let stub_gen: StubGen = toml::from_str(table).unwrap();
+            assert_eq!(stub_gen.constructor_style, Some(ConstructorStyle::Both));
+        }
+        assert!(toml::from_str::<StubGen>(r#"constructor-style = "call""#).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+//! Rendering of parameters defaulting to a list, dict, or set.
+//!
+//! A Rust default such as `Vec::new()` creates a fresh container on each call,
+//! while `= []` in a stub reads as a shared mutable default to Python readers and linters.
+
+use crate::{config::Config, generate::*, TypeInfo};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// How the default value of a parameter is rendered when it is a list, dict, or set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ContainerDefaultStyle {
+    /// Render the value as a literal, e.g. `= []`
+    #[default]
+    Literal,
+    /// Render `= ...`, and note the value in the docstring
+    Ellipsis,
+    /// Render `= None` if the parameter accepts `None`, otherwise `= ...`, and note the value in the docstring
+    NoneIfOptional,
+}
+
+/// Set how list, dict, and set defaults of parameters are rendered in generated stubs.
+///
+/// This can be overridden for each parameter by `#[gen_stub(container_default = "...")]`.
+pub fn set_container_default_style(style: ContainerDefaultStyle) {
+    Config::update(|config| config.container_default_style = style);
+}
+
+/// Retrieve how list, dict, and set defaults of parameters are rendered.
+pub fn container_default_style() -> ContainerDefaultStyle {
+    Config::current().container_default_style
+}
+
+/// Container a default value renders, judged from its Python representation
+fn container_kind(default: &str) -> Option<&'static str> {
+    if default.starts_with('[') && default.ends_with(']') {
+        Some("list")
+    } else if default == "set()" {
+        Some("set")
+    } else if default == "{}" || (default.starts_with('{') && default.contains(": ")) {
+        Some("dict")
+    } else if default.starts_with('{') && default.ends_with('}') {
+        Some("set")
+    } else {
+        None
+    }
+}
+
+/// Whether `type_info` accepts `None`
+fn is_optional(type_info: &TypeInfo) -> bool {
+    let name = type_info.name.as_str();
+    name.starts_with("typing.Optional[") || name.starts_with("None | ") || name.ends_with(" | None")
+}
+
+impl Parameter {
+    fn container_default(&self) -> Option<(&str, &'static str)> {
+        match &self.default {
+            ParameterDefault::Expr(expr) => Some((expr.as_str(), container_kind(expr)?)),
+            ParameterDefault::None => None,
+        }
+    }
+
+    fn container_default_style(&self) -> ContainerDefaultStyle {
+        self.container_default
+            .unwrap_or_else(container_default_style)
+    }
+
+    /// The default value as rendered in the signature
+    pub(crate) fn rendered_default(&self) -> Option<&str> {
+        let ParameterDefault::Expr(expr) = &self.default else {
+            return None;
+        };
+        if self.container_default().is_none() {
+            return Some(expr);
+        }
+        Some(match self.container_default_style() {
+            ContainerDefaultStyle::Literal => expr,
+            ContainerDefaultStyle::NoneIfOptional if is_optional(&self.type_info) => "None",
+            ContainerDefaultStyle::Ellipsis | ContainerDefaultStyle::NoneIfOptional => "...",
+        })
+    }
+
+    /// Docstring note on the default value, if it is not rendered in the signature
+    fn container_default_note(&self) -> Option<String> {
+        let (expr, kind) = self.container_default()?;
+        if self.container_default_style() == ContainerDefaultStyle::Literal {
+            return None;
+        }
+        let value = if matches!(expr, "[]" | "{}" | "set()") {
+            format!("an empty {kind}")
+        } else {
+            format!("a new {kind} `{expr}`")
+        };
+        Some(format!("`{}` defaults to {value}.", self.name))
+    }
+}
+
+/// Append the notes on container defaults of `parameters` to `doc`.
+pub(crate) fn docstring_with_container_defaults<'a>(
+    doc: &'a str,
+    parameters: &Parameters,
+) -> Cow<'a, str> {
+    let notes: Vec<String> = parameters
+        .iter_entries()
+        .filter_map(Parameter::container_default_note)
+        .collect();
+    if notes.is_empty() {
+        return Cow::Borrowed(doc);
+    }
+    let notes = notes.join("\n");
+    let doc = doc.trim_end();
+    if doc.is_empty() {
+        Cow::Owned(notes)
+    } else {
+        Cow::Owned(format!("{doc}\n\n{notes}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::ParameterKind;
+    use test_case::test_case;
+
+    fn tag_function(style: ContainerDefaultStyle) -> FunctionDef {
+        let mut tags = Parameter::new(
+            "tags",
+            ParameterKind::PositionalOrKeyword,
+            TypeInfo::builtin("list[builtins.str]"),
+        );
+        tags.default = ParameterDefault::Expr("[]".to_string());
+        let mut options = Parameter::new(
+            "options",
+            ParameterKind::PositionalOrKeyword,
+            TypeInfo::with_module(
+                "typing.Optional[builtins.dict[builtins.str, builtins.int]]",
+                "typing".into(),
+            ),
+        );
+        options.default = ParameterDefault::Expr("{'retries': 3}".to_string());
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword = vec![tags, options];
+        for parameter in parameters.iter_entries_mut() {
+            parameter.container_default = Some(style);
+        }
+        let mut function = FunctionDef::new("tag", parameters, TypeInfo::none());
+        function.doc = "Tag items.";
+        function
+    }
+
+    #[test_case(ContainerDefaultStyle::Literal, r#"def tag(tags: builtins.list[builtins.str] = [], options: typing.Optional[builtins.dict[builtins.str, builtins.int]] = {'retries': 3}) -> None:
+    r"""
+    Tag items.
+    """
+"# ; "literal")]
+    #[test_case(ContainerDefaultStyle::Ellipsis, r#"def tag(tags: builtins.list[builtins.str] = ..., options: typing.Optional[builtins.dict[builtins.str, builtins.int]] = ...) -> None:
+    r"""
+    Tag items.
+    
+    `tags` defaults to an empty list.
+    `options` defaults to a new dict `{'retries': 3}`.
+    """
+"# ; "ellipsis")]
+    #[test_case(ContainerDefaultStyle::NoneIfOptional, r#"def tag(tags: builtins.list[builtins.str] = ..., options: typing.Optional[builtins.dict[builtins.str, builtins.int]] = None) -> None:
+    r"""
+    Tag items.
+    
+    `tags` defaults to an empty list.
+    `options` defaults to a new dict `{'retries': 3}`.
+    """
+"# ; "none if optional")]
+    fn container_defaults(style: ContainerDefaultStyle, expected: &str) {
+        assert_eq!(
+            tag_function(style).to_string().trim_end(),
+            expected.trim_end()
+        );
+    }
+
+    #[test_case("[1, 2]", Some("list"))]
+    #[test_case("{}", Some("dict"))]
+    #[test_case("{'a': 1}", Some("dict"))]
+    #[test_case("{1, 2}", Some("set"))]
+    #[test_case("set()", Some("set"))]
+    #[test_case("'[x]'", None)]
+    #[test_case("None", None)]
+    fn kind(default: &str, expected: Option<&str>) {
+        assert_eq!(container_kind(default), expected);
+    }
+
+    #[test]
+    fn style_in_pyproject() {
+        use crate::pyproject::StubGen;
+        for table in [
+            r#"container-default = "none-if-optional""#,
+            r#"container_default = "none-if-optional""#,
+        ] {
+            let stub_gen: StubGen = toml::from_str(table).unwrap();
+            assert_eq!(
+                stub_gen.container_default,
+                Some(ContainerDefaultStyle::NoneIfOptional)
+            );
+        }
+        assert!(toml::from_str::<StubGen>(r#"container-default = "none""#).is_err());
+    }
+}
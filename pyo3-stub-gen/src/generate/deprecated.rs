@@ -1,15 +1,15 @@
-use crate::type_info::DeprecatedInfo;
-use std::fmt;
+use crate::{config::Config, generate::Parameters, type_info::DeprecatedInfo};
+use std::{borrow::Cow, fmt};
 
 impl fmt::Display for DeprecatedInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "@typing_extensions.deprecated(")?;
         match (&self.since, &self.note) {
             (Some(since), Some(note)) => {
-                write!(f, "\"[Since {since}] {note}\"")?;
+                write!(f, "\"deprecated since {since}: {note}\"")?;
             }
             (Some(since), None) => {
-                write!(f, "\"[Since {since}]\"")?;
+                write!(f, "\"deprecated since {since}\"")?;
             }
             (None, Some(note)) => {
                 write!(f, "\"{note}\"")?;
@@ -22,3 +22,232 @@ impl fmt::Display for DeprecatedInfo {
         write!(f, ")")
     }
 }
+
+/// How `#[deprecated]` is expressed in generated stubs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum DeprecatedStyle {
+    /// `@typing_extensions.deprecated(...)` decorator, which requires importing `typing_extensions` in the stub.
+    Decorator = 0,
+    /// A `Deprecated:` section appended to the docstring, without any decorator or import.
+    Docstring = 1,
+    /// Both of the decorator and the `Deprecated:` section, so that the deprecation is also seen in the docstring.
+    Both = 2,
+}
+
+impl DeprecatedStyle {
+    fn renders_decorator(self) -> bool {
+        self != DeprecatedStyle::Docstring
+    }
+
+    fn renders_docstring(self) -> bool {
+        self != DeprecatedStyle::Decorator
+    }
+}
+
+/// Category of items whose [DeprecatedStyle] can be configured separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeprecatedTarget {
+    Function = 0,
+    Method = 1,
+    Property = 2,
+    Class = 3,
+}
+
+/// Set how deprecated items of the given category are rendered in generated stubs.
+pub fn set_deprecated_style(target: DeprecatedTarget, style: DeprecatedStyle) {
+    Config::update(|config| config.deprecated_styles[target as usize] = style);
+}
+
+/// Retrieve how deprecated items of the given category are rendered.
+pub fn deprecated_style(target: DeprecatedTarget) -> DeprecatedStyle {
+    Config::current().deprecated_styles[target as usize]
+}
+
+/// Set whether parameters of `#[gen_stub(deprecated_param(...))]` are annotated as
+/// `typing.Annotated[T, typing_extensions.deprecated("...")]` in addition to the docstring line.
+///
+/// This is set from `tool.pyo3-stub-gen.annotate-deprecated-parameters` in `pyproject.toml`.
+pub fn set_annotate_deprecated_parameters(enabled: bool) {
+    Config::update(|config| config.annotate_deprecated_parameters = enabled);
+}
+
+/// Retrieve whether deprecated parameters are annotated with `typing_extensions.deprecated`.
+pub fn annotate_deprecated_parameters() -> bool {
+    Config::current().annotate_deprecated_parameters
+}
+
+impl DeprecatedInfo {
+    /// Body of the `Deprecated:` docstring section.
+    fn docstring_note(&self) -> String {
+        match (&self.since, &self.note) {
+            (Some(since), Some(note)) => format!("Since {since}: {note}"),
+            (Some(since), None) => format!("Since {since}"),
+            (None, Some(note)) => note.to_string(),
+            (None, None) => "This item is deprecated.".to_string(),
+        }
+    }
+}
+
+/// The decorator to render, unless `target` uses [DeprecatedStyle::Docstring].
+pub(crate) fn deprecated_decorator(
+    deprecated: &Option<DeprecatedInfo>,
+    target: DeprecatedTarget,
+) -> Option<&DeprecatedInfo> {
+    deprecated
+        .as_ref()
+        .filter(|_| deprecated_style(target).renders_decorator())
+}
+
+/// Append a `Deprecated:` section to `doc` unless `target` uses [DeprecatedStyle::Decorator].
+pub(crate) fn docstring_with_deprecation<'a>(
+    doc: &'a str,
+    deprecated: &Option<DeprecatedInfo>,
+    target: DeprecatedTarget,
+) -> Cow<'a, str> {
+    match deprecated {
+        Some(deprecated) if deprecated_style(target).renders_docstring() => {
+            append_deprecated_section(doc, deprecated)
+        }
+        _ => Cow::Borrowed(doc),
+    }
+}
+
+/// Append a `Deprecated:` section to `doc` whatever the style is,
+/// for items such as module variables which cannot be decorated.
+pub(crate) fn append_deprecated_section<'a>(
+    doc: &'a str,
+    deprecated: &DeprecatedInfo,
+) -> Cow<'a, str> {
+    let doc = doc.trim_end();
+    // Indent the section like the lines of `doc`, or like a `///` comment if empty,
+    // so that it keeps its own indentation when the docstring is dedented
+    let margin = doc
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .min_by_key(|margin| margin.len())
+        .unwrap_or(" ");
+    let section = format!(
+        "{margin}Deprecated:\n{margin}    {}",
+        deprecated.docstring_note()
+    );
+    if doc.is_empty() {
+        Cow::Owned(section)
+    } else {
+        Cow::Owned(format!("{doc}\n\n{section}"))
+    }
+}
+
+/// Append a `Deprecated parameters:` line listing the deprecated parameters of `parameters` to `doc`.
+pub(crate) fn docstring_with_deprecated_parameters<'a>(
+    doc: &'a str,
+    parameters: &Parameters,
+) -> Cow<'a, str> {
+    let entries: Vec<String> = parameters
+        .iter_entries()
+        .filter_map(|parameter| match parameter.deprecated? {
+            "" => Some(parameter.name.to_string()),
+            note => Some(format!("{} — {note}", parameter.name)),
+        })
+        .collect();
+    if entries.is_empty() {
+        return Cow::Borrowed(doc);
+    }
+    let line = format!("Deprecated parameters: {}", entries.join("; "));
+    let doc = doc.trim_end();
+    if doc.is_empty() {
+        Cow::Owned(line)
+    } else {
+        Cow::Owned(format!("{doc}\n\n{line}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::{FunctionDef, Import, Parameter, QualName};
+    use crate::{type_info::ParameterKind, TypeInfo};
+
+    #[test]
+    fn deprecated_section_with_existing_doc() {
+        let deprecated = DeprecatedInfo {
+            since: Some("1.0"),
+            note: Some("Use `bar` instead"),
+        };
+        assert_eq!(
+            append_deprecated_section(" Compute foo.\n More details.", &deprecated),
+            " Compute foo.\n More details.\n\n Deprecated:\n     Since 1.0: Use `bar` instead"
+        );
+    }
+
+    #[test]
+    fn deprecated_section_without_doc() {
+        let deprecated = DeprecatedInfo {
+            since: None,
+            note: None,
+        };
+        assert_eq!(
+            append_deprecated_section("", &deprecated),
+            " Deprecated:\n     This item is deprecated."
+        );
+    }
+
+    #[test]
+    fn docstring_style_replaces_decorator() {
+        let function = FunctionDef {
+            name: "old_api".into(),
+            parameters: Parameters::new(),
+            r#return: TypeInfo::none(),
+            doc: "Do something.",
+            is_async: false,
+            deprecated: Some(DeprecatedInfo {
+                since: Some("0.5"),
+                note: Some("Use `new_api`"),
+            }),
+            type_ignored: None,
+            qualname: QualName::default(),
+            source_location: None,
+        };
+        let mut config = Config::current();
+        config.deprecated_styles[DeprecatedTarget::Function as usize] = DeprecatedStyle::Docstring;
+        let _config = config.scope();
+        let rendered = function.to_string();
+        let import = function.import();
+
+        assert!(!rendered.contains("@typing_extensions.deprecated"));
+        assert!(rendered.contains("    Deprecated:\n        Since 0.5: Use `new_api`\n"));
+        assert!(!import.contains(&"typing_extensions".into()));
+    }
+
+    #[test]
+    fn deprecated_parameters_line() {
+        let mut parameters = Parameters::new();
+        for (name, deprecated) in [
+            ("size", None),
+            ("old_size", Some("use size")),
+            ("legacy", Some("")),
+        ] {
+            let mut parameter = Parameter::new(
+                name,
+                ParameterKind::PositionalOrKeyword,
+                TypeInfo::builtin("int"),
+            );
+            parameter.deprecated = deprecated;
+            parameters.push(parameter);
+        }
+        assert_eq!(
+            docstring_with_deprecated_parameters(" Resize the canvas.\n", &parameters),
+            " Resize the canvas.\n\nDeprecated parameters: old_size — use size; legacy"
+        );
+        assert_eq!(
+            docstring_with_deprecated_parameters("", &parameters),
+            "Deprecated parameters: old_size — use size; legacy"
+        );
+        assert_eq!(
+            docstring_with_deprecated_parameters(" Resize.", &Parameters::new()),
+            " Resize."
+        );
+    }
+}
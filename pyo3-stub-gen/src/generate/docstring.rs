@@ -1,42 +1,433 @@
-use std::fmt;
+use crate::{config::Config, generate::MemberDef};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt};
 
-pub fn write_docstring(f: &mut fmt::Formatter, doc: &str, indent: &str) -> fmt::Result {
-    let doc = doc.trim();
-    if !doc.is_empty() {
-        writeln!(f, r#"{indent}r""""#)?;
+/// How the `# Arguments` section of Rust doc comments is rendered in docstrings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum DocstringStyle {
+    /// Keep the doc comment as written
+    #[default]
+    Verbatim,
+    /// Convert into an `Args:` section of the Google style
+    Google,
+    /// Convert into a `Parameters` section of the NumPy style
+    Numpy,
+}
 
-        // Dedent the docstring (similar to Python's textwrap.dedent)
-        let lines: Vec<&str> = doc.lines().collect();
+/// Set how the `# Arguments` section of doc comments is rendered in generated stubs.
+pub fn set_docstring_style(style: DocstringStyle) {
+    Config::update(|config| config.docstring_style = style);
+}
 
-        // Find the minimum indentation of non-empty lines (excluding the first line)
-        let min_indent = lines
-            .iter()
-            .skip(1) // Skip first line as it's usually right after the opening """
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
-            .min()
-            .unwrap_or(0);
-
-        // Write each line with common indentation removed
-        for (i, line) in lines.iter().enumerate() {
+/// Retrieve how the `# Arguments` section of doc comments is rendered.
+pub fn docstring_style() -> DocstringStyle {
+    Config::current().docstring_style
+}
+
+/// Configure whether an `Attributes:` section listing the attributes and properties is appended to class docstrings.
+/// Disabled by default.
+///
+/// This is set from `tool.pyo3-stub-gen.synthesize-attributes-section` in `pyproject.toml`.
+pub fn set_synthesize_attributes_section(enabled: bool) {
+    Config::update(|config| config.synthesize_attributes_section = enabled);
+}
+
+/// Whether an `Attributes:` section is appended to class docstrings.
+pub fn synthesize_attributes_section() -> bool {
+    Config::current().synthesize_attributes_section
+}
+
+pub(crate) fn write_docstring(f: &mut impl fmt::Write, doc: &str, indent: &str) -> fmt::Result {
+    write_docstring_lines(f, &docstring_lines(doc), indent)
+}
+
+/// Lines of the docstring of `doc`, dedented and with the `# Arguments` section converted,
+/// or none for an empty `doc`
+pub(crate) fn docstring_lines(doc: &str) -> Vec<String> {
+    // Python reads a lone `\r` as a newline too
+    let raw = &doc.replace("\r\n", "\n").replace('\r', "\n");
+    let doc = raw.trim();
+    if doc.is_empty() {
+        return Vec::new();
+    }
+    // Dedent the docstring (similar to Python's textwrap.dedent)
+    let lines: Vec<&str> = doc.lines().collect();
+    let indent_of = |line: &str| line.chars().take_while(|c| c.is_whitespace()).count();
+
+    // Find the minimum indentation of non-empty lines (excluding the first line)
+    let min_indent = lines
+        .iter()
+        .skip(1) // Skip first line as it's usually right after the opening """
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_of(line))
+        .min()
+        .unwrap_or(0);
+    // ...unless the first line is indented like a `///` comment, which the others are aligned with
+    let min_indent = match indent_of(raw.trim_start_matches(['\r', '\n'])) {
+        0 => min_indent,
+        first => min_indent.min(first),
+    };
+
+    // Remove common indentation
+    let lines: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
             if i == 0 {
-                // First line: write as-is (it's usually not indented in the original)
-                writeln!(f, "{indent}{line}")?;
+                // First line: keep as-is (it's usually not indented in the original)
+                line.to_string()
             } else if line.trim().is_empty() {
-                // Empty line: write just the base indent
-                writeln!(f, "{indent}")?;
+                String::new()
+            } else if line.len() >= min_indent {
+                line[min_indent..].to_string()
             } else {
-                // Other lines: remove common indentation
-                let dedented = if line.len() >= min_indent {
-                    &line[min_indent..]
-                } else {
-                    line.trim_start()
-                };
-                writeln!(f, "{indent}{dedented}")?;
+                line.trim_start().to_string()
+            }
+        })
+        .collect();
+    convert_arguments(&lines, docstring_style()).unwrap_or(lines)
+}
+
+pub(crate) fn write_docstring_lines(
+    f: &mut impl fmt::Write,
+    lines: &[String],
+    indent: &str,
+) -> fmt::Result {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let literal = Literal::of(lines);
+    writeln!(f, "{indent}{}", literal.open())?;
+    for line in lines {
+        if line.is_empty() {
+            writeln!(f, "{indent}")?;
+        } else {
+            writeln!(f, "{indent}{}", literal.escape(line))?;
+        }
+    }
+    writeln!(f, "{indent}{}", literal.close())
+}
+
+/// Append a section listing `attributes` with their types and the first lines of their docs,
+/// in the style of `Attributes:` of Google, or of NumPy for [DocstringStyle::Numpy],
+/// unless `lines` already has such a section.
+pub(crate) fn append_attributes_section(
+    lines: &mut Vec<String>,
+    attributes: &[&MemberDef],
+    style: DocstringStyle,
+) {
+    if attributes.is_empty()
+        || lines
+            .iter()
+            .any(|line| matches!(line.trim(), "Attributes:" | "Attributes"))
+    {
+        return;
+    }
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    let summary = |attribute: &MemberDef| attribute.doc.trim().lines().next().unwrap_or("");
+    match style {
+        DocstringStyle::Numpy => {
+            lines.push("Attributes".to_string());
+            lines.push("----------".to_string());
+            for attribute in attributes {
+                lines.push(format!("{} : {}", attribute.name, attribute.r#type));
+                match summary(attribute) {
+                    "" => {}
+                    summary => lines.push(format!("    {summary}")),
+                }
+            }
+        }
+        DocstringStyle::Verbatim | DocstringStyle::Google => {
+            lines.push("Attributes:".to_string());
+            for attribute in attributes {
+                let line = format!("    {} ({})", attribute.name, attribute.r#type);
+                lines.push(match summary(attribute) {
+                    "" => line,
+                    summary => format!("{line}: {summary}"),
+                });
             }
         }
+    }
+}
+
+/// Kind of the string literal a docstring is written in
+///
+/// The delimiters are on their own lines, so that a quote or a backslash at the end of the docstring
+/// cannot merge with the closing delimiter.
+/// Rust strings cannot contain lone surrogates, and U+FFFD left by a lossy conversion is written as is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Literal {
+    /// `r"""`, keeping backslashes, e.g. of Windows paths, as written
+    Raw,
+    /// `r'''`, for a docstring containing `"""` but not `'''`
+    RawSingle,
+    /// `"""` with escapes, since control characters other than tabs cannot be written in a raw string
+    Escaped,
+}
+
+impl Literal {
+    fn of(lines: &[String]) -> Self {
+        if lines
+            .iter()
+            .any(|line| line.contains(|c: char| c.is_control() && c != '\t'))
+        {
+            Self::Escaped
+        } else if lines.iter().any(|line| line.contains(r#"""""#))
+            && !lines.iter().any(|line| line.contains("'''"))
+        {
+            Self::RawSingle
+        } else {
+            Self::Raw
+        }
+    }
+
+    fn open(self) -> &'static str {
+        match self {
+            Self::Raw => r#"r""""#,
+            Self::RawSingle => "r'''",
+            Self::Escaped => r#"""""#,
+        }
+    }
+
+    fn close(self) -> &'static str {
+        self.open().trim_start_matches('r')
+    }
+
+    fn escape(self, line: &str) -> Cow<'_, str> {
+        match self {
+            // `\"` keeps a quote from closing the raw string, and the backslash is kept in the docstring
+            Self::Raw if line.contains(r#"""""#) => Cow::Owned(line.replace(r#"""""#, r#"\"\"\""#)),
+            Self::Raw | Self::RawSingle => Cow::Borrowed(line),
+            Self::Escaped => Cow::Owned(
+                line.chars()
+                    .map(|c| match c {
+                        '\\' => r"\\".to_string(),
+                        '"' => r#"\""#.to_string(),
+                        '\t' => c.to_string(),
+                        // Control characters are all below U+0100
+                        c if c.is_control() => format!(r"\x{:02x}", c as u32),
+                        c => c.to_string(),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Parameter described in the `# Arguments` section
+#[derive(Debug, PartialEq)]
+struct Argument<'a> {
+    name: &'a str,
+    description: Vec<&'a str>,
+}
 
-        writeln!(f, r#"{indent}""""#)?;
+/// Rewrite the `# Arguments` section of `lines` in the given style.
+///
+/// Returns `None` to keep the docstring verbatim, i.e. for [DocstringStyle::Verbatim],
+/// or if the section is missing or not in the form of `` * `name` - description ``.
+fn convert_arguments(lines: &[String], style: DocstringStyle) -> Option<Vec<String>> {
+    if style == DocstringStyle::Verbatim {
+        return None;
+    }
+    let start = lines
+        .iter()
+        .position(|line| matches!(line.trim_end(), "# Arguments" | "# Args" | "# Parameters"))?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.starts_with('#'))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    let arguments = parse_arguments(&lines[start + 1..end])?;
+
+    let mut out = lines[..start].to_vec();
+    match style {
+        DocstringStyle::Verbatim => return None,
+        DocstringStyle::Google => {
+            out.push("Args:".to_string());
+            for Argument { name, description } in &arguments {
+                let (first, rest) = description.split_first()?;
+                out.push(format!("    {name}: {first}"));
+                out.extend(rest.iter().map(|line| format!("        {line}")));
+            }
+        }
+        DocstringStyle::Numpy => {
+            out.push("Parameters".to_string());
+            out.push("----------".to_string());
+            for Argument { name, description } in &arguments {
+                out.push(name.to_string());
+                out.extend(description.iter().map(|line| format!("    {line}")));
+            }
+        }
+    }
+    if end < lines.len() {
+        out.push(String::new());
+        out.extend_from_slice(&lines[end..]);
+    }
+    Some(out)
+}
+
+/// Parse the body of the `# Arguments` section, or `None` if any line is not recognized.
+fn parse_arguments(lines: &[String]) -> Option<Vec<Argument<'_>>> {
+    let mut arguments: Vec<Argument> = Vec::new();
+    for line in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(item) = line.strip_prefix("* ").or_else(|| line.strip_prefix("- ")) {
+            let (name, description) = item.split_once(" - ").or_else(|| item.split_once(": "))?;
+            let name = name.trim();
+            let name = name
+                .strip_prefix('`')
+                .and_then(|name| name.strip_suffix('`'))
+                .unwrap_or(name);
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return None;
+            }
+            arguments.push(Argument {
+                name,
+                description: vec![description.trim()],
+            });
+        } else if line.starts_with(char::is_whitespace) {
+            // Continuation of the description of the previous argument
+            arguments.last_mut()?.description.push(line.trim());
+        } else {
+            return None;
+        }
+    }
+    (!arguments.is_empty()).then_some(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(doc: &str) -> Vec<String> {
+        doc.lines().map(ToString::to_string).collect()
+    }
+
+    const DOC: &str = "\
+Scale a point.
+
+# Arguments
+
+* `x` - The x coordinate
+* `factor` - Multiplier applied to
+  every coordinate
+
+# Returns
+
+The scaled point";
+
+    #[test]
+    fn google_style() {
+        assert_eq!(
+            convert_arguments(&lines(DOC), DocstringStyle::Google).unwrap(),
+            lines(
+                "\
+Scale a point.
+
+Args:
+    x: The x coordinate
+    factor: Multiplier applied to
+        every coordinate
+
+# Returns
+
+The scaled point"
+            )
+        );
+    }
+
+    #[test]
+    fn numpy_style() {
+        assert_eq!(
+            convert_arguments(&lines(DOC), DocstringStyle::Numpy).unwrap(),
+            lines(
+                "\
+Scale a point.
+
+Parameters
+----------
+x
+    The x coordinate
+factor
+    Multiplier applied to
+    every coordinate
+
+# Returns
+
+The scaled point"
+            )
+        );
+    }
+
+    struct Docstring(&'static str);
+
+    impl fmt::Display for Docstring {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write_docstring(f, self.0, "")
+        }
+    }
+
+    #[test]
+    fn quotes_and_backslashes() {
+        assert_eq!(Docstring(r#"""""#).to_string(), "r'''\n\"\"\"\n'''\n");
+        assert_eq!(
+            Docstring(r"Ends with \").to_string(),
+            "r\"\"\"\nEnds with \\\n\"\"\"\n"
+        );
+        assert_eq!(
+            Docstring(r"Reads C:\Users\me\config.toml").to_string(),
+            "r\"\"\"\nReads C:\\Users\\me\\config.toml\n\"\"\"\n"
+        );
+        assert_eq!(
+            Docstring(r#"Both """ and '''"#).to_string(),
+            "r\"\"\"\nBoth \\\"\\\"\\\" and '''\n\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn newlines_and_control_characters() {
+        assert_eq!(
+            Docstring("First\r\nSecond\rThird").to_string(),
+            "r\"\"\"\nFirst\nSecond\nThird\n\"\"\"\n"
+        );
+        assert_eq!(
+            Docstring("Bell \u{7} in \"C:\\\"").to_string(),
+            "\"\"\"\nBell \\x07 in \\\"C:\\\\\\\"\n\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn unrecognized_section_is_kept_verbatim() {
+        let doc = lines("Summary\n\n# Arguments\n\nTakes `x` and `y`.");
+        assert_eq!(convert_arguments(&doc, DocstringStyle::Google), None);
+        assert_eq!(
+            convert_arguments(&lines(DOC), DocstringStyle::Verbatim),
+            None
+        );
+        assert_eq!(
+            convert_arguments(&lines("No arguments"), DocstringStyle::Numpy),
+            None
+        );
+    }
+
+    #[test]
+    fn style_in_pyproject() {
+        use crate::pyproject::StubGen;
+        for table in [
+            r#"docstring-style = "google""#,
+            r#"docstring_style = "google""#,
+        ] {
+            let stub_gen: StubGen = toml::from_str(table).unwrap();
+            assert_eq!(stub_gen.docstring_style, Some(DocstringStyle::Google));
+        }
+        let stub_gen: StubGen = toml::from_str(r#"docstring-style = "numpy""#).unwrap();
+        assert_eq!(stub_gen.docstring_style, Some(DocstringStyle::Numpy));
+        let stub_gen: StubGen = toml::from_str("synthesize-attributes-section = true").unwrap();
+        assert_eq!(stub_gen.synthesize_attributes_section, Some(true));
     }
-    Ok(())
 }
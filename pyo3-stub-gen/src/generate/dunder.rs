@@ -0,0 +1,348 @@
+//! Signatures of class-protocol hooks such as `__init_subclass__`, which Python calls with fixed arguments,
+//! and returns of conversion methods such as `__fspath__`, whose results Python checks.
+//!
+//! Declared parameters and returns are kept as long as they are compatible with what Python expects.
+//! Conflicts are logged as warnings, and `#[gen_stub(keep_signature)]` opts out of the fixups.
+
+use crate::{
+    generate::{qualname, MethodDef, Parameter},
+    stub_type::PyStubType,
+    type_info::{MethodType, ParameterInfo, ParameterKind},
+    TypeInfo,
+};
+use pyo3::types::PyAny;
+
+/// Signature of a special method expected by Python
+struct Protocol {
+    name: &'static str,
+    r#type: MethodType,
+    /// Parameters after `self` or `cls`, or `None` to keep the declared ones
+    parameters: Option<&'static [fn() -> TypeInfo]>,
+    /// Whether `**kwargs: typing.Any` is appended unless declared
+    varkw: bool,
+    r#return: fn() -> TypeInfo,
+}
+
+const PROTOCOLS: &[Protocol] = &[
+    Protocol {
+        name: "__init_subclass__",
+        r#type: MethodType::Class,
+        parameters: None,
+        varkw: true,
+        r#return: TypeInfo::none,
+    },
+    Protocol {
+        name: "__set_name__",
+        r#type: MethodType::Instance,
+        parameters: Some(&[TypeInfo::class_of::<PyAny>, String::type_output]),
+        varkw: false,
+        r#return: TypeInfo::none,
+    },
+    Protocol {
+        name: "__class_getitem__",
+        r#type: MethodType::Class,
+        parameters: Some(&[TypeInfo::any]),
+        varkw: false,
+        r#return: || TypeInfo::with_module("types.GenericAlias", "types".into()),
+    },
+    conversion("__fspath__", || TypeInfo::builtin("str")),
+    conversion("__bytes__", || TypeInfo::builtin("bytes")),
+    conversion("__index__", || TypeInfo::builtin("int")),
+    conversion("__int__", || TypeInfo::builtin("int")),
+    conversion("__hash__", || TypeInfo::builtin("int")),
+    conversion("__len__", || TypeInfo::builtin("int")),
+    conversion("__float__", || TypeInfo::builtin("float")),
+    conversion("__bool__", || TypeInfo::builtin("bool")),
+    conversion("__repr__", || TypeInfo::builtin("str")),
+    conversion("__str__", || TypeInfo::builtin("str")),
+];
+
+/// Instance method without arguments whose result must be of the type `r#return`
+const fn conversion(name: &'static str, r#return: fn() -> TypeInfo) -> Protocol {
+    Protocol {
+        name,
+        r#type: MethodType::Instance,
+        parameters: None,
+        varkw: false,
+        r#return,
+    }
+}
+
+/// Whether a declared type is replaced by the one of the protocol without a warning
+fn is_untyped(type_info: &TypeInfo) -> bool {
+    matches!(
+        type_info.name.as_str(),
+        "typing.Any" | "builtins.object" | "object"
+    )
+}
+
+impl MethodDef {
+    /// Fix up the signature of a class-protocol hook, whose parameters are declared by `infos`
+    pub(crate) fn apply_protocol(&mut self, infos: &[ParameterInfo]) {
+        for warning in self.fix_protocol(infos) {
+            log::warn!("{warning}");
+        }
+    }
+
+    /// Fix up the signature as [MethodDef::apply_protocol] does, returning the conflicts to be warned
+    fn fix_protocol(&mut self, infos: &[ParameterInfo]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let Some(protocol) = PROTOCOLS.iter().find(|protocol| protocol.name == self.name) else {
+            return warnings;
+        };
+        // Pointing at the Rust method in the warnings, e.g. `__len__` at src/lib.rs:12
+        let name = format!(
+            "`{}`{}",
+            self.name,
+            qualname::at_location(self.source_location)
+        );
+        if self.r#type != protocol.r#type {
+            warnings.push(format!(
+                "{name} is called by Python as {}, but it is declared as {}",
+                describe_type(protocol.r#type),
+                describe_type(self.r#type)
+            ));
+        }
+
+        let parameters = &mut self.parameters;
+        if let Some(expected) = protocol.parameters {
+            let declared =
+                parameters.positional_only.len() + parameters.positional_or_keyword.len();
+            if declared != expected.len()
+                || parameters.varargs.is_some()
+                || !parameters.keyword_only.is_empty()
+            {
+                warnings.push(format!(
+                    "{name} is called by Python with {} argument(s), but it declares {declared} positional parameter(s)",
+                    expected.len()
+                ));
+            } else {
+                for (parameter, expected) in parameters
+                    .positional_only
+                    .iter_mut()
+                    .chain(parameters.positional_or_keyword.iter_mut())
+                    .zip(expected)
+                {
+                    let expected = expected();
+                    let overridden = infos
+                        .iter()
+                        .any(|info| info.name == parameter.name && info.type_overridden);
+                    if overridden || parameter.type_info == expected {
+                        continue;
+                    }
+                    if is_untyped(&parameter.type_info) {
+                        parameter.type_info = expected;
+                    } else {
+                        warnings.push(format!(
+                            "Parameter `{}` of {name} is declared as `{}`, but Python passes `{}`",
+                            parameter.name, parameter.type_info, expected
+                        ));
+                    }
+                }
+            }
+        }
+        if protocol.varkw && parameters.varkw.is_none() {
+            parameters.varkw = Some(Parameter::new(
+                "kwargs",
+                ParameterKind::VarKeyword,
+                TypeInfo::any(),
+            ));
+        }
+
+        let expected = (protocol.r#return)();
+        if self.r#return != expected {
+            if is_untyped(&self.r#return) {
+                self.r#return = expected;
+            } else {
+                warnings.push(format!(
+                    "{name} is declared to return `{}`, but Python expects `{expected}`",
+                    self.r#return
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+fn describe_type(r#type: MethodType) -> &'static str {
+    match r#type {
+        MethodType::Instance => "an instance method",
+        MethodType::Static => "a static method",
+        MethodType::Class => "a class method",
+        MethodType::New => "a constructor",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generate::{MethodDef, Parameters},
+        type_info::{MethodInfo, MethodType, ParameterDefault, ParameterInfo, ParameterKind},
+        TypeInfo,
+    };
+
+    fn parameter(name: &'static str, type_info: fn() -> TypeInfo) -> ParameterInfo {
+        ParameterInfo {
+            name,
+            kind: ParameterKind::PositionalOrKeyword,
+            type_info,
+            default: ParameterDefault::None,
+            type_overridden: false,
+            container_default: None,
+            deprecated: None,
+        }
+    }
+
+    fn method(
+        name: &'static str,
+        r#type: MethodType,
+        parameters: &'static [ParameterInfo],
+        r#return: fn() -> TypeInfo,
+        keep_signature: bool,
+    ) -> String {
+        MethodDef::from(&MethodInfo {
+            name,
+            parameters,
+            r#return,
+            r#type,
+            keep_signature,
+            ..MethodInfo::DEFAULT
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn init_subclass() {
+        static PARAMS: std::sync::LazyLock<[ParameterInfo; 1]> =
+            std::sync::LazyLock::new(|| [parameter("registry", || TypeInfo::builtin("str"))]);
+        assert_eq!(
+            method(
+                "__init_subclass__",
+                MethodType::Class,
+                &*PARAMS,
+                TypeInfo::none,
+                false
+            ),
+            "    @classmethod\n    def __init_subclass__(cls, registry: builtins.str, **kwargs: typing.Any) -> None: ...\n"
+        );
+        assert_eq!(
+            method(
+                "__init_subclass__",
+                MethodType::Class,
+                &*PARAMS,
+                TypeInfo::none,
+                true
+            ),
+            "    @classmethod\n    def __init_subclass__(cls, registry: builtins.str) -> None: ...\n"
+        );
+    }
+
+    #[test]
+    fn set_name() {
+        static PARAMS: std::sync::LazyLock<[ParameterInfo; 2]> = std::sync::LazyLock::new(|| {
+            [
+                parameter("owner", TypeInfo::any),
+                parameter("name", || TypeInfo::builtin("str")),
+            ]
+        });
+        assert_eq!(
+            method("__set_name__", MethodType::Instance, &*PARAMS, TypeInfo::none, false),
+            "    def __set_name__(self, owner: type[typing.Any], name: builtins.str) -> None: ...\n"
+        );
+
+        // Conflicting types are kept
+        static CONFLICTING: std::sync::LazyLock<[ParameterInfo; 2]> =
+            std::sync::LazyLock::new(|| {
+                [
+                    parameter("owner", TypeInfo::any),
+                    parameter("name", || TypeInfo::builtin("int")),
+                ]
+            });
+        assert_eq!(
+            method(
+                "__set_name__",
+                MethodType::Instance,
+                &*CONFLICTING,
+                TypeInfo::none,
+                false
+            ),
+            "    def __set_name__(self, owner: type[typing.Any], name: builtins.int) -> None: ...\n"
+        );
+    }
+
+    #[test]
+    fn warnings_point_at_source_location() {
+        static PARAMS: std::sync::LazyLock<[ParameterInfo; 1]> =
+            std::sync::LazyLock::new(|| [parameter("item", TypeInfo::any)]);
+        let mut method = MethodDef::new(
+            "__class_getitem__",
+            MethodType::Instance,
+            Parameters::from_infos(&*PARAMS),
+            TypeInfo::any(),
+        );
+        method.source_location = Some("src/lib.rs:42");
+        assert_eq!(
+            method.fix_protocol(&*PARAMS),
+            ["`__class_getitem__` at src/lib.rs:42 is called by Python as a class method, but it is declared as an instance method"]
+        );
+    }
+
+    #[test]
+    fn class_getitem() {
+        static PARAMS: std::sync::LazyLock<[ParameterInfo; 1]> =
+            std::sync::LazyLock::new(|| [parameter("item", TypeInfo::any)]);
+        assert_eq!(
+            method(
+                "__class_getitem__",
+                MethodType::Class,
+                &*PARAMS,
+                TypeInfo::any,
+                false
+            ),
+            "    @classmethod\n    def __class_getitem__(cls, item: typing.Any) -> types.GenericAlias: ...\n"
+        );
+    }
+
+    #[test]
+    fn conversion_returns() {
+        assert_eq!(
+            method(
+                "__fspath__",
+                MethodType::Instance,
+                &[],
+                TypeInfo::any,
+                false
+            ),
+            "    def __fspath__(self) -> builtins.str: ...\n"
+        );
+        assert_eq!(
+            method(
+                "__bytes__",
+                MethodType::Instance,
+                &[],
+                || TypeInfo::builtin("object"),
+                false
+            ),
+            "    def __bytes__(self) -> builtins.bytes: ...\n"
+        );
+        assert_eq!(
+            method("__len__", MethodType::Instance, &[], TypeInfo::any, true),
+            "    def __len__(self) -> typing.Any: ...\n"
+        );
+    }
+
+    #[test]
+    fn conflicting_conversion_return() {
+        // The declared type is kept, and a warning is logged
+        assert_eq!(
+            method(
+                "__repr__",
+                MethodType::Instance,
+                &[],
+                || TypeInfo::builtin("int"),
+                false
+            ),
+            "    def __repr__(self) -> builtins.int: ...\n"
+        );
+    }
+}
@@ -1,8 +1,15 @@
-use crate::{generate::*, type_info::*};
+use crate::{
+    generate::{
+        member::{GetterDisplay, SetterDisplay},
+        *,
+    },
+    type_info::*,
+};
 use std::fmt;
 
 /// Definition of a Python enum.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct EnumDef {
     pub name: &'static str,
     pub doc: &'static str,
@@ -11,6 +18,24 @@ pub struct EnumDef {
     pub attrs: Vec<MemberDef>,
     pub getters: Vec<MemberDef>,
     pub setters: Vec<MemberDef>,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+}
+
+impl EnumDef {
+    /// Enum with the given `(name, doc)` pairs of variants and no other members
+    pub fn new(name: &'static str, variants: &'static [(&'static str, &'static str)]) -> Self {
+        Self {
+            name,
+            doc: "",
+            variants,
+            methods: Vec::new(),
+            attrs: Vec::new(),
+            getters: Vec::new(),
+            setters: Vec::new(),
+            qualname: QualName::default(),
+        }
+    }
 }
 
 impl From<&PyEnumInfo> for EnumDef {
@@ -23,6 +48,7 @@ impl From<&PyEnumInfo> for EnumDef {
             attrs: Vec::new(),
             getters: Vec::new(),
             setters: Vec::new(),
+            qualname: QualName::default(),
         }
     }
 }
@@ -83,3 +109,33 @@ impl fmt::Display for EnumDef {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documented_members() {
+        let mut enum_ = EnumDef::new(
+            "Color",
+            &[("RED", " Primary color.\n Used for errors."), ("GREEN", "")],
+        );
+        enum_.doc = "Color of a label";
+        assert_eq!(
+            enum_.to_string(),
+            r#"@typing.final
+class Color(enum.Enum):
+    r"""
+    Color of a label
+    """
+    RED = ...
+    r"""
+    Primary color.
+    Used for errors.
+    """
+    GREEN = ...
+
+"#
+        );
+    }
+}
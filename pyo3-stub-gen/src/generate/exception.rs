@@ -0,0 +1,94 @@
+use crate::{
+    generate::{docstring, indent, Import, QualName},
+    stub_type::ImportRef,
+    type_info::PyExceptionInfo,
+    TypeInfo,
+};
+use std::{collections::HashSet, fmt};
+
+/// Definition of an exception class created by [crate::create_exception] or [crate::gen_stub_create_exception].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ExceptionDef {
+    pub name: &'static str,
+    /// Base exception class, e.g. `builtins.RuntimeError` or another exception created in this crate
+    pub base: TypeInfo,
+    pub doc: &'static str,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+}
+
+impl ExceptionDef {
+    /// Exception `name` derived from `base` without a docstring
+    pub fn new(name: &'static str, base: TypeInfo) -> Self {
+        Self {
+            name,
+            base,
+            doc: "",
+            qualname: QualName::default(),
+        }
+    }
+}
+
+impl From<&PyExceptionInfo> for ExceptionDef {
+    fn from(info: &PyExceptionInfo) -> Self {
+        Self {
+            name: info.name,
+            base: (info.base)(),
+            doc: info.doc,
+            qualname: QualName::default(),
+        }
+    }
+}
+
+impl Import for ExceptionDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        self.base.import.clone()
+    }
+}
+
+impl fmt::Display for ExceptionDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "class {}({}):", self.name, self.base.name)?;
+        let indent = indent();
+        docstring::write_docstring(f, self.doc, indent)?;
+        writeln!(f, "{indent}...")?;
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Module;
+
+    #[test]
+    fn render() {
+        let mut def = ExceptionDef::new("MyError", TypeInfo::builtin("RuntimeError"));
+        assert_eq!(
+            def.to_string(),
+            "class MyError(builtins.RuntimeError):\n    ...\n\n"
+        );
+        def.doc = "Raised when the input is broken.";
+        assert_eq!(
+            def.to_string(),
+            "class MyError(builtins.RuntimeError):\n    r\"\"\"\n    Raised when the input is broken.\n    \"\"\"\n    ...\n\n"
+        );
+    }
+
+    #[test]
+    fn base_in_another_module() {
+        let mut module = Module::new("mypkg.io", "mypkg");
+        let base = TypeInfo::locally_defined("BaseError", "mypkg.errors".into());
+        module
+            .exceptions
+            .insert("ReadError", ExceptionDef::new("ReadError", base));
+        let stub = module.to_string();
+        assert!(
+            stub.contains("from mypkg.errors import BaseError\n"),
+            "{stub}"
+        );
+        assert!(stub.contains("class ReadError(BaseError):\n"), "{stub}");
+        assert!(stub.contains("    \"ReadError\",\n"), "{stub}");
+    }
+}
@@ -0,0 +1,333 @@
+//! Exclusion and inclusion of modules and symbols configured by `[tool.pyo3-stub-gen.filter]`.
+
+use crate::{generate::*, pyproject::Filter};
+
+/// Match a dotted name against a glob.
+///
+/// `*` and `?` match within a single component, and a `**` component matches zero or more components.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let name: Vec<&str> = name.split('.').collect();
+    match_components(&pattern, &name)
+}
+
+fn match_components(pattern: &[&str], name: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((&"**", rest)) => (0..=name.len()).any(|i| match_components(rest, &name[i..])),
+        Some((head, rest)) => name.split_first().is_some_and(|(first, name_rest)| {
+            match_component(head.as_bytes(), first.as_bytes()) && match_components(rest, name_rest)
+        }),
+    }
+}
+
+fn match_component(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| match_component(rest, &name[i..])),
+        Some((b'?', rest)) => !name.is_empty() && match_component(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_component(rest, &name[1..]),
+    }
+}
+
+struct Rule<'a> {
+    key: &'static str,
+    pattern: &'a str,
+    /// Number of names matched by the pattern
+    matched: usize,
+    /// Number of items removed by an exclude rule, or kept by an include rule
+    applied: usize,
+}
+
+impl<'a> Rule<'a> {
+    fn new(key: &'static str, patterns: &'a [String]) -> impl Iterator<Item = Self> + 'a {
+        patterns.iter().map(move |pattern| Self {
+            key,
+            pattern,
+            matched: 0,
+            applied: 0,
+        })
+    }
+}
+
+struct Rules<'a> {
+    exclude: Vec<Rule<'a>>,
+    include: Vec<Rule<'a>>,
+}
+
+impl<'a> Rules<'a> {
+    fn modules(filter: &'a Filter) -> Self {
+        Self {
+            exclude: Rule::new("exclude-modules", &filter.exclude_modules).collect(),
+            include: Rule::new("include-modules", &filter.include_modules).collect(),
+        }
+    }
+
+    fn symbols(filter: &'a Filter) -> Self {
+        Self {
+            exclude: Rule::new("exclude-symbols", &filter.exclude_symbols).collect(),
+            include: Rule::new("include-symbols", &filter.include_symbols).collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.exclude.is_empty() && self.include.is_empty()
+    }
+
+    /// Whether the item named `name` is kept
+    fn keep(&mut self, name: &str) -> bool {
+        let mut excluded = false;
+        for rule in &mut self.exclude {
+            if glob_match(rule.pattern, name) {
+                rule.matched += 1;
+                excluded = true;
+            }
+        }
+        let mut included = false;
+        for rule in &mut self.include {
+            if glob_match(rule.pattern, name) {
+                rule.matched += 1;
+                if excluded {
+                    rule.applied += 1;
+                }
+                included = true;
+            }
+        }
+        if excluded && !included {
+            for rule in &mut self.exclude {
+                if glob_match(rule.pattern, name) {
+                    rule.applied += 1;
+                }
+            }
+        }
+        !excluded || included
+    }
+
+    fn report(&self) {
+        for rule in &self.exclude {
+            log::info!(
+                "Filter `{}` in {} removed {} item(s)",
+                rule.pattern,
+                rule.key,
+                rule.applied
+            );
+        }
+        for rule in &self.include {
+            log::info!(
+                "Filter `{}` in {} kept {} excluded item(s)",
+                rule.pattern,
+                rule.key,
+                rule.applied
+            );
+        }
+        for rule in self.exclude.iter().chain(&self.include) {
+            if rule.matched == 0 {
+                log::warn!(
+                    "Filter `{}` in {} does not match anything. Consider removing it.",
+                    rule.pattern,
+                    rule.key
+                );
+            }
+        }
+    }
+}
+
+impl StubInfo {
+    /// Remove modules and symbols according to `[tool.pyo3-stub-gen.filter]`.
+    ///
+    /// Removing a module also removes it from the submodules, and hence `__all__`, of its parent.
+    /// Removing a class removes its members as well.
+    pub fn apply_filter(&mut self, filter: &Filter) -> &mut Self {
+        let mut modules = Rules::modules(filter);
+        if !modules.is_empty() {
+            let removed: Vec<String> = self
+                .modules
+                .keys()
+                .filter(|name| !modules.keep(name))
+                .cloned()
+                .collect();
+            for name in &removed {
+                self.modules.remove(name);
+            }
+            for (name, module) in self.modules.iter_mut() {
+                module
+                    .submodules
+                    .retain(|sub| !removed.contains(&format!("{name}.{sub}")));
+            }
+            modules.report();
+        }
+
+        let mut symbols = Rules::symbols(filter);
+        if !symbols.is_empty() {
+            for (name, module) in self.modules.iter_mut() {
+                module.retain_symbols(name, &mut |name| symbols.keep(name));
+            }
+            symbols.report();
+        }
+        self
+    }
+}
+
+impl Module {
+    fn retain_symbols(&mut self, module: &str, keep: &mut impl FnMut(&str) -> bool) {
+        self.class.retain(|_, class| {
+            let name = format!("{module}.{}", class.name);
+            keep(&name) && {
+                class.retain_members(&name, keep);
+                true
+            }
+        });
+        self.enum_.retain(|_, enum_| {
+            let name = format!("{module}.{}", enum_.name);
+            keep(&name) && {
+                enum_
+                    .methods
+                    .retain(|m| keep(&format!("{name}.{}", m.name)));
+                for members in [&mut enum_.attrs, &mut enum_.getters, &mut enum_.setters] {
+                    members.retain(|m| keep(&format!("{name}.{}", m.name)));
+                }
+                true
+            }
+        });
+        self.exceptions
+            .retain(|name, _| keep(&format!("{module}.{name}")));
+        self.function
+            .retain(|name, _| keep(&format!("{module}.{name}")));
+        self.variables
+            .retain(|name, _| keep(&format!("{module}.{name}")));
+        self.synthesized
+            .retain(|name, _| keep(&format!("{module}.{name}")));
+    }
+}
+
+impl ClassDef {
+    fn retain_members(&mut self, qualname: &str, keep: &mut impl FnMut(&str) -> bool) {
+        self.methods
+            .retain(|name, _| keep(&format!("{qualname}.{name}")));
+        self.attrs
+            .retain(|attr| keep(&format!("{qualname}.{}", attr.name)));
+        self.getter_setters
+            .retain(|name, _| keep(&format!("{qualname}.{name}")));
+        self.classes.retain_mut(|class| {
+            let name = format!("{qualname}.{}", class.name);
+            keep(&name) && {
+                class.retain_members(&name, keep);
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeInfo;
+    use std::{any::TypeId, collections::BTreeMap};
+    use test_case::test_case;
+
+    #[test_case("mypkg", "mypkg" => true; "exact")]
+    #[test_case("mypkg", "mypkg.sub" => false; "exact does not match children")]
+    #[test_case("mypkg.*", "mypkg.sub" => true; "star matches a component")]
+    #[test_case("mypkg.*", "mypkg.sub.deep" => false; "star does not cross dots")]
+    #[test_case("mypkg.*", "mypkg" => false; "star requires a component")]
+    #[test_case("mypkg.**", "mypkg.sub.deep" => true; "double star matches many components")]
+    #[test_case("mypkg.**", "mypkg" => true; "double star matches zero components")]
+    #[test_case("**._unstable_*", "mypkg.Class._unstable_method" => true; "prefix at any depth")]
+    #[test_case("**._unstable_*", "mypkg._unstable" => false; "prefix requires the underscore")]
+    #[test_case("mypkg.**.helper", "mypkg.helper" => true; "double star in the middle")]
+    #[test_case("mypkg.**.helper", "mypkg.a.b.helper" => true; "double star spans components")]
+    #[test_case("my?kg", "mypkg" => true; "question mark")]
+    #[test_case("*", "mypkg.sub" => false; "single star")]
+    #[test_case("**", "mypkg.sub" => true; "double star matches everything")]
+    fn glob(pattern: &str, name: &str) -> bool {
+        glob_match(pattern, name)
+    }
+
+    fn class(name: &'static str, methods: &[&'static str]) -> ClassDef {
+        let mut class = ClassDef::new(name);
+        for name in methods {
+            class.add_method(MethodDef::new(
+                *name,
+                crate::type_info::MethodType::Instance,
+                Parameters::new(),
+                TypeInfo::none(),
+            ));
+        }
+        class
+    }
+
+    fn module(name: &str, classes: Vec<(TypeId, ClassDef)>, submodules: &[&str]) -> Module {
+        Module {
+            name: name.to_string(),
+            default_module_name: "mypkg".to_string(),
+            class: classes.into_iter().collect(),
+            submodules: submodules.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn stub_info() -> StubInfo {
+        let modules = [
+            module(
+                "mypkg",
+                vec![
+                    (
+                        TypeId::of::<u8>(),
+                        class("Public", &["run", "_unstable_run"]),
+                    ),
+                    (TypeId::of::<u16>(), class("_unstable_Class", &["run"])),
+                ],
+                &["experimental", "_partner"],
+            ),
+            module("mypkg.experimental", vec![], &["deep"]),
+            module("mypkg.experimental.deep", vec![], &[]),
+            module("mypkg._partner", vec![], &[]),
+        ];
+        StubInfo {
+            modules: modules
+                .into_iter()
+                .map(|m| (m.name.clone(), m))
+                .collect::<BTreeMap<_, _>>(),
+            python_root: Default::default(),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn filter_modules_and_symbols() {
+        let mut info = stub_info();
+        info.apply_filter(&Filter {
+            exclude_modules: vec!["mypkg.experimental.**".to_string(), "mypkg._*".to_string()],
+            include_modules: vec!["mypkg._partner".to_string()],
+            exclude_symbols: vec!["**._unstable_*".to_string()],
+            include_symbols: vec!["mypkg._unstable_Class".to_string()],
+        });
+        assert_eq!(
+            info.modules.keys().collect::<Vec<_>>(),
+            ["mypkg", "mypkg._partner"]
+        );
+        let root = &info.modules["mypkg"];
+        assert_eq!(root.public_names(), ["Public"]);
+        assert!(root.submodules.contains("_partner"));
+
+        let public = &root.class[&TypeId::of::<u8>()];
+        assert_eq!(public.methods.keys().collect::<Vec<_>>(), ["run"]);
+        // Kept by `include-symbols`, and its members are not excluded
+        let unstable = &root.class[&TypeId::of::<u16>()];
+        assert_eq!(unstable.methods.keys().collect::<Vec<_>>(), ["run"]);
+    }
+
+    #[test]
+    fn filtering_class_removes_members() {
+        let mut info = stub_info();
+        info.apply_filter(&Filter {
+            exclude_symbols: vec!["mypkg.*".to_string()],
+            include_symbols: vec!["mypkg.Public.run".to_string()],
+            ..Default::default()
+        });
+        // Members of a removed class are removed regardless of `include-symbols`
+        assert!(info.modules["mypkg"].class.is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+//! Classes rendered as `enum.Flag` by `#[gen_stub(flag_enum)]`, e.g. bitflags wrapped in a `#[pyclass]`.
+//!
+//! `enum.Flag` defines the bitwise operators, so the operator methods of such a class are not rendered,
+//! and its class attributes of the class itself become the members.
+
+use crate::{generate::*, type_info::*, TypeInfo};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet, HashMap},
+};
+
+/// Binary operators defined by `enum.Flag`, which take and return the class itself
+const BINARY_OPERATORS: [&str; 6] = [
+    "__or__", "__and__", "__xor__", "__ror__", "__rand__", "__rxor__",
+];
+
+/// Collect the integer values of the class attributes submitted to [inventory], keyed by the class and the attribute name
+pub(crate) fn flag_values() -> HashMap<(TypeId, &'static str), &'static str> {
+    inventory::iter::<PyMethodsInfo>
+        .into_iter()
+        .flat_map(|info| {
+            let struct_id = (info.struct_id)();
+            info.attrs
+                .iter()
+                .filter_map(move |attr| Some(((struct_id, attr.name), attr.int_value?)))
+        })
+        .collect()
+}
+
+/// Render the classes in `flag_enums` as `enum.Flag`, with the member values taken from `values`,
+/// and return the classes rendered so.
+///
+/// A class whose operator methods or bases do not fit `enum.Flag` is kept as a plain class with a warning.
+pub(crate) fn render_flag_enums(
+    modules: &mut BTreeMap<String, Module>,
+    flag_enums: &BTreeSet<TypeId>,
+    values: &HashMap<(TypeId, &'static str), &'static str>,
+) -> BTreeSet<TypeId> {
+    let mut rendered = BTreeSet::new();
+    for (module_name, module) in modules.iter_mut() {
+        for (id, class) in module.class.iter_mut() {
+            if !flag_enums.contains(id) {
+                continue;
+            }
+            if let Some(reason) = incompatibility(class) {
+                log::warn!(
+                    "`{module_name}.{}` is rendered as a plain class instead of `enum.Flag`, since {reason}",
+                    class.name
+                );
+                continue;
+            }
+            into_flag(class, |name| values.get(&(*id, name)).copied());
+            rendered.insert(*id);
+        }
+    }
+    rendered
+}
+
+/// Whether `ty` is the class `name` itself
+fn is_own(name: &str, ty: &TypeInfo) -> bool {
+    ty.name == name || ty.name == "Self"
+}
+
+/// Whether `method` has the signature of the operator in `enum.Flag`,
+/// i.e. `(self, other: Self) -> Self` for a binary operator, or `(self) -> Self` for `__invert__`
+fn fits_flag(class_name: &str, method: &MethodDef) -> bool {
+    let Parameters {
+        positional_only,
+        positional_or_keyword,
+        keyword_only,
+        varargs,
+        varkw,
+    } = &method.parameters;
+    let operands: Vec<_> = positional_only
+        .iter()
+        .chain(positional_or_keyword)
+        .collect();
+    let operands_fit = if method.name == "__invert__" {
+        operands.is_empty()
+    } else {
+        matches!(operands.as_slice(), [other] if is_own(class_name, &other.type_info))
+    };
+    method.r#type == MethodType::Instance
+        && !method.is_async
+        && operands_fit
+        && keyword_only.is_empty()
+        && varargs.is_none()
+        && varkw.is_none()
+        && is_own(class_name, &method.r#return)
+}
+
+/// Reason why `class` cannot be rendered as `enum.Flag`, if any
+fn incompatibility(class: &ClassDef) -> Option<String> {
+    if let Some(base) = class.bases.first() {
+        return Some(format!("it extends `{}`", base.name));
+    }
+    BINARY_OPERATORS
+        .into_iter()
+        .chain(["__invert__"])
+        .find(|name| {
+            class.methods.get(*name).is_some_and(|overloads| {
+                overloads
+                    .iter()
+                    .any(|method| !fits_flag(&class.name, method))
+            })
+        })
+        .map(|name| {
+            format!(
+                "`{name}` does not take and return `{}` as `enum.Flag` does",
+                class.name
+            )
+        })
+}
+
+/// Turn the class attributes of `class` typed as itself into the members of `enum.Flag`,
+/// and drop the operators `enum.Flag` defines
+fn into_flag(class: &mut ClassDef, value: impl Fn(&str) -> Option<&'static str>) {
+    for name in BINARY_OPERATORS.into_iter().chain(["__invert__"]) {
+        class.methods.shift_remove(name);
+    }
+    let (members, attrs): (Vec<_>, Vec<_>) = std::mem::take(&mut class.attrs)
+        .into_iter()
+        .partition(|attr| attr.is_classattr && is_own(&class.name, &attr.r#type));
+    class.attrs = attrs;
+    let members = members
+        .into_iter()
+        .map(|member| MemberDef {
+            default: value(member.name).map(str::to_string),
+            ..member
+        })
+        .collect();
+    class.flag = Some(FlagDef { members });
+    class
+        .bases
+        .push(TypeInfo::with_module("enum.Flag", "enum".into()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_type::ModuleRef;
+
+    fn method_def(name: &'static str, parameters: &[TypeInfo], r#return: TypeInfo) -> MethodDef {
+        let mut params = Parameters::new();
+        params.positional_or_keyword = parameters
+            .iter()
+            .map(|type_info| {
+                Parameter::new(
+                    "other",
+                    ParameterKind::PositionalOrKeyword,
+                    type_info.clone(),
+                )
+            })
+            .collect();
+        MethodDef::new(name, MethodType::Instance, params, r#return)
+    }
+
+    fn flags() -> TypeInfo {
+        TypeInfo::locally_defined("Flags", ModuleRef::Default)
+    }
+
+    fn constant(name: &'static str, r#type: TypeInfo) -> MemberDef {
+        MemberDef {
+            is_classattr: true,
+            ..MemberDef::new(name, r#type)
+        }
+    }
+
+    /// Render the class `Flags` with the operator `__or__` taking `other`, and return the stub of its module
+    fn render(other: TypeInfo) -> String {
+        let mut class = ClassDef::new("Flags");
+        class.attrs = vec![
+            constant("READ", flags()),
+            constant("WRITE", flags()),
+            constant("BITS", TypeInfo::builtin("int")),
+        ];
+        class.add_method(method_def("__or__", &[other], flags()));
+        class.add_method(method_def("__invert__", &[], TypeInfo::self_type()));
+        class.add_method(method_def("bits", &[], TypeInfo::builtin("int")));
+        let id = TypeId::of::<u8>();
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(id, class);
+        let mut modules = BTreeMap::from([("pkg".to_string(), module)]);
+        let values = HashMap::from([((id, "READ"), "1")]);
+        render_flag_enums(&mut modules, &BTreeSet::from([id]), &values);
+        modules["pkg"].to_string()
+    }
+
+    #[test]
+    fn flag_members_and_operators() {
+        let rendered = render(flags());
+        assert!(rendered.contains("import enum\n"), "{rendered}");
+        assert!(
+            rendered.contains(
+                "class Flags(enum.Flag):
+    READ = 1
+    WRITE = ...
+    BITS: typing.Final[builtins.int] = ...
+    def bits(self) -> builtins.int: ...
+"
+            ),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("__or__"), "{rendered}");
+        assert!(!rendered.contains("__invert__"), "{rendered}");
+    }
+
+    #[test]
+    fn incompatible_operator_keeps_plain_class() {
+        let rendered = render(TypeInfo::builtin("int"));
+        assert!(rendered.contains("class Flags:"), "{rendered}");
+        assert!(
+            rendered.contains("READ: typing.Final[Flags] = ..."),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("def __or__(self, other: builtins.int) -> Flags: ..."),
+            "{rendered}"
+        );
+    }
+}
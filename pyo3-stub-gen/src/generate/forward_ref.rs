@@ -0,0 +1,367 @@
+//! References to classes defined later in the same module, e.g. a method of `A` returning `B` while `B` is rendered after `A`.
+//!
+//! Stub files allow such forward references, but some tools evaluate the annotations in order and reject them.
+//! They are resolved either by defining the referenced classes first, or by quoting the annotations, e.g. `"B"`.
+//! Bases cannot be quoted, so reordering always puts them first.
+
+use crate::{
+    generate::*,
+    stub_type::{ImportRef, ModuleRef},
+    TypeInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::TypeId,
+    collections::{BTreeSet, HashMap},
+};
+
+/// How references to classes defined later in the same module are handled,
+/// set by `forward-references` in `[tool.pyo3-stub-gen]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ForwardReferences {
+    /// Keep them unquoted in the order sorted by name, which stub files allow
+    #[default]
+    Keep,
+    /// Define the referenced classes first, and quote the references which cannot be ordered due to a cycle
+    Reorder,
+    /// Keep the order sorted by name, and quote the annotations referencing a later class
+    Quote,
+}
+
+impl StubInfo {
+    /// Resolve the references to classes defined later in the same module by `strategy`
+    pub(crate) fn resolve_forward_references(&mut self, strategy: ForwardReferences) {
+        if strategy == ForwardReferences::Keep {
+            return;
+        }
+        for module in self.modules.values_mut() {
+            if strategy == ForwardReferences::Reorder {
+                module.class_order = module.topological_order();
+            }
+            module.quote_forward_references();
+        }
+    }
+}
+
+/// Local classes and enums referenced by a class or an enum
+#[derive(Default)]
+struct References {
+    /// Referenced by the bases, which must be defined first
+    bases: BTreeSet<TypeId>,
+    /// Referenced by the annotations, which can be quoted instead
+    annotations: BTreeSet<TypeId>,
+}
+
+impl Module {
+    /// Whether `module` refers to this module
+    fn is_local(&self, module: &ModuleRef) -> bool {
+        module.get().unwrap_or(&self.default_module_name) == self.name
+    }
+
+    /// Names of the classes and enums of this module, which annotations reference
+    fn local_names(&self) -> HashMap<String, TypeId> {
+        let classes = self
+            .class
+            .iter()
+            .map(|(id, class)| (class.name.to_string(), *id));
+        let enums = self
+            .enum_
+            .iter()
+            .map(|(id, enum_)| (enum_.name.to_string(), *id));
+        classes.chain(enums).collect()
+    }
+
+    /// Local classes and enums referenced by `ty`
+    fn referenced_ids(&self, names: &HashMap<String, TypeId>, ty: &TypeInfo) -> Vec<TypeId> {
+        ty.import
+            .iter()
+            .filter_map(|import| match import {
+                ImportRef::Type(type_ref) if self.is_local(&type_ref.module) => {
+                    names.get(type_ref.name.as_str()).copied()
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Local classes and enums referenced by each class and enum, except by themselves
+    fn references(&mut self) -> HashMap<TypeId, References> {
+        let names = self.local_names();
+        let mut references: HashMap<TypeId, References> = HashMap::new();
+        let mut classes = std::mem::take(&mut self.class);
+        for (id, class) in &mut classes {
+            let mut refs = References::default();
+            for base in class.nested_bases() {
+                refs.bases.extend(self.referenced_ids(&names, base));
+            }
+            class.for_each_annotation_mut(&mut |ty| {
+                refs.annotations.extend(self.referenced_ids(&names, ty))
+            });
+            references.insert(*id, refs);
+        }
+        self.class = classes;
+        let mut enums = std::mem::take(&mut self.enum_);
+        for (id, enum_) in &mut enums {
+            let mut refs = References::default();
+            enum_.for_each_annotation_mut(&mut |ty| {
+                refs.annotations.extend(self.referenced_ids(&names, ty))
+            });
+            references.insert(*id, refs);
+        }
+        self.enum_ = enums;
+        for (id, refs) in &mut references {
+            refs.bases.remove(id);
+            refs.annotations.remove(id);
+        }
+        references
+    }
+
+    /// Classes and enums ordered so that each comes after the ones it references,
+    /// keeping the rendered order otherwise.
+    ///
+    /// In a cycle, the first class in the rendered order whose bases are defined comes first,
+    /// and its references to the others are left to be quoted.
+    fn topological_order(&mut self) -> Vec<TypeId> {
+        let references = self.references();
+        let mut remaining = self.rendered_order();
+        let mut placed = BTreeSet::new();
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let bases_placed = |id: &TypeId| references[id].bases.is_subset(&placed);
+            let next = remaining
+                .iter()
+                .position(|id| bases_placed(id) && references[id].annotations.is_subset(&placed))
+                .or_else(|| remaining.iter().position(bases_placed))
+                .unwrap_or(0);
+            let id = remaining.remove(next);
+            placed.insert(id);
+            order.push(id);
+        }
+        order
+    }
+
+    /// Quote the annotations referencing a class or an enum rendered after the one they belong to
+    fn quote_forward_references(&mut self) {
+        let names = self.local_names();
+        let position: HashMap<TypeId, usize> = self
+            .rendered_order()
+            .into_iter()
+            .enumerate()
+            .map(|(k, id)| (id, k))
+            .collect();
+        let quote = |module: &Module, id: &TypeId, ty: &mut TypeInfo| {
+            let forward = module
+                .referenced_ids(&names, ty)
+                .iter()
+                .any(|referenced| position[referenced] > position[id]);
+            if forward {
+                quote_annotation(ty);
+            }
+        };
+        let mut classes = std::mem::take(&mut self.class);
+        for (id, class) in &mut classes {
+            class.for_each_annotation_mut(&mut |ty| quote(self, id, ty));
+        }
+        self.class = classes;
+        let mut enums = std::mem::take(&mut self.enum_);
+        for (id, enum_) in &mut enums {
+            enum_.for_each_annotation_mut(&mut |ty| quote(self, id, ty));
+        }
+        self.enum_ = enums;
+    }
+}
+
+/// Render `ty` as a string, e.g. `"B | None"`, with single quotes if it contains a double quote
+fn quote_annotation(ty: &mut TypeInfo) {
+    if ty.name.starts_with(['"', '\'']) {
+        return;
+    }
+    ty.name = if ty.name.contains('"') {
+        format!("'{}'", ty.name)
+    } else {
+        format!("\"{}\"", ty.name)
+    };
+}
+
+impl ClassDef {
+    /// Bases of this class and its nested classes
+    fn nested_bases(&self) -> Vec<&TypeInfo> {
+        let nested = self.classes.iter().flat_map(ClassDef::nested_bases);
+        self.bases.iter().chain(nested).collect()
+    }
+
+    /// Visit the annotations of the members and methods of this class and its nested classes
+    fn for_each_annotation_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        let fields = self
+            .dataclass
+            .iter_mut()
+            .flat_map(|dataclass| &mut dataclass.fields);
+        for member in self.attrs.iter_mut().chain(fields) {
+            f(&mut member.r#type);
+        }
+        for (getter, setter) in self.getter_setters.values_mut() {
+            for member in getter.iter_mut().chain(setter.iter_mut()) {
+                f(&mut member.r#type);
+            }
+        }
+        for method in self.methods.values_mut().flatten() {
+            method.for_each_annotation_mut(f);
+        }
+        for class in &mut self.classes {
+            class.for_each_annotation_mut(f);
+        }
+    }
+}
+
+impl EnumDef {
+    fn for_each_annotation_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        for member in self
+            .attrs
+            .iter_mut()
+            .chain(self.getters.iter_mut())
+            .chain(self.setters.iter_mut())
+        {
+            f(&mut member.r#type);
+        }
+        for method in &mut self.methods {
+            method.for_each_annotation_mut(f);
+        }
+    }
+}
+
+impl MethodDef {
+    fn for_each_annotation_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        f(&mut self.r#return);
+        for parameter in self.parameters.iter_entries_mut() {
+            f(&mut parameter.type_info);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::MethodType;
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    /// Class `name` with a method `peer` returning `peer`
+    fn class(name: &'static str, peer: TypeInfo) -> ClassDef {
+        let mut class = ClassDef::new(name);
+        class.add_method(MethodDef::new(
+            "peer",
+            MethodType::Instance,
+            Parameters::new(),
+            peer,
+        ));
+        class
+    }
+
+    fn local(name: &str) -> TypeInfo {
+        TypeInfo::locally_defined(name, ModuleRef::Default)
+    }
+
+    fn render(classes: Vec<ClassDef>, strategy: ForwardReferences) -> String {
+        let mut module = Module::new("pkg", "pkg");
+        let ids = [TypeId::of::<u8>(), TypeId::of::<u16>(), TypeId::of::<u32>()];
+        module.class = ids.into_iter().zip(classes).collect();
+        let mut info = StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), module)]),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        };
+        info.resolve_forward_references(strategy);
+        info.modules["pkg"].to_string()
+    }
+
+    /// Names of the classes in the order they are rendered
+    fn class_names(rendered: &str) -> Vec<&str> {
+        rendered
+            .lines()
+            .filter_map(|line| line.strip_prefix("class "))
+            .filter_map(|line| line.split([':', '(']).next())
+            .collect()
+    }
+
+    #[test]
+    fn two_class_cycle() {
+        let cycle = || {
+            vec![
+                class("A", local("B")),
+                class("B", local("A") | TypeInfo::none()),
+            ]
+        };
+
+        let kept = render(cycle(), ForwardReferences::Keep);
+        assert_eq!(class_names(&kept), ["A", "B"]);
+        assert!(kept.contains("def peer(self) -> B: ..."), "{kept}");
+
+        for strategy in [ForwardReferences::Quote, ForwardReferences::Reorder] {
+            let rendered = render(cycle(), strategy);
+            assert_eq!(class_names(&rendered), ["A", "B"]);
+            assert!(
+                rendered.contains("def peer(self) -> \"B\": ..."),
+                "{rendered}"
+            );
+            assert!(
+                rendered.contains("def peer(self) -> A | None: ..."),
+                "{rendered}"
+            );
+        }
+    }
+
+    #[test]
+    fn reorder_acyclic_references() {
+        let classes = || {
+            vec![
+                class("A", local("B")),
+                class("B", local("C")),
+                class("C", TypeInfo::none()),
+            ]
+        };
+        let reordered = render(classes(), ForwardReferences::Reorder);
+        assert_eq!(class_names(&reordered), ["C", "B", "A"]);
+        assert!(!reordered.contains("-> \""), "{reordered}");
+
+        let quoted = render(classes(), ForwardReferences::Quote);
+        assert_eq!(class_names(&quoted), ["A", "B", "C"]);
+        assert!(quoted.contains("def peer(self) -> \"B\": ..."), "{quoted}");
+        assert!(quoted.contains("def peer(self) -> \"C\": ..."), "{quoted}");
+    }
+
+    #[test]
+    fn bases_come_first() {
+        // `Root` references its subclass, which cannot be defined before its base
+        let mut derived = class("Derived", TypeInfo::none());
+        derived.bases.push(local("Root"));
+        let rendered = render(
+            vec![derived, class("Root", TypeInfo::list(local("Derived")))],
+            ForwardReferences::Reorder,
+        );
+        assert_eq!(class_names(&rendered), ["Root", "Derived"]);
+        assert!(
+            rendered.contains("def peer(self) -> \"builtins.list[Derived]\": ..."),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn quote_annotations_with_literals() {
+        let mut ty = TypeInfo::builtin("str");
+        ty.name = "typing.Literal[\"a\"] | B".to_string();
+        quote_annotation(&mut ty);
+        assert_eq!(ty.name, "'typing.Literal[\"a\"] | B'");
+        quote_annotation(&mut ty);
+        assert_eq!(ty.name, "'typing.Literal[\"a\"] | B'");
+    }
+
+    #[test]
+    fn forward_references_in_pyproject() {
+        use crate::pyproject::StubGen;
+        let stub_gen: StubGen = toml::from_str(r#"forward-references = "quote""#).unwrap();
+        assert_eq!(stub_gen.forward_references, Some(ForwardReferences::Quote));
+    }
+}
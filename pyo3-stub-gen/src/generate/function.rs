@@ -1,18 +1,22 @@
 use crate::stub_type::ImportRef;
-use crate::{generate::*, rule_name::RuleName, type_info::*, TypeInfo};
-use itertools::Itertools;
-use std::fmt;
+use crate::{generate::*, type_info::*, TypeInfo};
+use std::{borrow::Cow, fmt};
 
 /// Definition of a Python function.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct FunctionDef {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     pub parameters: Parameters,
     pub r#return: TypeInfo,
     pub doc: &'static str,
     pub is_async: bool,
     pub deprecated: Option<DeprecatedInfo>,
-    pub type_ignored: Option<IgnoreTarget>,
+    pub type_ignored: Option<TypeIgnore>,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+    /// Source location of the Rust item, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
 }
 
 impl Import for FunctionDef {
@@ -20,23 +24,46 @@ impl Import for FunctionDef {
         let mut import = self.r#return.import.clone();
         import.extend(self.parameters.import());
         // Add typing_extensions import if deprecated
-        if self.deprecated.is_some() {
+        if deprecated_decorator(&self.deprecated, DeprecatedTarget::Function).is_some() {
             import.insert("typing_extensions".into());
         }
         import
     }
 }
 
+impl FunctionDef {
+    /// Synchronous function without a docstring
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        parameters: Parameters,
+        r#return: TypeInfo,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            parameters,
+            r#return,
+            doc: "",
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            qualname: QualName::default(),
+            source_location: None,
+        }
+    }
+}
+
 impl From<&PyFunctionInfo> for FunctionDef {
     fn from(info: &PyFunctionInfo) -> Self {
         Self {
-            name: info.name,
+            name: info.name.into(),
             parameters: Parameters::from_infos(info.parameters),
             r#return: (info.r#return)(),
             doc: info.doc,
             is_async: info.is_async,
             deprecated: info.deprecated.clone(),
-            type_ignored: info.type_ignored,
+            type_ignored: info.type_ignored.map(Into::into),
+            qualname: QualName::default(),
+            source_location: info.source_location,
         }
     }
 }
@@ -44,7 +71,8 @@ impl From<&PyFunctionInfo> for FunctionDef {
 impl fmt::Display for FunctionDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Add deprecated decorator if present
-        if let Some(deprecated) = &self.deprecated {
+        if let Some(deprecated) = deprecated_decorator(&self.deprecated, DeprecatedTarget::Function)
+        {
             writeln!(f, "{deprecated}")?;
         }
 
@@ -55,36 +83,23 @@ impl fmt::Display for FunctionDef {
             self.name, self.parameters, self.r#return
         )?;
 
-        // Calculate type: ignore comment once
-        let type_ignore_comment = if let Some(target) = &self.type_ignored {
-            match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
-                    let rules_str = rules
-                        .iter()
-                        .map(|r| {
-                            let result = r.parse::<RuleName>().unwrap();
-                            if let RuleName::Custom(custom) = &result {
-                                log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
-                            }
-                            result
-                        })
-                        .join(",");
-                    Some(format!("  # type: ignore[{rules_str}]"))
-                }
-            }
-        } else {
-            None
-        };
+        let type_ignore_comment = type_ignore_comment(
+            &self.type_ignored,
+            &self.qualname,
+            &self.name,
+            self.source_location,
+        );
 
-        let doc = self.doc;
+        let doc = docstring_with_container_defaults(self.doc, &self.parameters);
+        let doc = docstring_with_deprecated_parameters(&doc, &self.parameters);
+        let doc = docstring_with_deprecation(&doc, &self.deprecated, DeprecatedTarget::Function);
         if !doc.is_empty() {
             // Add type: ignore comment for functions with docstrings
             if let Some(comment) = &type_ignore_comment {
                 write!(f, "{comment}")?;
             }
             writeln!(f)?;
-            docstring::write_docstring(f, self.doc, indent())?;
+            docstring::write_docstring(f, &doc, indent())?;
         } else {
             write!(f, " ...")?;
             // Add type: ignore comment for functions without docstrings
@@ -0,0 +1,259 @@
+//! Flattening of base classes marked by `#[gen_stub(hidden_base)]` into their subclasses.
+
+use crate::{
+    generate::*,
+    stub_type::{ImportRef, TypeRef},
+    TypeInfo,
+};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// Remove the classes in `hidden` from `modules`, and copy their members into their subclasses.
+///
+/// A hidden base in the bases of a subclass is replaced by the bases of the hidden class,
+/// and members already defined in the subclass are not copied.
+pub(crate) fn flatten_hidden_bases(
+    modules: &mut BTreeMap<String, Module>,
+    hidden: &BTreeSet<TypeId>,
+    default_module_name: &str,
+) {
+    if hidden.is_empty() {
+        return;
+    }
+    let mut hidden_classes = Vec::new();
+    for (module_name, module) in modules.iter_mut() {
+        for id in hidden {
+            if let Some(class) = module.class.remove(id) {
+                hidden_classes.push((module_name.clone(), class));
+            }
+        }
+    }
+    for module in modules.values_mut() {
+        for class in module.class.values_mut() {
+            // Hidden bases may have hidden bases themselves, which are flattened in later iterations
+            for _ in 0..=hidden_classes.len() {
+                let Some((index, base)) =
+                    class.bases.iter().enumerate().find_map(|(index, base)| {
+                        hidden_classes
+                            .iter()
+                            .find(|(module, hidden)| {
+                                refers_to(base, &hidden.name, module, default_module_name)
+                            })
+                            .map(|(_, hidden)| (index, hidden))
+                    })
+                else {
+                    break;
+                };
+                class.bases.remove(index);
+                for (offset, inherited) in base
+                    .bases
+                    .iter()
+                    .filter(|inherited| !class.bases.contains(inherited))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .enumerate()
+                {
+                    class.bases.insert(index + offset, inherited);
+                }
+                class.inherit_members(base);
+            }
+        }
+    }
+}
+
+/// Whether `type_info` is the class `name` defined in `module`
+pub(crate) fn refers_to(
+    type_info: &TypeInfo,
+    name: &str,
+    module: &str,
+    default_module_name: &str,
+) -> bool {
+    type_info.name == name
+        && type_info.import.iter().any(|import| match import {
+            ImportRef::Type(TypeRef {
+                module: type_module,
+                name: type_name,
+            }) => type_name == name && type_module.get().unwrap_or(default_module_name) == module,
+            ImportRef::Module(_) => false,
+        })
+}
+
+impl ClassDef {
+    /// Whether a method, property, or attribute named `name` is defined
+    fn has_member(&self, name: &str) -> bool {
+        self.methods.contains_key(name)
+            || self.getter_setters.contains_key(name)
+            || self.attrs.iter().any(|attr| attr.name == name)
+    }
+
+    /// Copy the members of `base` which are not overridden in this class.
+    fn inherit_members(&mut self, base: &ClassDef) {
+        let mut inherits_abstract = false;
+        let attrs: Vec<_> = base
+            .attrs
+            .iter()
+            .filter(|attr| !self.has_member(attr.name))
+            .cloned()
+            .collect();
+        let getter_setters: Vec<_> = base
+            .getter_setters
+            .iter()
+            .filter(|(name, _)| !self.has_member(name))
+            .map(|(name, members)| (name.clone(), members.clone()))
+            .collect();
+        let methods: Vec<_> = base
+            .methods
+            .iter()
+            .filter(|(name, _)| !self.has_member(name))
+            .map(|(name, methods)| (name.clone(), methods.clone()))
+            .collect();
+        self.attrs.extend(attrs);
+        for (name, (getter, setter)) in getter_setters {
+            inherits_abstract |= [&getter, &setter]
+                .into_iter()
+                .flatten()
+                .any(|member| member.is_abstract);
+            self.getter_setters.insert(name, (getter, setter));
+        }
+        for (name, methods) in methods {
+            inherits_abstract |= methods.iter().any(|method| method.is_abstract);
+            self.methods.insert(name, methods);
+        }
+        if inherits_abstract {
+            self.mark_abstract();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::MethodType;
+
+    fn class(name: &'static str, bases: Vec<TypeInfo>) -> ClassDef {
+        ClassDef {
+            bases,
+            subclass: true,
+            ..ClassDef::new(name)
+        }
+    }
+
+    fn method(name: &'static str, r#return: TypeInfo) -> (String, Vec<MethodDef>) {
+        (
+            name.to_string(),
+            vec![MethodDef::new(
+                name,
+                MethodType::Instance,
+                Parameters::new(),
+                r#return,
+            )],
+        )
+    }
+
+    fn base_handle() -> TypeInfo {
+        TypeInfo::locally_defined("_BaseHandle", Default::default())
+    }
+
+    fn module() -> Module {
+        let mut base = class("_BaseHandle", Vec::new());
+        base.getter_setters.insert(
+            "fd".to_string(),
+            (
+                Some(MemberDef {
+                    name: "fd",
+                    r#type: TypeInfo::builtin("int"),
+                    doc: "",
+                    default: None,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_abstract: false,
+                    is_classattr: false,
+                    qualname: QualName::default(),
+                }),
+                None,
+            ),
+        );
+        base.methods.extend([
+            method("duplicate", TypeInfo::self_type()),
+            method(
+                "path",
+                TypeInfo::with_module("pathlib.Path", "pathlib".into()),
+            ),
+            method("close", TypeInfo::none()),
+        ]);
+
+        let mut file = class("File", vec![base_handle()]);
+        file.methods
+            .extend([method("close", TypeInfo::builtin("bool"))]);
+        let socket = class("Socket", vec![base_handle()]);
+
+        let mut module = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        module.class.insert(TypeId::of::<u8>(), base);
+        module.class.insert(TypeId::of::<u16>(), file);
+        module.class.insert(TypeId::of::<u32>(), socket);
+        module
+    }
+
+    #[test]
+    fn flatten_into_subclasses() {
+        let mut modules = BTreeMap::from([("pkg".to_string(), module())]);
+        flatten_hidden_bases(&mut modules, &BTreeSet::from([TypeId::of::<u8>()]), "pkg");
+        let module = &modules["pkg"];
+        assert_eq!(module.public_names(), ["File", "Socket"]);
+        assert_eq!(
+            module.to_string(),
+            r#"# This file is automatically generated by pyo3_stub_gen
+# ruff: noqa: E501, F401
+
+import builtins
+import pathlib
+from typing import Self
+
+__all__ = [
+    "File",
+    "Socket",
+]
+
+class File:
+    @property
+    def fd(self) -> builtins.int: ...
+    def close(self) -> builtins.bool: ...
+    def duplicate(self) -> Self: ...
+    def path(self) -> pathlib.Path: ...
+
+class Socket:
+    @property
+    def fd(self) -> builtins.int: ...
+    def duplicate(self) -> Self: ...
+    def path(self) -> pathlib.Path: ...
+    def close(self) -> None: ...
+
+"#
+        );
+    }
+
+    #[test]
+    fn bases_of_hidden_base_are_inherited() {
+        let mut module = module();
+        let base = module.class.get_mut(&TypeId::of::<u8>()).unwrap();
+        base.bases
+            .push(TypeInfo::with_module("os.PathLike", "os".into()));
+        let mut modules = BTreeMap::from([("pkg".to_string(), module)]);
+        flatten_hidden_bases(&mut modules, &BTreeSet::from([TypeId::of::<u8>()]), "pkg");
+        let file = &modules["pkg"].class[&TypeId::of::<u16>()];
+        assert_eq!(
+            file.bases
+                .iter()
+                .map(|b| b.name.as_str())
+                .collect::<Vec<_>>(),
+            ["os.PathLike"]
+        );
+    }
+}
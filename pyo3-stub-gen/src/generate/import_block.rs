@@ -0,0 +1,398 @@
+//! Import statements at the top of a stub file.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Top-level modules of the Python standard library, sorted for binary search.
+///
+/// These are the public names of `sys.stdlib_module_names` of Python 3.11, keeping the modules removed later
+/// for the stubs targeting older versions. A test checks that they cover the Python found on the machine.
+const STDLIB: &[&str] = &[
+    "abc",
+    "aifc",
+    "antigravity",
+    "argparse",
+    "array",
+    "ast",
+    "asynchat",
+    "asyncio",
+    "asyncore",
+    "atexit",
+    "audioop",
+    "base64",
+    "bdb",
+    "binascii",
+    "bisect",
+    "builtins",
+    "bz2",
+    "cProfile",
+    "calendar",
+    "cgi",
+    "cgitb",
+    "chunk",
+    "cmath",
+    "cmd",
+    "code",
+    "codecs",
+    "codeop",
+    "collections",
+    "colorsys",
+    "compileall",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "copyreg",
+    "crypt",
+    "csv",
+    "ctypes",
+    "curses",
+    "dataclasses",
+    "datetime",
+    "dbm",
+    "decimal",
+    "difflib",
+    "dis",
+    "distutils",
+    "doctest",
+    "email",
+    "encodings",
+    "ensurepip",
+    "enum",
+    "errno",
+    "faulthandler",
+    "fcntl",
+    "filecmp",
+    "fileinput",
+    "fnmatch",
+    "fractions",
+    "ftplib",
+    "functools",
+    "gc",
+    "genericpath",
+    "getopt",
+    "getpass",
+    "gettext",
+    "glob",
+    "graphlib",
+    "grp",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "idlelib",
+    "imaplib",
+    "imghdr",
+    "imp",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "keyword",
+    "lib2to3",
+    "linecache",
+    "locale",
+    "logging",
+    "lzma",
+    "mailbox",
+    "mailcap",
+    "marshal",
+    "math",
+    "mimetypes",
+    "mmap",
+    "modulefinder",
+    "msilib",
+    "msvcrt",
+    "multiprocessing",
+    "netrc",
+    "nis",
+    "nntplib",
+    "nt",
+    "ntpath",
+    "nturl2path",
+    "numbers",
+    "opcode",
+    "operator",
+    "optparse",
+    "os",
+    "ossaudiodev",
+    "pathlib",
+    "pdb",
+    "pickle",
+    "pickletools",
+    "pipes",
+    "pkgutil",
+    "platform",
+    "plistlib",
+    "poplib",
+    "posix",
+    "posixpath",
+    "pprint",
+    "profile",
+    "pstats",
+    "pty",
+    "pwd",
+    "py_compile",
+    "pyclbr",
+    "pydoc",
+    "pydoc_data",
+    "pyexpat",
+    "queue",
+    "quopri",
+    "random",
+    "re",
+    "readline",
+    "reprlib",
+    "resource",
+    "rlcompleter",
+    "runpy",
+    "sched",
+    "secrets",
+    "select",
+    "selectors",
+    "shelve",
+    "shlex",
+    "shutil",
+    "signal",
+    "site",
+    "smtpd",
+    "smtplib",
+    "sndhdr",
+    "socket",
+    "socketserver",
+    "spwd",
+    "sqlite3",
+    "sre_compile",
+    "sre_constants",
+    "sre_parse",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "stringprep",
+    "struct",
+    "subprocess",
+    "sunau",
+    "symtable",
+    "sys",
+    "sysconfig",
+    "syslog",
+    "tabnanny",
+    "tarfile",
+    "telnetlib",
+    "tempfile",
+    "termios",
+    "textwrap",
+    "this",
+    "threading",
+    "time",
+    "timeit",
+    "tkinter",
+    "token",
+    "tokenize",
+    "tomllib",
+    "trace",
+    "traceback",
+    "tracemalloc",
+    "tty",
+    "turtle",
+    "turtledemo",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uu",
+    "uuid",
+    "venv",
+    "warnings",
+    "wave",
+    "weakref",
+    "webbrowser",
+    "winreg",
+    "winsound",
+    "wsgiref",
+    "xdrlib",
+    "xml",
+    "xmlrpc",
+    "zipapp",
+    "zipfile",
+    "zipimport",
+    "zlib",
+    "zoneinfo",
+];
+
+/// Group of imports, rendered in this order and separated by blank lines as isort does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Section {
+    Stdlib,
+    ThirdParty,
+    /// Modules of the package of the stub file, and relative imports
+    Local,
+}
+
+/// Import statements of a stub file, deduplicated and sorted independently of the order they are added in.
+///
+/// Plain `import x` statements come before `from x import y` in each section,
+/// and `from x import *` re-exporting the public names of `x` comes last.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportBlock {
+    /// Top-level package of the stub file, whose modules are local
+    package: String,
+    modules: BTreeSet<String>,
+    /// Names imported from each module
+    names: BTreeMap<String, BTreeSet<String>>,
+    /// Modules all of whose public names are re-exported by `from module import *`
+    star: BTreeSet<String>,
+}
+
+impl ImportBlock {
+    /// Empty block of a stub file of the module `module_name`
+    pub fn new(module_name: &str) -> Self {
+        Self {
+            package: top_level(module_name).to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Add `import module`
+    pub fn import(&mut self, module: &str) {
+        self.modules.insert(module.to_string());
+    }
+
+    /// Add `from module import name`, where `module` may be relative, e.g. `.`
+    pub fn import_from(&mut self, module: &str, name: &str) {
+        self.names
+            .entry(module.to_string())
+            .or_default()
+            .insert(name.to_string());
+    }
+
+    /// Add `from module import *`, which re-exports the public names of `module` from the stub file
+    pub fn reexport_all(&mut self, module: &str) {
+        self.star.insert(module.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.names.is_empty() && self.star.is_empty()
+    }
+
+    fn section(&self, module: &str) -> Section {
+        let top_level = top_level(module);
+        if module.starts_with('.') || top_level == self.package {
+            Section::Local
+        } else if STDLIB.binary_search(&top_level).is_ok() {
+            Section::Stdlib
+        } else {
+            Section::ThirdParty
+        }
+    }
+}
+
+fn top_level(module: &str) -> &str {
+    module.split('.').next().unwrap_or(module)
+}
+
+impl fmt::Display for ImportBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sections: BTreeMap<Section, Vec<String>> = BTreeMap::new();
+        for module in &self.modules {
+            sections
+                .entry(self.section(module))
+                .or_default()
+                .push(format!("import {module}"));
+        }
+        // Relative imports come after absolute ones
+        let mut names: Vec<_> = self.names.iter().collect();
+        names.sort_by_key(|(module, _)| (module.starts_with('.'), module.as_str()));
+        for (module, names) in names {
+            let names = names.iter().cloned().collect::<Vec<_>>().join(", ");
+            sections
+                .entry(self.section(module))
+                .or_default()
+                .push(format!("from {module} import {names}"));
+        }
+        for module in &self.star {
+            sections
+                .entry(self.section(module))
+                .or_default()
+                .push(format!("from {module} import *"));
+        }
+        for (index, lines) in sections.values().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for line in lines {
+                writeln!(f, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let mut block = ImportBlock::new("pkg._core");
+        block.import_from(".", "sub");
+        block.import_from("pkg._io", "Reader");
+        block.import("numpy");
+        block.import_from("typing_extensions", "Self");
+        block.import("typing");
+        block.import_from("pkg._io", "Writer");
+        block.import("builtins");
+        block.import("collections.abc");
+        block.import("pkg");
+        block.import("typing");
+        assert_eq!(
+            block.to_string(),
+            "import builtins\nimport collections.abc\nimport typing\n\nimport numpy\nfrom typing_extensions import Self\n\nimport pkg\nfrom pkg._io import Reader, Writer\nfrom . import sub\n"
+        );
+    }
+
+    #[test]
+    fn reexport_all() {
+        let mut block = ImportBlock::new("pkg");
+        block.reexport_all("pkg._core");
+        block.import_from("pkg._core", "run");
+        block.import_from("pkg._core", "Engine");
+        block.import_from("pkg._core", "run");
+        assert_eq!(
+            block.to_string(),
+            "from pkg._core import Engine, run\nfrom pkg._core import *\n"
+        );
+    }
+
+    #[test]
+    fn sorted_stdlib() {
+        assert!(STDLIB.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn stdlib_covers_python() {
+        let output = match std::process::Command::new("python3")
+            .args(["-c", "import sys; print(*sys.stdlib_module_names)"])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            // `sys.stdlib_module_names` is missing before Python 3.10
+            _ => return,
+        };
+        let names = String::from_utf8(output.stdout).unwrap();
+        let missing: Vec<_> = names
+            .split_whitespace()
+            .filter(|name| !name.starts_with('_') && STDLIB.binary_search(name).is_err())
+            .collect();
+        assert!(missing.is_empty(), "Add {missing:?} to `STDLIB`");
+    }
+}
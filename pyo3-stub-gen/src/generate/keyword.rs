@@ -0,0 +1,244 @@
+//! Functions, methods, and parameters named after hard Python keywords.
+//!
+//! PyO3 exposes a Rust parameter `r#in` as `in`, which works at runtime when passed positionally,
+//! but `def f(in: int)` is a syntax error in the stub. Soft keywords like `type` and `match` are valid names.
+
+use crate::generate::{module_rename::KEYWORDS, transform::leak, *};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// How parameters named after a hard Python keyword are handled,
+/// set by `keyword-parameters` in `[tool.pyo3-stub-gen]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum KeywordParameters {
+    /// Fail the generation
+    #[default]
+    Error,
+    /// Append `_` to the name in the stub, e.g. `class_`, and log a warning
+    Rename,
+}
+
+impl StubInfo {
+    /// Fail if a function, a method, or an attribute is named after a hard Python keyword,
+    /// and handle such parameters by `handling`
+    pub(crate) fn check_keywords(&mut self, handling: KeywordParameters) -> Result<()> {
+        let mut errors = Vec::new();
+        for module in self.modules.values_mut() {
+            module.for_each_signature_mut(&mut |qualname, name, parameters| {
+                if KEYWORDS.contains(&name) {
+                    errors.push(format!(
+                        "`{qualname}` is named after the Python keyword `{name}`; rename it by `#[pyo3(name = \"...\")]`"
+                    ));
+                }
+                for parameter in parameters.iter_entries_mut() {
+                    if !KEYWORDS.contains(&parameter.name) {
+                        continue;
+                    }
+                    match handling {
+                        KeywordParameters::Error => errors.push(format!(
+                            "Parameter `{}` of `{qualname}` is named after a Python keyword; rename it, or set `keyword-parameters = \"rename\"`",
+                            parameter.name
+                        )),
+                        KeywordParameters::Rename => {
+                            let renamed = format!("{}_", parameter.name);
+                            log::warn!(
+                                "Parameter `{}` of `{qualname}` is named after a Python keyword, and rendered as `{renamed}`",
+                                parameter.name
+                            );
+                            parameter.name = leak(renamed);
+                        }
+                    }
+                }
+            });
+            module.for_each_member(&mut |member| {
+                let error = format!(
+                    "`{}` is named after the Python keyword `{}`; rename it by `#[pyo3(name = \"...\")]`",
+                    member.qualname, member.name
+                );
+                // A getter and its setter share the name
+                if KEYWORDS.contains(&member.name) && !errors.contains(&error) {
+                    errors.push(error);
+                }
+            });
+        }
+        if !errors.is_empty() {
+            let list = errors
+                .iter()
+                .map(|error| format!("  - {error}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            bail!("Python keywords used as names in the generated stubs:\n{list}");
+        }
+        Ok(())
+    }
+}
+
+impl Module {
+    /// Visit the qualified name, the name, and the parameters of every function and method
+    fn for_each_signature_mut(&mut self, f: &mut impl FnMut(&QualName, &str, &mut Parameters)) {
+        for function in self.function.values_mut().flatten() {
+            f(&function.qualname, &function.name, &mut function.parameters);
+        }
+        for class in self.class.values_mut() {
+            class.for_each_signature_mut(f);
+        }
+        for enum_ in self.enum_.values_mut() {
+            for method in &mut enum_.methods {
+                f(&method.qualname, &method.name, &mut method.parameters);
+            }
+        }
+    }
+
+    /// Visit every attribute, getter, and setter of the classes and enums
+    fn for_each_member(&self, f: &mut impl FnMut(&MemberDef)) {
+        for class in self.class.values() {
+            class.for_each_member(f);
+        }
+        for enum_ in self.enum_.values() {
+            enum_
+                .attrs
+                .iter()
+                .chain(&enum_.getters)
+                .chain(&enum_.setters)
+                .for_each(&mut *f);
+        }
+    }
+}
+
+impl ClassDef {
+    fn for_each_signature_mut(&mut self, f: &mut impl FnMut(&QualName, &str, &mut Parameters)) {
+        for method in self.methods.values_mut().flatten() {
+            f(&method.qualname, &method.name, &mut method.parameters);
+        }
+        for class in &mut self.classes {
+            class.for_each_signature_mut(f);
+        }
+    }
+
+    fn for_each_member(&self, f: &mut impl FnMut(&MemberDef)) {
+        let fields = self
+            .dataclass
+            .iter()
+            .flat_map(|dataclass| &dataclass.fields);
+        let properties = self
+            .getter_setters
+            .values()
+            .flat_map(|(getter, setter)| getter.iter().chain(setter));
+        self.attrs
+            .iter()
+            .chain(fields)
+            .chain(properties)
+            .for_each(&mut *f);
+        for class in &self.classes {
+            class.for_each_member(f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{type_info::ParameterKind, TypeInfo};
+    use std::{any::TypeId, collections::BTreeMap, path::PathBuf};
+
+    fn stub_info(function: &'static str, parameter: &'static str) -> StubInfo {
+        let mut module = Module::new("pkg", "pkg");
+        module.function.insert(
+            function.into(),
+            vec![FunctionDef::new(
+                function,
+                [Parameter::new(
+                    parameter,
+                    ParameterKind::PositionalOrKeyword,
+                    TypeInfo::builtin("str"),
+                )]
+                .into_iter()
+                .collect(),
+                TypeInfo::none(),
+            )],
+        );
+        module.assign_qualnames();
+        StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), module)]),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn soft_keywords_are_kept() -> Result<()> {
+        let mut info = stub_info("match", "type");
+        info.check_keywords(KeywordParameters::Error)?;
+        assert!(info.modules["pkg"]
+            .to_string()
+            .contains("def match(type: builtins.str) -> None: ..."));
+        Ok(())
+    }
+
+    #[test]
+    fn hard_keyword_parameter() -> Result<()> {
+        let err = stub_info("load", "from")
+            .check_keywords(KeywordParameters::Error)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Python keywords used as names in the generated stubs:\n  - Parameter `from` of `pkg.load` is named after a Python keyword; rename it, or set `keyword-parameters = \"rename\"`"
+        );
+
+        let mut info = stub_info("load", "from");
+        info.check_keywords(KeywordParameters::Rename)?;
+        assert!(info.modules["pkg"]
+            .to_string()
+            .contains("def load(from_: builtins.str) -> None: ..."));
+        Ok(())
+    }
+
+    #[test]
+    fn hard_keyword_function() {
+        let err = stub_info("import", "path")
+            .check_keywords(KeywordParameters::Rename)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`pkg.import` is named after the Python keyword `import`"));
+    }
+
+    #[test]
+    fn hard_keyword_getter() {
+        let mut info = stub_info("load", "path");
+        let module = info.modules.get_mut("pkg").unwrap();
+        let mut class = ClassDef::new("Node");
+        let member = || MemberDef {
+            name: "class",
+            r#type: TypeInfo::builtin("str"),
+            doc: "",
+            default: None,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            is_classattr: false,
+            qualname: QualName::default(),
+        };
+        class
+            .getter_setters
+            .insert("class".to_string(), (Some(member()), Some(member())));
+        module.class.insert(TypeId::of::<()>(), class);
+        module.assign_qualnames();
+        let err = info.check_keywords(KeywordParameters::Rename).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Python keywords used as names in the generated stubs:\n  - `pkg.Node.class` is named after the Python keyword `class`; rename it by `#[pyo3(name = \"...\")]`"
+        );
+    }
+
+    #[test]
+    fn keyword_parameters_in_pyproject() {
+        use crate::pyproject::StubGen;
+        let stub_gen: StubGen = toml::from_str(r#"keyword-parameters = "rename""#).unwrap();
+        assert_eq!(stub_gen.keyword_parameters, Some(KeywordParameters::Rename));
+    }
+}
@@ -0,0 +1,124 @@
+//! Placement of stub files, set by `stub-layout` and `merge` in `[tool.pyo3-stub-gen]`.
+//!
+//! With `merge = true`, the generated stub is written between [BEGIN_MARKER] and [END_MARKER],
+//! so that handwritten content around the block, e.g. extra overloads, survives regeneration.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Line starting the block of generated content in a merged stub file
+pub(crate) const BEGIN_MARKER: &str = "# BEGIN pyo3-stub-gen";
+/// Line ending the block of generated content in a merged stub file
+pub(crate) const END_MARKER: &str = "# END pyo3-stub-gen";
+
+/// Paths of the stub files of modules
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum StubLayout {
+    /// `mypkg/_core.pyi`, or `mypkg/_core/__init__.pyi` for modules with submodules
+    #[default]
+    Module,
+    /// `mypkg/_core/__init__.pyi` for every module
+    Package,
+}
+
+/// Replace the marker block of `existing` by `generated`.
+///
+/// Without an existing file, the whole file becomes the block,
+/// and the block is appended to an existing file without markers.
+/// A file with only one of the markers is an error, since where the handwritten content ends is unknown.
+pub(crate) fn merge_block(existing: Option<&str>, generated: &str) -> Result<String> {
+    let block = format!("{BEGIN_MARKER}\n{generated}{END_MARKER}\n");
+    let Some(existing) = existing else {
+        return Ok(block);
+    };
+    let mut offset = 0;
+    let mut begin = None;
+    for line in existing.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        match line.trim() {
+            BEGIN_MARKER if begin.is_none() => begin = Some(start),
+            END_MARKER => match begin {
+                Some(begin) => {
+                    return Ok(format!(
+                        "{}{block}{}",
+                        &existing[..begin],
+                        &existing[offset..]
+                    ))
+                }
+                None => bail!("`{END_MARKER}` appears without `{BEGIN_MARKER}` before it"),
+            },
+            _ => {}
+        }
+    }
+    if begin.is_some() {
+        bail!("`{BEGIN_MARKER}` is not followed by `{END_MARKER}`");
+    }
+    if existing.trim().is_empty() {
+        return Ok(block);
+    }
+    Ok(format!("{}\n\n{block}", existing.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_handwritten_content() {
+        let existing = "import typing\n\n# BEGIN pyo3-stub-gen\ndef old() -> None: ...\n# END pyo3-stub-gen\n\n@typing.overload\ndef extra(x: int) -> int: ...\n";
+        assert_eq!(
+            merge_block(Some(existing), "def new() -> None: ...\n").unwrap(),
+            "import typing\n\n# BEGIN pyo3-stub-gen\ndef new() -> None: ...\n# END pyo3-stub-gen\n\n@typing.overload\ndef extra(x: int) -> int: ...\n"
+        );
+    }
+
+    #[test]
+    fn without_block() -> Result<()> {
+        let generated = "def new() -> None: ...\n";
+        let block = "# BEGIN pyo3-stub-gen\ndef new() -> None: ...\n# END pyo3-stub-gen\n";
+        assert_eq!(merge_block(None, generated)?, block);
+        assert_eq!(merge_block(Some("\n"), generated)?, block);
+        // The handwritten content of a file without markers is kept
+        assert_eq!(
+            merge_block(Some("def old() -> None: ...\n"), generated)?,
+            format!("def old() -> None: ...\n\n{block}")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn incomplete_block() {
+        let err = merge_block(
+            Some("# BEGIN pyo3-stub-gen\ndef old() -> None: ...\n"),
+            "def new() -> None: ...\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`# BEGIN pyo3-stub-gen` is not followed by `# END pyo3-stub-gen`"
+        );
+        let err = merge_block(
+            Some("def old() -> None: ...\n# END pyo3-stub-gen\n"),
+            "def new() -> None: ...\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`# END pyo3-stub-gen` appears without `# BEGIN pyo3-stub-gen` before it"
+        );
+    }
+
+    #[test]
+    fn in_pyproject() {
+        use crate::pyproject::StubGen;
+        for key in ["stub-layout", "stub_layout"] {
+            let stub_gen: StubGen =
+                toml::from_str(&format!("{key} = \"package\"\nmerge = true")).unwrap();
+            assert_eq!(stub_gen.stub_layout, Some(StubLayout::Package));
+            assert_eq!(stub_gen.merge, Some(true));
+        }
+    }
+}
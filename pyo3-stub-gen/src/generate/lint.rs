@@ -0,0 +1,209 @@
+//! Heuristic checks for type overrides given by users, reported as warnings while [StubInfo](crate::StubInfo) is built.
+
+use crate::type_info::{ParameterInfo, PyClassInfo, PyFunctionInfo, PyMethodsInfo};
+use crate::TypeInfo;
+
+/// Whether the type is a Python builtin, e.g. `str`, `builtins.str`, or `list[int]`
+fn is_builtin(type_info: &TypeInfo) -> bool {
+    let name = type_info.name.trim();
+    let head = name.split('[').next().unwrap_or(name);
+    head.starts_with("builtins.")
+        || (!head.contains('.') && head.starts_with(|c: char| c.is_ascii_lowercase()))
+}
+
+/// Warn when only one side of a class is overridden with a builtin type,
+/// e.g. `type_output_override(type_repr = "str")` without `type_input_override`.
+pub(crate) fn class_override_warnings(info: &PyClassInfo) -> Vec<String> {
+    let mut warnings = Vec::new();
+    match (info.type_override.input, info.type_override.output) {
+        (None, Some(output)) if is_builtin(&output()) => warnings.push(format!(
+            "Class `{}` overrides its output type with the builtin `{}` but keeps the default input type. \
+             Set `type_input_override(...)` too if arguments accept the same type.",
+            info.pyclass_name,
+            output().name,
+        )),
+        (Some(input), None) if is_builtin(&input()) => warnings.push(format!(
+            "Class `{}` overrides its input type with the builtin `{}` but keeps the default output type. \
+             Set `type_output_override(...)` too if the class is returned as the same type.",
+            info.pyclass_name,
+            input().name,
+        )),
+        _ => {}
+    }
+    warnings
+}
+
+/// Warn when an overridden parameter type is exactly the default output of a class tagged by `custom_from_py_object`.
+/// Such a class usually accepts wider inputs, so the override is likely to be too narrow.
+pub(crate) fn parameter_override_warnings(
+    owner: &str,
+    parameters: &[ParameterInfo],
+    classes: &[&PyClassInfo],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for parameter in parameters.iter().filter(|p| p.type_overridden) {
+        let name = (parameter.type_info)().name;
+        for class in classes
+            .iter()
+            .filter(|c| c.type_override.custom_from_py_object)
+        {
+            let qualified = class
+                .module
+                .map(|module| format!("{module}.{}", class.pyclass_name));
+            if name == class.pyclass_name || Some(&name) == qualified.as_ref() {
+                warnings.push(format!(
+                    "Parameter `{}` of `{owner}` overrides its type to `{name}`, which is the output type of `{}`. \
+                     `{}` has a custom `FromPyObject`, so the input type may be wider.",
+                    parameter.name, class.pyclass_name, class.pyclass_name,
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// Run all checks over the collected definitions
+pub(crate) fn override_warnings() -> Vec<String> {
+    let classes: Vec<&PyClassInfo> = inventory::iter::<PyClassInfo>.into_iter().collect();
+    let mut warnings = Vec::new();
+    for class in &classes {
+        warnings.extend(class_override_warnings(class));
+    }
+    for function in inventory::iter::<PyFunctionInfo> {
+        warnings.extend(parameter_override_warnings(
+            function.name,
+            function.parameters,
+            &classes,
+        ));
+    }
+    for methods in inventory::iter::<PyMethodsInfo> {
+        let class_name = classes
+            .iter()
+            .find(|class| (class.struct_id)() == (methods.struct_id)())
+            .map(|class| class.pyclass_name);
+        for method in methods.methods {
+            let owner = match class_name {
+                Some(class_name) => format!("{class_name}.{}", method.name),
+                None => method.name.to_string(),
+            };
+            warnings.extend(parameter_override_warnings(
+                &owner,
+                method.parameters,
+                &classes,
+            ));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::{ClassTypeOverride, ParameterDefault, ParameterKind};
+    use std::any::TypeId;
+
+    struct Dummy;
+
+    fn class(type_override: ClassTypeOverride) -> PyClassInfo {
+        PyClassInfo {
+            struct_id: TypeId::of::<Dummy>,
+            pyclass_name: "Path",
+            name_given: false,
+            module: Some("mymod"),
+            doc: "",
+            getters: &[],
+            setters: &[],
+            bases: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override,
+            dataclass: false,
+            rust_path: "test::Dummy",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
+        }
+    }
+
+    fn parameter(type_info: fn() -> TypeInfo, type_overridden: bool) -> ParameterInfo {
+        ParameterInfo {
+            name: "path",
+            kind: ParameterKind::PositionalOrKeyword,
+            type_info,
+            default: ParameterDefault::None,
+            type_overridden,
+            container_default: None,
+            deprecated: None,
+        }
+    }
+
+    #[test]
+    fn one_sided_builtin_override() {
+        let output_only = class(ClassTypeOverride {
+            output: Some(|| TypeInfo::builtin("str")),
+            ..Default::default()
+        });
+        assert_eq!(class_override_warnings(&output_only).len(), 1);
+
+        let input_only = class(ClassTypeOverride {
+            input: Some(|| TypeInfo::with_module("builtins.str", "builtins".into())),
+            ..Default::default()
+        });
+        assert_eq!(class_override_warnings(&input_only).len(), 1);
+
+        let both = class(ClassTypeOverride {
+            input: Some(|| TypeInfo::builtin("str")),
+            output: Some(|| TypeInfo::builtin("str")),
+            custom_from_py_object: false,
+        });
+        assert!(class_override_warnings(&both).is_empty());
+
+        let not_builtin = class(ClassTypeOverride {
+            output: Some(|| TypeInfo::with_module("os.PathLike", "os".into())),
+            ..Default::default()
+        });
+        assert!(class_override_warnings(&not_builtin).is_empty());
+    }
+
+    #[test]
+    fn overridden_parameter_equals_custom_from_py_object_output() {
+        let tagged = class(ClassTypeOverride {
+            custom_from_py_object: true,
+            ..Default::default()
+        });
+        let untagged = class(ClassTypeOverride::default());
+        let param = parameter(|| TypeInfo::unqualified("Path"), true);
+        let qualified = parameter(|| TypeInfo::unqualified("mymod.Path"), true);
+        let derived = parameter(|| TypeInfo::unqualified("Path"), false);
+        let other = parameter(|| TypeInfo::builtin("str"), true);
+
+        assert_eq!(
+            parameter_override_warnings("f", std::slice::from_ref(&param), &[&tagged]).len(),
+            1
+        );
+        assert_eq!(
+            parameter_override_warnings("f", std::slice::from_ref(&qualified), &[&tagged]).len(),
+            1
+        );
+        assert!(
+            parameter_override_warnings("f", std::slice::from_ref(&param), &[&untagged]).is_empty()
+        );
+        assert!(
+            parameter_override_warnings("f", std::slice::from_ref(&derived), &[&tagged]).is_empty()
+        );
+        assert!(
+            parameter_override_warnings("f", std::slice::from_ref(&other), &[&tagged]).is_empty()
+        );
+    }
+}
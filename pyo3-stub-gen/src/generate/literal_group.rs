@@ -0,0 +1,179 @@
+//! `typing.Literal` aliases collected from the values of module-level variables by [crate::literal_group].
+//!
+//! The members are taken from the rendered values of the variables on each generation,
+//! so that the alias follows the constants instead of being maintained by hand.
+
+use crate::{generate::*, type_info::LiteralGroupInfo, TypeInfo};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+/// Register the aliases of `groups` as synthesized symbols of their modules.
+pub(crate) fn add_literal_groups<'a>(
+    modules: &BTreeMap<String, Module>,
+    synthesized: &mut synthesized::SynthesizedSymbols,
+    groups: impl IntoIterator<Item = &'a LiteralGroupInfo>,
+) -> Result<()> {
+    for group in groups {
+        let origin = format!("literal group `{}` in {}", group.name, group.rust_path);
+        let variables = modules.get(group.module).map(|module| &module.variables);
+        let mut members = Vec::with_capacity(group.variables.len());
+        for name in group.variables {
+            let Some(variable) = variables.and_then(|variables| variables.get(name)) else {
+                bail!(
+                    "Variable `{}.{name}` of {origin} is not added by `module_variable!`",
+                    group.module
+                );
+            };
+            match variable.default.as_deref() {
+                Some(value) if is_literal(value) => members.push(value),
+                _ => bail!(
+                    "Variable `{}.{name}` of {origin} has no literal value",
+                    group.module
+                ),
+            }
+        }
+        let alias = TypeInfo::with_module(
+            &format!("typing.Literal[{}]", members.join(", ")),
+            "typing".into(),
+        );
+        let registered = synthesized.register(
+            group.module,
+            group.name,
+            SynthesizedKind::TypeAlias(alias),
+            origin.clone(),
+        );
+        if registered != group.name {
+            bail!(
+                "Alias `{}.{}` of {origin} collides with another synthesized symbol",
+                group.module,
+                group.name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Whether a rendered value can be a member of `typing.Literal`, i.e. `str`, `bytes`, `int`, `bool`, or `None`
+fn is_literal(value: &str) -> bool {
+    let quoted = |s: &str| {
+        s.len() >= 2
+            && ((s.starts_with('\'') && s.ends_with('\''))
+                || (s.starts_with('"') && s.ends_with('"')))
+    };
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    quoted(value)
+        || value.strip_prefix('b').is_some_and(quoted)
+        || (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+        || matches!(value, "True" | "False" | "None")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stub_type::ModuleRef, type_info::ParameterKind};
+    use test_case::test_case;
+
+    fn variable(name: &'static str, default: &str) -> VariableDef {
+        let mut def = VariableDef::new(name, TypeInfo::builtin("str"));
+        def.default = Some(default.to_string());
+        def
+    }
+
+    fn modules(slow: &str) -> BTreeMap<String, Module> {
+        let mut module = Module::new("mypkg", "mypkg");
+        for def in [
+            variable("MODE_FAST", "'fast'"),
+            variable("MODE_SLOW", slow),
+            variable("MODE_AUTO", "'auto'"),
+        ] {
+            module.variables.insert(def.name, def);
+        }
+        BTreeMap::from([("mypkg".to_string(), module)])
+    }
+
+    fn group(variables: &'static [&'static str]) -> LiteralGroupInfo {
+        LiteralGroupInfo {
+            module: "mypkg",
+            name: "Mode",
+            rust_path: "mypkg::modes",
+            variables,
+        }
+    }
+
+    fn build(modules: &mut BTreeMap<String, Module>, group: &LiteralGroupInfo) -> Result<()> {
+        let mut synthesized = synthesized::SynthesizedSymbols::default();
+        add_literal_groups(modules, &mut synthesized, [group])?;
+        synthesized.apply(modules, "mypkg")
+    }
+
+    #[test]
+    fn alias_of_constants() -> Result<()> {
+        let mut modules = modules("'slow'");
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword.push(Parameter::new(
+            "mode",
+            ParameterKind::PositionalOrKeyword,
+            TypeInfo::locally_defined("Mode", ModuleRef::from("mypkg")),
+        ));
+        modules.get_mut("mypkg").unwrap().function.insert(
+            "run".into(),
+            vec![FunctionDef::new("run", parameters, TypeInfo::none())],
+        );
+        build(
+            &mut modules,
+            &group(&["MODE_FAST", "MODE_SLOW", "MODE_AUTO"]),
+        )?;
+        let stub = modules["mypkg"].to_string();
+        assert!(
+            stub.contains("Mode: typing.TypeAlias = typing.Literal['fast', 'slow', 'auto']"),
+            "{stub}"
+        );
+        assert!(stub.contains("def run(mode: Mode) -> None: ..."), "{stub}");
+        assert!(stub.contains("import typing\n"), "{stub}");
+
+        // The alias follows the values of the constants
+        let mut modules = self::modules("'careful'");
+        build(
+            &mut modules,
+            &group(&["MODE_FAST", "MODE_SLOW", "MODE_AUTO"]),
+        )?;
+        let stub = modules["mypkg"].to_string();
+        assert!(
+            stub.contains("typing.Literal['fast', 'careful', 'auto']"),
+            "{stub}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_variable() {
+        let err = build(&mut modules("'slow'"), &group(&["MODE_FAST", "MODE_TURBO"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Variable `mypkg.MODE_TURBO` of literal group `Mode` in mypkg::modes is not added by `module_variable!`"
+        );
+    }
+
+    #[test]
+    fn non_literal_variable() {
+        let err = build(&mut modules("..."), &group(&["MODE_FAST", "MODE_SLOW"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Variable `mypkg.MODE_SLOW` of literal group `Mode` in mypkg::modes has no literal value"
+        );
+    }
+
+    #[test_case("'fast'", true ; "str")]
+    #[test_case("\"it's\"", true ; "double quoted str")]
+    #[test_case("b'raw'", true ; "bytes")]
+    #[test_case("-42", true ; "int")]
+    #[test_case("True", true ; "bool")]
+    #[test_case("None", true ; "none")]
+    #[test_case("1.5", false ; "float")]
+    #[test_case("...", false ; "ellipsis")]
+    #[test_case("['a']", false ; "list")]
+    #[test_case("-", false ; "minus")]
+    fn literal(value: &str, expected: bool) {
+        assert_eq!(is_literal(value), expected);
+    }
+}
@@ -0,0 +1,110 @@
+//! References to the names of a module qualified by the module itself, e.g. `mypkg.core.Other` in `mypkg.core`.
+//!
+//! The stub file of a module never imports the module itself, so such references are rendered unqualified.
+
+use crate::{generate::*, TypeInfo};
+use std::collections::HashSet;
+
+impl Module {
+    /// Names defined at the top level of this module
+    fn top_level_names(&self) -> HashSet<String> {
+        self.class
+            .values()
+            .map(|class| class.name.as_ref())
+            .chain(self.enum_.values().map(|enum_| enum_.name))
+            .chain(self.exceptions.keys().copied())
+            .chain(self.function.keys().map(AsRef::as_ref))
+            .chain(self.variables.keys().copied())
+            .chain(self.synthesized.keys().map(String::as_str))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Render the references to the names of this module qualified by its name unqualified, see [TypeInfo::unqualify]
+    pub(crate) fn unqualify_local_references(&mut self) {
+        let names = self.top_level_names();
+        let module_name = self.name.clone();
+        let mut unqualify =
+            |ty: &mut TypeInfo| ty.unqualify(&module_name, |name| names.contains(name));
+        self.for_each_type_mut(&mut unqualify);
+        for symbol in self.synthesized.values_mut() {
+            let SynthesizedKind::TypeAlias(ty) = &mut symbol.kind;
+            unqualify(ty);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::MethodType;
+    use std::any::TypeId;
+
+    /// Module `name` defining classes `names` with a method `peer` returning `peer`
+    fn render(name: &str, names: &[&'static str], peer: &str) -> String {
+        let mut module = Module::new(name, "mypkg");
+        let ids = [TypeId::of::<u8>(), TypeId::of::<u16>()];
+        for (id, name) in ids.into_iter().zip(names) {
+            let mut class = ClassDef::new(*name);
+            let (peer_module, _) = peer.rsplit_once('.').unwrap();
+            class.add_method(MethodDef::new(
+                "peer",
+                MethodType::Instance,
+                Parameters::new(),
+                TypeInfo::with_module(peer, peer_module.into()),
+            ));
+            module.class.insert(id, class);
+        }
+        module.unqualify_local_references();
+        module.to_string()
+    }
+
+    #[test]
+    fn same_module() {
+        let rendered = render("mypkg.core", &["Other", "Peer"], "mypkg.core.Other");
+        assert!(
+            rendered.contains("def peer(self) -> Other: ..."),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("import mypkg"), "{rendered}");
+    }
+
+    #[test]
+    fn parent_module() {
+        let rendered = render("mypkg.core", &["Other"], "mypkg.Other");
+        assert!(
+            rendered.contains("def peer(self) -> mypkg.Other: ..."),
+            "{rendered}"
+        );
+        assert!(rendered.contains("import mypkg\n"), "{rendered}");
+
+        // Names of a submodule are not defined by its parent module
+        let rendered = render("mypkg", &["Other"], "mypkg.core.Other");
+        assert!(
+            rendered.contains("def peer(self) -> mypkg.core.Other: ..."),
+            "{rendered}"
+        );
+        assert!(rendered.contains("import mypkg.core\n"), "{rendered}");
+    }
+
+    #[test]
+    fn sibling_module() {
+        for peer in ["mypkg.util.Other", "mypkg.core_ext.Other"] {
+            let rendered = render("mypkg.core", &["Other"], peer);
+            assert!(
+                rendered.contains(&format!("def peer(self) -> {peer}: ...")),
+                "{rendered}"
+            );
+        }
+    }
+
+    #[test]
+    fn nested_submodule() {
+        let rendered = render("mypkg.core", &["Other"], "mypkg.core.sub.Other");
+        assert!(
+            rendered.contains("def peer(self) -> mypkg.core.sub.Other: ..."),
+            "{rendered}"
+        );
+        assert!(rendered.contains("import mypkg.core.sub\n"), "{rendered}");
+    }
+}
@@ -0,0 +1,416 @@
+//! Machine-readable summary of the public API described by the stub files.
+//!
+//! [ApiManifest] is a plain-data mirror of [StubInfo] meant for external tools,
+//! e.g. to diff the API surface between releases. Types are rendered as the Python annotations
+//! written in the stubs, and items are sorted by name so that the JSON is stable between builds.
+
+use crate::{
+    generate::*,
+    type_info::{MethodType, ParameterKind},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Public API of all the modules of [StubInfo]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiManifest {
+    /// Modules in the order of their dotted names
+    pub modules: Vec<ModuleManifest>,
+}
+
+/// Items of a module
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleManifest {
+    /// Dotted name, e.g. `mypkg.sub`
+    pub name: String,
+    /// Names of the direct submodules
+    pub submodules: Vec<String>,
+    /// Classes including exceptions
+    pub classes: Vec<ClassManifest>,
+    pub enums: Vec<EnumManifest>,
+    /// Functions, where each overload is a separate entry of the same name
+    pub functions: Vec<FunctionManifest>,
+    /// Module-level variables including type aliases
+    pub variables: Vec<VariableManifest>,
+}
+
+/// Members of a class
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassManifest {
+    pub name: String,
+    /// Fully-qualified name, e.g. `mypkg.sub.Outer.Inner`
+    pub qualname: String,
+    pub bases: Vec<String>,
+    pub is_abstract: bool,
+    pub deprecated: bool,
+    /// Class attributes, and the fields of a dataclass
+    pub attributes: Vec<VariableManifest>,
+    pub properties: Vec<PropertyManifest>,
+    /// Methods in the order of the stub, where each overload is a separate entry of the same name
+    pub methods: Vec<FunctionManifest>,
+    /// Nested classes, e.g. the variants of a complex enum
+    pub classes: Vec<ClassManifest>,
+}
+
+/// Members of an enum
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumManifest {
+    pub name: String,
+    /// Fully-qualified name, e.g. `mypkg.Color`
+    pub qualname: String,
+    /// Names of the variants in the order of declaration
+    pub members: Vec<String>,
+    pub properties: Vec<PropertyManifest>,
+    pub methods: Vec<FunctionManifest>,
+}
+
+/// Signature of a function or a method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionManifest {
+    pub name: String,
+    /// Kind of the method, or `None` for a module-level function
+    pub method_type: Option<MethodType>,
+    pub parameters: Vec<ParameterManifest>,
+    pub return_type: String,
+    pub is_async: bool,
+    pub deprecated: bool,
+}
+
+/// Parameter of a [FunctionManifest], excluding `self` and `cls`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterManifest {
+    pub name: String,
+    pub kind: ParameterKind,
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Default value as a Python expression, or `None` if required
+    pub default: Option<String>,
+}
+
+/// Property of a class or an enum
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyManifest {
+    pub name: String,
+    /// Type of the getter, or of the setter for a write-only property
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub readable: bool,
+    pub writable: bool,
+    pub deprecated: bool,
+}
+
+/// Module-level variable, class attribute, or dataclass field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariableManifest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Value as a Python expression, e.g. the default of a dataclass field or the target of a type alias
+    pub value: Option<String>,
+}
+
+impl StubInfo {
+    /// Summary of the public API of all modules
+    pub fn to_manifest(&self) -> ApiManifest {
+        let _config = self.config.scope();
+        ApiManifest {
+            modules: self.modules.values().map(ModuleManifest::from).collect(),
+        }
+    }
+
+    /// Write [StubInfo::to_manifest] to `path` as pretty-printed JSON
+    pub fn write_manifest(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(&self.to_manifest())?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, json + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        log::info!("Write API manifest at {}", path.display());
+        Ok(())
+    }
+}
+
+impl From<&Module> for ModuleManifest {
+    fn from(module: &Module) -> Self {
+        let mut classes: Vec<_> = module
+            .class
+            .values()
+            .map(ClassManifest::from)
+            .chain(module.exceptions.values().map(ClassManifest::from))
+            .collect();
+        classes.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut enums: Vec<_> = module.enum_.values().map(EnumManifest::from).collect();
+        enums.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut variables: Vec<_> = module
+            .variables
+            .values()
+            .map(|variable| VariableManifest {
+                name: variable.name.to_string(),
+                type_: variable.type_.name.clone(),
+                value: variable.default.clone(),
+            })
+            .chain(module.synthesized.values().map(|symbol| {
+                let SynthesizedKind::TypeAlias(ty) = &symbol.kind;
+                VariableManifest {
+                    name: symbol.name.clone(),
+                    type_: "typing.TypeAlias".to_string(),
+                    value: Some(ty.name.clone()),
+                }
+            }))
+            .collect();
+        variables.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            name: module.name.clone(),
+            submodules: module.submodules.iter().cloned().collect(),
+            classes,
+            enums,
+            functions: module
+                .function
+                .values()
+                .flatten()
+                .map(FunctionManifest::from)
+                .collect(),
+            variables,
+        }
+    }
+}
+
+impl From<&ClassDef> for ClassManifest {
+    fn from(class: &ClassDef) -> Self {
+        let fields = class
+            .dataclass
+            .iter()
+            .flat_map(|dataclass| &dataclass.fields);
+        Self {
+            name: class.name.to_string(),
+            qualname: class.qualname.to_string(),
+            bases: class.bases.iter().map(|base| base.name.clone()).collect(),
+            is_abstract: class.is_abstract,
+            deprecated: class.deprecated.is_some(),
+            attributes: class
+                .attrs
+                .iter()
+                .chain(fields)
+                .map(VariableManifest::from)
+                .collect(),
+            properties: class
+                .getter_setters
+                .values()
+                .filter_map(|(getter, setter)| {
+                    PropertyManifest::new(getter.as_ref(), setter.as_ref())
+                })
+                .collect(),
+            methods: class
+                .methods
+                .values()
+                .flatten()
+                .map(FunctionManifest::from)
+                .collect(),
+            classes: class.classes.iter().map(ClassManifest::from).collect(),
+        }
+    }
+}
+
+impl From<&ExceptionDef> for ClassManifest {
+    fn from(exception: &ExceptionDef) -> Self {
+        Self {
+            name: exception.name.to_string(),
+            qualname: exception.qualname.to_string(),
+            bases: vec![exception.base.name.clone()],
+            is_abstract: false,
+            deprecated: false,
+            attributes: Vec::new(),
+            properties: Vec::new(),
+            methods: Vec::new(),
+            classes: Vec::new(),
+        }
+    }
+}
+
+impl From<&EnumDef> for EnumManifest {
+    fn from(enum_: &EnumDef) -> Self {
+        let getter = |name| enum_.getters.iter().find(|getter| getter.name == name);
+        let setter = |name| enum_.setters.iter().find(|setter| setter.name == name);
+        let properties = enum_
+            .getters
+            .iter()
+            .filter_map(|getter| PropertyManifest::new(Some(getter), setter(getter.name)))
+            .chain(
+                enum_
+                    .setters
+                    .iter()
+                    .filter(|setter| getter(setter.name).is_none())
+                    .filter_map(|setter| PropertyManifest::new(None, Some(setter))),
+            )
+            .collect();
+        Self {
+            name: enum_.name.to_string(),
+            qualname: enum_.qualname.to_string(),
+            members: enum_
+                .variants
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            properties,
+            methods: enum_.methods.iter().map(FunctionManifest::from).collect(),
+        }
+    }
+}
+
+impl From<&FunctionDef> for FunctionManifest {
+    fn from(function: &FunctionDef) -> Self {
+        Self {
+            name: function.name.to_string(),
+            method_type: None,
+            parameters: parameters(&function.parameters),
+            return_type: function.r#return.name.clone(),
+            is_async: function.is_async,
+            deprecated: function.deprecated.is_some(),
+        }
+    }
+}
+
+impl From<&MethodDef> for FunctionManifest {
+    fn from(method: &MethodDef) -> Self {
+        Self {
+            name: method.name.to_string(),
+            method_type: Some(method.r#type),
+            parameters: parameters(&method.parameters),
+            return_type: method.r#return.name.clone(),
+            is_async: method.is_async,
+            deprecated: method.deprecated.is_some(),
+        }
+    }
+}
+
+fn parameters(parameters: &Parameters) -> Vec<ParameterManifest> {
+    parameters
+        .iter_entries()
+        .map(|parameter| ParameterManifest {
+            name: parameter.name.to_string(),
+            kind: parameter.kind,
+            type_: parameter.type_info.name.clone(),
+            default: match &parameter.default {
+                ParameterDefault::None => None,
+                ParameterDefault::Expr(expr) => Some(expr.clone()),
+            },
+        })
+        .collect()
+}
+
+impl PropertyManifest {
+    fn new(getter: Option<&MemberDef>, setter: Option<&MemberDef>) -> Option<Self> {
+        let member = getter.or(setter)?;
+        Some(Self {
+            name: member.name.to_string(),
+            type_: member.r#type.name.clone(),
+            readable: getter.is_some(),
+            writable: setter.is_some(),
+            deprecated: getter
+                .into_iter()
+                .chain(setter)
+                .any(|member| member.deprecated.is_some()),
+        })
+    }
+}
+
+impl From<&MemberDef> for VariableManifest {
+    fn from(member: &MemberDef) -> Self {
+        Self {
+            name: member.name.to_string(),
+            type_: member.r#type.name.clone(),
+            value: member.default.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeInfo;
+    use std::{any::TypeId, collections::BTreeMap, path::PathBuf};
+
+    fn stub_info() -> StubInfo {
+        let mut scale = Parameter::new(
+            "factor",
+            ParameterKind::PositionalOrKeyword,
+            TypeInfo::builtin("float"),
+        );
+        scale.default = ParameterDefault::Expr("1.0".to_string());
+        let mut class = ClassDef::new("Point");
+        class.add_property(MemberDef::new("x", TypeInfo::builtin("float")), true);
+        class.add_method(MethodDef::new(
+            "scale",
+            MethodType::Instance,
+            [scale].into_iter().collect(),
+            TypeInfo::builtin("float"),
+        ));
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(TypeId::of::<u8>(), class);
+        module.enum_.insert(
+            TypeId::of::<u16>(),
+            EnumDef::new("Color", &[("RED", ""), ("GREEN", "")]),
+        );
+        module.function.insert(
+            "origin".into(),
+            vec![FunctionDef::new(
+                "origin",
+                Parameters::new(),
+                TypeInfo::locally_defined("Point", "pkg".into()),
+            )],
+        );
+        module.variables.insert(
+            "VERSION",
+            VariableDef::new("VERSION", TypeInfo::builtin("str")),
+        );
+        module.assign_qualnames();
+        StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), module)]),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let manifest = stub_info().to_manifest();
+        let json = serde_json::to_string(&manifest)?;
+        assert_eq!(serde_json::from_str::<ApiManifest>(&json)?, manifest);
+
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        let module = &value["modules"][0];
+        assert_eq!(module["name"], "pkg");
+        let class = &module["classes"][0];
+        assert_eq!(class["qualname"], "pkg.Point");
+        assert_eq!(class["properties"][0]["type"], "builtins.float");
+        assert_eq!(class["properties"][0]["writable"], true);
+        let method = &class["methods"][0];
+        assert_eq!(method["name"], "scale");
+        assert_eq!(method["method_type"], "instance");
+        assert_eq!(
+            method["parameters"][0],
+            serde_json::json!({
+                "name": "factor",
+                "kind": "positional_or_keyword",
+                "type": "builtins.float",
+                "default": "1.0",
+            })
+        );
+        assert_eq!(
+            module["enums"][0]["members"],
+            serde_json::json!(["RED", "GREEN"])
+        );
+        assert_eq!(
+            module["functions"][0]["method_type"],
+            serde_json::Value::Null
+        );
+        assert_eq!(module["functions"][0]["return_type"], "Point");
+        assert_eq!(module["variables"][0]["type"], "builtins.str");
+        Ok(())
+    }
+}
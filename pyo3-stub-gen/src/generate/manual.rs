@@ -0,0 +1,235 @@
+//! Items described by hand in the `gen_stub` executable, e.g. classes of a dependency crate
+//! registered by `m.add_class::<T>()` without `#[gen_stub_pyclass]`.
+
+use crate::generate::*;
+use anyhow::{bail, Context, Result};
+use std::any::TypeId;
+
+impl StubInfo {
+    /// Add a class described by hand to the module `module`, where `T` is the Rust type of the class.
+    ///
+    /// The module is created if it does not exist yet.
+    pub fn register_class_manually<T: 'static>(
+        &mut self,
+        module: &str,
+        class: ClassDef,
+    ) -> Result<()> {
+        let module = self.module_mut(module)?;
+        module.ensure_undefined(&class.name)?;
+        if module.class.contains_key(&TypeId::of::<T>()) {
+            bail!(
+                "Class of `{}` is already defined in `{}`",
+                std::any::type_name::<T>(),
+                module.name
+            );
+        }
+        module.class.insert(TypeId::of::<T>(), class);
+        module.assign_qualnames();
+        Ok(())
+    }
+
+    /// Add a function described by hand to the module `module`.
+    ///
+    /// A function of the same name as one already added is rendered as an overload of it.
+    pub fn register_function_manually(
+        &mut self,
+        module: &str,
+        function: FunctionDef,
+    ) -> Result<()> {
+        let module = self.module_mut(module)?;
+        if !module.function.contains_key(&function.name) {
+            module.ensure_undefined(&function.name)?;
+        }
+        module
+            .function
+            .entry(function.name.clone())
+            .or_default()
+            .push(function);
+        module.assign_qualnames();
+        Ok(())
+    }
+
+    /// Module `name`, which is created if missing together with its missing parent modules,
+    /// each registered as a submodule of its parent
+    fn module_mut(&mut self, name: &str) -> Result<&mut Module> {
+        module_rename::validate(name)
+            .with_context(|| format!("`{name}` is not a valid Python module name"))?;
+        let default_module_name = self
+            .modules
+            .values()
+            .next()
+            .map_or(name, |module| &module.default_module_name)
+            .to_string();
+        let ancestors = name.match_indices('.').map(|(end, _)| &name[..end]);
+        for path in ancestors.chain([name]) {
+            if self.modules.contains_key(path) {
+                continue;
+            }
+            self.modules.insert(
+                path.to_string(),
+                Module::new(path, default_module_name.clone()),
+            );
+            if let Some((parent, child)) = path.rsplit_once('.') {
+                self.modules
+                    .get_mut(parent)
+                    .expect("parents are created first")
+                    .submodules
+                    .insert(child.to_string());
+            }
+        }
+        Ok(self.modules.get_mut(name).expect("inserted above"))
+    }
+}
+
+impl Module {
+    /// Fail if `name` is already used by an item of this module
+    pub(crate) fn ensure_undefined(&self, name: &str) -> Result<()> {
+        let defined = self.class.values().any(|class| class.name == name)
+            || self.enum_.values().any(|enum_| enum_.name == name)
+            || self.exceptions.contains_key(name)
+            || self.function.contains_key(name)
+            || self.variables.contains_key(name)
+            || self.synthesized.contains_key(name)
+            || self.submodules.contains(name);
+        if defined {
+            bail!("`{}.{name}` is already defined", self.name);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        type_info::{MethodType, ParameterKind},
+        TypeInfo,
+    };
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::PathBuf,
+    };
+
+    /// Class of a dependency crate without `#[gen_stub_pyclass]`
+    struct Engine;
+
+    fn stub_info() -> StubInfo {
+        StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), Module::new("pkg", "pkg"))]),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn register_class() -> Result<()> {
+        let mut class = ClassDef::new("Engine");
+        class.doc = "Engine of a dependency crate";
+        class.add_property(MemberDef::new("speed", TypeInfo::builtin("float")), true);
+        class.add_method(MethodDef::new(
+            "start",
+            MethodType::Instance,
+            [Parameter::new(
+                "warmup",
+                ParameterKind::KeywordOnly,
+                TypeInfo::builtin("bool"),
+            )]
+            .into_iter()
+            .collect(),
+            TypeInfo::none(),
+        ));
+        let mut info = stub_info();
+        info.register_class_manually::<Engine>("pkg.engine", class)?;
+        info.register_function_manually(
+            "pkg.engine",
+            FunctionDef::new(
+                "create",
+                Parameters::new(),
+                TypeInfo::locally_defined("Engine", "pkg.engine".into()),
+            ),
+        )?;
+        assert_eq!(
+            info.modules["pkg"].submodules,
+            BTreeSet::from(["engine".to_string()])
+        );
+        assert_eq!(
+            info.modules["pkg.engine"].to_string(),
+            r#"# This file is automatically generated by pyo3_stub_gen
+# ruff: noqa: E501, F401
+
+import builtins
+import typing
+
+__all__ = [
+    "Engine",
+    "create",
+]
+
+@typing.final
+class Engine:
+    r"""
+    Engine of a dependency crate
+    """
+    @property
+    def speed(self) -> builtins.float: ...
+    @speed.setter
+    def speed(self, value: builtins.float) -> None: ...
+    def start(self, *, warmup: builtins.bool) -> None: ...
+
+def create() -> Engine: ...
+
+"#
+        );
+        assert_eq!(
+            info.modules["pkg.engine"].class[&TypeId::of::<Engine>()]
+                .qualname
+                .to_string(),
+            "pkg.engine.Engine"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_parent_modules() -> Result<()> {
+        let mut info = stub_info();
+        let name = format!("create_{}", "engine");
+        info.register_function_manually(
+            "pkg.vendor.engine",
+            FunctionDef::new(name, Parameters::new(), TypeInfo::none()),
+        )?;
+        assert_eq!(
+            info.modules["pkg"].submodules,
+            BTreeSet::from(["vendor".to_string()])
+        );
+        assert_eq!(
+            info.modules["pkg.vendor"].submodules,
+            BTreeSet::from(["engine".to_string()])
+        );
+        assert!(info.modules["pkg.vendor.engine"]
+            .function
+            .contains_key("create_engine"));
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_names() -> Result<()> {
+        let mut info = stub_info();
+        let function = || FunctionDef::new("Engine", Parameters::new(), TypeInfo::none());
+        info.register_function_manually("pkg", function())?;
+        // Overloads of a function
+        info.register_function_manually("pkg", function())?;
+        assert_eq!(info.modules["pkg"].function["Engine"].len(), 2);
+
+        let err = info
+            .register_class_manually::<Engine>("pkg", ClassDef::new("Engine"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "`pkg.Engine` is already defined");
+        let err = info
+            .register_class_manually::<Engine>("1pkg", ClassDef::new("Engine"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "`1pkg` is not a valid Python module name");
+        Ok(())
+    }
+}
@@ -7,29 +7,62 @@ use std::{
 
 /// Definition of a class member.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct MemberDef {
     pub name: &'static str,
     pub r#type: TypeInfo,
     pub doc: &'static str,
     pub default: Option<String>,
     pub deprecated: Option<DeprecatedInfo>,
+    /// `# type: ignore` comment after the annotation, or after the signature of a property
+    pub type_ignored: Option<TypeIgnore>,
     pub is_abstract: bool,
+    /// Class attribute defined by `#[classattr]`, which is rendered as `typing.Final`
+    pub is_classattr: bool,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
 }
 
 impl Import for MemberDef {
     fn import(&self) -> HashSet<ImportRef> {
         let mut import = self.r#type.import.clone();
         // Add typing_extensions import if deprecated
-        if self.deprecated.is_some() {
+        if deprecated_decorator(&self.deprecated, DeprecatedTarget::Property).is_some() {
             import.insert("typing_extensions".into());
         }
         if self.is_abstract {
             import.insert("abc".into());
         }
+        if self.is_classattr {
+            // for `typing.Final`
+            import.insert("typing".into());
+        }
         import
     }
 }
 
+impl MemberDef {
+    /// Member without a docstring nor a default value
+    pub fn new(name: &'static str, r#type: TypeInfo) -> Self {
+        Self {
+            name,
+            r#type,
+            doc: "",
+            default: None,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            is_classattr: false,
+            qualname: QualName::default(),
+        }
+    }
+
+    /// Trailing `# type: ignore` comment of the member, if any
+    fn type_ignore_comment(&self) -> Option<String> {
+        type_ignore_comment(&self.type_ignored, &self.qualname, self.name, None)
+    }
+}
+
 impl From<&MemberInfo> for MemberDef {
     fn from(info: &MemberInfo) -> Self {
         Self {
@@ -38,7 +71,10 @@ impl From<&MemberInfo> for MemberDef {
             doc: info.doc,
             default: info.default.map(|f| f()),
             deprecated: info.deprecated.clone(),
+            type_ignored: info.type_ignored.map(Into::into),
             is_abstract: info.is_abstract,
+            is_classattr: info.is_classattr,
+            qualname: QualName::default(),
         }
     }
 }
@@ -48,31 +84,48 @@ impl fmt::Display for MemberDef {
         let indent = indent();
         // Constants cannot have deprecated decorators in Python syntax
         // Log a warning if deprecated is present but will be ignored
-        if let Some(_deprecated) = &self.deprecated {
+        if deprecated_decorator(&self.deprecated, DeprecatedTarget::Property).is_some() {
             log::warn!(
                 "Ignoring #[deprecated] on constant '{}': Python constants cannot have decorators. \
                 Consider using a function instead if deprecation is needed.",
-                self.name
+                qualname::describe(&self.qualname, self.name)
             );
         }
-        write!(f, "{indent}{}: {}", self.name, self.r#type)?;
-        if let Some(default) = &self.default {
-            write!(f, " = {default}")?;
+        if self.is_classattr {
+            // Class attributes cannot be reassigned from Python, and `...` stands for a value unknown at compile time
+            let value = self.default.as_deref().unwrap_or("...");
+            write!(
+                f,
+                "{indent}{}: typing.Final[{}] = {value}",
+                self.name, self.r#type
+            )?;
+        } else {
+            write!(f, "{indent}{}: {}", self.name, self.r#type)?;
+            if let Some(default) = &self.default {
+                write!(f, " = {default}")?;
+            }
+        }
+        if let Some(comment) = self.type_ignore_comment() {
+            write!(f, "{comment}")?;
         }
         writeln!(f)?;
-        docstring::write_docstring(f, self.doc, indent)?;
+        let doc =
+            docstring_with_deprecation(self.doc, &self.deprecated, DeprecatedTarget::Property);
+        docstring::write_docstring(f, &doc, indent)?;
         Ok(())
     }
 }
 
-pub struct GetterDisplay<'a>(pub &'a MemberDef);
-pub struct SetterDisplay<'a>(pub &'a MemberDef);
+pub(crate) struct GetterDisplay<'a>(pub &'a MemberDef);
+pub(crate) struct SetterDisplay<'a>(pub &'a MemberDef);
 
 impl fmt::Display for GetterDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let indent = indent();
         // Add deprecated decorator if present
-        if let Some(deprecated) = &self.0.deprecated {
+        if let Some(deprecated) =
+            deprecated_decorator(&self.0.deprecated, DeprecatedTarget::Property)
+        {
             writeln!(f, "{indent}{deprecated}")?;
         }
         writeln!(f, "{indent}@property")?;
@@ -92,13 +145,8 @@ impl fmt::Display for GetterDisplay<'_> {
         } else {
             Cow::Borrowed(self.0.doc)
         };
-        if !doc.is_empty() {
-            writeln!(f)?;
-            let double_indent = format!("{indent}{indent}");
-            docstring::write_docstring(f, &doc, &double_indent)
-        } else {
-            writeln!(f, " ...")
-        }
+        let doc = docstring_with_deprecation(&doc, &self.0.deprecated, DeprecatedTarget::Property);
+        write_body(f, &doc, self.0.type_ignore_comment())
     }
 }
 
@@ -110,7 +158,9 @@ impl fmt::Display for SetterDisplay<'_> {
         if self.0.is_abstract {
             writeln!(f, "{indent}@abc.abstractmethod")?;
         }
-        if let Some(deprecated) = &self.0.deprecated {
+        if let Some(deprecated) =
+            deprecated_decorator(&self.0.deprecated, DeprecatedTarget::Property)
+        {
             writeln!(f, "{indent}{deprecated}")?;
         }
         write!(
@@ -130,14 +180,26 @@ impl fmt::Display for SetterDisplay<'_> {
         } else {
             Cow::Borrowed(self.0.doc)
         };
-        if !doc.is_empty() {
-            writeln!(f)?;
-            let double_indent = format!("{indent}{indent}");
-            docstring::write_docstring(f, &doc, &double_indent)
-        } else {
-            writeln!(f, " ...")
-        }
+        let doc = docstring_with_deprecation(&doc, &self.0.deprecated, DeprecatedTarget::Property);
+        write_body(f, &doc, self.0.type_ignore_comment())
+    }
+}
+
+/// Body of a property after its signature, i.e. the docstring or `...`,
+/// with the `# type: ignore` comment at the end of the signature line
+fn write_body(f: &mut fmt::Formatter, doc: &str, type_ignore: Option<String>) -> fmt::Result {
+    if doc.is_empty() {
+        write!(f, " ...")?;
+    }
+    if let Some(comment) = type_ignore {
+        write!(f, "{comment}")?;
     }
+    writeln!(f)?;
+    if !doc.is_empty() {
+        let indent = indent();
+        docstring::write_docstring(f, doc, &format!("{indent}{indent}"))?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -152,7 +214,10 @@ mod tests {
             doc: "",
             default: None,
             deprecated: None,
+            type_ignored: None,
             is_abstract: true,
+            is_classattr: false,
+            qualname: QualName::default(),
         };
         let rendered = format!("{}", GetterDisplay(&member));
         assert!(rendered.contains("@abc.abstractmethod"));
@@ -166,9 +231,110 @@ mod tests {
             doc: "",
             default: None,
             deprecated: None,
+            type_ignored: None,
             is_abstract: true,
+            is_classattr: false,
+            qualname: QualName::default(),
         };
         let rendered = format!("{}", SetterDisplay(&member));
         assert!(rendered.contains("@abc.abstractmethod"));
     }
+
+    #[test]
+    fn deprecated_property_with_type_ignore() {
+        let mut member = MemberDef::new("handle", TypeInfo::builtin("int"));
+        member.deprecated = Some(DeprecatedInfo {
+            since: Some("1.0"),
+            note: Some("use fd"),
+        });
+        member.type_ignored = Some(TypeIgnore::Rules(vec!["override".to_string()]));
+        assert_eq!(
+            GetterDisplay(&member).to_string(),
+            r#"    @typing_extensions.deprecated("deprecated since 1.0: use fd")
+    @property
+    def handle(self) -> builtins.int:  # type: ignore[override]
+        r"""
+        Deprecated:
+            Since 1.0: use fd
+        """
+"#
+        );
+        assert!(member.import().contains(&"typing_extensions".into()));
+
+        member.deprecated = None;
+        assert_eq!(
+            SetterDisplay(&member).to_string(),
+            "    @handle.setter\n    def handle(self, value: builtins.int) -> None: ...  # type: ignore[override]\n"
+        );
+        member.type_ignored = Some(TypeIgnore::All);
+        assert_eq!(
+            member.to_string(),
+            "    handle: builtins.int  # type: ignore\n"
+        );
+    }
+
+    #[test]
+    fn classattr_is_final() {
+        let mut member = MemberDef::new("NAMES", TypeInfo::list(TypeInfo::builtin("str")));
+        member.is_classattr = true;
+        assert_eq!(
+            member.to_string(),
+            "    NAMES: typing.Final[builtins.list[builtins.str]] = ...\n"
+        );
+        assert!(member.import().contains(&"typing".into()));
+
+        member.default = Some("'1.2.3'".to_string());
+        member.r#type = TypeInfo::builtin("str");
+        assert_eq!(
+            member.to_string(),
+            "    NAMES: typing.Final[builtins.str] = '1.2.3'\n"
+        );
+
+        // Instance attributes, e.g. keys of a `TypedDict`, are not final
+        member.is_classattr = false;
+        assert_eq!(member.to_string(), "    NAMES: builtins.str = '1.2.3'\n");
+    }
+
+    #[test]
+    fn documented_attribute() {
+        let mut member = MemberDef::new("width", TypeInfo::builtin("int"));
+        // As collected from `///` comments
+        member.doc = " Width in pixels.\n\n Zero for \"\"\"auto\"\"\".";
+        assert_eq!(
+            member.to_string(),
+            r#"    width: builtins.int
+    r'''
+    Width in pixels.
+    
+    Zero for """auto""".
+    '''
+"#
+        );
+    }
+
+    #[test]
+    fn shared_field() {
+        use crate::PyStubType;
+        use std::sync::{Arc, Mutex};
+
+        let info = MemberInfo {
+            name: "tags",
+            r#type: <Mutex<Arc<Vec<String>>> as PyStubType>::type_output,
+            doc: "",
+            default: None,
+            deprecated: None,
+            item: false,
+            is_abstract: false,
+            also_as: &[],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
+        };
+        let member = MemberDef::from(&info);
+        let rendered = GetterDisplay(&member).to_string();
+        assert!(
+            rendered.contains("def tags(self) -> builtins.list[builtins.str]: ..."),
+            "{rendered}"
+        );
+    }
 }
@@ -1,22 +1,28 @@
 use crate::stub_type::ImportRef;
-use crate::{generate::*, rule_name::RuleName, type_info::*, TypeInfo};
-use itertools::Itertools;
-use std::{collections::HashSet, fmt};
+use crate::{generate::*, type_info::*, TypeInfo};
+use std::{borrow::Cow, collections::HashSet, fmt};
 
 pub use crate::type_info::MethodType;
 
 /// Definition of a class method.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct MethodDef {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     pub parameters: Parameters,
     pub r#return: TypeInfo,
     pub doc: &'static str,
     pub r#type: MethodType,
     pub is_async: bool,
     pub deprecated: Option<DeprecatedInfo>,
-    pub type_ignored: Option<IgnoreTarget>,
+    pub type_ignored: Option<TypeIgnore>,
     pub is_abstract: bool,
+    /// Whether this overrides a method of a base class, rendered as `@typing.override`
+    pub is_override: bool,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+    /// Source location of the Rust item, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
 }
 
 impl Import for MethodDef {
@@ -24,45 +30,218 @@ impl Import for MethodDef {
         let mut import = self.r#return.import.clone();
         import.extend(self.parameters.import());
         // Add typing_extensions import if deprecated
-        if self.deprecated.is_some() {
+        if deprecated_decorator(&self.deprecated, DeprecatedTarget::Method).is_some() {
             import.insert("typing_extensions".into());
         }
         if self.is_abstract {
             import.insert("abc".into());
         }
+        if self.is_override {
+            import.insert(override_module().into());
+        }
+        if let Some(cls) = self.cls_annotation(annotate_cls()) {
+            import.extend(cls.import);
+        }
         import
     }
 }
 
+/// Module providing `override`, which is `typing` since Python 3.12
+pub(crate) fn override_module() -> &'static str {
+    if target_version().has_override() {
+        "typing"
+    } else {
+        "typing_extensions"
+    }
+}
+
 impl From<&MethodInfo> for MethodDef {
     fn from(info: &MethodInfo) -> Self {
+        Self::named(info, info.name)
+    }
+}
+
+impl MethodDef {
+    /// Synchronous, concrete method without a docstring
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        r#type: MethodType,
+        parameters: Parameters,
+        r#return: TypeInfo,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            parameters,
+            r#return,
+            doc: "",
+            r#type,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: None,
+        }
+    }
+
+    /// Method defined by `info` under the given `name`, which differs from [MethodInfo::name] for `also_as`
+    pub(crate) fn named(info: &MethodInfo, name: &'static str) -> Self {
         let mut return_type = (info.r#return)();
         if info.r#type == MethodType::New {
-            return_type = TypeInfo::self_type();
+            return_type = constructor_style().return_type();
         }
-        Self {
-            name: info.name,
+        let mut method = Self {
+            name: name.into(),
             parameters: Parameters::from_infos(info.parameters),
             r#return: return_type,
             doc: info.doc,
             r#type: info.r#type,
             is_async: info.is_async,
             deprecated: info.deprecated.clone(),
-            type_ignored: info.type_ignored,
+            type_ignored: info.type_ignored.map(Into::into),
             is_abstract: info.is_abstract,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: info.source_location,
+        };
+        if method.is_context_manager_method("__exit__", "__aexit__") {
+            method.set_exit_signature(info.parameters);
+        }
+        if !info.keep_signature {
+            method.apply_protocol(info.parameters);
+        }
+        method
+    }
+
+    /// Whether this is the synchronous `sync` or the `async def` variant `async_` of a context manager method
+    fn is_context_manager_method(&self, sync: &str, async_: &str) -> bool {
+        self.r#type == MethodType::Instance
+            && if self.is_async {
+                self.name == async_
+            } else {
+                self.name == sync
+            }
+    }
+
+    /// Use the canonical signature of `__exit__`, except for parameters whose types are overridden:
+    ///
+    /// ```text
+    /// (self, exc_type: type[BaseException] | None, exc_value: BaseException | None, traceback: types.TracebackType | None) -> bool | None
+    /// ```
+    fn set_exit_signature(&mut self, infos: &[ParameterInfo]) {
+        let base_exception = TypeInfo::builtin("BaseException");
+        let canonical = [
+            TypeInfo {
+                name: format!("type[{}]", base_exception.name),
+                import: base_exception.import.clone(),
+            } | TypeInfo::none(),
+            base_exception | TypeInfo::none(),
+            TypeInfo::with_module("types.TracebackType", "types".into()) | TypeInfo::none(),
+        ];
+        let parameters = &mut self.parameters;
+        if parameters.varargs.is_none()
+            && parameters.varkw.is_none()
+            && parameters.keyword_only.is_empty()
+            && parameters.positional_only.len() + parameters.positional_or_keyword.len() == 3
+        {
+            for (parameter, type_info) in parameters
+                .positional_only
+                .iter_mut()
+                .chain(parameters.positional_or_keyword.iter_mut())
+                .zip(canonical)
+            {
+                let overridden = infos
+                    .iter()
+                    .any(|info| info.name == parameter.name && info.type_overridden);
+                if !overridden {
+                    parameter.type_info = type_info;
+                }
+            }
+        }
+        // `bool` from Rust, or no return value; other types are set explicitly
+        if ["builtins.bool", "None", "typing.Any"].contains(&self.r#return.name.as_str()) {
+            self.r#return = TypeInfo::builtin("bool") | TypeInfo::none();
+        }
+    }
+
+    /// Return `Self` from `__enter__` of the class `class_name` defined in `module` when it returns the class itself,
+    /// e.g. `PyRefMut<Self>`. A class of the same name in another module, or an untyped object, is kept.
+    pub(crate) fn resolve_enter_return(
+        &mut self,
+        class_name: &str,
+        module: &str,
+        default_module_name: &str,
+    ) {
+        if !self.is_context_manager_method("__enter__", "__aenter__") {
+            return;
+        }
+        if hidden_base::refers_to(&self.r#return, class_name, module, default_module_name) {
+            self.r#return = TypeInfo::self_type();
         }
     }
 }
 
 impl fmt::Display for MethodDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        StyledMethod(self, constructor_style()).fmt(f)
+    }
+}
+
+/// [MethodDef] whose constructor of [MethodType::New] is rendered as the given style
+pub(crate) struct StyledMethod<'a>(pub &'a MethodDef, pub ConstructorStyle);
+
+impl fmt::Display for StyledMethod<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.write(f, self.1, annotate_cls())
+    }
+}
+
+impl MethodDef {
+    /// Annotation of `cls` if `annotate_cls` is set and this is a classmethod or `__new__`
+    fn cls_annotation(&self, annotate_cls: bool) -> Option<TypeInfo> {
+        let takes_cls = matches!(self.r#type, MethodType::Class | MethodType::New);
+        (takes_cls && annotate_cls).then(receiver::cls_annotation)
+    }
+
+    /// Render this method, where a constructor of [MethodType::New] is rendered as `style`,
+    /// and `cls` is annotated if `annotate_cls` is set
+    fn write(
+        &self,
+        f: &mut fmt::Formatter,
+        style: ConstructorStyle,
+        annotate_cls: bool,
+    ) -> fmt::Result {
+        let cls = self.cls_annotation(annotate_cls);
+        if self.r#type != MethodType::New {
+            return self.write_def(f, &self.name, &self.r#return, cls.as_ref());
+        }
+        if style.renders_new() {
+            self.write_def(f, &self.name, &self.r#return, cls.as_ref())?;
+        }
+        if style.renders_init() {
+            self.write_def(f, "__init__", &TypeInfo::none(), None)?;
+        }
+        Ok(())
+    }
+
+    fn write_def(
+        &self,
+        f: &mut fmt::Formatter,
+        name: &str,
+        r#return: &TypeInfo,
+        cls: Option<&TypeInfo>,
+    ) -> fmt::Result {
         let indent = indent();
         let async_ = if self.is_async { "async " } else { "" };
 
         // Add deprecated decorator if present
-        if let Some(deprecated) = &self.deprecated {
+        if let Some(deprecated) = deprecated_decorator(&self.deprecated, DeprecatedTarget::Method) {
             writeln!(f, "{indent}{deprecated}")?;
         }
+        if self.is_override {
+            writeln!(f, "{indent}@{}.override", override_module())?;
+        }
 
         let params_str = if self.parameters.is_empty() {
             String::new()
@@ -76,7 +255,10 @@ impl fmt::Display for MethodDef {
                 if self.is_abstract {
                     writeln!(f, "{indent}@abc.abstractmethod")?;
                 }
-                write!(f, "{indent}{async_}def {}({})", self.name, self.parameters)?;
+                write!(f, "{indent}{async_}def {name}({})", self.parameters)?;
+            }
+            MethodType::New if name == "__init__" => {
+                write!(f, "{indent}def __init__(self{params_str})")?;
             }
             MethodType::Class | MethodType::New => {
                 if self.r#type == MethodType::Class {
@@ -86,40 +268,31 @@ impl fmt::Display for MethodDef {
                 if self.is_abstract {
                     writeln!(f, "{indent}@abc.abstractmethod")?;
                 }
-                write!(f, "{indent}{async_}def {}(cls{})", self.name, params_str)?;
+                let cls = match cls {
+                    Some(ty) => format!("cls: {ty}"),
+                    None => "cls".to_string(),
+                };
+                write!(f, "{indent}{async_}def {name}({cls}{params_str})")?;
             }
             MethodType::Instance => {
                 if self.is_abstract {
                     writeln!(f, "{indent}@abc.abstractmethod")?;
                 }
-                write!(f, "{indent}{async_}def {}(self{})", self.name, params_str)?;
+                write!(f, "{indent}{async_}def {name}(self{params_str})")?;
             }
         }
-        write!(f, " -> {}:", self.r#return)?;
-
-        // Calculate type: ignore comment once
-        let type_ignore_comment = if let Some(target) = &self.type_ignored {
-            match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
-                    let rules_str = rules
-                        .iter()
-                        .map(|r| {
-                            let result = r.parse::<RuleName>().unwrap();
-                            if let RuleName::Custom(custom) = &result {
-                                log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
-                            }
-                            result
-                        })
-                        .join(",");
-                    Some(format!("  # type: ignore[{rules_str}]"))
-                }
-            }
-        } else {
-            None
-        };
+        write!(f, " -> {}:", r#return)?;
 
-        let doc = self.doc;
+        let type_ignore_comment = type_ignore_comment(
+            &self.type_ignored,
+            &self.qualname,
+            &self.name,
+            self.source_location,
+        );
+
+        let doc = docstring_with_container_defaults(self.doc, &self.parameters);
+        let doc = docstring_with_deprecated_parameters(&doc, &self.parameters);
+        let doc = docstring_with_deprecation(&doc, &self.deprecated, DeprecatedTarget::Method);
         if !doc.is_empty() {
             // Add type: ignore comment for methods with docstrings
             if let Some(comment) = &type_ignore_comment {
@@ -127,7 +300,7 @@ impl fmt::Display for MethodDef {
             }
             writeln!(f)?;
             let double_indent = format!("{indent}{indent}");
-            docstring::write_docstring(f, self.doc, &double_indent)?;
+            docstring::write_docstring(f, &doc, &double_indent)?;
         } else {
             write!(f, " ...")?;
             // Add type: ignore comment for methods without docstrings
@@ -147,7 +320,7 @@ mod tests {
     #[test]
     fn abstract_instance_method_renders_decorator() {
         let method = MethodDef {
-            name: "do_work",
+            name: "do_work".into(),
             parameters: Parameters::new(),
             r#return: TypeInfo::builtin("int"),
             doc: "",
@@ -156,9 +329,218 @@ mod tests {
             deprecated: None,
             type_ignored: None,
             is_abstract: true,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: None,
         };
         let rendered = method.to_string();
         assert!(rendered.contains("@abc.abstractmethod"));
         assert!(rendered.contains("def do_work(self"));
     }
+
+    fn any_or_none() -> TypeInfo {
+        TypeInfo::any() | TypeInfo::none()
+    }
+
+    fn parameter(name: &'static str, type_overridden: bool) -> ParameterInfo {
+        ParameterInfo {
+            name,
+            kind: ParameterKind::PositionalOrKeyword,
+            type_info: any_or_none,
+            default: crate::type_info::ParameterDefault::None,
+            type_overridden,
+            container_default: None,
+            deprecated: None,
+        }
+    }
+
+    fn context_manager_method(
+        name: &'static str,
+        is_async: bool,
+        parameters: &'static [ParameterInfo],
+        r#return: fn() -> TypeInfo,
+    ) -> MethodDef {
+        MethodDef::from(&MethodInfo {
+            name,
+            parameters,
+            r#return,
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: None,
+        })
+    }
+
+    #[test]
+    fn context_manager_signatures() {
+        static EXIT_PARAMS: std::sync::LazyLock<[ParameterInfo; 3]> =
+            std::sync::LazyLock::new(|| {
+                [
+                    parameter("exc_type", false),
+                    parameter("exc_value", false),
+                    parameter("traceback", false),
+                ]
+            });
+        let lock = || TypeInfo::locally_defined("Lock", "pkg".into());
+        let mut enter = context_manager_method("__enter__", false, &[], lock);
+        enter.resolve_enter_return("Lock", "pkg", "pkg");
+        assert_eq!(enter.to_string(), "    def __enter__(self) -> Self: ...\n");
+
+        // A class of the same name in another module, or an untyped object, is not the class itself
+        let mut other = context_manager_method("__enter__", false, &[], || {
+            TypeInfo::locally_defined("Lock", "pkg.sync".into())
+        });
+        other.resolve_enter_return("Lock", "pkg", "pkg");
+        assert_eq!(other.r#return.name, "Lock");
+        let mut untyped = context_manager_method("__enter__", false, &[], TypeInfo::any);
+        untyped.resolve_enter_return("Lock", "pkg", "pkg");
+        assert_eq!(untyped.r#return.name, "typing.Any");
+
+        let exit = context_manager_method("__exit__", false, &*EXIT_PARAMS, || {
+            TypeInfo::builtin("bool")
+        });
+        assert_eq!(
+            exit.to_string(),
+            "    def __exit__(self, exc_type: type[builtins.BaseException] | None, exc_value: builtins.BaseException | None, traceback: types.TracebackType | None) -> builtins.bool | None: ...\n"
+        );
+        assert!(exit.import().contains(&"types".into()));
+
+        let mut aenter = context_manager_method("__aenter__", true, &[], lock);
+        aenter.resolve_enter_return("Lock", "pkg", "pkg");
+        assert_eq!(
+            aenter.to_string(),
+            "    async def __aenter__(self) -> Self: ...\n"
+        );
+        let aexit = context_manager_method("__aexit__", true, &*EXIT_PARAMS, TypeInfo::none);
+        assert!(aexit
+            .to_string()
+            .ends_with("traceback: types.TracebackType | None) -> builtins.bool | None: ...\n"));
+
+        // A synchronous `__aenter__` returns an awaitable, not the class itself
+        let mut sync_aenter = context_manager_method("__aenter__", false, &[], lock);
+        sync_aenter.resolve_enter_return("Lock", "pkg", "pkg");
+        assert_eq!(sync_aenter.r#return.name, "Lock");
+    }
+
+    #[test]
+    fn exit_keeps_overridden_types() {
+        static EXIT_PARAMS: std::sync::LazyLock<[ParameterInfo; 3]> =
+            std::sync::LazyLock::new(|| {
+                [
+                    parameter("exc_type", false),
+                    ParameterInfo {
+                        type_info: || TypeInfo::with_module("mymod.MyError", "mymod".into()),
+                        ..parameter("exc", true)
+                    },
+                    parameter("tb", false),
+                ]
+            });
+        let exit = context_manager_method("__exit__", false, &*EXIT_PARAMS, || {
+            TypeInfo::with_module("typing.Literal[False]", "typing".into())
+        });
+        assert_eq!(
+            exit.to_string(),
+            "    def __exit__(self, exc_type: type[builtins.BaseException] | None, exc: mymod.MyError, tb: types.TracebackType | None) -> typing.Literal[False]: ...\n"
+        );
+    }
+
+    #[test]
+    fn constructor_styles() {
+        struct Styled<'a>(&'a MethodDef, ConstructorStyle);
+        impl fmt::Display for Styled<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.write(f, self.1, false)
+            }
+        }
+        static PARAMS: std::sync::LazyLock<[ParameterInfo; 1]> = std::sync::LazyLock::new(|| {
+            [ParameterInfo {
+                type_info: || TypeInfo::builtin("int"),
+                ..parameter("x", false)
+            }]
+        });
+        let new = MethodDef::from(&MethodInfo {
+            name: "__new__",
+            parameters: &*PARAMS,
+            r#return: || TypeInfo::unqualified("Point"),
+            doc: "",
+            r#type: MethodType::New,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: None,
+        });
+        assert_eq!(
+            Styled(&new, ConstructorStyle::New).to_string(),
+            "    def __new__(cls, x: builtins.int) -> Self: ...\n"
+        );
+        assert_eq!(
+            Styled(&new, ConstructorStyle::Init).to_string(),
+            "    def __init__(self, x: builtins.int) -> None: ...\n"
+        );
+        assert_eq!(
+            Styled(&new, ConstructorStyle::Both).to_string(),
+            "    def __new__(cls, x: builtins.int) -> Self: ...\n    def __init__(self, x: builtins.int) -> None: ...\n"
+        );
+        assert_eq!(
+            ConstructorStyle::Init.return_type(),
+            TypeInfo::none(),
+            "`Self` is not imported only for `__init__`"
+        );
+    }
+
+    #[test]
+    fn annotated_cls() {
+        struct Annotated<'a>(&'a MethodDef);
+        impl fmt::Display for Annotated<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.write(f, ConstructorStyle::Both, true)
+            }
+        }
+        let method = |name, r#type| {
+            MethodDef::new(
+                name,
+                r#type,
+                Parameters::new(),
+                TypeInfo::unqualified("Self"),
+            )
+        };
+        let from_json = method("from_json", MethodType::Class);
+        assert_eq!(
+            Annotated(&from_json).to_string(),
+            "    @classmethod\n    def from_json(cls: type[Self]) -> Self: ...\n"
+        );
+        assert!(from_json
+            .cls_annotation(true)
+            .unwrap()
+            .import
+            .iter()
+            .any(|import| matches!(import, ImportRef::Type(ty) if ty.name == "Self")));
+        assert_eq!(
+            Annotated(&method("__new__", MethodType::New)).to_string(),
+            "    def __new__(cls: type[Self]) -> Self: ...\n    def __init__(self) -> None: ...\n"
+        );
+        assert_eq!(
+            Annotated(&method("size", MethodType::Instance)).to_string(),
+            "    def size(self) -> Self: ...\n"
+        );
+        assert_eq!(
+            method("size", MethodType::Static).cls_annotation(true),
+            None
+        );
+    }
+
+    #[test]
+    fn annotate_cls_in_pyproject() {
+        use crate::pyproject::StubGen;
+        let stub_gen: StubGen = toml::from_str("annotate-cls = true").unwrap();
+        assert_eq!(stub_gen.annotate_cls, Some(true));
+    }
 }
@@ -1,24 +1,57 @@
+use crate::config::Config;
 use crate::generate::*;
 use crate::stub_type::ImportRef;
 use itertools::Itertools;
 use std::{
     any::TypeId,
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet},
     fmt,
 };
 
+/// Configure whether `__all__` is emitted in generated modules. Enabled by default.
+///
+/// This is set from `tool.pyo3-stub-gen.generate-all` in `pyproject.toml`.
+pub fn set_generate_dunder_all(enabled: bool) {
+    Config::update(|config| config.generate_dunder_all = enabled);
+}
+
+/// Whether `__all__` is emitted in generated modules.
+pub fn generate_dunder_all() -> bool {
+    Config::current().generate_dunder_all
+}
+
 /// Type info for a Python (sub-)module. This corresponds to a single `*.pyi` file.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[non_exhaustive]
 pub struct Module {
     pub doc: String,
     pub class: BTreeMap<TypeId, ClassDef>,
     pub enum_: BTreeMap<TypeId, EnumDef>,
-    pub function: BTreeMap<&'static str, Vec<FunctionDef>>,
+    /// Order of the classes and enums, which are sorted by name after the listed ones
+    pub class_order: Vec<TypeId>,
+    /// Exception classes created by [crate::create_exception], rendered before the other classes
+    pub exceptions: BTreeMap<&'static str, ExceptionDef>,
+    pub function: BTreeMap<Cow<'static, str>, Vec<FunctionDef>>,
     pub variables: BTreeMap<&'static str, VariableDef>,
+    /// Helper symbols synthesized while [StubInfo](crate::StubInfo) is built, e.g. type aliases of unions
+    pub synthesized: BTreeMap<String, SynthesizedDef>,
     pub name: String,
     pub default_module_name: String,
     /// Direct submodules of this module.
     pub submodules: BTreeSet<String>,
+    /// Whether the stub ends with a `__getattr__` fallback for the attributes created at runtime
+    pub attr_fallback: bool,
+    /// Whether the stub is marked as partial by a `# partial` comment
+    pub partial: bool,
+    /// Modules whose public names are re-exported by `from module import *`
+    pub reexport_all: BTreeSet<String>,
+    /// Comment lines replacing the default banner at the top of the stub, e.g. an SPDX license identifier.
+    /// An empty header renders no banner at all.
+    pub header: Option<String>,
+    /// Configuration this module is rendered with, which is [StubInfo::config](crate::StubInfo::config)
+    /// of the [StubInfo](crate::StubInfo) it is built into, or [Config::current] if `None`
+    pub config: Option<Config>,
 }
 
 impl Import for Module {
@@ -30,71 +63,168 @@ impl Import for Module {
         for enum_ in self.enum_.values() {
             imports.extend(enum_.import());
         }
+        for exception in self.exceptions.values() {
+            imports.extend(exception.import());
+        }
         for function in self.function.values().flatten() {
             imports.extend(function.import());
         }
+        for variable in self.variables.values() {
+            imports.extend(variable.import());
+        }
+        for symbol in self.synthesized.values() {
+            imports.extend(symbol.import());
+        }
         imports
     }
 }
 
-impl fmt::Display for Module {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "# This file is automatically generated by pyo3_stub_gen")?;
-        writeln!(f, "# ruff: noqa: E501, F401")?;
-        if !self.doc.is_empty() {
-            docstring::write_docstring(f, &self.doc, "")?;
+impl Module {
+    /// Empty module `name`, where [ModuleRef::Default](crate::api::ModuleRef::Default) refers to `default_module_name`
+    pub fn new(name: impl Into<String>, default_module_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            default_module_name: default_module_name.into(),
+            ..Default::default()
         }
-        writeln!(f)?;
+    }
+
+    /// Import statements of the stub file of this module
+    pub fn import_block(&self) -> ImportBlock {
+        let mut block = ImportBlock::new(&self.name);
         let mut imports = self.import();
-        let any_overloaded = self.function.values().any(|functions| functions.len() > 1);
-        if any_overloaded {
+        if self.function.values().any(|functions| functions.len() > 1) {
             imports.insert("typing".into());
         }
-
-        // To gather `from submod import A, B, C` style imports
-        let mut type_ref_grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
-        for import_ref in imports.into_iter().sorted() {
+        if self.has_attr_fallback() {
+            imports.insert("builtins".into());
+            imports.insert("typing".into());
+        }
+        for import_ref in imports {
             match import_ref {
                 ImportRef::Module(module_ref) => {
                     let name = module_ref.get().unwrap_or(&self.default_module_name);
                     if name != self.name {
-                        writeln!(f, "import {name}")?;
+                        block.import(name);
                     }
                 }
                 ImportRef::Type(type_ref) => {
                     let module_name = type_ref.module.get().unwrap_or(&self.default_module_name);
                     if module_name != self.name {
-                        type_ref_grouped
-                            .entry(module_name.to_string())
-                            .or_default()
-                            .push(type_ref.name);
+                        block.import_from(module_name, &type_ref.name);
                     }
                 }
             }
         }
-        for (module_name, type_names) in type_ref_grouped {
-            let mut sorted_type_names = type_names.clone();
-            sorted_type_names.sort();
-            writeln!(
-                f,
-                "from {} import {}",
-                module_name,
-                sorted_type_names.join(", ")
-            )?;
-        }
         for submod in &self.submodules {
-            writeln!(f, "from . import {submod}")?;
+            block.import_from(".", submod);
+        }
+        for module in &self.reexport_all {
+            block.reexport_all(module);
+        }
+        block
+    }
+
+    /// Classes and enums in the order they are rendered, i.e. [Module::class_order] followed by the classes and then the enums sorted by name
+    pub fn rendered_order(&self) -> Vec<TypeId> {
+        let listed = self
+            .class_order
+            .iter()
+            .filter(|id| self.class.contains_key(id) || self.enum_.contains_key(id))
+            .copied();
+        let classes = self
+            .class
+            .iter()
+            .sorted_by_key(|(_, class)| &class.name)
+            .map(|(id, _)| *id);
+        let enums = self
+            .enum_
+            .iter()
+            .sorted_by_key(|(_, enum_)| enum_.name)
+            .map(|(id, _)| *id);
+        listed.chain(classes).chain(enums).unique().collect()
+    }
+
+    /// Public names defined in this module, i.e. the contents of `__all__`, sorted alphabetically.
+    ///
+    /// Variant classes of complex enums are attributes of their enum, not of the module, so they are not listed.
+    pub fn public_names(&self) -> Vec<&str> {
+        self.class
+            .values()
+            .map(|class| class.name.as_ref())
+            .chain(self.enum_.values().map(|enum_| enum_.name))
+            .chain(self.exceptions.keys().copied())
+            .chain(self.function.keys().map(AsRef::as_ref))
+            .chain(self.variables.keys().copied())
+            .chain(self.synthesized.keys().map(String::as_str))
+            .chain(self.submodules.iter().map(String::as_str))
+            .filter(|name| !name.starts_with('_'))
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Whether the `__getattr__` fallback is rendered, i.e. requested and not defined by the module itself
+    fn has_attr_fallback(&self) -> bool {
+        self.attr_fallback && !self.function.contains_key("__getattr__")
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let _config = self.config.map(Config::scope);
+        match &self.header {
+            Some(header) => {
+                for line in header.lines() {
+                    // Lines are commented out unless they are comments already
+                    if line.is_empty() || line.starts_with('#') {
+                        writeln!(f, "{line}")?;
+                    } else {
+                        writeln!(f, "# {line}")?;
+                    }
+                }
+            }
+            None => writeln!(f, "# This file is automatically generated by pyo3_stub_gen")?,
+        }
+        writeln!(f, "# ruff: noqa: E501, F401")?;
+        if self.partial {
+            writeln!(f, "# partial")?;
+        }
+        if !self.doc.is_empty() {
+            docstring::write_docstring(f, &self.doc, "")?;
         }
         writeln!(f)?;
+        write!(f, "{}", self.import_block())?;
+        writeln!(f)?;
 
+        let public_names = self.public_names();
+        if generate_dunder_all() && !public_names.is_empty() {
+            writeln!(f, "__all__ = [")?;
+            for name in public_names {
+                writeln!(f, "    \"{name}\",")?;
+            }
+            writeln!(f, "]")?;
+            writeln!(f)?;
+        }
+
+        for symbol in self.synthesized.values() {
+            write!(f, "{symbol}")?;
+        }
+        if !self.synthesized.is_empty() {
+            writeln!(f)?;
+        }
         for var in self.variables.values() {
             writeln!(f, "{var}")?;
         }
-        for class in self.class.values().sorted_by_key(|class| class.name) {
-            write!(f, "{class}")?;
+        for exception in self.exceptions.values() {
+            write!(f, "{exception}")?;
         }
-        for enum_ in self.enum_.values().sorted_by_key(|class| class.name) {
-            write!(f, "{enum_}")?;
+        for id in self.rendered_order() {
+            if let Some(class) = self.class.get(&id) {
+                write!(f, "{class}")?;
+            } else if let Some(enum_) = self.enum_.get(&id) {
+                write!(f, "{enum_}")?;
+            }
         }
         for functions in self.function.values() {
             let overloaded = functions.len() > 1;
@@ -105,6 +235,9 @@ impl fmt::Display for Module {
                 write!(f, "{function}")?;
             }
         }
+        if self.has_attr_fallback() {
+            writeln!(f, "def __getattr__(name: builtins.str) -> typing.Any: ...")?;
+        }
         Ok(())
     }
 }
@@ -112,14 +245,18 @@ impl fmt::Display for Module {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stub_type::{self_import_strategy, set_self_import_strategy, SelfImportStrategy};
+    use crate::stub_type::SelfImportStrategy;
     use crate::TypeInfo;
     use indexmap::IndexMap;
     use std::any::TypeId;
 
     fn render_new_method_module() -> String {
+        render_method_module("__new__", MethodType::New, TypeInfo::self_type())
+    }
+
+    fn render_method_module(name: &'static str, r#type: MethodType, r#return: TypeInfo) -> String {
         let mut class = ClassDef {
-            name: "Foo",
+            name: "Foo".into(),
             doc: "",
             attrs: Vec::new(),
             getter_setters: IndexMap::new(),
@@ -129,34 +266,48 @@ mod tests {
             match_args: None,
             subclass: false,
             is_abstract: false,
+            dataclass: None,
+            slots: None,
+            flag: None,
+            total_ordering: false,
+            deprecated: None,
+            qualname: QualName::default(),
         };
         class
             .methods
-            .entry("__new__".to_string())
+            .entry(name.to_string())
             .or_default()
             .push(MethodDef {
-                name: "__new__",
+                name: name.into(),
                 parameters: Parameters::new(),
-                r#return: TypeInfo::self_type(),
+                r#return,
                 doc: "",
-                r#type: MethodType::New,
+                r#type,
                 is_async: false,
                 deprecated: None,
                 type_ignored: None,
                 is_abstract: false,
+                is_override: false,
+                qualname: QualName::default(),
+                source_location: None,
             });
 
-        let mut module = Module::default();
-        module.name = "foo".into();
-        module.default_module_name = "foo".into();
+        let mut module = Module {
+            name: "foo".into(),
+            default_module_name: "foo".into(),
+            ..Default::default()
+        };
         module.class.insert(TypeId::of::<()>(), class);
         module.to_string()
     }
 
     #[test]
     fn new_method_uses_self_return_type() {
-        let original = self_import_strategy();
-        set_self_import_strategy(SelfImportStrategy::Typing);
+        let _config = Config {
+            self_import_strategy: SelfImportStrategy::Typing,
+            ..Config::current()
+        }
+        .scope();
         let rendered = render_new_method_module();
         assert!(
             rendered.contains("from typing import Self"),
@@ -166,18 +317,290 @@ mod tests {
             rendered.contains("def __new__(cls) -> Self"),
             "expected __new__ signature with Self, got:\n{rendered}"
         );
-        set_self_import_strategy(original);
     }
 
     #[test]
     fn new_method_switches_to_typing_extensions_when_configured() {
-        let original = self_import_strategy();
-        set_self_import_strategy(SelfImportStrategy::TypingExtensions);
+        let _config = Config {
+            self_import_strategy: SelfImportStrategy::TypingExtensions,
+            ..Config::current()
+        }
+        .scope();
         let rendered = render_new_method_module();
         assert!(
             rendered.contains("from typing_extensions import Self"),
             "expected typing_extensions import, got:\n{rendered}"
         );
-        set_self_import_strategy(original);
+    }
+
+    #[test]
+    fn builder_method_imports_self_conditionally() {
+        let _config = Config {
+            self_import_strategy: SelfImportStrategy::TypingExtensions,
+            ..Config::current()
+        }
+        .scope();
+        let rendered =
+            render_method_module("with_name", MethodType::Instance, TypeInfo::self_type());
+        assert!(
+            rendered.contains("from typing_extensions import Self"),
+            "expected typing_extensions import, got:\n{rendered}"
+        );
+        assert!(
+            rendered.contains("def with_name(self) -> Self: ..."),
+            "expected builder method returning Self, got:\n{rendered}"
+        );
+
+        let rendered = render_method_module(
+            "with_name",
+            MethodType::Instance,
+            TypeInfo::locally_defined("Foo", "foo".into()),
+        );
+        assert!(
+            !rendered.contains("import Self"),
+            "expected no import of Self, got:\n{rendered}"
+        );
+    }
+
+    fn function(name: &'static str) -> FunctionDef {
+        FunctionDef {
+            name: name.into(),
+            parameters: Parameters::new(),
+            r#return: TypeInfo::none(),
+            doc: "",
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            qualname: QualName::default(),
+            source_location: None,
+        }
+    }
+
+    #[test]
+    fn overridden_return_imports_are_deduplicated() {
+        let mut module = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        for name in ["load", "load_legacy"] {
+            let mut def = function(name);
+            def.r#return = TypeInfo::with_module("pandas.DataFrame", "pandas".into());
+            module.function.insert(name.into(), vec![def]);
+        }
+        module.function.get_mut("load_legacy").unwrap()[0].deprecated =
+            Some(crate::type_info::DeprecatedInfo {
+                since: None,
+                note: Some("use load"),
+            });
+        let rendered = module.to_string();
+        assert_eq!(rendered.matches("import pandas\n").count(), 1, "{rendered}");
+        assert!(rendered.contains("def load() -> pandas.DataFrame: ..."));
+        assert!(rendered.contains("def load_legacy() -> pandas.DataFrame:\n"));
+    }
+
+    #[test]
+    fn no_return_function() {
+        let mut module = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        let mut abort = function("abort");
+        abort.r#return = TypeInfo {
+            name: "typing.Never".to_string(),
+            import: HashSet::from(["typing".into()]),
+        };
+        abort.doc = "Raise an error with the message";
+        module.function.insert("abort".into(), vec![abort]);
+        let mut find = function("find");
+        find.r#return = TypeInfo::optional(TypeInfo::builtin("int"));
+        module.function.insert("find".into(), vec![find]);
+        assert_eq!(
+            module.to_string(),
+            r#"# This file is automatically generated by pyo3_stub_gen
+# ruff: noqa: E501, F401
+
+import builtins
+import typing
+
+__all__ = [
+    "abort",
+    "find",
+]
+
+def abort() -> typing.Never:
+    r"""
+    Raise an error with the message
+    """
+
+def find() -> typing.Optional[builtins.int]: ...
+
+"#
+        );
+    }
+
+    #[test]
+    fn dunder_all_lists_public_names() {
+        let mut module = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            submodules: BTreeSet::from(["sub".to_string()]),
+            ..Default::default()
+        };
+        module.class.insert(
+            TypeId::of::<()>(),
+            ClassDef {
+                subclass: true,
+                ..ClassDef::new("Point")
+            },
+        );
+        module.enum_.insert(
+            TypeId::of::<u8>(),
+            EnumDef {
+                name: "Color",
+                doc: "",
+                variants: &[("RED", "")],
+                methods: Vec::new(),
+                attrs: Vec::new(),
+                getters: Vec::new(),
+                setters: Vec::new(),
+                qualname: QualName::default(),
+            },
+        );
+        module.function.insert("add".into(), vec![function("add")]);
+        module
+            .function
+            .insert("_private_helper".into(), vec![function("_private_helper")]);
+        module.variables.insert(
+            "MAX_SIZE",
+            VariableDef {
+                name: "MAX_SIZE",
+                type_: TypeInfo::any(),
+                default: None,
+                doc: "",
+                deprecated: None,
+                type_ignored: None,
+                qualname: QualName::default(),
+            },
+        );
+
+        let rendered = module.to_string();
+        let without_all = {
+            let _config = Config {
+                generate_dunder_all: false,
+                ..Config::current()
+            }
+            .scope();
+            module.to_string()
+        };
+
+        assert_eq!(
+            rendered,
+            r#"# This file is automatically generated by pyo3_stub_gen
+# ruff: noqa: E501, F401
+
+import enum
+import typing
+
+from . import sub
+
+__all__ = [
+    "Color",
+    "MAX_SIZE",
+    "Point",
+    "add",
+    "sub",
+]
+
+MAX_SIZE: typing.Any
+class Point:
+    ...
+
+@typing.final
+class Color(enum.Enum):
+    RED = ...
+
+def _private_helper() -> None: ...
+
+def add() -> None: ...
+
+"#
+        );
+        assert!(!without_all.contains("__all__"));
+    }
+
+    #[test]
+    fn attr_fallback_and_partial() {
+        let mut module = Module {
+            name: "pkg._core".into(),
+            default_module_name: "pkg._core".into(),
+            attr_fallback: true,
+            partial: true,
+            ..Default::default()
+        };
+        module.function.insert("add".into(), vec![function("add")]);
+        assert_eq!(
+            module.to_string(),
+            r#"# This file is automatically generated by pyo3_stub_gen
+# ruff: noqa: E501, F401
+# partial
+
+import builtins
+import typing
+
+__all__ = [
+    "add",
+]
+
+def add() -> None: ...
+
+def __getattr__(name: builtins.str) -> typing.Any: ...
+"#
+        );
+
+        // The module's own `__getattr__` is kept instead
+        module
+            .function
+            .insert("__getattr__".into(), vec![function("__getattr__")]);
+        module.partial = false;
+        let rendered = module.to_string();
+        assert_eq!(rendered.matches("def __getattr__").count(), 1, "{rendered}");
+        assert!(rendered.contains("def __getattr__() -> None: ..."));
+        assert!(!rendered.contains("# partial"));
+    }
+
+    #[test]
+    fn custom_header() {
+        let mut module = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            doc: "Package docs".into(),
+            header: Some("SPDX-License-Identifier: MIT\n# Generated stubs, do not edit".into()),
+            ..Default::default()
+        };
+        module.function.insert("add".into(), vec![function("add")]);
+        assert_eq!(
+            module.to_string(),
+            r#"# SPDX-License-Identifier: MIT
+# Generated stubs, do not edit
+# ruff: noqa: E501, F401
+r"""
+Package docs
+"""
+
+
+__all__ = [
+    "add",
+]
+
+def add() -> None: ...
+
+"#
+        );
+
+        // An empty header suppresses the banner
+        module.header = Some(String::new());
+        assert!(module.to_string().starts_with("# ruff: noqa: E501, F401\n"));
     }
 }
@@ -0,0 +1,296 @@
+//! Renaming of modules by `[tool.pyo3-stub-gen.module-rename]`, e.g. when the extension registered
+//! as `rust_core` is shipped as `mypkg._native`.
+
+use crate::{
+    generate::*,
+    stub_type::{ImportRef, ModuleRef},
+    TypeInfo,
+};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+/// Mapping from module names to new names, where an entry also renames the submodules
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ModuleRenames(BTreeMap<String, String>);
+
+impl ModuleRenames {
+    pub(crate) fn new(renames: BTreeMap<String, String>) -> Self {
+        Self(renames)
+    }
+
+    /// New name of `module` by the longest matching entry, if renamed
+    pub(crate) fn rename(&self, module: &str) -> Option<String> {
+        self.0
+            .iter()
+            .filter_map(|(old, new)| {
+                let rest = module.strip_prefix(old.as_str())?;
+                (rest.is_empty() || rest.starts_with('.'))
+                    .then(|| (old.len(), format!("{new}{rest}")))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, renamed)| renamed)
+    }
+
+    /// Rename `modules` and `default_module_name`, and rewrite the references to renamed modules.
+    pub(crate) fn apply(
+        &self,
+        modules: &mut BTreeMap<String, Module>,
+        default_module_name: &mut String,
+    ) -> Result<()> {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        let mut renamed: BTreeMap<String, (String, Module)> = BTreeMap::new();
+        for (name, mut module) in std::mem::take(modules) {
+            let new_name = self.rename(&name).unwrap_or_else(|| name.clone());
+            if let Some((other, _)) = renamed.get(&new_name) {
+                bail!("Modules `{other}` and `{name}` both become `{new_name}` by `module-rename`");
+            }
+            module.for_each_type_mut(&mut |ty| self.rewrite(ty, default_module_name));
+            for symbol in module.synthesized.values_mut() {
+                let SynthesizedKind::TypeAlias(ty) = &mut symbol.kind;
+                self.rewrite(ty, default_module_name);
+            }
+            module.name = new_name.clone();
+            renamed.insert(new_name, (name, module));
+        }
+        if let Some(new_name) = self.rename(default_module_name) {
+            *default_module_name = new_name;
+        }
+        *modules = renamed
+            .into_iter()
+            .map(|(name, (_, mut module))| {
+                module.default_module_name = default_module_name.clone();
+                (name, module)
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Rewrite the imports of `ty` from renamed modules, and the module names qualifying its name
+    pub(crate) fn rewrite(&self, ty: &mut TypeInfo, default_module_name: &str) {
+        let mut qualifiers = Vec::new();
+        ty.import = std::mem::take(&mut ty.import)
+            .into_iter()
+            .map(|import| match import {
+                ImportRef::Module(module) => {
+                    let name = module.get().unwrap_or(default_module_name);
+                    match self.rename(name) {
+                        Some(new_name) => {
+                            qualifiers.push((name.to_string(), new_name.clone()));
+                            ImportRef::Module(rename_ref(module, new_name))
+                        }
+                        None => ImportRef::Module(module),
+                    }
+                }
+                ImportRef::Type(mut type_ref) => {
+                    if let Some(new_name) = type_ref.module.get().and_then(|name| self.rename(name))
+                    {
+                        type_ref.module = ModuleRef::Named(new_name);
+                    }
+                    ImportRef::Type(type_ref)
+                }
+            })
+            .collect();
+        // Longer module names first, so that `pkg.sub.` is not rewritten by `pkg.`
+        qualifiers.sort_by_key(|(old, _)| std::cmp::Reverse(old.len()));
+        for (old, new) in qualifiers {
+            ty.name = rename_qualifier(&ty.name, &old, &new);
+        }
+    }
+}
+
+/// Module name with dashes replaced by underscores, e.g. `my_pkg.core` for a module named after the `my-pkg` crate
+pub(crate) fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Keywords of Python, which cannot be used as identifiers
+pub(crate) const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Check that `name` is a dotted Python identifier, e.g. `mypkg._core`
+pub(crate) fn validate(name: &str) -> Result<()> {
+    for component in name.split('.') {
+        if component.is_empty() {
+            bail!("it has an empty component");
+        }
+        if component.starts_with(|c: char| c.is_ascii_digit()) {
+            bail!("`{component}` starts with a digit");
+        }
+        if let Some(c) = component
+            .chars()
+            .find(|&c| !(c.is_alphanumeric() || c == '_'))
+        {
+            bail!("`{component}` contains `{c}`");
+        }
+        if KEYWORDS.contains(&component) {
+            bail!("`{component}` is a Python keyword");
+        }
+    }
+    Ok(())
+}
+
+/// The default module keeps referring to the default module, which is renamed as a whole
+fn rename_ref(module: ModuleRef, new_name: String) -> ModuleRef {
+    match module {
+        ModuleRef::Named(_) => ModuleRef::Named(new_name),
+        ModuleRef::Default => ModuleRef::Default,
+    }
+}
+
+/// Replace the qualifier `old.` in a type expression, e.g. `builtins.list[old.Item]`,
+/// skipping longer names like `other_old.Item` or `pkg.old.Item`.
+fn rename_qualifier(name: &str, old: &str, new: &str) -> String {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    let pattern = format!("{old}.");
+    let mut out = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(pos) = rest.find(&pattern) {
+        let before = rest[..pos].chars().last().or_else(|| out.chars().last());
+        out.push_str(&rest[..pos]);
+        if before.is_some_and(is_ident) {
+            out.push_str(&pattern);
+        } else {
+            out.push_str(new);
+            out.push('.');
+        }
+        rest = &rest[pos + pattern.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn renames() -> ModuleRenames {
+        ModuleRenames::new(BTreeMap::from([
+            ("rust_core".to_string(), "mypkg._native".to_string()),
+            ("rust_core.legacy".to_string(), "mypkg.compat".to_string()),
+        ]))
+    }
+
+    #[test_case("my_pkg._core", None ; "valid")]
+    #[test_case("1pkg.core", Some("`1pkg` starts with a digit") ; "digit")]
+    #[test_case("my pkg", Some("`my pkg` contains ` `") ; "space")]
+    #[test_case("mypkg..core", Some("it has an empty component") ; "empty")]
+    #[test_case("mypkg.class", Some("`class` is a Python keyword") ; "keyword")]
+    fn validate_name(name: &str, expected: Option<&str>) {
+        assert_eq!(
+            validate(name).err().map(|err| err.to_string()).as_deref(),
+            expected
+        );
+    }
+
+    #[test_case("rust_core", Some("mypkg._native") ; "exact")]
+    #[test_case("rust_core.sub", Some("mypkg._native.sub") ; "submodule")]
+    #[test_case("rust_core.legacy.v1", Some("mypkg.compat.v1") ; "longest prefix")]
+    #[test_case("rust_core_ext", None ; "longer name")]
+    #[test_case("other", None ; "other")]
+    fn rename(module: &str, expected: Option<&str>) {
+        assert_eq!(renames().rename(module).as_deref(), expected);
+    }
+
+    fn module(name: &str, parameter_type: TypeInfo) -> Module {
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword.push(Parameter::new(
+            "value",
+            crate::type_info::ParameterKind::PositionalOrKeyword,
+            parameter_type,
+        ));
+        let mut module = Module::new(name, "rust_core");
+        module.function.insert(
+            "use_foo".into(),
+            vec![FunctionDef::new("use_foo", parameters, TypeInfo::none())],
+        );
+        module
+    }
+
+    #[test]
+    fn rename_modules() -> Result<()> {
+        let mut modules = BTreeMap::from([
+            (
+                "rust_core".to_string(),
+                module(
+                    "rust_core",
+                    TypeInfo::locally_defined("Foo", "rust_core.sub".into()),
+                ),
+            ),
+            (
+                "other".to_string(),
+                module(
+                    "other",
+                    TypeInfo::with_module("rust_core.Bar", ModuleRef::Default),
+                ),
+            ),
+        ]);
+        let mut default_module_name = "rust_core".to_string();
+        renames().apply(&mut modules, &mut default_module_name)?;
+
+        assert_eq!(default_module_name, "mypkg._native");
+        assert_eq!(
+            modules.keys().collect::<Vec<_>>(),
+            ["mypkg._native", "other"]
+        );
+        let native = &modules["mypkg._native"];
+        assert_eq!(native.name, "mypkg._native");
+        let stub = native.to_string();
+        assert!(stub.contains("from mypkg._native.sub import Foo"), "{stub}");
+
+        let other = &modules["other"];
+        assert_eq!(other.default_module_name, "mypkg._native");
+        let ty = &other.function["use_foo"][0]
+            .parameters
+            .positional_or_keyword[0]
+            .type_info;
+        assert_eq!(ty.name, "mypkg._native.Bar");
+        assert!(ty.import.contains(&ImportRef::Module(ModuleRef::Default)));
+        let stub = other.to_string();
+        assert!(stub.contains("import mypkg._native\n"), "{stub}");
+        Ok(())
+    }
+
+    #[test]
+    fn collision() {
+        let mut modules = BTreeMap::from([
+            (
+                "mypkg._native".to_string(),
+                Module::new("mypkg._native", "rust_core"),
+            ),
+            (
+                "rust_core".to_string(),
+                Module::new("rust_core", "rust_core"),
+            ),
+        ]);
+        let err = renames()
+            .apply(&mut modules, &mut "rust_core".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Modules `mypkg._native` and `rust_core` both become `mypkg._native` by `module-rename`"
+        );
+    }
+
+    #[test]
+    fn in_pyproject() {
+        use crate::pyproject::StubGen;
+        for table in ["[module-rename]", "[module_rename]"] {
+            let stub_gen: StubGen =
+                toml::from_str(&format!("{table}\nrust_core = \"mypkg._native\"")).unwrap();
+            assert_eq!(
+                stub_gen.module_rename,
+                Some(BTreeMap::from([(
+                    "rust_core".to_string(),
+                    "mypkg._native".to_string()
+                )]))
+            );
+        }
+    }
+}
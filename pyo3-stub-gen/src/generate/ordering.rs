@@ -0,0 +1,233 @@
+//! Order of the items independent of the order [inventory] registers them in.
+//!
+//! Inventory registration order differs between linkers and machines, so that the items gathered from several
+//! submissions, i.e. `#[pymethods]` blocks of a class and overloads of a function, are put in a stable order.
+//! Items of a single submission, e.g. a `#[pymethods]` block or a `gen_methods_from_python!`, keep their order in the source.
+
+use crate::{generate::*, type_info::PyMethodsInfo};
+use itertools::Itertools;
+use std::{cmp::Reverse, collections::HashMap};
+
+/// `#[pymethods]` blocks ordered by the names of their items instead of their registration
+pub(crate) fn sorted_methods<'a>(
+    infos: impl IntoIterator<Item = &'a PyMethodsInfo>,
+) -> Vec<&'a PyMethodsInfo> {
+    infos
+        .into_iter()
+        .sorted_by_cached_key(|info| {
+            let members = info.attrs.iter().chain(info.getters).chain(info.setters);
+            let names: Vec<_> = members
+                .map(|member| member.name)
+                .chain(info.methods.iter().map(|method| method.name))
+                .collect();
+            ((info.struct_name)(), names)
+        })
+        .collect()
+}
+
+/// Key ordering overloads submitted separately by their source locations, the later first as [inventory] yields them
+/// on most platforms, so that an overload `submit!`ted after its `#[pyfunction]` or `#[pymethods]`, usually a narrower one,
+/// precedes it. Overloads of a single submission share the location, and keep their declared order by the stable sort.
+fn overload_key(source_location: Option<&str>) -> Reverse<Option<(&str, u32)>> {
+    Reverse(
+        source_location.map(|location| match location.rsplit_once(':') {
+            Some((file, line)) => (file, line.parse().unwrap_or(0)),
+            None => (location, 0),
+        }),
+    )
+}
+
+/// Sort `methods` by their names in the order the names first appear, and their overloads by [overload_key]
+fn sort_method_overloads(methods: &mut [MethodDef]) {
+    let mut first = HashMap::new();
+    for (index, method) in methods.iter().enumerate() {
+        first.entry(method.name.clone()).or_insert(index);
+    }
+    methods.sort_by_key(|method| (first[&method.name], overload_key(method.source_location)));
+}
+
+/// Sort members gathered from several `#[pymethods]` blocks by their names
+fn sort_members(members: &mut [MemberDef]) {
+    members.sort_by_key(|member| member.name);
+}
+
+impl Module {
+    /// Sort the overloads of each function and method, and the members of classes and enums gathered from several submissions
+    pub(crate) fn sort_overloads(&mut self) {
+        for functions in self.function.values_mut() {
+            functions.sort_by_key(|function| overload_key(function.source_location));
+        }
+        for class in self.class.values_mut() {
+            class.sort_overloads();
+        }
+        for enum_ in self.enum_.values_mut() {
+            sort_members(&mut enum_.attrs);
+            sort_members(&mut enum_.getters);
+            sort_members(&mut enum_.setters);
+            sort_method_overloads(&mut enum_.methods);
+        }
+    }
+}
+
+impl ClassDef {
+    fn sort_overloads(&mut self) {
+        for methods in self.methods.values_mut() {
+            methods.sort_by_key(|method| overload_key(method.source_location));
+        }
+        // Class attributes come from `#[pymethods]` blocks, while the fields of a class keep their declared order
+        self.attrs
+            .sort_by_key(|attr| attr.is_classattr.then_some(attr.name));
+        for class in &mut self.classes {
+            class.sort_overloads();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        type_info::{MemberInfo, MethodInfo, MethodType},
+        TypeInfo,
+    };
+
+    fn function(r#return: TypeInfo, source_location: &'static str) -> FunctionDef {
+        FunctionDef {
+            source_location: Some(source_location),
+            ..FunctionDef::new("load", Parameters::new(), r#return)
+        }
+    }
+
+    #[test]
+    fn overloads_in_any_registration_order() {
+        let render = |functions: Vec<FunctionDef>| {
+            let mut module = Module::new("pkg", "pkg");
+            module.function.insert("load".into(), functions);
+            module.sort_overloads();
+            module.to_string()
+        };
+        // The function, and a narrower overload submitted after it
+        let wide = || function(TypeInfo::builtin("float"), "src/lib.rs:10");
+        let narrow = || function(TypeInfo::builtin("int"), "src/lib.rs:14");
+        let rendered = render(vec![wide(), narrow()]);
+        assert_eq!(rendered, render(vec![narrow(), wide()]));
+        assert!(rendered.contains(
+            "@typing.overload\ndef load() -> builtins.int: ...\n\n@typing.overload\ndef load() -> builtins.float: ..."
+        ));
+
+        // Overloads of a single submission keep their declared order, even if it is not alphabetical
+        let rendered = render(vec![
+            function(TypeInfo::builtin("str"), "src/lib.rs:20"),
+            function(TypeInfo::builtin("bytes"), "src/lib.rs:20"),
+        ]);
+        assert!(rendered.contains(
+            "@typing.overload\ndef load() -> builtins.str: ...\n\n@typing.overload\ndef load() -> builtins.bytes: ..."
+        ));
+    }
+
+    #[test]
+    fn overloads_of_methods_and_members_of_enums() {
+        let method = |name: &'static str, source_location: &'static str| MethodDef {
+            source_location: Some(source_location),
+            ..MethodDef::new(
+                name,
+                MethodType::Instance,
+                Parameters::new(),
+                TypeInfo::none(),
+            )
+        };
+        let mut methods = vec![
+            method("b", "src/a.rs:1"),
+            method("a", "src/a.rs:2"),
+            method("b", "src/b.rs:1"),
+            method("a", "src/a.rs:2"),
+        ];
+        sort_method_overloads(&mut methods);
+        let order: Vec<_> = methods
+            .iter()
+            .map(|method| (method.name.as_ref(), method.source_location.unwrap()))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("b", "src/b.rs:1"),
+                ("b", "src/a.rs:1"),
+                ("a", "src/a.rs:2"),
+                ("a", "src/a.rs:2")
+            ]
+        );
+
+        let member = |name: &'static str, is_classattr: bool| MemberDef {
+            is_classattr,
+            ..MemberDef::new(name, TypeInfo::none())
+        };
+        let mut class = ClassDef::new("Foo");
+        class.attrs = vec![
+            member("y", false),
+            member("ZERO", true),
+            member("x", false),
+            member("ONE", true),
+        ];
+        class.sort_overloads();
+        let names: Vec<_> = class.attrs.iter().map(|attr| attr.name).collect();
+        assert_eq!(names, ["y", "x", "ONE", "ZERO"]);
+    }
+
+    #[test]
+    fn methods_blocks_in_any_registration_order() {
+        fn struct_id() -> std::any::TypeId {
+            std::any::TypeId::of::<()>()
+        }
+        fn struct_name() -> &'static str {
+            "Foo"
+        }
+        static GETTERS: [MemberInfo; 1] = [MemberInfo {
+            name: "value",
+            r#type: TypeInfo::none,
+            doc: "",
+            default: None,
+            deprecated: None,
+            item: false,
+            is_abstract: false,
+            also_as: &[],
+            is_classattr: false,
+            int_value: None,
+            type_ignored: None,
+        }];
+        static METHODS: [MethodInfo; 1] = [MethodInfo {
+            name: "reset",
+            parameters: &[],
+            r#return: TypeInfo::none,
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: None,
+        }];
+        let block =
+            |getters: &'static [MemberInfo], methods: &'static [MethodInfo]| PyMethodsInfo {
+                struct_id,
+                struct_name,
+                attrs: &[],
+                getters,
+                setters: &[],
+                methods,
+                source_location: None,
+            };
+        let getter = block(&GETTERS, &[]);
+        let method = block(&[], &METHODS);
+        // Blocks are identified by their getters here
+        let order = |infos: Vec<&PyMethodsInfo>| {
+            infos
+                .iter()
+                .map(|info| info.getters.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(sorted_methods([&getter, &method])), [0, 1]);
+        assert_eq!(order(sorted_methods([&method, &getter])), [0, 1]);
+    }
+}
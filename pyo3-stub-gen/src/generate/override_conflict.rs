@@ -0,0 +1,525 @@
+//! Detection of methods overriding a method of a base class with an incompatible signature.
+//!
+//! A subclass method taking a narrower parameter type or returning a wider type is reported by type checkers,
+//! e.g. `override` of mypy or `reportIncompatibleMethodOverride` of pyright, against the generated stubs.
+//! Such methods are marked by `# type: ignore[override]`, since the stubs only describe what the extension does.
+//!
+//! Types are compared only as far as their relation is known, i.e. unions, `typing.Any`, `builtins.object`,
+//! and the numeric types of `builtins`, so that other types such as classes are never reported.
+
+use crate::{generate::*, type_info::ParameterKind, TypeInfo};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Rule name of the comment appended to conflicting overrides
+const OVERRIDE_RULE: &str = "override";
+
+/// Methods which type checkers do not compare with those of the bases
+pub(crate) const EXEMPT: &[&str] = &["__init__", "__new__", "__init_subclass__", "__post_init__"];
+
+/// Append `# type: ignore[override]` to the methods of classes in `modules` which override a method
+/// of a base class in `modules` with an incompatible signature.
+pub(crate) fn ignore_override_conflicts(
+    modules: &mut BTreeMap<String, Module>,
+    default_module_name: &str,
+) {
+    let classes: Vec<(String, ClassDef)> = modules
+        .iter()
+        .flat_map(|(name, module)| {
+            module
+                .class
+                .values()
+                .map(move |class| (name.clone(), class.clone()))
+        })
+        .collect();
+    for (module_name, module) in modules.iter_mut() {
+        for class in module.class.values_mut() {
+            let ancestors = ancestors(class, module_name, &classes, default_module_name);
+            let class_name = class.name.clone();
+            for (name, methods) in class.methods.iter_mut() {
+                let [method] = methods.as_mut_slice() else {
+                    continue;
+                };
+                if EXEMPT.contains(&method.name.as_ref()) {
+                    continue;
+                }
+                // The nearest base defining the method is the one compared
+                let Some((base_module, base, base_methods)) = ancestors
+                    .iter()
+                    .find_map(|&(module, base)| Some((module, base, base.methods.get(name)?)))
+                else {
+                    continue;
+                };
+                let [base_method] = base_methods.as_slice() else {
+                    continue;
+                };
+                if let Some(reason) = incompatibility(method, base_method) {
+                    log::info!(
+                        "`{module_name}.{class_name}.{name}` overrides `{base_module}.{}.{name}` incompatibly since {reason}, and is marked by `# type: ignore[{OVERRIDE_RULE}]`",
+                        base.name
+                    );
+                    ignore_override(method);
+                }
+            }
+        }
+    }
+}
+
+/// Base classes of `class` defined in `classes`, and their bases in turn, nearest first
+pub(crate) fn ancestors<'a>(
+    class: &ClassDef,
+    module_name: &str,
+    classes: &'a [(String, ClassDef)],
+    default_module_name: &str,
+) -> Vec<(&'a str, &'a ClassDef)> {
+    let mut found: Vec<(&str, &ClassDef)> = Vec::new();
+    let mut visited = BTreeSet::from([(module_name, class.name.as_ref())]);
+    let mut queue: Vec<&ClassDef> = vec![class];
+    while !queue.is_empty() {
+        let mut next = Vec::new();
+        for class in queue {
+            for base in &class.bases {
+                let Some((module, base)) = classes.iter().find(|(module, candidate)| {
+                    hidden_base::refers_to(base, &candidate.name, module, default_module_name)
+                }) else {
+                    continue;
+                };
+                if visited.insert((module.as_str(), base.name.as_ref())) {
+                    found.push((module, base));
+                    next.push(base);
+                }
+            }
+        }
+        queue = next;
+    }
+    found
+}
+
+fn ignore_override(method: &mut MethodDef) {
+    match &mut method.type_ignored {
+        None => method.type_ignored = Some(TypeIgnore::Rules(vec![OVERRIDE_RULE.to_string()])),
+        Some(TypeIgnore::Rules(rules)) if !rules.iter().any(|rule| rule == OVERRIDE_RULE) => {
+            rules.push(OVERRIDE_RULE.to_string());
+        }
+        Some(_) => {}
+    }
+}
+
+/// Why `method` cannot be called in place of `base`, if so
+fn incompatibility<'a>(method: &'a MethodDef, base: &'a MethodDef) -> Option<String> {
+    if method.r#type != base.r#type {
+        return Some("its method type differs".to_string());
+    }
+    let ours = &method.parameters;
+    let theirs = &base.parameters;
+    let positional = |parameters: &'a Parameters| -> Vec<&'a Parameter> {
+        parameters
+            .positional_only
+            .iter()
+            .chain(&parameters.positional_or_keyword)
+            .collect()
+    };
+    let (ours_positional, theirs_positional) = (positional(ours), positional(theirs));
+    for (index, expected) in theirs_positional.iter().enumerate() {
+        match ours_positional.get(index) {
+            Some(parameter) => {
+                if expected.kind == ParameterKind::PositionalOrKeyword
+                    && parameter.name != expected.name
+                {
+                    return Some(format!(
+                        "parameter `{}` is renamed to `{}`",
+                        expected.name, parameter.name
+                    ));
+                }
+                if let Some(reason) = incompatible_parameter(parameter, expected) {
+                    return Some(reason);
+                }
+            }
+            None if ours.varargs.is_some() => {}
+            None => return Some(format!("parameter `{}` is missing", expected.name)),
+        }
+    }
+    if let Some(extra) = ours_positional
+        .iter()
+        .skip(theirs_positional.len())
+        .find(|parameter| parameter.default == ParameterDefault::None)
+    {
+        return Some(format!("parameter `{}` is required", extra.name));
+    }
+    for expected in &theirs.keyword_only {
+        let parameter = ours
+            .keyword_only
+            .iter()
+            .chain(&ours.positional_or_keyword)
+            .find(|parameter| parameter.name == expected.name);
+        match parameter {
+            Some(parameter) => {
+                if let Some(reason) = incompatible_parameter(parameter, expected) {
+                    return Some(reason);
+                }
+            }
+            None if ours.varkw.is_some() => {}
+            None => return Some(format!("parameter `{}` is missing", expected.name)),
+        }
+    }
+    if let Some(extra) = ours.keyword_only.iter().find(|parameter| {
+        parameter.default == ParameterDefault::None
+            && !theirs
+                .keyword_only
+                .iter()
+                .any(|expected| expected.name == parameter.name)
+    }) {
+        return Some(format!("parameter `{}` is required", extra.name));
+    }
+    if theirs.varargs.is_some() && ours.varargs.is_none() {
+        return Some("`*args` is missing".to_string());
+    }
+    if theirs.varkw.is_some() && ours.varkw.is_none() {
+        return Some("`**kwargs` is missing".to_string());
+    }
+    // Returns are covariant
+    if !accepts(&base.r#return, &method.r#return) {
+        return Some(format!(
+            "it returns `{}` instead of `{}`",
+            method.r#return, base.r#return
+        ));
+    }
+    None
+}
+
+/// Why `parameter` does not accept what is passed to `expected` of the base, if so
+fn incompatible_parameter(parameter: &Parameter, expected: &Parameter) -> Option<String> {
+    if expected.default != ParameterDefault::None && parameter.default == ParameterDefault::None {
+        return Some(format!("parameter `{}` lost its default", parameter.name));
+    }
+    if !accepts(&parameter.type_info, &expected.type_info) {
+        return Some(format!(
+            "parameter `{}` takes `{}` instead of `{}`",
+            parameter.name, parameter.type_info, expected.type_info
+        ));
+    }
+    None
+}
+
+/// Whether a value of type `ours` may be any value of type `theirs`, i.e. `theirs` is not known to be
+/// wider than `ours`. Every member of a union `theirs` must be a subtype of a member of `ours`.
+fn accepts(ours: &TypeInfo, theirs: &TypeInfo) -> bool {
+    let ours = union_members(&ours.name);
+    union_members(&theirs.name).iter().all(|theirs| {
+        ours.iter().any(|ours| is_subtype(theirs, ours))
+            // The relation to a type other than the known ones is not decided here
+            || !ours.iter().chain([theirs]).all(|member| is_known(member))
+    })
+}
+
+/// Numeric types of `builtins` in the order of the promotion of PEP 484, e.g. `int` is accepted as `float`
+const NUMERIC_TOWER: [&str; 4] = [
+    "builtins.bool",
+    "builtins.int",
+    "builtins.float",
+    "builtins.complex",
+];
+
+/// Types whose relation to each other is decided by [is_subtype]
+fn is_known(name: &str) -> bool {
+    matches!(
+        name,
+        "typing.Any" | "builtins.object" | "None" | "builtins.str" | "builtins.bytes"
+    ) || NUMERIC_TOWER.contains(&name)
+}
+
+/// Whether `sub` is a subtype of `sup`, or either of them is `typing.Any`
+fn is_subtype(sub: &str, sup: &str) -> bool {
+    if sub == sup || sub == "typing.Any" || sup == "typing.Any" || sup == "builtins.object" {
+        return true;
+    }
+    let rank = |name| NUMERIC_TOWER.iter().position(|numeric| *numeric == name);
+    matches!((rank(sub), rank(sup)), (Some(sub), Some(sup)) if sub <= sup)
+}
+
+/// Members of a union `A | B`, `typing.Optional[A]` or `typing.Union[A, B]`,
+/// splitting only at the top level of brackets
+fn union_members(name: &str) -> BTreeSet<&str> {
+    let name = name.trim();
+    if let Some(inner) = name
+        .strip_prefix("typing.Optional[")
+        .and_then(|inner| inner.strip_suffix(']'))
+    {
+        let mut members = union_members(inner);
+        members.insert("None");
+        return members;
+    }
+    if let Some(inner) = name
+        .strip_prefix("typing.Union[")
+        .and_then(|inner| inner.strip_suffix(']'))
+    {
+        return split_top_level(inner, ',')
+            .into_iter()
+            .flat_map(union_members)
+            .collect();
+    }
+    let members = split_top_level(name, '|');
+    if members.len() == 1 {
+        return BTreeSet::from([name]);
+    }
+    members.into_iter().flat_map(union_members).collect()
+}
+
+/// `name` split at `separator` outside of brackets
+fn split_top_level(name: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in name.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(name[start..index].trim());
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(name[start..].trim());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::MethodType;
+    use std::any::TypeId;
+
+    struct Base;
+    struct Derived;
+    struct Leaf;
+
+    fn method(name: &'static str, parameters: Vec<Parameter>) -> MethodDef {
+        let mut params = Parameters::new();
+        params.positional_or_keyword = parameters;
+        MethodDef::new(name, MethodType::Instance, params, TypeInfo::none())
+    }
+
+    fn parameter(name: &'static str, type_info: TypeInfo) -> Parameter {
+        Parameter::new(name, ParameterKind::PositionalOrKeyword, type_info)
+    }
+
+    fn class(name: &'static str, bases: Vec<TypeInfo>, methods: Vec<MethodDef>) -> ClassDef {
+        let mut class = ClassDef::new(name);
+        class.bases = bases;
+        for method in methods {
+            class.add_method(method);
+        }
+        class
+    }
+
+    fn module(classes: Vec<(TypeId, ClassDef)>) -> BTreeMap<String, Module> {
+        let mut module = Module::new("pkg", "pkg");
+        module.class.extend(classes);
+        BTreeMap::from([("pkg".to_string(), module)])
+    }
+
+    fn base_type() -> TypeInfo {
+        TypeInfo::locally_defined("Base", "pkg".into())
+    }
+
+    fn rendered(modules: &BTreeMap<String, Module>, id: TypeId, name: &str) -> String {
+        modules["pkg"].class[&id].methods[name][0].to_string()
+    }
+
+    #[test]
+    fn narrower_parameter() {
+        let mut modules = module(vec![
+            (
+                TypeId::of::<Base>(),
+                class(
+                    "Base",
+                    vec![],
+                    vec![
+                        method(
+                            "update",
+                            vec![parameter(
+                                "value",
+                                TypeInfo::builtin("int") | TypeInfo::builtin("str"),
+                            )],
+                        ),
+                        method("reset", vec![parameter("value", TypeInfo::builtin("int"))]),
+                    ],
+                ),
+            ),
+            (
+                TypeId::of::<Derived>(),
+                class(
+                    "Derived",
+                    vec![base_type()],
+                    vec![
+                        method("update", vec![parameter("value", TypeInfo::builtin("int"))]),
+                        method(
+                            "reset",
+                            vec![parameter(
+                                "value",
+                                TypeInfo::builtin("int") | TypeInfo::none(),
+                            )],
+                        ),
+                    ],
+                ),
+            ),
+        ]);
+        ignore_override_conflicts(&mut modules, "pkg");
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Derived>(), "update"),
+            "    def update(self, value: builtins.int) -> None: ...  # type: ignore[override]\n"
+        );
+        // Widening the parameter is compatible
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Derived>(), "reset"),
+            "    def reset(self, value: builtins.int | None) -> None: ...\n"
+        );
+    }
+
+    #[test]
+    fn inherited_through_bases() {
+        let mut modules = module(vec![
+            (
+                TypeId::of::<Base>(),
+                class(
+                    "Base",
+                    vec![],
+                    vec![method("run", vec![]), method("stop", vec![])],
+                ),
+            ),
+            (
+                TypeId::of::<Derived>(),
+                class("Derived", vec![base_type()], vec![]),
+            ),
+            (
+                TypeId::of::<Leaf>(),
+                class(
+                    "Leaf",
+                    vec![TypeInfo::locally_defined("Derived", "pkg".into())],
+                    vec![
+                        method("run", vec![parameter("fast", TypeInfo::builtin("bool"))]),
+                        method(
+                            "__init__",
+                            vec![parameter("fast", TypeInfo::builtin("bool"))],
+                        ),
+                        method("stop", vec![]),
+                    ],
+                ),
+            ),
+        ]);
+        ignore_override_conflicts(&mut modules, "pkg");
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Leaf>(), "run"),
+            "    def run(self, fast: builtins.bool) -> None: ...  # type: ignore[override]\n"
+        );
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Leaf>(), "__init__"),
+            "    def __init__(self, fast: builtins.bool) -> None: ...\n"
+        );
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Leaf>(), "stop"),
+            "    def stop(self) -> None: ...\n"
+        );
+    }
+
+    #[test]
+    fn existing_type_ignore() {
+        let mut overriding = method("run", vec![parameter("fast", TypeInfo::builtin("bool"))]);
+        overriding.type_ignored = Some(TypeIgnore::Rules(vec!["misc".to_string()]));
+        let mut modules = module(vec![
+            (
+                TypeId::of::<Base>(),
+                class("Base", vec![], vec![method("run", vec![])]),
+            ),
+            (
+                TypeId::of::<Derived>(),
+                class("Derived", vec![base_type()], vec![overriding]),
+            ),
+        ]);
+        ignore_override_conflicts(&mut modules, "pkg");
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Derived>(), "run"),
+            "    def run(self, fast: builtins.bool) -> None: ...  # type: ignore[misc,override]\n"
+        );
+    }
+
+    #[test]
+    fn union_members_in_brackets() {
+        assert_eq!(
+            union_members("dict[str, int | None] | None"),
+            BTreeSet::from(["dict[str, int | None]", "None"])
+        );
+        assert_eq!(
+            union_members("typing.Optional[typing.Union[builtins.int, dict[str, int]]]"),
+            BTreeSet::from(["builtins.int", "dict[str, int]", "None"])
+        );
+    }
+
+    fn optional_int() -> TypeInfo {
+        TypeInfo::with_module("typing.Optional[builtins.int]", "typing".into())
+    }
+
+    #[test]
+    fn parameter_types() {
+        let int = TypeInfo::builtin("int");
+        let float = TypeInfo::builtin("float");
+        // `Optional[int]` takes every `int`, but not the other way around
+        assert!(accepts(&optional_int(), &int));
+        assert!(!accepts(&int, &optional_int()));
+        assert!(accepts(&(int.clone() | TypeInfo::none()), &optional_int()));
+        // `float` takes `int` by the numeric promotion
+        assert!(accepts(&float, &int));
+        assert!(!accepts(&int, &float));
+        assert!(accepts(&TypeInfo::builtin("object"), &optional_int()));
+        // Classes may be related in ways not known here
+        let class = TypeInfo::locally_defined("Base", "pkg".into());
+        assert!(accepts(
+            &class,
+            &TypeInfo::locally_defined("Other", "pkg".into())
+        ));
+        assert!(accepts(&int, &class));
+    }
+
+    #[test]
+    fn covariant_return() {
+        let returning = |name: &'static str, r#return: TypeInfo| {
+            let mut method = method(name, vec![]);
+            method.r#return = r#return;
+            method
+        };
+        let mut modules = module(vec![
+            (
+                TypeId::of::<Base>(),
+                class(
+                    "Base",
+                    vec![],
+                    vec![
+                        returning("value", TypeInfo::builtin("float")),
+                        returning("count", TypeInfo::builtin("int")),
+                    ],
+                ),
+            ),
+            (
+                TypeId::of::<Derived>(),
+                class(
+                    "Derived",
+                    vec![base_type()],
+                    vec![
+                        returning("value", TypeInfo::builtin("int")),
+                        returning("count", optional_int()),
+                    ],
+                ),
+            ),
+        ]);
+        ignore_override_conflicts(&mut modules, "pkg");
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Derived>(), "value"),
+            "    def value(self) -> builtins.int: ...\n"
+        );
+        assert_eq!(
+            rendered(&modules, TypeId::of::<Derived>(), "count"),
+            "    def count(self) -> typing.Optional[builtins.int]: ...  # type: ignore[override]\n"
+        );
+    }
+}
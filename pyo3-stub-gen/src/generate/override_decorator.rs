@@ -0,0 +1,128 @@
+//! `@typing.override` of PEP 698 on methods overriding a method of a base class, by `emit-override-decorator`.
+//!
+//! Only bases generated in the same [StubInfo](crate::StubInfo) are inspected, so that a method of a builtin base
+//! such as `Exception` or `collections.abc.Mapping` is not marked.
+
+use crate::generate::{override_conflict::*, *};
+use std::collections::BTreeMap;
+
+/// Mark the methods of classes in `modules` overriding a method of a base class in `modules` by `@typing.override`
+pub(crate) fn mark_overrides(modules: &mut BTreeMap<String, Module>, default_module_name: &str) {
+    let classes: Vec<(String, ClassDef)> = modules
+        .iter()
+        .flat_map(|(name, module)| {
+            module
+                .class
+                .values()
+                .map(move |class| (name.clone(), class.clone()))
+        })
+        .collect();
+    for (module_name, module) in modules.iter_mut() {
+        for class in module.class.values_mut() {
+            let ancestors = ancestors(class, module_name, &classes, default_module_name);
+            for (name, methods) in class.methods.iter_mut() {
+                if EXEMPT.contains(&name.as_str())
+                    || !ancestors
+                        .iter()
+                        .any(|(_, base)| base.methods.contains_key(name))
+                {
+                    continue;
+                }
+                for method in methods {
+                    method.is_override = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{type_info::MethodType, TypeInfo};
+    use std::any::TypeId;
+
+    struct Base;
+    struct Derived;
+    struct Leaf;
+
+    fn class(name: &'static str, base: TypeInfo, methods: &[&'static str]) -> ClassDef {
+        let mut class = ClassDef::new(name);
+        class.bases = vec![base];
+        for name in methods {
+            class.add_method(MethodDef::new(
+                *name,
+                MethodType::Instance,
+                Parameters::new(),
+                TypeInfo::none(),
+            ));
+        }
+        class
+    }
+
+    #[test]
+    fn two_level_hierarchy() {
+        let mut module = Module::new("pkg", "pkg");
+        module.class.extend([
+            (
+                TypeId::of::<Base>(),
+                class(
+                    "Base",
+                    TypeInfo::builtin("Exception"),
+                    &["run", "stop", "__init__"],
+                ),
+            ),
+            (
+                TypeId::of::<Derived>(),
+                class(
+                    "Derived",
+                    TypeInfo::locally_defined("Base", "pkg".into()),
+                    &["stop", "pause"],
+                ),
+            ),
+            (
+                TypeId::of::<Leaf>(),
+                class(
+                    "Leaf",
+                    TypeInfo::locally_defined("Derived", "pkg".into()),
+                    &["run", "pause", "resume", "__init__", "with_traceback"],
+                ),
+            ),
+        ]);
+        let mut modules = BTreeMap::from([("pkg".to_string(), module)]);
+        mark_overrides(&mut modules, "pkg");
+        let module = &modules["pkg"];
+        let overrides = |id: TypeId| -> Vec<&str> {
+            module.class[&id]
+                .methods
+                .values()
+                .flatten()
+                .filter(|method| method.is_override)
+                .map(|method| method.name.as_ref())
+                .collect()
+        };
+        assert_eq!(overrides(TypeId::of::<Base>()), Vec::<&str>::new());
+        assert_eq!(overrides(TypeId::of::<Derived>()), ["stop"]);
+        // `with_traceback` of the builtin base `Exception` is not seen
+        assert_eq!(overrides(TypeId::of::<Leaf>()), ["run", "pause"]);
+
+        let rendered = module.to_string();
+        let typing = override_module();
+        assert!(
+            rendered.contains(&format!("import {typing}\n")),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains(&format!(
+                "class Leaf(Derived):
+    @{typing}.override
+    def run(self) -> None: ...
+    @{typing}.override
+    def pause(self) -> None: ...
+    def resume(self) -> None: ...
+"
+            )),
+            "{rendered}"
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    generate::Import,
+    generate::{annotate_deprecated_parameters, target_version, ContainerDefaultStyle, Import},
     stub_type::ImportRef,
     type_info::{ParameterDefault as ParameterDefaultInfo, ParameterInfo, ParameterKind},
     TypeInfo,
@@ -19,6 +19,7 @@ pub enum ParameterDefault {
 ///
 /// This struct represents a parameter at runtime during stub generation.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct Parameter {
     /// Parameter name
     pub name: &'static str,
@@ -28,11 +29,35 @@ pub struct Parameter {
     pub type_info: TypeInfo,
     /// Default value
     pub default: ParameterDefault,
+    /// Rendering of a list, dict, or set default given by `#[gen_stub(container_default = "...")]`,
+    /// instead of [container_default_style](crate::generate::container_default_style)
+    pub container_default: Option<ContainerDefaultStyle>,
+    /// Note of `#[gen_stub(deprecated_param(...))]`, empty if none is given
+    pub deprecated: Option<&'static str>,
 }
 
 impl Import for Parameter {
     fn import(&self) -> HashSet<ImportRef> {
-        self.type_info.import.clone()
+        let mut import = self.type_info.import.clone();
+        if self.deprecated.is_some() && annotate_deprecated_parameters() {
+            import.insert(annotated_module().into());
+            import.insert("typing_extensions".into());
+        }
+        import
+    }
+}
+
+impl Parameter {
+    /// Parameter without a default value
+    pub fn new(name: &'static str, kind: ParameterKind, type_info: TypeInfo) -> Self {
+        Self {
+            name,
+            kind,
+            type_info,
+            default: ParameterDefault::None,
+            container_default: None,
+            deprecated: None,
+        }
     }
 }
 
@@ -46,24 +71,70 @@ impl From<&ParameterInfo> for Parameter {
                 ParameterDefaultInfo::None => ParameterDefault::None,
                 ParameterDefaultInfo::Expr(f) => ParameterDefault::Expr(f()),
             },
+            container_default: info.container_default,
+            deprecated: info.deprecated,
+        }
+    }
+}
+
+impl Parameter {
+    /// Annotation of the parameter, wrapped in `typing.Annotated` with the deprecation if
+    /// [annotate_deprecated_parameters] is set
+    fn annotation(&self) -> String {
+        match self.deprecated {
+            Some(note) if annotate_deprecated_parameters() => format!(
+                "{}.Annotated[{}, typing_extensions.deprecated({})]",
+                annotated_module(),
+                self.type_info,
+                string_literal(note)
+            ),
+            _ => self.type_info.to_string(),
         }
     }
 }
 
+/// Module providing `Annotated`, which is `typing` since Python 3.9
+fn annotated_module() -> &'static str {
+    if target_version().has_annotated() {
+        "typing"
+    } else {
+        "typing_extensions"
+    }
+}
+
+/// Python string literal of `value` in double quotes
+fn string_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\x{:02x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
 impl fmt::Display for Parameter {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.kind {
             ParameterKind::VarPositional => {
-                write!(f, "*{}: {}", self.name, self.type_info)
+                write!(f, "*{}: {}", self.name, self.annotation())
             }
             ParameterKind::VarKeyword => {
-                write!(f, "**{}: {}", self.name, self.type_info)
+                write!(f, "**{}: {}", self.name, self.annotation())
             }
             _ => {
-                write!(f, "{}: {}", self.name, self.type_info)?;
-                match &self.default {
-                    ParameterDefault::None => Ok(()),
-                    ParameterDefault::Expr(expr) => write!(f, " = {}", expr),
+                write!(f, "{}: {}", self.name, self.annotation())?;
+                match self.rendered_default() {
+                    None => Ok(()),
+                    Some(default) => write!(f, " = {default}"),
                 }
             }
         }
@@ -102,20 +173,18 @@ impl Parameters {
 
     /// Build Parameters from a slice of ParameterInfo
     pub fn from_infos(infos: &[ParameterInfo]) -> Self {
-        let mut params = Self::new();
+        infos.iter().map(Parameter::from).collect()
+    }
 
-        for info in infos {
-            let param = Parameter::from(info);
-            match param.kind {
-                ParameterKind::PositionalOnly => params.positional_only.push(param),
-                ParameterKind::PositionalOrKeyword => params.positional_or_keyword.push(param),
-                ParameterKind::KeywordOnly => params.keyword_only.push(param),
-                ParameterKind::VarPositional => params.varargs = Some(param),
-                ParameterKind::VarKeyword => params.varkw = Some(param),
-            }
+    /// Add a parameter after the others of its kind
+    pub fn push(&mut self, param: Parameter) {
+        match param.kind {
+            ParameterKind::PositionalOnly => self.positional_only.push(param),
+            ParameterKind::PositionalOrKeyword => self.positional_or_keyword.push(param),
+            ParameterKind::KeywordOnly => self.keyword_only.push(param),
+            ParameterKind::VarPositional => self.varargs = Some(param),
+            ParameterKind::VarKeyword => self.varkw = Some(param),
         }
-
-        params
     }
 
     /// Iterate over all parameters in signature order
@@ -128,6 +197,16 @@ impl Parameters {
             .chain(self.varkw.iter())
     }
 
+    /// Iterate mutably over all parameters in signature order
+    pub fn iter_entries_mut(&mut self) -> impl Iterator<Item = &mut Parameter> {
+        self.positional_only
+            .iter_mut()
+            .chain(self.positional_or_keyword.iter_mut())
+            .chain(self.varargs.iter_mut())
+            .chain(self.keyword_only.iter_mut())
+            .chain(self.varkw.iter_mut())
+    }
+
     /// Check if there are no parameters at all
     pub fn is_empty(&self) -> bool {
         self.positional_only.is_empty()
@@ -138,6 +217,16 @@ impl Parameters {
     }
 }
 
+impl FromIterator<Parameter> for Parameters {
+    fn from_iter<I: IntoIterator<Item = Parameter>>(iter: I) -> Self {
+        let mut params = Self::new();
+        for param in iter {
+            params.push(param);
+        }
+        params
+    }
+}
+
 impl Default for Parameters {
     fn default() -> Self {
         Self::new()
@@ -194,6 +283,7 @@ impl fmt::Display for Parameters {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{config::Config, generate::TargetVersion};
 
     #[test]
     fn test_positional_only() {
@@ -204,12 +294,16 @@ mod tests {
                     kind: ParameterKind::PositionalOnly,
                     type_info: TypeInfo::builtin("int"),
                     default: ParameterDefault::None,
+                    container_default: None,
+                    deprecated: None,
                 },
                 Parameter {
                     name: "y",
                     kind: ParameterKind::PositionalOnly,
                     type_info: TypeInfo::builtin("int"),
                     default: ParameterDefault::None,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             ..Default::default()
@@ -226,6 +320,8 @@ mod tests {
                 kind: ParameterKind::KeywordOnly,
                 type_info: TypeInfo::builtin("str"),
                 default: ParameterDefault::Expr("None".to_string()),
+                container_default: None,
+                deprecated: None,
             }],
             ..Default::default()
         };
@@ -241,6 +337,8 @@ mod tests {
                 kind: ParameterKind::PositionalOnly,
                 type_info: TypeInfo::builtin("str"),
                 default: ParameterDefault::None,
+                container_default: None,
+                deprecated: None,
             }],
             keyword_only: vec![
                 Parameter {
@@ -248,12 +346,16 @@ mod tests {
                     kind: ParameterKind::KeywordOnly,
                     type_info: TypeInfo::builtin("int"),
                     default: ParameterDefault::Expr("3".to_string()),
+                    container_default: None,
+                    deprecated: None,
                 },
                 Parameter {
                     name: "timeout",
                     kind: ParameterKind::KeywordOnly,
                     type_info: TypeInfo::builtin("float"),
                     default: ParameterDefault::None,
+                    container_default: None,
+                    deprecated: None,
                 },
             ],
             ..Default::default()
@@ -265,6 +367,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deprecated_annotation() {
+        let param = Parameter {
+            deprecated: Some("use \"size\"\nor C:\\size"),
+            ..Parameter::new(
+                "old_size",
+                ParameterKind::PositionalOrKeyword,
+                TypeInfo::builtin("int"),
+            )
+        };
+        let config = Config {
+            annotate_deprecated_parameters: true,
+            ..Config::current()
+        };
+        {
+            let _config = config.scope();
+            assert_eq!(
+                param.to_string(),
+                r#"old_size: typing.Annotated[builtins.int, typing_extensions.deprecated("use \"size\"\nor C:\\size")]"#
+            );
+        }
+        let _config = Config {
+            target_version: TargetVersion::Py38,
+            ..config
+        }
+        .scope();
+        assert!(param
+            .to_string()
+            .starts_with("old_size: typing_extensions.Annotated[builtins.int, "));
+        assert!(!param.import().contains(&"typing".into()));
+    }
+
     #[test]
     fn test_varargs_kwargs() {
         let params = Parameters {
@@ -273,12 +407,16 @@ mod tests {
                 kind: ParameterKind::VarPositional,
                 type_info: TypeInfo::builtin("str"),
                 default: ParameterDefault::None,
+                container_default: None,
+                deprecated: None,
             }),
             varkw: Some(Parameter {
                 name: "kwargs",
                 kind: ParameterKind::VarKeyword,
                 type_info: TypeInfo::any(),
                 default: ParameterDefault::None,
+                container_default: None,
+                deprecated: None,
             }),
             ..Default::default()
         };
@@ -0,0 +1,241 @@
+//! Pickling protocol of classes with `#[gen_stub(pickle_state = "...")]`.
+//!
+//! `__getstate__` and `__setstate__` are usually implemented returning or taking `PyObject`, rendered as `typing.Any`,
+//! so that they are annotated by the declared state instead, and synthesized if missing.
+
+use crate::{generate::*, type_info::ParameterKind, TypeInfo};
+use std::{any::TypeId, collections::BTreeMap};
+
+/// Annotate `__getstate__`, `__setstate__`, and `__reduce__` of the classes in `states` by their state.
+///
+/// An annotation other than `typing.Any` or `object` differing from the state is replaced with a warning.
+/// `__reduce__` is only annotated if it is defined and rendered as `typing.Any`.
+pub(crate) fn add_pickle_states(
+    modules: &mut BTreeMap<String, Module>,
+    states: &BTreeMap<TypeId, TypeInfo>,
+) {
+    for (module_name, module) in modules.iter_mut() {
+        for (id, class) in module.class.iter_mut() {
+            let Some(state) = states.get(id) else {
+                continue;
+            };
+            let qualname = format!("{module_name}.{}", class.name);
+            set_getstate(class, state, &qualname);
+            set_setstate(class, state, &qualname);
+            if let Some(methods) = class.methods.get_mut("__reduce__") {
+                for method in methods {
+                    if is_any(&method.r#return) {
+                        method.r#return = reduce_value();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether `ty` tells nothing about the state, e.g. `PyObject` rendered as `typing.Any`
+fn is_any(ty: &TypeInfo) -> bool {
+    matches!(normalized(&ty.name).as_str(), "Any" | "object")
+}
+
+/// `name` without whitespace and the `builtins.` and `typing.` qualifiers,
+/// so that `tuple[int, str]` given by `pickle_state` equals `builtins.tuple[builtins.int, builtins.str]` rendered from Rust
+fn normalized(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(c) = rest.chars().next() {
+        let at_name = !out.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '.');
+        if let Some(unqualified) = ["builtins.", "typing."]
+            .iter()
+            .find_map(|qualifier| rest.strip_prefix(qualifier))
+            .filter(|_| at_name)
+        {
+            rest = unqualified;
+            continue;
+        }
+        if !c.is_whitespace() {
+            out.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+/// `tuple[typing.Any, ...]` returned by `__reduce__`
+fn reduce_value() -> TypeInfo {
+    let mut ty = TypeInfo::builtin("tuple[typing.Any, ...]");
+    ty.import.insert("typing".into());
+    ty
+}
+
+/// Replace `ty` with `state`, warning if `ty` was annotated otherwise
+fn annotate(ty: &mut TypeInfo, state: &TypeInfo, what: &str) {
+    if !is_any(ty) && normalized(&ty.name) != normalized(&state.name) {
+        log::warn!(
+            "{what} is annotated as `{}`, which is replaced by the pickle state `{}`",
+            ty.name,
+            state.name
+        );
+    }
+    *ty = state.clone();
+}
+
+fn set_getstate(class: &mut ClassDef, state: &TypeInfo, qualname: &str) {
+    let Some(methods) = class.methods.get_mut("__getstate__") else {
+        class.add_method(MethodDef::new(
+            "__getstate__",
+            MethodType::Instance,
+            Parameters::new(),
+            state.clone(),
+        ));
+        return;
+    };
+    for method in methods {
+        annotate(
+            &mut method.r#return,
+            state,
+            &format!("The return of `{qualname}.__getstate__`"),
+        );
+    }
+}
+
+fn set_setstate(class: &mut ClassDef, state: &TypeInfo, qualname: &str) {
+    let Some(methods) = class.methods.get_mut("__setstate__") else {
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword = vec![Parameter::new(
+            "state",
+            ParameterKind::PositionalOrKeyword,
+            state.clone(),
+        )];
+        class.add_method(MethodDef::new(
+            "__setstate__",
+            MethodType::Instance,
+            parameters,
+            TypeInfo::none(),
+        ));
+        return;
+    };
+    for method in methods {
+        let parameters = &mut method.parameters;
+        match parameters
+            .positional_only
+            .iter_mut()
+            .chain(&mut parameters.positional_or_keyword)
+            .next()
+        {
+            Some(parameter) => annotate(
+                &mut parameter.type_info,
+                state,
+                &format!(
+                    "Parameter `{}` of `{qualname}.__setstate__`",
+                    parameter.name
+                ),
+            ),
+            None => log::warn!(
+                "`{qualname}.__setstate__` takes no positional parameter for the pickle state `{}`",
+                state.name
+            ),
+        }
+        method.r#return = TypeInfo::none();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> TypeInfo {
+        TypeInfo::unqualified("tuple[int, str, list[float]]")
+    }
+
+    /// Render the class `Record` defining `methods` with the pickle state [state]
+    fn render(methods: Vec<MethodDef>) -> String {
+        let mut class = ClassDef::new("Record");
+        for method in methods {
+            class.add_method(method);
+        }
+        let id = TypeId::of::<u8>();
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(id, class);
+        let mut modules = BTreeMap::from([("pkg".to_string(), module)]);
+        add_pickle_states(&mut modules, &BTreeMap::from([(id, state())]));
+        modules["pkg"].to_string()
+    }
+
+    fn method(name: &'static str, parameters: &[&'static str], r#return: TypeInfo) -> MethodDef {
+        let mut params = Parameters::new();
+        params.positional_or_keyword = parameters
+            .iter()
+            .map(|name| Parameter::new(name, ParameterKind::PositionalOrKeyword, TypeInfo::any()))
+            .collect();
+        MethodDef::new(name, MethodType::Instance, params, r#return)
+    }
+
+    #[test]
+    fn annotate_implemented_methods() {
+        let rendered = render(vec![
+            method("__getstate__", &[], TypeInfo::any()),
+            method("__setstate__", &["data"], TypeInfo::any()),
+            method("__reduce__", &[], TypeInfo::any()),
+        ]);
+        assert!(
+            rendered.contains(
+                "class Record:
+    def __getstate__(self) -> tuple[int, str, list[float]]: ...
+    def __setstate__(self, data: tuple[int, str, list[float]]) -> None: ...
+    def __reduce__(self) -> builtins.tuple[typing.Any, ...]: ...
+"
+            ),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn normalize_qualified_names() {
+        assert_eq!(
+            normalized("builtins.tuple[builtins.int, typing.Optional[builtins.str]]"),
+            "tuple[int,Optional[str]]"
+        );
+        assert_eq!(normalized("mybuiltins.int"), "mybuiltins.int");
+        assert_eq!(
+            normalized(&state().name),
+            normalized("builtins.tuple[builtins.int, builtins.str, builtins.list[builtins.float]]")
+        );
+        assert!(is_any(&TypeInfo::any()));
+        assert!(is_any(&TypeInfo::builtin("object")));
+        assert!(!is_any(&TypeInfo::builtin("bytes")));
+    }
+
+    #[test]
+    fn synthesize_missing_methods() {
+        let rendered = render(vec![]);
+        assert!(
+            rendered.contains(
+                "class Record:
+    def __getstate__(self) -> tuple[int, str, list[float]]: ...
+    def __setstate__(self, state: tuple[int, str, list[float]]) -> None: ...
+"
+            ),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("__reduce__"), "{rendered}");
+    }
+
+    #[test]
+    fn replace_mismatched_annotation() {
+        let reduce = TypeInfo::unqualified("tuple[type[Record], tuple[int]]");
+        let rendered = render(vec![
+            method("__getstate__", &[], TypeInfo::builtin("bytes")),
+            method("__reduce__", &[], reduce.clone()),
+        ]);
+        assert!(
+            rendered.contains("def __getstate__(self) -> tuple[int, str, list[float]]: ..."),
+            "{rendered}"
+        );
+        // An explicit `__reduce__` is kept
+        assert!(
+            rendered.contains(&format!("def __reduce__(self) -> {}: ...", reduce.name)),
+            "{rendered}"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::generate::*;
+use std::fmt;
+
+/// Fully-qualified Python name of a definition, e.g. `mypkg.sub.Class.method`.
+///
+/// This is assigned when [StubInfo] is built, i.e. after the module of each item is resolved.
+/// Definitions constructed manually have an empty qualified name until [Module::assign_qualnames] is called.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct QualName {
+    module: String,
+    path: Vec<String>,
+}
+
+impl QualName {
+    pub fn new(
+        module: impl Into<String>,
+        path: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            module: module.into(),
+            path: path.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Qualified name of a member of this item, e.g. a method of a class.
+    pub fn child(&self, name: impl Into<String>) -> Self {
+        let mut path = self.path.clone();
+        path.push(name.into());
+        Self {
+            module: self.module.clone(),
+            path,
+        }
+    }
+
+    /// Dotted module name, e.g. `mypkg.sub`
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Names inside the module, e.g. `["Class", "method"]`
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// The last component, i.e. the name of the item itself
+    pub fn name(&self) -> &str {
+        self.path.last().map(String::as_str).unwrap_or(&self.module)
+    }
+
+    /// All components including those of the module, e.g. `["mypkg", "sub", "Class", "method"]`
+    pub fn components(&self) -> Vec<&str> {
+        self.module
+            .split('.')
+            .filter(|c| !c.is_empty())
+            .chain(self.path.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Dotted representation, e.g. `mypkg.sub.Class.method`
+    pub fn dotted(&self) -> String {
+        self.components().join(".")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.module.is_empty() && self.path.is_empty()
+    }
+}
+
+/// Name used in log messages, falling back to `name` when no qualified name has been assigned.
+pub(crate) fn describe(qualname: &QualName, name: &str) -> String {
+    if qualname.is_empty() {
+        name.to_string()
+    } else {
+        qualname.dotted()
+    }
+}
+
+/// Suffix of log messages pointing at the Rust definition, e.g. ` at src/lib.rs:12`, or empty if unknown.
+pub(crate) fn at_location(source_location: Option<&str>) -> String {
+    source_location
+        .map(|location| format!(" at {location}"))
+        .unwrap_or_default()
+}
+
+impl fmt::Display for QualName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.dotted())
+    }
+}
+
+impl Module {
+    /// Set [QualName] of every definition in this module based on [Module::name].
+    pub fn assign_qualnames(&mut self) {
+        let module = QualName::new(self.name.clone(), Vec::<String>::new());
+        for class in self.class.values_mut() {
+            class.assign_qualnames(module.child(class.name.as_ref()));
+        }
+        for enum_ in self.enum_.values_mut() {
+            let qualname = module.child(enum_.name);
+            for method in &mut enum_.methods {
+                method.qualname = qualname.child(method.name.as_ref());
+            }
+            for member in enum_
+                .attrs
+                .iter_mut()
+                .chain(enum_.getters.iter_mut())
+                .chain(enum_.setters.iter_mut())
+            {
+                member.qualname = qualname.child(member.name);
+            }
+            enum_.qualname = qualname;
+        }
+        for exception in self.exceptions.values_mut() {
+            exception.qualname = module.child(exception.name);
+        }
+        for function in self.function.values_mut().flatten() {
+            function.qualname = module.child(function.name.as_ref());
+        }
+        for variable in self.variables.values_mut() {
+            variable.qualname = module.child(variable.name);
+        }
+        for symbol in self.synthesized.values_mut() {
+            symbol.qualname = module.child(symbol.name.clone());
+        }
+    }
+}
+
+impl ClassDef {
+    fn assign_qualnames(&mut self, qualname: QualName) {
+        for attr in &mut self.attrs {
+            attr.qualname = qualname.child(attr.name);
+        }
+        for field in self.dataclass.iter_mut().flat_map(|d| d.fields.iter_mut()) {
+            field.qualname = qualname.child(field.name);
+        }
+        for (getter, setter) in self.getter_setters.values_mut() {
+            for member in getter.iter_mut().chain(setter.iter_mut()) {
+                member.qualname = qualname.child(member.name);
+            }
+        }
+        for method in self.methods.values_mut().flatten() {
+            method.qualname = qualname.child(method.name.as_ref());
+        }
+        for class in &mut self.classes {
+            class.assign_qualnames(qualname.child(class.name.as_ref()));
+        }
+        self.qualname = qualname;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualname_components() {
+        let qualname = QualName::new("mypkg.sub", ["Shape", "Circle"]).child("__new__");
+        assert_eq!(qualname.dotted(), "mypkg.sub.Shape.Circle.__new__");
+        assert_eq!(
+            qualname.components(),
+            ["mypkg", "sub", "Shape", "Circle", "__new__"]
+        );
+        assert_eq!(qualname.module(), "mypkg.sub");
+        assert_eq!(qualname.path(), ["Shape", "Circle", "__new__"]);
+        assert_eq!(qualname.name(), "__new__");
+    }
+}
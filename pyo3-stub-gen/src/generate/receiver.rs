@@ -0,0 +1,24 @@
+//! Annotation of the `cls` parameter of classmethods and `__new__`, e.g. `def from_json(cls: type[Self], data: str) -> Self`,
+//! which strict checkers such as pyright in strict mode otherwise report as missing.
+
+use crate::{config::Config, generate::target_version, TypeInfo};
+
+/// Set whether `cls` of classmethods and `__new__` is annotated as `type[Self]`.
+///
+/// This is set from `tool.pyo3-stub-gen.annotate-cls` in `pyproject.toml`.
+pub fn set_annotate_cls(enabled: bool) {
+    Config::update(|config| config.annotate_cls = enabled);
+}
+
+/// Retrieve whether `cls` of classmethods and `__new__` is annotated as `type[Self]`.
+pub fn annotate_cls() -> bool {
+    Config::current().annotate_cls
+}
+
+/// `type[Self]` of `cls`, importing `Self` by the [SelfImportStrategy](crate::SelfImportStrategy)
+pub(crate) fn cls_annotation() -> TypeInfo {
+    let mut ty = TypeInfo::self_type();
+    ty.name = format!("type[{}]", ty.name);
+    target_version::downgrade(&mut ty, target_version::target_version());
+    ty
+}
@@ -0,0 +1,344 @@
+//! Validation of references to the generated modules, e.g. a parameter of type `Config` imported from
+//! `mypkg.settings` while `Config` is not exported to the stub of `mypkg.settings`.
+//!
+//! Only the modules of [StubInfo] are checked, so that imports from the standard library,
+//! third-party packages, and modules not generated by this crate are never reported.
+
+use crate::{generate::*, stub_type::ImportRef, TypeInfo};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt};
+
+/// How references to names missing from the generated modules are reported,
+/// set by `unresolved-references` in `[tool.pyo3-stub-gen]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum UnresolvedReferences {
+    /// Do not check the references
+    Ignore,
+    /// Log a warning for each unresolved reference
+    #[default]
+    Warn,
+    /// Fail the generation
+    Error,
+}
+
+/// Reference to a name which the generated module it is imported from does not define
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub struct UnresolvedReference {
+    /// Definition referencing the name, e.g. `mypkg.core.load`
+    pub location: QualName,
+    /// Module the name is imported from, e.g. `mypkg.settings`
+    pub module: String,
+    /// The missing name, e.g. `Config`
+    pub name: String,
+}
+
+impl fmt::Display for UnresolvedReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` references `{}.{}`, but `{}` is not in the stub of `{}`; is `#[gen_stub_pyclass]` or a similar attribute missing?",
+            self.location, self.module, self.name, self.name, self.module
+        )
+    }
+}
+
+impl StubInfo {
+    /// References to names that the generated modules they are imported from do not define
+    pub fn unresolved_references(&self) -> Vec<UnresolvedReference> {
+        let mut unresolved = BTreeSet::new();
+        for module in self.modules.values() {
+            module.for_each_type(&mut |location, ty| {
+                for (target, name) in self.referenced_names(module, ty) {
+                    let defined = self.modules[target].defines(name)
+                        || self.modules.contains_key(&format!("{target}.{name}"));
+                    if !defined {
+                        unresolved.insert(UnresolvedReference {
+                            location: location.clone(),
+                            module: target.to_string(),
+                            name: name.to_string(),
+                        });
+                    }
+                }
+            });
+        }
+        unresolved.into_iter().collect()
+    }
+
+    /// Report [StubInfo::unresolved_references] as set by `level`
+    pub fn validate(&self, level: UnresolvedReferences) -> Result<()> {
+        if level == UnresolvedReferences::Ignore {
+            return Ok(());
+        }
+        let unresolved = self.unresolved_references();
+        match level {
+            UnresolvedReferences::Error if !unresolved.is_empty() => {
+                let list = unresolved
+                    .iter()
+                    .map(|reference| format!("  - {reference}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                bail!("Unresolved references in the generated stubs:\n{list}");
+            }
+            _ => {
+                for reference in &unresolved {
+                    log::warn!("{reference}");
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Names in `ty` referring to the generated modules, as pairs of the module and the name
+    fn referenced_names<'a>(
+        &'a self,
+        module: &'a Module,
+        ty: &'a TypeInfo,
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut names = Vec::new();
+        for import in &ty.import {
+            match import {
+                ImportRef::Type(type_ref) => {
+                    let target = type_ref.module.get().unwrap_or(&module.default_module_name);
+                    if let Some((target, _)) = self.modules.get_key_value(target) {
+                        // The outermost class of a nested one, e.g. `Shape` of `Shape.Circle`
+                        let name = type_ref.name.split('.').next().unwrap_or_default();
+                        names.push((target.as_str(), name));
+                    }
+                }
+                ImportRef::Module(module_ref) => {
+                    let target = module_ref.get().unwrap_or(&module.default_module_name);
+                    if let Some((target, _)) = self.modules.get_key_value(target) {
+                        names.extend(
+                            qualified_names(&ty.name, target)
+                                .into_iter()
+                                .map(|name| (target.as_str(), name)),
+                        );
+                    }
+                }
+            }
+        }
+        names
+    }
+}
+
+/// Names qualified by `module` in a type expression, e.g. `Item` of `builtins.list[mypkg.Item]` for `mypkg`,
+/// skipping longer names like `other_mypkg.Item` or `pkg.mypkg.Item`.
+fn qualified_names<'a>(expr: &'a str, module: &str) -> Vec<&'a str> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let pattern = format!("{module}.");
+    expr.match_indices(&pattern)
+        .filter_map(|(pos, _)| {
+            let before = expr[..pos].chars().last();
+            if before.is_some_and(|c| is_ident(c) || c == '.') {
+                return None;
+            }
+            let rest = &expr[pos + pattern.len()..];
+            let end = rest.find(|c| !is_ident(c)).unwrap_or(rest.len());
+            (end > 0).then(|| &rest[..end])
+        })
+        .collect()
+}
+
+impl Module {
+    /// Whether this module defines a top-level name, including submodules
+    fn defines(&self, name: &str) -> bool {
+        self.class.values().any(|class| class.name == name)
+            || self.enum_.values().any(|enum_| enum_.name == name)
+            || self.exceptions.contains_key(name)
+            || self.function.contains_key(name)
+            || self.variables.contains_key(name)
+            || self.synthesized.values().any(|symbol| symbol.name == name)
+            || self.submodules.contains(name)
+    }
+
+    /// Visit the types in this module with the qualified names of the definitions using them
+    fn for_each_type(&self, f: &mut impl FnMut(&QualName, &TypeInfo)) {
+        for class in self.class.values() {
+            class.for_each_type(f);
+        }
+        for enum_ in self.enum_.values() {
+            for method in &enum_.methods {
+                method.for_each_type(f);
+            }
+            for member in enum_
+                .attrs
+                .iter()
+                .chain(enum_.getters.iter())
+                .chain(enum_.setters.iter())
+            {
+                f(&member.qualname, &member.r#type);
+            }
+        }
+        for exception in self.exceptions.values() {
+            f(&exception.qualname, &exception.base);
+        }
+        for function in self.function.values().flatten() {
+            f(&function.qualname, &function.r#return);
+            for parameter in function.parameters.iter_entries() {
+                f(&function.qualname, &parameter.type_info);
+            }
+        }
+        for variable in self.variables.values() {
+            f(&variable.qualname, &variable.type_);
+        }
+        for symbol in self.synthesized.values() {
+            let SynthesizedKind::TypeAlias(ty) = &symbol.kind;
+            f(&symbol.qualname, ty);
+        }
+    }
+}
+
+impl ClassDef {
+    fn for_each_type(&self, f: &mut impl FnMut(&QualName, &TypeInfo)) {
+        for base in &self.bases {
+            f(&self.qualname, base);
+        }
+        let fields = self
+            .dataclass
+            .iter()
+            .flat_map(|dataclass| &dataclass.fields);
+        for member in self.attrs.iter().chain(fields) {
+            f(&member.qualname, &member.r#type);
+        }
+        for (getter, setter) in self.getter_setters.values() {
+            for member in getter.iter().chain(setter.iter()) {
+                f(&member.qualname, &member.r#type);
+            }
+        }
+        for method in self.methods.values().flatten() {
+            method.for_each_type(f);
+        }
+        for class in &self.classes {
+            class.for_each_type(f);
+        }
+    }
+}
+
+impl MethodDef {
+    fn for_each_type(&self, f: &mut impl FnMut(&QualName, &TypeInfo)) {
+        f(&self.qualname, &self.r#return);
+        for parameter in self.parameters.iter_entries() {
+            f(&self.qualname, &parameter.type_info);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stub_type::ModuleRef, type_info::ParameterKind};
+    use std::{collections::BTreeMap, path::PathBuf};
+    use test_case::test_case;
+
+    fn function(name: &'static str, parameter_type: TypeInfo) -> FunctionDef {
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword.push(Parameter::new(
+            "value",
+            ParameterKind::PositionalOrKeyword,
+            parameter_type,
+        ));
+        FunctionDef::new(name, parameters, TypeInfo::none())
+    }
+
+    fn stub_info(parameter_type: TypeInfo) -> StubInfo {
+        let mut core = Module::new("mypkg.core", "mypkg");
+        core.function
+            .insert("load".into(), vec![function("load", parameter_type)]);
+        let mut settings = Module::new("mypkg.settings", "mypkg");
+        settings.variables.insert(
+            "DEFAULTS",
+            VariableDef::new("DEFAULTS", TypeInfo::builtin("str")),
+        );
+        let mut pkg = Module::new("mypkg", "mypkg");
+        pkg.submodules
+            .extend(["core".to_string(), "settings".to_string()]);
+        let mut modules = BTreeMap::new();
+        for mut module in [pkg, core, settings] {
+            module.assign_qualnames();
+            modules.insert(module.name.clone(), module);
+        }
+        StubInfo {
+            modules,
+            python_root: PathBuf::new(),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    fn unresolved(parameter_type: TypeInfo) -> Vec<String> {
+        stub_info(parameter_type)
+            .unresolved_references()
+            .iter()
+            .map(|reference| {
+                format!(
+                    "{}: {}.{}",
+                    reference.location, reference.module, reference.name
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn missing_class() {
+        assert_eq!(
+            unresolved(TypeInfo::locally_defined("Config", "mypkg.settings".into())),
+            ["mypkg.core.load: mypkg.settings.Config"]
+        );
+        assert_eq!(
+            unresolved(TypeInfo::with_module(
+                "builtins.list[mypkg.settings.Config]",
+                "mypkg.settings".into()
+            )),
+            ["mypkg.core.load: mypkg.settings.Config"]
+        );
+        // The default module
+        assert_eq!(
+            unresolved(TypeInfo::locally_defined("Config", ModuleRef::Default)),
+            ["mypkg.core.load: mypkg.Config"]
+        );
+    }
+
+    #[test_case(TypeInfo::locally_defined("DEFAULTS", "mypkg.settings".into()) ; "defined")]
+    #[test_case(TypeInfo::with_module("mypkg.settings.DEFAULTS", "mypkg".into()) ; "submodule")]
+    #[test_case(TypeInfo::with_module("collections.abc.Mapping", "collections.abc".into()) ; "stdlib")]
+    #[test_case(TypeInfo::with_module("numpy.typing.NDArray", "numpy.typing".into()) ; "third party")]
+    #[test_case(TypeInfo::locally_defined("Helper", "mypkg.helpers".into()) ; "not generated")]
+    fn resolved(parameter_type: TypeInfo) {
+        assert!(unresolved(parameter_type).is_empty());
+    }
+
+    #[test]
+    fn validate() {
+        let info = stub_info(TypeInfo::locally_defined("Config", "mypkg.settings".into()));
+        assert!(info.validate(UnresolvedReferences::Ignore).is_ok());
+        assert!(info.validate(UnresolvedReferences::Warn).is_ok());
+        assert_eq!(
+            info.validate(UnresolvedReferences::Error).unwrap_err().to_string(),
+            "Unresolved references in the generated stubs:\n  - `mypkg.core.load` references `mypkg.settings.Config`, but `Config` is not in the stub of `mypkg.settings`; is `#[gen_stub_pyclass]` or a similar attribute missing?"
+        );
+    }
+
+    #[test]
+    fn in_pyproject() {
+        use crate::pyproject::StubGen;
+        for key in ["unresolved-references", "unresolved_references"] {
+            let stub_gen: StubGen = toml::from_str(&format!("{key} = \"error\"")).unwrap();
+            assert_eq!(
+                stub_gen.unresolved_references,
+                Some(UnresolvedReferences::Error)
+            );
+        }
+    }
+
+    #[test_case("builtins.list[mypkg.Item]", "mypkg", &["Item"] ; "nested")]
+    #[test_case("mypkg.A | mypkg.B", "mypkg", &["A", "B"] ; "union")]
+    #[test_case("other_mypkg.Item | pkg.mypkg.Item", "mypkg", &[] ; "longer names")]
+    fn qualified(expr: &str, module: &str, expected: &[&str]) {
+        assert_eq!(qualified_names(expr, module), expected);
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::{Context, Result};
+use std::{fs, path::*};
+
+/// Destination of generated stub files.
+///
+/// Paths passed to a sink are relative to the python root, e.g. `my_pkg/__init__.pyi`.
+/// [StubInfo::generate](crate::StubInfo::generate) writes into the filesystem with [FsSink],
+/// while [StubInfo::generate_into](crate::StubInfo::generate_into) accepts any sink.
+pub trait StubSink {
+    /// Write `content` to `rel_path`, replacing any existing entry.
+    fn write(&mut self, rel_path: &Path, content: &str) -> Result<()>;
+
+    /// Check whether an entry exists at `rel_path`.
+    fn exists(&self, rel_path: &Path) -> bool;
+
+    /// Read the entry at `rel_path`.
+    fn read(&self, rel_path: &Path) -> Result<String>;
+
+    /// Called once after all stub files have been written.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes stub files under a directory on the local filesystem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FsSink {
+    root: PathBuf,
+}
+
+impl FsSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl StubSink for FsSink {
+    fn write(&mut self, rel_path: &Path, content: &str) -> Result<()> {
+        let dest = self.root.join(rel_path);
+        let dir = dest.parent().context("Cannot get parent directory")?;
+        if !dir.exists() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&dest, content).with_context(|| format!("Failed to write {}", dest.display()))?;
+        Ok(())
+    }
+
+    fn exists(&self, rel_path: &Path) -> bool {
+        self.root.join(rel_path).exists()
+    }
+
+    fn read(&self, rel_path: &Path) -> Result<String> {
+        let path = self.root.join(rel_path);
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+}
+
+#[cfg(feature = "zip")]
+pub use self::zip_sink::ZipSink;
+
+#[cfg(feature = "zip")]
+mod zip_sink {
+    use super::StubSink;
+    use anyhow::{bail, Context, Result};
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use sha2::{Digest, Sha256};
+    use std::{
+        collections::BTreeMap,
+        fs,
+        io::{Cursor, Read, Write},
+        path::*,
+    };
+    use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+    /// Writes stub files into an existing wheel or zip archive.
+    ///
+    /// Entries not written by the generator are copied over unchanged.
+    /// If the archive contains a `*.dist-info/RECORD` file,
+    /// the hash and size of every written entry are updated in it.
+    /// The archive is rewritten when [StubSink::finish] is called.
+    #[derive(Debug, Clone)]
+    pub struct ZipSink {
+        path: Option<PathBuf>,
+        archive: Vec<u8>,
+        written: BTreeMap<String, Vec<u8>>,
+    }
+
+    impl ZipSink {
+        /// Open an archive on disk. It is overwritten by [StubSink::finish].
+        pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+            let path = path.as_ref();
+            let archive =
+                fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut sink = Self::from_bytes(archive)?;
+            sink.path = Some(path.to_path_buf());
+            Ok(sink)
+        }
+
+        /// Use an in-memory archive. Get the result by [ZipSink::into_bytes] after [StubSink::finish].
+        pub fn from_bytes(archive: Vec<u8>) -> Result<Self> {
+            ZipArchive::new(Cursor::new(archive.as_slice())).context("Invalid zip archive")?;
+            Ok(Self {
+                path: None,
+                archive,
+                written: BTreeMap::new(),
+            })
+        }
+
+        pub fn into_bytes(self) -> Vec<u8> {
+            self.archive
+        }
+
+        fn entry_name(rel_path: &Path) -> String {
+            rel_path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        }
+
+        fn record_name(archive: &ZipArchive<Cursor<&[u8]>>) -> Result<Option<String>> {
+            let records: Vec<&str> = archive
+                .file_names()
+                .filter(|name| {
+                    name.strip_suffix("/RECORD")
+                        .is_some_and(|dir| dir.ends_with(".dist-info") && !dir.contains('/'))
+                })
+                .collect();
+            match records.as_slice() {
+                [] => Ok(None),
+                [record] => Ok(Some(record.to_string())),
+                _ => bail!("Multiple RECORD files found: {}", records.join(", ")),
+            }
+        }
+
+        fn rebuild(&self) -> Result<Vec<u8>> {
+            let mut archive = ZipArchive::new(Cursor::new(self.archive.as_slice()))?;
+            let record_name = Self::record_name(&archive)?;
+            let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+            let options =
+                SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+            let mut record = None;
+            for index in 0..archive.len() {
+                let name = archive
+                    .name_for_index(index)
+                    .unwrap_or_default()
+                    .to_string();
+                if record_name.as_deref() == Some(name.as_str()) {
+                    let mut content = String::new();
+                    archive.by_index(index)?.read_to_string(&mut content)?;
+                    record = Some(content);
+                    continue;
+                }
+                if self.written.contains_key(&name) {
+                    continue;
+                }
+                writer.raw_copy_file(archive.by_index_raw(index)?)?;
+            }
+
+            for (name, content) in &self.written {
+                writer.start_file(name.as_str(), options)?;
+                writer.write_all(content)?;
+            }
+
+            if let (Some(name), Some(record)) = (record_name, record) {
+                writer.start_file(name.as_str(), options)?;
+                writer.write_all(update_record(&record, &name, &self.written).as_bytes())?;
+            }
+            Ok(writer.finish()?.into_inner())
+        }
+    }
+
+    /// Replace or insert RECORD rows for written entries, keeping the RECORD row itself last.
+    fn update_record(
+        record: &str,
+        record_name: &str,
+        written: &BTreeMap<String, Vec<u8>>,
+    ) -> String {
+        let mut rows: Vec<String> = record
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter(|line| {
+                let path = record_path(line);
+                path != record_name && !written.contains_key(path)
+            })
+            .map(str::to_string)
+            .collect();
+        for (name, content) in written {
+            let digest = URL_SAFE_NO_PAD.encode(Sha256::digest(content));
+            rows.push(format!("{name},sha256={digest},{}", content.len()));
+        }
+        rows.push(format!("{record_name},,"));
+        rows.join("\n") + "\n"
+    }
+
+    fn record_path(line: &str) -> &str {
+        if let Some(quoted) = line.strip_prefix('"') {
+            quoted.split('"').next().unwrap_or_default()
+        } else {
+            line.split(',').next().unwrap_or_default()
+        }
+    }
+
+    impl StubSink for ZipSink {
+        fn write(&mut self, rel_path: &Path, content: &str) -> Result<()> {
+            self.written
+                .insert(Self::entry_name(rel_path), content.as_bytes().to_vec());
+            Ok(())
+        }
+
+        fn exists(&self, rel_path: &Path) -> bool {
+            let name = Self::entry_name(rel_path);
+            self.written.contains_key(&name)
+                || ZipArchive::new(Cursor::new(self.archive.as_slice()))
+                    .is_ok_and(|archive| archive.index_for_name(&name).is_some())
+        }
+
+        fn read(&self, rel_path: &Path) -> Result<String> {
+            let name = Self::entry_name(rel_path);
+            if let Some(content) = self.written.get(&name) {
+                return Ok(String::from_utf8(content.clone())?);
+            }
+            let mut archive = ZipArchive::new(Cursor::new(self.archive.as_slice()))?;
+            let mut content = String::new();
+            archive
+                .by_name(&name)
+                .with_context(|| format!("Entry {name} not found in archive"))?
+                .read_to_string(&mut content)?;
+            Ok(content)
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            if self.written.is_empty() {
+                return Ok(());
+            }
+            self.archive = self.rebuild()?;
+            self.written.clear();
+            if let Some(path) = &self.path {
+                fs::write(path, &self.archive)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn wheel() -> Vec<u8> {
+            let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+            let options = SimpleFileOptions::default();
+            writer.start_file("pkg/__init__.py", options).unwrap();
+            writer.write_all(b"from ._core import *\n").unwrap();
+            writer.start_file("pkg/_core.pyi", options).unwrap();
+            writer.write_all(b"# stale\n").unwrap();
+            writer
+                .start_file("pkg-0.1.0.dist-info/RECORD", options)
+                .unwrap();
+            writer
+                .write_all(
+                    b"pkg/__init__.py,sha256=abc,21\n\
+                      pkg/_core.pyi,sha256=stale,8\n\
+                      pkg-0.1.0.dist-info/RECORD,,\n",
+                )
+                .unwrap();
+            writer.finish().unwrap().into_inner()
+        }
+
+        fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        }
+
+        #[test]
+        fn round_trip_updates_record() -> Result<()> {
+            let mut sink = ZipSink::from_bytes(wheel())?;
+            assert!(sink.exists(Path::new("pkg/_core.pyi")));
+            assert_eq!(sink.read(Path::new("pkg/_core.pyi"))?, "# stale\n");
+
+            let stub = "def f() -> int: ...\n";
+            sink.write(Path::new("pkg/_core.pyi"), stub)?;
+            sink.write(Path::new("pkg/py.typed"), "")?;
+            sink.finish()?;
+
+            let mut archive = ZipArchive::new(Cursor::new(sink.into_bytes()))?;
+            assert_eq!(
+                read_entry(&mut archive, "pkg/__init__.py"),
+                "from ._core import *\n"
+            );
+            assert_eq!(read_entry(&mut archive, "pkg/_core.pyi"), stub);
+            assert_eq!(read_entry(&mut archive, "pkg/py.typed"), "");
+
+            let record = read_entry(&mut archive, "pkg-0.1.0.dist-info/RECORD");
+            let rows: Vec<&str> = record.lines().collect();
+            assert_eq!(rows.len(), 4);
+            assert_eq!(rows[0], "pkg/__init__.py,sha256=abc,21");
+            assert_eq!(rows[3], "pkg-0.1.0.dist-info/RECORD,,");
+            for (name, content) in [("pkg/_core.pyi", stub), ("pkg/py.typed", "")] {
+                let digest = URL_SAFE_NO_PAD.encode(Sha256::digest(content.as_bytes()));
+                let row = format!("{name},sha256={digest},{}", content.len());
+                assert!(rows.contains(&row.as_str()), "{row} not in RECORD");
+            }
+            Ok(())
+        }
+    }
+}
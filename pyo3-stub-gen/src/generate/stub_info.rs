@@ -1,79 +1,71 @@
-#[cfg(test)]
-use crate::stub_type::self_import_strategy;
 use crate::{
-    generate::*,
-    pyproject::PyProject,
-    stub_type::{set_self_import_strategy, SelfImportStrategy},
+    config::Config,
+    generate::{conflict::*, *},
+    pyproject::{Filter, PyProject},
+    stub_type::{complex_enum_alias, inline_complex_enum_unions, SelfImportStrategy},
     type_info::*,
 };
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use std::{
-    collections::{BTreeMap, BTreeSet},
-    fs,
-    io::Write,
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Write as _,
     path::*,
 };
 
+/// Stub files of a project, i.e. the [Module]s to be written under `python_root`.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct StubInfo {
     pub modules: BTreeMap<String, Module>,
     pub python_root: PathBuf,
+    /// Paths of the stub files
+    pub layout: StubLayout,
+    /// Whether only the marker block of existing stub files is replaced
+    pub merge: bool,
+    /// Configuration the stubs are rendered with, i.e. the one of the setters overridden by `pyproject.toml`.
+    /// The built modules keep it in [Module::config], so that they are rendered alike when formatted alone.
+    pub config: Config,
+}
+
+/// Stub files handled by [StubInfo::generate_with_report], as paths under [StubInfo::python_root].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GenerateReport {
+    /// Files created or rewritten
+    pub written: Vec<PathBuf>,
+    /// Files left untouched since their content is up to date
+    pub unchanged: Vec<PathBuf>,
 }
 
-fn configure_self_import_strategy_from_requires_python(spec: Option<&str>) {
+/// Target the minimum version of `requires-python` unless `target-version` is given
+fn target_version_of(
+    requires_python: Option<&str>,
+    target_version: Option<TargetVersion>,
+) -> TargetVersion {
+    let derived = requires_python
+        .and_then(parse_minimum_python_version)
+        .map(TargetVersion::from_minimum);
+    target_version.or(derived).unwrap_or_default()
+}
+
+fn self_import_strategy_from_requires_python(spec: Option<&str>) -> SelfImportStrategy {
     use SelfImportStrategy::{Typing, TypingExtensions};
 
     if let Some(min_version) = spec.and_then(parse_minimum_python_version) {
-        let strategy = if min_version.0 > 3 || (min_version.0 == 3 && min_version.1 >= 11) {
+        if min_version.0 > 3 || (min_version.0 == 3 && min_version.1 >= 11) {
             Typing
         } else {
             TypingExtensions
-        };
-        set_self_import_strategy(strategy);
+        }
     } else {
-        set_self_import_strategy(Typing);
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::stub_type::{set_self_import_strategy, SelfImportStrategy};
-
-    #[test]
-    fn parses_minimum_python_version() {
-        assert_eq!(parse_minimum_python_version(">=3.10"), Some((3, 10)));
-        assert_eq!(parse_minimum_python_version(">=3.8, <3.12"), Some((3, 8)));
-        assert_eq!(parse_minimum_python_version("~=3.11.0"), Some((3, 11)));
-        assert_eq!(parse_minimum_python_version(""), None);
-        assert_eq!(parse_minimum_python_version(">=3"), Some((3, 0)));
-    }
-
-    #[test]
-    fn configure_strategy_defaults_to_typing_when_unspecified() {
-        set_self_import_strategy(SelfImportStrategy::TypingExtensions);
-        configure_self_import_strategy_from_requires_python(None);
-        assert_eq!(self_import_strategy(), SelfImportStrategy::Typing);
-    }
-
-    #[test]
-    fn configure_strategy_prefers_typing_extensions_below_311() {
-        set_self_import_strategy(SelfImportStrategy::Typing);
-        configure_self_import_strategy_from_requires_python(Some(">=3.10"));
-        assert_eq!(self_import_strategy(), SelfImportStrategy::TypingExtensions);
-    }
-
-    #[test]
-    fn configure_strategy_prefers_typing_from_311_onwards() {
-        set_self_import_strategy(SelfImportStrategy::TypingExtensions);
-        configure_self_import_strategy_from_requires_python(Some(">=3.11"));
-        assert_eq!(self_import_strategy(), SelfImportStrategy::Typing);
+        Typing
     }
 }
 
 fn parse_minimum_python_version(spec: &str) -> Option<(u8, u8)> {
     let mut minimum: Option<(u8, u8)> = None;
-    for token in spec.split(|c| c == ',' || c == ' ') {
+    for token in spec.split([',', ' ']) {
         let token = token.trim();
         if token.is_empty() {
             continue;
@@ -108,7 +100,7 @@ fn parse_python_version_fragment(fragment: &str) -> Option<(u8, u8)> {
     let mut parts = cleaned.split('.');
     let major: u8 = parts.next()?.parse().ok()?;
     let mut minor_part = parts.next().unwrap_or("0").trim();
-    if let Some(idx) = minor_part.chars().position(|ch| !matches!(ch, '0'..='9')) {
+    if let Some(idx) = minor_part.chars().position(|ch: char| !ch.is_ascii_digit()) {
         minor_part = &minor_part[..idx];
     }
     let minor: u8 = if minor_part.is_empty() {
@@ -128,44 +120,191 @@ fn max_version(a: (u8, u8), b: (u8, u8)) -> (u8, u8) {
 }
 
 impl StubInfo {
-    /// Initialize [StubInfo] from a `pyproject.toml` file in `CARGO_MANIFEST_DIR`.
+    /// Initialize [StubInfo] from a `pyproject.toml` file, usually in `CARGO_MANIFEST_DIR`.
+    /// The stubs are written into `python-source` of `[tool.maturin]`, or next to `pyproject.toml` without it.
     /// This is automatically set up by the [crate::define_stub_info_gatherer] macro.
     pub fn from_pyproject_toml(path: impl AsRef<Path>) -> Result<Self> {
         let pyproject = PyProject::parse_toml(path)?;
-        Ok(StubInfoBuilder::from_pyproject_toml(pyproject).build())
+        StubInfoBuilder::from_pyproject_toml(pyproject)?.build()
     }
 
     /// Initialize [StubInfo] with a specific module name and project root.
     /// This must be placed in your PyO3 library crate, i.e. the same crate where [inventory::submit]ted,
     /// not in the `gen_stub` executables due to [inventory]'s mechanism.
     pub fn from_project_root(default_module_name: String, project_root: PathBuf) -> Result<Self> {
-        Ok(StubInfoBuilder::from_project_root(default_module_name, project_root).build())
+        StubInfoBuilder::from_project_root(default_module_name, project_root).build()
+    }
+
+    /// Replace the banner at the top of every stub file by `header`, whose lines are rendered as comments,
+    /// or remove the banner if `None` or empty.
+    pub fn set_header(&mut self, header: Option<String>) {
+        let header = header.unwrap_or_default();
+        for module in self.modules.values_mut() {
+            module.header = Some(header.clone());
+        }
+    }
+
+    /// Keep only the modules whose name satisfies `predicate`.
+    pub fn filtered(mut self, mut predicate: impl FnMut(&str) -> bool) -> Self {
+        self.modules.retain(|name, _| predicate(name));
+        self
     }
 
+    /// Write stub files under [StubInfo::python_root].
+    /// Files whose content is up to date are not rewritten to keep their modification times.
     pub fn generate(&self) -> Result<()> {
-        for (name, module) in self.modules.iter() {
-            // Convert dashes to underscores for Python compatibility
-            let normalized_name = name.replace("-", "_");
-            let path = normalized_name.replace(".", "/");
-            let dest = if module.submodules.is_empty() {
-                self.python_root.join(format!("{path}.pyi"))
+        self.generate_to(&self.python_root)
+    }
+
+    /// Same as [StubInfo::generate], but reports which files are written and which are unchanged.
+    pub fn generate_with_report(&self) -> Result<GenerateReport> {
+        let mut sink = FsSink::new(&self.python_root);
+        let report = self.write_stubs(&mut sink)?;
+        sink.finish()?;
+        Ok(report)
+    }
+
+    /// Write stub files under `dir` instead of [StubInfo::python_root].
+    pub fn generate_to(&self, dir: impl AsRef<Path>) -> Result<()> {
+        self.generate_into(&mut FsSink::new(dir.as_ref()))
+    }
+
+    /// Write stub files into the given [StubSink], e.g. a wheel archive.
+    pub fn generate_into<S: StubSink + ?Sized>(&self, sink: &mut S) -> Result<()> {
+        self.write_stubs(sink)?;
+        sink.finish()
+    }
+
+    /// Write the stub files whose content differs from the existing entries of `sink`
+    fn write_stubs<S: StubSink + ?Sized>(&self, sink: &mut S) -> Result<GenerateReport> {
+        let mut report = GenerateReport::default();
+        for (name, dest, content) in self.stub_files()? {
+            let content = self.merged(sink, &dest, content)?;
+            let path = self.python_root.join(&dest);
+            if sink.exists(&dest) && sink.read(&dest).is_ok_and(|current| current == content) {
+                log::debug!(
+                    "Stub file of a module `{name}` at {} is unchanged",
+                    path.display()
+                );
+                report.unchanged.push(path);
+                continue;
+            }
+            sink.write(&dest, &content)
+                .with_context(|| format!("Failed to write the stub of module `{name}`"))?;
+            log::info!(
+                "Generate stub file of a module `{name}` at {}",
+                path.display()
+            );
+            report.written.push(path);
+        }
+        for dest in self.partial_markers() {
+            let current = if sink.exists(&dest) {
+                sink.read(&dest)?
             } else {
-                self.python_root.join(path).join("__init__.pyi")
+                String::new()
             };
-
-            let dir = dest.parent().context("Cannot get parent directory")?;
-            if !dir.exists() {
-                fs::create_dir_all(dir)?;
+            if is_partial(&current) {
+                continue;
             }
-
-            let mut f = fs::File::create(&dest)?;
-            write!(f, "{module}")?;
+            // Other lines of an existing `py.typed` are kept
+            let separator = if current.is_empty() || current.ends_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            sink.write(&dest, &format!("{current}{separator}partial\n"))?;
             log::info!(
-                "Generate stub file of a module `{name}` at {dest}",
-                dest = dest.display()
+                "Mark the package as partial at {}",
+                self.python_root.join(&dest).display()
             );
         }
-        Ok(())
+        Ok(report)
+    }
+
+    /// Paths of stub files under [StubInfo::python_root] which are missing or out of date.
+    pub fn check(&self) -> Result<Vec<PathBuf>> {
+        self.check_against(&FsSink::new(&self.python_root))
+    }
+
+    /// Paths of stub files in the given [StubSink] which are missing or out of date.
+    /// The paths are relative to the root of the sink.
+    pub fn check_against<S: StubSink + ?Sized>(&self, sink: &S) -> Result<Vec<PathBuf>> {
+        let mut outdated = Vec::new();
+        for (_, dest, content) in self.stub_files()? {
+            let content = self.merged(sink, &dest, content)?;
+            if !sink.exists(&dest) || sink.read(&dest)? != content {
+                outdated.push(dest);
+            }
+        }
+        for dest in self.partial_markers() {
+            if !sink.exists(&dest) || !is_partial(&sink.read(&dest)?) {
+                outdated.push(dest);
+            }
+        }
+        Ok(outdated)
+    }
+
+    /// Module name, path relative to the python root, and content of each stub file
+    pub(crate) fn stub_files(&self) -> Result<Vec<(&str, PathBuf, String)>> {
+        let _config = self.config.scope();
+        self.modules
+            .iter()
+            .map(|(name, module)| {
+                let mut content = String::new();
+                write!(content, "{module}")
+                    .map_err(|_| anyhow!("Failed to render the stub of module `{name}`"))?;
+                Ok((name.as_str(), self.stub_path(name, module), content))
+            })
+            .collect()
+    }
+
+    /// Path of the stub file of a module relative to the python root
+    fn stub_path(&self, name: &str, module: &Module) -> PathBuf {
+        let path = PathBuf::from(name.replace(".", "/"));
+        if module.submodules.is_empty() && self.layout == StubLayout::Module {
+            path.with_extension("pyi")
+        } else {
+            path.join("__init__.pyi")
+        }
+    }
+
+    /// Paths of `py.typed` of the top-level packages containing [Module::partial] modules
+    fn partial_markers(&self) -> BTreeSet<PathBuf> {
+        let mut markers = BTreeSet::new();
+        for (name, module) in &self.modules {
+            if !module.partial {
+                continue;
+            }
+            let dest = self.stub_path(name, module);
+            match dest.components().next() {
+                Some(package) if dest.components().count() > 1 => {
+                    markers.insert(Path::new(package.as_os_str()).join("py.typed"));
+                }
+                _ => log::warn!(
+                    "Module `{name}` is marked as partial, but has no package to write `py.typed` into"
+                ),
+            }
+        }
+        markers
+    }
+
+    /// Content of the stub file at `dest`, merged into its existing marker block if [StubInfo::merge] is set
+    pub(crate) fn merged<S: StubSink + ?Sized>(
+        &self,
+        sink: &S,
+        dest: &Path,
+        content: String,
+    ) -> Result<String> {
+        if !self.merge {
+            return Ok(content);
+        }
+        let existing = if sink.exists(dest) {
+            Some(sink.read(dest)?)
+        } else {
+            None
+        };
+        layout::merge_block(existing.as_deref(), &content)
+            .with_context(|| format!("Failed to merge into {}", dest.display()))
     }
 }
 
@@ -173,35 +312,222 @@ struct StubInfoBuilder {
     modules: BTreeMap<String, Module>,
     default_module_name: String,
     python_root: PathBuf,
+    synthesized: synthesized::SynthesizedSymbols,
+    filter: Option<Filter>,
+    renames: Renames,
+    module_renames: module_rename::ModuleRenames,
+    /// Module names normalized by [module_rename::normalize], to their normalized names
+    normalized_modules: BTreeMap<String, String>,
+    unresolved_references: UnresolvedReferences,
+    keyword_parameters: KeywordParameters,
+    forward_references: ForwardReferences,
+    /// Whether overriding methods are decorated by `@typing.override`
+    emit_override_decorator: bool,
+    layout: StubLayout,
+    merge: bool,
+    /// Cargo features whose items of `#[gen_stub(feature = "...")]` are included, or all of them if `None`
+    enabled_tags: Option<HashSet<String>>,
+    /// Prefix stripped from the Rust names of classes and enums by `strip-class-prefix`
+    strip_class_prefix: Option<String>,
+    /// Modules listed in `attr-fallback`, by their names in the generated stubs
+    attr_fallback: Vec<String>,
+    /// Modules listed in `partial`, by their names in the generated stubs
+    partial: Vec<String>,
+    /// Modules listed in `namespace-packages`, by their names in the generated stubs
+    namespace_packages: Vec<String>,
+    /// Modules listed in `reexport-all` with the modules they re-export, by their names in the generated stubs
+    reexport_all: BTreeMap<String, Vec<String>>,
+    /// `header` replacing the default banner of the stub files
+    header: Option<String>,
+    /// Modules of the classes and enums by their ids, so that their methods are added by a single lookup
+    owners: HashMap<TypeId, String>,
+    /// Configuration of the setters when the builder is created, overridden by `pyproject.toml`
+    config: Config,
+}
+
+/// Environment variable of the comma-separated features whose tagged items are included in stubs
+const ENABLED_TAGS_VAR: &str = "PYO3_STUB_GEN_TAGS";
+
+/// Features listed in `PYO3_STUB_GEN_TAGS`, e.g. `cuda,experimental`
+fn enabled_tags_from_env() -> Option<HashSet<String>> {
+    let tags = std::env::var(ENABLED_TAGS_VAR).ok()?;
+    Some(parse_tags(&tags))
+}
+
+fn parse_tags(tags: &str) -> HashSet<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 impl StubInfoBuilder {
-    fn from_pyproject_toml(pyproject: PyProject) -> Self {
-        configure_self_import_strategy_from_requires_python(
-            pyproject.project.requires_python.as_deref(),
+    fn from_pyproject_toml(pyproject: PyProject) -> Result<Self> {
+        // Not `CARGO_MANIFEST_DIR` at runtime, which is unset or of another crate when run from the cdylib
+        let python_root = pyproject
+            .python_source()
+            .unwrap_or_else(|| pyproject.project_root());
+        let mut builder =
+            StubInfoBuilder::from_project_root(pyproject.module_name().to_string(), python_root);
+        let requires_python = pyproject.project.requires_python.as_deref();
+        let stub_gen = pyproject.stub_gen();
+        let config = &mut builder.config;
+        config.self_import_strategy = self_import_strategy_from_requires_python(requires_python);
+        config.target_version = target_version_of(
+            requires_python,
+            stub_gen.and_then(|stub_gen| stub_gen.target_version),
         );
-        StubInfoBuilder::from_project_root(
-            pyproject.module_name().to_string(),
-            pyproject
-                .python_source()
-                .unwrap_or(PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())),
-        )
+        config.generate_dunder_all = stub_gen
+            .and_then(|stub_gen| stub_gen.generate_all)
+            .unwrap_or(true);
+        config.docstring_style = stub_gen
+            .and_then(|stub_gen| stub_gen.docstring_style)
+            .unwrap_or_default();
+        config.synthesize_attributes_section = stub_gen
+            .and_then(|stub_gen| stub_gen.synthesize_attributes_section)
+            .unwrap_or(false);
+        config.container_default_style = stub_gen
+            .and_then(|stub_gen| stub_gen.container_default)
+            .unwrap_or_default();
+        config.annotate_cls = stub_gen
+            .and_then(|stub_gen| stub_gen.annotate_cls)
+            .unwrap_or(false);
+        config.constructor_style = stub_gen
+            .and_then(|stub_gen| stub_gen.constructor_style)
+            .unwrap_or_default();
+        config.abstract_set_input = stub_gen
+            .and_then(|stub_gen| stub_gen.abstract_set_input)
+            .unwrap_or(false);
+        config.annotate_deprecated_parameters = stub_gen
+            .and_then(|stub_gen| stub_gen.annotate_deprecated_parameters)
+            .unwrap_or(false);
+        config.inline_complex_enum_unions = stub_gen
+            .and_then(|stub_gen| stub_gen.inline_complex_enum_unions)
+            .unwrap_or(false);
+        builder.filter = pyproject.filter().cloned();
+        if let Some(module_rename) = pyproject
+            .stub_gen()
+            .and_then(|stub_gen| stub_gen.module_rename.clone())
+        {
+            // Renames apply to the normalized names of modules
+            let module_rename = module_rename
+                .into_iter()
+                .map(|(old, new)| (module_rename::normalize(&old), new))
+                .collect();
+            builder.module_renames = module_rename::ModuleRenames::new(module_rename);
+        }
+        if let Some(unresolved_references) = pyproject
+            .stub_gen()
+            .and_then(|stub_gen| stub_gen.unresolved_references)
+        {
+            builder.unresolved_references = unresolved_references;
+        }
+        if let Some(stub_gen) = pyproject.stub_gen() {
+            builder.layout = stub_gen.stub_layout.unwrap_or_default();
+            builder.merge = stub_gen.merge.unwrap_or(false);
+            builder.keyword_parameters = stub_gen.keyword_parameters.unwrap_or_default();
+            builder.forward_references = stub_gen.forward_references.unwrap_or_default();
+            builder.emit_override_decorator = stub_gen.emit_override_decorator.unwrap_or(false);
+            builder.strip_class_prefix = stub_gen.strip_class_prefix.clone();
+            builder.attr_fallback = stub_gen.attr_fallback.clone().unwrap_or_default();
+            builder.partial = stub_gen.partial.clone().unwrap_or_default();
+            builder.namespace_packages = stub_gen.namespace_packages.clone().unwrap_or_default();
+            builder.reexport_all = stub_gen.reexport_all.clone().unwrap_or_default();
+            builder.header = stub_gen.header.clone();
+            // `PYO3_STUB_GEN_TAGS` takes precedence over `tags`
+            if builder.enabled_tags.is_none() {
+                builder.enabled_tags = stub_gen
+                    .tags
+                    .as_ref()
+                    .map(|tags| tags.iter().cloned().collect());
+            }
+        }
+        Ok(builder)
     }
 
     fn from_project_root(default_module_name: String, project_root: PathBuf) -> Self {
-        Self {
+        let mut normalized_modules = BTreeMap::new();
+        let normalized = module_rename::normalize(&default_module_name);
+        if normalized != default_module_name {
+            normalized_modules.insert(default_module_name, normalized.clone());
+        }
+        let builder = Self {
             modules: BTreeMap::new(),
-            default_module_name,
+            default_module_name: normalized,
             python_root: project_root,
+            synthesized: synthesized::SynthesizedSymbols::default(),
+            filter: None,
+            renames: Renames::default(),
+            module_renames: module_rename::ModuleRenames::default(),
+            normalized_modules,
+            unresolved_references: UnresolvedReferences::default(),
+            keyword_parameters: KeywordParameters::default(),
+            forward_references: ForwardReferences::default(),
+            emit_override_decorator: false,
+            layout: StubLayout::default(),
+            merge: false,
+            enabled_tags: None,
+            strip_class_prefix: None,
+            attr_fallback: Vec::new(),
+            partial: Vec::new(),
+            namespace_packages: Vec::new(),
+            reexport_all: BTreeMap::new(),
+            header: None,
+            owners: HashMap::new(),
+            config: Config::current(),
+        };
+        match enabled_tags_from_env() {
+            Some(tags) => builder.with_enabled_tags(tags),
+            None => builder,
         }
     }
 
-    fn get_module(&mut self, name: Option<&str>) -> &mut Module {
-        let name = name.unwrap_or(&self.default_module_name).to_string();
-        let module = self.modules.entry(name.clone()).or_default();
-        module.name = name;
+    /// Include the items tagged by `#[gen_stub(feature = "...")]` only if their feature is in `tags`.
+    /// Items without a tag are always included.
+    fn with_enabled_tags(mut self, tags: HashSet<String>) -> Self {
+        self.enabled_tags = Some(tags);
+        self
+    }
+
+    /// Whether an item tagged by `feature` is included
+    fn is_enabled(&self, feature: Option<&str>) -> bool {
+        match (feature, &self.enabled_tags) {
+            (Some(feature), Some(tags)) => tags.contains(feature),
+            _ => true,
+        }
+    }
+
+    /// Module of the given name, or the default module, registered by its normalized name
+    fn get_module(&mut self, name: Option<&str>) -> Result<&mut Module> {
+        let given = name.unwrap_or(&self.default_module_name);
+        let normalized = module_rename::normalize(given);
+        module_rename::validate(&normalized).with_context(|| match name {
+            Some(name) => format!(
+                "Module name `{name}` of `#[pyclass(module = \"{name}\")]` or a similar attribute is not a valid Python module name"
+            ),
+            None => format!(
+                "Module name `{given}` of `module-name` in `[tool.maturin]` is not a valid Python module name"
+            ),
+        })?;
+        if normalized != given {
+            self.normalized_modules
+                .insert(given.to_string(), normalized.clone());
+        }
+        let module = self.modules.entry(normalized.clone()).or_default();
+        module.name = normalized;
         module.default_module_name = self.default_module_name.clone();
-        module
+        Ok(module)
+    }
+
+    /// Rewrite the references to modules registered by names other than their normalized names,
+    /// e.g. an import from `my-pkg.core` into the one from `my_pkg.core`.
+    fn normalize_module_references(&mut self) -> Result<()> {
+        let normalized =
+            module_rename::ModuleRenames::new(std::mem::take(&mut self.normalized_modules));
+        self.synthesized
+            .rename_modules(&normalized, &self.default_module_name);
+        normalized.apply(&mut self.modules, &mut self.default_module_name)
     }
 
     fn register_submodules(&mut self) {
@@ -223,46 +549,195 @@ impl StubInfoBuilder {
         }
     }
 
-    fn add_class(&mut self, info: &PyClassInfo) {
-        self.get_module(info.module)
-            .class
-            .insert((info.struct_id)(), ClassDef::from(info));
+    /// Add a class to `module`, indexed by `id` for its methods
+    fn insert_class(&mut self, module: Option<&str>, id: TypeId, def: ClassDef) -> Result<()> {
+        let module = self.get_module(module)?;
+        module.class.insert(id, def);
+        let module = module.name.clone();
+        self.owners.insert(id, module);
+        Ok(())
     }
 
-    fn add_complex_enum(&mut self, info: &PyComplexEnumInfo) {
-        self.get_module(info.module)
-            .class
-            .insert((info.enum_id)(), ClassDef::from(info));
+    fn add_class(&mut self, info: &PyClassInfo) -> Result<()> {
+        let mut def = ClassDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Class, info.rust_path, info.pyclass_name)
+            .into();
+        self.insert_class(info.module, (info.struct_id)(), def)
+    }
+
+    fn add_complex_enum(&mut self, info: &PyComplexEnumInfo) -> Result<()> {
+        let mut def = ClassDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Enum, info.rust_path, info.pyclass_name)
+            .into();
+        let name = def.name.to_string();
+        let id = (info.enum_id)();
+        self.insert_class(info.module, id, def)?;
+        // `PyStubType` of the enum refers to the alias by the Rust name, which is rewritten after all items are added
+        if let Some(union) = info.variant_union.filter(|_| !inline_complex_enum_unions()) {
+            let mut union = union();
+            union.name = transform::rename_ident(&union.name, info.pyclass_name, &name);
+            self.synthesized.define(
+                &self.owners[&id],
+                &complex_enum_alias(&name),
+                SynthesizedKind::TypeAlias(union),
+                format!("complex enum `{name}`"),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn add_enum(&mut self, info: &PyEnumInfo) -> Result<()> {
+        let mut def = EnumDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Enum, info.rust_path, info.pyclass_name);
+        let module = self.get_module(info.module)?;
+        module.enum_.insert((info.enum_id)(), def);
+        let module = module.name.clone();
+        self.owners.insert((info.enum_id)(), module);
+        Ok(())
+    }
+
+    fn add_named_tuple(&mut self, info: &NamedTupleInfo) -> Result<()> {
+        let mut def = ClassDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Class, info.rust_path, info.name)
+            .into();
+        self.insert_class(Some(info.module), (info.struct_id)(), def)
+    }
+
+    fn add_typed_dict(&mut self, info: &TypedDictInfo) -> Result<()> {
+        let mut def = ClassDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Class, info.rust_path, info.name)
+            .into();
+        self.insert_class(info.module, (info.struct_id)(), def)
     }
 
-    fn add_enum(&mut self, info: &PyEnumInfo) {
-        self.get_module(info.module)
-            .enum_
-            .insert((info.enum_id)(), EnumDef::from(info));
+    fn add_exception(&mut self, info: &PyExceptionInfo) -> Result<()> {
+        let mut def = ExceptionDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Exception, info.rust_path, info.name);
+        self.get_module(Some(info.module))?
+            .exceptions
+            .insert(def.name, def);
+        Ok(())
     }
 
-    fn add_function(&mut self, info: &PyFunctionInfo) {
-        let target = self
-            .get_module(info.module)
+    fn add_function(&mut self, info: &PyFunctionInfo) -> Result<()> {
+        let mut def = FunctionDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Function, info.rust_path, info.name)
+            .into();
+        self.get_module(info.module)?
             .function
-            .entry(info.name)
-            .or_default();
-        target.push(FunctionDef::from(info));
+            .entry(def.name.clone())
+            .or_default()
+            .push(def);
+        Ok(())
     }
 
-    fn add_variable(&mut self, info: &PyVariableInfo) {
-        self.get_module(Some(info.module))
+    fn add_variable(&mut self, info: &PyVariableInfo) -> Result<()> {
+        let mut def = VariableDef::from(info);
+        def.name = self
+            .renames
+            .name(ItemKind::Variable, info.rust_path, info.name);
+        self.get_module(Some(info.module).filter(|module| !module.is_empty()))?
             .variables
-            .insert(info.name, VariableDef::from(info));
+            .insert(def.name, def);
+        Ok(())
     }
 
-    fn add_module_doc(&mut self, info: &ModuleDocInfo) {
-        self.get_module(Some(info.module)).doc = (info.doc)();
+    fn add_module_doc(&mut self, info: &ModuleDocInfo) -> Result<()> {
+        self.get_module(Some(info.module))?.doc = (info.doc)();
+        Ok(())
     }
 
-    fn add_methods(&mut self, info: &PyMethodsInfo) {
+    fn add_attr_fallback(&mut self, info: &ModuleAttrFallbackInfo) -> Result<()> {
+        self.get_module(Some(info.module))?.attr_fallback = true;
+        Ok(())
+    }
+
+    /// Set the flags of the modules listed in `attr-fallback` and `partial`
+    fn flag_modules(&mut self) -> Result<()> {
+        for name in &self.attr_fallback {
+            listed_module(&mut self.modules, "attr-fallback", name)?.attr_fallback = true;
+        }
+        for name in &self.partial {
+            listed_module(&mut self.modules, "partial", name)?.partial = true;
+        }
+        for (name, modules) in self.reexport_all.clone() {
+            for target in &modules {
+                // A namespace package has no stub to re-export from
+                if !self.modules.contains_key(target) || self.namespace_packages.contains(target) {
+                    bail!(no_stub("reexport-all", target));
+                }
+            }
+            // The package re-exporting its submodules may have no item of its own
+            self.get_module(Some(&name))?.reexport_all.extend(modules);
+        }
+        Ok(())
+    }
+
+    /// Drop the stubs of `namespace-packages`, which exist only as the parents of their submodules,
+    /// and unlist them from their parents
+    fn remove_namespace_packages(&mut self) -> Result<()> {
+        for name in &self.namespace_packages {
+            // A namespace package may have no module of its own, but only submodules
+            let prefix = format!("{name}.");
+            if !self
+                .modules
+                .keys()
+                .any(|module| module == name || module.starts_with(&prefix))
+            {
+                bail!(no_stub("namespace-packages", name));
+            }
+            if let Some(module) = self.modules.remove(name) {
+                let defines_names = !(module.class.is_empty()
+                    && module.enum_.is_empty()
+                    && module.exceptions.is_empty()
+                    && module.function.is_empty()
+                    && module.variables.is_empty());
+                if defines_names {
+                    bail!(
+                        "Module `{name}` in `namespace-packages` of `[tool.pyo3-stub-gen]` defines names, \
+                         which a namespace package cannot hold"
+                    );
+                }
+            }
+            if let Some((parent, child)) = name.rsplit_once('.') {
+                if let Some(parent) = self.modules.get_mut(parent) {
+                    parent.submodules.remove(child);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Dotted path of the class or enum of `struct_id`, e.g. `mypkg.Foo`, if added
+    fn owner_of(&self, struct_id: TypeId) -> Option<String> {
+        let name = self.owners.get(&struct_id)?;
+        let module = &self.modules[name];
+        let owner = module
+            .class
+            .get(&struct_id)
+            .map(|class| class.name.as_ref());
+        let owner = owner.or_else(|| module.enum_.get(&struct_id).map(|enum_| enum_.name));
+        owner.map(|owner| format!("{name}.{owner}"))
+    }
+
+    fn add_methods(&mut self, info: &PyMethodsInfo) -> Result<()> {
         let struct_id = (info.struct_id)();
-        for module in self.modules.values_mut() {
+        let owner = self.owners.get(&struct_id);
+        if let Some(module) = owner.and_then(|name| self.modules.get_mut(name)) {
             if let Some(entry) = module.class.get_mut(&struct_id) {
                 for attr in info.attrs {
                     entry.attrs.push(MemberDef {
@@ -271,7 +746,10 @@ impl StubInfoBuilder {
                         doc: attr.doc,
                         default: attr.default.map(|f| f()),
                         deprecated: attr.deprecated.clone(),
+                        type_ignored: attr.type_ignored.map(Into::into),
                         is_abstract: false,
+                        is_classattr: attr.is_classattr,
+                        qualname: QualName::default(),
                     });
                 }
                 for getter in info.getters {
@@ -285,7 +763,10 @@ impl StubInfoBuilder {
                         doc: getter.doc,
                         default: getter.default.map(|f| f()),
                         deprecated: getter.deprecated.clone(),
+                        type_ignored: getter.type_ignored.map(Into::into),
                         is_abstract: getter.is_abstract,
+                        is_classattr: false,
+                        qualname: QualName::default(),
                     });
                     if getter.is_abstract {
                         entry.mark_abstract();
@@ -302,14 +783,37 @@ impl StubInfoBuilder {
                         doc: setter.doc,
                         default: setter.default.map(|f| f()),
                         deprecated: setter.deprecated.clone(),
+                        type_ignored: setter.type_ignored.map(Into::into),
                         is_abstract: setter.is_abstract,
+                        is_classattr: false,
+                        qualname: QualName::default(),
                     });
                     if setter.is_abstract {
                         entry.mark_abstract();
                     }
                 }
+                if let Some(dataclass) = &mut entry.dataclass {
+                    // A `#[setter]` makes the instances mutable as `#[pyo3(set)]` does
+                    dataclass.frozen &= info.setters.is_empty();
+                }
                 for method in info.methods {
-                    let method_def = MethodDef::from(method);
+                    let mut method_def = MethodDef::from(method);
+                    method_def.resolve_enter_return(
+                        &entry.name,
+                        &module.name,
+                        &module.default_module_name,
+                    );
+                    if let Some(dataclass) = &entry.dataclass {
+                        if method_def.r#type == MethodType::New {
+                            dataclass.check_constructor(&method_def).with_context(|| {
+                                format!(
+                                    "`#[new]` of dataclass `{}`{} does not match its fields",
+                                    entry.name,
+                                    qualname::at_location(method_def.source_location)
+                                )
+                            })?;
+                        }
+                    }
                     if method_def.is_abstract {
                         entry.mark_abstract();
                     }
@@ -319,7 +823,7 @@ impl StubInfoBuilder {
                         .or_default()
                         .push(method_def);
                 }
-                return;
+                return Ok(());
             } else if let Some(entry) = module.enum_.get_mut(&struct_id) {
                 for attr in info.attrs {
                     entry.attrs.push(MemberDef {
@@ -328,7 +832,10 @@ impl StubInfoBuilder {
                         doc: attr.doc,
                         default: attr.default.map(|f| f()),
                         deprecated: attr.deprecated.clone(),
+                        type_ignored: attr.type_ignored.map(Into::into),
                         is_abstract: false,
+                        is_classattr: attr.is_classattr,
+                        qualname: QualName::default(),
                     });
                 }
                 for getter in info.getters {
@@ -338,7 +845,10 @@ impl StubInfoBuilder {
                         doc: getter.doc,
                         default: getter.default.map(|f| f()),
                         deprecated: getter.deprecated.clone(),
+                        type_ignored: getter.type_ignored.map(Into::into),
                         is_abstract: getter.is_abstract,
+                        is_classattr: false,
+                        qualname: QualName::default(),
                     });
                 }
                 for setter in info.setters {
@@ -348,44 +858,1184 @@ impl StubInfoBuilder {
                         doc: setter.doc,
                         default: setter.default.map(|f| f()),
                         deprecated: setter.deprecated.clone(),
+                        type_ignored: setter.type_ignored.map(Into::into),
                         is_abstract: setter.is_abstract,
+                        is_classattr: false,
+                        qualname: QualName::default(),
                     });
                 }
                 for method in info.methods {
-                    entry.methods.push(MethodDef::from(method))
+                    let mut method_def = MethodDef::from(method);
+                    method_def.resolve_enter_return(
+                        entry.name,
+                        &module.name,
+                        &module.default_module_name,
+                    );
+                    entry.methods.push(method_def)
                 }
-                return;
+                return Ok(());
             }
         }
-        unreachable!("Missing struct_id/enum_id = {:?}", struct_id);
+        bail!(
+            "Methods of `{}`{} are generated, but it is not a class or enum of `#[gen_stub_pyclass]`, `#[gen_stub_pyclass_enum]` or `#[gen_stub_pyclass_complex_enum]`",
+            (info.struct_name)(),
+            qualname::at_location(info.source_location)
+        );
     }
 
-    fn build(mut self) -> StubInfo {
+    fn build(mut self) -> Result<StubInfo> {
+        // `PyStubType` and the passes below read the configuration of this builder, not the one of the setters
+        let config = self.config;
+        let _config = config.scope();
+        for warning in lint::override_warnings() {
+            log::warn!("{warning}");
+        }
+        self.renames = resolve_name_conflicts(
+            named_items(&self.default_module_name),
+            config.name_conflict_resolver,
+        )?;
+        // Items are independent of each other, so that all of the broken ones are reported at once
+        let default_module = self.default_module_name.clone();
+        let module = |module: Option<&str>| module.unwrap_or(&default_module).to_string();
+        let mut errors = Vec::new();
+        // Classes and enums of disabled features, whose methods are dropped as well
+        let mut disabled = BTreeSet::new();
         for info in inventory::iter::<PyClassInfo> {
-            self.add_class(info);
+            if !self.is_enabled(info.feature) {
+                log::debug!(
+                    "Skip class `{}` of a disabled feature `{}`",
+                    info.pyclass_name,
+                    info.feature.unwrap_or_default()
+                );
+                disabled.insert((info.struct_id)());
+                continue;
+            }
+            let item = || {
+                describe(
+                    "class",
+                    &module(info.module),
+                    info.pyclass_name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_class(info)).err());
         }
         for info in inventory::iter::<PyComplexEnumInfo> {
-            self.add_complex_enum(info);
+            if !self.is_enabled(info.feature) {
+                log::debug!(
+                    "Skip enum `{}` of a disabled feature `{}`",
+                    info.pyclass_name,
+                    info.feature.unwrap_or_default()
+                );
+                disabled.insert((info.enum_id)());
+                continue;
+            }
+            let item = || {
+                describe(
+                    "enum",
+                    &module(info.module),
+                    info.pyclass_name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_complex_enum(info)).err());
         }
         for info in inventory::iter::<PyEnumInfo> {
-            self.add_enum(info);
+            if !self.is_enabled(info.feature) {
+                log::debug!(
+                    "Skip enum `{}` of a disabled feature `{}`",
+                    info.pyclass_name,
+                    info.feature.unwrap_or_default()
+                );
+                disabled.insert((info.enum_id)());
+                continue;
+            }
+            let item = || {
+                describe(
+                    "enum",
+                    &module(info.module),
+                    info.pyclass_name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_enum(info)).err());
+        }
+        for info in inventory::iter::<PyExceptionInfo> {
+            if !self.is_enabled(info.feature) {
+                log::debug!(
+                    "Skip exception `{}` of a disabled feature `{}`",
+                    info.name,
+                    info.feature.unwrap_or_default()
+                );
+                continue;
+            }
+            let item = || {
+                describe(
+                    "exception",
+                    info.module,
+                    info.name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_exception(info)).err());
+        }
+        for info in inventory::iter::<NamedTupleInfo> {
+            let item = || {
+                describe(
+                    "named tuple",
+                    info.module,
+                    info.name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_named_tuple(info)).err());
+        }
+        for info in inventory::iter::<TypedDictInfo> {
+            let item = || {
+                describe(
+                    "typed dict",
+                    &module(info.module),
+                    info.name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_typed_dict(info)).err());
         }
         for info in inventory::iter::<PyFunctionInfo> {
-            self.add_function(info);
+            if !self.is_enabled(info.feature) {
+                log::debug!(
+                    "Skip function `{}` of a disabled feature `{}`",
+                    info.name,
+                    info.feature.unwrap_or_default()
+                );
+                continue;
+            }
+            let item = || {
+                describe(
+                    "function",
+                    &module(info.module),
+                    info.name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_function(info)).err());
         }
         for info in inventory::iter::<PyVariableInfo> {
-            self.add_variable(info);
+            let item = || {
+                describe(
+                    "variable",
+                    info.module,
+                    info.name,
+                    info.rust_path,
+                    info.source_location,
+                )
+            };
+            errors.extend(add_item(item, || self.add_variable(info)).err());
         }
         for info in inventory::iter::<ModuleDocInfo> {
-            self.add_module_doc(info);
+            let item = || format!("doc of module `{}`", info.module);
+            errors.extend(add_item(item, || self.add_module_doc(info)).err());
+        }
+        for info in inventory::iter::<ModuleAttrFallbackInfo> {
+            let item = || format!("`__getattr__` fallback of module `{}`", info.module);
+            errors.extend(add_item(item, || self.add_attr_fallback(info)).err());
         }
-        for info in inventory::iter::<PyMethodsInfo> {
-            self.add_methods(info);
+        let skipped: BTreeSet<_> = inventory::iter::<SkippedClassInfo>
+            .into_iter()
+            .inspect(|info| {
+                if info.as_any {
+                    log::warn!(
+                        "`{}`{} is skipped by `#[gen_stub(skip)]`, and rendered as `typing.Any` where it is referred to",
+                        info.pyclass_name,
+                        qualname::at_location(info.source_location)
+                    );
+                }
+            })
+            .map(|info| (info.struct_id)())
+            .collect();
+        for info in ordering::sorted_methods(inventory::iter::<PyMethodsInfo>) {
+            let struct_id = (info.struct_id)();
+            if skipped.contains(&struct_id) || disabled.contains(&struct_id) {
+                continue;
+            }
+            let owner = self.owner_of(struct_id);
+            let item = || match &owner {
+                Some(owner) => format!("methods of `{owner}` ({})", (info.struct_name)()),
+                None => format!("methods of `{}`", (info.struct_name)()),
+            };
+            errors.extend(add_item(item, || self.add_methods(info)).err());
+        }
+        report(errors)?;
+        for info in inventory::iter::<PyComplexEnumInfo> {
+            let name = self
+                .renames
+                .name(ItemKind::Enum, info.rust_path, info.pyclass_name);
+            if let Some(owner) = self
+                .owners
+                .get(&(info.enum_id)())
+                .filter(|_| name != info.pyclass_name)
+            {
+                transform::rename_references(
+                    &mut self.modules,
+                    owner,
+                    &complex_enum_alias(info.pyclass_name),
+                    &complex_enum_alias(name),
+                );
+            }
         }
+        literal_group::add_literal_groups(
+            &self.modules,
+            &mut self.synthesized,
+            inventory::iter::<LiteralGroupInfo>,
+        )?;
+        alias::add_aliases(&mut self.modules, &alias::aliased_members())?;
+        let hidden_bases = inventory::iter::<PyClassInfo>
+            .into_iter()
+            .filter(|info| info.hidden_base)
+            .map(|info| (info.struct_id)())
+            .collect();
+        hidden_base::flatten_hidden_bases(
+            &mut self.modules,
+            &hidden_bases,
+            &self.default_module_name,
+        );
+        let flag_enums = inventory::iter::<PyClassInfo>
+            .into_iter()
+            .filter(|info| info.flag_enum)
+            .map(|info| (info.struct_id)())
+            .collect();
+        let flags =
+            flag_enum::render_flag_enums(&mut self.modules, &flag_enums, &flag_enum::flag_values());
+        // `enum.Flag` is already iterable and hashable
+        let mut no_abc: BTreeSet<TypeId> = inventory::iter::<PyClassInfo>
+            .into_iter()
+            .filter(|info| info.no_abc)
+            .map(|info| (info.struct_id)())
+            .collect();
+        no_abc.extend(flags);
+        let total_orderings = inventory::iter::<PyClassInfo>
+            .into_iter()
+            .filter(|info| info.total_ordering)
+            .map(|info| (info.struct_id)())
+            .collect();
+        total_ordering::add_total_orderings(&mut self.modules, &total_orderings);
+        let pickle_states = inventory::iter::<PyClassInfo>
+            .into_iter()
+            .filter_map(|info| Some(((info.struct_id)(), (info.pickle_state?)())))
+            .collect();
+        pickle::add_pickle_states(&mut self.modules, &pickle_states);
+        abc_bases::add_abc_bases(&mut self.modules, &no_abc);
+        override_conflict::ignore_override_conflicts(&mut self.modules, &self.default_module_name);
+        if self.emit_override_decorator {
+            override_decorator::mark_overrides(&mut self.modules, &self.default_module_name);
+        }
+        self.normalize_module_references()?;
+        self.synthesized
+            .rename_modules(&self.module_renames, &self.default_module_name);
+        self.module_renames
+            .apply(&mut self.modules, &mut self.default_module_name)?;
+        // Packages created for `reexport-all` are listed as the parents of their submodules too
+        self.flag_modules()?;
         self.register_submodules();
-        StubInfo {
+        self.remove_namespace_packages()?;
+        self.synthesized
+            .apply(&mut self.modules, &self.default_module_name)?;
+        for module in self.modules.values_mut() {
+            module.unqualify_local_references();
+        }
+        if config.target_version < TargetVersion::Py311 {
+            for module in self.modules.values_mut() {
+                module.for_each_type_mut(&mut |ty| {
+                    target_version::downgrade(ty, config.target_version)
+                });
+                for symbol in module.synthesized.values_mut() {
+                    symbol.downgrade(config.target_version);
+                }
+            }
+        }
+        for module in self.modules.values_mut() {
+            module.sort_overloads();
+            module.assign_qualnames();
+            module.config = Some(config);
+        }
+        let mut info = StubInfo {
             modules: self.modules,
             python_root: self.python_root,
+            layout: self.layout,
+            merge: self.merge,
+            config,
+        };
+        if let Some(header) = self.header {
+            info.set_header(Some(header));
+        }
+        if let Some(prefix) = &self.strip_class_prefix {
+            info.rename_types(|name| strip_class_prefix(name, prefix))?;
+        }
+        if let Some(filter) = &self.filter {
+            info.apply_filter(filter);
+        }
+        info.resolve_forward_references(self.forward_references);
+        info.check_keywords(self.keyword_parameters)?;
+        info.validate(self.unresolved_references)?;
+        Ok(info)
+    }
+}
+
+/// `name` without `prefix` if an uppercase letter follows it, e.g. `Foo` of `PyFoo` but not `thon` of `Python`
+pub(crate) fn strip_class_prefix(name: &str, prefix: &str) -> Option<String> {
+    name.strip_prefix(prefix)
+        .filter(|rest| rest.starts_with(|c: char| c.is_uppercase()))
+        .map(str::to_string)
+}
+
+/// Module `name` listed in `key` of `[tool.pyo3-stub-gen]`
+fn listed_module<'a>(
+    modules: &'a mut BTreeMap<String, Module>,
+    key: &str,
+    name: &str,
+) -> Result<&'a mut Module> {
+    modules.get_mut(name).with_context(|| no_stub(key, name))
+}
+
+fn no_stub(key: &str, name: &str) -> String {
+    format!("Module `{name}` in `{key}` of `[tool.pyo3-stub-gen]` has no stub")
+}
+
+/// Whether the content of `py.typed` marks the package as partial following PEP 561
+fn is_partial(py_typed: &str) -> bool {
+    py_typed.lines().any(|line| line.trim() == "partial")
+}
+
+/// Describe an item in errors, e.g. ``function `mypkg.run` (my_crate::run)``
+fn describe(
+    kind: &str,
+    module: &str,
+    name: &str,
+    rust_path: &str,
+    source_location: Option<&str>,
+) -> String {
+    let at = qualname::at_location(source_location);
+    format!("{kind} `{module}.{name}` ({rust_path}{at})")
+}
+
+/// Run `f` adding an item, and attribute its error to the `item`
+fn add_item(item: impl Fn() -> String, f: impl FnOnce() -> Result<()>) -> Result<()> {
+    f().with_context(item)
+}
+
+/// Report the errors of items as one error, listing all of them
+fn report(mut errors: Vec<anyhow::Error>) -> Result<()> {
+    if errors.len() > 1 {
+        let list = errors
+            .iter()
+            .map(|err| format!("  - {err:#}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "Failed to generate stubs of {} items:\n{list}",
+            errors.len()
+        );
+    }
+    errors.pop().map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeInfo;
+
+    #[test]
+    fn parses_minimum_python_version() {
+        assert_eq!(parse_minimum_python_version(">=3.10"), Some((3, 10)));
+        assert_eq!(parse_minimum_python_version(">=3.8, <3.12"), Some((3, 8)));
+        assert_eq!(parse_minimum_python_version("~=3.11.0"), Some((3, 11)));
+        assert_eq!(parse_minimum_python_version(""), None);
+        assert_eq!(parse_minimum_python_version(">=3"), Some((3, 0)));
+    }
+
+    #[test]
+    fn configure_strategy_defaults_to_typing_when_unspecified() {
+        assert_eq!(
+            self_import_strategy_from_requires_python(None),
+            SelfImportStrategy::Typing
+        );
+    }
+
+    #[test]
+    fn configure_strategy_prefers_typing_extensions_below_311() {
+        assert_eq!(
+            self_import_strategy_from_requires_python(Some(">=3.10")),
+            SelfImportStrategy::TypingExtensions
+        );
+    }
+
+    #[test]
+    fn configure_strategy_prefers_typing_from_311_onwards() {
+        assert_eq!(
+            self_import_strategy_from_requires_python(Some(">=3.11")),
+            SelfImportStrategy::Typing
+        );
+    }
+
+    fn stub_info() -> StubInfo {
+        let module = |name: &str| Module {
+            name: name.into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        let mut pkg = module("pkg");
+        pkg.submodules.insert("_core".into());
+        StubInfo {
+            modules: BTreeMap::from([
+                ("pkg".into(), pkg),
+                ("pkg._core".into(), module("pkg._core")),
+            ]),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    /// Sink holding the stub files in memory
+    #[derive(Default)]
+    struct MemorySink(BTreeMap<PathBuf, String>);
+
+    impl StubSink for MemorySink {
+        fn write(&mut self, rel_path: &Path, content: &str) -> Result<()> {
+            self.0.insert(rel_path.to_path_buf(), content.to_string());
+            Ok(())
+        }
+
+        fn exists(&self, rel_path: &Path) -> bool {
+            self.0.contains_key(rel_path)
+        }
+
+        fn read(&self, rel_path: &Path) -> Result<String> {
+            Ok(self.0[rel_path].clone())
+        }
+    }
+
+    /// Sink rejecting every write, e.g. of a read-only directory
+    struct ReadOnlySink;
+
+    impl StubSink for ReadOnlySink {
+        fn write(&mut self, _: &Path, _: &str) -> Result<()> {
+            bail!("read-only file system")
+        }
+
+        fn exists(&self, _: &Path) -> bool {
+            false
+        }
+
+        fn read(&self, rel_path: &Path) -> Result<String> {
+            bail!("{} does not exist", rel_path.display())
+        }
+    }
+
+    #[test]
+    fn errors_of_rendering_name_the_module() {
+        let err = stub_info().generate_into(&mut ReadOnlySink).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "Failed to write the stub of module `pkg`: read-only file system"
+        );
+
+        let mut sink = MemorySink::default();
+        let dest = PathBuf::from("pkg/_core.pyi");
+        sink.0.insert(dest, format!("{}\n", layout::BEGIN_MARKER));
+        let info = StubInfo {
+            merge: true,
+            ..stub_info()
+        };
+        let err = info.generate_into(&mut sink).unwrap_err();
+        assert!(
+            format!("{err:#}").starts_with("Failed to merge into pkg/_core.pyi: "),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn filtered_keeps_matching_modules() {
+        let info = stub_info().filtered(|name| name == "pkg._core");
+        assert_eq!(info.modules.keys().collect::<Vec<_>>(), ["pkg._core"]);
+    }
+
+    #[test]
+    fn check_reports_outdated_stubs() -> Result<()> {
+        let info = stub_info();
+        let mut sink = MemorySink::default();
+        assert_eq!(
+            info.check_against(&sink)?,
+            [
+                PathBuf::from("pkg/__init__.pyi"),
+                PathBuf::from("pkg/_core.pyi")
+            ]
+        );
+
+        info.generate_into(&mut sink)?;
+        assert!(info.check_against(&sink)?.is_empty());
+
+        sink.write(Path::new("pkg/_core.pyi"), "# stale\n")?;
+        assert_eq!(info.check_against(&sink)?, [PathBuf::from("pkg/_core.pyi")]);
+        // Stubs of the modules filtered out are not checked
+        assert!(info
+            .filtered(|name| name == "pkg")
+            .check_against(&sink)?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn generate_skips_unchanged_stubs() -> Result<()> {
+        let root =
+            std::env::temp_dir().join(format!("pyo3-stub-gen-incremental-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let mut info = stub_info();
+        info.python_root = root.clone();
+        let paths = [root.join("pkg/__init__.pyi"), root.join("pkg/_core.pyi")];
+
+        let report = info.generate_with_report()?;
+        assert_eq!(report.written, paths);
+        assert!(report.unchanged.is_empty());
+        let mtimes = paths
+            .iter()
+            .map(|path| std::fs::metadata(path)?.modified())
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let report = info.generate_with_report()?;
+        assert!(report.written.is_empty());
+        assert_eq!(report.unchanged, paths);
+        for (path, mtime) in paths.iter().zip(&mtimes) {
+            assert_eq!(&std::fs::metadata(path)?.modified()?, mtime);
+        }
+
+        // Only the stale file is rewritten
+        std::fs::write(&paths[1], "# stale\n")?;
+        let report = info.generate_with_report()?;
+        assert_eq!(report.written, [paths[1].clone()]);
+        assert_eq!(report.unchanged, [paths[0].clone()]);
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn package_layout_and_merge() -> Result<()> {
+        let mut info = stub_info();
+        info.layout = StubLayout::Package;
+        info.merge = true;
+        let mut sink = MemorySink::default();
+        let core = Path::new("pkg/_core/__init__.pyi");
+        sink.write(
+            core,
+            "# BEGIN pyo3-stub-gen\n# END pyo3-stub-gen\n\ndef extra() -> None: ...\n",
+        )?;
+        info.generate_into(&mut sink)?;
+        assert_eq!(
+            sink.0.keys().collect::<Vec<_>>(),
+            [Path::new("pkg/__init__.pyi"), core]
+        );
+        let merged = sink.read(core)?;
+        let generated = info.modules["pkg._core"].to_string();
+        assert_eq!(
+            merged,
+            format!("# BEGIN pyo3-stub-gen\n{generated}# END pyo3-stub-gen\n\ndef extra() -> None: ...\n")
+        );
+        assert!(info.check_against(&sink)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn partial_module_marks_py_typed() -> Result<()> {
+        let mut info = stub_info();
+        info.modules.get_mut("pkg._core").unwrap().partial = true;
+        let mut sink = MemorySink::default();
+        let py_typed = PathBuf::from("pkg/py.typed");
+        assert!(info.check_against(&sink)?.contains(&py_typed));
+
+        info.generate_into(&mut sink)?;
+        assert_eq!(sink.read(&py_typed)?, "partial\n");
+        assert!(sink
+            .read(Path::new("pkg/_core.pyi"))?
+            .contains("\n# partial\n"));
+        assert!(info.check_against(&sink)?.is_empty());
+
+        sink.write(&py_typed, "")?;
+        assert_eq!(info.check_against(&sink)?, std::slice::from_ref(&py_typed));
+
+        // The `partial` line is appended to an existing marker
+        sink.write(&py_typed, "# Typed by the package")?;
+        info.generate_into(&mut sink)?;
+        assert_eq!(sink.read(&py_typed)?, "# Typed by the package\npartial\n");
+        Ok(())
+    }
+
+    fn builder() -> StubInfoBuilder {
+        StubInfoBuilder::from_project_root("mypkg".to_string(), PathBuf::new())
+    }
+
+    #[test]
+    fn modules_keep_configuration() -> Result<()> {
+        let mut builder = builder();
+        builder.config.generate_dunder_all = false;
+        builder.get_module(None)?.variables.insert(
+            "VERSION",
+            VariableDef::new("VERSION", TypeInfo::builtin("str")),
+        );
+        let info = builder.build()?;
+        // Formatted outside of the scope of `StubInfo`
+        assert!(Config::current().generate_dunder_all);
+        let rendered = info.modules["mypkg"].to_string();
+        assert!(!rendered.contains("__all__"), "{rendered}");
+        assert_eq!(info.stub_files()?[0].2, rendered);
+        Ok(())
+    }
+
+    #[test]
+    fn flag_modules() -> Result<()> {
+        let mut builder = builder();
+        builder.get_module(Some("mypkg._core"))?;
+        builder.attr_fallback = vec!["mypkg._core".to_string()];
+        builder.partial = vec!["mypkg._core".to_string()];
+        builder.flag_modules()?;
+        let core = &builder.modules["mypkg._core"];
+        assert!(core.attr_fallback && core.partial);
+
+        builder.partial = vec!["mypkg._plugins".to_string()];
+        assert_eq!(
+            builder.flag_modules().unwrap_err().to_string(),
+            "Module `mypkg._plugins` in `partial` of `[tool.pyo3-stub-gen]` has no stub"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn namespace_packages() -> Result<()> {
+        let mut company = StubInfoBuilder::from_project_root(
+            "company.ns.tools._impl".to_string(),
+            PathBuf::new(),
+        );
+        company.get_module(None)?;
+        company.namespace_packages = vec!["company".to_string(), "company.ns".to_string()];
+        company.reexport_all = BTreeMap::from([(
+            "company.ns.tools".to_string(),
+            vec!["company.ns.tools._impl".to_string()],
+        )]);
+        company.flag_modules()?;
+        company.register_submodules();
+        company.remove_namespace_packages()?;
+        let info = StubInfo {
+            modules: company.modules,
+            python_root: PathBuf::new(),
+            layout: StubLayout::Module,
+            merge: false,
+            config: Default::default(),
+        };
+        let paths: Vec<_> = info
+            .stub_files()
+            .unwrap()
+            .into_iter()
+            .map(|(_, dest, _)| dest)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("company/ns/tools/__init__.pyi"),
+                PathBuf::from("company/ns/tools/_impl.pyi"),
+            ]
+        );
+        let tools = info.modules["company.ns.tools"].to_string();
+        assert!(
+            tools.contains("from . import _impl\nfrom company.ns.tools._impl import *\n"),
+            "{tools}"
+        );
+
+        // Unknown modules are rejected as in the other lists
+        let mut typo = builder();
+        typo.get_module(Some("mypkg._core"))?;
+        typo.namespace_packages = vec!["mypgk".to_string()];
+        assert_eq!(
+            typo.remove_namespace_packages().unwrap_err().to_string(),
+            "Module `mypgk` in `namespace-packages` of `[tool.pyo3-stub-gen]` has no stub"
+        );
+        typo.namespace_packages = vec!["mypkg".to_string()];
+        typo.remove_namespace_packages()?;
+        typo.reexport_all =
+            BTreeMap::from([("mypkg._core".to_string(), vec!["mypkg._ext".to_string()])]);
+        assert_eq!(
+            typo.flag_modules().unwrap_err().to_string(),
+            "Module `mypkg._ext` in `reexport-all` of `[tool.pyo3-stub-gen]` has no stub"
+        );
+
+        // A namespace package cannot define names
+        let mut builder = builder();
+        builder.get_module(Some("mypkg"))?.variables.insert(
+            "VERSION",
+            VariableDef::new("VERSION", TypeInfo::builtin("str")),
+        );
+        builder.namespace_packages = vec!["mypkg".to_string()];
+        assert_eq!(
+            builder.remove_namespace_packages().unwrap_err().to_string(),
+            "Module `mypkg` in `namespace-packages` of `[tool.pyo3-stub-gen]` defines names, which a namespace package cannot hold"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn enabled_tags() {
+        let gpu = builder().with_enabled_tags(parse_tags(" cuda, ,experimental"));
+        assert!(gpu.is_enabled(None));
+        assert!(gpu.is_enabled(Some("cuda")));
+        assert!(gpu.is_enabled(Some("experimental")));
+        assert!(!gpu.is_enabled(Some("rocm")));
+
+        // Only untagged items are included in the default flavor
+        let default = builder().with_enabled_tags(parse_tags(""));
+        assert!(default.is_enabled(None));
+        assert!(!default.is_enabled(Some("cuda")));
+    }
+
+    #[test]
+    fn python_root_of_pyproject() -> Result<()> {
+        let pure = PyProject::from_toml_str(
+            r#"
+            [project]
+            name = "mypkg"
+            "#,
+            Path::new("/work/mypkg/pyproject.toml"),
+            None,
+        )?;
+        assert_eq!(
+            StubInfoBuilder::from_pyproject_toml(pure)?.python_root,
+            Path::new("/work/mypkg")
+        );
+
+        let mixed = PyProject::from_toml_str(
+            r#"
+            [project]
+            name = "mypkg"
+
+            [tool.maturin]
+            python-source = "python"
+            "#,
+            Path::new("/work/mypkg/pyproject.toml"),
+            None,
+        )?;
+        assert_eq!(
+            StubInfoBuilder::from_pyproject_toml(mixed)?.python_root,
+            Path::new("/work/mypkg/python")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tags_in_pyproject() -> Result<()> {
+        let pyproject = PyProject::from_toml_str(
+            r#"
+            [project]
+            name = "mypkg"
+
+            [tool.maturin]
+            python-source = "python"
+
+            [tool.pyo3-stub-gen]
+            tags = ["cuda"]
+            "#,
+            Path::new("/work/mypkg/pyproject.toml"),
+            None,
+        )?;
+        let builder = StubInfoBuilder::from_pyproject_toml(pyproject)?;
+        if std::env::var_os(ENABLED_TAGS_VAR).is_none() {
+            assert!(builder.is_enabled(Some("cuda")));
+            assert!(!builder.is_enabled(Some("rocm")));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn strip_class_prefix() {
+        assert_eq!(
+            super::strip_class_prefix("PyFoo", "Py").as_deref(),
+            Some("Foo")
+        );
+        assert_eq!(
+            super::strip_class_prefix("PyBarBaz", "Py").as_deref(),
+            Some("BarBaz")
+        );
+        assert_eq!(super::strip_class_prefix("Python", "Py"), None);
+        assert_eq!(super::strip_class_prefix("Py", "Py"), None);
+        assert_eq!(super::strip_class_prefix("Foo", "Py"), None);
+    }
+
+    #[test]
+    fn methods_of_missing_class() {
+        struct Unexported;
+        let info = PyMethodsInfo {
+            struct_id: TypeId::of::<Unexported>,
+            struct_name: std::any::type_name::<Unexported>,
+            attrs: &[],
+            getters: &[],
+            setters: &[],
+            methods: &[],
+            source_location: Some("src/lib.rs:42"),
+        };
+        let err = builder().add_methods(&info).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Methods of `pyo3_stub_gen::generate::stub_info::tests::methods_of_missing_class::Unexported` at src/lib.rs:42 are generated, but it is not a class or enum of `#[gen_stub_pyclass]`, `#[gen_stub_pyclass_enum]` or `#[gen_stub_pyclass_complex_enum]`"
+        );
+    }
+
+    /// Ids of 4096 distinct types, i.e. the triples of 16 primitive types
+    fn many_type_ids() -> Vec<fn() -> TypeId> {
+        macro_rules! for_primitives {
+            ($t:ident => $body:expr) => {{
+                for_primitives!(@each $t => $body; u8, u16, u32, u64, u128, usize, i8, i16,
+                    i32, i64, i128, isize, f32, f64, bool, char);
+            }};
+            (@each $t:ident => $body:expr; $($primitive:ty),*) => {
+                $({
+                    type $t = $primitive;
+                    $body;
+                })*
+            };
         }
+        fn third<A: 'static, B: 'static>(ids: &mut Vec<fn() -> TypeId>) {
+            for_primitives!(C => ids.push(TypeId::of::<(A, B, C)>));
+        }
+        fn second<A: 'static>(ids: &mut Vec<fn() -> TypeId>) {
+            for_primitives!(B => third::<A, B>(ids));
+        }
+        let mut ids = Vec::new();
+        for_primitives!(A => second::<A>(&mut ids));
+        ids
+    }
+
+    #[test]
+    fn methods_of_many_classes() -> Result<()> {
+        static METHODS: [MethodInfo; 1] = [MethodInfo {
+            name: "ping",
+            parameters: &[],
+            r#return: TypeInfo::none,
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            also_as: &[],
+            keep_signature: false,
+            source_location: None,
+        }];
+        let leak = |name: String| -> &'static str { Box::leak(name.into_boxed_str()) };
+        let ids = many_type_ids();
+        assert_eq!(
+            ids.iter().map(|id| id()).collect::<HashSet<_>>().len(),
+            4096
+        );
+        let mut builder = builder();
+        for (k, id) in ids.iter().enumerate() {
+            builder.add_class(&PyClassInfo {
+                struct_id: *id,
+                pyclass_name: leak(format!("C{k}")),
+                name_given: false,
+                module: Some(leak(format!("mypkg.m{}", k % 256))),
+                doc: "",
+                getters: &[],
+                setters: &[],
+                bases: &[],
+                has_eq: false,
+                has_ord: false,
+                has_hash: false,
+                has_str: false,
+                subclass: false,
+                is_abstract: false,
+                type_override: Default::default(),
+                dataclass: false,
+                rust_path: "my_crate::C",
+                hidden_base: false,
+                no_abc: false,
+                flag_enum: false,
+                total_ordering: false,
+                pickle_state: None,
+                slots: false,
+                weakref: false,
+                feature: None,
+                deprecated: None,
+                source_location: None,
+            })?;
+        }
+        for id in &ids {
+            builder.add_methods(&PyMethodsInfo {
+                struct_id: *id,
+                struct_name: || "my_crate::C",
+                attrs: &[],
+                getters: &[],
+                setters: &[],
+                methods: &METHODS,
+                source_location: None,
+            })?;
+        }
+        assert_eq!(builder.modules.len(), 256);
+        for module in builder.modules.values() {
+            assert_eq!(module.class.len(), 16);
+            assert!(module
+                .class
+                .values()
+                .all(|class| class.methods.contains_key("ping")));
+        }
+        assert_eq!(builder.owner_of(ids[300]()).unwrap(), "mypkg.m44.C300");
+        Ok(())
+    }
+
+    #[test]
+    fn dataclass_with_pymethods() -> Result<()> {
+        fn int_type() -> TypeInfo {
+            TypeInfo::builtin("int")
+        }
+        static FIELDS: [MemberInfo; 2] = [
+            MemberInfo {
+                name: "x",
+                r#type: int_type,
+                doc: "",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+            MemberInfo {
+                name: "y",
+                r#type: int_type,
+                doc: "",
+                default: None,
+                deprecated: None,
+                item: false,
+                is_abstract: false,
+                also_as: &[],
+                is_classattr: false,
+                int_value: None,
+                type_ignored: None,
+            },
+        ];
+        static PARAMETERS: [ParameterInfo; 2] = [
+            ParameterInfo {
+                name: "y",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: int_type,
+                default: crate::type_info::ParameterDefault::None,
+                type_overridden: false,
+                container_default: None,
+                deprecated: None,
+            },
+            ParameterInfo {
+                name: "x",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: int_type,
+                default: crate::type_info::ParameterDefault::None,
+                type_overridden: false,
+                container_default: None,
+                deprecated: None,
+            },
+        ];
+        static NEW: [MethodInfo; 1] = [MethodInfo {
+            name: "__new__",
+            parameters: &PARAMETERS,
+            r#type: MethodType::New,
+            source_location: Some("src/point.rs:9"),
+            ..MethodInfo::DEFAULT
+        }];
+        let class = PyClassInfo {
+            struct_id: TypeId::of::<u8>,
+            pyclass_name: "Point",
+            module: Some("mypkg"),
+            getters: &FIELDS,
+            dataclass: true,
+            ..PyClassInfo::DEFAULT
+        };
+        let methods = PyMethodsInfo {
+            struct_id: TypeId::of::<u8>,
+            struct_name: || "my_crate::Point",
+            setters: &FIELDS[..1],
+            ..PyMethodsInfo::DEFAULT
+        };
+
+        let mut mutable = builder();
+        mutable.add_class(&class)?;
+        mutable.add_methods(&methods)?;
+        let point = &mutable.modules["mypkg"].class[&TypeId::of::<u8>()];
+        assert!(!point.dataclass.as_ref().unwrap().frozen);
+
+        let mut reordered = builder();
+        reordered.add_class(&class)?;
+        let err = reordered
+            .add_methods(&PyMethodsInfo {
+                methods: &NEW,
+                ..methods
+            })
+            .unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "`#[new]` of dataclass `Point` at src/point.rs:9 does not match its fields: \
+             it takes (y, x), but the dataclass is constructed by (x, y)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn variable_in_default_module() -> Result<()> {
+        let info = PyVariableInfo {
+            name: "VERSION",
+            module: crate::concat_module!(),
+            rust_path: "my_crate",
+            r#type: || TypeInfo::builtin("str"),
+            default: None,
+            doc: "Version of the package",
+            ..PyVariableInfo::DEFAULT
+        };
+        let mut builder = builder();
+        builder.add_variable(&info)?;
+        assert_eq!(
+            builder.modules["mypkg"].variables["VERSION"].doc,
+            "Version of the package"
+        );
+        Ok(())
+    }
+
+    fn function_in(module: &'static str, r#return: fn() -> TypeInfo) -> PyFunctionInfo {
+        PyFunctionInfo {
+            name: "load",
+            rust_path: "my_crate::load",
+            parameters: &[],
+            r#return,
+            doc: "",
+            module: Some(module),
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            feature: None,
+            source_location: None,
+        }
+    }
+
+    #[test]
+    fn function_in_two_modules() -> Result<()> {
+        // Submitted once per module by `#[gen_stub_pyfunction(module = "...", module = "...")]`
+        let r#return = || TypeInfo::locally_defined("Handle", "mypkg._core".into());
+        let mut builder = builder();
+        builder.add_function(&function_in("mypkg._core", r#return))?;
+        builder.add_function(&function_in("mypkg.compat", r#return))?;
+        builder.normalize_module_references()?;
+        let core = builder.modules["mypkg._core"].to_string();
+        let compat = builder.modules["mypkg.compat"].to_string();
+        assert!(core.contains("def load() -> Handle: ..."), "{core}");
+        assert!(!core.contains("import Handle"), "{core}");
+        assert!(compat.contains("def load() -> Handle: ..."), "{compat}");
+        assert!(
+            compat.contains("from mypkg._core import Handle"),
+            "{compat}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dashed_module_names() -> Result<()> {
+        let class = PyClassInfo {
+            struct_id: TypeId::of::<u64>,
+            pyclass_name: "Foo",
+            name_given: false,
+            module: Some("my-pkg.core"),
+            doc: "",
+            getters: &[],
+            setters: &[],
+            bases: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            is_abstract: false,
+            type_override: Default::default(),
+            dataclass: false,
+            rust_path: "my_crate::Foo",
+            hidden_base: false,
+            no_abc: false,
+            flag_enum: false,
+            total_ordering: false,
+            pickle_state: None,
+            slots: false,
+            weakref: false,
+            feature: None,
+            deprecated: None,
+            source_location: None,
+        };
+        let mut builder = builder();
+        builder.add_class(&class)?;
+        builder.add_function(&function_in("mypkg.io", || {
+            TypeInfo::locally_defined("Foo", "my-pkg.core".into())
+        }))?;
+        builder.normalize_module_references()?;
+        assert_eq!(
+            builder.modules.keys().collect::<Vec<_>>(),
+            ["my_pkg.core", "mypkg.io"]
+        );
+        let rendered = builder.modules["mypkg.io"].to_string();
+        assert!(
+            rendered.contains("from my_pkg.core import Foo"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("my-pkg"), "{rendered}");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_module_name() {
+        let info = function_in("1pkg.core", TypeInfo::none);
+        let mut builder = builder();
+        let item = || {
+            describe(
+                "function",
+                info.module.unwrap(),
+                info.name,
+                info.rust_path,
+                info.source_location,
+            )
+        };
+        let err = add_item(item, || builder.add_function(&info)).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "function `1pkg.core.load` (my_crate::load): Module name `1pkg.core` of `#[pyclass(module = \"1pkg.core\")]` or a similar attribute is not a valid Python module name: `1pkg` starts with a digit"
+        );
+    }
+
+    #[test]
+    fn report_lists_all_errors() {
+        assert!(report(Vec::new()).is_ok());
+        assert_eq!(
+            report(vec![anyhow!("broken class")])
+                .unwrap_err()
+                .to_string(),
+            "broken class"
+        );
+        let err = report(vec![
+            anyhow!("broken class"),
+            anyhow!("broken variable").context("variable `mypkg.X`"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to generate stubs of 2 items:\n  - broken class\n  - variable `mypkg.X`: broken variable"
+        );
     }
 }
@@ -0,0 +1,348 @@
+use crate::{generate::*, TypeInfo};
+use anyhow::{bail, Result};
+use std::{collections::BTreeMap, fmt};
+
+/// Content of a [SynthesizedDef]. Two symbols are structurally equal when their kinds are equal.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SynthesizedKind {
+    /// `Name: typing.TypeAlias = <type>`
+    TypeAlias(TypeInfo),
+}
+
+/// Helper symbol generated by pyo3-stub-gen itself rather than declared in Rust code,
+/// e.g. a type alias for a union of types.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SynthesizedDef {
+    pub name: String,
+    pub kind: SynthesizedKind,
+    /// Descriptions of what requested this symbol, e.g. ``complex enum `Shape` ``, for diagnostics
+    pub origins: Vec<String>,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+    /// Module providing `TypeAlias`, which is `typing_extensions` for stubs targeting Python 3.9 or older
+    pub type_alias_module: &'static str,
+}
+
+impl SynthesizedDef {
+    /// Rewrite this symbol into the syntax of `target`
+    pub(crate) fn downgrade(&mut self, target: TargetVersion) {
+        let SynthesizedKind::TypeAlias(ty) = &mut self.kind;
+        target_version::downgrade(ty, target);
+        if !target.has_type_alias() {
+            self.type_alias_module = "typing_extensions";
+        }
+    }
+}
+
+impl Import for SynthesizedDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        match &self.kind {
+            SynthesizedKind::TypeAlias(type_info) => {
+                let mut import = type_info.import.clone();
+                import.insert(self.type_alias_module.into());
+                import
+            }
+        }
+    }
+}
+
+impl fmt::Display for SynthesizedDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            SynthesizedKind::TypeAlias(type_info) => {
+                writeln!(
+                    f,
+                    "{}: {}.TypeAlias = {type_info}",
+                    self.name, self.type_alias_module
+                )
+            }
+        }
+    }
+}
+
+/// Registry of [SynthesizedDef]s used while [StubInfo](crate::StubInfo) is built.
+///
+/// Every feature synthesizing helper symbols must register them here so that
+///
+/// - structurally identical symbols in a module are emitted only once,
+/// - structurally different symbols requesting the same name are renamed deterministically
+///   by appending `_1`, `_2`, ... in the order of registration, and
+/// - collisions with user-defined names are reported with both origins.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SynthesizedSymbols {
+    modules: BTreeMap<String, BTreeMap<String, SynthesizedDef>>,
+}
+
+impl SynthesizedSymbols {
+    /// Register a symbol requested as `name` in `module`, and return the name it is emitted as.
+    pub fn register(
+        &mut self,
+        module: &str,
+        name: &str,
+        kind: SynthesizedKind,
+        origin: impl Into<String>,
+    ) -> String {
+        let origin = origin.into();
+        let symbols = self.modules.entry(module.to_string()).or_default();
+        if let Some(existing) = symbols.values_mut().find(|symbol| symbol.kind == kind) {
+            if !existing.origins.contains(&origin) {
+                existing.origins.push(origin);
+            }
+            return existing.name.clone();
+        }
+        let mut candidate = name.to_string();
+        let mut suffix = 0;
+        while symbols.contains_key(&candidate) {
+            suffix += 1;
+            candidate = format!("{name}_{suffix}");
+        }
+        symbols.insert(
+            candidate.clone(),
+            SynthesizedDef {
+                name: candidate.clone(),
+                kind,
+                origins: vec![origin],
+                qualname: QualName::default(),
+                type_alias_module: "typing",
+            },
+        );
+        candidate
+    }
+
+    /// Register a symbol which must be emitted as `name` in `module`, e.g. because generated code refers to it by the name.
+    ///
+    /// Unlike [SynthesizedSymbols::register], a different symbol registered as `name` is an error instead of being renamed.
+    pub fn define(
+        &mut self,
+        module: &str,
+        name: &str,
+        kind: SynthesizedKind,
+        origin: impl Into<String>,
+    ) -> Result<()> {
+        let origin = origin.into();
+        let symbols = self.modules.entry(module.to_string()).or_default();
+        match symbols.get_mut(name) {
+            Some(existing) if existing.kind == kind => {
+                if !existing.origins.contains(&origin) {
+                    existing.origins.push(origin);
+                }
+            }
+            Some(existing) => bail!(
+                "Synthesized symbol `{module}.{name}` requested by {origin} collides with the one requested by {}",
+                existing.origins.join(", ")
+            ),
+            None => {
+                symbols.insert(
+                    name.to_string(),
+                    SynthesizedDef {
+                        name: name.to_string(),
+                        kind,
+                        origins: vec![origin],
+                        qualname: QualName::default(),
+                        type_alias_module: "typing",
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the symbols registered for modules renamed by `renames`, and rewrite their types.
+    pub(crate) fn rename_modules(
+        &mut self,
+        renames: &module_rename::ModuleRenames,
+        default_module_name: &str,
+    ) {
+        self.modules = std::mem::take(&mut self.modules)
+            .into_iter()
+            .map(|(module, mut symbols)| {
+                for symbol in symbols.values_mut() {
+                    let SynthesizedKind::TypeAlias(ty) = &mut symbol.kind;
+                    renames.rewrite(ty, default_module_name);
+                }
+                (renames.rename(&module).unwrap_or(module), symbols)
+            })
+            .collect();
+    }
+
+    /// Move the registered symbols into their modules, failing if one collides with a user-defined name.
+    pub(crate) fn apply(
+        self,
+        modules: &mut BTreeMap<String, Module>,
+        default_module_name: &str,
+    ) -> Result<()> {
+        for (module_name, symbols) in self.modules {
+            let module = modules.entry(module_name.clone()).or_default();
+            module.name = module_name.clone();
+            module.default_module_name = default_module_name.to_string();
+            for (name, symbol) in symbols {
+                if let Some(user) = user_defined(module, &name) {
+                    bail!(
+                        "Synthesized symbol `{module_name}.{name}` requested by {} collides with {user} `{module_name}.{name}`",
+                        symbol.origins.join(", "),
+                    );
+                }
+                module.synthesized.insert(name, symbol);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Kind of the user-defined item named `name` in the module if exists
+fn user_defined(module: &Module, name: &str) -> Option<&'static str> {
+    if module.class.values().any(|class| class.name == name) {
+        Some("class")
+    } else if module.enum_.values().any(|enum_| enum_.name == name) {
+        Some("enum")
+    } else if module.function.contains_key(name) {
+        Some("function")
+    } else if module.variables.contains_key(name) {
+        Some("variable")
+    } else if module.submodules.contains(name) {
+        Some("submodule")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(name: &str) -> SynthesizedKind {
+        SynthesizedKind::TypeAlias(TypeInfo::builtin(name))
+    }
+
+    #[test]
+    fn dedup_identical_symbols() {
+        let mut symbols = SynthesizedSymbols::default();
+        let a = symbols.register("mod", "Alias", alias("int"), "first");
+        let b = symbols.register("mod", "Other", alias("int"), "second");
+        assert_eq!(a, "Alias");
+        assert_eq!(b, "Alias");
+        let registered: Vec<_> = symbols.modules["mod"].values().collect();
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered[0].origins, ["first", "second"]);
+
+        // Other modules are independent
+        assert_eq!(
+            symbols.register("sub", "Alias", alias("int"), "third"),
+            "Alias"
+        );
+        assert_eq!(symbols.modules["sub"].len(), 1);
+    }
+
+    #[test]
+    fn suffix_renaming_for_different_symbols() {
+        let mut symbols = SynthesizedSymbols::default();
+        assert_eq!(symbols.register("mod", "Alias", alias("int"), "a"), "Alias");
+        assert_eq!(
+            symbols.register("mod", "Alias", alias("str"), "b"),
+            "Alias_1"
+        );
+        assert_eq!(
+            symbols.register("mod", "Alias", alias("bytes"), "c"),
+            "Alias_2"
+        );
+        assert_eq!(
+            symbols.register("mod", "Alias", alias("str"), "d"),
+            "Alias_1"
+        );
+    }
+
+    #[test]
+    fn collision_with_user_defined_name() {
+        let mut modules = BTreeMap::new();
+        let mut module = Module {
+            name: "mod".to_string(),
+            ..Default::default()
+        };
+        module.variables.insert(
+            "Alias",
+            VariableDef {
+                name: "Alias",
+                type_: TypeInfo::any(),
+                default: None,
+                doc: "",
+                deprecated: None,
+                type_ignored: None,
+                qualname: QualName::default(),
+            },
+        );
+        modules.insert("mod".to_string(), module);
+
+        let mut symbols = SynthesizedSymbols::default();
+        symbols.register("mod", "Alias", alias("int"), "complex enum `Shape`");
+        let err = symbols.apply(&mut modules, "mod").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Synthesized symbol `mod.Alias` requested by complex enum `Shape` collides with variable `mod.Alias`"
+        );
+    }
+
+    #[test]
+    fn type_alias_is_rendered() {
+        let mut modules = BTreeMap::new();
+        let mut symbols = SynthesizedSymbols::default();
+        let union = TypeInfo::builtin("int") | TypeInfo::builtin("str");
+        let name = symbols.register("mod", "IntOrStr", SynthesizedKind::TypeAlias(union), "test");
+        assert_eq!(name, "IntOrStr");
+        symbols.apply(&mut modules, "mod").unwrap();
+        let module = &modules["mod"];
+        assert_eq!(
+            module.synthesized["IntOrStr"].to_string(),
+            "IntOrStr: typing.TypeAlias = builtins.int | builtins.str\n"
+        );
+        assert!(module.public_names().contains(&"IntOrStr"));
+    }
+
+    #[test]
+    fn type_alias_for_python_38() {
+        let mut modules = BTreeMap::new();
+        let mut symbols = SynthesizedSymbols::default();
+        let union = TypeInfo::builtin("int") | TypeInfo::builtin("str");
+        symbols.register("mod", "IntOrStr", SynthesizedKind::TypeAlias(union), "test");
+        symbols.apply(&mut modules, "mod").unwrap();
+        // `requires-python = ">=3.8"`
+        let symbol = modules
+            .get_mut("mod")
+            .unwrap()
+            .synthesized
+            .get_mut("IntOrStr")
+            .unwrap();
+        symbol.downgrade(TargetVersion::from_minimum((3, 8)));
+        assert_eq!(
+            symbol.to_string(),
+            "IntOrStr: typing_extensions.TypeAlias = typing.Union[builtins.int, builtins.str]\n"
+        );
+        let import = symbol.import();
+        assert!(import.contains(&"typing_extensions".into()));
+        assert!(import.contains(&"typing".into()));
+    }
+
+    #[test]
+    fn define_keeps_name() {
+        let mut symbols = SynthesizedSymbols::default();
+        symbols
+            .define("mod", "ShapeVariant", alias("int"), "complex enum `Shape`")
+            .unwrap();
+        // The identical symbol is shared
+        symbols
+            .define("mod", "ShapeVariant", alias("int"), "complex enum `Shape`")
+            .unwrap();
+        assert_eq!(
+            symbols.register("mod", "Other", alias("int"), "other"),
+            "ShapeVariant"
+        );
+        assert_eq!(
+            symbols
+                .define("mod", "ShapeVariant", alias("str"), "complex enum `Other`")
+                .unwrap_err()
+                .to_string(),
+            "Synthesized symbol `mod.ShapeVariant` requested by complex enum `Other` collides with the one requested by complex enum `Shape`, other"
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Syntax of type annotations for the oldest Python version parsing the stubs.
+//!
+//! Annotations are written with PEP 585 builtin generics like `list[int]` and PEP 604 unions like `int | None`,
+//! and rewritten into the `typing` forms like `typing.List[int]` and `typing.Optional[int]` for older versions.
+//! `typing.Never` of Python 3.11 is rewritten into `typing.NoReturn` likewise.
+//! Synthesized aliases are declared by `typing_extensions.TypeAlias` before Python 3.10.
+
+use crate::{config::Config, stub_type::ImportRef, TypeInfo};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Oldest Python version the generated stubs are written for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum TargetVersion {
+    /// `typing.List[...]` and `typing.Union[...]`
+    #[serde(rename = "3.8")]
+    Py38,
+    /// `list[...]` and `typing.Union[...]`
+    #[serde(rename = "3.9")]
+    Py39,
+    /// `list[...]` and `A | B`
+    #[default]
+    #[serde(rename = "3.10")]
+    Py310,
+    /// `typing.Never` in addition to the syntax of 3.10
+    #[serde(rename = "3.11")]
+    Py311,
+    /// `typing.override` in addition to the syntax of 3.11
+    #[serde(rename = "3.12")]
+    Py312,
+}
+
+impl TargetVersion {
+    /// Target for the minimum Python version `(major, minor)` of `requires-python`
+    pub(crate) fn from_minimum((major, minor): (u8, u8)) -> Self {
+        match (major, minor) {
+            (..=2, _) | (3, ..=8) => TargetVersion::Py38,
+            (3, 9) => TargetVersion::Py39,
+            (3, 10) => TargetVersion::Py310,
+            (3, 11) => TargetVersion::Py311,
+            _ => TargetVersion::Py312,
+        }
+    }
+
+    /// `typing.Annotated` is available since Python 3.9
+    pub(crate) fn has_annotated(self) -> bool {
+        self >= TargetVersion::Py39
+    }
+
+    /// `typing.TypeAlias` is available since Python 3.10
+    pub(crate) fn has_type_alias(self) -> bool {
+        self >= TargetVersion::Py310
+    }
+
+    fn has_builtin_generics(self) -> bool {
+        self >= TargetVersion::Py39
+    }
+
+    fn has_union_operator(self) -> bool {
+        self >= TargetVersion::Py310
+    }
+
+    fn has_never(self) -> bool {
+        self >= TargetVersion::Py311
+    }
+
+    pub(crate) fn has_override(self) -> bool {
+        self >= TargetVersion::Py312
+    }
+}
+
+/// Set the oldest Python version the generated stubs are written for.
+pub fn set_target_version(version: TargetVersion) {
+    Config::update(|config| config.target_version = version);
+}
+
+/// Retrieve the oldest Python version the generated stubs are written for.
+pub fn target_version() -> TargetVersion {
+    Config::current().target_version
+}
+
+/// Type annotation `name` in the syntax of the [target_version]
+pub(crate) fn annotation(name: &str) -> Cow<'_, str> {
+    match rewrite(name, target_version()) {
+        Some(rewritten) => Cow::Owned(rewritten),
+        None => Cow::Borrowed(name),
+    }
+}
+
+/// Rewrite `ty` into the syntax of `target`, importing `typing` if used
+pub(crate) fn downgrade(ty: &mut TypeInfo, target: TargetVersion) {
+    if let Some(rewritten) = rewrite(&ty.name, target) {
+        if rewritten.contains("typing.") {
+            ty.import.insert(ImportRef::from("typing"));
+        }
+        ty.name = rewritten;
+    }
+}
+
+/// `name` in the syntax of `target`, or `None` if it is unchanged or cannot be parsed
+fn rewrite(name: &str, target: TargetVersion) -> Option<String> {
+    if target >= TargetVersion::Py311 {
+        return None;
+    }
+    let tokens = tokenize(name)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+    };
+    let expr = parser.union()?;
+    if parser.pos != tokens.len() {
+        return None;
+    }
+    let rewritten = expr.render(target);
+    (rewritten != name).then_some(rewritten)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Name, literal, or anything else between the punctuations
+    Atom(String),
+    Open,
+    Close,
+    Comma,
+    Pipe,
+}
+
+fn tokenize(name: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut atom = String::new();
+    let flush = |atom: &mut String, tokens: &mut Vec<Token>| {
+        let trimmed = atom.trim();
+        if !trimmed.is_empty() {
+            tokens.push(Token::Atom(trimmed.to_string()));
+        }
+        atom.clear();
+    };
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        let punctuation = match c {
+            '[' => Token::Open,
+            ']' => Token::Close,
+            ',' => Token::Comma,
+            '|' => Token::Pipe,
+            '"' | '\'' => {
+                // Punctuations in string literals are kept
+                atom.push(c);
+                loop {
+                    let next = chars.next()?;
+                    atom.push(next);
+                    if next == '\\' {
+                        atom.push(chars.next()?);
+                    } else if next == c {
+                        break;
+                    }
+                }
+                continue;
+            }
+            _ => {
+                atom.push(c);
+                continue;
+            }
+        };
+        flush(&mut atom, &mut tokens);
+        tokens.push(punctuation);
+    }
+    flush(&mut atom, &mut tokens);
+    Some(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Atom(String),
+    /// `Head[args]`
+    Subscript(String, Vec<Expr>),
+    /// `[args]`, e.g. the parameters of `Callable`
+    List(Vec<Expr>),
+    /// `A | B | ...`
+    Union(Vec<Expr>),
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matched = self.peek() == Some(token);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn union(&mut self) -> Option<Expr> {
+        let mut members = vec![self.postfix()?];
+        while self.eat(&Token::Pipe) {
+            members.push(self.postfix()?);
+        }
+        Some(if members.len() == 1 {
+            members.pop()?
+        } else {
+            Expr::Union(members)
+        })
+    }
+
+    fn postfix(&mut self) -> Option<Expr> {
+        if self.peek() == Some(&Token::Open) {
+            return Some(Expr::List(self.list()?));
+        }
+        let Some(Token::Atom(atom)) = self.peek().cloned() else {
+            return None;
+        };
+        self.pos += 1;
+        if self.peek() == Some(&Token::Open) {
+            Some(Expr::Subscript(atom, self.list()?))
+        } else {
+            Some(Expr::Atom(atom))
+        }
+    }
+
+    fn list(&mut self) -> Option<Vec<Expr>> {
+        if !self.eat(&Token::Open) {
+            return None;
+        }
+        let mut items = Vec::new();
+        while !self.eat(&Token::Close) {
+            items.push(self.union()?);
+            if !self.eat(&Token::Comma) && self.peek() != Some(&Token::Close) {
+                return None;
+            }
+        }
+        Some(items)
+    }
+}
+
+/// `typing` alias of a generic which cannot be subscripted before Python 3.9
+fn typing_alias(head: &str) -> Option<String> {
+    let name = head.strip_prefix("builtins.").unwrap_or(head);
+    let alias = match name {
+        "list" => "List",
+        "dict" => "Dict",
+        "set" => "Set",
+        "frozenset" => "FrozenSet",
+        "tuple" => "Tuple",
+        "type" => "Type",
+        "collections.abc.Set" => "AbstractSet",
+        "collections.OrderedDict" => "OrderedDict",
+        "collections.defaultdict" => "DefaultDict",
+        "collections.deque" => "Deque",
+        "collections.Counter" => "Counter",
+        "collections.ChainMap" => "ChainMap",
+        _ => return Some(format!("typing.{}", name.strip_prefix("collections.abc.")?)),
+    };
+    Some(format!("typing.{alias}"))
+}
+
+impl Expr {
+    fn render(&self, target: TargetVersion) -> String {
+        let join = |items: &[Expr]| {
+            items
+                .iter()
+                .map(|item| item.render(target))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Expr::Atom(atom) if atom == "typing.Never" && !target.has_never() => {
+                "typing.NoReturn".to_string()
+            }
+            Expr::Atom(atom) => atom.clone(),
+            Expr::Subscript(head, args) => {
+                let alias = if target.has_builtin_generics() {
+                    None
+                } else {
+                    typing_alias(head)
+                };
+                format!("{}[{}]", alias.as_deref().unwrap_or(head), join(args))
+            }
+            Expr::List(items) => format!("[{}]", join(items)),
+            Expr::Union(members) if target.has_union_operator() => members
+                .iter()
+                .map(|member| member.render(target))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Expr::Union(members) => {
+                let none = Expr::Atom("None".to_string());
+                match members.as_slice() {
+                    [other, none_] | [none_, other] if *none_ == none => {
+                        format!("typing.Optional[{}]", other.render(target))
+                    }
+                    _ => format!("typing.Union[{}]", join(members)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("builtins.int | None", "typing.Optional[builtins.int]" ; "optional")]
+    #[test_case("None | builtins.int", "typing.Optional[builtins.int]" ; "optional_none_first")]
+    #[test_case("builtins.int | builtins.str | None", "typing.Union[builtins.int, builtins.str, None]" ; "union")]
+    #[test_case("builtins.list[builtins.str]", "typing.List[builtins.str]" ; "list")]
+    #[test_case("builtins.dict[builtins.str, builtins.list[tuple[Foo, None]]] | None", "typing.Optional[typing.Dict[builtins.str, typing.List[typing.Tuple[Foo, None]]]]" ; "nested")]
+    #[test_case("collections.abc.Callable[[builtins.int | None], collections.abc.Set[builtins.str]]", "typing.Callable[[typing.Optional[builtins.int]], typing.AbstractSet[builtins.str]]" ; "callable")]
+    #[test_case("tuple[()]", "typing.Tuple[()]" ; "empty_tuple")]
+    #[test_case("type[builtins.BaseException] | None", "typing.Optional[typing.Type[builtins.BaseException]]" ; "class_object")]
+    #[test_case("typing.Literal['a | b', \"[c]\"] | None", "typing.Optional[typing.Literal['a | b', \"[c]\"]]" ; "literal")]
+    fn py38(name: &str, expected: &str) {
+        assert_eq!(
+            rewrite(name, TargetVersion::Py38).as_deref(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn py39() {
+        assert_eq!(
+            rewrite("builtins.list[builtins.int | None]", TargetVersion::Py39).as_deref(),
+            Some("builtins.list[typing.Optional[builtins.int]]")
+        );
+        assert_eq!(
+            rewrite("builtins.list[builtins.int]", TargetVersion::Py39),
+            None
+        );
+    }
+
+    #[test_case(TargetVersion::Py38 ; "py38")]
+    #[test_case(TargetVersion::Py310 ; "py310")]
+    fn never(target: TargetVersion) {
+        assert_eq!(
+            rewrite("typing.Never", target).as_deref(),
+            Some("typing.NoReturn")
+        );
+    }
+
+    #[test]
+    fn unchanged() {
+        assert_eq!(rewrite("builtins.int | None", TargetVersion::Py310), None);
+        assert_eq!(rewrite("typing.Never", TargetVersion::Py311), None);
+        assert_eq!(rewrite("typing.Any", TargetVersion::Py38), None);
+        // Unbalanced brackets are left as they are
+        assert_eq!(rewrite("builtins.list[int", TargetVersion::Py38), None);
+    }
+
+    #[test]
+    fn import_typing() {
+        let mut ty = TypeInfo::builtin("int") | TypeInfo::none();
+        downgrade(&mut ty, TargetVersion::Py38);
+        assert_eq!(ty.name, "typing.Optional[builtins.int]");
+        assert!(ty.import.contains(&ImportRef::from("typing")));
+    }
+
+    #[test_case((3, 7), TargetVersion::Py38)]
+    #[test_case((3, 8), TargetVersion::Py38)]
+    #[test_case((3, 9), TargetVersion::Py39)]
+    #[test_case((3, 10), TargetVersion::Py310)]
+    #[test_case((3, 11), TargetVersion::Py311)]
+    #[test_case((3, 12), TargetVersion::Py312)]
+    #[test_case((3, 13), TargetVersion::Py312)]
+    fn from_minimum(minimum: (u8, u8), expected: TargetVersion) {
+        assert_eq!(TargetVersion::from_minimum(minimum), expected);
+    }
+
+    #[test]
+    fn target_version_in_pyproject() {
+        use crate::pyproject::StubGen;
+        let stub_gen: StubGen = toml::from_str(r#"target-version = "3.8""#).unwrap();
+        assert_eq!(stub_gen.target_version, Some(TargetVersion::Py38));
+        assert!(toml::from_str::<StubGen>(r#"target-version = "3.7""#).is_err());
+    }
+}
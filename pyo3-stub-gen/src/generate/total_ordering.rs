@@ -0,0 +1,156 @@
+//! Classes decorated by `@functools.total_ordering` via `#[gen_stub(total_ordering)]`.
+//!
+//! A class implementing only `__eq__` and `__lt__` in Rust, e.g. by `__richcmp__`-free `#[pymethods]`,
+//! is completed by `functools.total_ordering` in Python, so that the comparisons it supplies are synthesized
+//! with the signature of the one defined. `__ne__` is synthesized from `__eq__` as Python derives it by default.
+
+use crate::{generate::*, TypeInfo};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// Comparisons of which `functools.total_ordering` requires one and supplies the others
+const ORDERING_METHODS: [&str; 4] = ["__lt__", "__le__", "__gt__", "__ge__"];
+
+/// Synthesize the missing comparisons of the classes in `total_orderings`, and decorate them by `@functools.total_ordering`.
+///
+/// A comparison already defined is kept as is. A class without any ordering method is kept undecorated with a warning,
+/// since `functools.total_ordering` raises `ValueError` for it.
+pub(crate) fn add_total_orderings(
+    modules: &mut BTreeMap<String, Module>,
+    total_orderings: &BTreeSet<TypeId>,
+) {
+    for (module_name, module) in modules.iter_mut() {
+        for (id, class) in module.class.iter_mut() {
+            if !total_orderings.contains(id) {
+                continue;
+            }
+            let Some(root) = ORDERING_METHODS
+                .into_iter()
+                .find(|name| class.methods.contains_key(*name))
+            else {
+                log::warn!(
+                    "`{module_name}.{}` is not decorated by `@functools.total_ordering`, since it defines none of {}",
+                    class.name,
+                    ORDERING_METHODS.join(", ")
+                );
+                continue;
+            };
+            for name in ORDERING_METHODS {
+                synthesize(class, root, name);
+            }
+            if class.methods.contains_key("__eq__") {
+                synthesize(class, "__eq__", "__ne__");
+            }
+            class.total_ordering = true;
+        }
+    }
+}
+
+/// Add the method `name` mirroring the overloads of `source` and returning `bool`, unless it is already defined
+fn synthesize(class: &mut ClassDef, source: &str, name: &'static str) {
+    if class.methods.contains_key(name) {
+        return;
+    }
+    let overloads = class.methods[source]
+        .iter()
+        .map(|method| MethodDef {
+            name: name.into(),
+            r#return: TypeInfo::builtin("bool"),
+            doc: "",
+            deprecated: None,
+            type_ignored: None,
+            is_abstract: false,
+            ..method.clone()
+        })
+        .collect();
+    class.methods.insert(name.to_string(), overloads);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stub_type::ModuleRef, type_info::ParameterKind};
+
+    fn comparison(name: &'static str, other: TypeInfo) -> MethodDef {
+        let mut params = Parameters::new();
+        params.positional_or_keyword = vec![Parameter::new(
+            "other",
+            ParameterKind::PositionalOrKeyword,
+            other,
+        )];
+        MethodDef::new(
+            name,
+            MethodType::Instance,
+            params,
+            TypeInfo::builtin("bool"),
+        )
+    }
+
+    fn version() -> TypeInfo {
+        TypeInfo::locally_defined("Version", ModuleRef::Default)
+    }
+
+    /// Render the class `Version` defining `methods` with `#[gen_stub(total_ordering)]`, and return the stub of its module
+    fn render(methods: Vec<MethodDef>) -> String {
+        let mut class = ClassDef::new("Version");
+        for method in methods {
+            class.add_method(method);
+        }
+        let id = TypeId::of::<u8>();
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(id, class);
+        let mut modules = BTreeMap::from([("pkg".to_string(), module)]);
+        add_total_orderings(&mut modules, &BTreeSet::from([id]));
+        modules["pkg"].to_string()
+    }
+
+    #[test]
+    fn synthesize_comparisons() {
+        let rendered = render(vec![
+            comparison("__eq__", TypeInfo::builtin("object")),
+            comparison("__lt__", version()),
+        ]);
+        assert!(rendered.contains("import functools\n"), "{rendered}");
+        assert!(
+            rendered.contains(
+                "@functools.total_ordering
+class Version:
+    def __eq__(self, other: builtins.object) -> builtins.bool: ...
+    def __lt__(self, other: Version) -> builtins.bool: ...
+    def __le__(self, other: Version) -> builtins.bool: ...
+    def __gt__(self, other: Version) -> builtins.bool: ...
+    def __ge__(self, other: Version) -> builtins.bool: ...
+    def __ne__(self, other: builtins.object) -> builtins.bool: ...
+"
+            ),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn keep_existing_comparisons() {
+        let rendered = render(vec![
+            comparison("__eq__", version()),
+            comparison("__gt__", version()),
+            comparison("__ne__", TypeInfo::builtin("int")),
+        ]);
+        assert!(
+            rendered.contains("def __ne__(self, other: builtins.int) -> builtins.bool: ..."),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("def __lt__(self, other: Version) -> builtins.bool: ..."),
+            "{rendered}"
+        );
+        assert_eq!(rendered.matches("def __gt__").count(), 1, "{rendered}");
+    }
+
+    #[test]
+    fn no_ordering_method() {
+        let rendered = render(vec![comparison("__eq__", version())]);
+        assert!(!rendered.contains("functools"), "{rendered}");
+        assert!(!rendered.contains("__lt__"), "{rendered}");
+    }
+}
@@ -0,0 +1,596 @@
+//! Post-processing of [StubInfo] before rendering stub files.
+
+use crate::{
+    generate::*,
+    stub_type::{complex_enum_alias, ImportRef, ModuleRef},
+    type_info::{PyClassInfo, PyComplexEnumInfo, PyEnumInfo},
+    TypeInfo,
+};
+use anyhow::{bail, Context, Result};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashSet},
+};
+
+/// Definitions keep `&'static str` since they are usually taken from [inventory],
+/// so the strings created by post-processing are leaked. They live until the stub generation ends anyway.
+pub(crate) fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+impl StubInfo {
+    /// Keep only the functions for which `f(module_name, function_name, def)` returns `true`.
+    pub fn retain_functions(
+        &mut self,
+        mut f: impl FnMut(&str, &str, &FunctionDef) -> bool,
+    ) -> &mut Self {
+        for (module_name, module) in self.modules.iter_mut() {
+            for (name, functions) in module.function.iter_mut() {
+                functions.retain(|def| f(module_name, name, def));
+            }
+            module.function.retain(|_, functions| !functions.is_empty());
+        }
+        self
+    }
+
+    /// Keep only the classes for which `f(module_name, def)` returns `true`.
+    pub fn retain_classes(&mut self, mut f: impl FnMut(&str, &ClassDef) -> bool) -> &mut Self {
+        for (module_name, module) in self.modules.iter_mut() {
+            module.class.retain(|_, def| f(module_name, def));
+        }
+        self
+    }
+
+    /// Rename the class or enum `old` defined in `module` to `new`.
+    ///
+    /// References in the signatures of the same module, and references imported from `module` by other modules,
+    /// are rewritten too. The alias `{old}Variant` of a complex enum is renamed to `{new}Variant` along with it.
+    pub fn rename_class(&mut self, module: &str, old: &str, new: &str) -> Result<&mut Self> {
+        let Some(target) = self.modules.get_mut(module) else {
+            bail!("Module `{module}` not found");
+        };
+        let (old_alias, new_alias) = (complex_enum_alias(old), complex_enum_alias(new));
+        let has_alias = target.synthesized.contains_key(&old_alias);
+        if has_alias {
+            target
+                .ensure_undefined(&new_alias)
+                .with_context(|| format!("Cannot rename the alias of `{module}.{old}`"))?;
+        }
+        if let Some(class) = target.class.values_mut().find(|class| class.name == old) {
+            class.name = new.to_string().into();
+        } else if let Some(enum_) = target.enum_.values_mut().find(|enum_| enum_.name == old) {
+            enum_.name = leak(new.to_string());
+        } else {
+            bail!("Class `{old}` not found in module `{module}`");
+        }
+        if let Some(mut alias) = target.synthesized.remove(&old_alias) {
+            alias.name = new_alias.clone();
+            target.synthesized.insert(new_alias.clone(), alias);
+        }
+        target.assign_qualnames();
+
+        rename_references(&mut self.modules, module, old, new);
+        if has_alias {
+            rename_references(&mut self.modules, module, &old_alias, &new_alias);
+        }
+        Ok(self)
+    }
+
+    /// Rename the classes and enums without `#[pyclass(name = "...")]` to `f(name)`, e.g. to strip a `Py` prefix
+    /// of their Rust names. `None` keeps the name, and references are rewritten as in [StubInfo::rename_class].
+    ///
+    /// Renamed classes keep their Rust names at runtime, so that they are listed in a warning.
+    pub fn rename_types(&mut self, f: impl FnMut(&str) -> Option<String>) -> Result<&mut Self> {
+        self.rename_implicitly_named(&implicitly_named(), f)
+    }
+
+    fn rename_implicitly_named(
+        &mut self,
+        implicit: &HashSet<TypeId>,
+        mut f: impl FnMut(&str) -> Option<String>,
+    ) -> Result<&mut Self> {
+        let mut renames = Vec::new();
+        for (module_name, module) in &self.modules {
+            let names = module
+                .class
+                .iter()
+                .map(|(id, class)| (id, class.name.as_ref()))
+                .chain(module.enum_.iter().map(|(id, enum_)| (id, enum_.name)));
+            for (id, name) in names {
+                if !implicit.contains(id) {
+                    continue;
+                }
+                if let Some(new) = f(name).filter(|new| new != name) {
+                    renames.push((module_name.clone(), name.to_string(), new));
+                }
+            }
+        }
+        if renames.is_empty() {
+            return Ok(self);
+        }
+        let list = renames
+            .iter()
+            .map(|(module, old, new)| format!("  - `{module}.{old}` as `{new}`"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        log::warn!(
+            "Classes are renamed in stubs but keep their Rust names at runtime; add `#[pyclass(name = \"...\")]` to them:\n{list}"
+        );
+        for (module, old, new) in &renames {
+            module_rename::validate(new).with_context(|| {
+                format!("`{new}` is not a valid Python name for `{module}.{old}`")
+            })?;
+            self.modules[module]
+                .ensure_undefined(new)
+                .with_context(|| format!("Cannot rename `{module}.{old}`"))?;
+            self.rename_class(module, old, new)?;
+        }
+        Ok(self)
+    }
+
+    /// Replace every docstring, including module docstrings, by `f(doc)`.
+    pub fn map_docstrings(&mut self, mut f: impl FnMut(&str) -> String) -> &mut Self {
+        for module in self.modules.values_mut() {
+            module.doc = f(&module.doc);
+            for class in module.class.values_mut() {
+                class.map_docstrings(&mut f);
+            }
+            for enum_ in module.enum_.values_mut() {
+                enum_.doc = leak(f(enum_.doc));
+                enum_.variants = Box::leak(
+                    enum_
+                        .variants
+                        .iter()
+                        .map(|(name, doc)| (*name, leak(f(doc))))
+                        .collect::<Vec<_>>()
+                        .into_boxed_slice(),
+                );
+                for method in &mut enum_.methods {
+                    method.doc = leak(f(method.doc));
+                }
+                for member in enum_
+                    .attrs
+                    .iter_mut()
+                    .chain(enum_.getters.iter_mut())
+                    .chain(enum_.setters.iter_mut())
+                {
+                    member.doc = leak(f(member.doc));
+                }
+            }
+            for function in module.function.values_mut().flatten() {
+                function.doc = leak(f(function.doc));
+            }
+        }
+        self
+    }
+}
+
+/// Classes and enums without `#[pyclass(name = "...")]`, whose Python names are their Rust names
+fn implicitly_named() -> HashSet<TypeId> {
+    let classes = inventory::iter::<PyClassInfo>
+        .into_iter()
+        .filter(|info| !info.name_given)
+        .map(|info| (info.struct_id)());
+    let complex_enums = inventory::iter::<PyComplexEnumInfo>
+        .into_iter()
+        .filter(|info| !info.name_given)
+        .map(|info| (info.enum_id)());
+    let enums = inventory::iter::<PyEnumInfo>
+        .into_iter()
+        .filter(|info| !info.name_given)
+        .map(|info| (info.enum_id)());
+    classes.chain(complex_enums).chain(enums).collect()
+}
+
+/// Rewrite the references to `old` defined in `module` into `new`, in the signatures and aliases of `module`
+/// and in those of the other modules importing it.
+pub(crate) fn rename_references(
+    modules: &mut BTreeMap<String, Module>,
+    module: &str,
+    old: &str,
+    new: &str,
+) {
+    for (module_name, current) in modules.iter_mut() {
+        let default_module_name = current.default_module_name.clone();
+        let is_target = module_name == module;
+        let mut rename = |ty: &mut TypeInfo| {
+            let imported = ty.import.iter().any(|import| {
+                matches!(import, ImportRef::Type(type_ref)
+                    if type_ref.name == old
+                        && resolve(&type_ref.module, &default_module_name) == module)
+            });
+            if !(is_target || imported) {
+                return;
+            }
+            ty.name = rename_ident(&ty.name, old, new);
+            ty.import = std::mem::take(&mut ty.import)
+                .into_iter()
+                .map(|import| match import {
+                    ImportRef::Type(mut type_ref)
+                        if type_ref.name == old
+                            && resolve(&type_ref.module, &default_module_name) == module =>
+                    {
+                        type_ref.name = new.to_string();
+                        ImportRef::Type(type_ref)
+                    }
+                    import => import,
+                })
+                .collect();
+        };
+        current.for_each_type_mut(&mut rename);
+        for symbol in current.synthesized.values_mut() {
+            let SynthesizedKind::TypeAlias(ty) = &mut symbol.kind;
+            rename(ty);
+        }
+    }
+}
+
+fn resolve<'a>(module: &'a ModuleRef, default_module_name: &'a str) -> &'a str {
+    module.get().unwrap_or(default_module_name)
+}
+
+/// Replace identifier `old` in a type expression, e.g. `builtins.list[Old]`,
+/// skipping attribute accesses like `other.Old` and longer identifiers like `OldValue`.
+pub(crate) fn rename_ident(name: &str, old: &str, new: &str) -> String {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(pos) = rest.find(old) {
+        let before = rest[..pos].chars().last().or_else(|| out.chars().last());
+        let after = rest[pos + old.len()..].chars().next();
+        out.push_str(&rest[..pos]);
+        if before.is_some_and(|c| is_ident(c) || c == '.') || after.is_some_and(is_ident) {
+            out.push_str(old);
+        } else {
+            out.push_str(new);
+        }
+        rest = &rest[pos + old.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+impl Module {
+    pub(crate) fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        for class in self.class.values_mut() {
+            class.for_each_type_mut(f);
+        }
+        for enum_ in self.enum_.values_mut() {
+            for method in &mut enum_.methods {
+                method.for_each_type_mut(f);
+            }
+            for member in enum_
+                .attrs
+                .iter_mut()
+                .chain(enum_.getters.iter_mut())
+                .chain(enum_.setters.iter_mut())
+            {
+                f(&mut member.r#type);
+            }
+        }
+        for exception in self.exceptions.values_mut() {
+            f(&mut exception.base);
+        }
+        for function in self.function.values_mut().flatten() {
+            f(&mut function.r#return);
+            for parameter in function.parameters.iter_entries_mut() {
+                f(&mut parameter.type_info);
+            }
+        }
+        for variable in self.variables.values_mut() {
+            f(&mut variable.type_);
+        }
+    }
+}
+
+impl ClassDef {
+    fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        for base in &mut self.bases {
+            f(base);
+        }
+        for attr in &mut self.attrs {
+            f(&mut attr.r#type);
+        }
+        for (getter, setter) in self.getter_setters.values_mut() {
+            for member in getter.iter_mut().chain(setter.iter_mut()) {
+                f(&mut member.r#type);
+            }
+        }
+        for method in self.methods.values_mut().flatten() {
+            method.for_each_type_mut(f);
+        }
+        for class in &mut self.classes {
+            class.for_each_type_mut(f);
+        }
+    }
+
+    fn map_docstrings(&mut self, f: &mut impl FnMut(&str) -> String) {
+        self.doc = leak(f(self.doc));
+        for attr in &mut self.attrs {
+            attr.doc = leak(f(attr.doc));
+        }
+        for (getter, setter) in self.getter_setters.values_mut() {
+            for member in getter.iter_mut().chain(setter.iter_mut()) {
+                member.doc = leak(f(member.doc));
+            }
+        }
+        for method in self.methods.values_mut().flatten() {
+            method.doc = leak(f(method.doc));
+        }
+        for class in &mut self.classes {
+            class.map_docstrings(f);
+        }
+    }
+}
+
+impl MethodDef {
+    fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        f(&mut self.r#return);
+        for parameter in self.parameters.iter_entries_mut() {
+            f(&mut parameter.type_info);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::{MethodType, ParameterKind};
+    use std::{any::TypeId, collections::BTreeMap, path::PathBuf};
+
+    fn class(name: &'static str, methods: Vec<MethodDef>) -> ClassDef {
+        let mut class = ClassDef {
+            doc: "A class.",
+            subclass: true,
+            ..ClassDef::new(name)
+        };
+        for method in methods {
+            class.add_method(method);
+        }
+        class
+    }
+
+    fn function(name: &'static str, parameter_type: TypeInfo) -> FunctionDef {
+        FunctionDef {
+            name: name.into(),
+            parameters: Parameters {
+                positional_or_keyword: vec![Parameter {
+                    name: "x",
+                    kind: ParameterKind::PositionalOrKeyword,
+                    type_info: parameter_type,
+                    default: ParameterDefault::None,
+                    container_default: None,
+                    deprecated: None,
+                }],
+                ..Parameters::new()
+            },
+            r#return: TypeInfo::none(),
+            doc: "",
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            qualname: QualName::default(),
+            source_location: None,
+        }
+    }
+
+    fn stub_info() -> StubInfo {
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword = vec![Parameter::new(
+            "other",
+            ParameterKind::PositionalOrKeyword,
+            TypeInfo::unqualified("BadName"),
+        )];
+        let merge = MethodDef {
+            doc: "Merge two values.",
+            ..MethodDef::new(
+                "merge",
+                MethodType::Instance,
+                parameters,
+                TypeInfo::unqualified("builtins.list[BadName]"),
+            )
+        };
+        let mut main = Module {
+            name: "pkg".into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        main.class
+            .insert(TypeId::of::<u8>(), class("BadName", vec![merge]));
+        main.class
+            .insert(TypeId::of::<u16>(), class("BadNameValue", Vec::new()));
+        main.function.insert(
+            "_internal_helper".into(),
+            vec![function("_internal_helper", TypeInfo::builtin("int"))],
+        );
+        main.function.insert(
+            "public".into(),
+            vec![function("public", TypeInfo::builtin("int"))],
+        );
+
+        let mut sub = Module {
+            name: "pkg.sub".into(),
+            default_module_name: "pkg".into(),
+            ..Default::default()
+        };
+        sub.function.insert(
+            "consume".into(),
+            vec![function(
+                "consume",
+                TypeInfo::locally_defined("BadName", ModuleRef::Default),
+            )],
+        );
+
+        StubInfo {
+            modules: BTreeMap::from([("pkg".into(), main), ("pkg.sub".into(), sub)]),
+            python_root: PathBuf::new(),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn retain_functions_drops_private_helpers() {
+        let mut stub_info = stub_info();
+        stub_info.retain_functions(|_, name, _| !name.starts_with("_internal"));
+        let rendered = stub_info.modules["pkg"].to_string();
+        assert!(!rendered.contains("_internal_helper"));
+        assert!(rendered.contains("def public(x: builtins.int) -> None: ..."));
+    }
+
+    #[test]
+    fn retain_classes_drops_classes() {
+        let mut stub_info = stub_info();
+        stub_info
+            .retain_classes(|module, class| !(module == "pkg" && class.name == "BadNameValue"));
+        let rendered = stub_info.modules["pkg"].to_string();
+        assert!(!rendered.contains("class BadNameValue"));
+        assert!(rendered.contains("class BadName:"));
+    }
+
+    #[test]
+    fn rename_class_rewrites_references() -> Result<()> {
+        let mut stub_info = stub_info();
+        stub_info.rename_class("pkg", "BadName", "GoodName")?;
+
+        let rendered = stub_info.modules["pkg"].to_string();
+        assert!(rendered.contains("class GoodName:"));
+        assert!(rendered.contains("def merge(self, other: GoodName) -> builtins.list[GoodName]:"));
+        // Other classes sharing the prefix are not renamed
+        assert!(rendered.contains("class BadNameValue:"));
+
+        let rendered = stub_info.modules["pkg.sub"].to_string();
+        assert!(rendered.contains("from pkg import GoodName"));
+        assert!(rendered.contains("def consume(x: GoodName) -> None: ..."));
+
+        assert!(stub_info.rename_class("pkg", "Missing", "Other").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rename_implicitly_named_classes() -> Result<()> {
+        let mut info = stub_info();
+        // `BadNameValue` has `#[pyclass(name = "...")]`
+        let implicit = HashSet::from([TypeId::of::<u8>()]);
+        info.rename_implicitly_named(&implicit, |name| {
+            name.strip_prefix("Bad").map(str::to_string)
+        })?;
+
+        let rendered = info.modules["pkg"].to_string();
+        assert!(rendered.contains("class Name:"));
+        assert!(rendered.contains("def merge(self, other: Name) -> builtins.list[Name]:"));
+        assert!(rendered.contains("class BadNameValue:"));
+        let rendered = info.modules["pkg.sub"].to_string();
+        assert!(rendered.contains("from pkg import Name"));
+        assert!(rendered.contains("def consume(x: Name) -> None: ..."));
+
+        // A renamed class must not shadow another item
+        let err = stub_info()
+            .rename_implicitly_named(&implicit, |_| Some("public".to_string()))
+            .unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "Cannot rename `pkg.BadName`: `pkg.public` is already defined"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn strip_class_prefix_of_complex_enum() -> Result<()> {
+        let mut info = stub_info();
+        let union =
+            || TypeInfo::unqualified("PyShape.Circle") | TypeInfo::unqualified("PyShape.Square");
+        let mut symbols = synthesized::SynthesizedSymbols::default();
+        symbols.define(
+            "pkg",
+            "PyShapeVariant",
+            SynthesizedKind::TypeAlias(union()),
+            "complex enum `PyShape`",
+        )?;
+        symbols.apply(&mut info.modules, "pkg")?;
+        let pkg = info.modules.get_mut("pkg").unwrap();
+        pkg.class
+            .insert(TypeId::of::<u64>(), class("PyShape", Vec::new()));
+        pkg.assign_qualnames();
+        info.modules.get_mut("pkg.sub").unwrap().function.insert(
+            "area".into(),
+            vec![function(
+                "area",
+                TypeInfo::complex_enum("PyShape", "pkg".into(), union),
+            )],
+        );
+
+        let implicit = HashSet::from([TypeId::of::<u64>()]);
+        info.rename_implicitly_named(&implicit, |name| stub_info::strip_class_prefix(name, "Py"))?;
+        let rendered = info.modules["pkg"].to_string();
+        assert!(rendered.contains("class Shape:"), "{rendered}");
+        assert!(
+            rendered.contains("ShapeVariant: typing.TypeAlias = Shape.Circle | Shape.Square"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("PyShape"), "{rendered}");
+        let rendered = info.modules["pkg.sub"].to_string();
+        assert!(
+            rendered.contains("from pkg import BadName, ShapeVariant"),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("def area(x: ShapeVariant) -> None: ..."),
+            "{rendered}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rename_class_updates_qualnames() -> Result<()> {
+        let mut stub_info = stub_info();
+        let mut module = stub_info.modules.remove("pkg.sub").unwrap();
+        module.name = "pkg.renamed".into();
+        module.class.insert(
+            TypeId::of::<u32>(),
+            class(
+                "Old",
+                vec![
+                    stub_info.modules["pkg"].class[&TypeId::of::<u8>()].methods["merge"][0].clone(),
+                ],
+            ),
+        );
+        stub_info.modules.insert("pkg.renamed".into(), module);
+
+        stub_info.rename_class("pkg.renamed", "Old", "New")?;
+        let class = &stub_info.modules["pkg.renamed"].class[&TypeId::of::<u32>()];
+        assert_eq!(class.qualname.dotted(), "pkg.renamed.New");
+        assert_eq!(
+            class.methods["merge"][0].qualname.components(),
+            ["pkg", "renamed", "New", "merge"]
+        );
+        assert_eq!(
+            stub_info.modules["pkg.renamed"].function["consume"][0]
+                .qualname
+                .dotted(),
+            "pkg.renamed.consume"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn map_docstrings_rewrites_all_docs() {
+        let mut stub_info = stub_info();
+        stub_info.map_docstrings(|doc| doc.replace("value", "item"));
+        let rendered = stub_info.modules["pkg"].to_string();
+        assert!(rendered.contains("Merge two items."));
+        assert!(!rendered.contains("Merge two values."));
+    }
+
+    #[test]
+    fn rename_ident_respects_boundaries() {
+        assert_eq!(rename_ident("A | None", "A", "B"), "B | None");
+        assert_eq!(
+            rename_ident("builtins.dict[A, AB]", "A", "B"),
+            "builtins.dict[B, AB]"
+        );
+        assert_eq!(rename_ident("other.A", "A", "B"), "other.A");
+        assert_eq!(rename_ident("A.Variant", "A", "B"), "B.Variant");
+    }
+}
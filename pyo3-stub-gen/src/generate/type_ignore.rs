@@ -0,0 +1,56 @@
+//! Trailing `# type: ignore` comments given by `#[gen_stub(type_ignore)]` or `#[gen_stub(type_ignore = [...])]`.
+
+use crate::{generate::*, rule_name::RuleName, type_info::IgnoreTarget};
+use itertools::Itertools;
+
+/// `# type: ignore` comment of a definition, which owns its rules so that rules can be added
+/// while generating the stubs, e.g. `override` for an incompatible override.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeIgnore {
+    /// Ignore all type checking errors `(# type: ignore)`
+    All,
+    /// Ignore specific type checking rules `(# type: ignore[rule1,rule2])`
+    Rules(Vec<String>),
+}
+
+impl From<IgnoreTarget> for TypeIgnore {
+    fn from(target: IgnoreTarget) -> Self {
+        match target {
+            IgnoreTarget::All => Self::All,
+            IgnoreTarget::Specified(rules) => {
+                Self::Rules(rules.iter().map(|rule| rule.to_string()).collect())
+            }
+        }
+    }
+}
+
+/// Comment rendered after the signature or the annotation of a definition, e.g. `  # type: ignore[override]`.
+///
+/// Rules unknown to the type checkers are warned about, pointing at the definition by `qualname`, `name`, and `source_location`.
+pub(crate) fn type_ignore_comment(
+    target: &Option<TypeIgnore>,
+    qualname: &QualName,
+    name: &str,
+    source_location: Option<&str>,
+) -> Option<String> {
+    match target.as_ref()? {
+        TypeIgnore::All => Some("  # type: ignore".to_string()),
+        TypeIgnore::Rules(rules) => {
+            let rules = rules
+                .iter()
+                .map(|rule| {
+                    let Ok(result) = rule.parse::<RuleName>();
+                    if let RuleName::Custom(custom) = &result {
+                        log::warn!(
+                            "Unknown custom rule name '{custom}' used in type ignore of '{}'{}. Ensure this is intended.",
+                            qualname::describe(qualname, name),
+                            qualname::at_location(source_location)
+                        );
+                    }
+                    result
+                })
+                .join(",");
+            Some(format!("  # type: ignore[{rules}]"))
+        }
+    }
+}
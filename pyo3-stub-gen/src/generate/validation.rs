@@ -0,0 +1,450 @@
+//! Validation of generated stub files, see [StubInfo::generate_with_validation].
+//!
+//! The built-in check works on the tokens of each file without a Python interpreter,
+//! which catches unbalanced brackets, broken indentation, and duplicated top-level definitions.
+
+use crate::generate::{module_rename::KEYWORDS, *};
+use anyhow::{bail, Result};
+use std::{collections::BTreeMap, io, path::PathBuf, process};
+
+/// Validation run by [StubInfo::generate_with_validation]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Validator {
+    /// Built-in structural checks of the rendered stubs, run before they are written
+    #[default]
+    Syntax,
+    /// The built-in checks, and then an external command given the paths of the written stub files.
+    ///
+    /// The command is skipped with a warning if the program is not found.
+    Command { program: String, args: Vec<String> },
+}
+
+impl Validator {
+    pub fn command(program: impl Into<String>, args: &[&str]) -> Self {
+        Self::Command {
+            program: program.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// `ruff check --select=PYI`
+    pub fn ruff() -> Self {
+        Self::command("ruff", &["check", "--select=PYI"])
+    }
+
+    /// `pyright`
+    pub fn pyright() -> Self {
+        Self::command("pyright", &[])
+    }
+
+    fn run(&self, paths: &[PathBuf]) -> Result<()> {
+        let Self::Command { program, args } = self else {
+            return Ok(());
+        };
+        let output = match process::Command::new(program)
+            .args(args)
+            .args(paths)
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                log::warn!("`{program}` is not found, skip validating the stubs with it");
+                return Ok(());
+            }
+            Err(err) => bail!("Failed to run `{program}`: {err}"),
+        };
+        if !output.status.success() {
+            bail!(
+                "`{program}` rejects the generated stubs ({}):\n{}{}",
+                output.status,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(())
+    }
+}
+
+impl StubInfo {
+    /// Same as [StubInfo::generate_with_report], but validates the stubs with `validator`.
+    ///
+    /// Nothing is written if the built-in checks fail.
+    /// With [StubInfo::merge], the stubs are checked after merging into the existing files.
+    pub fn generate_with_validation(&self, validator: &Validator) -> Result<GenerateReport> {
+        let sink = FsSink::new(&self.python_root);
+        let mut issues = Vec::new();
+        for (_, dest, content) in self.stub_files()? {
+            let content = self.merged(&sink, &dest, content)?;
+            let path = self.python_root.join(dest);
+            issues.extend(
+                check_syntax(&content)
+                    .into_iter()
+                    .map(|issue| format!("{}:{issue}", path.display())),
+            );
+        }
+        if !issues.is_empty() {
+            bail!("Generated stubs are invalid:\n{}", issues.join("\n"));
+        }
+        let report = self.generate_with_report()?;
+        let paths: Vec<PathBuf> = report
+            .written
+            .iter()
+            .chain(&report.unchanged)
+            .cloned()
+            .collect();
+        validator.run(&paths)?;
+        Ok(report)
+    }
+}
+
+/// State of [check_syntax] carried over lines
+#[derive(Default)]
+struct Scanner {
+    issues: Vec<String>,
+    /// Open brackets and their lines
+    brackets: Vec<(char, usize)>,
+    /// Delimiter and starting line of an open triple-quoted string
+    string: Option<(&'static str, usize)>,
+    /// Whether the previous line ends with a backslash
+    continued: bool,
+    /// Last character of the current logical line, except for comments
+    last: Option<char>,
+    indents: Vec<usize>,
+    /// Line of the statement ending with `:` whose block is expected next
+    block: Option<usize>,
+    /// Whether the next top-level definition is decorated by `@typing.overload`
+    overload: bool,
+    /// Top-level names, with the line of their first definition and whether it is an overload
+    names: BTreeMap<String, (usize, bool)>,
+}
+
+/// Structural issues of a stub file as `line: message`
+pub(crate) fn check_syntax(content: &str) -> Vec<String> {
+    let mut scanner = Scanner {
+        indents: vec![0],
+        ..Default::default()
+    };
+    for (index, line) in content.lines().enumerate() {
+        scanner.line(index + 1, line);
+    }
+    scanner.finish()
+}
+
+impl Scanner {
+    fn issue(&mut self, line: usize, message: impl Into<String>) {
+        self.issues.push(format!("{line}: {}", message.into()));
+    }
+
+    fn line(&mut self, n: usize, line: &str) {
+        let logical_start = self.brackets.is_empty() && self.string.is_none() && !self.continued;
+        let code = line.trim_start();
+        if logical_start {
+            if code.is_empty() || code.starts_with('#') {
+                return;
+            }
+            self.indent(n, line.len() - code.len());
+            if line.len() == code.len() {
+                self.top_level(n, code);
+            }
+        }
+        self.tokens(n, line);
+        self.continued = self.string.is_none() && line.trim_end().ends_with('\\');
+        if self.brackets.is_empty() && self.string.is_none() && !self.continued {
+            if self.last == Some(':') {
+                self.block = Some(n);
+            }
+            self.last = None;
+        }
+    }
+
+    fn indent(&mut self, n: usize, indent: usize) {
+        let current = *self.indents.last().unwrap_or(&0);
+        if let Some(block) = self.block.take() {
+            if indent > current {
+                self.indents.push(indent);
+            } else {
+                self.issue(n, format!("expected an indented block after line {block}"));
+            }
+        } else if indent > current {
+            self.issue(n, "unexpected indent");
+        } else if indent < current {
+            while self.indents.last().is_some_and(|&level| level > indent) {
+                self.indents.pop();
+            }
+            if self.indents.last() != Some(&indent) {
+                self.issue(n, "unindent does not match any outer indentation level");
+                self.indents.push(indent);
+            }
+        }
+    }
+
+    fn top_level(&mut self, n: usize, code: &str) {
+        if let Some(decorator) = code.strip_prefix('@') {
+            if matches!(decorator.trim_end(), "typing.overload" | "overload") {
+                self.overload = true;
+            }
+            return;
+        }
+        let overload = std::mem::take(&mut self.overload);
+        let definition = code.strip_prefix("async ").unwrap_or(code);
+        let name = if let Some(rest) = definition
+            .strip_prefix("class ")
+            .or_else(|| definition.strip_prefix("def "))
+        {
+            identifier(rest.trim_start())
+        } else {
+            // Annotations and assignments, e.g. `X: int` and `__all__ = [...]`
+            let name = identifier(code);
+            let rest = code[name.len()..].trim_start();
+            let assigned =
+                rest.starts_with(':') || (rest.starts_with('=') && !rest.starts_with("=="));
+            if KEYWORDS.contains(&name) || !assigned {
+                return;
+            }
+            name
+        };
+        if name.is_empty() {
+            return;
+        }
+        match self.names.get(name) {
+            Some(&(_, true)) if overload => {}
+            Some(&(first, _)) => self.issue(
+                n,
+                format!("duplicate definition of `{name}`, first defined at line {first}"),
+            ),
+            None => {
+                self.names.insert(name.to_string(), (n, overload));
+            }
+        }
+    }
+
+    fn tokens(&mut self, n: usize, line: &str) {
+        let mut rest = line;
+        loop {
+            if let Some((quote, _)) = self.string {
+                match find_closing(rest, quote) {
+                    Some(end) => {
+                        self.string = None;
+                        self.last = quote.chars().next();
+                        rest = &rest[end..];
+                    }
+                    None => return,
+                }
+            }
+            let Some(c) = rest.chars().next() else {
+                return;
+            };
+            rest = &rest[c.len_utf8()..];
+            match c {
+                '#' => return,
+                '"' | '\'' => {
+                    let triple = if c == '"' { "\"\"\"" } else { "'''" };
+                    if let Some(after) = rest.strip_prefix(&triple[1..]) {
+                        self.string = Some((triple, n));
+                        rest = after;
+                        continue;
+                    }
+                    let quote = if c == '"' { "\"" } else { "'" };
+                    match find_closing(rest, quote) {
+                        Some(end) => rest = &rest[end..],
+                        None => {
+                            self.issue(n, "unterminated string literal");
+                            return;
+                        }
+                    }
+                }
+                '(' | '[' | '{' => self.brackets.push((c, n)),
+                ')' | ']' | '}' => {
+                    let open = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    match self.brackets.pop() {
+                        Some((found, _)) if found == open => {}
+                        Some((found, line)) => self.issue(
+                            n,
+                            format!("closing `{c}` does not match `{found}` at line {line}"),
+                        ),
+                        None => self.issue(n, format!("unmatched `{c}`")),
+                    }
+                }
+                _ => {}
+            }
+            if !c.is_whitespace() {
+                self.last = Some(c);
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if let Some((_, line)) = self.string {
+            self.issue(line, "unterminated triple-quoted string");
+        }
+        for (c, line) in std::mem::take(&mut self.brackets) {
+            self.issue(line, format!("`{c}` is never closed"));
+        }
+        if let Some(block) = self.block {
+            self.issue(block, "expected an indented block");
+        }
+        self.issues
+    }
+}
+
+/// Leading identifier of `code`
+fn identifier(code: &str) -> &str {
+    let end = code
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(code.len());
+    &code[..end]
+}
+
+/// Byte offset just after the closing `quote` in `rest`, skipping escaped characters
+fn find_closing(rest: &str, quote: &str) -> Option<usize> {
+    let mut chars = rest.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if rest[i..].starts_with(quote) {
+            return Some(i + quote.len());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TypeInfo;
+    use std::any::TypeId;
+    use test_case::test_case;
+
+    #[test_case("def f(x: int) -> None: ...\n" => Vec::<String>::new(); "valid")]
+    #[test_case("def f(x: int -> None: ...\n" => vec!["1: `(` is never closed"]; "unclosed bracket")]
+    #[test_case("X: list[int]]\n" => vec!["1: unmatched `]`"]; "unmatched bracket")]
+    #[test_case("X: list[int)\n" => vec!["1: closing `)` does not match `[` at line 1"]; "mismatched bracket")]
+    #[test_case("X: str = \"a\n" => vec!["1: unterminated string literal"]; "unterminated string")]
+    #[test_case("X: str = 'a(\\'b'\n" => Vec::<String>::new(); "escaped quote")]
+    #[test_case("class A:\n    r\"\"\"\n    Doc with ( and :\n    \"\"\"\n" => Vec::<String>::new(); "docstring")]
+    #[test_case("class A:\n    r\"\"\"\n    Doc\n" => vec!["2: unterminated triple-quoted string"]; "unterminated docstring")]
+    #[test_case("def f(\n    x: int,\n) -> None: ...\n" => Vec::<String>::new(); "multiline brackets")]
+    #[test_case("class A:\n\ndef f() -> None: ...\n" => vec!["3: expected an indented block after line 1"]; "missing block")]
+    #[test_case("class A:\n" => vec!["1: expected an indented block"]; "missing block at end")]
+    #[test_case("X: int\n    Y: int\n" => vec!["2: unexpected indent"]; "unexpected indent")]
+    #[test_case("class A:\n    def f(self) -> None: ...\n  X: int\n" => vec!["3: unindent does not match any outer indentation level"]; "bad unindent")]
+    #[test_case("class A: ...\nclass A: ...\n" => vec!["2: duplicate definition of `A`, first defined at line 1"]; "duplicate class")]
+    #[test_case("X: int\ndef X() -> None: ...\n" => vec!["2: duplicate definition of `X`, first defined at line 1"]; "duplicate variable")]
+    #[test_case("@typing.overload\ndef f(x: int) -> int: ...\n@typing.overload\ndef f(x: str) -> str: ...\n" => Vec::<String>::new(); "overloads")]
+    #[test_case("@typing.overload\ndef f(x: int) -> int: ...\ndef f(x: str) -> str: ...\n" => vec!["3: duplicate definition of `f`, first defined at line 2"]; "overload and plain definition")]
+    #[test_case("class A:\n    @property\n    def x(self) -> int: ...\n    @x.setter\n    def x(self, value: int) -> None: ...\n" => Vec::<String>::new(); "nested definitions")]
+    fn syntax(content: &str) -> Vec<String> {
+        check_syntax(content)
+    }
+
+    #[test]
+    fn rendered_module() {
+        let mut module = Module::new("pkg", "pkg");
+        let mut class = ClassDef::new("A");
+        class.doc = "Doc\nwith lines";
+        module.class.insert(TypeId::of::<()>(), class);
+        module.function.insert(
+            "f".into(),
+            vec![
+                FunctionDef::new("f", Parameters::new(), TypeInfo::builtin("int")),
+                FunctionDef::new("f", Parameters::new(), TypeInfo::builtin("str")),
+            ],
+        );
+        assert_eq!(check_syntax(&module.to_string()), Vec::<String>::new());
+
+        // The same name as a class and a function
+        module.function.insert(
+            "A".into(),
+            vec![FunctionDef::new("A", Parameters::new(), TypeInfo::none())],
+        );
+        let issues = check_syntax(&module.to_string());
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(
+            issues[0].contains("duplicate definition of `A`"),
+            "{issues:?}"
+        );
+    }
+
+    #[test]
+    fn nothing_written_for_invalid_stubs() -> Result<()> {
+        let root =
+            std::env::temp_dir().join(format!("pyo3-stub-gen-validation-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let mut module = Module::new("pkg", "pkg");
+        module.class.insert(TypeId::of::<()>(), ClassDef::new("A"));
+        let mut info = StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), module)]),
+            python_root: root.clone(),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        };
+
+        // A missing program is skipped
+        let validator = Validator::command("pyo3-stub-gen-missing-validator", &[]);
+        let report = info.generate_with_validation(&validator)?;
+        assert_eq!(report.written, [root.join("pkg.pyi")]);
+
+        let module = info.modules.get_mut("pkg").unwrap();
+        module
+            .variables
+            .insert("A", VariableDef::new("A", TypeInfo::builtin("int")));
+        let err = info
+            .generate_with_validation(&Validator::Syntax)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("duplicate definition of `A`, first defined at line"),
+            "{err}"
+        );
+        assert!(!std::fs::read_to_string(root.join("pkg.pyi"))?.contains("A: builtins.int"));
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn merged_stubs_are_checked() -> Result<()> {
+        let root = std::env::temp_dir().join(format!(
+            "pyo3-stub-gen-validation-merge-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root)?;
+        let mut module = Module::new("pkg", "pkg");
+        module
+            .variables
+            .insert("A", VariableDef::new("A", TypeInfo::builtin("int")));
+        let info = StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), module)]),
+            python_root: root.clone(),
+            layout: StubLayout::default(),
+            merge: true,
+            config: Default::default(),
+        };
+
+        let existing = format!(
+            "class A: ...\n{}\n{}\n",
+            crate::generate::layout::BEGIN_MARKER,
+            crate::generate::layout::END_MARKER
+        );
+        std::fs::write(root.join("pkg.pyi"), &existing)?;
+        let err = info
+            .generate_with_validation(&Validator::Syntax)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("duplicate definition of `A`, first defined at line 1"),
+            "{err}"
+        );
+        assert_eq!(std::fs::read_to_string(root.join("pkg.pyi"))?, existing);
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -1,12 +1,43 @@
-use std::fmt;
+use std::{collections::HashSet, fmt};
 
-use crate::{type_info::PyVariableInfo, TypeInfo};
+use crate::{
+    generate::{
+        append_deprecated_section, docstring, type_ignore_comment, Import, QualName, TypeIgnore,
+    },
+    stub_type::ImportRef,
+    type_info::{DeprecatedInfo, PyVariableInfo},
+    TypeInfo,
+};
 
+/// Definition of a module-level variable.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct VariableDef {
     pub name: &'static str,
     pub type_: TypeInfo,
     pub default: Option<String>,
+    /// Attribute docstring rendered after the annotation
+    pub doc: &'static str,
+    pub deprecated: Option<DeprecatedInfo>,
+    /// `# type: ignore` comment after the annotation
+    pub type_ignored: Option<TypeIgnore>,
+    /// Fully-qualified Python name, assigned when [StubInfo](crate::StubInfo) is built
+    pub qualname: QualName,
+}
+
+impl VariableDef {
+    /// Variable without a default value
+    pub fn new(name: &'static str, type_: TypeInfo) -> Self {
+        Self {
+            name,
+            type_,
+            default: None,
+            doc: "",
+            deprecated: None,
+            type_ignored: None,
+            qualname: QualName::default(),
+        }
+    }
 }
 
 impl From<&PyVariableInfo> for VariableDef {
@@ -15,16 +46,155 @@ impl From<&PyVariableInfo> for VariableDef {
             name: info.name,
             type_: (info.r#type)(),
             default: info.default.map(|f| f()),
+            doc: info.doc,
+            deprecated: info.deprecated.clone(),
+            type_ignored: info.type_ignored.map(Into::into),
+            qualname: QualName::default(),
         }
     }
 }
 
+impl VariableDef {
+    /// Value of a constant, i.e. a default which is not rendered as `...`
+    fn value(&self) -> Option<&str> {
+        self.default.as_deref().filter(|default| *default != "...")
+    }
+}
+
+impl Import for VariableDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        let mut import = self.type_.import.clone();
+        if self.value().is_some() {
+            // for `typing.Final`
+            import.insert("typing".into());
+        }
+        import
+    }
+}
+
 impl fmt::Display for VariableDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: {}", self.name, self.type_)?;
-        if let Some(default) = &self.default {
-            write!(f, " = {default}")?;
+        if let Some(value) = self.value() {
+            write!(f, "{}: typing.Final[{}] = {value}", self.name, self.type_)?;
+        } else {
+            write!(f, "{}: {}", self.name, self.type_)?;
+            if let Some(default) = &self.default {
+                write!(f, " = {default}")?;
+            }
+        }
+        if let Some(comment) =
+            type_ignore_comment(&self.type_ignored, &self.qualname, self.name, None)
+        {
+            write!(f, "{comment}")?;
+        }
+        // Variables cannot be decorated, so the deprecation is only noted in the docstring
+        let doc = match &self.deprecated {
+            Some(deprecated) => append_deprecated_section(self.doc, deprecated),
+            None => self.doc.into(),
+        };
+        let mut rendered = String::new();
+        docstring::write_docstring(&mut rendered, &doc, "")?;
+        if !rendered.is_empty() {
+            write!(f, "\n{}", rendered.trim_end_matches('\n'))?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PyStubType;
+
+    fn variable(r#type: fn() -> TypeInfo, default: Option<fn() -> String>) -> VariableDef {
+        VariableDef::from(&PyVariableInfo {
+            name: "X",
+            module: "pkg",
+            rust_path: "pkg",
+            r#type,
+            default,
+            ..PyVariableInfo::DEFAULT
+        })
+    }
+
+    #[cfg(feature = "infer_signature")]
+    #[test]
+    fn constants() {
+        use crate::util::fmt_py_obj;
+        let constants = [
+            variable(<&str>::type_output, Some(|| fmt_py_obj("1.2.3"))),
+            variable(usize::type_output, Some(|| fmt_py_obj(32_usize))),
+            variable(bool::type_output, Some(|| fmt_py_obj(true))),
+            variable(<(i32, &str)>::type_output, Some(|| fmt_py_obj((1, "a")))),
+        ];
+        assert_eq!(
+            constants
+                .iter()
+                .map(|def| def.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "X: typing.Final[builtins.str] = '1.2.3'",
+                "X: typing.Final[builtins.int] = 32",
+                "X: typing.Final[builtins.bool] = True",
+                "X: typing.Final[tuple[builtins.int, builtins.str]] = (1, 'a')",
+            ]
+        );
+        for def in constants {
+            assert!(def.import().contains(&"typing".into()));
+        }
+    }
+
+    #[test]
+    fn type_ignore() {
+        let mut def = variable(usize::type_output, None);
+        def.type_ignored = Some(TypeIgnore::Rules(vec![
+            "assignment".to_string(),
+            "misc".to_string(),
+        ]));
+        assert_eq!(
+            def.to_string(),
+            "X: builtins.int  # type: ignore[assignment,misc]"
+        );
+        def.type_ignored = Some(TypeIgnore::All);
+        def.default = Some("1".to_string());
+        assert_eq!(
+            def.to_string(),
+            "X: typing.Final[builtins.int] = 1  # type: ignore"
+        );
+    }
+
+    #[test]
+    fn attribute_docstring() {
+        let mut def = variable(usize::type_output, None);
+        def.doc = "Port the server listens on";
+        def.type_ignored = Some(TypeIgnore::All);
+        assert_eq!(
+            def.to_string(),
+            "X: builtins.int  # type: ignore\nr\"\"\"\nPort the server listens on\n\"\"\""
+        );
+    }
+
+    #[test]
+    fn deprecated_variable() {
+        let mut def = variable(usize::type_output, None);
+        def.doc = "Port the server listens on";
+        def.deprecated = Some(DeprecatedInfo {
+            since: Some("0.3"),
+            note: Some("use PORTS"),
+        });
+        assert_eq!(
+            def.to_string(),
+            "X: builtins.int\nr\"\"\"\nPort the server listens on\n\nDeprecated:\n    Since 0.3: use PORTS\n\"\"\""
+        );
+    }
+
+    #[test]
+    fn without_value() {
+        let def = variable(usize::type_output, None);
+        assert_eq!(def.to_string(), "X: builtins.int");
+        assert!(!def.import().contains(&"typing".into()));
+        // A value which cannot be rendered is not a constant
+        let def = variable(usize::type_output, Some(|| "...".to_string()));
+        assert_eq!(def.to_string(), "X: builtins.int = ...");
+    }
+}
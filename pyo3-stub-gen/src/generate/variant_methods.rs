@@ -1,6 +1,8 @@
 use indexmap::IndexMap;
 
-use crate::generate::{MethodDef, MethodType, Parameter, ParameterDefault, Parameters};
+use crate::generate::{
+    constructor_style, MethodDef, MethodType, Parameter, ParameterDefault, Parameters, QualName,
+};
 use crate::type_info::{ParameterKind, PyComplexEnumInfo, VariantForm, VariantInfo};
 use crate::TypeInfo;
 
@@ -17,15 +19,18 @@ pub(super) fn get_variant_methods(
         .entry("__new__".to_string())
         .or_default()
         .push(MethodDef {
-            name: "__new__",
+            name: "__new__".into(),
             parameters: Parameters::from_infos(info.constr_args),
-            r#return: TypeInfo::self_type(),
+            r#return: constructor_style().return_type(),
             doc: "",
             r#type: MethodType::New,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_abstract: false,
+            is_override: false,
+            qualname: QualName::default(),
+            source_location: None,
         });
 
     if let VariantForm::Tuple = info.form {
@@ -34,7 +39,7 @@ pub(super) fn get_variant_methods(
             .entry(len_name.to_string())
             .or_default()
             .push(MethodDef {
-                name: len_name,
+                name: len_name.into(),
                 parameters: Parameters::new(),
                 r#return: TypeInfo::builtin("int"),
                 doc: "",
@@ -43,6 +48,9 @@ pub(super) fn get_variant_methods(
                 deprecated: None,
                 type_ignored: None,
                 is_abstract: false,
+                is_override: false,
+                qualname: QualName::default(),
+                source_location: None,
             });
 
         let getitem_name = "__getitem__";
@@ -50,13 +58,15 @@ pub(super) fn get_variant_methods(
             .entry(getitem_name.to_string())
             .or_default()
             .push(MethodDef {
-                name: getitem_name,
+                name: getitem_name.into(),
                 parameters: Parameters {
                     positional_or_keyword: vec![Parameter {
                         name: "key",
                         kind: ParameterKind::PositionalOrKeyword,
                         type_info: TypeInfo::builtin("int"),
                         default: ParameterDefault::None,
+                        container_default: None,
+                        deprecated: None,
                     }],
                     ..Parameters::new()
                 },
@@ -67,6 +77,9 @@ pub(super) fn get_variant_methods(
                 deprecated: None,
                 type_ignored: None,
                 is_abstract: false,
+                is_override: false,
+                qualname: QualName::default(),
+                source_location: None,
             });
     }
 
@@ -29,12 +29,17 @@
 //!         // Type ID of Rust struct (used to gathering phase discussed later)
 //!         struct_id: std::any::TypeId::of::<MyClass>,
 //!
+//!         // Path of the Rust struct, used in error messages
+//!         rust_path: "my_crate::MyClass",
+//!
 //!         // Python module name. Since stub file is generated per modules,
 //!         // this helps where the class definition should be placed.
 //!         module: Some("my_module"),
 //!
 //!         // Python class name
 //!         pyclass_name: "MyClass",
+//!         // Whether the name is given by `#[pyclass(name = "...")]`
+//!         name_given: false,
 //!
 //!         getters: &[
 //!             MemberInfo {
@@ -45,6 +50,10 @@
 //!                 deprecated: None,
 //!                 item: false,
 //!                 is_abstract: false,
+//!                 also_as: &[],
+//!                 is_classattr: false,
+//!                 int_value: None,
+//!                 type_ignored: None,
 //!             },
 //!             MemberInfo {
 //!                 name: "description",
@@ -54,6 +63,10 @@
 //!                 deprecated: None,
 //!                 item: false,
 //!                 is_abstract: false,
+//!                 also_as: &[],
+//!                 is_classattr: false,
+//!                 int_value: None,
+//!                 type_ignored: None,
 //!             },
 //!         ],
 //!
@@ -73,6 +86,25 @@
 //!         // Decorated with `#[pyclass(subclass)]`
 //!         subclass: false,
 //!         is_abstract: false,
+//!         // Set by `#[gen_stub(type_input_override(...), type_output_override(...))]`
+//!         type_override: ClassTypeOverride {
+//!             input: None,
+//!             output: None,
+//!             custom_from_py_object: false,
+//!         },
+//!         dataclass: false,
+//!         hidden_base: false,
+//!         no_abc: false,
+//!         flag_enum: false,
+//!         total_ordering: false,
+//!         pickle_state: None,
+//!         slots: false,
+//!         weakref: false,
+//!         feature: None,
+//!         deprecated: None,
+//!
+//!         // Other fields, e.g. the file and line of the Rust struct set by the proc-macro, are left empty
+//!         ..PyClassInfo::DEFAULT
 //!     }
 //! }
 //! ```
@@ -143,25 +175,14 @@
 //! ```rust
 //! use pyo3_stub_gen::{TypeInfo, generate::*, type_info::ParameterKind};
 //!
-//! let method = MethodDef {
-//!     name: "foo",
-//!     parameters: Parameters {
-//!         positional_or_keyword: vec![Parameter {
-//!             name: "x",
-//!             kind: ParameterKind::PositionalOrKeyword,
-//!             type_info: TypeInfo::builtin("int"),
-//!             default: ParameterDefault::None,
-//!         }],
-//!         ..Parameters::new()
-//!     },
-//!     r#return: TypeInfo::builtin("int"),
-//!     doc: "This is a foo method.",
-//!     r#type: MethodType::Instance,
-//!     deprecated: None,
-//!     is_async: false,
-//!     type_ignored: None,
-//!     is_abstract: false,
-//! };
+//! let mut parameters = Parameters::new();
+//! parameters.positional_or_keyword.push(Parameter::new(
+//!     "x",
+//!     ParameterKind::PositionalOrKeyword,
+//!     TypeInfo::builtin("int"),
+//! ));
+//! let mut method = MethodDef::new("foo", MethodType::Instance, parameters, TypeInfo::builtin("int"));
+//! method.doc = "This is a foo method.";
 //!
 //! assert_eq!(
 //!     method.to_string().trim(),
@@ -181,16 +202,33 @@
 //! [StubInfo] is instantiated with [pyproject::PyProject] to get where to generate the stub file,
 //! and [StubInfo::generate] generates the stub files for every modules.
 //!
+//! Tools working on these definitions should use them through the [api] module,
+//! which lists the items kept stable across minor releases.
+//!
 
 pub use inventory;
 pub use pyo3_stub_gen_derive as derive; // re-export to use in generated code
-pub use stub_type::{self_import_strategy, set_self_import_strategy, SelfImportStrategy};
+pub use stub_type::{
+    abstract_set_input, inline_complex_enum_unions, self_import_strategy, set_abstract_set_input,
+    set_inline_complex_enum_unions, set_self_import_strategy, SelfImportStrategy,
+};
 
+pub mod api;
+pub mod build_helper;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod config;
+#[cfg(feature = "coverage")]
+pub mod coverage;
 pub mod exception;
 pub mod generate;
 pub mod pyproject;
 pub mod rule_name;
+pub mod runner;
+#[cfg(feature = "runtime-check")]
+pub mod runtime_check;
 mod stub_type;
+pub mod testing;
 pub mod type_info;
 pub mod util;
 
@@ -205,6 +243,12 @@ pub type Result<T> = anyhow::Result<T>;
 /// This must be placed in your PyO3 library crate, i.e. same crate where [inventory::submit]ted,
 /// not in `gen_stub` executables due to [inventory] mechanism.
 ///
+/// With `export_entry`, the library also exports the entry point called by [runner::run_from_cdylib],
+/// and the marker of [build_helper::emit_rerun_and_marker] if `build.rs` calls it:
+///
+/// ```rust:ignore
+/// pyo3_stub_gen::define_stub_info_gatherer!(stub_info, export_entry);
+/// ```
 #[macro_export]
 macro_rules! define_stub_info_gatherer {
     ($function_name:ident) => {
@@ -214,6 +258,38 @@ macro_rules! define_stub_info_gatherer {
             $crate::StubInfo::from_pyproject_toml(manifest_dir.join("pyproject.toml"))
         }
     };
+    ($function_name:ident, export_entry) => {
+        $crate::define_stub_info_gatherer!($function_name);
+
+        /// Auto-generated entry point to generate stub files, called by `pyo3_stub_gen::runner::run_from_cdylib`
+        #[no_mangle]
+        pub extern "C" fn _pyo3_stub_gen_entry() -> ::std::os::raw::c_int {
+            $crate::runner::entry($function_name)
+        }
+
+        /// Auto-generated marker of the sources the library is built from
+        #[no_mangle]
+        pub extern "C" fn _pyo3_stub_gen_marker() -> u64 {
+            $crate::runner::marker(option_env!("PYO3_STUB_GEN_MARKER"))
+        }
+    };
+}
+
+/// Define the `main` function of a `stub_gen` executable, which runs the [cli] with the given function
+/// defined by [define_stub_info_gatherer].
+///
+/// ```rust:ignore
+/// // src/bin/stub_gen.rs
+/// pyo3_stub_gen::stub_gen_main!(my_crate::stub_info);
+/// ```
+#[cfg(feature = "cli")]
+#[macro_export]
+macro_rules! stub_gen_main {
+    ($stub_info:path) => {
+        fn main() -> ::std::process::ExitCode {
+            $crate::cli::run($stub_info)
+        }
+    };
 }
 
 /// Add module-level documention using interpolation of runtime expressions.
@@ -247,27 +323,58 @@ macro_rules! module_doc {
 /// Add module-level variable, the first argument `module_variable!` receives is the full module name;
 /// the second argument is the name of the variable, the third argument is the type of the variable,
 /// and (optional) the fourth argument is the default value of the variable.
+/// A variable with a value is rendered as a constant, e.g. `CONSTANT2: typing.Final[builtins.int] = 123`.
 /// ```rust
 /// pyo3_stub_gen::module_variable!("module.name", "CONSTANT1", usize);
 /// pyo3_stub_gen::module_variable!("module.name", "CONSTANT2", usize, 123);
 /// ```
+///
+/// `#[deprecated]` and `#[gen_stub(type_ignore)]` may precede the arguments as for a `#[pyfunction]`.
+/// Since a variable cannot be decorated, the former is noted in a `Deprecated:` section of its docstring,
+/// and the latter renders `# type: ignore[...]` after the annotation:
+/// ```rust
+/// pyo3_stub_gen::module_variable!(
+///     #[deprecated(since = "0.2.0", note = "use CONSTANT2")]
+///     #[gen_stub(type_ignore = ["misc"])]
+///     "module.name",
+///     "CONSTANT3",
+///     usize
+/// );
+/// ```
 #[macro_export]
 macro_rules! module_variable {
-    ($module:expr, $name:expr, $ty:ty) => {
+    (@info [$deprecated:expr] [$type_ignored:expr] [$doc:expr] #[deprecated $($args:tt)*] $($rest:tt)*) => {
+        $crate::module_variable!(
+            @info [Some($crate::module_variable!(@deprecated $($args)*))] [$type_ignored] [$doc] $($rest)*
+        );
+    };
+    (@info [$deprecated:expr] [$type_ignored:expr] [$doc:expr] #[gen_stub(type_ignore $($args:tt)*)] $($rest:tt)*) => {
+        $crate::module_variable!(
+            @info [$deprecated] [Some($crate::module_variable!(@type_ignore $($args)*))] [$doc] $($rest)*
+        );
+    };
+    (@info [$deprecated:expr] [$type_ignored:expr] [$doc:expr] $module:expr, $name:expr, $ty:ty) => {
         $crate::inventory::submit! {
             $crate::type_info::PyVariableInfo{
                 name: $name,
                 module: $module,
+                rust_path: module_path!(),
+                source_location: Some(concat!(file!(), ":", line!())),
                 r#type: <$ty as $crate::PyStubType>::type_output,
                 default: None,
+                doc: $doc,
+                deprecated: $deprecated,
+                type_ignored: $type_ignored,
             }
         }
     };
-    ($module:expr, $name:expr, $ty:ty, $value:expr) => {
+    (@info [$deprecated:expr] [$type_ignored:expr] [$doc:expr] $module:expr, $name:expr, $ty:ty, $value:expr) => {
         $crate::inventory::submit! {
             $crate::type_info::PyVariableInfo{
                 name: $name,
                 module: $module,
+                rust_path: module_path!(),
+                source_location: Some(concat!(file!(), ":", line!())),
                 r#type: <$ty as $crate::PyStubType>::type_output,
                 default: Some({
                     fn _fmt() -> String {
@@ -276,6 +383,228 @@ macro_rules! module_variable {
                     }
                     _fmt
                 }),
+                doc: $doc,
+                deprecated: $deprecated,
+                type_ignored: $type_ignored,
+            }
+        }
+    };
+    (@deprecated) => {
+        $crate::type_info::DeprecatedInfo { since: None, note: None }
+    };
+    (@deprecated = $note:literal) => {
+        $crate::type_info::DeprecatedInfo { since: None, note: Some($note) }
+    };
+    (@deprecated ($($key:ident = $value:literal),* $(,)?)) => {
+        $crate::type_info::DeprecatedInfo {
+            since: $crate::module_variable!(@deprecated_field since $($key = $value),*),
+            note: $crate::module_variable!(@deprecated_field note $($key = $value),*),
+        }
+    };
+    (@deprecated_field $field:ident) => {
+        None
+    };
+    (@deprecated_field since since = $value:literal $(, $($rest:tt)*)?) => {
+        Some($value)
+    };
+    (@deprecated_field note note = $value:literal $(, $($rest:tt)*)?) => {
+        Some($value)
+    };
+    (@deprecated_field $field:ident $key:ident = $value:literal $(, $($rest:tt)*)?) => {
+        $crate::module_variable!(@deprecated_field $field $($($rest)*)?)
+    };
+    (@type_ignore) => {
+        $crate::type_info::IgnoreTarget::All
+    };
+    (@type_ignore = [$($rule:literal),+ $(,)?]) => {
+        $crate::type_info::IgnoreTarget::Specified(&[$($rule),+])
+    };
+    (@type_ignore = $rule:literal) => {
+        $crate::type_info::IgnoreTarget::Specified(&[$rule])
+    };
+    ($($args:tt)*) => {
+        $crate::module_variable!(@info [None] [None] [""] $($args)*);
+    };
+}
+
+/// Add a module attribute set in the `#[pymodule]` function, e.g. by `m.add("DEFAULT_CONFIG", Config::default())`,
+/// with its type evaluated by [PyStubType::type_output] when the stub is generated,
+/// and (optional) `doc` rendered as the attribute docstring.
+/// `m_path` may be any `&'static str` constant, e.g. one joined by [concat_module!],
+/// so that a single constant drives the declarations of a module.
+/// ```rust
+/// use pyo3_stub_gen::{concat_module, gen_stub_module_attr};
+///
+/// const PACKAGE: &str = "my_pkg";
+/// const CONFIG_MODULE: &str = concat_module!(PACKAGE, "config");
+///
+/// gen_stub_module_attr!(m_path = CONFIG_MODULE, name = "DEFAULT_PORT", type = u16);
+/// gen_stub_module_attr!(
+///     m_path = CONFIG_MODULE,
+///     name = "DEFAULT_HOST",
+///     type = String,
+///     doc = "Host the server listens on unless configured",
+/// );
+/// // In the default module, i.e. `module-name` in `[tool.maturin]`
+/// gen_stub_module_attr!(m_path = concat_module!(), name = "VERSION", type = String);
+/// ```
+///
+/// `#[deprecated]` and `#[gen_stub(type_ignore)]` may precede the arguments as for [module_variable!].
+#[macro_export]
+macro_rules! gen_stub_module_attr {
+    (
+        $(#[$($attr:tt)*])*
+        m_path = $module:expr,
+        name = $name:expr,
+        type = $ty:ty
+        $(, doc = $doc:expr)?
+        $(,)?
+    ) => {
+        $crate::module_variable!(
+            @info [None] [None] [$crate::gen_stub_module_attr!(@doc $($doc)?)]
+            $(#[$($attr)*])* $module, $name, $ty
+        );
+    };
+    (@doc) => {
+        ""
+    };
+    (@doc $doc:expr) => {
+        $doc
+    };
+}
+
+/// Join `&'static str` constants of module path segments by `.` at compile time,
+/// or give the default module, i.e. `module-name` in `[tool.maturin]`, without segments.
+/// ```rust
+/// const PACKAGE: &str = "my_pkg";
+/// assert_eq!(pyo3_stub_gen::concat_module!(PACKAGE, "config", "io"), "my_pkg.config.io");
+/// assert_eq!(pyo3_stub_gen::concat_module!(), "");
+/// ```
+#[macro_export]
+macro_rules! concat_module {
+    () => {
+        ""
+    };
+    ($($segment:expr),+ $(,)?) => {{
+        const SEGMENTS: &[&str] = &[$($segment),+];
+        const BYTES: [u8; $crate::util::module_path_len(SEGMENTS)] =
+            $crate::util::join_module_path(SEGMENTS);
+        const PATH: &str = match ::core::str::from_utf8(&BYTES) {
+            Ok(path) => path,
+            Err(_) => panic!("module path segments joined into invalid UTF-8"),
+        };
+        PATH
+    }};
+}
+
+/// Add `__version__` of the given module, whose value is the version of the calling crate in `Cargo.toml`.
+/// ```rust
+/// pyo3_stub_gen::module_version!("module.name");
+/// ```
+///
+/// This renders `__version__: typing.Final[builtins.str] = '0.1.0'` for a crate of version `0.1.0`.
+/// The module still has to define the attribute, e.g. by `m.add("__version__", env!("CARGO_PKG_VERSION"))?`.
+#[macro_export]
+macro_rules! module_version {
+    ($module:expr) => {
+        $crate::module_variable!($module, "__version__", &str, env!("CARGO_PKG_VERSION"));
+    };
+}
+
+/// End the stub of the given module with `def __getattr__(name: builtins.str) -> typing.Any: ...`,
+/// which marks the module as partially typed for the attributes created at runtime, e.g. by a plugin registry.
+/// ```rust
+/// pyo3_stub_gen::gen_stub_module_attr_fallback!("module.name");
+/// ```
+///
+/// The same is done for the modules listed in `attr-fallback` of `[tool.pyo3-stub-gen]`.
+/// A `__getattr__` function defined by the module itself is kept instead.
+#[macro_export]
+macro_rules! gen_stub_module_attr_fallback {
+    ($module:expr) => {
+        $crate::inventory::submit! {
+            $crate::type_info::ModuleAttrFallbackInfo { module: $module }
+        }
+    };
+}
+
+/// Add a module-level `typing.Literal` alias of the values of variables added by [module_variable].
+/// The first argument is the full module name of the variables, the second is the name of the alias,
+/// and the third lists the names of the variables.
+/// ```rust
+/// pyo3_stub_gen::module_variable!("module.name", "MODE_FAST", &str, "fast");
+/// pyo3_stub_gen::module_variable!("module.name", "MODE_SLOW", &str, "slow");
+/// pyo3_stub_gen::literal_group!("module.name", "Mode", [MODE_FAST, MODE_SLOW]);
+/// ```
+///
+/// This renders `Mode: typing.TypeAlias = typing.Literal['fast', 'slow']` in `module.name`,
+/// which can be referred to by `#[gen_stub(override_type(type_repr = "Mode"))]`.
+/// Every variable must have a value rendered as a `str`, `bytes`, `int`, `bool`, or `None` literal.
+#[macro_export]
+macro_rules! literal_group {
+    ($module:expr, $name:expr, [$($variable:ident),* $(,)?]) => {
+        $crate::inventory::submit! {
+            $crate::type_info::LiteralGroupInfo {
+                module: $module,
+                name: $name,
+                rust_path: module_path!(),
+                variables: &[$(stringify!($variable)),*],
+            }
+        }
+    };
+}
+
+/// Stub-only `typing.NamedTuple` class, and a marker type of the same name referring to it.
+/// The first argument is the class with its fields, and the second is the full module name of the class.
+/// ```rust
+/// pyo3_stub_gen::gen_stub_named_tuple!(
+///     /// Score of a player
+///     pub ScoreEntry { name: String, score: f64 },
+///     module = "module.name"
+/// );
+/// ```
+///
+/// This renders the class below in `module.name`:
+///
+/// ```python
+/// class ScoreEntry(typing.NamedTuple):
+///     r"""
+///     Score of a player
+///     """
+///     name: builtins.str
+///     score: builtins.float
+/// ```
+///
+/// The marker is not a Python object, but it can be referred to in the stubs instead of a tuple with the same items,
+/// e.g. by `pyo3_stub_gen.RustType["Vec<ScoreEntry>"]` in place of `Vec<(String, f64)>`.
+#[macro_export]
+macro_rules! gen_stub_named_tuple {
+    (
+        $(#[doc = $doc:literal])*
+        $vis:vis $name:ident { $($field:ident : $ty:ty),* $(,)? },
+        module = $module:expr $(,)?
+    ) => {
+        $(#[doc = $doc])*
+        $vis struct $name;
+
+        impl $crate::PyStubType for $name {
+            fn type_output() -> $crate::TypeInfo {
+                $crate::TypeInfo::locally_defined(stringify!($name), $module.into())
+            }
+        }
+
+        $crate::inventory::submit! {
+            $crate::type_info::NamedTupleInfo {
+                struct_id: ::std::any::TypeId::of::<$name>,
+                name: stringify!($name),
+                module: $module,
+                rust_path: concat!(module_path!(), "::", stringify!($name)),
+                source_location: Some(concat!(file!(), ":", line!())),
+                doc: concat!($($doc, "\n"),*),
+                fields: &[$($crate::type_info::NamedTupleFieldInfo {
+                    name: stringify!($field),
+                    r#type: <$ty as $crate::PyStubType>::type_output,
+                }),*],
             }
         }
     };
@@ -1,4 +1,4 @@
-//! `pyproject.toml` parser for reading `[tool.maturin]` configuration.
+//! `pyproject.toml` parser for reading `[tool.maturin]` and `[tool.pyo3-stub-gen]` configuration.
 //!
 //! ```
 //! use pyo3_stub_gen::pyproject::PyProject;
@@ -10,12 +10,18 @@
 //! ).unwrap();
 //! ```
 
-use anyhow::{bail, Result};
+use crate::generate::{
+    ConstructorStyle, ContainerDefaultStyle, DocstringStyle, ForwardReferences, KeywordParameters,
+    StubLayout, TargetVersion, UnresolvedReferences,
+};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::*};
+use std::{collections::BTreeMap, fs, path::*};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PyProject {
+    /// `[project]` table, whose `name` is filled with the Cargo package name if absent
+    #[serde(default)]
     pub project: Project,
     pub tool: Option<Tool>,
 
@@ -24,13 +30,46 @@ pub struct PyProject {
 }
 
 impl PyProject {
+    /// Parse `path`, falling back to `CARGO_PKG_NAME` with dashes replaced by underscores
+    /// if neither `tool.maturin.module-name` nor `project.name` is given.
     pub fn parse_toml(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
         if path.file_name() != Some("pyproject.toml".as_ref()) {
             bail!("{} is not a pyproject.toml", path.display())
         }
-        let mut out: PyProject = toml::de::from_str(&fs::read_to_string(path)?)?;
-        out.toml_path = path.to_path_buf();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml_str(
+            &content,
+            path,
+            std::env::var("CARGO_PKG_NAME").ok().as_deref(),
+        )
+    }
+
+    /// Parse `content` of the pyproject.toml at `path`, falling back to `cargo_package` for the module name
+    pub(crate) fn from_toml_str(
+        content: &str,
+        path: &Path,
+        cargo_package: Option<&str>,
+    ) -> Result<Self> {
+        let mut out: PyProject = toml::de::from_str(content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        out.toml_path = std::path::absolute(path)?;
+        let has_module_name = out
+            .tool
+            .as_ref()
+            .and_then(|tool| tool.maturin.as_ref())
+            .is_some_and(|maturin| maturin.module_name.is_some());
+        if !has_module_name && out.project.name.is_empty() {
+            let Some(package) = cargo_package.filter(|package| !package.is_empty()) else {
+                bail!(
+                    "Cannot determine the module name from {}: none of `tool.maturin.module-name` and `project.name` \
+                     is given, and `CARGO_PKG_NAME` is not set",
+                    path.display()
+                );
+            };
+            out.project.name = package.replace('-', "_");
+        }
         Ok(out)
     }
 
@@ -46,24 +85,66 @@ impl PyProject {
     }
 
     /// Return `tool.maturin.python_source` if it exists, which means the project is a mixed Rust/Python project.
+    ///
+    /// A relative path is resolved against the directory of `pyproject.toml` instead of the current directory,
+    /// with `.` and `..` components folded.
     pub fn python_source(&self) -> Option<PathBuf> {
-        if let Some(tool) = &self.tool {
-            if let Some(maturin) = &tool.maturin {
-                if let Some(python_source) = &maturin.python_source {
-                    if let Some(base) = self.toml_path.parent() {
-                        return Some(base.join(python_source));
-                    } else {
-                        return Some(PathBuf::from(python_source));
-                    }
+        let python_source = self
+            .tool
+            .as_ref()?
+            .maturin
+            .as_ref()?
+            .python_source
+            .as_ref()?;
+        let path = match self.toml_path.parent() {
+            Some(base) => base.join(python_source),
+            None => PathBuf::from(python_source),
+        };
+        Some(normalize(&path))
+    }
+
+    /// Directory of `pyproject.toml`, where the stubs are written unless `python-source` is given
+    pub fn project_root(&self) -> PathBuf {
+        self.toml_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+
+    /// Return `tool.pyo3-stub-gen` if it exists.
+    pub fn stub_gen(&self) -> Option<&StubGen> {
+        self.tool.as_ref()?.pyo3_stub_gen.as_ref()
+    }
+
+    /// Return `tool.pyo3-stub-gen.filter` if it exists.
+    pub fn filter(&self) -> Option<&Filter> {
+        self.stub_gen()?.filter.as_ref()
+    }
+}
+
+/// `path` with `.` and `..` components folded without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
                 }
-            }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(component),
+            },
+            _ => normalized.push(component),
         }
-        None
     }
+    normalized
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Project {
+    /// Empty if absent, in which case [PyProject::parse_toml] fills it with the Cargo package name
+    #[serde(default)]
     pub name: String,
     #[serde(rename = "requires-python")]
     pub requires_python: Option<String>,
@@ -72,6 +153,8 @@ pub struct Project {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tool {
     pub maturin: Option<Maturin>,
+    #[serde(rename = "pyo3-stub-gen")]
+    pub pyo3_stub_gen: Option<StubGen>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,3 +164,211 @@ pub struct Maturin {
     #[serde(rename = "module-name")]
     pub module_name: Option<String>,
 }
+
+/// `[tool.pyo3-stub-gen]` table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct StubGen {
+    /// Emit `__all__` in generated modules. Defaults to `true`.
+    #[serde(rename = "generate-all", alias = "generate_all")]
+    pub generate_all: Option<bool>,
+    /// How the `# Arguments` section of doc comments is rendered. Defaults to `"verbatim"`.
+    #[serde(rename = "docstring-style", alias = "docstring_style")]
+    pub docstring_style: Option<DocstringStyle>,
+    /// Append an `Attributes:` section listing the attributes and properties to class docstrings. Defaults to `false`.
+    #[serde(
+        rename = "synthesize-attributes-section",
+        alias = "synthesize_attributes_section"
+    )]
+    pub synthesize_attributes_section: Option<bool>,
+    /// How list, dict, and set defaults of parameters are rendered. Defaults to `"literal"`.
+    #[serde(rename = "container-default", alias = "container_default")]
+    pub container_default: Option<ContainerDefaultStyle>,
+    /// How constructors of `#[new]` are rendered, `"new"`, `"init"`, or `"both"`. Defaults to `"new"`.
+    #[serde(rename = "constructor-style", alias = "constructor_style")]
+    pub constructor_style: Option<ConstructorStyle>,
+    /// Annotate `cls` of classmethods and `__new__` as `type[Self]`, as strict checkers expect. Defaults to `false`.
+    #[serde(rename = "annotate-cls", alias = "annotate_cls")]
+    pub annotate_cls: Option<bool>,
+    /// Annotate sets taken as arguments as `typing.AbstractSet` instead of `set`, accepting a `frozenset` too. Defaults to `false`.
+    #[serde(rename = "abstract-set-input", alias = "abstract_set_input")]
+    pub abstract_set_input: Option<bool>,
+    /// Annotate parameters of `#[gen_stub(deprecated_param(...))]` as `typing.Annotated[T, typing_extensions.deprecated("...")]`
+    /// in addition to the docstring line. Defaults to `false`.
+    #[serde(
+        rename = "annotate-deprecated-parameters",
+        alias = "annotate_deprecated_parameters"
+    )]
+    pub annotate_deprecated_parameters: Option<bool>,
+    /// Annotate complex enums by the union of their variants in place, instead of the alias `{Name}Variant`. Defaults to `false`.
+    #[serde(
+        rename = "inline-complex-enum-unions",
+        alias = "inline_complex_enum_unions"
+    )]
+    pub inline_complex_enum_unions: Option<bool>,
+    /// Oldest Python version the stubs are written for, e.g. `"3.8"` for `typing.Optional[...]` instead of `... | None`.
+    /// Defaults to the minimum version of `requires-python`.
+    #[serde(rename = "target-version", alias = "target_version")]
+    pub target_version: Option<TargetVersion>,
+    /// Modules and symbols to be excluded from stub files
+    pub filter: Option<Filter>,
+    /// New names of modules, e.g. `"rust_core" = "mypkg._native"`, which also rename their submodules
+    #[serde(rename = "module-rename", alias = "module_rename")]
+    pub module_rename: Option<BTreeMap<String, String>>,
+    /// Prefix stripped from the names of classes and enums without `#[pyclass(name = "...")]`,
+    /// e.g. `"Py"` to render `PyFoo` as `Foo`
+    #[serde(rename = "strip-class-prefix", alias = "strip_class_prefix")]
+    pub strip_class_prefix: Option<String>,
+    /// How parameters named after a hard Python keyword, e.g. `r#in`, are handled. Defaults to `"error"`.
+    #[serde(rename = "keyword-parameters", alias = "keyword_parameters")]
+    pub keyword_parameters: Option<KeywordParameters>,
+    /// How references to classes defined later in the same module are handled. Defaults to `"keep"`.
+    #[serde(rename = "forward-references", alias = "forward_references")]
+    pub forward_references: Option<ForwardReferences>,
+    /// Whether methods overriding a method of a generated base class are decorated by `@typing.override`
+    #[serde(rename = "emit-override-decorator", alias = "emit_override_decorator")]
+    pub emit_override_decorator: Option<bool>,
+    /// How references to names missing from the generated modules are reported
+    #[serde(rename = "unresolved-references", alias = "unresolved_references")]
+    pub unresolved_references: Option<UnresolvedReferences>,
+    /// Paths of the stub files. Defaults to `"module"`.
+    #[serde(rename = "stub-layout", alias = "stub_layout")]
+    pub stub_layout: Option<StubLayout>,
+    /// Modules whose stubs end with `def __getattr__(name: builtins.str) -> typing.Any: ...`
+    /// for the attributes created at runtime, by their names in the generated stubs
+    #[serde(rename = "attr-fallback", alias = "attr_fallback")]
+    pub attr_fallback: Option<Vec<String>>,
+    /// Modules marked as partial by a `# partial` comment, by their names in the generated stubs.
+    /// `py.typed` of their packages is written as `partial` following PEP 561.
+    pub partial: Option<Vec<String>>,
+    /// Namespace packages of PEP 420, whose `__init__.pyi` is not written even if they have submodules,
+    /// by their names in the generated stubs
+    #[serde(rename = "namespace-packages", alias = "namespace_packages")]
+    pub namespace_packages: Option<Vec<String>>,
+    /// Modules re-exporting all the public names of other modules by `from ... import *`,
+    /// e.g. `"mypkg" = ["mypkg._core"]`, by their names in the generated stubs
+    #[serde(rename = "reexport-all", alias = "reexport_all")]
+    pub reexport_all: Option<BTreeMap<String, Vec<String>>>,
+    /// Replace only the `# BEGIN pyo3-stub-gen` / `# END pyo3-stub-gen` block of existing stub files.
+    /// Defaults to `false`.
+    pub merge: Option<bool>,
+    /// Comment lines replacing the `# This file is automatically generated by pyo3_stub_gen` banner,
+    /// e.g. `"SPDX-License-Identifier: MIT"`, or `""` to remove the banner
+    pub header: Option<String>,
+    /// Cargo features whose items tagged by `#[gen_stub(feature = "...")]` are included, e.g. `["cuda"]`.
+    /// All the items are included if not set. Overridden by the environment variable `PYO3_STUB_GEN_TAGS`.
+    pub tags: Option<Vec<String>>,
+}
+
+/// `[tool.pyo3-stub-gen.filter]` table
+///
+/// Each entry is a glob matched against dotted names, i.e. module names like `mypkg.sub`
+/// or qualified names of symbols like `mypkg.sub.Class.method`.
+/// `*` and `?` match within a single component, and `**` matches any number of components.
+/// A name matching an `include-*` pattern is kept even if it also matches an `exclude-*` pattern.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct Filter {
+    pub exclude_modules: Vec<String>,
+    pub include_modules: Vec<String>,
+    pub exclude_symbols: Vec<String>,
+    pub include_symbols: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str, cargo_package: Option<&str>) -> Result<PyProject> {
+        PyProject::from_toml_str(
+            content,
+            Path::new("/work/pkg/pyproject.toml"),
+            cargo_package,
+        )
+    }
+
+    #[test]
+    fn missing_name() {
+        let pyproject = parse(
+            r#"
+            [project]
+            requires-python = ">=3.9"
+            "#,
+            Some("my-package"),
+        )
+        .unwrap();
+        assert_eq!(pyproject.module_name(), "my_package");
+    }
+
+    #[test]
+    fn missing_tool_table() {
+        let pyproject = parse(
+            r#"
+            [project]
+            name = "pkg"
+            "#,
+            Some("my-package"),
+        )
+        .unwrap();
+        assert_eq!(pyproject.module_name(), "pkg");
+        assert_eq!(pyproject.python_source(), None);
+        assert_eq!(pyproject.stub_gen(), None);
+    }
+
+    #[test]
+    fn snake_case_generate_all() {
+        let pyproject = parse(
+            r#"
+            [project]
+            name = "pkg"
+
+            [tool.pyo3-stub-gen]
+            generate_all = false
+            "#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(pyproject.stub_gen().unwrap().generate_all, Some(false));
+    }
+
+    #[test]
+    fn module_name_without_project_table() {
+        let pyproject = parse(
+            r#"
+            [tool.maturin]
+            module-name = "pkg._core"
+            "#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(pyproject.module_name(), "pkg._core");
+    }
+
+    #[test]
+    fn undeterminable_name() {
+        let err = parse("", None).unwrap_err().to_string();
+        assert!(err.contains("`tool.maturin.module-name`"), "{err}");
+        assert!(err.contains("`project.name`"), "{err}");
+        assert!(err.contains("`CARGO_PKG_NAME`"), "{err}");
+    }
+
+    #[test]
+    fn relative_python_source() {
+        let pyproject = parse(
+            r#"
+            [project]
+            name = "pkg"
+
+            [tool.maturin]
+            python-source = "../python/./src"
+            "#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            pyproject.python_source(),
+            Some(PathBuf::from("/work/python/src"))
+        );
+    }
+}
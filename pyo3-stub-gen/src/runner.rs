@@ -0,0 +1,243 @@
+//! Stub generation from a built extension module, without a `stub_gen` executable.
+//!
+//! The library crate exports the entry point by [define_stub_info_gatherer](crate::define_stub_info_gatherer)
+//! with `export_entry`, and [run_from_cdylib] loads the built library and calls it:
+//!
+//! ```rust:ignore
+//! // src/lib.rs of the PyO3 crate
+//! pyo3_stub_gen::define_stub_info_gatherer!(stub_info, export_entry);
+//!
+//! // a runner, e.g. an xtask, with the `runner` feature
+//! pyo3_stub_gen::runner::run_from_cdylib("target/debug/libmy_crate.so")?;
+//! ```
+//!
+//! Since the library is not linked to the runner, the features of the runner are not unified with those of the library,
+//! which a `stub_gen` executable in the same workspace suffers from.
+//! The library is loaded into a Python interpreter, which provides the symbols of libpython
+//! an extension module built with `pyo3/extension-module` is not linked to.
+//! With [emit_rerun_and_marker](crate::build_helper::emit_rerun_and_marker) in `build.rs`, the generation is skipped
+//! while the library is built from the same inputs as the stubs last generated, and the stubs still exist.
+
+use crate::StubInfo;
+use anyhow::Result;
+use std::{fmt::Write as _, os::raw::c_int};
+
+/// Symbol of the entry point generating the stubs, which returns `0` on success
+pub const ENTRY_SYMBOL: &str = "_pyo3_stub_gen_entry";
+/// Symbol returning the marker of [build_helper](crate::build_helper), or `0` if the library is built without it
+pub const MARKER_SYMBOL: &str = "_pyo3_stub_gen_marker";
+/// Environment variable naming the file the entry point lists the generated stub files in
+pub const OUTPUTS_ENV: &str = "PYO3_STUB_GEN_OUTPUTS";
+
+/// Body of the entry point, which generates the stubs by `stub_info` and reports an error to stderr.
+#[doc(hidden)]
+pub fn entry(stub_info: fn() -> Result<StubInfo>) -> c_int {
+    // A panic must not unwind across the `extern "C"` entry point
+    match std::panic::catch_unwind(|| generate(stub_info)) {
+        Ok(Ok(())) => 0,
+        Ok(Err(err)) => {
+            eprintln!("Error: {err:#}");
+            1
+        }
+        Err(_) => 2,
+    }
+}
+
+fn generate(stub_info: fn() -> Result<StubInfo>) -> Result<()> {
+    let report = stub_info()?.generate_with_report()?;
+    if let Some(outputs) = std::env::var_os(OUTPUTS_ENV) {
+        let mut list = String::new();
+        for path in report.written.iter().chain(&report.unchanged) {
+            writeln!(list, "{}", path.display())?;
+        }
+        std::fs::write(outputs, list)?;
+    }
+    Ok(())
+}
+
+/// Body of the marker symbol, parsing the marker given to the compilation of the library.
+#[doc(hidden)]
+pub fn marker(env: Option<&str>) -> u64 {
+    env.and_then(|marker| u64::from_str_radix(marker, 16).ok())
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "runner")]
+pub use cdylib::{run_from_cdylib, PYTHON_ENV};
+
+#[cfg(feature = "runner")]
+mod cdylib {
+    use super::*;
+    use anyhow::{bail, Context};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        process::Command,
+    };
+
+    /// Environment variable of the Python interpreter to load the library into, `python3` by default
+    pub const PYTHON_ENV: &str = "PYO3_PYTHON";
+
+    /// Loads the library given as the first argument, and calls the entry point,
+    /// or prints whether the entry point exists and the marker when the second argument is `probe`
+    const LOADER: &str = r#"
+import ctypes, sys
+library = ctypes.PyDLL(sys.argv[1])
+if sys.argv[2] == "probe":
+    marker = getattr(library, sys.argv[4], None)
+    if marker is not None:
+        marker.restype = ctypes.c_uint64
+    print(int(hasattr(library, sys.argv[3])), marker() if marker is not None else 0)
+else:
+    sys.exit(getattr(library, sys.argv[3])())
+"#;
+
+    /// Generate the stubs by the entry point of the built library at `path`.
+    ///
+    /// The library is loaded into the Python interpreter of [PYTHON_ENV] in a subprocess,
+    /// which must be the one the library is built for.
+    /// The marker of the library and the generated stub files are recorded next to it,
+    /// and the generation is skipped if the marker is unchanged and the stub files exist.
+    pub fn run_from_cdylib(path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let output = loader(path, "probe")
+            .output()
+            .with_context(|| format!("Failed to run {}", python()))?;
+        let probe = String::from_utf8_lossy(&output.stdout);
+        let (has_entry, marker) = match probe.split_once(' ') {
+            Some((has_entry, marker)) if output.status.success() => (
+                has_entry == "1",
+                marker
+                    .trim()
+                    .parse::<u64>()
+                    .with_context(|| format!("Unexpected marker `{}`", marker.trim()))?,
+            ),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+                .with_context(|| format!("Failed to load {}", path.display()))
+            }
+        };
+        if !has_entry {
+            bail!(
+                "{} does not export `{ENTRY_SYMBOL}`; use `define_stub_info_gatherer!(..., export_entry)`",
+                path.display()
+            );
+        }
+
+        let record = marker_path(path);
+        if is_up_to_date(&record, marker) {
+            log::info!("Stubs of {} are up to date", path.display());
+            return Ok(());
+        }
+        let outputs = outputs_path(path);
+        let status = loader(path, "entry")
+            .env(OUTPUTS_ENV, &outputs)
+            .status()
+            .with_context(|| format!("Failed to run {}", python()))?;
+        if !status.success() {
+            bail!("Stub generation by {} failed with {status}", path.display());
+        }
+        let generated = fs::read_to_string(&outputs)
+            .with_context(|| format!("Failed to read {}", outputs.display()))?;
+        fs::remove_file(&outputs)?;
+        if marker != 0 {
+            fs::write(&record, format!("{marker:016x}\n{generated}"))
+                .with_context(|| format!("Failed to write {}", record.display()))?;
+        }
+        Ok(())
+    }
+
+    fn python() -> String {
+        std::env::var(PYTHON_ENV).unwrap_or_else(|_| "python3".to_string())
+    }
+
+    fn loader(path: &Path, mode: &str) -> Command {
+        let mut command = Command::new(python());
+        command
+            .arg("-c")
+            .arg(LOADER)
+            .arg(path)
+            .arg(mode)
+            .arg(ENTRY_SYMBOL)
+            .arg(MARKER_SYMBOL);
+        command
+    }
+
+    /// File recording the marker of the library at `path` and the stub files generated by it
+    fn marker_path(path: &Path) -> PathBuf {
+        with_suffix(path, ".pyo3-stub-gen")
+    }
+
+    /// File the entry point lists the generated stub files in
+    fn outputs_path(path: &Path) -> PathBuf {
+        with_suffix(path, ".pyo3-stub-gen-outputs")
+    }
+
+    fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        path.with_file_name(name)
+    }
+
+    fn is_up_to_date(record: &Path, marker: u64) -> bool {
+        let Ok(recorded) = fs::read_to_string(record) else {
+            return false;
+        };
+        let mut lines = recorded.lines();
+        marker != 0
+            && lines.next() == Some(format!("{marker:016x}").as_str())
+            && lines.all(|output| Path::new(output).exists())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn marker_record() -> Result<()> {
+            let dir =
+                std::env::temp_dir().join(format!("pyo3-stub-gen-runner-{}", std::process::id()));
+            fs::create_dir_all(&dir)?;
+            let record = marker_path(&dir.join("libexample.so"));
+            assert_eq!(record, dir.join("libexample.so.pyo3-stub-gen"));
+
+            assert!(!is_up_to_date(&record, 0x2a));
+            let stub = dir.join("example.pyi");
+            fs::write(&stub, "")?;
+            fs::write(&record, format!("{:016x}\n{}\n", 0x2a, stub.display()))?;
+            assert!(is_up_to_date(&record, 0x2a));
+            assert!(!is_up_to_date(&record, 0x2b));
+            // A deleted stub file is generated again
+            fs::remove_file(&stub)?;
+            assert!(!is_up_to_date(&record, 0x2a));
+            // A library built without the marker is always run
+            fs::write(&record, format!("{:016x}\n", 0))?;
+            assert!(!is_up_to_date(&record, 0));
+
+            fs::remove_dir_all(&dir)?;
+            Ok(())
+        }
+
+        #[test]
+        fn missing_library() {
+            let err = run_from_cdylib("/nonexistent/libexample.so").unwrap_err();
+            assert_eq!(err.to_string(), "Failed to load /nonexistent/libexample.so");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    crate::define_stub_info_gatherer!(stub_info, export_entry);
+
+    #[test]
+    fn exported_marker() {
+        assert_eq!(_pyo3_stub_gen_marker(), 0);
+        assert_eq!(super::marker(Some("00000000deadbeef")), 0xdeadbeef);
+        assert_eq!(super::marker(Some("not hex")), 0);
+        let _ = stub_info;
+    }
+}
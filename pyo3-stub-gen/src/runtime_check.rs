@@ -0,0 +1,544 @@
+//! Comparison of the stubs with the modules imported at runtime, a lightweight `stubtest`.
+//!
+//! [StubInfo::verify_against_runtime] imports every module of the [StubInfo] and reports
+//! the public names defined only at runtime or only in the stubs,
+//! and the functions and methods whose parameters differ from those seen by `inspect.signature`.
+//! PyO3 sets `__text_signature__` of functions and methods, so that most of them can be compared.
+//!
+//! The extension module has to be importable, e.g. by a test binary registering it with `pyo3::append_to_inittab!`:
+//!
+//! ```no_run
+//! # use pyo3::prelude::*;
+//! # #[pymodule]
+//! # fn my_module(_m: &Bound<PyModule>) -> PyResult<()> { Ok(()) }
+//! # fn stub_info() -> pyo3_stub_gen::Result<pyo3_stub_gen::StubInfo> { unimplemented!() }
+//! #[test]
+//! fn stubs_match_runtime() {
+//!     pyo3::append_to_inittab!(my_module);
+//!     pyo3::Python::initialize();
+//!     let stub_info = stub_info().unwrap();
+//!     let mismatches = pyo3::Python::attach(|py| stub_info.verify_against_runtime(py));
+//!     assert!(mismatches.is_empty(), "{mismatches:#?}");
+//! }
+//! ```
+
+use crate::{generate::*, StubInfo};
+use pyo3::{prelude::*, types::PyModule};
+use std::{collections::BTreeMap, fmt};
+
+/// Kind of a [Mismatch]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// The module cannot be imported
+    Unimportable,
+    /// Defined at runtime but not in the stub
+    MissingFromStub,
+    /// Defined in the stub but not at runtime
+    MissingAtRuntime,
+    /// The function or method takes other parameters at runtime than in the stub
+    Arity,
+}
+
+/// Difference between a stub and the module imported at runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Full name of the module, e.g. `my_package._core`
+    pub module: String,
+    /// Name of the item in the module, e.g. `MyClass.method`, or empty for the module itself
+    pub name: String,
+    pub kind: MismatchKind,
+    /// Human-readable description of the difference
+    pub description: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "{}: {}", self.module, self.description)
+        } else {
+            write!(f, "{}.{}: {}", self.module, self.name, self.description)
+        }
+    }
+}
+
+impl StubInfo {
+    /// Import every module of the stubs and report how they differ from the stubs.
+    ///
+    /// Names starting with `_` are only compared in the stub-to-runtime direction,
+    /// and the names of a module with a `__getattr__` fallback or marked as partial are not required in the stub.
+    /// Overloaded functions and constructors are not compared by their parameters.
+    pub fn verify_against_runtime(&self, py: Python<'_>) -> Vec<Mismatch> {
+        let mut checker = Checker::default();
+        for (name, module) in &self.modules {
+            checker.module = name.clone();
+            checker.check_module(py, module);
+        }
+        checker.mismatches
+    }
+}
+
+/// Item defined in a stub, as compared with the runtime
+enum StubItem<'a> {
+    Function(&'a [FunctionDef]),
+    Class(&'a ClassDef),
+    Enum(&'a EnumDef),
+    Exception,
+    Variable,
+    Submodule,
+}
+
+impl StubItem<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Function(_) => "function",
+            Self::Class(_) | Self::Enum(_) => "class",
+            Self::Exception => "exception",
+            Self::Variable => "variable",
+            Self::Submodule => "submodule",
+        }
+    }
+}
+
+/// Member of a class defined in a stub, as compared with the runtime
+enum StubMember<'a> {
+    Method(&'a [MethodDef]),
+    Attribute,
+}
+
+/// Numbers of the parameters of a function, with the receiver excluded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Arity {
+    positional: usize,
+    keyword_only: usize,
+    varargs: bool,
+    varkw: bool,
+}
+
+impl From<&Parameters> for Arity {
+    fn from(parameters: &Parameters) -> Self {
+        Self {
+            positional: parameters.positional_only.len() + parameters.positional_or_keyword.len(),
+            keyword_only: parameters.keyword_only.len(),
+            varargs: parameters.varargs.is_some(),
+            varkw: parameters.varkw.is_some(),
+        }
+    }
+}
+
+impl Arity {
+    /// Arity given by `inspect.signature(obj)`, unless the signature is unavailable,
+    /// excluding the first positional parameter if `skip_receiver`
+    fn of_runtime(obj: &Bound<PyAny>, skip_receiver: bool) -> Option<Self> {
+        let inspect = obj.py().import("inspect").ok()?;
+        let signature = inspect.call_method1("signature", (obj,)).ok()?;
+        let mut arity = Arity {
+            positional: 0,
+            keyword_only: 0,
+            varargs: false,
+            varkw: false,
+        };
+        let parameters = signature
+            .getattr("parameters")
+            .ok()?
+            .call_method0("values")
+            .ok()?;
+        for parameter in parameters.try_iter().ok()? {
+            let kind: String = parameter
+                .ok()?
+                .getattr("kind")
+                .and_then(|kind| kind.getattr("name"))
+                .and_then(|name| name.extract())
+                .ok()?;
+            match kind.as_str() {
+                "POSITIONAL_ONLY" | "POSITIONAL_OR_KEYWORD" => arity.positional += 1,
+                "KEYWORD_ONLY" => arity.keyword_only += 1,
+                "VAR_POSITIONAL" => arity.varargs = true,
+                "VAR_KEYWORD" => arity.varkw = true,
+                _ => return None,
+            }
+        }
+        if skip_receiver {
+            arity.positional = arity.positional.checked_sub(1)?;
+        }
+        Some(arity)
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} positional and {} keyword-only parameter(s)",
+            self.positional, self.keyword_only
+        )?;
+        if self.varargs {
+            write!(f, ", *args")?;
+        }
+        if self.varkw {
+            write!(f, ", **kwargs")?;
+        }
+        Ok(())
+    }
+}
+
+/// Import the module `name`, or look it up as an attribute of its parent,
+/// since a submodule added by `PyModule::add_submodule` cannot be imported by its full name
+fn import<'py>(py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    let err = match py.import(name) {
+        Ok(module) => return Ok(module.into_any()),
+        Err(err) => err,
+    };
+    let Some((parent, child)) = name.rsplit_once('.') else {
+        return Err(err);
+    };
+    import(py, parent)?
+        .getattr(child)
+        .ok()
+        .filter(|module| module.is_instance_of::<PyModule>())
+        .ok_or(err)
+}
+
+/// Whether `class` only exists in the stub, e.g. a `typing.TypedDict` of a Rust struct
+fn is_stub_only(class: &ClassDef) -> bool {
+    class
+        .bases
+        .iter()
+        .any(|base| base.name == "typing.TypedDict" || base.name == "typing.NamedTuple")
+}
+
+/// Names of the iterable `names` not starting with `_`, or none if they cannot be listed
+fn public_names(names: PyResult<Bound<PyAny>>) -> Vec<String> {
+    let Ok(names) = names.and_then(|names| names.try_iter()) else {
+        return Vec::new();
+    };
+    names
+        .filter_map(|name| name.and_then(|name| name.extract::<String>()).ok())
+        .filter(|name| !name.starts_with('_'))
+        .collect()
+}
+
+#[derive(Default)]
+struct Checker {
+    /// Module being checked
+    module: String,
+    mismatches: Vec<Mismatch>,
+}
+
+impl Checker {
+    fn report(&mut self, name: impl Into<String>, kind: MismatchKind, description: String) {
+        self.mismatches.push(Mismatch {
+            module: self.module.clone(),
+            name: name.into(),
+            kind,
+            description,
+        });
+    }
+
+    fn check_module(&mut self, py: Python<'_>, module: &Module) {
+        let runtime = match import(py, &module.name) {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                self.report(
+                    "",
+                    MismatchKind::Unimportable,
+                    format!("cannot be imported: {err}"),
+                );
+                return;
+            }
+        };
+        let mut items = BTreeMap::new();
+        for (name, overloads) in &module.function {
+            items.insert(name.to_string(), StubItem::Function(overloads));
+        }
+        for class in module.class.values().filter(|class| !is_stub_only(class)) {
+            items.insert(class.name.to_string(), StubItem::Class(class));
+        }
+        for enum_ in module.enum_.values() {
+            items.insert(enum_.name.to_string(), StubItem::Enum(enum_));
+        }
+        for name in module.exceptions.keys() {
+            items.insert(name.to_string(), StubItem::Exception);
+        }
+        for name in module.variables.keys() {
+            items.insert(name.to_string(), StubItem::Variable);
+        }
+        for name in &module.submodules {
+            items.insert(name.clone(), StubItem::Submodule);
+        }
+
+        if !module.attr_fallback && !module.partial {
+            for name in public_names(runtime.dir().map(Bound::into_any)) {
+                if !items.contains_key(&name) && !module.synthesized.contains_key(&name) {
+                    self.report(
+                        name,
+                        MismatchKind::MissingFromStub,
+                        "is defined at runtime but not in the stub".to_string(),
+                    );
+                }
+            }
+        }
+        for (name, item) in &items {
+            let Ok(obj) = runtime.getattr(name.as_str()) else {
+                self.report(
+                    name,
+                    MismatchKind::MissingAtRuntime,
+                    format!("{} is in the stub but not defined at runtime", item.kind()),
+                );
+                continue;
+            };
+            match item {
+                StubItem::Function([function]) => {
+                    self.check_arity(name, &obj, Arity::from(&function.parameters), false)
+                }
+                StubItem::Class(class) => self.check_class(name, &obj, class),
+                StubItem::Enum(enum_) => self.check_enum(name, &obj, enum_),
+                StubItem::Submodule if !obj.is_instance_of::<PyModule>() => self.report(
+                    name,
+                    MismatchKind::MissingAtRuntime,
+                    "submodule is in the stub but not a module at runtime".to_string(),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_class(&mut self, name: &str, cls: &Bound<PyAny>, class: &ClassDef) {
+        let mut members = BTreeMap::new();
+        for (method, overloads) in &class.methods {
+            members.insert(method.as_str(), StubMember::Method(overloads));
+        }
+        for attr in class
+            .attrs
+            .iter()
+            .chain(class.flag.iter().flat_map(|flag| &flag.members))
+            .chain(
+                class
+                    .dataclass
+                    .iter()
+                    .flat_map(|dataclass| &dataclass.fields),
+            )
+        {
+            members.insert(attr.name, StubMember::Attribute);
+        }
+        for property in class.getter_setters.keys() {
+            members.insert(property.as_str(), StubMember::Attribute);
+        }
+        for nested in &class.classes {
+            members.insert(nested.name.as_ref(), StubMember::Attribute);
+        }
+        self.check_members(name, cls, members);
+    }
+
+    fn check_enum(&mut self, name: &str, cls: &Bound<PyAny>, enum_: &EnumDef) {
+        let mut members = BTreeMap::new();
+        for method in &enum_.methods {
+            members.insert(
+                method.name.as_ref(),
+                StubMember::Method(std::slice::from_ref(method)),
+            );
+        }
+        for attr in enum_
+            .attrs
+            .iter()
+            .chain(&enum_.getters)
+            .chain(&enum_.setters)
+        {
+            members.insert(attr.name, StubMember::Attribute);
+        }
+        for (variant, _) in enum_.variants {
+            members.insert(variant, StubMember::Attribute);
+        }
+        self.check_members(name, cls, members);
+    }
+
+    fn check_members(
+        &mut self,
+        class_name: &str,
+        cls: &Bound<PyAny>,
+        members: BTreeMap<&str, StubMember>,
+    ) {
+        for name in public_names(
+            cls.getattr("__dict__")
+                .and_then(|dict| dict.call_method0("keys")),
+        ) {
+            if !members.contains_key(name.as_str()) {
+                self.report(
+                    format!("{class_name}.{name}"),
+                    MismatchKind::MissingFromStub,
+                    "is defined at runtime but not in the stub".to_string(),
+                );
+            }
+        }
+        for (name, member) in members {
+            let qualname = format!("{class_name}.{name}");
+            let Ok(obj) = cls.getattr(name) else {
+                self.report(
+                    qualname,
+                    MismatchKind::MissingAtRuntime,
+                    "member is in the stub but not defined at runtime".to_string(),
+                );
+                continue;
+            };
+            if let StubMember::Method([method]) = member {
+                match method.r#type {
+                    // The signature of the class stands for the constructor
+                    MethodType::New => {}
+                    // Looked up on the class, an instance method still takes `self`
+                    MethodType::Instance => {
+                        self.check_arity(&qualname, &obj, Arity::from(&method.parameters), true)
+                    }
+                    MethodType::Static | MethodType::Class => {
+                        self.check_arity(&qualname, &obj, Arity::from(&method.parameters), false)
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_arity(&mut self, name: &str, obj: &Bound<PyAny>, stub: Arity, skip_receiver: bool) {
+        let Some(runtime) = Arity::of_runtime(obj, skip_receiver) else {
+            return;
+        };
+        if runtime != stub {
+            self.report(
+                name,
+                MismatchKind::Arity,
+                format!("takes {runtime} at runtime, but {stub} in the stub"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{type_info::ParameterKind, TypeInfo};
+    use std::any::TypeId;
+
+    const SOURCE: &std::ffi::CStr = cr#"
+def add(a, b, /):
+    return a + b
+
+def scale(x, *, factor=1):
+    return x * factor
+
+def untracked():
+    pass
+
+class Point:
+    def norm(self):
+        return 0
+
+    @staticmethod
+    def origin():
+        return Point()
+
+    def extra(self):
+        pass
+
+    @property
+    def x(self):
+        return 0
+
+VERSION = "1.0"
+"#;
+
+    fn function(name: &'static str, positional: usize) -> FunctionDef {
+        let mut parameters = Parameters::new();
+        parameters.positional_or_keyword = (0..positional)
+            .map(|_| Parameter::new("p", ParameterKind::PositionalOrKeyword, TypeInfo::any()))
+            .collect();
+        FunctionDef::new(name, parameters, TypeInfo::none())
+    }
+
+    fn stub_info() -> StubInfo {
+        let mut module = Module::new("runtime_check_sample", "runtime_check_sample");
+        module
+            .function
+            .insert("add".into(), vec![function("add", 2)]);
+        // `factor` is keyword-only at runtime
+        module
+            .function
+            .insert("scale".into(), vec![function("scale", 2)]);
+        module
+            .function
+            .insert("removed".into(), vec![function("removed", 0)]);
+        let mut point = ClassDef::new("Point");
+        point.add_method(MethodDef::new(
+            "norm",
+            MethodType::Instance,
+            Parameters::new(),
+            TypeInfo::builtin("float"),
+        ));
+        point.add_method(MethodDef::new(
+            "origin",
+            MethodType::Static,
+            Parameters::new(),
+            TypeInfo::self_type(),
+        ));
+        point.getter_setters.insert(
+            "x".to_string(),
+            (Some(MemberDef::new("x", TypeInfo::builtin("int"))), None),
+        );
+        point
+            .attrs
+            .push(MemberDef::new("ORIGIN", TypeInfo::self_type()));
+        module.class.insert(TypeId::of::<u8>(), point);
+        module.variables.insert(
+            "VERSION",
+            VariableDef::new("VERSION", TypeInfo::builtin("str")),
+        );
+        StubInfo {
+            modules: BTreeMap::from([("runtime_check_sample".to_string(), module)]),
+            python_root: Default::default(),
+            layout: Default::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn mismatches() {
+        Python::initialize();
+        let mismatches = Python::attach(|py| {
+            PyModule::from_code(
+                py,
+                SOURCE,
+                c"runtime_check_sample.py",
+                c"runtime_check_sample",
+            )
+            .unwrap();
+            stub_info().verify_against_runtime(py)
+        });
+        let mismatches: Vec<_> = mismatches.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            mismatches,
+            [
+                "runtime_check_sample.untracked: is defined at runtime but not in the stub",
+                "runtime_check_sample.Point.extra: is defined at runtime but not in the stub",
+                "runtime_check_sample.Point.ORIGIN: member is in the stub but not defined at runtime",
+                "runtime_check_sample.removed: function is in the stub but not defined at runtime",
+                "runtime_check_sample.scale: takes 1 positional and 1 keyword-only parameter(s) at runtime, \
+                but 2 positional and 0 keyword-only parameter(s) in the stub",
+            ]
+        );
+    }
+
+    #[test]
+    fn unimportable_module() {
+        Python::initialize();
+        let mut info = stub_info();
+        let module = info.modules.remove("runtime_check_sample").unwrap();
+        info.modules.insert(
+            "no_such_module".to_string(),
+            Module {
+                name: "no_such_module".to_string(),
+                ..module
+            },
+        );
+        let mismatches = Python::attach(|py| info.verify_against_runtime(py));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].kind, MismatchKind::Unimportable);
+        assert_eq!(mismatches[0].name, "");
+    }
+}
@@ -11,9 +11,21 @@ mod either;
 #[cfg(feature = "rust_decimal")]
 mod rust_decimal;
 
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "smallvec")]
+mod smallvec;
+
+#[cfg(feature = "bytes")]
+mod bytes;
+
+use crate::config::Config;
 use maplit::hashset;
 use std::cmp::Ordering;
-use std::sync::atomic::{AtomicU8, Ordering as AtomicOrdering};
 use std::{collections::HashSet, fmt, ops};
 
 /// Indicates what to import.
@@ -27,29 +39,51 @@ pub enum ImportRef {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum SelfImportStrategy {
     Typing = 0,
     TypingExtensions = 1,
 }
 
-static SELF_IMPORT_STRATEGY: AtomicU8 = AtomicU8::new(SelfImportStrategy::Typing as u8);
-
-fn decode_self_import_strategy(value: u8) -> SelfImportStrategy {
-    match value {
-        0 => SelfImportStrategy::Typing,
-        1 => SelfImportStrategy::TypingExtensions,
-        _ => SelfImportStrategy::Typing,
-    }
-}
-
 /// Set the strategy used for importing `Self` annotations in generated stubs.
 pub fn set_self_import_strategy(strategy: SelfImportStrategy) {
-    SELF_IMPORT_STRATEGY.store(strategy as u8, AtomicOrdering::Relaxed);
+    Config::update(|config| config.self_import_strategy = strategy);
 }
 
 /// Retrieve the currently configured `Self` import strategy.
 pub fn self_import_strategy() -> SelfImportStrategy {
-    decode_self_import_strategy(SELF_IMPORT_STRATEGY.load(AtomicOrdering::Relaxed))
+    Config::current().self_import_strategy
+}
+
+/// Set whether sets taken as arguments, e.g. `HashSet<T>`, are annotated as `typing.AbstractSet[T]` instead of `set[T]`,
+/// as maps and sequences are annotated as `typing.Mapping` and `typing.Sequence`.
+///
+/// This is set from `tool.pyo3-stub-gen.abstract-set-input` in `pyproject.toml`.
+pub fn set_abstract_set_input(enabled: bool) {
+    Config::update(|config| config.abstract_set_input = enabled);
+}
+
+/// Retrieve whether sets taken as arguments are annotated as `typing.AbstractSet[T]`.
+pub fn abstract_set_input() -> bool {
+    Config::current().abstract_set_input
+}
+
+/// Set whether complex enums are annotated by the union of their variants written out in place,
+/// instead of the alias `{Name}Variant` rendered with the enum, as the stubs were before the alias was introduced.
+///
+/// This is set from `tool.pyo3-stub-gen.inline-complex-enum-unions` in `pyproject.toml`.
+pub fn set_inline_complex_enum_unions(enabled: bool) {
+    Config::update(|config| config.inline_complex_enum_unions = enabled);
+}
+
+/// Retrieve whether complex enums are annotated by the union of their variants written out in place.
+pub fn inline_complex_enum_unions() -> bool {
+    Config::current().inline_complex_enum_unions
+}
+
+/// Name of the alias of the union of the variants of the complex enum `enum_name`
+pub(crate) fn complex_enum_alias(enum_name: &str) -> String {
+    format!("{enum_name}Variant")
 }
 
 impl From<&str> for ImportRef {
@@ -162,40 +196,205 @@ impl TypeInfo {
         }
     }
 
+    /// Generic type `base[arg1, arg2, ...]`, merging the imports of the arguments into `import`
+    fn generic(
+        base: &str,
+        args: impl IntoIterator<Item = TypeInfo>,
+        mut import: HashSet<ImportRef>,
+    ) -> Self {
+        let names = args
+            .into_iter()
+            .map(|arg| {
+                import.extend(arg.import);
+                arg.name
+            })
+            .collect::<Vec<_>>();
+        TypeInfo {
+            name: format!("{base}[{}]", names.join(", ")),
+            import,
+        }
+    }
+
+    /// A `list[Type]` type annotation.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let ty = TypeInfo::list(String::type_output());
+    /// assert_eq!(ty.name, "builtins.list[builtins.str]");
+    /// ```
+    pub fn list(item: TypeInfo) -> Self {
+        Self::generic("builtins.list", [item], hashset! { "builtins".into() })
+    }
+
     /// A `list[Type]` type annotation.
+    #[deprecated(note = "Use `TypeInfo::list(T::type_output())` instead")]
     pub fn list_of<T: PyStubType>() -> Self {
-        let TypeInfo { name, mut import } = T::type_output();
-        import.insert("builtins".into());
+        Self::list(T::type_output())
+    }
+
+    /// A `type[Type]` type annotation for the class object of `T`.
+    pub fn class_of<T: PyStubType>() -> Self {
+        let TypeInfo { name, import } = T::type_output();
         TypeInfo {
-            name: format!("builtins.list[{name}]"),
+            name: format!("type[{name}]"),
             import,
         }
     }
 
     /// A `set[Type]` type annotation.
+    pub fn set(item: TypeInfo) -> Self {
+        Self::generic("builtins.set", [item], hashset! { "builtins".into() })
+    }
+
+    /// A `set[Type]` type annotation.
+    #[deprecated(note = "Use `TypeInfo::set(T::type_output())` instead")]
     pub fn set_of<T: PyStubType>() -> Self {
-        let TypeInfo { name, mut import } = T::type_output();
-        import.insert("builtins".into());
+        Self::set(T::type_output())
+    }
+
+    /// A `typing.AbstractSet[Type]` type annotation, accepting a `frozenset` as well as a `set`.
+    pub fn abstract_set_of(item: TypeInfo) -> Self {
+        Self::generic("typing.AbstractSet", [item], hashset! { "typing".into() })
+    }
+
+    /// A `dict[Key, Value]` type annotation.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let foo = TypeInfo::with_module("mypkg.other.Foo", "mypkg.other".into());
+    /// let ty = TypeInfo::dict(String::type_output(), TypeInfo::list(foo));
+    /// assert_eq!(ty.name, "builtins.dict[builtins.str, builtins.list[mypkg.other.Foo]]");
+    /// assert!(ty.import.contains(&"mypkg.other".into()));
+    /// ```
+    pub fn dict(key: TypeInfo, value: TypeInfo) -> Self {
+        Self::generic(
+            "builtins.dict",
+            [key, value],
+            hashset! { "builtins".into() },
+        )
+    }
+
+    /// A `dict[Key, Value]` type annotation.
+    #[deprecated(note = "Use `TypeInfo::dict(K::type_output(), V::type_output())` instead")]
+    pub fn dict_of<K: PyStubType, V: PyStubType>() -> Self {
+        Self::dict(K::type_output(), V::type_output())
+    }
+
+    /// A `tuple[Type1, Type2, ...]` type annotation, or `tuple[()]` for an empty tuple.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let ty = TypeInfo::tuple_of(&[i32::type_output(), TypeInfo::none()]);
+    /// assert_eq!(ty.name, "tuple[builtins.int, None]");
+    /// ```
+    pub fn tuple_of(items: &[TypeInfo]) -> Self {
+        if items.is_empty() {
+            return Self::unqualified("tuple[()]");
+        }
+        Self::generic("tuple", items.iter().cloned(), HashSet::new())
+    }
+
+    /// A `tuple[Type, ...]` type annotation for a tuple of any length whose items are all `item`.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let ty = TypeInfo::variadic_tuple_of(f64::type_output());
+    /// assert_eq!(ty.name, "tuple[builtins.float, ...]");
+    /// ```
+    pub fn variadic_tuple_of(item: TypeInfo) -> Self {
+        let TypeInfo { name, import } = item;
         TypeInfo {
-            name: format!("builtins.set[{name}]"),
+            name: format!("tuple[{name}, ...]"),
             import,
         }
     }
 
-    /// A `dict[Type]` type annotation.
-    pub fn dict_of<K: PyStubType, V: PyStubType>() -> Self {
-        let TypeInfo {
-            name: name_k,
-            mut import,
-        } = K::type_output();
-        let TypeInfo {
-            name: name_v,
-            import: import_v,
-        } = V::type_output();
-        import.extend(import_v);
-        import.insert("builtins".into());
+    /// A `typing.Optional[Type]` type annotation.
+    pub fn optional(item: TypeInfo) -> Self {
+        Self::generic("typing.Optional", [item], hashset! { "typing".into() })
+    }
+
+    /// A `typing.Union[Type1, Type2, ...]` type annotation, which is the type itself for a single type,
+    /// and `typing.NoReturn` for no types.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let ty = TypeInfo::union(&[i32::type_output(), String::type_output()]);
+    /// assert_eq!(ty.name, "typing.Union[builtins.int, builtins.str]");
+    /// ```
+    pub fn union(items: &[TypeInfo]) -> Self {
+        match items {
+            [] => Self::with_module("typing.NoReturn", "typing".into()),
+            [item] => item.clone(),
+            _ => Self::generic(
+                "typing.Union",
+                items.iter().cloned(),
+                hashset! { "typing".into() },
+            ),
+        }
+    }
+
+    /// A `collections.abc.Callable[[Param1, ...], Return]` type annotation.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let ty = TypeInfo::callable(&[String::type_input()], bool::type_output());
+    /// assert_eq!(ty.name, "collections.abc.Callable[[builtins.str], builtins.bool]");
+    /// ```
+    pub fn callable(params: &[TypeInfo], r#return: TypeInfo) -> Self {
+        let TypeInfo { name, mut import } = Self::generic(
+            "",
+            params.iter().cloned(),
+            hashset! { "collections.abc".into() },
+        );
+        import.extend(r#return.import);
+        TypeInfo {
+            name: format!("collections.abc.Callable[{name}, {}]", r#return.name),
+            import,
+        }
+    }
+
+    /// A `collections.abc.Callable[..., Return]` type annotation, which accepts any arguments.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    ///
+    /// let ty = TypeInfo::callable_with_any_args(bool::type_output());
+    /// assert_eq!(ty.name, "collections.abc.Callable[..., builtins.bool]");
+    /// ```
+    pub fn callable_with_any_args(r#return: TypeInfo) -> Self {
+        let TypeInfo { name, mut import } = r#return;
+        import.insert("collections.abc".into());
+        TypeInfo {
+            name: format!("collections.abc.Callable[..., {name}]"),
+            import,
+        }
+    }
+
+    /// A `collections.abc.Awaitable[Type]` type annotation for an awaitable object resulting in `result`.
+    pub fn awaitable(result: TypeInfo) -> Self {
+        let TypeInfo { name, mut import } = result;
+        import.insert("collections.abc".into());
+        TypeInfo {
+            name: format!("collections.abc.Awaitable[{name}]"),
+            import,
+        }
+    }
+
+    /// A `collections.abc.Generator[typing.Any, typing.Any, Type]` type annotation returned by `__await__`
+    /// of an awaitable object resulting in `result`.
+    pub fn await_generator(result: TypeInfo) -> Self {
+        let TypeInfo { name, mut import } = result;
+        import.insert("collections.abc".into());
+        import.insert("typing".into());
         TypeInfo {
-            name: format!("builtins.dict[{name_k}, {name_v}]"),
+            name: format!("collections.abc.Generator[typing.Any, typing.Any, {name}]"),
             import,
         }
     }
@@ -231,6 +430,20 @@ impl TypeInfo {
         }
     }
 
+    /// `NotRequired[ty]` of a `typing.TypedDict` key, imported from the same module as [TypeInfo::self_type]
+    pub fn not_required(ty: TypeInfo) -> Self {
+        let module = match self_import_strategy() {
+            SelfImportStrategy::Typing => "typing",
+            SelfImportStrategy::TypingExtensions => "typing_extensions",
+        };
+        let mut import = ty.import;
+        import.insert(module.into());
+        Self {
+            name: format!("{module}.NotRequired[{}]", ty.name),
+            import,
+        }
+    }
+
     /// A type annotation of a type that must be imported. The type name must be qualified with the module name:
     ///
     /// ```
@@ -265,6 +478,82 @@ impl TypeInfo {
             import,
         }
     }
+
+    /// Union of the variants of the complex enum `enum_name` defined in `module`, i.e. the alias `{enum_name}Variant`
+    /// rendered with the enum, or `union` itself if [inline_complex_enum_unions] is set.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::TypeInfo;
+    /// let ty = TypeInfo::complex_enum("Shape", "geometry".into(), || {
+    ///     TypeInfo::unqualified("Shape.Circle") | TypeInfo::unqualified("Shape.Square")
+    /// });
+    /// assert_eq!(ty.name, "ShapeVariant");
+    /// ```
+    pub fn complex_enum(enum_name: &str, module: ModuleRef, union: fn() -> TypeInfo) -> Self {
+        if inline_complex_enum_unions() {
+            union()
+        } else {
+            Self::locally_defined(&complex_enum_alias(enum_name), module)
+        }
+    }
+
+    /// Strip the qualification by `module` from the references to its names for which `is_local` returns `true`,
+    /// e.g. `mypkg.core.Other` becomes `Other` when rendered in `mypkg.core`, and drop the import of `module` if no qualified reference remains.
+    ///
+    /// References to submodules like `mypkg.core.sub.Foo`, to modules sharing a prefix like `mypkg.core_ext.Foo`,
+    /// and string literals like `typing.Literal["mypkg.core.Other"]` are kept as is.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::TypeInfo;
+    /// let mut ty = TypeInfo::with_module("builtins.list[mypkg.core.Other]", "mypkg.core".into());
+    /// ty.unqualify("mypkg.core", |name| name == "Other");
+    /// assert_eq!(ty.name, "builtins.list[Other]");
+    /// assert!(ty.import.is_empty());
+    /// ```
+    pub fn unqualify(&mut self, module: &str, is_local: impl Fn(&str) -> bool) {
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let prefix = format!("{module}.");
+        let mut out = String::with_capacity(self.name.len());
+        let mut qualified = false;
+        let mut rest = self.name.as_str();
+        while let Some(c) = rest.chars().next() {
+            let len = if c == '"' || c == '\'' {
+                // Copy string literals up to the closing quote, skipping escaped characters
+                let mut escaped = false;
+                rest[1..]
+                    .find(|next| {
+                        let closing = !escaped && next == c;
+                        escaped = !escaped && next == '\\';
+                        closing
+                    })
+                    .map_or(rest.len(), |end| end + 2)
+            } else if is_ident(c) {
+                let len = rest
+                    .find(|next| !is_ident(next) && next != '.')
+                    .unwrap_or(rest.len());
+                let path = &rest[..len];
+                match path.strip_prefix(&prefix) {
+                    Some(local) if is_local(local.split('.').next().unwrap_or_default()) => {
+                        out.push_str(local);
+                        rest = &rest[len..];
+                        continue;
+                    }
+                    Some(_) => qualified = true,
+                    None => qualified |= path == module,
+                }
+                len
+            } else {
+                c.len_utf8()
+            };
+            out.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+        self.name = out;
+        if !qualified {
+            self.import
+                .remove(&ImportRef::Module(ModuleRef::from(module)));
+        }
+    }
 }
 
 impl ops::BitOr for TypeInfo {
@@ -333,6 +622,15 @@ macro_rules! impl_stub_type {
 }
 
 /// Annotate Rust types with Python type information.
+///
+/// Smart pointers such as `Box<T>`, `Rc<T>`, and `Arc<T>`, and locks such as `Mutex<T>` and `RwLock<T>`
+/// have the types of `T`. Trait objects like `Box<dyn Trait>` have no Python type,
+/// and need `#[gen_stub(override_type(...))]` on the parameter or `#[gen_stub(override_return_type(...))]`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no Python type for the stub file",
+    label = "`{Self}` does not implement `PyStubType`",
+    note = "derive it with `#[gen_stub_pyclass]` or similar, implement `PyStubType` by hand, or use `#[gen_stub(override_type(...))]` and `#[gen_stub(override_return_type(...))]`"
+)]
 pub trait PyStubType {
     /// The type to be used in the output signature, i.e. return type of the Python function or methods.
     fn type_output() -> TypeInfo;
@@ -344,15 +642,38 @@ pub trait PyStubType {
     fn type_input() -> TypeInfo {
         Self::type_output()
     }
+
+    /// Python expression of `value` as a default value of a parameter or a field,
+    /// e.g. `LogLevel.Info` for a variant of an enum of `#[gen_stub_pyclass_enum]`.
+    ///
+    /// This defaults to `None`, where the value is formatted by [crate::util::fmt_py_obj] instead.
+    fn default_repr(value: &Self) -> Option<String>
+    where
+        Self: Sized,
+    {
+        let _ = value;
+        None
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use maplit::hashset;
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
     use test_case::test_case;
 
+    struct MyClass;
+
+    impl PyStubType for MyClass {
+        fn type_output() -> TypeInfo {
+            TypeInfo::locally_defined("MyClass", ModuleRef::Default)
+        }
+    }
+
     #[test_case(bool::type_input(), "builtins.bool", hashset! { "builtins".into() } ; "bool_input")]
     #[test_case(<&str>::type_input(), "builtins.str", hashset! { "builtins".into() } ; "str_input")]
     #[test_case(Vec::<u32>::type_input(), "typing.Sequence[builtins.int]", hashset! { "typing".into(), "builtins".into() } ; "Vec_u32_input")]
@@ -364,8 +685,46 @@ mod test {
     #[test_case(HashMap::<u32, Vec<u32>>::type_input(), "typing.Mapping[builtins.int, typing.Sequence[builtins.int]]", hashset! { "builtins".into(), "typing".into() } ; "HashMap_u32_Vec_u32_input")]
     #[test_case(HashMap::<u32, Vec<u32>>::type_output(), "builtins.dict[builtins.int, builtins.list[builtins.int]]", hashset! { "builtins".into() } ; "HashMap_u32_Vec_u32_output")]
     #[test_case(HashSet::<u32>::type_input(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "HashSet_u32_input")]
+    #[test_case(Option::<indexmap::IndexMap<String, Vec<u8>>>::type_input(), "typing.Optional[typing.Mapping[builtins.str, typing.Sequence[builtins.int]]]", hashset! { "typing".into(), "builtins".into() } ; "Option_IndexMap_String_Vec_u8_input")]
+    #[test_case(Option::<indexmap::IndexMap<String, Vec<u8>>>::type_output(), "typing.Optional[builtins.dict[builtins.str, builtins.list[builtins.int]]]", hashset! { "typing".into(), "builtins".into() } ; "Option_IndexMap_String_Vec_u8_output")]
+    #[cfg_attr(feature = "smallvec", test_case(::smallvec::SmallVec::<[f32; 4]>::type_input(), "typing.Sequence[builtins.float]", hashset! { "typing".into(), "builtins".into() } ; "SmallVec_f32_input"))]
+    #[cfg_attr(feature = "smallvec", test_case(::smallvec::SmallVec::<[f32; 4]>::type_output(), "builtins.list[builtins.float]", hashset! { "builtins".into() } ; "SmallVec_f32_output"))]
+    #[cfg_attr(feature = "smallvec", test_case(indexmap::IndexMap::<String, ::smallvec::SmallVec<[MyClass; 2]>>::type_input(), "typing.Mapping[builtins.str, typing.Sequence[MyClass]]", hashset! { "typing".into(), "builtins".into(), ImportRef::Type(TypeRef::new(ModuleRef::Default, "MyClass".to_string())) } ; "IndexMap_String_SmallVec_MyClass_input"))]
+    #[cfg_attr(feature = "bytes", test_case(::bytes::Bytes::type_input(), "builtins.bytes", hashset! { "builtins".into() } ; "Bytes_input"))]
+    #[cfg_attr(feature = "bytes", test_case(Option::<::bytes::Bytes>::type_output(), "typing.Optional[builtins.bytes]", hashset! { "typing".into(), "builtins".into() } ; "Option_Bytes_output"))]
     #[test_case(indexmap::IndexSet::<u32>::type_input(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "IndexSet_u32_input")]
-    #[test_case(TypeInfo::dict_of::<u32, String>(), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "dict_of_u32_String")]
+    #[test_case(TypeInfo::dict(u32::type_output(), String::type_output()), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "dict_u32_String")]
+    #[test_case(#[allow(deprecated)] TypeInfo::dict_of::<u32, String>(), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "dict_of_u32_String")]
+    #[test_case(#[allow(deprecated)] TypeInfo::list_of::<String>(), "builtins.list[builtins.str]", hashset! { "builtins".into() } ; "list_of_String")]
+    #[test_case(#[allow(deprecated)] TypeInfo::set_of::<u32>(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "set_of_u32")]
+    #[test_case(::pyo3::Bound::<::pyo3::types::PyType>::type_input(), "type[typing.Any]", hashset! { "typing".into() } ; "Bound_PyType_input")]
+    #[test_case(crate::type_info::ClassOf::<u32>::type_input(), "type[builtins.int]", hashset! { "builtins".into() } ; "ClassOf_u32_input")]
+    #[test_case(crate::type_info::OptionalClassOf::<u32>::type_input(), "type[builtins.int] | None", hashset! { "builtins".into() } ; "OptionalClassOf_u32_input")]
+    #[test_case(TypeInfo::awaitable(String::type_output()), "collections.abc.Awaitable[builtins.str]", hashset! { "collections.abc".into(), "builtins".into() } ; "awaitable_String")]
+    #[test_case(TypeInfo::await_generator(u32::type_output()), "collections.abc.Generator[typing.Any, typing.Any, builtins.int]", hashset! { "collections.abc".into(), "typing".into(), "builtins".into() } ; "await_generator_u32")]
+    #[test_case(Arc::<Vec<String>>::type_output(), "builtins.list[builtins.str]", hashset! { "builtins".into() } ; "Arc_Vec_String_output")]
+    #[test_case(Box::<str>::type_input(), "builtins.str", hashset! { "builtins".into() } ; "Box_str_input")]
+    #[test_case(Box::<str>::type_output(), "builtins.str", hashset! { "builtins".into() } ; "Box_str_output")]
+    #[test_case(Mutex::<Arc<Vec<String>>>::type_output(), "builtins.list[builtins.str]", hashset! { "builtins".into() } ; "Mutex_Arc_Vec_String_output")]
+    #[test_case(Option::<Arc<MyClass>>::type_output(), "typing.Optional[MyClass]", hashset! { "typing".into(), ImportRef::Type(TypeRef::new(ModuleRef::Default, "MyClass".to_string())) } ; "Option_Arc_MyClass_output")]
+    #[test_case(Option::<::pyo3::Py<MyClass>>::type_output(), "typing.Optional[MyClass]", hashset! { "typing".into(), ImportRef::Type(TypeRef::new(ModuleRef::Default, "MyClass".to_string())) } ; "Option_Py_MyClass_output")]
+    #[test_case(::pyo3::Py::<::pyo3::types::PyDict>::type_output(), "dict", hashset! {} ; "Py_PyDict_output")]
+    #[test_case(::pyo3::Bound::<'static, ::pyo3::types::PyList>::type_input(), "list", hashset! {} ; "Bound_PyList_input")]
+    #[test_case(::pyo3::Py::<::pyo3::types::PyFrozenSet>::type_output(), "frozenset", hashset! {} ; "Py_PyFrozenSet_output")]
+    #[test_case(std::collections::BTreeSet::<u32>::type_output(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "BTreeSet_u32_output")]
+    #[test_case(std::path::PathBuf::type_input(), "builtins.str | os.PathLike[builtins.str]", hashset! { "builtins".into(), "os".into() } ; "PathBuf_input")]
+    #[test_case(std::path::PathBuf::type_output(), "pathlib.Path", hashset! { "pathlib".into() } ; "PathBuf_output")]
+    #[test_case(<&std::path::Path>::type_output(), "pathlib.Path", hashset! { "pathlib".into() } ; "Path_output")]
+    #[test_case(std::borrow::Cow::<std::path::Path>::type_output(), "pathlib.Path", hashset! { "pathlib".into() } ; "Cow_Path_output")]
+    #[test_case(Option::<std::path::PathBuf>::type_input(), "typing.Optional[builtins.str | os.PathLike[builtins.str]]", hashset! { "typing".into(), "builtins".into(), "os".into() } ; "Option_PathBuf_input")]
+    #[test_case(Option::<&std::path::Path>::type_output(), "typing.Optional[pathlib.Path]", hashset! { "typing".into(), "pathlib".into() } ; "Option_Path_output")]
+    #[test_case(Vec::<std::path::PathBuf>::type_input(), "typing.Sequence[builtins.str | os.PathLike[builtins.str]]", hashset! { "typing".into(), "builtins".into(), "os".into() } ; "Vec_PathBuf_input")]
+    #[test_case(Vec::<std::path::PathBuf>::type_output(), "builtins.list[pathlib.Path]", hashset! { "builtins".into(), "pathlib".into() } ; "Vec_PathBuf_output")]
+    #[test_case(std::ffi::OsString::type_input(), "builtins.str", hashset! { "builtins".into() } ; "OsString_input")]
+    #[test_case(Option::<std::ffi::OsString>::type_output(), "typing.Optional[builtins.str]", hashset! { "typing".into(), "builtins".into() } ; "Option_OsString_output")]
+    #[test_case(Vec::<&std::ffi::OsStr>::type_output(), "builtins.list[builtins.str]", hashset! { "builtins".into() } ; "Vec_OsStr_output")]
+    #[test_case(Option::<std::borrow::Cow<str>>::type_input(), "typing.Optional[builtins.str]", hashset! { "typing".into(), "builtins".into() } ; "Option_Cow_str_input")]
+    #[test_case(Vec::<std::borrow::Cow<str>>::type_output(), "builtins.list[builtins.str]", hashset! { "builtins".into() } ; "Vec_Cow_str_output")]
     fn test(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>) {
         assert_eq!(tinfo.name, name);
         if import.is_empty() {
@@ -374,4 +733,140 @@ mod test {
             assert_eq!(tinfo.import, import);
         }
     }
+
+    #[test]
+    fn default_repr() {
+        struct Level;
+        impl PyStubType for Level {
+            fn type_output() -> TypeInfo {
+                TypeInfo::locally_defined("Level", ModuleRef::Default)
+            }
+            fn default_repr(_: &Self) -> Option<String> {
+                Some("Level.INFO".to_string())
+            }
+        }
+        assert_eq!(
+            Option::<Level>::default_repr(&Some(Level)).as_deref(),
+            Some("Level.INFO")
+        );
+        assert_eq!(Option::<Level>::default_repr(&None), None);
+        assert_eq!(u32::default_repr(&8), None);
+    }
+
+    #[test]
+    fn nested_combinators() {
+        let foo = TypeInfo::locally_defined("Foo", "mypkg.other".into());
+        let ty = TypeInfo::optional(TypeInfo::dict(
+            TypeInfo::builtin("str"),
+            TypeInfo::list(TypeInfo::tuple_of(&[foo, TypeInfo::none()])),
+        ));
+        assert_eq!(
+            ty.name,
+            "typing.Optional[builtins.dict[builtins.str, builtins.list[tuple[Foo, None]]]]"
+        );
+        assert_eq!(
+            ty.import,
+            hashset! {
+                "typing".into(),
+                "builtins".into(),
+                ImportRef::Type(TypeRef::new("mypkg.other".into(), "Foo".to_string())),
+            }
+        );
+
+        let ty = TypeInfo::callable(
+            &[TypeInfo::union(&[
+                TypeInfo::builtin("int"),
+                TypeInfo::any(),
+            ])],
+            TypeInfo::set(TypeInfo::with_module("pathlib.Path", "pathlib".into())),
+        );
+        assert_eq!(
+            ty.name,
+            "collections.abc.Callable[[typing.Union[builtins.int, typing.Any]], builtins.set[pathlib.Path]]"
+        );
+        assert_eq!(
+            ty.import,
+            hashset! { "collections.abc".into(), "typing".into(), "builtins".into(), "pathlib".into() }
+        );
+    }
+
+    #[test]
+    fn stub_callable() {
+        use crate::type_info::{AnyArgs, StubCallable};
+        use std::path::PathBuf;
+
+        // A callback is called with what Rust passes, and returns what Rust extracts
+        type Callback = StubCallable<(PathBuf, String), Vec<i64>>;
+        assert_eq!(
+            Option::<Callback>::type_input().name,
+            "typing.Optional[collections.abc.Callable[[pathlib.Path, builtins.str], typing.Sequence[builtins.int]]]"
+        );
+        // A returned callable is called with what Python passes
+        assert_eq!(
+            Callback::type_output().name,
+            "collections.abc.Callable[[builtins.str | os.PathLike[builtins.str], builtins.str], builtins.list[builtins.int]]"
+        );
+        let ty = StubCallable::<AnyArgs, bool>::type_input();
+        assert_eq!(ty.name, "collections.abc.Callable[..., builtins.bool]");
+        assert_eq!(
+            ty.import,
+            hashset! { "collections.abc".into(), "builtins".into() }
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        use crate::type_info::VariadicTuple;
+
+        type Wide = (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, String);
+        assert_eq!(
+            Wide::type_output().name,
+            format!("tuple[{}builtins.str]", "builtins.int, ".repeat(11))
+        );
+        assert_eq!(
+            Vec::<VariadicTuple<(String, f64)>>::type_output().name,
+            "builtins.list[tuple[tuple[builtins.str, builtins.float], ...]]"
+        );
+        assert_eq!(
+            VariadicTuple::<std::path::PathBuf>::type_input().name,
+            "tuple[builtins.str | os.PathLike[builtins.str], ...]"
+        );
+    }
+
+    #[test]
+    fn degenerate_combinators() {
+        assert_eq!(TypeInfo::tuple_of(&[]).name, "tuple[()]");
+        assert_eq!(TypeInfo::union(&[]).name, "typing.NoReturn");
+        assert_eq!(TypeInfo::union(&[TypeInfo::none()]), TypeInfo::none());
+        assert_eq!(
+            TypeInfo::callable(&[], TypeInfo::none()).name,
+            "collections.abc.Callable[[], None]"
+        );
+    }
+
+    #[test_case("mypkg.core.Other", "Other" ; "same_module")]
+    #[test_case("mypkg.core.Other.Inner | None", "Other.Inner | None" ; "nested_class")]
+    #[test_case("mypkg.core.sub.Foo", "mypkg.core.sub.Foo" ; "submodule")]
+    #[test_case("mypkg.core_ext.Other", "mypkg.core_ext.Other" ; "shared_prefix")]
+    #[test_case("mypkg.Other", "mypkg.Other" ; "parent_module")]
+    #[test_case("typing.Literal['mypkg.core.Other', \"it's\"]", "typing.Literal['mypkg.core.Other', \"it's\"]" ; "literal")]
+    #[test_case("builtins.dict[mypkg.core.Other, mypkg.util.Other]", "builtins.dict[Other, mypkg.util.Other]" ; "sibling_module")]
+    fn unqualify(name: &str, expected: &str) {
+        let mut ty = TypeInfo::with_module(name, "mypkg.core".into());
+        ty.unqualify("mypkg.core", |name| name == "Other");
+        assert_eq!(ty.name, expected);
+    }
+
+    #[test]
+    fn unqualify_keeps_import_of_qualified_references() {
+        let mut ty =
+            TypeInfo::with_module("mypkg.core.sub.Foo | mypkg.core.Other", "mypkg.core".into());
+        ty.unqualify("mypkg.core", |name| name == "Other");
+        assert_eq!(ty.name, "mypkg.core.sub.Foo | Other");
+        assert_eq!(ty.import, hashset! { "mypkg.core".into() });
+
+        let mut ty = TypeInfo::with_module("mypkg.core.Other", "mypkg.core".into());
+        ty.unqualify("mypkg.core", |name| name == "Other");
+        assert_eq!(ty.import, hashset! {});
+    }
 }
@@ -4,14 +4,12 @@ use crate::stub_type::*;
 use std::{
     borrow::Cow,
     ffi::{OsStr, OsString},
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
-    sync::Arc,
+    sync::{Arc, Mutex, RwLock},
     time::SystemTime,
 };
 
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
-
 macro_rules! impl_builtin {
     ($ty:ty, $pytype:expr) => {
         impl PyStubType for $ty {
@@ -57,7 +55,7 @@ impl_builtin!(num_complex::Complex32, "complex");
 impl_builtin!(num_complex::Complex64, "complex");
 
 impl_builtin!(char, "str");
-impl_builtin!(&str, "str");
+impl_builtin!(str, "str");
 impl_builtin!(OsStr, "str");
 impl_builtin!(String, "str");
 impl_builtin!(OsString, "str");
@@ -74,33 +72,52 @@ mod impl_ordered_float {
     impl_builtin!(ordered_float::OrderedFloat<f64>, "float");
 }
 
-impl PyStubType for PathBuf {
-    fn type_output() -> TypeInfo {
-        TypeInfo::with_module("pathlib.Path", "pathlib".into())
-    }
+/// Paths are extracted by `os.fspath` as `str`, so that `os.PathLike[bytes]` is rejected,
+/// and converted into `pathlib.Path` by PyO3 0.26 and later.
+/// `OsStr` and `OsString` are extracted only from `str` unlike paths.
+macro_rules! impl_path {
+    ($ty:ty) => {
+        impl PyStubType for $ty {
+            fn type_output() -> TypeInfo {
+                TypeInfo::with_module("pathlib.Path", "pathlib".into())
+            }
+            fn type_input() -> TypeInfo {
+                let str = TypeInfo::builtin("str");
+                let mut path_like =
+                    TypeInfo::with_module(&format!("os.PathLike[{}]", str.name), "os".into());
+                path_like.import.extend(str.import.clone());
+                str | path_like
+            }
+        }
+    };
+}
+
+impl_path!(Path);
+impl_path!(PathBuf);
+impl_path!(Cow<'_, Path>);
+
+impl_with_module!(SystemTime, "datetime.datetime", "datetime");
+impl_with_module!(std::time::Duration, "datetime.timedelta", "datetime");
+
+impl<T: PyStubType + ?Sized> PyStubType for &T {
     fn type_input() -> TypeInfo {
-        TypeInfo::builtin("str")
-            | TypeInfo::with_module("os.PathLike", "os".into())
-            | TypeInfo::with_module("pathlib.Path", "pathlib".into())
+        T::type_input()
+    }
+    fn type_output() -> TypeInfo {
+        T::type_output()
     }
 }
 
-impl<Tz: TimeZone> PyStubType for DateTime<Tz> {
+impl<T: PyStubType + ?Sized> PyStubType for Rc<T> {
+    fn type_input() -> TypeInfo {
+        T::type_input()
+    }
     fn type_output() -> TypeInfo {
-        TypeInfo::with_module("datetime.datetime", "datetime".into())
+        T::type_output()
     }
 }
 
-impl_with_module!(SystemTime, "datetime.datetime", "datetime");
-impl_with_module!(NaiveDateTime, "datetime.datetime", "datetime");
-impl_with_module!(NaiveDate, "datetime.date", "datetime");
-impl_with_module!(NaiveTime, "datetime.time", "datetime");
-impl_with_module!(FixedOffset, "datetime.tzinfo", "datetime");
-impl_with_module!(Utc, "datetime.tzinfo", "datetime");
-impl_with_module!(std::time::Duration, "datetime.timedelta", "datetime");
-impl_with_module!(chrono::Duration, "datetime.timedelta", "datetime");
-
-impl<T: PyStubType> PyStubType for &T {
+impl<T: PyStubType + ?Sized> PyStubType for Arc<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
@@ -109,7 +126,8 @@ impl<T: PyStubType> PyStubType for &T {
     }
 }
 
-impl<T: PyStubType> PyStubType for Rc<T> {
+/// Fields behind a lock, e.g. with `#[pyo3(get)]`, are exposed as the inner value
+impl<T: PyStubType + ?Sized> PyStubType for Mutex<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
@@ -118,7 +136,7 @@ impl<T: PyStubType> PyStubType for Rc<T> {
     }
 }
 
-impl<T: PyStubType> PyStubType for Arc<T> {
+impl<T: PyStubType + ?Sized> PyStubType for RwLock<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
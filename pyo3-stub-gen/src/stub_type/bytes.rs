@@ -0,0 +1,7 @@
+use super::{PyStubType, TypeInfo};
+
+impl PyStubType for ::bytes::Bytes {
+    fn type_output() -> TypeInfo {
+        TypeInfo::builtin("bytes")
+    }
+}
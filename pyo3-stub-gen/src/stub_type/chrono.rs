@@ -0,0 +1,67 @@
+use super::{PyStubType, TypeInfo};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+impl<Tz: TimeZone> PyStubType for DateTime<Tz> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::with_module("datetime.datetime", "datetime".into())
+    }
+}
+
+macro_rules! impl_datetime {
+    ($ty:ty, $pytype:expr) => {
+        impl PyStubType for $ty {
+            fn type_output() -> TypeInfo {
+                TypeInfo::with_module($pytype, "datetime".into())
+            }
+        }
+    };
+}
+
+impl_datetime!(NaiveDateTime, "datetime.datetime");
+impl_datetime!(NaiveDate, "datetime.date");
+impl_datetime!(NaiveTime, "datetime.time");
+impl_datetime!(FixedOffset, "datetime.tzinfo");
+impl_datetime!(Utc, "datetime.tzinfo");
+impl_datetime!(chrono::Duration, "datetime.timedelta");
+
+#[cfg(feature = "chrono-tz")]
+impl PyStubType for chrono_tz::Tz {
+    fn type_output() -> TypeInfo {
+        TypeInfo::with_module("zoneinfo.ZoneInfo", "zoneinfo".into())
+    }
+    fn type_input() -> TypeInfo {
+        Self::type_output() | TypeInfo::builtin("str")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stub_type::ImportRef;
+    use maplit::hashset;
+    use std::collections::HashSet;
+    use test_case::test_case;
+
+    #[test_case(DateTime::<Utc>::type_input(), "datetime.datetime", hashset! { "datetime".into() } ; "DateTime_Utc_input")]
+    #[test_case(NaiveDate::type_output(), "datetime.date", hashset! { "datetime".into() } ; "NaiveDate_output")]
+    #[test_case(NaiveTime::type_output(), "datetime.time", hashset! { "datetime".into() } ; "NaiveTime_output")]
+    #[test_case(chrono::Duration::type_input(), "datetime.timedelta", hashset! { "datetime".into() } ; "Duration_input")]
+    fn test(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>) {
+        assert_eq!(tinfo.name, name);
+        assert_eq!(tinfo.import, import);
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_chrono_tz() {
+        let input = chrono_tz::Tz::type_input();
+        assert_eq!(input.name, "zoneinfo.ZoneInfo | builtins.str");
+        assert_eq!(
+            input.import,
+            hashset! { "zoneinfo".into(), "builtins".into() }
+        );
+        let output = chrono_tz::Tz::type_output();
+        assert_eq!(output.name, "zoneinfo.ZoneInfo");
+        assert_eq!(output.import, hashset! { "zoneinfo".into() });
+    }
+}
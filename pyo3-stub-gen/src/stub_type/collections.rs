@@ -3,24 +3,17 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 impl<T: PyStubType> PyStubType for Option<T> {
     fn type_input() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_input();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Optional[{name}]"),
-            import,
-        }
+        TypeInfo::optional(T::type_input())
     }
     fn type_output() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_output();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Optional[{name}]"),
-            import,
-        }
+        TypeInfo::optional(T::type_output())
+    }
+    fn default_repr(value: &Self) -> Option<String> {
+        value.as_ref().and_then(T::default_repr)
     }
 }
 
-impl<T: PyStubType> PyStubType for Box<T> {
+impl<T: PyStubType + ?Sized> PyStubType for Box<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
@@ -40,83 +33,73 @@ impl<T: PyStubType, E> PyStubType for Result<T, E> {
 
 impl<T: PyStubType> PyStubType for Vec<T> {
     fn type_input() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_input();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Sequence[{name}]"),
-            import,
-        }
+        TypeInfo::generic(
+            "typing.Sequence",
+            [T::type_input()],
+            hashset! { "typing".into() },
+        )
     }
     fn type_output() -> TypeInfo {
-        TypeInfo::list_of::<T>()
+        TypeInfo::list(T::type_output())
     }
 }
 
 impl<T: PyStubType, const N: usize> PyStubType for [T; N] {
     fn type_input() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_input();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Sequence[{name}]"),
-            import,
-        }
+        TypeInfo::generic(
+            "typing.Sequence",
+            [T::type_input()],
+            hashset! { "typing".into() },
+        )
     }
     fn type_output() -> TypeInfo {
-        TypeInfo::list_of::<T>()
+        TypeInfo::list(T::type_output())
     }
 }
 
-impl<T: PyStubType, State> PyStubType for HashSet<T, State> {
-    fn type_output() -> TypeInfo {
-        TypeInfo::set_of::<T>()
+/// Input type of a set of `item`, which is `typing.AbstractSet` if `abstract_input` is set
+fn set_input(item: TypeInfo, abstract_input: bool) -> TypeInfo {
+    if abstract_input {
+        TypeInfo::abstract_set_of(item)
+    } else {
+        TypeInfo::set(item)
     }
 }
 
+macro_rules! impl_set_inner {
+    () => {
+        fn type_input() -> TypeInfo {
+            set_input(T::type_input(), abstract_set_input())
+        }
+        fn type_output() -> TypeInfo {
+            TypeInfo::set(T::type_output())
+        }
+    };
+}
+
+impl<T: PyStubType, State> PyStubType for HashSet<T, State> {
+    impl_set_inner!();
+}
+
 impl<T: PyStubType> PyStubType for BTreeSet<T> {
-    fn type_output() -> TypeInfo {
-        TypeInfo::set_of::<T>()
-    }
+    impl_set_inner!();
 }
 
 impl<T: PyStubType> PyStubType for indexmap::IndexSet<T> {
-    fn type_output() -> TypeInfo {
-        TypeInfo::set_of::<T>()
-    }
+    impl_set_inner!();
 }
 
 macro_rules! impl_map_inner {
     () => {
         fn type_input() -> TypeInfo {
-            let TypeInfo {
-                name: key_name,
-                mut import,
-            } = Key::type_input();
-            let TypeInfo {
-                name: value_name,
-                import: value_import,
-            } = Value::type_input();
-            import.extend(value_import);
-            import.insert("typing".into());
-            TypeInfo {
-                name: format!("typing.Mapping[{}, {}]", key_name, value_name),
-                import,
-            }
+            TypeInfo::generic(
+                "typing.Mapping",
+                [Key::type_input(), Value::type_input()],
+                hashset! { "typing".into() },
+            )
         }
         fn type_output() -> TypeInfo {
-            let TypeInfo {
-                name: key_name,
-                mut import,
-            } = Key::type_output();
-            let TypeInfo {
-                name: value_name,
-                import: value_import,
-            } = Value::type_output();
-            import.extend(value_import);
-            import.insert("builtins".into());
-            TypeInfo {
-                name: format!("builtins.dict[{}, {}]", key_name, value_name),
-                import,
-            }
+            TypeInfo::dict(Key::type_output(), Value::type_output())
         }
     };
 }
@@ -139,30 +122,10 @@ macro_rules! impl_tuple {
     ($($T:ident),*) => {
         impl<$($T: PyStubType),*> PyStubType for ($($T),* ,) {
             fn type_output() -> TypeInfo {
-                let mut merged = HashSet::new();
-                let mut names = Vec::new();
-                $(
-                let TypeInfo { name, import } = $T::type_output();
-                names.push(name);
-                merged.extend(import);
-                )*
-                TypeInfo {
-                    name: format!("tuple[{}]", names.join(", ")),
-                    import: merged,
-                }
+                TypeInfo::tuple_of(&[$($T::type_output()),*])
             }
             fn type_input() -> TypeInfo {
-                let mut merged = HashSet::new();
-                let mut names = Vec::new();
-                $(
-                let TypeInfo { name, import } = $T::type_input();
-                names.push(name);
-                merged.extend(import);
-                )*
-                TypeInfo {
-                    name: format!("tuple[{}]", names.join(", ")),
-                    import: merged,
-                }
+                TypeInfo::tuple_of(&[$($T::type_input()),*])
             }
         }
     };
@@ -177,3 +140,37 @@ impl_tuple!(T1, T2, T3, T4, T5, T6);
 impl_tuple!(T1, T2, T3, T4, T5, T6, T7);
 impl_tuple!(T1, T2, T3, T4, T5, T6, T7, T8);
 impl_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_input_modes() {
+        assert_eq!(
+            set_input(u32::type_input(), false).name,
+            "builtins.set[builtins.int]"
+        );
+        let abstract_set = set_input(u32::type_input(), true);
+        assert_eq!(abstract_set.name, "typing.AbstractSet[builtins.int]");
+        assert_eq!(
+            abstract_set.import,
+            hashset! { "typing".into(), "builtins".into() }
+        );
+        // Outputs are `set` in either mode
+        assert_eq!(
+            BTreeSet::<String>::type_output().name,
+            "builtins.set[builtins.str]"
+        );
+    }
+
+    #[test]
+    fn abstract_set_input_in_pyproject() {
+        use crate::pyproject::StubGen;
+        let stub_gen: StubGen = toml::from_str("abstract-set-input = true").unwrap();
+        assert_eq!(stub_gen.abstract_set_input, Some(true));
+    }
+}
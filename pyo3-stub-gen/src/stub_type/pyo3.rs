@@ -53,6 +53,12 @@ impl<T: PyStubType> PyStubType for Bound<'_, T> {
     }
 }
 
+impl PyStubType for PyType {
+    fn type_output() -> TypeInfo {
+        TypeInfo::class_of::<PyAny>()
+    }
+}
+
 macro_rules! impl_builtin {
     ($ty:ty, $pytype:expr) => {
         impl PyStubType for $ty {
@@ -75,12 +81,12 @@ impl_builtin!(PyTuple, "tuple");
 impl_builtin!(PySlice, "slice");
 impl_builtin!(PyDict, "dict");
 impl_builtin!(PySet, "set");
+impl_builtin!(PyFrozenSet, "frozenset");
 impl_builtin!(PyString, "str");
 impl_builtin!(PyBackedStr, "str");
 impl_builtin!(PyByteArray, "bytearray");
 impl_builtin!(PyBytes, "bytes");
 impl_builtin!(PyBackedBytes, "bytes");
-impl_builtin!(PyType, "type");
 impl_builtin!(CompareOp, "int");
 
 macro_rules! impl_simple {
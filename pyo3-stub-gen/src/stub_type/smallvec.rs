@@ -0,0 +1,14 @@
+use crate::stub_type::*;
+
+impl<A> PyStubType for ::smallvec::SmallVec<A>
+where
+    A: ::smallvec::Array,
+    A::Item: PyStubType,
+{
+    fn type_input() -> TypeInfo {
+        Vec::<A::Item>::type_input()
+    }
+    fn type_output() -> TypeInfo {
+        Vec::<A::Item>::type_output()
+    }
+}
@@ -0,0 +1,38 @@
+use super::{PyStubType, TypeInfo};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+macro_rules! impl_datetime {
+    ($ty:ty, $pytype:expr) => {
+        impl PyStubType for $ty {
+            fn type_output() -> TypeInfo {
+                TypeInfo::with_module($pytype, "datetime".into())
+            }
+        }
+    };
+}
+
+impl_datetime!(OffsetDateTime, "datetime.datetime");
+impl_datetime!(PrimitiveDateTime, "datetime.datetime");
+impl_datetime!(Date, "datetime.date");
+impl_datetime!(Time, "datetime.time");
+impl_datetime!(UtcOffset, "datetime.tzinfo");
+impl_datetime!(time::Duration, "datetime.timedelta");
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stub_type::ImportRef;
+    use maplit::hashset;
+    use std::collections::HashSet;
+    use test_case::test_case;
+
+    #[test_case(OffsetDateTime::type_output(), "datetime.datetime", hashset! { "datetime".into() } ; "OffsetDateTime_output")]
+    #[test_case(PrimitiveDateTime::type_output(), "datetime.datetime", hashset! { "datetime".into() } ; "PrimitiveDateTime_output")]
+    #[test_case(Date::type_output(), "datetime.date", hashset! { "datetime".into() } ; "Date_output")]
+    #[test_case(Time::type_output(), "datetime.time", hashset! { "datetime".into() } ; "Time_output")]
+    #[test_case(time::Duration::type_input(), "datetime.timedelta", hashset! { "datetime".into() } ; "Duration_input")]
+    fn test(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>) {
+        assert_eq!(tinfo.name, name);
+        assert_eq!(tinfo.import, import);
+    }
+}
@@ -0,0 +1,285 @@
+//! Golden-file tests of the generated stubs.
+//!
+//! ```no_run
+//! # fn stub_info() -> pyo3_stub_gen::Result<pyo3_stub_gen::StubInfo> { unimplemented!() }
+//! #[test]
+//! fn stubs_are_up_to_date() {
+//!     let stub_info = stub_info().unwrap();
+//!     pyo3_stub_gen::testing::assert_stub_snapshot(&stub_info, "tests/snapshots");
+//! }
+//! ```
+//!
+//! Run the test with `PYO3_STUB_GEN_UPDATE_SNAPSHOTS=1` to write the golden files.
+
+use crate::StubInfo;
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable to write the golden files instead of comparing with them
+pub const UPDATE_SNAPSHOTS_VAR: &str = "PYO3_STUB_GEN_UPDATE_SNAPSHOTS";
+
+/// Lines of context around the changed lines in a diff
+const CONTEXT: usize = 3;
+
+/// Assert that the stub files of `stub_info` match the golden files under `dir`,
+/// where a module `mypkg.sub` is compared with `dir/mypkg/sub.pyi` as laid out by [StubInfo::generate].
+///
+/// Newlines are normalized before comparison, so that golden files checked out with `\r\n` also match.
+/// On a mismatch, the generated stubs are written into a temporary directory, whose path is in the panic message
+/// along with a diff of each file.
+/// The golden files are written instead if [UPDATE_SNAPSHOTS_VAR] is set to anything but `0`.
+#[track_caller]
+pub fn assert_stub_snapshot(stub_info: &StubInfo, dir: impl AsRef<Path>) {
+    let update = std::env::var(UPDATE_SNAPSHOTS_VAR).is_ok_and(|value| value != "0");
+    if let Err(err) = check_snapshot(stub_info, dir.as_ref(), update) {
+        panic!("{err:#}");
+    }
+}
+
+/// Compare the stubs of `stub_info` with the golden files under `dir`, or write them if `update`
+fn check_snapshot(stub_info: &StubInfo, dir: &Path, update: bool) -> Result<()> {
+    let mut mismatches = Vec::new();
+    let mut actual = Vec::new();
+    for (_, dest, content) in stub_info.stub_files()? {
+        let path = dir.join(&dest);
+        if update {
+            write(&path, &content)?;
+            continue;
+        }
+        let content = normalize(&content);
+        match fs::read_to_string(&path) {
+            Ok(golden) if normalize(&golden) == content => {}
+            Ok(golden) => mismatches.push(format!(
+                "{}:\n{}",
+                path.display(),
+                diff(&normalize(&golden), &content)
+            )),
+            Err(_) => mismatches.push(format!("{}: missing golden file", path.display())),
+        }
+        actual.push((dest, content));
+    }
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    let out = temp_dir();
+    for (dest, content) in &actual {
+        write(&out.join(dest), content)?;
+    }
+    anyhow::bail!(
+        "Generated stubs differ from the golden files in {}:\n\n{}\n\nThe generated stubs are written in {}. Set `{UPDATE_SNAPSHOTS_VAR}=1` to update the golden files.",
+        dir.display(),
+        mismatches.join("\n\n"),
+        out.display()
+    )
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// `content` with `\n` newlines
+fn normalize(content: &str) -> String {
+    content.replace("\r\n", "\n")
+}
+
+/// Fresh directory for the generated stubs of a failed comparison
+fn temp_dir() -> PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "pyo3-stub-gen-snapshot-{}-{}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Line of a [diff]
+#[derive(Clone, Copy, PartialEq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Unified diff from `expected` to `actual` with [CONTEXT] lines around each change
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let lines = diff_lines(&expected, &actual);
+    let changed: Vec<_> = (0..lines.len())
+        .filter(|&k| !matches!(lines[k], Line::Same(_)))
+        .collect();
+    let mut hunks = Vec::new();
+    let (mut old, mut new, mut k) = (1, 1, 0);
+    while k < lines.len() {
+        let Some(&next) = changed.iter().find(|&&c| c >= k) else {
+            break;
+        };
+        // Skip to the context of the next change
+        for line in &lines[k..next.saturating_sub(CONTEXT).max(k)] {
+            if let Line::Same(_) = line {
+                old += 1;
+                new += 1;
+            }
+        }
+        let start = next.saturating_sub(CONTEXT).max(k);
+        // Extend the hunk while the changes are close enough to share the context
+        let mut end = next;
+        while let Some(&c) = changed.iter().find(|&&c| c > end && c <= end + 2 * CONTEXT) {
+            end = c;
+        }
+        let end = (end + CONTEXT + 1).min(lines.len());
+        let hunk = &lines[start..end];
+        let old_len = hunk
+            .iter()
+            .filter(|line| !matches!(line, Line::Added(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|line| !matches!(line, Line::Removed(_)))
+            .count();
+        hunks.push(format!("@@ -{old},{old_len} +{new},{new_len} @@"));
+        hunks.extend(hunk.iter().map(|line| match line {
+            Line::Same(line) => format!(" {line}"),
+            Line::Removed(line) => format!("-{line}"),
+            Line::Added(line) => format!("+{line}"),
+        }));
+        old += old_len;
+        new += new_len;
+        k = end;
+    }
+    hunks.join("\n")
+}
+
+/// Lines of the shortest edit from `expected` to `actual` by their longest common subsequence
+fn diff_lines<'a>(expected: &[&'a str], actual: &[&'a str]) -> Vec<Line<'a>> {
+    // The common prefix and suffix are cut off to keep the table small
+    let prefix = expected
+        .iter()
+        .zip(actual)
+        .take_while(|(e, a)| e == a)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(e, a)| e == a)
+        .count();
+    let old = &expected[prefix..expected.len() - suffix];
+    let new = &actual[prefix..actual.len() - suffix];
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut lines: Vec<_> = expected[..prefix]
+        .iter()
+        .map(|line| Line::Same(line))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(
+        expected[expected.len() - suffix..]
+            .iter()
+            .map(|line| Line::Same(line)),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::{Module, StubLayout};
+    use std::collections::BTreeMap;
+
+    fn stub_info() -> StubInfo {
+        StubInfo {
+            modules: BTreeMap::from([("pkg".to_string(), Module::new("pkg", "pkg"))]),
+            python_root: PathBuf::from("python"),
+            layout: StubLayout::default(),
+            merge: false,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn golden_files() -> Result<()> {
+        let dir = temp_dir();
+        let info = stub_info();
+        let err = check_snapshot(&info, &dir, false).unwrap_err().to_string();
+        assert!(err.contains("pkg.pyi: missing golden file"), "{err}");
+
+        check_snapshot(&info, &dir, true)?;
+        check_snapshot(&info, &dir, false)?;
+
+        // Golden files checked out with CRLF newlines
+        let golden = dir.join("pkg.pyi");
+        let content = fs::read_to_string(&golden)?;
+        fs::write(&golden, content.replace('\n', "\r\n"))?;
+        check_snapshot(&info, &dir, false)?;
+
+        fs::write(&golden, content.replace("# ruff", "# stale\n# ruff"))?;
+        let err = check_snapshot(&info, &dir, false).unwrap_err().to_string();
+        assert!(
+            err.contains(
+                "@@ -1,5 +1,4 @@\n # This file is automatically generated by pyo3_stub_gen\n-# stale\n # ruff: noqa: E501, F401\n \n \n"
+            ),
+            "{err}"
+        );
+        assert!(err.contains("PYO3_STUB_GEN_UPDATE_SNAPSHOTS=1"));
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn diff_of_changed_lines() {
+        let expected = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+        let actual = "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\nk\nl\nm\nn\n";
+        assert_eq!(
+            diff(expected, actual),
+            [
+                "@@ -2,7 +2,7 @@",
+                " b",
+                " c",
+                " d",
+                "-e",
+                "+E",
+                " f",
+                " g",
+                " h",
+                "@@ -11,3 +11,4 @@",
+                " k",
+                " l",
+                " m",
+                "+n",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -20,9 +20,16 @@
 //! we have to reconstruct these information corresponding to a Python `class`.
 //! This process is done at runtime in [gen_stub](../../gen_stub) executable.
 //!
+//! Hand-built infos
+//! ----------------
+//! The infos are usually submitted by the proc-macros, but may be written by hand, e.g. in tests.
+//! Each of them has a `DEFAULT` const to be completed by the struct update syntax,
+//! so that such infos keep compiling when a field is added to the struct.
+//!
 
 use crate::{PyStubType, TypeInfo};
-use std::any::TypeId;
+use serde::{Deserialize, Serialize};
+use std::{any::TypeId, marker::PhantomData};
 
 /// Represents the target of type ignore comments
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -51,7 +58,8 @@ pub fn no_return_type_output() -> TypeInfo {
 }
 
 /// Kind of parameter in Python function signature
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ParameterKind {
     /// Positional-only parameter (before `/`)
     PositionalOnly,
@@ -103,10 +111,18 @@ pub struct ParameterInfo {
     pub type_info: fn() -> TypeInfo,
     /// Default value
     pub default: ParameterDefault,
+    /// Whether the type is set by the user with `#[gen_stub(override_type(...))]`
+    /// instead of being derived from the Rust type
+    pub type_overridden: bool,
+    /// Rendering of a list, dict, or set default given by `#[gen_stub(container_default = "...")]`
+    pub container_default: Option<crate::generate::ContainerDefaultStyle>,
+    /// Note of `#[gen_stub(deprecated_param(...))]`, empty if none is given
+    pub deprecated: Option<&'static str>,
 }
 
 /// Type of a method
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MethodType {
     Instance,
     Static,
@@ -126,6 +142,30 @@ pub struct MethodInfo {
     pub deprecated: Option<DeprecatedInfo>,
     pub type_ignored: Option<IgnoreTarget>,
     pub is_abstract: bool,
+    /// Additional names given by `#[gen_stub(also_as(...))]`
+    pub also_as: &'static [&'static str],
+    /// Set by `#[gen_stub(keep_signature)]` to render a class-protocol hook such as `__init_subclass__` as declared
+    pub keep_signature: bool,
+    /// Source location of the method, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
+}
+
+impl MethodInfo {
+    /// Instance method returning `None`, e.g. `MethodInfo { name: "close", ..MethodInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        name: "",
+        parameters: &[],
+        r#return: TypeInfo::none,
+        doc: "",
+        r#type: MethodType::Instance,
+        is_async: false,
+        deprecated: None,
+        type_ignored: None,
+        is_abstract: false,
+        also_as: &[],
+        keep_signature: false,
+        source_location: None,
+    };
 }
 
 /// Info of getter method decorated with `#[getter]` or `#[pyo3(get, set)]` appears in `#[pyclass]`
@@ -138,6 +178,15 @@ pub struct MemberInfo {
     pub deprecated: Option<DeprecatedInfo>,
     pub item: bool,
     pub is_abstract: bool,
+    /// Additional names given by `#[gen_stub(also_as(...))]`
+    pub also_as: &'static [&'static str],
+    /// Defined by `#[classattr]`, which is rendered as `typing.Final`
+    pub is_classattr: bool,
+    /// Integer literal wrapped by the value of a `#[classattr]`, e.g. `1` of `Flags(1)`,
+    /// which is the value of the member of `#[gen_stub(flag_enum)]`
+    pub int_value: Option<&'static str>,
+    /// `# type: ignore` comment after the annotation or the signature
+    pub type_ignored: Option<IgnoreTarget>,
 }
 
 /// Info of `#[pymethod]`
@@ -145,6 +194,10 @@ pub struct MemberInfo {
 pub struct PyMethodsInfo {
     // The Rust struct type-id of `impl` block where `#[pymethod]` acts on
     pub struct_id: fn() -> TypeId,
+    /// Rust type name of the struct, e.g. `my_crate::Foo`, used in diagnostics
+    pub struct_name: fn() -> &'static str,
+    /// Source location of the `impl` block, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
     /// Method/Const with `#[classattr]`
     pub attrs: &'static [MemberInfo],
     /// Methods decorated with `#[getter]`
@@ -155,6 +208,19 @@ pub struct PyMethodsInfo {
     pub methods: &'static [MethodInfo],
 }
 
+impl PyMethodsInfo {
+    /// `#[pymethods]` without any item, e.g. `PyMethodsInfo { struct_id: TypeId::of::<Foo>, ..PyMethodsInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        struct_id: TypeId::of::<()>,
+        struct_name: || "",
+        source_location: None,
+        attrs: &[],
+        getters: &[],
+        setters: &[],
+        methods: &[],
+    };
+}
+
 inventory::collect!(PyMethodsInfo);
 
 /// Info of `#[pyclass]` with Rust struct
@@ -162,8 +228,14 @@ inventory::collect!(PyMethodsInfo);
 pub struct PyClassInfo {
     // Rust struct type-id
     pub struct_id: fn() -> TypeId,
+    /// Path of the Rust item, e.g. `my_crate::config::Config`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the Rust item, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
     // The name exposed to Python
     pub pyclass_name: &'static str,
+    /// Whether the name is given by `#[pyclass(name = "...")]` rather than the Rust name
+    pub name_given: bool,
     /// Module name specified by `#[pyclass(module = "foo.bar")]`
     pub module: Option<&'static str>,
     /// Docstring
@@ -187,10 +259,96 @@ pub struct PyClassInfo {
     /// Whether the class should be treated as abstract via `#[gen_stub(abstract_class)]`
     /// (or `#[gen_stub(r#abstract)]` using a raw identifier)
     pub is_abstract: bool,
+    /// Stub types set by the user instead of the class itself
+    pub type_override: ClassTypeOverride,
+    /// Whether the class should be rendered as `@dataclasses.dataclass` via `#[gen_stub(dataclass)]`
+    pub dataclass: bool,
+    /// Whether the class is omitted from stubs and its members are copied into subclasses via `#[gen_stub(hidden_base)]`
+    pub hidden_base: bool,
+    /// Whether `collections.abc` bases inferred from dunder methods are suppressed via `#[gen_stub(no_abc)]`
+    pub no_abc: bool,
+    /// Whether the class is rendered as `enum.Flag` via `#[gen_stub(flag_enum)]`
+    pub flag_enum: bool,
+    /// Whether the missing rich comparisons are synthesized as `functools.total_ordering` does, via `#[gen_stub(total_ordering)]`
+    pub total_ordering: bool,
+    /// State of `__getstate__` and `__setstate__` given by `#[gen_stub(pickle_state = "...")]`
+    pub pickle_state: Option<fn() -> TypeInfo>,
+    /// Whether `__slots__` is rendered via `#[gen_stub(slots)]`
+    pub slots: bool,
+    /// Whether the class has weakref attribute `#[pyclass(weakref)]`
+    pub weakref: bool,
+    /// Cargo feature the class belongs to, given by `#[gen_stub(feature = "...")]`
+    pub feature: Option<&'static str>,
+    /// Deprecation by `#[deprecated]` on the struct
+    pub deprecated: Option<DeprecatedInfo>,
+}
+
+impl PyClassInfo {
+    /// Class without any member, e.g. `PyClassInfo { pyclass_name: "Foo", ..PyClassInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        struct_id: TypeId::of::<()>,
+        rust_path: "",
+        source_location: None,
+        pyclass_name: "",
+        name_given: false,
+        module: None,
+        doc: "",
+        getters: &[],
+        setters: &[],
+        bases: &[],
+        has_eq: false,
+        has_ord: false,
+        has_hash: false,
+        has_str: false,
+        subclass: false,
+        is_abstract: false,
+        type_override: ClassTypeOverride {
+            input: None,
+            output: None,
+            custom_from_py_object: false,
+        },
+        dataclass: false,
+        hidden_base: false,
+        no_abc: false,
+        flag_enum: false,
+        total_ordering: false,
+        pickle_state: None,
+        slots: false,
+        weakref: false,
+        feature: None,
+        deprecated: None,
+    };
 }
 
 inventory::collect!(PyClassInfo);
 
+/// Info of `#[pyclass]` omitted from stubs by `#[gen_stub(skip)]`
+#[derive(Debug)]
+pub struct SkippedClassInfo {
+    // Rust struct type-id
+    pub struct_id: fn() -> TypeId,
+    pub pyclass_name: &'static str,
+    /// Whether the class is rendered as `typing.Any` where it is referred to,
+    /// i.e. its types are not overridden by `#[gen_stub(type_input_override(...), type_output_override(...))]`
+    pub as_any: bool,
+    /// Source location of the struct, e.g. `src/lib.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
+}
+
+inventory::collect!(SkippedClassInfo);
+
+/// Stub types of a class set by `#[gen_stub(type_input_override(...), type_output_override(...))]`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClassTypeOverride {
+    /// Type used when the class appears in argument position
+    pub input: Option<fn() -> TypeInfo>,
+    /// Type used when the class appears in return position
+    pub output: Option<fn() -> TypeInfo>,
+    /// Whether the class is marked by `#[gen_stub(custom_from_py_object)]`,
+    /// i.e. it is extracted by a custom `FromPyObject` accepting more than the class itself
+    pub custom_from_py_object: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VariantForm {
     Unit,
@@ -215,14 +373,40 @@ pub struct VariantInfo {
 pub struct PyComplexEnumInfo {
     // Rust struct type-id
     pub enum_id: fn() -> TypeId,
+    /// Path of the Rust item, e.g. `my_crate::config::Config`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the Rust item, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
     // The name exposed to Python
     pub pyclass_name: &'static str,
+    /// Whether the name is given by `#[pyclass(name = "...")]` rather than the Rust name
+    pub name_given: bool,
     /// Module name specified by `#[pyclass(module = "foo.bar")]`
     pub module: Option<&'static str>,
     /// Docstring
     pub doc: &'static str,
     /// static members by `#[pyo3(get, set)]`
     pub variants: &'static [VariantInfo],
+    /// Union of the variants, rendered as the alias `{pyclass_name}Variant`, or `None` without any variant
+    pub variant_union: Option<fn() -> TypeInfo>,
+    /// Cargo feature the enum belongs to, given by `#[gen_stub(feature = "...")]`
+    pub feature: Option<&'static str>,
+}
+
+impl PyComplexEnumInfo {
+    /// Complex enum without any variant, e.g. `PyComplexEnumInfo { pyclass_name: "Shape", ..PyComplexEnumInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        enum_id: TypeId::of::<()>,
+        rust_path: "",
+        source_location: None,
+        pyclass_name: "",
+        name_given: false,
+        module: None,
+        doc: "",
+        variants: &[],
+        variant_union: None,
+        feature: None,
+    };
 }
 
 inventory::collect!(PyComplexEnumInfo);
@@ -232,14 +416,37 @@ inventory::collect!(PyComplexEnumInfo);
 pub struct PyEnumInfo {
     // Rust struct type-id
     pub enum_id: fn() -> TypeId,
+    /// Path of the Rust item, e.g. `my_crate::config::Config`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the Rust item, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
     // The name exposed to Python
     pub pyclass_name: &'static str,
+    /// Whether the name is given by `#[pyclass(name = "...")]` rather than the Rust name
+    pub name_given: bool,
     /// Module name specified by `#[pyclass(module = "foo.bar")]`
     pub module: Option<&'static str>,
     /// Docstring
     pub doc: &'static str,
     /// Variants of enum (name, doc)
     pub variants: &'static [(&'static str, &'static str)],
+    /// Cargo feature the enum belongs to, given by `#[gen_stub(feature = "...")]`
+    pub feature: Option<&'static str>,
+}
+
+impl PyEnumInfo {
+    /// Enum without any variant, e.g. `PyEnumInfo { pyclass_name: "Color", ..PyEnumInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        enum_id: TypeId::of::<()>,
+        rust_path: "",
+        source_location: None,
+        pyclass_name: "",
+        name_given: false,
+        module: None,
+        doc: "",
+        variants: &[],
+        feature: None,
+    };
 }
 
 inventory::collect!(PyEnumInfo);
@@ -248,6 +455,10 @@ inventory::collect!(PyEnumInfo);
 #[derive(Debug)]
 pub struct PyFunctionInfo {
     pub name: &'static str,
+    /// Path of the Rust item, e.g. `my_crate::config::Config`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the Rust item, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
     pub parameters: &'static [ParameterInfo],
     pub r#return: fn() -> TypeInfo,
     pub doc: &'static str,
@@ -255,6 +466,25 @@ pub struct PyFunctionInfo {
     pub is_async: bool,
     pub deprecated: Option<DeprecatedInfo>,
     pub type_ignored: Option<IgnoreTarget>,
+    /// Cargo feature the function belongs to, given by `#[gen_stub(feature = "...")]`
+    pub feature: Option<&'static str>,
+}
+
+impl PyFunctionInfo {
+    /// Function without parameters returning `None`, e.g. `PyFunctionInfo { name: "run", ..PyFunctionInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        name: "",
+        rust_path: "",
+        source_location: None,
+        parameters: &[],
+        r#return: TypeInfo::none,
+        doc: "",
+        module: None,
+        is_async: false,
+        deprecated: None,
+        type_ignored: None,
+        feature: None,
+    };
 }
 
 inventory::collect!(PyFunctionInfo);
@@ -262,13 +492,83 @@ inventory::collect!(PyFunctionInfo);
 #[derive(Debug)]
 pub struct PyVariableInfo {
     pub name: &'static str,
+    /// Module of the variable, or empty for the default module, i.e. `module-name` in `[tool.maturin]`
     pub module: &'static str,
+    /// Rust module where the variable is declared, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the declaration, e.g. `src/config.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
     pub r#type: fn() -> TypeInfo,
     pub default: Option<fn() -> String>,
+    /// Rendered as the attribute docstring after the variable
+    pub doc: &'static str,
+    /// Deprecation by `#[deprecated]`
+    pub deprecated: Option<DeprecatedInfo>,
+    /// `# type: ignore` comment after the annotation
+    pub type_ignored: Option<IgnoreTarget>,
+}
+
+impl PyVariableInfo {
+    /// Variable of `typing.Any`, e.g. `PyVariableInfo { name: "VERSION", module: "mypkg", ..PyVariableInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        name: "",
+        module: "",
+        rust_path: "",
+        source_location: None,
+        r#type: TypeInfo::any,
+        default: None,
+        doc: "",
+        deprecated: None,
+        type_ignored: None,
+    };
 }
 
 inventory::collect!(PyVariableInfo);
 
+/// Info of an exception class submitted by [crate::create_exception] or [crate::gen_stub_create_exception]
+#[derive(Debug)]
+pub struct PyExceptionInfo {
+    pub name: &'static str,
+    pub module: &'static str,
+    /// Path of the Rust item, e.g. `my_crate::errors::MyError`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the Rust item, e.g. `src/errors.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
+    pub base: fn() -> TypeInfo,
+    pub doc: &'static str,
+    /// Cargo feature the exception belongs to, given by `feature = "..."` of [crate::gen_stub_create_exception]
+    pub feature: Option<&'static str>,
+}
+
+impl PyExceptionInfo {
+    /// Exception deriving from `typing.Any`, e.g. `PyExceptionInfo { name: "Error", module: "mypkg", ..PyExceptionInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        name: "",
+        module: "",
+        rust_path: "",
+        source_location: None,
+        base: TypeInfo::any,
+        doc: "",
+        feature: None,
+    };
+}
+
+inventory::collect!(PyExceptionInfo);
+
+/// `typing.Literal` alias of the values of module-level variables, submitted by [crate::literal_group]
+#[derive(Debug)]
+pub struct LiteralGroupInfo {
+    pub module: &'static str,
+    /// Name of the alias
+    pub name: &'static str,
+    /// Rust module where the group is declared, used in diagnostics
+    pub rust_path: &'static str,
+    /// Names of the variables registered by [crate::module_variable] in `module`
+    pub variables: &'static [&'static str],
+}
+
+inventory::collect!(LiteralGroupInfo);
+
 #[derive(Debug)]
 pub struct ModuleDocInfo {
     pub module: &'static str,
@@ -276,3 +576,203 @@ pub struct ModuleDocInfo {
 }
 
 inventory::collect!(ModuleDocInfo);
+
+/// Module whose stub ends with a `__getattr__` fallback, submitted by [crate::gen_stub_module_attr_fallback]
+#[derive(Debug)]
+pub struct ModuleAttrFallbackInfo {
+    pub module: &'static str,
+}
+
+inventory::collect!(ModuleAttrFallbackInfo);
+
+/// Stub-only `typing.NamedTuple` class, submitted by [crate::gen_stub_named_tuple]
+#[derive(Debug)]
+pub struct NamedTupleInfo {
+    /// Type-id of the marker type
+    pub struct_id: fn() -> TypeId,
+    pub name: &'static str,
+    pub module: &'static str,
+    /// Path of the marker type, e.g. `my_crate::scores::ScoreEntry`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the marker type, e.g. `src/scores.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
+    pub doc: &'static str,
+    /// Fields in order
+    pub fields: &'static [NamedTupleFieldInfo],
+}
+
+impl NamedTupleInfo {
+    /// Named tuple without any field, e.g. `NamedTupleInfo { name: "Point", module: "mypkg", ..NamedTupleInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        struct_id: TypeId::of::<()>,
+        name: "",
+        module: "",
+        rust_path: "",
+        source_location: None,
+        doc: "",
+        fields: &[],
+    };
+}
+
+inventory::collect!(NamedTupleInfo);
+
+/// Field of a [NamedTupleInfo]
+#[derive(Debug)]
+pub struct NamedTupleFieldInfo {
+    pub name: &'static str,
+    pub r#type: fn() -> TypeInfo,
+}
+
+/// Stub-only `typing.TypedDict` class of a plain Rust struct, submitted by `#[derive(PyStubType)]`
+#[derive(Debug)]
+pub struct TypedDictInfo {
+    /// Type-id of the struct
+    pub struct_id: fn() -> TypeId,
+    pub name: &'static str,
+    /// Module name specified by `#[gen_stub(module = "...")]`
+    pub module: Option<&'static str>,
+    /// Path of the struct, e.g. `my_crate::stats::Summary`, used in diagnostics
+    pub rust_path: &'static str,
+    /// Source location of the struct, e.g. `src/stats.rs:12`, used in diagnostics
+    pub source_location: Option<&'static str>,
+    pub doc: &'static str,
+    /// Keys in the order of the fields
+    pub fields: &'static [TypedDictFieldInfo],
+}
+
+impl TypedDictInfo {
+    /// Typed dict without any field, e.g. `TypedDictInfo { name: "Options", ..TypedDictInfo::DEFAULT }`
+    pub const DEFAULT: Self = Self {
+        struct_id: TypeId::of::<()>,
+        name: "",
+        module: None,
+        rust_path: "",
+        source_location: None,
+        doc: "",
+        fields: &[],
+    };
+}
+
+inventory::collect!(TypedDictInfo);
+
+/// Key of a [TypedDictInfo]
+#[derive(Debug)]
+pub struct TypedDictFieldInfo {
+    pub name: &'static str,
+    pub r#type: fn() -> TypeInfo,
+    pub doc: &'static str,
+    /// Whether the key is always present, i.e. the field is not an `Option`, or it is rendered as `typing.NotRequired`
+    pub required: bool,
+}
+
+/// Class object of `T`, rendered as `type[T]`.
+///
+/// Parameters and return values with `#[gen_stub(class_of = "T")]` use this in place of `Bound<PyType>`.
+pub struct ClassOf<T>(PhantomData<T>);
+
+impl<T: PyStubType> PyStubType for ClassOf<T> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::class_of::<T>()
+    }
+}
+
+/// Python callable taking the arguments `Args` and returning `R`, rendered as `collections.abc.Callable[[...], R]`.
+///
+/// This is a marker for type overrides, e.g. `pyo3_stub_gen.RustType["StubCallable<(i64, String), bool>"]`,
+/// standing for a callback received as `Py<PyFunction>` or `PyObject`.
+/// `Args` is a tuple of the argument types, or [AnyArgs] for `Callable[..., R]`.
+/// The argument types are rendered as what Rust passes to a callback, and as what Python passes to a returned callable.
+pub struct StubCallable<Args, R>(PhantomData<(Args, R)>);
+
+/// Arguments of a [StubCallable], which is called with any arguments, rendered as `...`
+pub struct AnyArgs;
+
+/// Argument types of a [StubCallable]
+pub trait CallableArgs {
+    /// Types of the arguments as passed from Rust, or `None` for any arguments
+    fn type_outputs() -> Option<Vec<TypeInfo>>;
+    /// Types of the arguments as passed from Python, or `None` for any arguments
+    fn type_inputs() -> Option<Vec<TypeInfo>>;
+}
+
+impl CallableArgs for AnyArgs {
+    fn type_outputs() -> Option<Vec<TypeInfo>> {
+        None
+    }
+    fn type_inputs() -> Option<Vec<TypeInfo>> {
+        None
+    }
+}
+
+macro_rules! impl_callable_args {
+    ($($T:ident),*) => {
+        impl<$($T: PyStubType),*> CallableArgs for ($($T,)*) {
+            fn type_outputs() -> Option<Vec<TypeInfo>> {
+                Some(vec![$($T::type_output()),*])
+            }
+            fn type_inputs() -> Option<Vec<TypeInfo>> {
+                Some(vec![$($T::type_input()),*])
+            }
+        }
+    };
+}
+
+impl_callable_args!();
+impl_callable_args!(T1);
+impl_callable_args!(T1, T2);
+impl_callable_args!(T1, T2, T3);
+impl_callable_args!(T1, T2, T3, T4);
+impl_callable_args!(T1, T2, T3, T4, T5);
+impl_callable_args!(T1, T2, T3, T4, T5, T6);
+
+fn callable(args: Option<Vec<TypeInfo>>, r#return: TypeInfo) -> TypeInfo {
+    match args {
+        Some(args) => TypeInfo::callable(&args, r#return),
+        None => TypeInfo::callable_with_any_args(r#return),
+    }
+}
+
+impl<Args: CallableArgs, R: PyStubType> PyStubType for StubCallable<Args, R> {
+    fn type_output() -> TypeInfo {
+        callable(Args::type_inputs(), R::type_output())
+    }
+    fn type_input() -> TypeInfo {
+        callable(Args::type_outputs(), R::type_input())
+    }
+}
+
+/// Tuple of any length whose items are all `T`, rendered as `tuple[T, ...]`.
+///
+/// This is a marker for type overrides, e.g. `pyo3_stub_gen.RustType["VariadicTuple<f64>"]`,
+/// standing for a `Vec<T>` converted into a `PyTuple`.
+/// Arrays and slices are converted into lists by PyO3, so that they are still rendered as `list[T]`.
+pub struct VariadicTuple<T>(PhantomData<T>);
+
+impl<T: PyStubType> PyStubType for VariadicTuple<T> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::variadic_tuple_of(T::type_output())
+    }
+    fn type_input() -> TypeInfo {
+        TypeInfo::variadic_tuple_of(T::type_input())
+    }
+}
+
+/// `typing.Self`, used as the return type `"Self"` of `#[gen_stub(getitem(...))]`.
+pub struct SelfType;
+
+impl PyStubType for SelfType {
+    fn type_output() -> TypeInfo {
+        TypeInfo::self_type()
+    }
+}
+
+/// Optional class object of `T`, rendered as `type[T] | None`.
+///
+/// Used in place of `Option<Bound<PyType>>` with `#[gen_stub(class_of = "T")]`.
+pub struct OptionalClassOf<T>(PhantomData<T>);
+
+impl<T: PyStubType> PyStubType for OptionalClassOf<T> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::class_of::<T>() | TypeInfo::none()
+    }
+}
@@ -11,6 +11,12 @@ pub fn all_builtin_types(any: &Bound<'_, PyAny>) -> bool {
     {
         return true;
     }
+    if any.is_instance_of::<PySet>() || any.is_instance_of::<PyFrozenSet>() {
+        return any
+            .try_iter()
+            .map(|mut iter| iter.all(|v| v.is_ok_and(|v| all_builtin_types(&v))))
+            .unwrap_or(false);
+    }
     if any.is_instance_of::<PyDict>() {
         return any
             .downcast::<PyDict>()
@@ -53,21 +59,64 @@ fn get_globals<'py>(any: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyDict>> {
     Ok(globals)
 }
 
+/// Whether `any` is a unit variant of a `#[pyclass]` enum, e.g. `Number.Float`.
+///
+/// Unlike [valid_external_repr], this does not require `#[pyclass(eq)]`.
+pub fn is_enum_variant(any: &Bound<'_, PyAny>) -> bool {
+    let Ok(repr) = any.repr().map(|repr| repr.to_string()) else {
+        return false;
+    };
+    let type_object = any.get_type();
+    let Ok(type_name) = type_object
+        .getattr("__name__")
+        .and_then(|name| name.extract::<String>())
+    else {
+        return false;
+    };
+    let Some(variant) = repr.strip_prefix(&format!("{type_name}.")) else {
+        return false;
+    };
+    type_object.getattr(variant).is_ok_and(|attr| {
+        attr.is_instance(&type_object).unwrap_or(false)
+            && attr
+                .repr()
+                .is_ok_and(|attr_repr| attr_repr.to_string() == repr)
+    })
+}
+
+/// Format a default value as a Python literal.
+///
+/// `None`, builtin values including (empty) containers, and unit variants of enums are rendered as literals.
+/// Any other value, e.g. an instance of a custom `#[pyclass]`, is rendered as `...`.
+/// This never panics, even if the conversion into a Python object does.
 #[cfg_attr(not(feature = "infer_signature"), allow(unused_variables))]
 pub fn fmt_py_obj<T: for<'py> pyo3::IntoPyObjectExt<'py>>(obj: T) -> String {
     #[cfg(feature = "infer_signature")]
     {
-        pyo3::Python::initialize();
-        pyo3::Python::attach(|py| -> String {
-            if let Ok(any) = obj.into_bound_py_any(py) {
-                if all_builtin_types(&any) || valid_external_repr(&any).is_some_and(|valid| valid) {
-                    if let Ok(py_str) = any.repr() {
-                        return py_str.to_string();
-                    }
+        let formatted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pyo3::Python::initialize();
+            pyo3::Python::attach(|py| -> Option<String> {
+                let any = obj.into_bound_py_any(py).ok()?;
+                if any.is_none() {
+                    return Some("None".to_owned());
                 }
+                if all_builtin_types(&any)
+                    || valid_external_repr(&any).is_some_and(|valid| valid)
+                    || is_enum_variant(&any)
+                {
+                    return any.repr().ok().map(|repr| repr.to_string());
+                }
+                None
+            })
+        }));
+        match formatted {
+            Ok(Some(formatted)) => formatted,
+            Ok(None) => "...".to_owned(),
+            Err(_) => {
+                log::warn!("Failed to format a default value, falling back to `...`");
+                "...".to_owned()
             }
-            "...".to_owned()
-        })
+        }
     }
     #[cfg(not(feature = "infer_signature"))]
     {
@@ -75,6 +124,48 @@ pub fn fmt_py_obj<T: for<'py> pyo3::IntoPyObjectExt<'py>>(obj: T) -> String {
     }
 }
 
+/// Format a default value by [PyStubType::default_repr](crate::PyStubType::default_repr),
+/// or by [fmt_py_obj] if the type has no rendering of its own.
+pub fn fmt_default<T: crate::PyStubType + for<'py> pyo3::IntoPyObjectExt<'py>>(obj: T) -> String {
+    T::default_repr(&obj).unwrap_or_else(|| fmt_py_obj(obj))
+}
+
+/// Length of the module path joining `segments` by `.`, used by [crate::concat_module!]
+pub const fn module_path_len(segments: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < segments.len() {
+        if i > 0 {
+            len += 1;
+        }
+        len += segments[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Bytes of the module path joining `segments` by `.`, used by [crate::concat_module!]
+pub const fn join_module_path<const N: usize>(segments: &[&str]) -> [u8; N] {
+    let mut path = [0; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < segments.len() {
+        if i > 0 {
+            path[pos] = b'.';
+            pos += 1;
+        }
+        let bytes = segments[i].as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            path[pos] = bytes[j];
+            pos += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    path
+}
+
 #[cfg(all(test, feature = "infer_signature"))]
 mod test {
     use super::*;
@@ -136,6 +227,9 @@ mod test {
         assert_eq!("None", fmt_py_obj(none));
         // class A variable can not be formatted
         assert_eq!("...", fmt_py_obj(A {}));
+        // constants without `PyStubType::default_repr` are formatted as values
+        const DEFAULT_N: usize = 8;
+        assert_eq!("8", fmt_default(DEFAULT_N));
     }
     #[test]
     fn test_fmt_enum() {
@@ -147,4 +241,48 @@ mod test {
         }
         assert_eq!("Number.Float", fmt_py_obj(Number::Float));
     }
+    #[test]
+    fn test_fmt_none_and_empty_containers() {
+        use std::collections::{BTreeMap, HashMap, HashSet};
+        assert_eq!("None", fmt_py_obj(None::<i64>));
+        assert_eq!("[]", fmt_py_obj(Vec::<i64>::new()));
+        assert_eq!("{}", fmt_py_obj(HashMap::<String, i64>::new()));
+        assert_eq!("{}", fmt_py_obj(BTreeMap::<String, i64>::new()));
+        assert_eq!("set()", fmt_py_obj(HashSet::<i64>::new()));
+        assert_eq!("{1}", fmt_py_obj(HashSet::from([1])));
+    }
+    #[test]
+    fn test_fmt_enum_without_eq() {
+        #[pyclass]
+        #[derive(Debug, Clone)]
+        pub enum Direction {
+            Left,
+            Right,
+        }
+        assert_eq!("Direction.Right", fmt_py_obj(Direction::Right));
+    }
+    #[test]
+    fn test_fmt_custom_pyclass_instance() {
+        #[pyclass]
+        #[derive(Debug, Clone)]
+        struct Config {
+            #[pyo3(get)]
+            timeout: u64,
+        }
+        assert_eq!("...", fmt_py_obj(Config { timeout: 30 }));
+        assert_eq!("...", fmt_py_obj(Some(Config { timeout: 30 })));
+    }
+    #[test]
+    fn test_fmt_panicking_conversion() {
+        struct Unconvertible;
+        impl<'py> IntoPyObject<'py> for Unconvertible {
+            type Target = PyAny;
+            type Output = Bound<'py, PyAny>;
+            type Error = PyErr;
+            fn into_pyobject(self, _py: Python<'py>) -> PyResult<Self::Output> {
+                panic!("cannot convert")
+            }
+        }
+        assert_eq!("...", fmt_py_obj(Unconvertible));
+    }
 }
@@ -0,0 +1,62 @@
+//! The setters change the configuration of the process, so this runs in its own test binary.
+
+use pyo3_stub_gen::{
+    generate::{ClassDef, MethodDef, Module, Parameters},
+    set_self_import_strategy,
+    type_info::MethodType,
+    Result, SelfImportStrategy, StubInfo, TypeInfo,
+};
+use std::any::TypeId;
+
+fn stub_info_with_self_returning_class() -> Result<StubInfo> {
+    let mut class = ClassDef::new("Builder");
+    class.methods.insert(
+        "chain".to_string(),
+        vec![MethodDef::new(
+            "chain",
+            MethodType::Instance,
+            Parameters::new(),
+            TypeInfo::self_type(),
+        )],
+    );
+    let mut module = Module::new("pkg", "pkg");
+    module.class.insert(TypeId::of::<()>(), class);
+    let mut info = StubInfo::from_project_root("pkg".to_string(), std::env::temp_dir())?;
+    info.modules.insert("pkg".to_string(), module);
+    Ok(info)
+}
+
+fn rendered(info: &StubInfo) -> Result<String> {
+    let dir = std::env::temp_dir().join(format!("pyo3-stub-gen-config-{}", std::process::id()));
+    info.generate_to(&dir)?;
+    let stub = std::fs::read_to_string(dir.join("pkg.pyi"))?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(stub)
+}
+
+#[test]
+fn stub_info_keeps_configuration_it_is_built_with() -> Result<()> {
+    set_self_import_strategy(SelfImportStrategy::TypingExtensions);
+    let with_extensions = stub_info_with_self_returning_class()?;
+    set_self_import_strategy(SelfImportStrategy::Typing);
+    let with_typing = stub_info_with_self_returning_class()?;
+
+    // Changing the setters afterwards affects neither of them
+    let stub = rendered(&with_extensions)?;
+    assert!(
+        stub.contains("from typing_extensions import Self"),
+        "{stub}"
+    );
+    assert!(!stub.contains("from typing import Self"), "{stub}");
+    let stub = rendered(&with_typing)?;
+    assert!(stub.contains("from typing import Self"), "{stub}");
+
+    // Merged stubs are rendered with the configuration of the first one
+    let merged = with_extensions.merge(with_typing)?;
+    let stub = rendered(&merged)?;
+    assert!(
+        stub.contains("from typing_extensions import Self"),
+        "{stub}"
+    );
+    Ok(())
+}